@@ -0,0 +1,49 @@
+//! Measures key generation, encapsulation and decapsulation of every KEM with criterion
+//!
+//! Run with `cargo bench --features bench`, optionally followed by `-- <filter>`
+//! to only measure the KEMs whose name matches the filter, e.g. `-- MlKem`.
+//! criterion compares each run with the previous one, to spot regressions.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use quantcrypt::kems::{KemBenchmarkRunner, KemType};
+
+fn bench_kems(c: &mut Criterion) {
+    for kem_type in KemType::all() {
+        let name = format!("{:?}", kem_type);
+        // Report failures and carry on, so one broken backend doesn't hide the others
+        let mut kem = match KemBenchmarkRunner::new_kem(kem_type) {
+            Ok(kem) => kem,
+            Err(e) => {
+                println!("{:<28} failed: {:?}", name, e);
+                continue;
+            }
+        };
+        let round_trip = kem.key_gen().and_then(|(pk, sk)| {
+            let (ss, ct) = kem.encap(&pk)?;
+            let same = ss == kem.decap(&sk, &ct)?;
+            Ok((pk, sk, ct, same))
+        });
+        let (pk, sk, ct) = match round_trip {
+            Ok((pk, sk, ct, true)) => (pk, sk, ct),
+            Ok(_) => {
+                println!("{:<28} failed: the shared secrets differ", name);
+                continue;
+            }
+            Err(e) => {
+                println!("{:<28} failed: {:?}", name, e);
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(name);
+        group.bench_function("key_gen", |b| b.iter(|| kem.key_gen().unwrap()));
+        group.bench_function("encap", |b| b.iter(|| kem.encap(black_box(&pk)).unwrap()));
+        group.bench_function("decap", |b| {
+            b.iter(|| kem.decap(black_box(&sk), black_box(&ct)).unwrap())
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_kems);
+criterion_main!(benches);
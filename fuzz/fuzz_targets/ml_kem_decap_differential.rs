@@ -0,0 +1,99 @@
+//! Differential fuzzing of ML-KEM decapsulation against liboqs.
+//!
+//! Each input selects a parameter set, seeds a deterministic key pair and a
+//! valid encapsulation, then XORs the remaining input into the ciphertext.
+//! The mutated ciphertext is decapsulated both through quantcrypt's public API
+//! and with liboqs, an independent C implementation. Both must agree: either
+//! both reject the ciphertext or both return the same shared secret. In
+//! particular, a tampered ciphertext of the right length must go through
+//! implicit rejection and never surface as an error.
+//!
+//! The target needs the `liboqs-interop` feature, which builds liboqs
+//! (requires cmake). Run with
+//! `cargo fuzz run --features liboqs-interop ml_kem_decap_differential` from
+//! the repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ml_kem::{
+    EncapsulateDeterministic, EncodedSizeUser, KemCore, MlKem1024, MlKem512, MlKem768, B32,
+};
+use oqs::kem::Algorithm as OqsKemAlgorithm;
+use quantcrypt::kems::{self, KemAlgorithm};
+
+macro_rules! differential_decap {
+    ($kem:ident, $algorithm:expr, $oqs_algorithm:expr, $data:expr) => {{
+        let data: &[u8] = $data;
+        if data.len() < 96 {
+            return;
+        }
+        let d = B32::try_from(&data[0..32]).unwrap();
+        let z = B32::try_from(&data[32..64]).unwrap();
+        let m = B32::try_from(&data[64..96]).unwrap();
+        let mutation = &data[96..];
+
+        // ml-kem only generates the inputs, the decapsulations being compared
+        // are quantcrypt's and liboqs'
+        let (dk, ek) = $kem::generate_deterministic(&d, &z);
+        let (ct, _) = ek.encapsulate_deterministic(&m).unwrap();
+
+        // XOR the mutation into the ciphertext. A mutation longer than the
+        // ciphertext extends it, which must be rejected by both sides.
+        let mut ct = ct.to_vec();
+        for (i, b) in mutation.iter().enumerate() {
+            match ct.get_mut(i) {
+                Some(c) => *c ^= b,
+                None => ct.push(*b),
+            }
+        }
+
+        let sk = dk.as_bytes().to_vec();
+        let ours =
+            kems::Ciphertext::new($algorithm, &ct).and_then(|ct| kems::decap($algorithm, &sk, &ct));
+
+        let oqs_kem = oqs::kem::Kem::new($oqs_algorithm).unwrap();
+        let oqs_sk = oqs_kem.secret_key_from_bytes(&sk).unwrap();
+        let reference = oqs_kem
+            .ciphertext_from_bytes(&ct)
+            .map(|ct| oqs_kem.decapsulate(oqs_sk, ct).unwrap());
+
+        match (ours, reference) {
+            (Ok(ours), Some(reference)) => {
+                assert_eq!(ours.expose_secret(), reference.as_ref())
+            }
+            (Err(_), None) => {}
+            (ours, reference) => panic!(
+                "divergence: quantcrypt ok = {}, liboqs ok = {}",
+                ours.is_ok(),
+                reference.is_some()
+            ),
+        }
+    }};
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, data)) = data.split_first() else {
+        return;
+    };
+    match selector % 3 {
+        0 => differential_decap!(
+            MlKem512,
+            KemAlgorithm::MlKem512,
+            OqsKemAlgorithm::MlKem512,
+            data
+        ),
+        1 => differential_decap!(
+            MlKem768,
+            KemAlgorithm::MlKem768,
+            OqsKemAlgorithm::MlKem768,
+            data
+        ),
+        _ => differential_decap!(
+            MlKem1024,
+            KemAlgorithm::MlKem1024,
+            OqsKemAlgorithm::MlKem1024,
+            data
+        ),
+    }
+});
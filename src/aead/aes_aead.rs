@@ -9,10 +9,85 @@ use super::common::config::n_min::NMin;
 use super::common::config::p_max::PMax;
 use super::common::{aead_info::AeadInfo, config::a_max::AMax};
 
+#[cfg(feature = "openssl")]
 use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// Get the OpenSSL cipher for the AEAD type
+#[cfg(feature = "openssl")]
+fn get_cipher(aead_type: &AeadType) -> Cipher {
+    match aead_type {
+        AeadType::AesGcm128 => Cipher::aes_128_gcm(),
+        AeadType::AesGcm256 => Cipher::aes_256_gcm(),
+    }
+}
+
+/// Seal with OpenSSL, returning the ciphertext and the tag
+#[cfg(feature = "openssl")]
+fn gcm_seal(
+    aead_type: &AeadType,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut tag = vec![0u8; 16];
+    let ct = encrypt_aead(
+        get_cipher(aead_type),
+        key,
+        Some(nonce),
+        aad,
+        plaintext,
+        &mut tag,
+    )
+    .map_err(|_| QuantCryptError::Unknown)?;
+    Ok((ct, tag))
+}
+
+/// Open with OpenSSL
+#[cfg(feature = "openssl")]
+fn gcm_open(
+    aead_type: &AeadType,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ct: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>> {
+    decrypt_aead(get_cipher(aead_type), key, Some(nonce), aad, ct, tag)
+        .map_err(|_| QuantCryptError::DecryptionFailed)
+}
+
+/// AES-GCM is computed with OpenSSL
+#[cfg(not(feature = "openssl"))]
+fn gcm_seal(
+    aead_type: &AeadType,
+    _key: &[u8],
+    _nonce: &[u8],
+    _aad: &[u8],
+    _plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    Err(QuantCryptError::BackendUnavailable {
+        component: format!("{:?}", aead_type),
+    })
+}
+
+/// AES-GCM is computed with OpenSSL
+#[cfg(not(feature = "openssl"))]
+fn gcm_open(
+    aead_type: &AeadType,
+    _key: &[u8],
+    _nonce: &[u8],
+    _aad: &[u8],
+    _ct: &[u8],
+    _tag: &[u8],
+) -> Result<Vec<u8>> {
+    Err(QuantCryptError::BackendUnavailable {
+        component: format!("{:?}", aead_type),
+    })
+}
+
 // Implement clone
 #[derive(Clone)]
 pub struct AesAeadManager {
@@ -24,6 +99,11 @@ impl Aead for AesAeadManager {
     where
         Self: Sized,
     {
+        if !cfg!(feature = "openssl") {
+            return Err(QuantCryptError::BackendUnavailable {
+                component: format!("{:?}", aead_type),
+            });
+        }
         Ok(AesAeadManager { aead_type })
     }
 
@@ -42,14 +122,7 @@ impl Aead for AesAeadManager {
             return Err(QuantCryptError::InvalidAeadPlaintextLength);
         }
 
-        let cipher = match self.aead_type {
-            AeadType::AesGcm128 => Cipher::aes_128_gcm(),
-            AeadType::AesGcm256 => Cipher::aes_256_gcm(),
-        };
-
-        let mut tag = vec![0u8; 16];
-        let ct = encrypt_aead(cipher, key, Some(nonce), aad, plaintext, &mut tag)
-            .map_err(|_| QuantCryptError::Unknown)?;
+        let (ct, tag) = gcm_seal(&self.aead_type, key, nonce, aad, plaintext)?;
 
         // Combine the ciphertext and tag
         // An AEAD_AES_128_GCM ciphertext is exactly 16 octets longer than its
@@ -75,18 +148,10 @@ impl Aead for AesAeadManager {
             return Err(QuantCryptError::InvalidAeadCiphertextLength);
         }
 
-        let cipher = match self.aead_type {
-            AeadType::AesGcm128 => Cipher::aes_128_gcm(),
-            AeadType::AesGcm256 => Cipher::aes_256_gcm(),
-        };
-
         let ct_len = ciphertext.len();
         let (ct, tag) = ciphertext.split_at(ct_len - 16);
 
-        let pt = decrypt_aead(cipher, key, Some(nonce), aad, ct, tag)
-            .map_err(|_| QuantCryptError::DecryptionFailed)?;
-
-        Ok(pt)
+        gcm_open(&self.aead_type, key, nonce, aad, ct, tag)
     }
 
     fn get_aead_info(&self) -> super::common::aead_info::AeadInfo {
@@ -94,7 +159,7 @@ impl Aead for AesAeadManager {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
     use super::*;
     use crate::aead::common::aead_type::AeadType;
@@ -123,6 +188,42 @@ mod tests {
         assert_eq!(plaintext, decrypted.as_slice());
     }
 
+    #[test]
+    fn test_aead_aes_gcm_committing() {
+        use crate::aead::common::aead_trait::KEY_COMMITMENT_LEN;
+
+        let aes = AesAeadManager::new(AeadType::AesGcm256).unwrap();
+        let key = b"01234567890123456789012345678901";
+        let other_key = b"11234567890123456789012345678901";
+        let nonce = b"012345678901";
+        let aad = b"01234567890123456789";
+        let plaintext = b"0123456789012345678901234567890123456789";
+        let ciphertext = aes.seal_committing(key, nonce, aad, plaintext).unwrap();
+        assert_eq!(ciphertext.len(), KEY_COMMITMENT_LEN + plaintext.len() + 16);
+        let decrypted = aes.open_committing(key, nonce, aad, &ciphertext).unwrap();
+        assert_eq!(plaintext, decrypted.as_slice());
+
+        // The ciphertext does not open under another key
+        assert_eq!(
+            aes.open_committing(other_key, nonce, aad, &ciphertext)
+                .unwrap_err(),
+            QuantCryptError::DecryptionFailed
+        );
+
+        // Nor with a tampered commitment
+        let mut tampered = ciphertext.clone();
+        tampered[0] ^= 1;
+        assert_eq!(
+            aes.open_committing(key, nonce, aad, &tampered).unwrap_err(),
+            QuantCryptError::DecryptionFailed
+        );
+        assert_eq!(
+            aes.open_committing(key, nonce, aad, &ciphertext[..16])
+                .unwrap_err(),
+            QuantCryptError::InvalidAeadCiphertextLength
+        );
+    }
+
     #[test]
     fn test_aead_aes_gcm_128_invalid_key_length() {
         let aes = AesAeadManager::new(AeadType::AesGcm128).unwrap();
@@ -1,8 +1,36 @@
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
 use crate::aead::common::aead_info::AeadInfo;
+use crate::utils::ct_eq::ct_eq;
 use crate::{aead::common::aead_type::AeadType, QuantCryptError};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// The length of the key commitment prepended by `seal_committing`, in bytes
+pub const KEY_COMMITMENT_LEN: usize = 32;
+
+/// Derive the AEAD key and the key commitment from a key
+///
+/// # Arguments
+///
+/// * `key` - The key, e.g. derived from a KEM shared secret
+/// * `k_len` - The key length of the AEAD
+///
+/// # Returns
+///
+/// A tuple containing the AEAD key and the commitment (key, commitment)
+fn derive_committed_key(key: &[u8], k_len: usize) -> Result<(Zeroizing<Vec<u8>>, Vec<u8>)> {
+    let hkdf = Hkdf::<Sha256>::new(None, key);
+    let mut aead_key = Zeroizing::new(vec![0u8; k_len]);
+    let mut commitment = vec![0u8; KEY_COMMITMENT_LEN];
+    hkdf.expand(b"quantcrypt aead key", &mut aead_key)
+        .and_then(|_| hkdf.expand(b"quantcrypt aead key commitment", &mut commitment))
+        .map_err(|_| QuantCryptError::InvalidAeadKeyLength)?;
+    Ok((aead_key, commitment))
+}
+
 pub trait Aead {
     /// Create a new Aead instance
     ///
@@ -17,6 +45,94 @@ pub trait Aead {
 
     fn open(&self, key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>>;
 
+    /// Encrypt a plaintext such that the ciphertext commits to the key
+    ///
+    /// With AES-GCM alone, a ciphertext can be crafted that decrypts under two
+    /// different keys. Here the AEAD key and a commitment are both derived from
+    /// `key` with HKDF-SHA256, and the commitment is prepended to the ciphertext,
+    /// so it only opens under the key it was sealed with.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, of the key length of the AEAD
+    /// * `nonce` - The nonce
+    /// * `aad` - The additional authenticated data
+    /// * `plaintext` - The plaintext
+    ///
+    /// # Returns
+    ///
+    /// The commitment followed by the ciphertext
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "openssl")]
+    /// # {
+    /// use quantcrypt::aeads::{Aead, AeadManager, AeadType};
+    ///
+    /// let aead = AeadManager::new(AeadType::AesGcm256).unwrap();
+    /// let key = [1u8; 32];
+    /// let nonce = [0u8; 12];
+    /// let ciphertext = aead.seal_committing(&key, &nonce, b"aad", b"secret").unwrap();
+    /// let plaintext = aead.open_committing(&key, &nonce, b"aad", &ciphertext).unwrap();
+    /// assert_eq!(plaintext, b"secret");
+    ///
+    /// // The ciphertext doesn't open under any other key
+    /// let other_key = [2u8; 32];
+    /// assert!(aead.open_committing(&other_key, &nonce, b"aad", &ciphertext).is_err());
+    /// # }
+    /// ```
+    fn seal_committing(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        if key.len() != self.get_aead_info().k_byte_len {
+            return Err(QuantCryptError::InvalidAeadKeyLength);
+        }
+        let (aead_key, mut result) = derive_committed_key(key, key.len())?;
+        result.extend(self.seal(&aead_key, nonce, aad, plaintext)?);
+        Ok(result)
+    }
+
+    /// Decrypt a ciphertext created by `seal_committing`
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, of the key length of the AEAD
+    /// * `nonce` - The nonce
+    /// * `aad` - The additional authenticated data
+    /// * `ciphertext` - The commitment followed by the ciphertext
+    ///
+    /// # Returns
+    ///
+    /// The plaintext
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::DecryptionFailed` if the ciphertext does not commit to the key
+    fn open_committing(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        if key.len() != self.get_aead_info().k_byte_len {
+            return Err(QuantCryptError::InvalidAeadKeyLength);
+        }
+        if ciphertext.len() < KEY_COMMITMENT_LEN {
+            return Err(QuantCryptError::InvalidAeadCiphertextLength);
+        }
+        let (commitment, ciphertext) = ciphertext.split_at(KEY_COMMITMENT_LEN);
+        let (aead_key, expected) = derive_committed_key(key, key.len())?;
+        if !ct_eq(commitment, &expected) {
+            return Err(QuantCryptError::DecryptionFailed);
+        }
+        self.open(&aead_key, nonce, aad, ciphertext)
+    }
+
     /// Get AEAD metadata information such as the key lengths,
     /// size of ciphertext, etc.
     ///
@@ -53,6 +53,20 @@ pub fn is_valid_kem_or_dsa_oid(oid: &String) -> bool {
     all_dsa_oids.contains(oid) || all_kem_oids.contains(oid)
 }
 
+/// Check if an OID is a legacy DSA OID, whose signatures can be verified
+/// but never produced
+///
+/// # Arguments
+///
+/// * `oid` - The OID to check
+///
+/// # Returns
+///
+/// True if the OID is a verify-only DSA OID, false otherwise
+pub fn is_verify_only_dsa_oid(oid: &str) -> bool {
+    DsaType::from_oid(oid).is_some_and(|dsa_type| dsa_type.is_legacy())
+}
+
 /// Check if an OID is a composite KEM / DSA OID
 ///
 /// # Arguments
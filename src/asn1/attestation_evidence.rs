@@ -0,0 +1,403 @@
+use der::asn1::{Any, Null, OctetString};
+use der::{Decode, Encode, Sequence};
+use pkcs8::ObjectIdentifier;
+
+use crate::asn1::certificate::Certificate;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// id-aa-evidence, the CSR attribute carrying attestation evidence
+/// (draft-ietf-lamps-csr-attestation)
+pub const EVIDENCE_ATTRIBUTE_OID: &str = "1.2.840.113549.1.9.16.2.59";
+
+/// tcg-attest-tpm-certify, the statement type of TPM 2.0 evidence
+pub const TPM_EVIDENCE_OID: &str = "2.23.133.20.1";
+
+/// tcg-dice-TcbInfo, the statement type of DICE evidence
+pub const DICE_EVIDENCE_OID: &str = "2.23.133.5.4.1";
+
+/// TPM_GENERATED_VALUE, the magic number starting every TPMS_ATTEST
+const TPM_GENERATED_VALUE: u32 = 0xff54_4347;
+
+/// TPM_ST_ATTEST_CERTIFY
+const TPM_ST_ATTEST_CERTIFY: u16 = 0x8017;
+
+/// TPM_ST_ATTEST_QUOTE
+const TPM_ST_ATTEST_QUOTE: u16 = 0x8018;
+
+/// EvidenceStatement ::= SEQUENCE {
+///     type   EVIDENCE-STATEMENT.&id,
+///     stmt   EVIDENCE-STATEMENT.&Type,
+///     hint   UTF8String OPTIONAL
+/// }
+#[derive(Debug, Clone, Sequence)]
+pub(crate) struct EvidenceStatement {
+    pub evidence_type: ObjectIdentifier,
+    pub stmt: Any,
+    #[asn1(optional = "true")]
+    pub hint: Option<String>,
+}
+
+/// EvidenceBundle ::= SEQUENCE {
+///     evidences   SEQUENCE SIZE (1..MAX) OF EvidenceStatement,
+///     certs       SEQUENCE SIZE (1..MAX) OF Certificate OPTIONAL
+/// }
+#[derive(Debug, Clone, Sequence)]
+pub(crate) struct EvidenceBundle {
+    pub evidences: Vec<EvidenceStatement>,
+    #[asn1(optional = "true")]
+    pub certs: Option<Vec<x509_cert::Certificate>>,
+}
+
+/// TcgAttestCertify ::= SEQUENCE {
+///     tpmSAttest   OCTET STRING,
+///     signature    OCTET STRING,
+///     tpmTPublic   OCTET STRING OPTIONAL
+/// }
+#[derive(Debug, Clone, Sequence)]
+struct TcgAttestCertify {
+    tpm_s_attest: OctetString,
+    signature: OctetString,
+    #[asn1(optional = "true")]
+    tpm_t_public: Option<OctetString>,
+}
+
+/// Hardware-backed evidence that a key was generated and is held by a device
+///
+/// Evidence is carried in certificate signing requests, in the id-aa-evidence
+/// attribute of draft-ietf-lamps-csr-attestation, so that a CA enrolling
+/// devices can require proof that their keys live in a TPM or a DICE-based
+/// root of trust. Each evidence is a statement of a given type, with the
+/// certificates needed to verify it:
+///
+/// * TPM 2.0: a TPMS_ATTEST structure, produced by TPM2_Quote or
+///   TPM2_Certify, with its TPMT_SIGNATURE by the attestation key. The
+///   certificates are the chain of the attestation key.
+/// * DICE: the chain of DICE certificates, alias certificate first. The
+///   measurements are in the TcbInfo extensions of the certificates.
+///
+/// Evidence of other types can be carried with `AttestationEvidence::new`.
+///
+/// # Example
+/// ```ignore
+/// use quantcrypt::certificates::{AttestationEvidence, CsrBuilder};
+///
+/// let evidence = AttestationEvidence::tpm(&tpms_attest, &tpmt_signature, ak_chain)?;
+/// let mut builder = CsrBuilder::new("CN=device-1234".to_string(), &sk)?;
+/// builder.add_evidence(evidence);
+/// let csr = builder.build()?;
+/// ```
+#[derive(Clone)]
+pub struct AttestationEvidence {
+    evidence_type: String,
+    statement: Vec<u8>,
+    hint: Option<String>,
+    certs: Vec<Certificate>,
+}
+
+impl AttestationEvidence {
+    /// Create evidence of any type
+    ///
+    /// # Arguments
+    ///
+    /// * `evidence_type` - The OID of the statement type
+    /// * `statement` - The DER encoded statement
+    /// * `hint` - A hint identifying the verifier of the evidence, if any
+    /// * `certs` - The certificates needed to verify the statement
+    ///
+    /// # Returns
+    ///
+    /// The evidence
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidOid` if the type isn't an OID,
+    /// `QuantCryptError::InvalidEvidence` if the statement isn't DER
+    pub fn new(
+        evidence_type: &str,
+        statement: &[u8],
+        hint: Option<String>,
+        certs: Vec<Certificate>,
+    ) -> Result<AttestationEvidence> {
+        ObjectIdentifier::new(evidence_type).map_err(|_| QuantCryptError::InvalidOid)?;
+        Any::from_der(statement).map_err(|_| QuantCryptError::InvalidEvidence)?;
+        Ok(AttestationEvidence {
+            evidence_type: evidence_type.to_string(),
+            statement: statement.to_vec(),
+            hint,
+            certs,
+        })
+    }
+
+    /// Create TPM 2.0 evidence
+    ///
+    /// # Arguments
+    ///
+    /// * `tpms_attest` - The TPMS_ATTEST structure returned by TPM2_Quote or TPM2_Certify
+    /// * `signature` - The TPMT_SIGNATURE of the structure by the attestation key
+    /// * `ak_chain` - The certificate chain of the attestation key, its certificate first
+    ///
+    /// # Returns
+    ///
+    /// The evidence
+    pub fn tpm(
+        tpms_attest: &[u8],
+        signature: &[u8],
+        ak_chain: Vec<Certificate>,
+    ) -> Result<AttestationEvidence> {
+        let statement = TcgAttestCertify {
+            tpm_s_attest: OctetString::new(tpms_attest)
+                .map_err(|_| QuantCryptError::InvalidEvidence)?,
+            signature: OctetString::new(signature).map_err(|_| QuantCryptError::InvalidEvidence)?,
+            tpm_t_public: None,
+        }
+        .to_der()
+        .map_err(|_| QuantCryptError::InvalidEvidence)?;
+        AttestationEvidence::new(TPM_EVIDENCE_OID, &statement, None, ak_chain)
+    }
+
+    /// Create DICE evidence
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The DICE certificate chain, alias certificate first
+    ///
+    /// # Returns
+    ///
+    /// The evidence
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEvidence` if the chain is empty
+    pub fn dice(chain: Vec<Certificate>) -> Result<AttestationEvidence> {
+        if chain.is_empty() {
+            return Err(QuantCryptError::InvalidEvidence);
+        }
+        let statement = Null
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidEvidence)?;
+        AttestationEvidence::new(DICE_EVIDENCE_OID, &statement, None, chain)
+    }
+
+    /// Get the OID of the statement type
+    pub fn get_type(&self) -> &str {
+        &self.evidence_type
+    }
+
+    /// Get the DER encoded statement
+    pub fn get_statement(&self) -> &[u8] {
+        &self.statement
+    }
+
+    /// Get the hint identifying the verifier of the evidence
+    pub fn get_hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    /// Get the certificates needed to verify the statement
+    pub fn get_certs(&self) -> &[Certificate] {
+        &self.certs
+    }
+
+    /// Check if this is TPM 2.0 evidence
+    pub fn is_tpm(&self) -> bool {
+        self.evidence_type == TPM_EVIDENCE_OID
+    }
+
+    /// Check if this is DICE evidence
+    pub fn is_dice(&self) -> bool {
+        self.evidence_type == DICE_EVIDENCE_OID
+    }
+
+    /// Get the TPMS_ATTEST structure and its TPMT_SIGNATURE of TPM 2.0 evidence
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the structure and the signature (tpms_attest, signature)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEvidence` if this isn't TPM 2.0 evidence
+    pub fn get_tpm_attest(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        if !self.is_tpm() {
+            return Err(QuantCryptError::InvalidEvidence);
+        }
+        let statement = TcgAttestCertify::from_der(&self.statement)
+            .map_err(|_| QuantCryptError::InvalidEvidence)?;
+        Ok((
+            statement.tpm_s_attest.as_bytes().to_vec(),
+            statement.signature.as_bytes().to_vec(),
+        ))
+    }
+
+    /// Get the qualifying data (extraData) of TPM 2.0 evidence
+    ///
+    /// The qualifying data is chosen by the caller of TPM2_Quote or
+    /// TPM2_Certify. In an enrollment flow it is a nonce from the CA, which
+    /// proves that the evidence is fresh.
+    ///
+    /// # Returns
+    ///
+    /// The qualifying data
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEvidence` if this isn't TPM 2.0 evidence, or
+    /// the structure isn't a TPMS_ATTEST of a quote or certification
+    pub fn get_tpm_qualifying_data(&self) -> Result<Vec<u8>> {
+        let (attest, _) = self.get_tpm_attest()?;
+        parse_tpms_attest(&attest)
+    }
+
+    pub(crate) fn to_bundle(&self) -> Result<EvidenceBundle> {
+        let statement = EvidenceStatement {
+            evidence_type: ObjectIdentifier::new(&self.evidence_type)
+                .map_err(|_| QuantCryptError::InvalidOid)?,
+            stmt: Any::from_der(&self.statement).map_err(|_| QuantCryptError::InvalidEvidence)?,
+            hint: self.hint.clone(),
+        };
+        let certs = if self.certs.is_empty() {
+            None
+        } else {
+            Some(self.certs.iter().map(|c| c.get_inner().clone()).collect())
+        };
+        Ok(EvidenceBundle {
+            evidences: vec![statement],
+            certs,
+        })
+    }
+
+    pub(crate) fn from_bundle(bundle: EvidenceBundle) -> Result<Vec<AttestationEvidence>> {
+        if bundle.evidences.is_empty() {
+            return Err(QuantCryptError::InvalidEvidence);
+        }
+        let certs: Vec<Certificate> = bundle
+            .certs
+            .unwrap_or_default()
+            .into_iter()
+            .map(Certificate::new)
+            .collect();
+        bundle
+            .evidences
+            .into_iter()
+            .map(|statement| {
+                Ok(AttestationEvidence {
+                    evidence_type: statement.evidence_type.to_string(),
+                    statement: statement
+                        .stmt
+                        .to_der()
+                        .map_err(|_| QuantCryptError::InvalidEvidence)?,
+                    hint: statement.hint,
+                    certs: certs.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse the header of a TPMS_ATTEST structure, up to the qualifying data
+///
+/// ```text
+/// magic: u32, type: u16, qualifiedSigner: TPM2B_NAME, extraData: TPM2B_DATA, ...
+/// ```
+fn parse_tpms_attest(attest: &[u8]) -> Result<Vec<u8>> {
+    fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+        if input.len() < len {
+            return Err(QuantCryptError::InvalidEvidence);
+        }
+        let (head, tail) = input.split_at(len);
+        *input = tail;
+        Ok(head)
+    }
+    fn take_u16(input: &mut &[u8]) -> Result<u16> {
+        let bytes = take(input, 2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    let mut input = attest;
+    let magic = take(&mut input, 4)?;
+    if u32::from_be_bytes([magic[0], magic[1], magic[2], magic[3]]) != TPM_GENERATED_VALUE {
+        return Err(QuantCryptError::InvalidEvidence);
+    }
+    match take_u16(&mut input)? {
+        TPM_ST_ATTEST_CERTIFY | TPM_ST_ATTEST_QUOTE => {}
+        _ => return Err(QuantCryptError::InvalidEvidence),
+    }
+    let signer_len = take_u16(&mut input)? as usize;
+    take(&mut input, signer_len)?;
+    let extra_data_len = take_u16(&mut input)? as usize;
+    Ok(take(&mut input, extra_data_len)?.to_vec())
+}
+
+/// Verifies the signatures of attestation evidence, for CAs enrolling devices
+///
+/// `CertificateSigningRequest::verify_attestation` checks the structure of
+/// the evidence, its freshness and the linkage of its certificate chain. The
+/// signatures of TPM attestation keys and DICE certificates are made with
+/// the algorithms of the hardware, typically RSA and ECDSA, and whether the
+/// chain ends at a trusted manufacturer is a policy of the CA, so both are
+/// left to the verifier.
+pub trait EvidenceVerifier {
+    /// Verify the signatures of the evidence and the trust in its certificates
+    ///
+    /// # Arguments
+    ///
+    /// * `evidence` - The evidence
+    ///
+    /// # Returns
+    ///
+    /// True if the evidence is authentic and trusted, false otherwise
+    fn verify(&self, evidence: &AttestationEvidence) -> Result<bool>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tpms_attest(extra_data: &[u8]) -> Vec<u8> {
+        let mut attest = TPM_GENERATED_VALUE.to_be_bytes().to_vec();
+        attest.extend_from_slice(&TPM_ST_ATTEST_QUOTE.to_be_bytes());
+        attest.extend_from_slice(&[0, 2, 0x00, 0x0b]);
+        attest.extend_from_slice(&(extra_data.len() as u16).to_be_bytes());
+        attest.extend_from_slice(extra_data);
+        attest.extend_from_slice(&[0u8; 17]);
+        attest
+    }
+
+    #[test]
+    fn test_tpm_evidence() {
+        let attest = tpms_attest(b"nonce");
+        let evidence = AttestationEvidence::tpm(&attest, b"signature", Vec::new()).unwrap();
+        assert!(evidence.is_tpm());
+        assert!(!evidence.is_dice());
+        assert_eq!(evidence.get_type(), TPM_EVIDENCE_OID);
+        assert_eq!(
+            evidence.get_tpm_attest().unwrap(),
+            (attest.clone(), b"signature".to_vec())
+        );
+        assert_eq!(evidence.get_tpm_qualifying_data().unwrap(), b"nonce");
+
+        let parsed = AttestationEvidence::from_bundle(evidence.to_bundle().unwrap()).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].get_statement(), evidence.get_statement());
+
+        // Not a TPMS_ATTEST, or truncated
+        let mut bad = attest.clone();
+        bad[0] = 0;
+        let evidence = AttestationEvidence::tpm(&bad, b"signature", Vec::new()).unwrap();
+        assert_eq!(
+            evidence.get_tpm_qualifying_data(),
+            Err(QuantCryptError::InvalidEvidence)
+        );
+        let evidence = AttestationEvidence::tpm(&attest[..10], b"signature", Vec::new()).unwrap();
+        assert_eq!(
+            evidence.get_tpm_qualifying_data(),
+            Err(QuantCryptError::InvalidEvidence)
+        );
+
+        assert!(AttestationEvidence::dice(Vec::new()).is_err());
+        assert!(AttestationEvidence::new("not an oid", &[0x05, 0x00], None, Vec::new()).is_err());
+        assert!(AttestationEvidence::new(DICE_EVIDENCE_OID, &[0x05], None, Vec::new()).is_err());
+    }
+}
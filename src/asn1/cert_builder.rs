@@ -1,19 +1,29 @@
 use std::error::Error;
 use std::str::FromStr;
+use std::time::Duration;
 
 use chrono::{DateTime, Datelike, TimeZone, Timelike};
+use der::asn1::{BitString, GeneralizedTime, OctetString};
+use der::{Decode, Encode};
 use pkcs8::spki::SubjectPublicKeyInfo;
+use pkcs8::ObjectIdentifier;
 use rand::RngCore;
 use rand_core::OsRng;
+use signature::Keypair;
 use x509_cert::builder::Builder;
 pub use x509_cert::builder::Profile;
+use x509_cert::ext::pkix::{AuthorityKeyIdentifier, PrivateKeyUsagePeriod, SubjectKeyIdentifier};
 use x509_cert::ext::AsExtension;
 use x509_cert::time::Time;
+use x509_cert::TbsCertificate;
 use x509_cert::{name::Name, serial_number::SerialNumber, time::Validity};
 
 use crate::{errors::QuantCryptError, keys::PrivateKey, keys::PublicKey};
 
 use crate::asn1::certificate::Certificate;
+use crate::asn1::key_id::KeyIdMethod;
+use crate::asn1::oid_alias::OidAliasTable;
+use crate::utils::deprecation::{report_deprecation, DeprecationWarning};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
@@ -162,6 +172,10 @@ impl CertValidity {
 /// ```
 pub struct CertificateBuilder<'a> {
     builder: x509_cert::builder::CertificateBuilder<'a, PrivateKey>,
+    signer: &'a PrivateKey,
+    legacy_aliases: Option<OidAliasTable>,
+    key_id_method: KeyIdMethod,
+    warnings: Vec<DeprecationWarning>,
 }
 
 impl<'a> CertificateBuilder<'a> {
@@ -176,6 +190,14 @@ impl<'a> CertificateBuilder<'a> {
     ) -> Result<CertificateBuilder<'a>> {
         let subject = Name::from_str(&subject).map_err(|_| QuantCryptError::BadSubject)?;
 
+        // The signer's algorithm is reported by its DSA manager when signing
+        let subject_warning = DeprecationWarning::for_oid(cert_public_key.get_oid());
+        report_deprecation(subject_warning.clone());
+        let warnings = subject_warning
+            .into_iter()
+            .chain(DeprecationWarning::for_oid(signer.get_oid()))
+            .collect();
+
         let spki = SubjectPublicKeyInfo::from_key(cert_public_key)
             .map_err(|_| QuantCryptError::BadPublicKey)?;
 
@@ -200,7 +222,22 @@ impl<'a> CertificateBuilder<'a> {
         )
         .map_err(|_| QuantCryptError::Unknown)?;
 
-        Ok(CertificateBuilder { builder })
+        Ok(CertificateBuilder {
+            builder,
+            signer,
+            legacy_aliases: None,
+            key_id_method: KeyIdMethod::default(),
+            warnings,
+        })
+    }
+
+    /// Get the warnings about deprecated algorithms of the subject key and the signer
+    ///
+    /// # Returns
+    ///
+    /// The warnings, empty if neither algorithm is deprecated
+    pub fn get_warnings(&self) -> &[DeprecationWarning] {
+        &self.warnings
     }
 
     pub fn add_extension(&mut self, extension: impl AsExtension) -> Result<&mut Self> {
@@ -211,6 +248,93 @@ impl<'a> CertificateBuilder<'a> {
         Ok(self)
     }
 
+    /// Emit legacy OIDs for the subject public key and signature algorithm
+    ///
+    /// Use this when issuing certificates to partners who only understand an earlier
+    /// draft's OIDs. Algorithms without a legacy OID in the table are emitted unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `aliases` - The OID alias table
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn emit_legacy_oids(&mut self, aliases: &OidAliasTable) -> &mut Self {
+        self.legacy_aliases = Some(aliases.clone());
+        self
+    }
+
+    /// Set the method used to compute the Subject and Authority Key Identifiers
+    ///
+    /// By default, key identifiers use the SHA-1 method of RFC 5280. All certificates
+    /// in a hierarchy should use the same method, so that the Authority Key Identifier
+    /// of a child matches the Subject Key Identifier of its issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The key identifier method
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn set_key_id_method(&mut self, method: KeyIdMethod) -> &mut Self {
+        self.key_id_method = method;
+        self
+    }
+
+    /// Add a Private Key Usage Period extension
+    ///
+    /// The private key usage period restricts when the private key may sign, independently
+    /// of the validity of the certificate, which may outlive it so that signatures stay
+    /// verifiable.
+    ///
+    /// # Arguments
+    ///
+    /// * `not_before` - The start of the usage period in RFC3339 format, if bounded
+    /// * `not_after` - The end of the usage period in RFC3339 format, if bounded
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidNotBefore` or `QuantCryptError::InvalidNotAfter` if a date
+    /// cannot be parsed, is before the Unix epoch, or the period is empty
+    pub fn set_private_key_usage_period(
+        &mut self,
+        not_before: Option<&str>,
+        not_after: Option<&str>,
+    ) -> Result<&mut Self> {
+        let parse = |time: &str, err: QuantCryptError| {
+            let secs = DateTime::parse_from_rfc3339(time)
+                .ok()
+                .and_then(|time| u64::try_from(time.timestamp()).ok());
+            match secs {
+                Some(secs) => {
+                    GeneralizedTime::from_unix_duration(Duration::from_secs(secs)).map_err(|_| err)
+                }
+                None => Err(err),
+            }
+        };
+        let not_before = not_before
+            .map(|time| parse(time, QuantCryptError::InvalidNotBefore))
+            .transpose()?;
+        let not_after = not_after
+            .map(|time| parse(time, QuantCryptError::InvalidNotAfter))
+            .transpose()?;
+        if let (Some(not_before), Some(not_after)) = (not_before, not_after) {
+            if not_before > not_after {
+                return Err(QuantCryptError::InvalidNotBefore);
+            }
+        }
+        self.add_extension(PrivateKeyUsagePeriod {
+            not_before,
+            not_after,
+        })
+    }
+
     /// Return a random SerialNumber value
     fn get_random_serial() -> Result<SerialNumber> {
         let mut serial = [0u8; 20];
@@ -220,20 +344,139 @@ impl<'a> CertificateBuilder<'a> {
         Ok(serial)
     }
 
+    /// Replace the key identifiers added by the profile with ones computed using the configured method
+    fn set_key_ids(&self, tbs: &mut TbsCertificate) -> Result<()> {
+        let spki = &tbs.subject_public_key_info;
+        let spki_der = spki.to_der().map_err(|_| QuantCryptError::BadPublicKey)?;
+        let skid = self
+            .key_id_method
+            .compute(spki.subject_public_key.raw_bytes(), &spki_der);
+        let akid = self.signer.verifying_key().get_key_id(self.key_id_method)?;
+
+        for ext in tbs.extensions.iter_mut().flatten() {
+            let value = if ext.extn_id == const_oid::db::rfc5280::ID_CE_SUBJECT_KEY_IDENTIFIER {
+                SubjectKeyIdentifier(
+                    OctetString::new(skid.clone()).map_err(|_| QuantCryptError::BadExtension)?,
+                )
+                .to_der()
+            } else if ext.extn_id == const_oid::db::rfc5280::ID_CE_AUTHORITY_KEY_IDENTIFIER {
+                AuthorityKeyIdentifier {
+                    key_identifier: Some(
+                        OctetString::new(akid.clone())
+                            .map_err(|_| QuantCryptError::BadExtension)?,
+                    ),
+                    authority_cert_issuer: None,
+                    authority_cert_serial_number: None,
+                }
+                .to_der()
+            } else {
+                continue;
+            };
+            let value = value.map_err(|_| QuantCryptError::BadExtension)?;
+            ext.extn_value = OctetString::new(value).map_err(|_| QuantCryptError::BadExtension)?;
+        }
+        Ok(())
+    }
+
     pub fn build(self) -> Result<Certificate> {
-        let cert_inner = self.builder.build().map_err(|_| QuantCryptError::Unknown)?;
-        let cert = Certificate::new(cert_inner);
-        Ok(cert)
+        if self.legacy_aliases.is_none() && self.key_id_method == KeyIdMethod::default() {
+            let cert_inner = self.builder.build().map_err(|_| QuantCryptError::Unknown)?;
+            return Ok(Certificate::new(cert_inner));
+        }
+        self.build_checked(|_| Ok(()))
+    }
+
+    /// Build the certificate, passing the final TBS certificate to a check before signing it
+    ///
+    /// # Arguments
+    ///
+    /// * `check` - Inspects the TBS certificate. If it returns an error, nothing is signed.
+    ///
+    /// # Returns
+    ///
+    /// The signed certificate
+    pub(crate) fn build_checked(
+        mut self,
+        check: impl FnOnce(&TbsCertificate) -> Result<()>,
+    ) -> Result<Certificate> {
+        // Rewrite the TBS certificate before signing it, so the signature
+        // covers the legacy OIDs and key identifiers
+        let tbs_der = self
+            .builder
+            .finalize()
+            .map_err(|_| QuantCryptError::Unknown)?;
+        let mut tbs = TbsCertificate::from_der(&tbs_der).map_err(|_| QuantCryptError::Unknown)?;
+        if let Some(aliases) = &self.legacy_aliases {
+            for alg in [
+                &mut tbs.signature,
+                &mut tbs.subject_public_key_info.algorithm,
+            ] {
+                if let Some(legacy) = aliases.legacy_for(&alg.oid.to_string()) {
+                    alg.oid =
+                        ObjectIdentifier::new(legacy).map_err(|_| QuantCryptError::InvalidOid)?;
+                }
+            }
+        }
+        if self.key_id_method != KeyIdMethod::default() {
+            self.set_key_ids(&mut tbs)?;
+        }
+        check(&tbs)?;
+        let tbs_der = tbs.to_der().map_err(|_| QuantCryptError::Unknown)?;
+
+        let sig = self.signer.sign(&tbs_der)?;
+        let signature = BitString::from_bytes(&sig).map_err(|_| QuantCryptError::Unknown)?;
+        let cert_inner = x509_cert::Certificate {
+            signature_algorithm: tbs.signature.clone(),
+            tbs_certificate: tbs,
+            signature,
+        };
+        Ok(Certificate::new(cert_inner))
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod test {
 
     use crate::{dsas::DsaAlgorithm, dsas::DsaKeyGenerator};
 
     use super::*;
 
+    #[test]
+    fn test_deprecation_warnings() {
+        let validity = CertValidity::new(None, "2034-01-01T00:00:00Z").unwrap();
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44Rsa2048PssSha256)
+            .generate()
+            .unwrap();
+        let builder = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity.clone(),
+            "CN=RSA-2048 Root".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap();
+        let warnings = builder.get_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].algorithm, "MlDsa44Rsa2048PssSha256");
+        assert_eq!(warnings[0].deprecation.sunset_year, Some(2030));
+        assert!(builder.build().is_ok());
+
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+        let builder = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=ML-DSA Root".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap();
+        assert!(builder.get_warnings().is_empty());
+    }
+
     #[test]
     fn gen_pq_hackathon_artifacts_r4() {
         // Generate R4 artifacts for the hackathon
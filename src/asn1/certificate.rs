@@ -1,3 +1,5 @@
+use crate::asn1::key_id::KeyIdMethod;
+use crate::asn1::oid_alias::OidAliasTable;
 use crate::{
     dsa::{
         common::{dsa_trait::Dsa, prehash_dsa_trait::PrehashDsa},
@@ -5,28 +7,47 @@ use crate::{
     },
     kem::{common::kem_trait::Kem, kem_manager::KemManager},
     keys::PublicKey,
+    utils::clock_skew::{get_clock_skew_tolerance, is_within},
+    utils::verification_report::VerificationReport,
 };
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use cms::enveloped_data::RecipientIdentifier;
-use der::{Decode, DecodePem, Encode, EncodePem};
+use cms::signed_data::SignerIdentifier;
+use der::{Decode, Encode, EncodePem};
+use pkcs8::ObjectIdentifier;
+use std::time::Duration;
 use x509_cert::{
-    ext::pkix::{AuthorityKeyIdentifier, KeyUsage, SubjectKeyIdentifier},
+    ext::pkix::{
+        crl::dp::DistributionPoint,
+        name::{DistributionPointName, GeneralName},
+        AuthorityInfoAccessSyntax, AuthorityKeyIdentifier, BasicConstraints, CrlDistributionPoints,
+        FreshestCrl, KeyUsage, PrivateKeyUsagePeriod, SubjectKeyIdentifier,
+    },
     name::RdnSequence,
     serial_number::SerialNumber,
 };
 
 use crate::errors::QuantCryptError;
+use crate::utils::resource_limits::{check_der_input, check_input_len, read_file_limited};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// The bounds of a private key usage period, either of which may be absent
+type UsagePeriod = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
 /// A certificate
 ///
 /// # Example
 /// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
 /// use quantcrypt::certificates::Certificate;
 /// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
 /// let cert = Certificate::from_file(cert_path).unwrap();
 /// assert!(cert.verify_self_signed().unwrap());
+/// # }
 /// ```
 #[derive(Clone)]
 pub struct Certificate {
@@ -86,7 +107,11 @@ impl Certificate {
     /// # Errors
     ///
     /// `CertificateError::InvalidCertificate` will be returned if the certificate is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input or its decoding
+    /// doesn't fit in the parse budget
     pub fn from_der(der: &[u8]) -> Result<Certificate> {
+        check_der_input(der)?;
         let cert = x509_cert::Certificate::from_der(der)
             .map_err(|_| QuantCryptError::InvalidCertificate)?;
 
@@ -106,10 +131,15 @@ impl Certificate {
     /// # Errors
     ///
     /// `CertificateError::InvalidCertificate` will be returned if the certificate is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input is larger than the parse budget
     pub fn from_pem(pem: &str) -> Result<Certificate> {
-        let cert = x509_cert::Certificate::from_pem(pem)
-            .map_err(|_| QuantCryptError::InvalidCertificate)?;
-        Ok(Certificate::new(cert))
+        check_input_len(pem.len())?;
+        let pem = pem::parse(pem).map_err(|_| QuantCryptError::InvalidCertificate)?;
+        if pem.tag() != "CERTIFICATE" {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        Self::from_der(pem.contents())
     }
 
     /// Get the subject name
@@ -158,12 +188,162 @@ impl Certificate {
         Err(QuantCryptError::SkidNotFound)
     }
 
+    /// Get the HTTP URLs of the CRL distribution points
+    ///
+    /// # Returns
+    ///
+    /// The URLs listed in the CRL Distribution Points extension, empty if the extension is absent
+    pub fn get_crl_distribution_points(&self) -> Vec<String> {
+        self.cert
+            .tbs_certificate
+            .extensions
+            .iter()
+            .flatten()
+            .filter(|ext| ext.extn_id == const_oid::db::rfc5280::ID_CE_CRL_DISTRIBUTION_POINTS)
+            .filter_map(|ext| CrlDistributionPoints::from_der(ext.extn_value.as_bytes()).ok())
+            .flat_map(|cdp| get_distribution_point_urls(&cdp.0))
+            .collect()
+    }
+
+    /// Get the HTTP URLs of the delta CRLs
+    ///
+    /// # Returns
+    ///
+    /// The URLs listed in the Freshest CRL extension, empty if the extension is absent
+    pub fn get_freshest_crl_urls(&self) -> Vec<String> {
+        self.cert
+            .tbs_certificate
+            .extensions
+            .iter()
+            .flatten()
+            .filter(|ext| ext.extn_id == const_oid::db::rfc5280::ID_CE_FRESHEST_CRL)
+            .filter_map(|ext| FreshestCrl::from_der(ext.extn_value.as_bytes()).ok())
+            .flat_map(|freshest| get_distribution_point_urls(&freshest.0))
+            .collect()
+    }
+
+    /// Get the HTTP URLs of the OCSP responders
+    ///
+    /// # Returns
+    ///
+    /// The OCSP URLs listed in the Authority Information Access extension, empty if there are none
+    pub fn get_ocsp_responders(&self) -> Vec<String> {
+        self.get_authority_info_access(const_oid::db::rfc5280::ID_AD_OCSP)
+    }
+
+    /// Get the HTTP URLs where the issuer's certificate can be downloaded
+    ///
+    /// # Returns
+    ///
+    /// The CA Issuers URLs listed in the Authority Information Access extension, empty if there are none
+    pub fn get_ca_issuers(&self) -> Vec<String> {
+        self.get_authority_info_access(const_oid::db::rfc5280::ID_AD_CA_ISSUERS)
+    }
+
+    /// Get the HTTP URLs in the Authority Information Access extension for an access method
+    fn get_authority_info_access(&self, access_method: ObjectIdentifier) -> Vec<String> {
+        let mut urls = Vec::new();
+        if let Some(exts) = &self.cert.tbs_certificate.extensions {
+            for ext in exts {
+                if ext.extn_id == const_oid::db::rfc5280::ID_PE_AUTHORITY_INFO_ACCESS {
+                    if let Ok(aia) = AuthorityInfoAccessSyntax::from_der(ext.extn_value.as_bytes())
+                    {
+                        urls.extend(
+                            aia.0
+                                .iter()
+                                .filter(|ad| ad.access_method == access_method)
+                                .filter_map(|ad| get_http_url(&ad.access_location)),
+                        );
+                    }
+                }
+            }
+        }
+        urls
+    }
+
+    /// Get the underlying x509-cert certificate
+    pub(crate) fn get_inner(&self) -> &x509_cert::Certificate {
+        &self.cert
+    }
+
+    /// Compute the key identifier of the certificate's public key
+    ///
+    /// Unlike `get_subject_key_identifier`, this doesn't rely on the extension being present.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The key identifier method
+    ///
+    /// # Returns
+    ///
+    /// The key identifier
+    pub fn get_key_id(&self, method: KeyIdMethod) -> Result<Vec<u8>> {
+        let spki = &self.cert.tbs_certificate.subject_public_key_info;
+        let spki_der = spki
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidCertificate)?;
+        Ok(method.compute(spki.subject_public_key.raw_bytes(), &spki_der))
+    }
+
+    /// Compute the SPKI pin of the certificate's public key
+    ///
+    /// The pin is the base64 encoded SHA-256 of the DER encoded
+    /// SubjectPublicKeyInfo, as used by HPKP (RFC 7469) and mobile pinning
+    /// libraries. It stays the same when the certificate is renewed for the same key.
+    ///
+    /// # Returns
+    ///
+    /// The pin
+    pub fn get_spki_pin(&self) -> Result<String> {
+        Ok(STANDARD.encode(self.get_key_id(KeyIdMethod::SpkiSha256)?))
+    }
+
     /// Verify that the certificate is self-signed
     ///
     /// # Returns
     ///
     /// True if the certificate is self-signed, false otherwise
     pub fn verify_self_signed(&self) -> Result<bool> {
+        Ok(self.verify_self_signed_report()?.is_valid())
+    }
+
+    /// Verify that the certificate is self-signed, reporting the checks performed
+    ///
+    /// # Returns
+    ///
+    /// A report with the outcome and the checks performed
+    pub fn verify_self_signed_report(&self) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.valid = self.verify_self_signed_inner(None, &mut report)?;
+        Ok(report)
+    }
+
+    /// Verify that the certificate is self-signed, accepting legacy OIDs
+    ///
+    /// Any legacy OID found in the certificate's public key or signature algorithm
+    /// is translated using the alias table and reported as a warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `aliases` - The OID alias table
+    ///
+    /// # Returns
+    ///
+    /// A report with the outcome, the checks performed and any warnings
+    pub fn verify_self_signed_with_aliases(
+        &self,
+        aliases: &OidAliasTable,
+    ) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.valid = self.verify_self_signed_inner(Some(aliases), &mut report)?;
+        Ok(report)
+    }
+
+    fn verify_self_signed_inner(
+        &self,
+        aliases: Option<&OidAliasTable>,
+        report: &mut VerificationReport,
+    ) -> Result<bool> {
         // The certificate must contain basic constraints with cA set to true
         if let Some(exts) = self.cert.tbs_certificate.extensions.clone() {
             for ext in exts {
@@ -178,11 +358,15 @@ impl Certificate {
                 }
             }
         } else {
-            return Ok(false);
+            return Ok(report.check("extensions present", false));
         }
+        report.check("extensions present", true);
 
         // The subject and issuer must be the same
-        if self.get_subject() != self.get_issuer() {
+        if !report.check(
+            "subject matches issuer",
+            self.get_subject() == self.get_issuer(),
+        ) {
             return Ok(false);
         }
 
@@ -194,11 +378,12 @@ impl Certificate {
 
         let sig = self.cert.signature.raw_bytes();
 
-        let pk = self.get_public_key()?;
+        let pk = self.get_public_key_inner(aliases, &mut report.warnings)?;
+        self.check_signature_oid(aliases, &mut report.warnings);
 
         let result = pk.verify(&msg, sig).unwrap_or(false);
 
-        Ok(result)
+        Ok(report.check("signature", result))
     }
 
     /// Get the public key
@@ -207,18 +392,59 @@ impl Certificate {
     ///
     /// The public key
     pub fn get_public_key(&self) -> Result<PublicKey> {
-        let pk_der = self
+        self.get_public_key_inner(None, &mut Vec::new())
+    }
+
+    /// Get the public key, translating a legacy algorithm OID
+    ///
+    /// # Arguments
+    ///
+    /// * `aliases` - The OID alias table
+    ///
+    /// # Returns
+    ///
+    /// The public key, carrying the current OID
+    pub fn get_public_key_with_aliases(&self, aliases: &OidAliasTable) -> Result<PublicKey> {
+        self.get_public_key_inner(Some(aliases), &mut Vec::new())
+    }
+
+    fn get_public_key_inner(
+        &self,
+        aliases: Option<&OidAliasTable>,
+        warnings: &mut Vec<String>,
+    ) -> Result<PublicKey> {
+        let mut pk_der = self
             .cert
             .tbs_certificate
             .subject_public_key_info
             .to_der()
             .map_err(|_| QuantCryptError::InvalidCertificate)?;
 
+        if let Some(aliases) = aliases {
+            let (translated, legacy_oid) = aliases
+                .translate_spki(&pk_der)
+                .map_err(|_| QuantCryptError::InvalidCertificate)?;
+            if let Some(legacy_oid) = legacy_oid {
+                warnings.push(aliases.warning("public key", &legacy_oid));
+            }
+            pk_der = translated;
+        }
+
         let pk = PublicKey::from_der(&pk_der).map_err(|_| QuantCryptError::InvalidCertificate)?;
 
         Ok(pk)
     }
 
+    /// Record a warning if the signature algorithm OID is a legacy OID
+    fn check_signature_oid(&self, aliases: Option<&OidAliasTable>, warnings: &mut Vec<String>) {
+        if let Some(aliases) = aliases {
+            let oid = self.get_signature_oid();
+            if aliases.resolve(&oid).is_some() {
+                warnings.push(aliases.warning("signature algorithm", &oid));
+            }
+        }
+    }
+
     /// Verify that the specified certificate is a child of this certificate.
     ///
     /// This checks that the specified child certificate has the same issuer as this certificate's subject,
@@ -233,8 +459,59 @@ impl Certificate {
     ///
     /// True if the child certificate is a child of this certificate, false otherwise
     pub fn verify_child(&self, child: &Certificate) -> Result<bool> {
+        Ok(self.verify_child_report(child)?.is_valid())
+    }
+
+    /// Verify that the specified certificate is a child of this certificate, reporting
+    /// the checks performed
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The child certificate
+    ///
+    /// # Returns
+    ///
+    /// A report with the outcome and the checks performed
+    pub fn verify_child_report(&self, child: &Certificate) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.valid = self.verify_child_inner(child, None, &mut report)?;
+        Ok(report)
+    }
+
+    /// Verify that the specified certificate is a child of this certificate, accepting legacy OIDs
+    ///
+    /// Any legacy OID found in this certificate's public key or the child's signature
+    /// algorithm is translated using the alias table and reported as a warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The child certificate
+    /// * `aliases` - The OID alias table
+    ///
+    /// # Returns
+    ///
+    /// A report with the outcome, the checks performed and any warnings
+    pub fn verify_child_with_aliases(
+        &self,
+        child: &Certificate,
+        aliases: &OidAliasTable,
+    ) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.valid = self.verify_child_inner(child, Some(aliases), &mut report)?;
+        Ok(report)
+    }
+
+    fn verify_child_inner(
+        &self,
+        child: &Certificate,
+        aliases: Option<&OidAliasTable>,
+        report: &mut VerificationReport,
+    ) -> Result<bool> {
         // If the child has a different issuer than the parent's subject, it cannot be a child
-        if self.get_subject() != child.get_issuer() {
+        if !report.check(
+            "issuer matches parent subject",
+            self.get_subject() == child.get_issuer(),
+        ) {
             return Ok(false);
         }
 
@@ -249,10 +526,10 @@ impl Certificate {
                         let akid = if let Some(akid) = akid.key_identifier {
                             akid
                         } else {
-                            return Ok(false);
+                            return Ok(report.check("authority key identifier", false));
                         };
 
-                        if akid != parent_skid.0 {
+                        if !report.check("authority key identifier", akid == parent_skid.0) {
                             return Ok(false);
                         }
                     }
@@ -267,13 +544,14 @@ impl Certificate {
             .to_der()
             .map_err(|_| QuantCryptError::InvalidCertificate)?;
         let sig = child.cert.signature.raw_bytes();
-        let pk = self.get_public_key()?;
+        let pk = self.get_public_key_inner(aliases, &mut report.warnings)?;
+        child.check_signature_oid(aliases, &mut report.warnings);
 
         let result = pk
             .verify(&msg, sig)
             .map_err(|_| QuantCryptError::InvalidCertificate)?;
 
-        Ok(result)
+        Ok(report.check("signature", result))
     }
 
     /// Load a certificate from the specified file. The file can be in either DER or PEM format.
@@ -287,7 +565,7 @@ impl Certificate {
     /// The certificate
     pub fn from_file(path: &str) -> Result<Certificate> {
         // Read the contents of the file as bytes
-        let contents = std::fs::read(path).map_err(|_| QuantCryptError::FileReadError)?;
+        let contents = read_file_limited(path)?;
 
         // Try to interpret as DER
         let result = Certificate::from_der(&contents);
@@ -359,12 +637,52 @@ impl Certificate {
         false
     }
 
+    /// Check if this certificate is identified by the specified signer identifier
+    ///
+    /// This could match by either issuer and serial number or subject key identifier
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The signer identifier
+    ///
+    /// # Returns
+    ///
+    /// True if the certificate is identified by the signer identifier, false otherwise
+    pub fn is_identified_by_sid(&self, sid: &SignerIdentifier) -> bool {
+        match sid {
+            SignerIdentifier::IssuerAndSerialNumber(issuer) => {
+                self.get_issuer() == issuer.issuer
+                    && self.get_serial_number() == issuer.serial_number
+            }
+            SignerIdentifier::SubjectKeyIdentifier(ski) => self
+                .get_subject_key_identifier()
+                .map(|cert_ski| cert_ski == *ski)
+                .unwrap_or(false),
+        }
+    }
+
     /// Check if this certificate is valid
     ///
+    /// The validity period is checked with the clock-skew tolerance set by
+    /// `set_clock_skew_tolerance` (5 minutes by default).
+    ///
     /// # Returns
     ///
     /// True if the certificate is valid, false otherwise
     pub fn is_valid(&self) -> bool {
+        self.is_valid_with_tolerance(get_clock_skew_tolerance())
+    }
+
+    /// Check if this certificate is valid, with a specific clock-skew tolerance
+    ///
+    /// # Arguments
+    ///
+    /// * `tolerance` - How far the current time may be outside the validity period
+    ///
+    /// # Returns
+    ///
+    /// True if the certificate is valid, false otherwise
+    pub fn is_valid_with_tolerance(&self, tolerance: Duration) -> bool {
         // Get the notBefore and notAfter fields as DateTime
         let not_before = self.cert.tbs_certificate.validity.not_before.to_date_time();
         let not_after = self.cert.tbs_certificate.validity.not_after.to_date_time();
@@ -373,11 +691,8 @@ impl Certificate {
         let not_before: DateTime<Utc> = not_before.to_system_time().into();
         let not_after: DateTime<Utc> = not_after.to_system_time().into();
 
-        // Get the current time
-        let now = chrono::Utc::now();
-
         // Check if the current time is within the validity period
-        let result = now >= not_before && now <= not_after;
+        let result = is_within(not_before, Some(not_after), tolerance);
 
         // Certificate sig oid must match the expected sig oid
         let oid = self.cert.signature_algorithm.oid;
@@ -389,6 +704,68 @@ impl Certificate {
         result
     }
 
+    /// Get the private key usage period of the certificate
+    ///
+    /// # Returns
+    ///
+    /// The (not before, not after) bounds of the usage period, either of which may be
+    /// absent, or None if the certificate has no Private Key Usage Period extension
+    pub fn get_private_key_usage_period(&self) -> Option<UsagePeriod> {
+        let period = self
+            .cert
+            .tbs_certificate
+            .extensions
+            .iter()
+            .flatten()
+            .find(|ext| ext.extn_id == const_oid::db::rfc5280::ID_CE_PRIVATE_KEY_USAGE_PERIOD)
+            .and_then(|ext| PrivateKeyUsagePeriod::from_der(ext.extn_value.as_bytes()).ok())?;
+        let to_chrono = |time: der::asn1::GeneralizedTime| time.to_system_time().into();
+        Some((
+            period.not_before.map(to_chrono),
+            period.not_after.map(to_chrono),
+        ))
+    }
+
+    /// Check if the current time is within the private key usage period of this certificate
+    ///
+    /// The private key usage period (RFC 3280 Section 4.2.1.4) can be shorter than the
+    /// validity of the certificate, so that signatures stay verifiable after the key
+    /// has been retired. The clock-skew tolerance set by `set_clock_skew_tolerance`
+    /// applies. The validity of the certificate itself is not checked.
+    ///
+    /// # Returns
+    ///
+    /// True if the current time is within the private key usage period, or the
+    /// certificate has no Private Key Usage Period extension
+    pub fn is_private_key_usable(&self) -> bool {
+        match self.get_private_key_usage_period() {
+            Some((not_before, not_after)) => is_within(
+                not_before.unwrap_or(DateTime::<Utc>::UNIX_EPOCH),
+                not_after,
+                get_clock_skew_tolerance(),
+            ),
+            None => true,
+        }
+    }
+
+    /// Check if this certificate is a CA certificate
+    ///
+    /// # Returns
+    ///
+    /// True if the Basic Constraints extension is present and the CA flag is set, false otherwise
+    pub fn is_ca(&self) -> bool {
+        if let Some(exts) = &self.cert.tbs_certificate.extensions {
+            for ext in exts {
+                if ext.extn_id == const_oid::db::rfc5280::ID_CE_BASIC_CONSTRAINTS {
+                    if let Ok(bc) = BasicConstraints::from_der(ext.extn_value.as_bytes()) {
+                        return bc.ca;
+                    }
+                }
+            }
+        }
+        false
+    }
+
     /// Check if key encipherment is enabled
     ///
     /// # Returns
@@ -452,9 +829,35 @@ impl Certificate {
     }
 }
 
+/// Get the URL of a general name, if it is an HTTP URI
+/// Get the HTTP URLs of distribution points
+pub(crate) fn get_distribution_point_urls(points: &[DistributionPoint]) -> Vec<String> {
+    points
+        .iter()
+        .filter_map(|dp| match &dp.distribution_point {
+            Some(DistributionPointName::FullName(names)) => Some(names),
+            _ => None,
+        })
+        .flat_map(|names| names.iter().filter_map(get_http_url))
+        .collect()
+}
+
+fn get_http_url(name: &GeneralName) -> Option<String> {
+    match name {
+        GeneralName::UniformResourceIdentifier(uri) => {
+            let uri = uri.to_string();
+            uri.to_ascii_lowercase()
+                .starts_with("http://")
+                .then_some(uri)
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::certificates::CertValidity;
+    use crate::keys::KeyIdMethod;
 
     #[test]
     fn test_akid_skid() {
@@ -528,15 +931,83 @@ mod tests {
         .unwrap()
         .build()
         .unwrap();
-        assert!(!cert.is_valid());
+        let strict = std::time::Duration::ZERO;
+        assert!(!cert.is_valid_with_tolerance(strict));
+        // Within the default clock-skew tolerance
+        assert!(cert.is_valid());
         // sleep for 1 second
         std::thread::sleep(std::time::Duration::from_secs(3));
-        assert!(cert.is_valid());
+        assert!(cert.is_valid_with_tolerance(strict));
         // sleep for 3 seconds
         std::thread::sleep(std::time::Duration::from_secs(5));
-        assert!(!cert.is_valid());
+        assert!(!cert.is_valid_with_tolerance(strict));
+        assert!(cert.is_valid());
     }
 
+    #[test]
+    fn test_private_key_usage_period() {
+        let now = chrono::Utc::now();
+        let day = chrono::Duration::days(1);
+        let (pk, sk) = crate::dsas::DsaKeyGenerator::new(crate::dsas::DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let issue = |not_before: Option<&str>, not_after: Option<&str>| {
+            let mut builder = crate::certificates::CertificateBuilder::new(
+                crate::certificates::Profile::Root,
+                None,
+                CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+                "CN=example.com".to_string(),
+                pk.clone(),
+                &sk,
+            )
+            .unwrap();
+            builder
+                .set_private_key_usage_period(not_before, not_after)
+                .unwrap();
+            builder.build().unwrap()
+        };
+
+        // The key was retired yesterday, but the certificate is still valid
+        let retired = issue(
+            Some(&(now - day * 2).to_rfc3339()),
+            Some(&(now - day).to_rfc3339()),
+        );
+        let (not_before, not_after) = retired.get_private_key_usage_period().unwrap();
+        assert_eq!(not_after.unwrap().timestamp(), (now - day).timestamp());
+        assert!(not_before.unwrap() < not_after.unwrap());
+        assert!(retired.is_valid());
+        assert!(!retired.is_private_key_usable());
+
+        let mut builder = crate::content::SignedDataContent::get_builder();
+        builder
+            .content(b"data")
+            .unwrap()
+            .signer(&retired, &sk, &Default::default())
+            .unwrap();
+        assert_eq!(
+            builder.build(),
+            Err(crate::QuantCryptError::PrivateKeyOutsideUsagePeriod)
+        );
+
+        let current = issue(None, Some(&(now + day).to_rfc3339()));
+        assert!(current.is_private_key_usable());
+        assert_eq!(current.get_private_key_usage_period().unwrap().0, None);
+
+        let mut builder = crate::certificates::CertificateBuilder::new(
+            crate::certificates::Profile::Root,
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=example.com".to_string(),
+            pk.clone(),
+            &sk,
+        )
+        .unwrap();
+        assert!(builder
+            .set_private_key_usage_period(Some(&now.to_rfc3339()), Some(&(now - day).to_rfc3339()))
+            .is_err());
+    }
+
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_bc_cert_artifacts() {
         let base_folder_path = "test/data/bc_artifacts_certs_r4/";
@@ -596,4 +1067,139 @@ mod tests {
             let _ = pk.encap().unwrap();
         }
     }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_legacy_oid_aliases() {
+        let aliases = crate::certificates::OidAliasTable::with_legacy_composite_oids();
+        let alg = crate::dsas::DsaAlgorithm::MlDsa44Ed25519;
+        let (pk, sk) = crate::dsas::DsaKeyGenerator::new(alg).generate().unwrap();
+
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let mut builder = crate::certificates::CertificateBuilder::new(
+            crate::certificates::Profile::Root,
+            None,
+            validity,
+            "CN=example.com".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap();
+        builder.emit_legacy_oids(&aliases);
+        let cert = builder.build().unwrap();
+
+        let legacy_oid = aliases.legacy_for(&alg.get_oid()).unwrap().to_string();
+        assert_eq!(cert.get_public_key_oid(), legacy_oid);
+        assert_eq!(cert.get_signature_oid(), legacy_oid);
+
+        // Without aliases the legacy OID is unknown
+        assert!(cert.verify_self_signed().is_err());
+
+        let report = cert.verify_self_signed_with_aliases(&aliases).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[0].contains(&legacy_oid));
+
+        let pk = cert.get_public_key_with_aliases(&aliases).unwrap();
+        assert_eq!(pk.get_oid(), alg.get_oid());
+
+        // Current OIDs produce no warnings
+        let ta_path = "test/data/bc_artifacts_certs_r4/ml-dsa-44-2.16.840.1.101.3.4.3.17_ta.der";
+        let cert = crate::certificates::Certificate::from_file(ta_path).unwrap();
+        let report = cert.verify_self_signed_with_aliases(&aliases).unwrap();
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty());
+        let names: Vec<_> = report.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["extensions present", "subject matches issuer", "signature"]
+        );
+
+        // A self-signed certificate verifies as its own child, an unrelated one does not
+        let report = cert.verify_child_report(&cert).unwrap();
+        assert!(report.is_valid());
+        let ee_path = "test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der";
+        let ee = crate::certificates::Certificate::from_file(ee_path).unwrap();
+        let report = cert.verify_child_report(&ee).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "issuer matches parent subject"
+        );
+    }
+
+    #[test]
+    fn test_key_id_methods() {
+        let (pk, sk) = crate::dsas::DsaKeyGenerator::new(crate::dsas::DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+
+        // The default profile SKI is the RFC 5280 SHA-1 key identifier
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let cert = crate::certificates::CertificateBuilder::new(
+            crate::certificates::Profile::Root,
+            None,
+            validity.clone(),
+            "CN=example.com".to_string(),
+            pk.clone(),
+            &sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let ski = cert.get_subject_key_identifier().unwrap();
+        assert_eq!(
+            ski.0.as_bytes(),
+            cert.get_key_id(KeyIdMethod::Sha1).unwrap().as_slice()
+        );
+        assert_eq!(
+            pk.get_key_id(KeyIdMethod::Sha1).unwrap(),
+            cert.get_key_id(KeyIdMethod::Sha1).unwrap()
+        );
+
+        // A hierarchy using RFC 7093 method 4
+        let method = KeyIdMethod::SpkiSha256;
+        let mut builder = crate::certificates::CertificateBuilder::new(
+            crate::certificates::Profile::Root,
+            None,
+            validity.clone(),
+            "CN=example.com".to_string(),
+            pk.clone(),
+            &sk,
+        )
+        .unwrap();
+        builder.set_key_id_method(method);
+        let root = builder.build().unwrap();
+        assert!(root.verify_self_signed().unwrap());
+        let ski = root.get_subject_key_identifier().unwrap();
+        assert_eq!(ski.0.as_bytes(), pk.get_key_id(method).unwrap().as_slice());
+
+        let (pk_ee, _) = crate::dsas::DsaKeyGenerator::new(crate::dsas::DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let mut builder = crate::certificates::CertificateBuilder::new(
+            crate::certificates::Profile::Leaf {
+                issuer: root.get_subject(),
+                enable_key_agreement: false,
+                enable_key_encipherment: false,
+            },
+            None,
+            validity,
+            "CN=ee.example.com".to_string(),
+            pk_ee.clone(),
+            &sk,
+        )
+        .unwrap();
+        builder.set_key_id_method(method);
+        let ee = builder.build().unwrap();
+        assert!(root.verify_child(&ee).unwrap());
+        assert_eq!(
+            ee.get_subject_key_identifier().unwrap().0.as_bytes(),
+            pk_ee.get_key_id(method).unwrap().as_slice()
+        );
+
+        // Thumbprints are stable and differ between keys
+        assert_eq!(pk.get_jwk_thumbprint(), pk.get_jwk_thumbprint());
+        assert_ne!(pk.get_jwk_thumbprint(), pk_ee.get_jwk_thumbprint());
+    }
 }
@@ -0,0 +1,503 @@
+use std::str::FromStr;
+
+use der::asn1::{Any, BitString, SetOfVec};
+use der::{Decode, Encode, EncodePem};
+use pkcs8::spki::{DynSignatureAlgorithmIdentifier, SubjectPublicKeyInfoOwned};
+use pkcs8::ObjectIdentifier;
+use signature::Keypair;
+use x509_cert::attr::{Attribute, Attributes};
+use x509_cert::name::{Name, RdnSequence};
+use x509_cert::request::{CertReq, CertReqInfo, Version};
+
+use crate::asn1::asn_util::is_dsa_oid;
+use crate::asn1::attestation_evidence::{
+    AttestationEvidence, EvidenceBundle, EvidenceVerifier, EVIDENCE_ATTRIBUTE_OID,
+};
+use crate::asn1::private_key::PrivateKey;
+use crate::asn1::public_key::PublicKey;
+use crate::utils::resource_limits::{check_der_input, check_input_len};
+use crate::utils::verification_report::VerificationReport;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Builds PKCS#10 certificate signing requests
+///
+/// The request is signed with the private key it asks a certificate for,
+/// which proves possession of the key, so only signing keys can be used.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{CertificateSigningRequest, CsrBuilder};
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let csr = CsrBuilder::new("CN=device-1234".to_string(), &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// let csr = CertificateSigningRequest::from_der(&csr.to_der().unwrap()).unwrap();
+/// assert!(csr.verify().unwrap());
+/// assert_eq!(csr.get_public_key().unwrap().to_der().unwrap(), pk.to_der().unwrap());
+/// ```
+pub struct CsrBuilder<'a> {
+    subject: Name,
+    signer: &'a PrivateKey,
+    attributes: Attributes,
+    evidence: Vec<AttestationEvidence>,
+}
+
+impl<'a> CsrBuilder<'a> {
+    /// Create a new builder
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the request, e.g. "CN=device-1234"
+    /// * `signer` - The private key of the request
+    ///
+    /// # Returns
+    ///
+    /// The builder
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::BadSubject` if the subject can't be parsed
+    pub fn new(subject: String, signer: &'a PrivateKey) -> Result<CsrBuilder<'a>> {
+        let subject = Name::from_str(&subject).map_err(|_| QuantCryptError::BadSubject)?;
+        Ok(CsrBuilder {
+            subject,
+            signer,
+            attributes: Attributes::new(),
+            evidence: Vec::new(),
+        })
+    }
+
+    /// Add an attribute
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - The attribute to add
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn add_attribute(&mut self, attribute: &Attribute) -> Result<&mut Self> {
+        self.attributes
+            .insert(attribute.clone())
+            .map_err(|_| QuantCryptError::InvalidAttribute)?;
+        Ok(self)
+    }
+
+    /// Add attestation evidence for the key
+    ///
+    /// All the evidence is carried in a single id-aa-evidence attribute.
+    ///
+    /// # Arguments
+    ///
+    /// * `evidence` - The evidence to add
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn add_evidence(&mut self, evidence: AttestationEvidence) -> &mut Self {
+        self.evidence.push(evidence);
+        self
+    }
+
+    /// Build and sign the request
+    ///
+    /// # Returns
+    ///
+    /// The signed request
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` if the key isn't a signing key
+    pub fn build(self) -> Result<CertificateSigningRequest> {
+        if !is_dsa_oid(self.signer.get_oid()) {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let mut attributes = self.attributes;
+        if !self.evidence.is_empty() {
+            let bundles = self
+                .evidence
+                .iter()
+                .map(|e| e.to_bundle())
+                .collect::<Result<Vec<EvidenceBundle>>>()?;
+            let value = Any::encode_from(&bundles).map_err(|_| QuantCryptError::InvalidEvidence)?;
+            let values =
+                SetOfVec::try_from(vec![value]).map_err(|_| QuantCryptError::InvalidAttribute)?;
+            attributes
+                .insert(Attribute {
+                    oid: ObjectIdentifier::new_unwrap(EVIDENCE_ATTRIBUTE_OID),
+                    values,
+                })
+                .map_err(|_| QuantCryptError::InvalidAttribute)?;
+        }
+
+        let pk_der = self.signer.verifying_key().to_der()?;
+        let info = CertReqInfo {
+            version: Version::V1,
+            subject: self.subject,
+            public_key: SubjectPublicKeyInfoOwned::from_der(&pk_der)
+                .map_err(|_| QuantCryptError::BadPublicKey)?,
+            attributes,
+        };
+        let info_der = info.to_der().map_err(|_| QuantCryptError::InvalidCsr)?;
+        let sig = self.signer.sign(&info_der)?;
+        let req = CertReq {
+            info,
+            algorithm: self
+                .signer
+                .signature_algorithm_identifier()
+                .map_err(|_| QuantCryptError::InvalidOid)?,
+            signature: BitString::from_bytes(&sig).map_err(|_| QuantCryptError::Unknown)?,
+        };
+        Ok(CertificateSigningRequest { req })
+    }
+}
+
+/// A PKCS#10 certificate signing request
+#[derive(Clone)]
+pub struct CertificateSigningRequest {
+    req: CertReq,
+}
+
+impl CertificateSigningRequest {
+    /// Create a request from DER format bytes
+    ///
+    /// # Arguments
+    ///
+    /// * `der` - The DER format bytes
+    ///
+    /// # Returns
+    ///
+    /// The request
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCsr` will be returned if the request is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input or its decoding
+    /// doesn't fit in the parse budget
+    pub fn from_der(der: &[u8]) -> Result<CertificateSigningRequest> {
+        check_der_input(der)?;
+        let req = CertReq::from_der(der).map_err(|_| QuantCryptError::InvalidCsr)?;
+        Ok(CertificateSigningRequest { req })
+    }
+
+    /// Create a request from a PEM format string
+    ///
+    /// # Arguments
+    ///
+    /// * `pem` - The PEM format string
+    ///
+    /// # Returns
+    ///
+    /// The request
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCsr` will be returned if the request is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input is larger than the parse budget
+    pub fn from_pem(pem: &str) -> Result<CertificateSigningRequest> {
+        check_input_len(pem.len())?;
+        let pem = pem::parse(pem).map_err(|_| QuantCryptError::InvalidCsr)?;
+        if pem.tag() != "CERTIFICATE REQUEST" {
+            return Err(QuantCryptError::InvalidCsr);
+        }
+        Self::from_der(pem.contents())
+    }
+
+    /// Convert the request to DER format bytes
+    pub fn to_der(&self) -> Result<Vec<u8>> {
+        self.req.to_der().map_err(|_| QuantCryptError::InvalidCsr)
+    }
+
+    /// Convert the request to PEM format
+    pub fn to_pem(&self) -> Result<String> {
+        self.req
+            .to_pem(pkcs8::LineEnding::LF)
+            .map_err(|_| QuantCryptError::InvalidCsr)
+    }
+
+    /// Get the subject name
+    pub fn get_subject(&self) -> RdnSequence {
+        self.req.info.subject.clone()
+    }
+
+    /// Get the public key the request asks a certificate for
+    pub fn get_public_key(&self) -> Result<PublicKey> {
+        let pk_der = self
+            .req
+            .info
+            .public_key
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidCsr)?;
+        PublicKey::from_der(&pk_der)
+    }
+
+    /// Get the attributes of the request
+    pub fn get_attributes(&self) -> &Attributes {
+        &self.req.info.attributes
+    }
+
+    /// Verify the signature of the request, which proves possession of the private key
+    ///
+    /// # Returns
+    ///
+    /// True if the signature is valid, false otherwise
+    pub fn verify(&self) -> Result<bool> {
+        let info_der = self
+            .req
+            .info
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidCsr)?;
+        let pk = self.get_public_key()?;
+        if self.req.algorithm.oid.to_string() != pk.get_oid() {
+            return Ok(false);
+        }
+        pk.verify(&info_der, self.req.signature.raw_bytes())
+    }
+
+    /// Get the attestation evidence carried in the request
+    ///
+    /// # Returns
+    ///
+    /// The evidence, empty if the request has no id-aa-evidence attribute
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEvidence` if the attribute can't be parsed
+    pub fn get_evidence(&self) -> Result<Vec<AttestationEvidence>> {
+        let mut evidence = Vec::new();
+        for attribute in self.req.info.attributes.iter() {
+            if attribute.oid.to_string() != EVIDENCE_ATTRIBUTE_OID {
+                continue;
+            }
+            for value in attribute.values.iter() {
+                let bundles: Vec<EvidenceBundle> = value
+                    .decode_as()
+                    .map_err(|_| QuantCryptError::InvalidEvidence)?;
+                if bundles.is_empty() {
+                    return Err(QuantCryptError::InvalidEvidence);
+                }
+                for bundle in bundles {
+                    evidence.extend(AttestationEvidence::from_bundle(bundle)?);
+                }
+            }
+        }
+        Ok(evidence)
+    }
+
+    /// Verify the request and its attestation evidence, for enrolling devices
+    ///
+    /// The request must prove possession of its key and carry evidence.
+    /// For each evidence:
+    ///
+    /// * TPM 2.0: the TPMS_ATTEST must be a quote or a certification, with
+    ///   the nonce as its qualifying data.
+    /// * DICE: each certificate of the chain must be issued by the next one,
+    ///   and all of them must be valid.
+    ///
+    /// The verifier then checks the signatures and whether the evidence is
+    /// trusted. Evidence of other types is only checked by the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `verifier` - Verifies the signatures of the evidence
+    /// * `nonce` - The nonce the CA sent to the device, if any
+    ///
+    /// # Returns
+    ///
+    /// A report with the outcome and the checks performed
+    pub fn verify_attestation(
+        &self,
+        verifier: &dyn EvidenceVerifier,
+        nonce: Option<&[u8]>,
+    ) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.policy = Some(format!("attestation evidence of {}", self.req.info.subject));
+        if !report.check("proof of possession", self.verify()?) {
+            return Ok(report);
+        }
+        let evidence = self.get_evidence()?;
+        if !report.check("evidence present", !evidence.is_empty()) {
+            return Ok(report);
+        }
+        for evidence in &evidence {
+            if evidence.is_tpm() {
+                let qualifying_data = evidence.get_tpm_qualifying_data()?;
+                if let Some(nonce) = nonce {
+                    if !report.check("TPM nonce", qualifying_data == nonce) {
+                        return Ok(report);
+                    }
+                }
+            } else if evidence.is_dice() {
+                let chain = evidence.get_certs();
+                let linked = chain
+                    .windows(2)
+                    .all(|pair| pair[0].get_issuer() == pair[1].get_subject());
+                if !report.check("DICE chain linked", linked)
+                    || !report.check(
+                        "DICE certificates valid",
+                        chain.iter().all(|c| c.is_valid()),
+                    )
+                {
+                    return Ok(report);
+                }
+            }
+            if !report.check("evidence verified", verifier.verify(evidence)?) {
+                return Ok(report);
+            }
+        }
+        report.valid = true;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asn1::attestation_evidence::TPM_EVIDENCE_OID;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::kems::{KemAlgorithm, KemKeyGenerator};
+
+    struct AcceptTpm;
+
+    impl EvidenceVerifier for AcceptTpm {
+        fn verify(&self, evidence: &AttestationEvidence) -> Result<bool> {
+            Ok(evidence.get_type() == TPM_EVIDENCE_OID)
+        }
+    }
+
+    fn tpms_attest(extra_data: &[u8]) -> Vec<u8> {
+        // TPM_GENERATED_VALUE, TPM_ST_ATTEST_QUOTE, a 2 byte qualifiedSigner,
+        // extraData, then the rest of the structure
+        let mut attest = vec![0xff, 0x54, 0x43, 0x47, 0x80, 0x18, 0, 2, 0x00, 0x0b];
+        attest.extend_from_slice(&(extra_data.len() as u16).to_be_bytes());
+        attest.extend_from_slice(extra_data);
+        attest.extend_from_slice(&[0u8; 17]);
+        attest
+    }
+
+    #[test]
+    fn test_csr_with_evidence() {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let (ca_pk, ca_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let ca_cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=Device Manufacturer".to_string(),
+            ca_pk,
+            &ca_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let ak_cert = CertificateBuilder::new(
+            Profile::Leaf {
+                issuer: ca_cert.get_subject(),
+                enable_key_agreement: false,
+                enable_key_encipherment: false,
+            },
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=Attestation Key".to_string(),
+            pk.clone(),
+            &ca_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let tpm = AttestationEvidence::tpm(
+            &tpms_attest(b"nonce"),
+            b"signature",
+            vec![ak_cert.clone(), ca_cert.clone()],
+        )
+        .unwrap();
+        let dice = AttestationEvidence::dice(vec![ak_cert.clone(), ca_cert.clone()]).unwrap();
+        let mut builder = CsrBuilder::new("CN=device-1234".to_string(), &sk).unwrap();
+        builder.add_evidence(tpm);
+        let csr = builder.build().unwrap();
+
+        let csr = CertificateSigningRequest::from_pem(&csr.to_pem().unwrap()).unwrap();
+        assert!(csr.verify().unwrap());
+        assert_eq!(
+            csr.get_public_key().unwrap().to_der().unwrap(),
+            pk.to_der().unwrap()
+        );
+        assert_eq!(csr.get_subject().to_string(), "CN=device-1234");
+        let evidence = csr.get_evidence().unwrap();
+        assert_eq!(evidence.len(), 1);
+        assert!(evidence[0].is_tpm());
+        assert_eq!(evidence[0].get_certs().len(), 2);
+        assert_eq!(
+            evidence[0].get_certs()[0].to_der().unwrap(),
+            ak_cert.to_der().unwrap()
+        );
+
+        let report = csr.verify_attestation(&AcceptTpm, Some(b"nonce")).unwrap();
+        assert!(report.is_valid());
+        let report = csr.verify_attestation(&AcceptTpm, Some(b"stale")).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.get_failed_check().unwrap().name, "TPM nonce");
+
+        // The DICE chain is linked, but the verifier doesn't trust it
+        let mut builder = CsrBuilder::new("CN=device-1234".to_string(), &sk).unwrap();
+        builder.add_evidence(dice.clone());
+        let csr = builder.build().unwrap();
+        let report = csr.verify_attestation(&AcceptTpm, None).unwrap();
+        assert_eq!(report.get_failed_check().unwrap().name, "evidence verified");
+
+        // The chain is in the wrong order
+        let reversed = AttestationEvidence::dice(vec![ca_cert, ak_cert]).unwrap();
+        let mut builder = CsrBuilder::new("CN=device-1234".to_string(), &sk).unwrap();
+        builder.add_evidence(reversed);
+        let csr = builder.build().unwrap();
+        let report = csr.verify_attestation(&AcceptTpm, None).unwrap();
+        assert_eq!(report.get_failed_check().unwrap().name, "DICE chain linked");
+
+        // No evidence
+        let csr = CsrBuilder::new("CN=device-1234".to_string(), &sk)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(csr.get_evidence().unwrap().is_empty());
+        let report = csr.verify_attestation(&AcceptTpm, None).unwrap();
+        assert_eq!(report.get_failed_check().unwrap().name, "evidence present");
+
+        // A tampered request doesn't prove possession
+        let mut der = csr.to_der().unwrap();
+        let len = der.len();
+        der[len - 1] ^= 1;
+        let csr = CertificateSigningRequest::from_der(&der).unwrap();
+        assert!(!csr.verify().unwrap());
+        let report = csr.verify_attestation(&AcceptTpm, None).unwrap();
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "proof of possession"
+        );
+
+        // KEM keys can't sign a request
+        let (_, kem_sk) = KemKeyGenerator::new(KemAlgorithm::MlKem512)
+            .generate()
+            .unwrap();
+        assert!(matches!(
+            CsrBuilder::new("CN=device-1234".to_string(), &kem_sk)
+                .unwrap()
+                .build(),
+            Err(QuantCryptError::InvalidPrivateKey)
+        ));
+        assert!(CsrBuilder::new("not a name".to_string(), &sk).is_err());
+    }
+}
@@ -0,0 +1,549 @@
+use std::fmt;
+
+use der::asn1::ObjectIdentifier;
+use der::Decode;
+use spki::SubjectPublicKeyInfoOwned;
+
+use crate::asn1::lenient_certificate::{read_children, read_tlv, Tlv};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The maximum nesting depth compared, to bound the recursion on hostile input
+const MAX_DEPTH: usize = 64;
+
+/// The number of bytes of a value shown in hex
+const MAX_HEX_BYTES: usize = 16;
+
+/// The kind of a difference between two DER objects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    /// The field is present on both sides with a different encoding
+    Changed,
+    /// The field is only present in the first object
+    Removed,
+    /// The field is only present in the second object
+    Added,
+}
+
+/// A difference in a single field of two DER objects
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// The path of the field, e.g. `tbsCertificate.extensions[2.5.29.15].critical`
+    pub path: String,
+    /// The kind of difference
+    pub kind: DiffKind,
+    /// The value in the first object, if present
+    pub left: Option<String>,
+    /// The value in the second object, if present
+    pub right: Option<String>,
+    /// The offset of the first differing byte of the values, if both have the same tag
+    pub first_difference: Option<usize>,
+}
+
+/// A field-by-field diff of two DER objects
+///
+/// Fields are named after the ASN.1 definitions of certificates, public keys
+/// (SubjectPublicKeyInfo), private keys (OneAsymmetricKey) and CMS objects
+/// (ContentInfo), and by position elsewhere. BIT STRINGs and OCTET STRINGs
+/// wrapping DER, such as composite keys or extension values, are compared
+/// element by element too.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Asn1Diff {
+    /// The differences, in the order of the fields of the first object
+    pub differences: Vec<FieldDiff>,
+}
+
+impl Asn1Diff {
+    /// Check if the objects are identical
+    ///
+    /// # Returns
+    ///
+    /// True if no difference was found
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for Asn1Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diff in &self.differences {
+            let path = if diff.path.is_empty() {
+                "<root>"
+            } else {
+                &diff.path
+            };
+            let none = String::new();
+            match diff.kind {
+                DiffKind::Changed => {
+                    write!(
+                        f,
+                        "~ {}: {} -> {}",
+                        path,
+                        diff.left.as_ref().unwrap_or(&none),
+                        diff.right.as_ref().unwrap_or(&none)
+                    )?;
+                    if let Some(offset) = diff.first_difference {
+                        write!(f, " (first difference at byte {})", offset)?;
+                    }
+                    writeln!(f)?;
+                }
+                DiffKind::Removed => {
+                    writeln!(f, "- {}: {}", path, diff.left.as_ref().unwrap_or(&none))?
+                }
+                DiffKind::Added => {
+                    writeln!(f, "+ {}: {}", path, diff.right.as_ref().unwrap_or(&none))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The ASN.1 structure of an element, used to name its fields
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Schema {
+    Certificate,
+    TbsCertificate,
+    Validity,
+    /// The explicitly tagged extensions of a TBSCertificate
+    ExplicitExtensions,
+    Extensions,
+    Extension,
+    SubjectPublicKeyInfo,
+    AlgorithmIdentifier,
+    OneAsymmetricKey,
+    ContentInfo,
+    Unknown,
+}
+
+/// Guess the structure of a DER object
+fn detect_schema(der: &[u8]) -> Schema {
+    if x509_cert::Certificate::from_der(der).is_ok() {
+        Schema::Certificate
+    } else if SubjectPublicKeyInfoOwned::from_der(der).is_ok() {
+        Schema::SubjectPublicKeyInfo
+    } else if pkcs8::PrivateKeyInfo::from_der(der).is_ok() {
+        Schema::OneAsymmetricKey
+    } else if cms::content_info::ContentInfo::from_der(der).is_ok() {
+        Schema::ContentInfo
+    } else {
+        Schema::Unknown
+    }
+}
+
+/// Name the children of an element after its structure
+///
+/// Each child gets a path segment and a structure. Optional fields are named by their
+/// context-specific tag, so that the children of both objects line up by name even if
+/// one of them lacks an optional field.
+fn name_children(schema: Schema, children: &[Tlv<'_>]) -> Vec<(String, Schema)> {
+    let positional = |names: &[(&str, Schema)], i: usize| {
+        names
+            .get(i)
+            .map(|(name, schema)| (name.to_string(), *schema))
+            .unwrap_or_else(|| (format!("[{}]", i), Schema::Unknown))
+    };
+
+    match schema {
+        Schema::Certificate => (0..children.len())
+            .map(|i| {
+                positional(
+                    &[
+                        ("tbsCertificate", Schema::TbsCertificate),
+                        ("signatureAlgorithm", Schema::AlgorithmIdentifier),
+                        ("signature", Schema::Unknown),
+                    ],
+                    i,
+                )
+            })
+            .collect(),
+        Schema::TbsCertificate => {
+            let fields = [
+                ("serialNumber", Schema::Unknown),
+                ("signature", Schema::AlgorithmIdentifier),
+                ("issuer", Schema::Unknown),
+                ("validity", Schema::Validity),
+                ("subject", Schema::Unknown),
+                ("subjectPublicKeyInfo", Schema::SubjectPublicKeyInfo),
+            ];
+            let mut next = 0;
+            children
+                .iter()
+                .map(|child| match child.tag {
+                    0xa0 => ("version".to_string(), Schema::Unknown),
+                    0x81 => ("issuerUniqueID".to_string(), Schema::Unknown),
+                    0x82 => ("subjectUniqueID".to_string(), Schema::Unknown),
+                    0xa3 => ("extensions".to_string(), Schema::ExplicitExtensions),
+                    _ => {
+                        next += 1;
+                        positional(&fields, next - 1)
+                    }
+                })
+                .collect()
+        }
+        Schema::Validity => (0..children.len())
+            .map(|i| {
+                positional(
+                    &[
+                        ("notBefore", Schema::Unknown),
+                        ("notAfter", Schema::Unknown),
+                    ],
+                    i,
+                )
+            })
+            .collect(),
+        Schema::ExplicitExtensions => (0..children.len())
+            .map(|i| positional(&[("", Schema::Extensions)], i))
+            .collect(),
+        Schema::Extensions => children
+            .iter()
+            .enumerate()
+            .map(|(i, ext)| {
+                let oid = read_children(ext.value)
+                    .first()
+                    .filter(|id| id.tag == 0x06)
+                    .and_then(|id| ObjectIdentifier::from_bytes(id.value).ok());
+                match oid {
+                    Some(oid) => (format!("[{}]", oid), Schema::Extension),
+                    None => (format!("[{}]", i), Schema::Unknown),
+                }
+            })
+            .collect(),
+        Schema::Extension => children
+            .iter()
+            .map(|child| match child.tag {
+                0x06 => ("extnID".to_string(), Schema::Unknown),
+                0x01 => ("critical".to_string(), Schema::Unknown),
+                _ => ("extnValue".to_string(), Schema::Unknown),
+            })
+            .collect(),
+        Schema::SubjectPublicKeyInfo => (0..children.len())
+            .map(|i| {
+                positional(
+                    &[
+                        ("algorithm", Schema::AlgorithmIdentifier),
+                        ("subjectPublicKey", Schema::Unknown),
+                    ],
+                    i,
+                )
+            })
+            .collect(),
+        Schema::AlgorithmIdentifier => (0..children.len())
+            .map(|i| {
+                positional(
+                    &[
+                        ("algorithm", Schema::Unknown),
+                        ("parameters", Schema::Unknown),
+                    ],
+                    i,
+                )
+            })
+            .collect(),
+        Schema::OneAsymmetricKey => children
+            .iter()
+            .enumerate()
+            .map(|(i, child)| match child.tag {
+                0xa0 => ("attributes".to_string(), Schema::Unknown),
+                0x81 | 0xa1 => ("publicKey".to_string(), Schema::Unknown),
+                _ => positional(
+                    &[
+                        ("version", Schema::Unknown),
+                        ("privateKeyAlgorithm", Schema::AlgorithmIdentifier),
+                        ("privateKey", Schema::Unknown),
+                    ],
+                    i,
+                ),
+            })
+            .collect(),
+        Schema::ContentInfo => (0..children.len())
+            .map(|i| {
+                positional(
+                    &[
+                        ("contentType", Schema::Unknown),
+                        ("content", Schema::Unknown),
+                    ],
+                    i,
+                )
+            })
+            .collect(),
+        Schema::Unknown => (0..children.len())
+            .map(|i| (format!("[{}]", i), Schema::Unknown))
+            .collect(),
+    }
+}
+
+/// Append a segment to a path
+fn join(path: &str, segment: &str) -> String {
+    if path.is_empty() || segment.is_empty() || segment.starts_with('[') {
+        format!("{}{}", path, segment)
+    } else {
+        format!("{}.{}", path, segment)
+    }
+}
+
+/// Check if an element is constructed
+fn is_constructed(tlv: &Tlv<'_>) -> bool {
+    tlv.tag & 0x20 != 0
+}
+
+/// Get the DER wrapped in a BIT STRING or OCTET STRING, if it holds a single constructed element
+fn get_encapsulated<'a>(tlv: &Tlv<'a>) -> Option<Tlv<'a>> {
+    let content = match tlv.tag {
+        // Skip the unused bits count
+        0x03 => tlv.value.get(1..)?,
+        0x04 => tlv.value,
+        _ => return None,
+    };
+    let (inner, consumed) = read_tlv(content)?;
+    if consumed != content.len() || inner.truncated || !is_constructed(&inner) {
+        return None;
+    }
+    Some(inner)
+}
+
+/// Get the name of a tag
+fn tag_name(tag: u8) -> String {
+    match tag {
+        0x01 => "BOOLEAN".to_string(),
+        0x02 => "INTEGER".to_string(),
+        0x03 => "BIT STRING".to_string(),
+        0x04 => "OCTET STRING".to_string(),
+        0x05 => "NULL".to_string(),
+        0x06 => "OBJECT IDENTIFIER".to_string(),
+        0x0c => "UTF8String".to_string(),
+        0x13 => "PrintableString".to_string(),
+        0x16 => "IA5String".to_string(),
+        0x17 => "UTCTime".to_string(),
+        0x18 => "GeneralizedTime".to_string(),
+        0x30 => "SEQUENCE".to_string(),
+        0x31 => "SET".to_string(),
+        _ if tag & 0xc0 == 0x80 => format!("[{}]", tag & 0x1f),
+        _ => format!("tag 0x{:02x}", tag),
+    }
+}
+
+/// Render the value of an element for a diff
+fn render(tlv: &Tlv<'_>) -> String {
+    let text = match tlv.tag {
+        0x01 => tlv.value.first().map(|b| (*b != 0).to_string()),
+        0x06 => ObjectIdentifier::from_bytes(tlv.value)
+            .ok()
+            .map(|oid| oid.to_string()),
+        0x0c | 0x13 | 0x16 | 0x17 | 0x18 => std::str::from_utf8(tlv.value)
+            .ok()
+            .map(|s| format!("\"{}\"", s)),
+        _ => None,
+    };
+    let text = text.unwrap_or_else(|| {
+        let hex = hex::encode(&tlv.value[..tlv.value.len().min(MAX_HEX_BYTES)]);
+        let ellipsis = if tlv.value.len() > MAX_HEX_BYTES {
+            "..."
+        } else {
+            ""
+        };
+        format!("({} bytes) {}{}", tlv.value.len(), hex, ellipsis)
+    });
+    let truncated = if tlv.truncated { " (truncated)" } else { "" };
+    format!("{} {}{}", tag_name(tlv.tag), text, truncated)
+}
+
+/// Compare two elements, recording their differences
+fn diff_elements(
+    path: &str,
+    schema: Schema,
+    a: &Tlv<'_>,
+    b: &Tlv<'_>,
+    depth: usize,
+    out: &mut Vec<FieldDiff>,
+) {
+    if a.encoded == b.encoded {
+        return;
+    }
+    let changed = |first_difference| FieldDiff {
+        path: path.to_string(),
+        kind: DiffKind::Changed,
+        left: Some(render(a)),
+        right: Some(render(b)),
+        first_difference,
+    };
+    if a.tag != b.tag || depth >= MAX_DEPTH {
+        out.push(changed(None));
+        return;
+    }
+
+    if is_constructed(a) {
+        diff_children(path, schema, a.value, b.value, depth, out);
+        return;
+    }
+    // Encapsulated DER doesn't get a path segment of its own
+    if let (Some(inner_a), Some(inner_b)) = (get_encapsulated(a), get_encapsulated(b)) {
+        diff_elements(path, Schema::Unknown, &inner_a, &inner_b, depth + 1, out);
+        return;
+    }
+
+    let first_difference = a
+        .value
+        .iter()
+        .zip(b.value)
+        .position(|(x, y)| x != y)
+        .unwrap_or_else(|| a.value.len().min(b.value.len()));
+    out.push(changed(Some(first_difference)));
+}
+
+/// Compare the children of two elements, lining them up by name
+fn diff_children(
+    path: &str,
+    schema: Schema,
+    a: &[u8],
+    b: &[u8],
+    depth: usize,
+    out: &mut Vec<FieldDiff>,
+) {
+    let children_a = read_children(a);
+    let children_b = read_children(b);
+    let names_a = name_children(schema, &children_a);
+    let names_b = name_children(schema, &children_b);
+
+    let mut used = vec![false; children_b.len()];
+    for (child_a, (name, child_schema)) in children_a.iter().zip(&names_a) {
+        let child_path = join(path, name);
+        let matching = names_b
+            .iter()
+            .enumerate()
+            .position(|(i, (name_b, _))| !used[i] && name_b == name);
+        match matching {
+            Some(i) => {
+                used[i] = true;
+                diff_elements(
+                    &child_path,
+                    *child_schema,
+                    child_a,
+                    &children_b[i],
+                    depth + 1,
+                    out,
+                );
+            }
+            None => out.push(FieldDiff {
+                path: child_path,
+                kind: DiffKind::Removed,
+                left: Some(render(child_a)),
+                right: None,
+                first_difference: None,
+            }),
+        }
+    }
+    for (i, child_b) in children_b.iter().enumerate() {
+        if !used[i] {
+            out.push(FieldDiff {
+                path: join(path, &names_b[i].0),
+                kind: DiffKind::Added,
+                left: None,
+                right: Some(render(child_b)),
+                first_difference: None,
+            });
+        }
+    }
+}
+
+/// Compare two DER encoded certificates, keys or CMS objects field by field
+///
+/// The structure is detected from the first object that can be parsed as a
+/// certificate, a SubjectPublicKeyInfo, a OneAsymmetricKey or a ContentInfo.
+/// Other DER is compared too, with fields named by position. The inputs don't
+/// need to be valid beyond their DER framing, which is what makes this useful
+/// to chase an interop difference in an object that fails to parse.
+///
+/// # Arguments
+///
+/// * `der_a` - The first DER encoded object
+/// * `der_b` - The second DER encoded object
+///
+/// # Returns
+///
+/// The differences between the objects
+///
+/// # Errors
+///
+/// `QuantCryptError::SerializationFailed` if an input is not a DER element
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{diff, DiffKind};
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let der = std::fs::read(cert_path).unwrap();
+///
+/// let mut other = der.clone();
+/// let last = other.len() - 1;
+/// other[last] ^= 1;
+///
+/// let diff = diff(&der, &other).unwrap();
+/// assert_eq!(diff.differences.len(), 1);
+/// // s of the ECDSA component of the composite signature
+/// assert_eq!(diff.differences[0].path, "signature[1][1]");
+/// assert_eq!(diff.differences[0].kind, DiffKind::Changed);
+/// println!("{}", diff);
+/// ```
+pub fn diff(der_a: &[u8], der_b: &[u8]) -> Result<Asn1Diff> {
+    let (a, _) = read_tlv(der_a).ok_or(QuantCryptError::SerializationFailed)?;
+    let (b, _) = read_tlv(der_b).ok_or(QuantCryptError::SerializationFailed)?;
+    let schema = match detect_schema(der_a) {
+        Schema::Unknown => detect_schema(der_b),
+        schema => schema,
+    };
+
+    let mut differences = Vec::new();
+    diff_elements("", schema, &a, &b, 0, &mut differences);
+    Ok(Asn1Diff { differences })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::Certificate;
+
+    const CERT_PATH: &str = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+
+    #[test]
+    fn test_diff() {
+        let der = std::fs::read(CERT_PATH).unwrap();
+        assert!(diff(&der, &der).unwrap().is_identical());
+
+        // A single byte of the serial number
+        let cert = Certificate::from_der(&der).unwrap();
+        let serial = cert.get_serial_number();
+        let pos = der
+            .windows(serial.as_bytes().len())
+            .position(|w| w == serial.as_bytes())
+            .unwrap();
+        let mut other = der.clone();
+        other[pos + 3] ^= 0xff;
+        let result = diff(&der, &other).unwrap();
+        assert_eq!(result.differences.len(), 1);
+        let field = &result.differences[0];
+        assert_eq!(field.path, "tbsCertificate.serialNumber");
+        assert_eq!(field.first_difference, Some(3));
+        assert!(field.left.as_ref().unwrap().starts_with("INTEGER"));
+
+        // The classical component of a composite public key
+        let spki =
+            der::Encode::to_der(&cert.get_inner().tbs_certificate.subject_public_key_info).unwrap();
+        let mut other = spki.clone();
+        let last = other.len() - 1;
+        other[last] ^= 1;
+        let result = diff(&spki, &other).unwrap();
+        assert_eq!(result.differences.len(), 1);
+        assert_eq!(result.differences[0].path, "subjectPublicKey[1]");
+        assert!(result.to_string().starts_with("~ subjectPublicKey"));
+
+        // Fields only in one object
+        let result = diff(&[0x30, 0x03, 0x02, 0x01, 0x01], &[0x30, 0x00]).unwrap();
+        assert_eq!(result.differences[0].path, "[0]");
+        assert_eq!(result.differences[0].kind, DiffKind::Removed);
+        let result = diff(&[0x30, 0x00], &[0x30, 0x03, 0x02, 0x01, 0x01]).unwrap();
+        assert_eq!(result.differences[0].kind, DiffKind::Added);
+
+        assert_eq!(diff(&[], &der), Err(QuantCryptError::SerializationFailed));
+    }
+}
@@ -0,0 +1,218 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::dsa::api::algorithm::DsaAlgorithm;
+use crate::kem::api::algorithm::KemAlgorithm;
+
+/// The length of the truncated key identifiers of RFC 7093 methods 1 to 3
+const TRUNCATED_KEY_ID_LEN: usize = 20;
+
+/// A method to compute a key identifier from a public key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum KeyIdMethod {
+    /// RFC 5280 method 1: SHA-1 of the subjectPublicKey BIT STRING value.
+    /// This is what the certificate builder uses by default.
+    #[default]
+    Sha1,
+    /// RFC 7093 method 1: SHA-256 of the subjectPublicKey BIT STRING value, truncated to 160 bits
+    Sha256Truncated,
+    /// RFC 7093 method 2: SHA-384 of the subjectPublicKey BIT STRING value, truncated to 160 bits
+    Sha384Truncated,
+    /// RFC 7093 method 3: SHA-512 of the subjectPublicKey BIT STRING value, truncated to 160 bits
+    Sha512Truncated,
+    /// RFC 7093 method 4: SHA-256 of the DER encoded SubjectPublicKeyInfo
+    SpkiSha256,
+}
+
+impl KeyIdMethod {
+    /// Get all key identifier methods
+    ///
+    /// # Returns
+    ///
+    /// All supported methods
+    pub fn all() -> [KeyIdMethod; 5] {
+        [
+            KeyIdMethod::Sha1,
+            KeyIdMethod::Sha256Truncated,
+            KeyIdMethod::Sha384Truncated,
+            KeyIdMethod::Sha512Truncated,
+            KeyIdMethod::SpkiSha256,
+        ]
+    }
+
+    /// Compute a key identifier
+    ///
+    /// # Arguments
+    ///
+    /// * `subject_public_key` - The value of the subjectPublicKey BIT STRING
+    /// * `spki_der` - The DER encoded SubjectPublicKeyInfo
+    ///
+    /// # Returns
+    ///
+    /// The key identifier
+    pub(crate) fn compute(&self, subject_public_key: &[u8], spki_der: &[u8]) -> Vec<u8> {
+        match self {
+            KeyIdMethod::Sha1 => Sha1::digest(subject_public_key).to_vec(),
+            KeyIdMethod::Sha256Truncated => {
+                Sha256::digest(subject_public_key)[..TRUNCATED_KEY_ID_LEN].to_vec()
+            }
+            KeyIdMethod::Sha384Truncated => {
+                Sha384::digest(subject_public_key)[..TRUNCATED_KEY_ID_LEN].to_vec()
+            }
+            KeyIdMethod::Sha512Truncated => {
+                Sha512::digest(subject_public_key)[..TRUNCATED_KEY_ID_LEN].to_vec()
+            }
+            KeyIdMethod::SpkiSha256 => Sha256::digest(spki_der).to_vec(),
+        }
+    }
+}
+
+/// Get the JOSE `alg` value for a key
+///
+/// Algorithms registered by the JOSE / COSE drafts use their registered name.
+/// Algorithms without a registration (e.g. most composites) use their dotted OID,
+/// so every key has a stable thumbprint.
+///
+/// # Arguments
+///
+/// * `oid` - The OID of the key
+///
+/// # Returns
+///
+/// The `alg` value
+pub(crate) fn get_jose_alg(oid: &str) -> String {
+    let name = if let Some(alg) = DsaAlgorithm::from_oid(oid) {
+        match alg {
+            DsaAlgorithm::MlDsa44 => Some("ML-DSA-44"),
+            DsaAlgorithm::MlDsa65 => Some("ML-DSA-65"),
+            DsaAlgorithm::MlDsa87 => Some("ML-DSA-87"),
+            DsaAlgorithm::SlhDsaSha2_128s => Some("SLH-DSA-SHA2-128s"),
+            DsaAlgorithm::SlhDsaSha2_128f => Some("SLH-DSA-SHA2-128f"),
+            DsaAlgorithm::SlhDsaSha2_192s => Some("SLH-DSA-SHA2-192s"),
+            DsaAlgorithm::SlhDsaSha2_192f => Some("SLH-DSA-SHA2-192f"),
+            DsaAlgorithm::SlhDsaSha2_256s => Some("SLH-DSA-SHA2-256s"),
+            DsaAlgorithm::SlhDsaSha2_256f => Some("SLH-DSA-SHA2-256f"),
+            DsaAlgorithm::SlhDsaShake128s => Some("SLH-DSA-SHAKE-128s"),
+            DsaAlgorithm::SlhDsaShake128f => Some("SLH-DSA-SHAKE-128f"),
+            DsaAlgorithm::SlhDsaShake192s => Some("SLH-DSA-SHAKE-192s"),
+            DsaAlgorithm::SlhDsaShake192f => Some("SLH-DSA-SHAKE-192f"),
+            DsaAlgorithm::SlhDsaShake256s => Some("SLH-DSA-SHAKE-256s"),
+            DsaAlgorithm::SlhDsaShake256f => Some("SLH-DSA-SHAKE-256f"),
+            _ => None,
+        }
+    } else if let Some(alg) = KemAlgorithm::from_oid(oid) {
+        match alg {
+            KemAlgorithm::MlKem512 => Some("ML-KEM-512"),
+            KemAlgorithm::MlKem768 => Some("ML-KEM-768"),
+            KemAlgorithm::MlKem1024 => Some("ML-KEM-1024"),
+            _ => None,
+        }
+    } else {
+        None
+    };
+    name.map(|n| n.to_string())
+        .unwrap_or_else(|| oid.to_string())
+}
+
+/// Get the COSE `alg` value of an algorithm
+///
+/// Only ML-DSA has a registered COSE algorithm and `AKP` key representation.
+///
+/// # Arguments
+///
+/// * `oid` - The OID of the algorithm
+///
+/// # Returns
+///
+/// The `alg` value, or `None` if the algorithm has no COSE representation
+pub(crate) fn get_cose_alg(oid: &str) -> Option<i64> {
+    match DsaAlgorithm::from_oid(oid)? {
+        DsaAlgorithm::MlDsa44 => Some(-48),
+        DsaAlgorithm::MlDsa65 => Some(-49),
+        DsaAlgorithm::MlDsa87 => Some(-50),
+        _ => None,
+    }
+}
+
+/// Get the OID of a COSE `alg` value
+///
+/// # Arguments
+///
+/// * `alg` - The `alg` value
+///
+/// # Returns
+///
+/// The OID, or `None` if the value is not supported
+pub(crate) fn get_oid_from_cose_alg(alg: i64) -> Option<String> {
+    [
+        DsaAlgorithm::MlDsa44,
+        DsaAlgorithm::MlDsa65,
+        DsaAlgorithm::MlDsa87,
+    ]
+    .into_iter()
+    .map(|dsa| dsa.get_oid())
+    .find(|oid| get_cose_alg(oid) == Some(alg))
+}
+
+/// Compute the RFC 7638 thumbprint of an `AKP` (algorithm key pair) JWK
+///
+/// The required members of an `AKP` public key are `alg`, `kty` and `pub`,
+/// serialized in lexicographic order without whitespace.
+///
+/// # Arguments
+///
+/// * `alg` - The JOSE `alg` value
+/// * `pk` - The raw public key
+///
+/// # Returns
+///
+/// The base64url encoded SHA-256 thumbprint
+pub(crate) fn jwk_thumbprint(alg: &str, pk: &[u8]) -> String {
+    let jwk = format!(
+        r#"{{"alg":"{}","kty":"AKP","pub":"{}"}}"#,
+        alg,
+        URL_SAFE_NO_PAD.encode(pk)
+    );
+    URL_SAFE_NO_PAD.encode(Sha256::digest(jwk.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_id_lengths() {
+        let pk = [1u8; 32];
+        let spki = [2u8; 48];
+        for method in KeyIdMethod::all() {
+            let key_id = method.compute(&pk, &spki);
+            match method {
+                KeyIdMethod::SpkiSha256 => assert_eq!(key_id, Sha256::digest(spki).to_vec()),
+                _ => assert_eq!(key_id.len(), 20),
+            }
+        }
+        assert_eq!(
+            KeyIdMethod::Sha256Truncated.compute(&pk, &spki),
+            Sha256::digest(pk)[..20].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_jwk_thumbprint() {
+        assert_eq!(get_jose_alg(&DsaAlgorithm::MlDsa65.get_oid()), "ML-DSA-65");
+        assert_eq!(
+            get_jose_alg(&KemAlgorithm::MlKem768.get_oid()),
+            "ML-KEM-768"
+        );
+        let oid = DsaAlgorithm::MlDsa44Rsa2048Pss.get_oid();
+        assert_eq!(get_jose_alg(&oid), oid);
+
+        // SHA-256 of {"alg":"ML-DSA-44","kty":"AKP","pub":"AAEC"}
+        let expected = URL_SAFE_NO_PAD.encode(Sha256::digest(
+            br#"{"alg":"ML-DSA-44","kty":"AKP","pub":"AAEC"}"#,
+        ));
+        assert_eq!(jwk_thumbprint("ML-DSA-44", &[0, 1, 2]), expected);
+    }
+}
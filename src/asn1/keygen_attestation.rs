@@ -0,0 +1,336 @@
+use crate::asn1::private_key::PrivateKey;
+use crate::asn1::public_key::PublicKey;
+use crate::utils::cbor::Value;
+use crate::utils::clock_skew::get_clock_skew_tolerance;
+use crate::utils::fips::get_fips_mode;
+use crate::utils::verification_report::{unix_now, VerificationReport};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The version of the attestation structure
+const ATTESTATION_VERSION: i64 = 1;
+
+/// Prefixed to the statement before signing, so the identity key can't be
+/// tricked into attesting with a signature made for another purpose
+const ATTESTATION_LABEL: &[u8] = b"quantcrypt key generation attestation\0";
+
+/// The Cargo features of quantcrypt that are recorded in an attestation
+const FEATURES: [(&str, bool); 8] = [
+    ("liboqs-interop", cfg!(feature = "liboqs-interop")),
+    ("http-fetcher", cfg!(feature = "http-fetcher")),
+    ("test-utils", cfg!(feature = "test-utils")),
+    ("legacy", cfg!(feature = "legacy")),
+    ("rayon", cfg!(feature = "rayon")),
+    ("kem-traits", cfg!(feature = "kem-traits")),
+    ("bench", cfg!(feature = "bench")),
+    ("pure-rust", cfg!(feature = "pure-rust")),
+];
+
+/// The build of quantcrypt that generated a key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// The name of the crate
+    pub crate_name: String,
+    /// The version of the crate
+    pub crate_version: String,
+    /// The operating system of the target, e.g. "linux"
+    pub target_os: String,
+    /// The architecture of the target, e.g. "x86_64"
+    pub target_arch: String,
+    /// The Cargo features that were enabled
+    pub features: Vec<String>,
+    /// Whether the FIPS mode was enabled when the key was generated
+    pub fips_mode: bool,
+}
+
+impl BuildInfo {
+    /// Get the build running this code
+    pub fn current() -> BuildInfo {
+        BuildInfo {
+            crate_name: env!("CARGO_PKG_NAME").to_string(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            target_os: std::env::consts::OS.to_string(),
+            target_arch: std::env::consts::ARCH.to_string(),
+            features: FEATURES
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            fips_mode: get_fips_mode(),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Array(vec![
+            Value::Text(self.crate_name.clone()),
+            Value::Text(self.crate_version.clone()),
+            Value::Text(self.target_os.clone()),
+            Value::Text(self.target_arch.clone()),
+            Value::Array(self.features.iter().cloned().map(Value::Text).collect()),
+            Value::Int(self.fips_mode as i64),
+        ])
+    }
+
+    fn from_value(value: &Value) -> Result<BuildInfo> {
+        let [crate_name, crate_version, target_os, target_arch, features, fips_mode] =
+            value.as_array()?
+        else {
+            return Err(QuantCryptError::InvalidCbor);
+        };
+        Ok(BuildInfo {
+            crate_name: crate_name.as_text()?.to_string(),
+            crate_version: crate_version.as_text()?.to_string(),
+            target_os: target_os.as_text()?.to_string(),
+            target_arch: target_arch.as_text()?.to_string(),
+            features: features
+                .as_array()?
+                .iter()
+                .map(|f| f.as_text().map(str::to_string))
+                .collect::<Result<_>>()?,
+            fips_mode: match fips_mode.as_int()? {
+                0 => false,
+                1 => true,
+                _ => return Err(QuantCryptError::InvalidCbor),
+            },
+        })
+    }
+}
+
+/// A signed statement that a key was generated by quantcrypt
+///
+/// Produced by `DsaKeyGenerator::generate_attested` and
+/// `KemKeyGenerator::generate_attested`, for key ceremony records: the
+/// identity key of the device or platform running the ceremony signs the new
+/// public key, its algorithm, the build of quantcrypt and the time of the
+/// generation. Attestations can't be made for keys generated elsewhere.
+///
+/// The attestation is only as trustworthy as the identity key and the
+/// platform it runs on. It records what the signing software claims, and
+/// doesn't prove that the private key never left it.
+///
+/// The statement is signed with the identity key, prefixed with a label, as
+/// the CBOR structure
+///
+/// ```text
+/// [ version: 1, public_key: bstr, algorithm: tstr, timestamp: uint,
+///   [ crate_name: tstr, crate_version: tstr, target_os: tstr, target_arch: tstr,
+///     [* feature: tstr], fips_mode: uint ] ]
+/// ```
+///
+/// where the public key is its DER encoded SubjectPublicKeyInfo and the time
+/// is in seconds since the Unix epoch. `to_bytes` appends the signature as a
+/// byte string to this array.
+///
+/// # Example
+/// ```
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+/// use quantcrypt::kems::{KemAlgorithm, KemKeyGenerator};
+/// use quantcrypt::keys::KeyGenAttestation;
+///
+/// let (device_pk, device_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65).generate().unwrap();
+///
+/// let (pk, _sk, attestation) = KemKeyGenerator::new(KemAlgorithm::MlKem768)
+///     .generate_attested(&device_sk)
+///     .unwrap();
+/// let attestation = KeyGenAttestation::from_bytes(&attestation.to_bytes()).unwrap();
+/// assert!(attestation.verify(&device_pk, &pk).unwrap().is_valid());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyGenAttestation {
+    /// The DER encoded SubjectPublicKeyInfo of the generated key
+    pub public_key: Vec<u8>,
+    /// The OID of the algorithm of the generated key
+    pub algorithm: String,
+    /// When the key was generated, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// The build of quantcrypt that generated the key
+    pub build: BuildInfo,
+    /// The signature of the identity key over the statement
+    pub signature: Vec<u8>,
+}
+
+impl KeyGenAttestation {
+    /// Attest that a key was just generated
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key that was generated
+    /// * `identity` - The signing key of the device or platform
+    ///
+    /// # Returns
+    ///
+    /// The signed attestation
+    pub(crate) fn attest(pk: &PublicKey, identity: &PrivateKey) -> Result<KeyGenAttestation> {
+        let mut attestation = KeyGenAttestation {
+            public_key: pk.to_der()?,
+            algorithm: pk.get_oid().to_string(),
+            timestamp: unix_now(),
+            build: BuildInfo::current(),
+            signature: Vec::new(),
+        };
+        attestation.signature = identity.sign(&attestation.get_signed_data())?;
+        Ok(attestation)
+    }
+
+    /// Get the statement fields, without the signature
+    fn statement_fields(&self) -> Vec<Value> {
+        vec![
+            Value::Int(ATTESTATION_VERSION),
+            Value::Bytes(self.public_key.clone()),
+            Value::Text(self.algorithm.clone()),
+            Value::Int(self.timestamp as i64),
+            self.build.to_value(),
+        ]
+    }
+
+    /// Get the data covered by the signature, the label followed by the encoded statement
+    pub fn get_signed_data(&self) -> Vec<u8> {
+        let mut data = ATTESTATION_LABEL.to_vec();
+        data.extend(Value::Array(self.statement_fields()).to_vec());
+        data
+    }
+
+    /// Encode the attestation as CBOR
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut fields = self.statement_fields();
+        fields.push(Value::Bytes(self.signature.clone()));
+        Value::Array(fields).to_vec()
+    }
+
+    /// Decode an attestation encoded with `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCbor` will be returned if the structure is malformed
+    pub fn from_bytes(bytes: &[u8]) -> Result<KeyGenAttestation> {
+        let value = Value::from_slice(bytes)?;
+        let [version, public_key, algorithm, timestamp, build, signature] = value.as_array()?
+        else {
+            return Err(QuantCryptError::InvalidCbor);
+        };
+        if version.as_int()? != ATTESTATION_VERSION {
+            return Err(QuantCryptError::InvalidCbor);
+        }
+        Ok(KeyGenAttestation {
+            public_key: public_key.as_bytes()?.to_vec(),
+            algorithm: algorithm.as_text()?.to_string(),
+            timestamp: u64::try_from(timestamp.as_int()?)
+                .map_err(|_| QuantCryptError::InvalidCbor)?,
+            build: BuildInfo::from_value(build)?,
+            signature: signature.as_bytes()?.to_vec(),
+        })
+    }
+
+    /// Get the attested public key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the key can't be decoded
+    pub fn get_public_key(&self) -> Result<PublicKey> {
+        PublicKey::from_der(&self.public_key)
+    }
+
+    /// Verify that an attestation was signed by an identity key for a public key
+    ///
+    /// Checks that the attested key is `pk`, that the attested algorithm is the
+    /// one of the key, that the attestation isn't timestamped in the future
+    /// (allowing for the clock skew tolerance, see `set_clock_skew_tolerance`)
+    /// and the signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - The public key of the device or platform
+    /// * `pk` - The public key the attestation should be for
+    ///
+    /// # Returns
+    ///
+    /// The verification report
+    pub fn verify(&self, identity: &PublicKey, pk: &PublicKey) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.policy = Some(format!(
+            "key generation attestation by {}",
+            identity.get_spki_pin()?
+        ));
+        report.valid = report.check("public key matches", self.public_key == pk.to_der()?)
+            && report.check("algorithm matches", self.algorithm == pk.get_oid())
+            && report.check(
+                "not in the future",
+                self.timestamp <= unix_now() + get_clock_skew_tolerance().as_secs(),
+            )
+            && report.check(
+                "signature",
+                identity.verify(&self.get_signed_data(), &self.signature)?,
+            );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::kems::{KemAlgorithm, KemKeyGenerator};
+
+    #[test]
+    fn test_keygen_attestation() {
+        let (identity_pk, identity_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+
+        let (pk, _, attestation) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate_attested(&identity_sk)
+            .unwrap();
+        assert_eq!(attestation.algorithm, DsaAlgorithm::MlDsa65.get_oid());
+        assert_eq!(attestation.build, BuildInfo::current());
+        assert_eq!(
+            attestation.get_public_key().unwrap().to_der().unwrap(),
+            pk.to_der().unwrap()
+        );
+        let report = attestation.verify(&identity_pk, &pk).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.checks.len(), 4);
+
+        let (kem_pk, _, kem_attestation) = KemKeyGenerator::new(KemAlgorithm::MlKem768X25519)
+            .generate_attested(&identity_sk)
+            .unwrap();
+        let decoded = KeyGenAttestation::from_bytes(&kem_attestation.to_bytes()).unwrap();
+        assert_eq!(decoded, kem_attestation);
+        assert!(decoded.verify(&identity_pk, &kem_pk).unwrap().is_valid());
+
+        // Another key
+        let report = attestation.verify(&identity_pk, &kem_pk).unwrap();
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "public key matches"
+        );
+
+        // Another identity
+        let (other_pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        assert!(!attestation.verify(&other_pk, &pk).unwrap().is_valid());
+
+        // A tampered statement
+        let mut forged = attestation.clone();
+        forged.build.fips_mode = !forged.build.fips_mode;
+        let report = forged.verify(&identity_pk, &pk).unwrap();
+        assert_eq!(report.get_failed_check().unwrap().name, "signature");
+        let mut future = attestation.clone();
+        future.timestamp += 3600;
+        let report = future.verify(&identity_pk, &pk).unwrap();
+        assert_eq!(report.get_failed_check().unwrap().name, "not in the future");
+
+        assert!(KeyGenAttestation::from_bytes(&attestation.to_bytes()[1..]).is_err());
+
+        // The identity key must be able to sign
+        assert!(KemKeyGenerator::new(KemAlgorithm::MlKem768)
+            .generate_attested(
+                &KemKeyGenerator::new(KemAlgorithm::MlKem768)
+                    .generate()
+                    .unwrap()
+                    .1
+            )
+            .is_err());
+    }
+}
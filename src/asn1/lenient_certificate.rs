@@ -0,0 +1,466 @@
+use chrono::{DateTime, Utc};
+use der::asn1::BitString;
+use der::Decode;
+use spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_cert::ext::Extension;
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::time::Time;
+
+/// The DER tag of a SEQUENCE
+const TAG_SEQUENCE: u8 = 0x30;
+/// The DER tag of the explicitly tagged version field of a TBSCertificate
+const TAG_VERSION: u8 = 0xa0;
+/// The DER tag of the implicitly tagged issuerUniqueID field of a TBSCertificate
+const TAG_ISSUER_UNIQUE_ID: u8 = 0x81;
+/// The DER tag of the implicitly tagged subjectUniqueID field of a TBSCertificate
+const TAG_SUBJECT_UNIQUE_ID: u8 = 0x82;
+/// The DER tag of the explicitly tagged extensions field of a TBSCertificate
+const TAG_EXTENSIONS: u8 = 0xa3;
+
+/// An error found while parsing a single field of a certificate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    /// The name of the field, e.g. `tbsCertificate.subject`
+    pub field: String,
+    /// A description of the error
+    pub error: String,
+}
+
+/// An extension of a leniently parsed certificate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LenientExtension {
+    /// The OID of the extension
+    pub oid: String,
+    /// Whether the extension is marked critical
+    pub critical: bool,
+    /// The DER encoded value of the extension
+    pub value: Vec<u8>,
+}
+
+/// A certificate parsed field by field, for inspecting damaged or non-conformant certificates
+///
+/// Unlike `Certificate::from_der`, parsing never fails as a whole. Every field
+/// that can be decoded is extracted, and every field that can't be is left as
+/// `None` with an entry in `errors`. This is meant for forensics, the result
+/// carries no assurance and must never be used to make trust decisions.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::LenientCertificate;
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let der = std::fs::read(cert_path).unwrap();
+///
+/// // Cut the certificate short, losing the signature
+/// let cert = LenientCertificate::parse(&der[..der.len() / 2]);
+/// assert!(cert.subject.is_some());
+/// assert!(cert.signature.is_none());
+/// assert!(!cert.errors.is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LenientCertificate {
+    /// The version (0 for v1, 2 for v3)
+    pub version: Option<u8>,
+    /// The big-endian bytes of the serial number
+    pub serial_number: Option<Vec<u8>>,
+    /// The OID of the signature algorithm in the TBSCertificate
+    pub tbs_signature_algorithm: Option<String>,
+    /// The issuer distinguished name
+    pub issuer: Option<String>,
+    /// The start of the validity period
+    pub not_before: Option<DateTime<Utc>>,
+    /// The end of the validity period
+    pub not_after: Option<DateTime<Utc>>,
+    /// The subject distinguished name
+    pub subject: Option<String>,
+    /// The OID of the public key algorithm
+    pub public_key_algorithm: Option<String>,
+    /// The raw public key
+    pub public_key: Option<Vec<u8>>,
+    /// The extensions that could be decoded
+    pub extensions: Vec<LenientExtension>,
+    /// The OID of the outer signature algorithm
+    pub signature_algorithm: Option<String>,
+    /// The raw signature
+    pub signature: Option<Vec<u8>>,
+    /// The errors found, one per field that couldn't be decoded
+    pub errors: Vec<FieldError>,
+}
+
+/// A single tag-length-value element
+pub(crate) struct Tlv<'a> {
+    pub(crate) tag: u8,
+    /// The value, cut short if the element is truncated
+    pub(crate) value: &'a [u8],
+    /// The whole element, including the header
+    pub(crate) encoded: &'a [u8],
+    pub(crate) truncated: bool,
+}
+
+/// Read a DER element, tolerating a length that runs past the end of the input
+///
+/// Returns the element and the number of bytes consumed, or None if not even the header can be read.
+pub(crate) fn read_tlv(data: &[u8]) -> Option<(Tlv<'_>, usize)> {
+    let tag = *data.first()?;
+    // Multi-byte tags are not used in certificates
+    if tag & 0x1f == 0x1f {
+        return None;
+    }
+    let first = *data.get(1)? as usize;
+    let (len, header_len) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let num_bytes = first & 0x7f;
+        // Indefinite lengths are not DER, and lengths over 4 bytes are not plausible
+        if num_bytes == 0 || num_bytes > 4 {
+            return None;
+        }
+        let len_bytes = data.get(2..2 + num_bytes)?;
+        let len = len_bytes
+            .iter()
+            .fold(0usize, |len, b| (len << 8) | *b as usize);
+        (len, 2 + num_bytes)
+    };
+    let available = data.len() - header_len;
+    let truncated = len > available;
+    let len = len.min(available);
+    let tlv = Tlv {
+        tag,
+        value: &data[header_len..header_len + len],
+        encoded: &data[..header_len + len],
+        truncated,
+    };
+    Some((tlv, header_len + len))
+}
+
+/// Split the value of a constructed element into its children
+///
+/// Stops at the first child whose header can't be read.
+pub(crate) fn read_children(data: &[u8]) -> Vec<Tlv<'_>> {
+    let mut children = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        match read_tlv(rest) {
+            Some((tlv, consumed)) => {
+                children.push(tlv);
+                rest = &rest[consumed..];
+            }
+            None => break,
+        }
+    }
+    children
+}
+
+/// Convert an X.509 time to a chrono time
+fn to_chrono(time: &Time) -> DateTime<Utc> {
+    time.to_system_time().into()
+}
+
+impl LenientCertificate {
+    /// Parse a certificate, extracting whatever fields can be decoded
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The certificate, in DER or PEM format
+    ///
+    /// # Returns
+    ///
+    /// The fields that could be decoded, and the errors for the ones that couldn't
+    pub fn parse(data: &[u8]) -> LenientCertificate {
+        let mut cert = LenientCertificate::default();
+
+        let pem_der = std::str::from_utf8(data)
+            .ok()
+            .filter(|text| text.trim_start().starts_with("-----BEGIN"))
+            .map(pem::parse);
+        let der = match &pem_der {
+            Some(Ok(pem)) => pem.contents(),
+            Some(Err(e)) => {
+                cert.error("pem", e.to_string());
+                return cert;
+            }
+            None => data,
+        };
+
+        let outer = match read_tlv(der) {
+            Some((outer, consumed)) => {
+                if consumed < der.len() {
+                    cert.error("certificate", "trailing data after the certificate");
+                }
+                outer
+            }
+            None => {
+                cert.error("certificate", "not a DER element");
+                return cert;
+            }
+        };
+        cert.check_element(&outer, "certificate", TAG_SEQUENCE);
+
+        let mut fields = read_children(outer.value).into_iter();
+        match fields.next() {
+            Some(tbs) => cert.parse_tbs(&tbs),
+            None => cert.error("tbsCertificate", "missing"),
+        }
+        match fields.next() {
+            Some(alg) => {
+                cert.signature_algorithm = cert
+                    .decode::<AlgorithmIdentifierOwned>(&alg, "signatureAlgorithm")
+                    .map(|alg| alg.oid.to_string())
+            }
+            None => cert.error("signatureAlgorithm", "missing"),
+        }
+        match fields.next() {
+            Some(sig) => {
+                cert.signature = cert
+                    .decode::<BitString>(&sig, "signature")
+                    .map(|sig| sig.raw_bytes().to_vec())
+            }
+            None => cert.error("signature", "missing"),
+        }
+        if fields.next().is_some() {
+            cert.error("certificate", "unexpected fields after the signature");
+        }
+        cert
+    }
+
+    /// Check if no error was found
+    ///
+    /// A certificate without errors can be parsed strictly with `Certificate::from_der`.
+    ///
+    /// # Returns
+    ///
+    /// True if every field was decoded
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Parse the TBSCertificate
+    fn parse_tbs(&mut self, tbs: &Tlv<'_>) {
+        self.check_element(tbs, "tbsCertificate", TAG_SEQUENCE);
+        let mut fields = read_children(tbs.value).into_iter().peekable();
+
+        if let Some(version) = fields.next_if(|field| field.tag == TAG_VERSION) {
+            let value = read_children(version.value);
+            self.version = match value.first() {
+                Some(value) => self.decode::<u8>(value, "tbsCertificate.version"),
+                None => {
+                    self.error("tbsCertificate.version", "empty");
+                    None
+                }
+            };
+        } else {
+            // DEFAULT v1
+            self.version = Some(0);
+        }
+
+        match fields.next() {
+            Some(serial) => {
+                self.serial_number = self
+                    .decode::<SerialNumber>(&serial, "tbsCertificate.serialNumber")
+                    .map(|serial| serial.as_bytes().to_vec())
+            }
+            None => self.error("tbsCertificate.serialNumber", "missing"),
+        }
+
+        match fields.next() {
+            Some(alg) => {
+                self.tbs_signature_algorithm = self
+                    .decode::<AlgorithmIdentifierOwned>(&alg, "tbsCertificate.signature")
+                    .map(|alg| alg.oid.to_string())
+            }
+            None => self.error("tbsCertificate.signature", "missing"),
+        }
+
+        match fields.next() {
+            Some(issuer) => {
+                self.issuer = self
+                    .decode::<Name>(&issuer, "tbsCertificate.issuer")
+                    .map(|name| name.to_string())
+            }
+            None => self.error("tbsCertificate.issuer", "missing"),
+        }
+
+        match fields.next() {
+            Some(validity) => self.parse_validity(&validity),
+            None => self.error("tbsCertificate.validity", "missing"),
+        }
+
+        match fields.next() {
+            Some(subject) => {
+                self.subject = self
+                    .decode::<Name>(&subject, "tbsCertificate.subject")
+                    .map(|name| name.to_string())
+            }
+            None => self.error("tbsCertificate.subject", "missing"),
+        }
+
+        match fields.next() {
+            Some(spki) => {
+                if let Some(spki) = self.decode::<SubjectPublicKeyInfoOwned>(
+                    &spki,
+                    "tbsCertificate.subjectPublicKeyInfo",
+                ) {
+                    self.public_key_algorithm = Some(spki.algorithm.oid.to_string());
+                    self.public_key = Some(spki.subject_public_key.raw_bytes().to_vec());
+                }
+            }
+            None => self.error("tbsCertificate.subjectPublicKeyInfo", "missing"),
+        }
+
+        fields.next_if(|field| field.tag == TAG_ISSUER_UNIQUE_ID);
+        fields.next_if(|field| field.tag == TAG_SUBJECT_UNIQUE_ID);
+
+        if let Some(extensions) = fields.next_if(|field| field.tag == TAG_EXTENSIONS) {
+            self.parse_extensions(&extensions);
+        }
+
+        if fields.next().is_some() {
+            self.error("tbsCertificate", "unexpected fields after the extensions");
+        }
+    }
+
+    /// Parse the validity, decoding each time separately
+    fn parse_validity(&mut self, validity: &Tlv<'_>) {
+        self.check_element(validity, "tbsCertificate.validity", TAG_SEQUENCE);
+        let mut times = read_children(validity.value).into_iter();
+        match times.next() {
+            Some(time) => {
+                self.not_before = self
+                    .decode::<Time>(&time, "tbsCertificate.validity.notBefore")
+                    .map(|time| to_chrono(&time))
+            }
+            None => self.error("tbsCertificate.validity.notBefore", "missing"),
+        }
+        match times.next() {
+            Some(time) => {
+                self.not_after = self
+                    .decode::<Time>(&time, "tbsCertificate.validity.notAfter")
+                    .map(|time| to_chrono(&time))
+            }
+            None => self.error("tbsCertificate.validity.notAfter", "missing"),
+        }
+    }
+
+    /// Parse the extensions, decoding each extension separately
+    fn parse_extensions(&mut self, extensions: &Tlv<'_>) {
+        let sequence = match read_children(extensions.value).into_iter().next() {
+            Some(sequence) => sequence,
+            None => {
+                self.error("tbsCertificate.extensions", "empty");
+                return;
+            }
+        };
+        self.check_element(&sequence, "tbsCertificate.extensions", TAG_SEQUENCE);
+        for (i, ext) in read_children(sequence.value).iter().enumerate() {
+            let field = format!("tbsCertificate.extensions[{}]", i);
+            if let Some(ext) = self.decode::<Extension>(ext, &field) {
+                self.extensions.push(LenientExtension {
+                    oid: ext.extn_id.to_string(),
+                    critical: ext.critical,
+                    value: ext.extn_value.as_bytes().to_vec(),
+                });
+            }
+        }
+    }
+
+    /// Record an error if an element is truncated or has an unexpected tag
+    fn check_element(&mut self, tlv: &Tlv<'_>, field: &str, tag: u8) {
+        if tlv.tag != tag {
+            self.error(
+                field,
+                format!("unexpected tag 0x{:02x}, expected 0x{:02x}", tlv.tag, tag),
+            );
+        }
+        if tlv.truncated {
+            self.error(field, "truncated");
+        }
+    }
+
+    /// Decode an element, recording an error if it can't be decoded
+    fn decode<'a, T: Decode<'a>>(&mut self, tlv: &Tlv<'a>, field: &str) -> Option<T> {
+        if tlv.truncated {
+            self.error(field, "truncated");
+            return None;
+        }
+        match T::from_der(tlv.encoded) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error(field, e.to_string());
+                None
+            }
+        }
+    }
+
+    fn error(&mut self, field: &str, error: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            error: error.into(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::Certificate;
+
+    const CERT_PATH: &str = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+
+    #[test]
+    fn test_lenient_parsing() {
+        let der = std::fs::read(CERT_PATH).unwrap();
+        let strict = Certificate::from_der(&der).unwrap();
+
+        // A well formed certificate is parsed completely
+        let cert = LenientCertificate::parse(&der);
+        assert!(cert.is_complete(), "{:?}", cert.errors);
+        assert_eq!(cert.version, Some(2));
+        assert_eq!(cert.subject, Some(strict.get_subject().to_string()));
+        assert_eq!(
+            cert.serial_number.as_deref(),
+            Some(strict.get_serial_number().as_bytes())
+        );
+        assert_eq!(cert.public_key_algorithm, Some(strict.get_public_key_oid()));
+        assert_eq!(cert.signature_algorithm, Some(strict.get_signature_oid()));
+        assert!(!cert.extensions.is_empty());
+        assert!(cert.not_before.unwrap() < cert.not_after.unwrap());
+
+        // PEM is accepted too
+        let pem = LenientCertificate::parse(strict.to_pem().unwrap().as_bytes());
+        assert_eq!(pem, cert);
+
+        // A damaged issuer name only loses the issuer
+        let issuer = strict.get_issuer();
+        let issuer_der = der::Encode::to_der(&issuer).unwrap();
+        let pos = der
+            .windows(issuer_der.len())
+            .position(|w| w == issuer_der.as_slice())
+            .unwrap();
+        let mut damaged = der.clone();
+        // Turn the first RDN SET into an INTEGER
+        damaged[pos + 2] = 0x02;
+        assert!(Certificate::from_der(&damaged).is_err());
+        let cert = LenientCertificate::parse(&damaged);
+        assert_eq!(cert.issuer, None);
+        assert_eq!(cert.subject, Some(strict.get_subject().to_string()));
+        assert!(cert.public_key.is_some());
+        assert!(cert.signature.is_some());
+        assert_eq!(cert.errors.len(), 1);
+        assert_eq!(cert.errors[0].field, "tbsCertificate.issuer");
+
+        // A truncated certificate keeps the fields before the cut
+        let cert = LenientCertificate::parse(&der[..200]);
+        assert!(cert.serial_number.is_some());
+        assert!(cert.signature_algorithm.is_none());
+        assert!(cert
+            .errors
+            .iter()
+            .any(|e| e.field == "certificate" && e.error == "truncated"));
+
+        // Garbage doesn't panic
+        let cert = LenientCertificate::parse(&[0x30, 0x84, 0xff]);
+        assert!(!cert.is_complete());
+        assert!(!LenientCertificate::parse(&[]).is_complete());
+    }
+}
@@ -1,8 +1,15 @@
 pub mod asn_util;
+pub mod attestation_evidence;
 pub mod cert_builder;
 pub mod certificate;
 pub mod composite_private_key;
 pub mod composite_public_key;
+pub mod csr;
+pub mod diff;
+pub mod key_id;
+pub mod keygen_attestation;
+pub mod lenient_certificate;
+pub mod oid_alias;
 pub mod private_key;
 pub mod public_key;
 pub mod public_key_info;
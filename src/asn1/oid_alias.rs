@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use der::{Decode, Encode};
+use pkcs8::spki::SubjectPublicKeyInfoOwned;
+use pkcs8::ObjectIdentifier;
+
+use crate::asn1::asn_util::is_valid_kem_or_dsa_oid;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The arc under which the composite signature drafts allocate OIDs
+const COMPOSITE_SIG_ARC: &str = "2.16.840.1.114027.80.8.1";
+
+/// Offset between the pre-draft-03 composite signature OIDs and the current ones
+const COMPOSITE_SIG_LEGACY_OFFSET: u32 = 20;
+
+/// A table mapping legacy OIDs to the OIDs currently used by this crate
+///
+/// The LAMPS composite drafts have renumbered their algorithm identifiers
+/// between revisions. Partners who are still on an earlier draft will send
+/// certificates carrying the old OIDs, which this crate would otherwise
+/// reject as unknown. An alias table allows verification to accept such
+/// OIDs by translating them to the current ones, and allows issuance to emit
+/// the legacy OIDs for partners that cannot yet parse the current ones.
+///
+/// Only the OIDs are translated. The key and signature encodings must be the
+/// ones of the current draft.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::OidAliasTable;
+///
+/// let aliases = OidAliasTable::with_legacy_composite_oids();
+/// assert_eq!(
+///     aliases.resolve("2.16.840.1.114027.80.8.1.1"),
+///     Some("2.16.840.1.114027.80.8.1.21")
+/// );
+/// assert_eq!(
+///     aliases.legacy_for("2.16.840.1.114027.80.8.1.21"),
+///     Some("2.16.840.1.114027.80.8.1.1")
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OidAliasTable {
+    /// Legacy OID -> current OID
+    aliases: HashMap<String, String>,
+}
+
+impl OidAliasTable {
+    /// Create a new, empty alias table
+    ///
+    /// # Returns
+    ///
+    /// A new alias table
+    pub fn new() -> OidAliasTable {
+        OidAliasTable::default()
+    }
+
+    /// Create an alias table prepopulated with the composite signature OIDs
+    /// used before the arc was renumbered
+    ///
+    /// Earlier revisions of the composite signature draft allocated
+    /// `2.16.840.1.114027.80.8.1.1` to `2.16.840.1.114027.80.8.1.13`, which
+    /// are now `2.16.840.1.114027.80.8.1.21` to `2.16.840.1.114027.80.8.1.33`.
+    ///
+    /// # Returns
+    ///
+    /// A new alias table
+    pub fn with_legacy_composite_oids() -> OidAliasTable {
+        let mut table = OidAliasTable::new();
+        for legacy in (1..=13).filter(|i| *i != 5) {
+            let legacy_oid = format!("{}.{}", COMPOSITE_SIG_ARC, legacy);
+            let current_oid = format!(
+                "{}.{}",
+                COMPOSITE_SIG_ARC,
+                legacy + COMPOSITE_SIG_LEGACY_OFFSET
+            );
+            table.aliases.insert(legacy_oid, current_oid);
+        }
+        table
+    }
+
+    /// Add an alias to the table
+    ///
+    /// # Arguments
+    ///
+    /// * `legacy_oid` - The legacy OID
+    /// * `current_oid` - The OID it should be translated to
+    ///
+    /// # Returns
+    ///
+    /// The table, for chaining
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidOid` if either OID is malformed, if `current_oid` is not
+    /// an algorithm supported by this crate, or if `legacy_oid` is itself a current OID
+    pub fn add_alias(&mut self, legacy_oid: &str, current_oid: &str) -> Result<&mut Self> {
+        ObjectIdentifier::new(legacy_oid).map_err(|_| QuantCryptError::InvalidOid)?;
+        if !is_valid_kem_or_dsa_oid(&current_oid.to_string())
+            || is_valid_kem_or_dsa_oid(&legacy_oid.to_string())
+        {
+            return Err(QuantCryptError::InvalidOid);
+        }
+        self.aliases
+            .insert(legacy_oid.to_string(), current_oid.to_string());
+        Ok(self)
+    }
+
+    /// Translate a legacy OID to the current OID
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The OID to translate
+    ///
+    /// # Returns
+    ///
+    /// The current OID if `oid` is a known legacy OID, None otherwise
+    pub fn resolve(&self, oid: &str) -> Option<&str> {
+        self.aliases.get(oid).map(|s| s.as_str())
+    }
+
+    /// Get the legacy OID for a current OID
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The current OID
+    ///
+    /// # Returns
+    ///
+    /// The legacy OID if one is registered, None otherwise
+    pub fn legacy_for(&self, oid: &str) -> Option<&str> {
+        self.aliases
+            .iter()
+            .find(|(_, current)| current.as_str() == oid)
+            .map(|(legacy, _)| legacy.as_str())
+    }
+
+    /// Check if the table is empty
+    ///
+    /// # Returns
+    ///
+    /// True if no aliases are registered
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Rewrite the algorithm OID of a DER encoded SubjectPublicKeyInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `spki_der` - The DER encoded SubjectPublicKeyInfo
+    ///
+    /// # Returns
+    ///
+    /// The (possibly rewritten) SubjectPublicKeyInfo and the legacy OID that was translated, if any
+    pub(crate) fn translate_spki(&self, spki_der: &[u8]) -> Result<(Vec<u8>, Option<String>)> {
+        let mut spki = SubjectPublicKeyInfoOwned::from_der(spki_der)
+            .map_err(|_| QuantCryptError::InvalidPublicKey)?;
+        let oid = spki.algorithm.oid.to_string();
+        if let Some(current) = self.resolve(&oid) {
+            spki.algorithm.oid =
+                ObjectIdentifier::new(current).map_err(|_| QuantCryptError::InvalidOid)?;
+            let der = spki
+                .to_der()
+                .map_err(|_| QuantCryptError::InvalidPublicKey)?;
+            Ok((der, Some(oid)))
+        } else {
+            Ok((spki_der.to_vec(), None))
+        }
+    }
+
+    /// Build the warning reported when a legacy OID is accepted
+    pub(crate) fn warning(&self, what: &str, legacy_oid: &str) -> String {
+        format!(
+            "{} uses legacy OID {}, accepted as {}",
+            what,
+            legacy_oid,
+            self.resolve(legacy_oid).unwrap_or("unknown")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsa::api::algorithm::DsaAlgorithm;
+
+    #[test]
+    fn test_legacy_composite_oids() {
+        let table = OidAliasTable::with_legacy_composite_oids();
+        assert_eq!(table.aliases.len(), 12);
+        for current in table.aliases.values() {
+            assert!(DsaAlgorithm::from_oid(current).is_some(), "{}", current);
+        }
+        assert_eq!(table.resolve("2.16.840.1.114027.80.8.1.5"), None);
+        assert_eq!(
+            table.resolve("2.16.840.1.114027.80.8.1.13"),
+            Some(DsaAlgorithm::MlDsa87Ed448.get_oid().as_str())
+        );
+        assert_eq!(table.legacy_for("2.16.840.1.114027.80.8.1.34"), None);
+    }
+
+    #[test]
+    fn test_add_alias() {
+        let mut table = OidAliasTable::new();
+        assert!(table.is_empty());
+        let current = DsaAlgorithm::MlDsa44.get_oid();
+        table.add_alias("1.2.3.4", &current).unwrap();
+        assert_eq!(table.resolve("1.2.3.4"), Some(current.as_str()));
+        assert_eq!(table.legacy_for(&current), Some("1.2.3.4"));
+
+        // Unknown target, current OID as legacy, and malformed OIDs are rejected
+        assert!(table.add_alias("1.2.3.5", "1.2.3.6").is_err());
+        assert!(table.add_alias(&current, &current).is_err());
+        assert!(table.add_alias("not an oid", &current).is_err());
+    }
+}
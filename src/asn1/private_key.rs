@@ -6,17 +6,30 @@ use pkcs8::{spki::AlgorithmIdentifier, PrivateKeyInfo};
 
 use crate::asn1::asn_util::{is_composite_kem_or_dsa_oid, is_valid_kem_or_dsa_oid};
 use crate::asn1::signature::DsaSignature;
+use crate::dsa::api::verify_after_sign::{sign_dsa, sign_prehash_dsa};
 use crate::dsa::common::dsa_trait::Dsa;
 use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
 use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
 use crate::dsa::dsa_manager::{DsaManager, PrehashDsaManager};
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::shared_secret::SharedSecret;
 use crate::kem::common::kem_trait::Kem;
-use crate::kem::kem_manager::KemManager;
+use crate::utils::manager_pool::{
+    get_dsa_manager_from_oid, get_kem_manager_from_oid, get_prehash_dsa_manager,
+};
 use crate::{asn1::composite_private_key::CompositePrivateKey, errors};
 use crate::{keys::PublicKey, QuantCryptError};
 use signature::{Keypair, Signer};
+use zeroize::Zeroizing;
 
 use crate::asn1::asn_util::is_dsa_oid;
+use crate::asn1::key_id::get_cose_alg;
+use crate::utils::cbor::{
+    cose_protected_header, cose_sign1_to_be_signed, encode_with_oid, Value, COSE_SIGN1_TAG,
+};
+use crate::utils::ct_codec::{decode_base64_ct, decode_hex_ct, encode_base64_ct, encode_hex_ct};
+use crate::utils::entropy::SharedRng;
+use crate::utils::resource_limits::{check_der_input, check_input_len, read_file_limited};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 /// A raw private key for use with the certificate builder
@@ -207,7 +220,10 @@ impl PrivateKey {
     /// # Errors
     ///
     /// `KeyError::InvalidPrivateKey` will be returned if the private key is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input is larger than the parse budget
     pub fn from_pem(pem: &str) -> Result<Self> {
+        check_input_len(pem.len())?;
         let pem = pem::parse(pem).map_err(|_| errors::QuantCryptError::InvalidPrivateKey)?;
         // Header should be "PRIVATE KEY"
         if pem.tag() != "PRIVATE KEY" {
@@ -231,7 +247,11 @@ impl PrivateKey {
     /// # Errors
     ///
     /// `KeyError::InvalidPrivateKey` will be returned if the private key is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input or its decoding
+    /// doesn't fit in the parse budget
     pub fn from_der(der: &[u8]) -> Result<Self> {
+        check_der_input(der)?;
         let priv_key_info = PrivateKeyInfo::from_der(der)
             .map_err(|_| errors::QuantCryptError::InvalidPrivateKey)?;
 
@@ -252,6 +272,103 @@ impl PrivateKey {
         })
     }
 
+    /// Get the key as a single line string for environment variables and configuration
+    ///
+    /// The string is the body of the PEM encoding on one line, i.e. the padded
+    /// base64 encoding of the DER, without the armor or line breaks. It is
+    /// encoded in constant time and zeroized when dropped.
+    ///
+    /// # Returns
+    ///
+    /// The base64 encoded PKCS#8 structure
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` will be returned if the private key is invalid
+    ///
+    /// # Example
+    /// ```
+    /// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    /// use quantcrypt::keys::PrivateKey;
+    ///
+    /// let (_, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+    /// let line = sk.to_pem_like_string().unwrap();
+    /// assert!(!line.contains('\n'));
+    ///
+    /// // A full PEM block is accepted as well
+    /// let sk2 = PrivateKey::from_pem_like_string(&line).unwrap();
+    /// let sk3 = PrivateKey::from_pem_like_string(&sk.to_pem().unwrap()).unwrap();
+    /// assert_eq!(sk2.to_der().unwrap(), sk3.to_der().unwrap());
+    /// ```
+    pub fn to_pem_like_string(&self) -> Result<Zeroizing<String>> {
+        let der = Zeroizing::new(self.to_der()?);
+        Ok(encode_base64_ct(&der))
+    }
+
+    /// Create a new private key from the string of `to_pem_like_string`
+    ///
+    /// The base64 is decoded in constant time. Whitespace is ignored and PEM
+    /// armor lines are skipped, so a full PEM block is accepted too.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The base64 encoded PKCS#8 structure
+    ///
+    /// # Returns
+    ///
+    /// A new private key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEncoding` will be returned if the string is not valid base64
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` will be returned if the private key is invalid
+    pub fn from_pem_like_string(s: &str) -> Result<Self> {
+        check_input_len(s.len())?;
+        let body: Zeroizing<String> = Zeroizing::new(
+            s.lines()
+                .filter(|line| !line.trim_start().starts_with("-----"))
+                .collect(),
+        );
+        let der = decode_base64_ct(&body)?;
+        Self::from_der(&der)
+    }
+
+    /// Get the key as the hex encoding of the DER, encoded in constant time
+    ///
+    /// # Returns
+    ///
+    /// The lowercase hex encoded PKCS#8 structure, zeroized when dropped
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` will be returned if the private key is invalid
+    pub fn to_hex(&self) -> Result<Zeroizing<String>> {
+        let der = Zeroizing::new(self.to_der()?);
+        Ok(encode_hex_ct(&der))
+    }
+
+    /// Create a new private key from the hex encoding of the DER, decoded in constant time
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The hex encoded PKCS#8 structure
+    ///
+    /// # Returns
+    ///
+    /// A new private key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEncoding` will be returned if the string is not valid hex
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` will be returned if the private key is invalid
+    pub fn from_hex(s: &str) -> Result<Self> {
+        check_input_len(s.len())?;
+        let der = decode_hex_ct(s)?;
+        Self::from_der(&der)
+    }
+
     /// Sign a message
     ///
     /// # Arguments
@@ -262,22 +379,84 @@ impl PrivateKey {
     ///
     /// The signature
     pub fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.sign_with_rng(data, None)
+    }
+
+    /// Sign a message, drawing the signing randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to sign
+    /// * `rng` - The RNG, or None for the default randomness of the algorithm
+    ///
+    /// # Returns
+    ///
+    /// The signature
+    pub(crate) fn sign_with_rng(&self, data: &[u8], rng: Option<&SharedRng>) -> Result<Vec<u8>> {
         // Signing is only possible with DSA keys
         if !is_dsa_oid(&self.oid) {
             return Err(errors::QuantCryptError::UnsupportedOperation);
         }
 
         if let Some(dsa_type) = PrehashDsaType::from_oid(&self.oid) {
-            let dsa_manager = PrehashDsaManager::new(dsa_type)?;
-            let sig = dsa_manager.sign(&self.private_key, data)?;
-            Ok(sig)
+            let mut dsa_manager = get_prehash_dsa_manager(dsa_type)?;
+            if let Some(rng) = rng {
+                dsa_manager.set_rng(rng.clone())?;
+            }
+            sign_prehash_dsa(&dsa_manager, &self.private_key, data)
         } else {
-            let dsa_manager = DsaManager::new_from_oid(&self.oid)?;
-            let sig = dsa_manager.sign(&self.private_key, data)?;
-            Ok(sig)
+            let mut dsa_manager = get_dsa_manager_from_oid(&self.oid)?;
+            if let Some(rng) = rng {
+                dsa_manager.set_rng(rng.clone())?;
+            }
+            sign_dsa(&dsa_manager, &self.private_key, data)
         }
     }
 
+    /// Sign a message, returning the signature in a compact CBOR encoding
+    ///
+    /// The signature is encoded as `[111(oid), signature]`, with the algorithm
+    /// as an RFC 9090 tagged OID. Use `PublicKey::verify_cbor` to verify it.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to sign
+    ///
+    /// # Returns
+    ///
+    /// The CBOR encoded signature
+    pub fn sign_cbor(&self, data: &[u8]) -> Result<Vec<u8>> {
+        encode_with_oid(&self.oid, &self.sign(data)?)
+    }
+
+    /// Sign a payload as a tagged COSE_Sign1 message (RFC 9052)
+    ///
+    /// The protected header holds the COSE algorithm, and there is no external data.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to sign, included in the message
+    ///
+    /// # Returns
+    ///
+    /// The COSE_Sign1 message
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` will be returned if the key has no COSE algorithm (only ML-DSA has one)
+    pub fn sign_cose_sign1(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let alg = get_cose_alg(&self.oid).ok_or(QuantCryptError::NotImplemented)?;
+        let protected = cose_protected_header(alg);
+        let signature = self.sign(&cose_sign1_to_be_signed(&protected, payload))?;
+        let message = Value::Array(vec![
+            Value::Bytes(protected),
+            Value::Map(Vec::new()),
+            Value::Bytes(payload.to_vec()),
+            Value::Bytes(signature),
+        ]);
+        Ok(Value::Tag(COSE_SIGN1_TAG, Box::new(message)).to_vec())
+    }
+
     /// Use the private key to decapsulate a shared secret from a ciphertext
     ///
     /// # Arguments
@@ -291,13 +470,18 @@ impl PrivateKey {
     /// # Errors
     ///
     /// `QuantCryptError::UnsupportedOperation` will be returned if this private key is not a KEM key
-    pub(crate) fn decap(&self, ct: &[u8]) -> Result<Vec<u8>> {
+    ///
+    /// `QuantCryptError::InvalidCiphertext` will be returned if the ciphertext belongs to a different algorithm
+    pub fn decap(&self, ct: &Ciphertext) -> Result<SharedSecret> {
         if is_dsa_oid(&self.oid) {
             return Err(errors::QuantCryptError::UnsupportedOperation);
         }
-        let kem = KemManager::new_from_oid(&self.oid)?;
-        let ss = kem.decap(&self.private_key, ct)?;
-        Ok(ss)
+        if ct.get_algorithm().get_oid() != self.oid {
+            return Err(errors::QuantCryptError::InvalidCiphertext);
+        }
+        let kem = get_kem_manager_from_oid(&self.oid)?;
+        let ss = kem.decap(&self.private_key, ct.as_bytes())?;
+        Ok(SharedSecret::new(ss))
     }
 
     /// Load a private key from a file. The file can be in either DER or PEM format
@@ -311,7 +495,7 @@ impl PrivateKey {
     /// The private key
     pub fn from_file(path: &str) -> Result<Self> {
         // Read the contents of the file as bytes
-        let contents = std::fs::read(path).map_err(|_| QuantCryptError::FileReadError)?;
+        let contents = read_file_limited(path)?;
 
         // Try to interpret as DER
         let result = PrivateKey::from_der(&contents);
@@ -390,7 +574,7 @@ mod test {
         );
 
         let key_bytes = pk.get_key();
-        let pk2 = CompositePrivateKey::from_der(&pk.oid, &key_bytes).unwrap();
+        let pk2 = CompositePrivateKey::from_der(&pk.oid, key_bytes).unwrap();
 
         assert_eq!(pk.oid, pk2.get_oid());
 
@@ -490,4 +674,44 @@ mod test {
         let der2 = pk2.to_der().unwrap();
         assert_eq!(der, der2);
     }
+
+    #[test]
+    fn test_sk_string_encodings() {
+        let pem_bytes = include_bytes!("../../test/data/mldsa44_ecdsa_p256_sha256_sk.pem");
+        let pem = std::str::from_utf8(pem_bytes).unwrap();
+        let sk = PrivateKey::from_pem(pem).unwrap();
+        let der = sk.to_der().unwrap();
+
+        // The PEM body on one line
+        let line = sk.to_pem_like_string().unwrap();
+        let body: String = pem.lines().filter(|l| !l.starts_with("-----")).collect();
+        assert_eq!(line.as_str(), body);
+        for s in [line.as_str(), pem, &pem.replace('\n', "\r\n")] {
+            assert_eq!(
+                PrivateKey::from_pem_like_string(s)
+                    .unwrap()
+                    .to_der()
+                    .unwrap(),
+                der
+            );
+        }
+
+        let hex_sk = sk.to_hex().unwrap();
+        assert_eq!(hex_sk.as_str(), hex::encode(&der));
+        let upper = hex_sk.to_uppercase();
+        assert_eq!(PrivateKey::from_hex(&upper).unwrap().to_der().unwrap(), der);
+
+        assert!(matches!(
+            PrivateKey::from_pem_like_string(&line[1..]),
+            Err(QuantCryptError::InvalidEncoding)
+        ));
+        assert!(matches!(
+            PrivateKey::from_hex("zz"),
+            Err(QuantCryptError::InvalidEncoding)
+        ));
+        assert!(matches!(
+            PrivateKey::from_hex("00"),
+            Err(QuantCryptError::InvalidPrivateKey)
+        ));
+    }
 }
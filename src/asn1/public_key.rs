@@ -1,11 +1,19 @@
-use crate::asn1::asn_util::{is_composite_kem_or_dsa_oid, is_valid_kem_or_dsa_oid};
+use crate::asn1::asn_util::{
+    is_composite_kem_or_dsa_oid, is_valid_kem_or_dsa_oid, is_verify_only_dsa_oid,
+};
 use crate::dsa::common::dsa_trait::Dsa;
 use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
 use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
-use crate::dsa::dsa_manager::{DsaManager, PrehashDsaManager};
 use crate::errors;
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::shared_secret::SharedSecret;
 use crate::kem::common::kem_trait::Kem;
-use crate::kem::kem_manager::KemManager;
+use crate::utils::manager_pool::{
+    get_dsa_manager_from_oid, get_kem_manager_from_oid, get_prehash_dsa_manager,
+};
+use crate::utils::verification_report::VerificationReport;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use der::{asn1::BitString, Document};
 use der::{Decode, Encode};
 use pem::EncodeConfig;
@@ -13,14 +21,30 @@ use pkcs8::ObjectIdentifier;
 use pkcs8::{spki::AlgorithmIdentifierWithOid, EncodePublicKey};
 
 use crate::asn1::composite_public_key::CompositePublicKey;
+use crate::asn1::key_id::{
+    get_cose_alg, get_jose_alg, get_oid_from_cose_alg, jwk_thumbprint, KeyIdMethod,
+};
+use crate::utils::cbor::{
+    cose_protected_header, cose_sign1_to_be_signed, decode_with_oid, encode_with_oid, Value,
+    COSE_SIGN1_TAG,
+};
 
 use crate::asn1::public_key_info::PublicKeyInfo;
 
 use super::asn_util::{is_dsa_oid, is_kem_oid};
+use crate::utils::resource_limits::{check_der_input, check_input_len, read_file_limited};
 use errors::QuantCryptError;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// The COSE key type of algorithm key pairs (`AKP`)
+const COSE_KTY_AKP: i64 = 7;
+
+/// The COSE_Key labels of the key type, the algorithm and the public key
+const COSE_KEY_KTY: i64 = 1;
+const COSE_KEY_ALG: i64 = 3;
+const COSE_KEY_PUB: i64 = -1;
+
 #[derive(Clone)]
 /// A raw public key for use with the certificate builder
 pub struct PublicKey {
@@ -49,7 +73,7 @@ impl PublicKey {
     /// `KeyError::InvalidPublicKey` will be returned if the OID is invalid
     /// or the key is invalid
     pub fn new(oid: &str, key: &[u8]) -> Result<Self> {
-        if !is_valid_kem_or_dsa_oid(&oid.to_string()) {
+        if !is_valid_kem_or_dsa_oid(&oid.to_string()) && !is_verify_only_dsa_oid(oid) {
             return Err(errors::QuantCryptError::InvalidPublicKey);
         }
         let is_composite = is_composite_kem_or_dsa_oid(oid);
@@ -139,6 +163,41 @@ impl PublicKey {
         Ok(pk_bs)
     }
 
+    /// Compute the key identifier of the public key
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The key identifier method
+    ///
+    /// # Returns
+    ///
+    /// The key identifier
+    pub fn get_key_id(&self, method: KeyIdMethod) -> Result<Vec<u8>> {
+        Ok(method.compute(&self.key, &self.to_der()?))
+    }
+
+    /// Compute the RFC 7638 JWK thumbprint of the public key
+    ///
+    /// The key is represented as an `AKP` JWK. See `KeyIdMethod` for the binary key identifiers.
+    ///
+    /// # Returns
+    ///
+    /// The base64url encoded SHA-256 thumbprint, suitable for use as a `kid`
+    pub fn get_jwk_thumbprint(&self) -> String {
+        jwk_thumbprint(&get_jose_alg(&self.oid), &self.key)
+    }
+
+    /// Compute the SPKI pin of the public key
+    ///
+    /// The pin is the base64 encoded SHA-256 of the DER encoded SubjectPublicKeyInfo (RFC 7469).
+    ///
+    /// # Returns
+    ///
+    /// The pin
+    pub fn get_spki_pin(&self) -> Result<String> {
+        Ok(STANDARD.encode(self.get_key_id(KeyIdMethod::SpkiSha256)?))
+    }
+
     /// Convert the public key to a DER-encoded byte array. The raw public key is wrapped in a
     /// SubjectPublicKeyInfo structure.
     ///
@@ -183,7 +242,10 @@ impl PublicKey {
     /// # Errors
     ///
     /// `KeyError::InvalidPublicKey` will be returned if the public key is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input is larger than the parse budget
     pub fn from_pem(pem: &str) -> Result<Self> {
+        check_input_len(pem.len())?;
         let pem = pem::parse(pem).map_err(|_| errors::QuantCryptError::InvalidPublicKey)?;
         // Header should be "PUBLIC KEY"
         if pem.tag() != "PUBLIC KEY" {
@@ -207,7 +269,11 @@ impl PublicKey {
     /// # Errors
     ///
     /// `KeyError::InvalidPublicKey` will be returned if the public key is invalid
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the input or its decoding
+    /// doesn't fit in the parse budget
     pub fn from_der(der: &[u8]) -> Result<Self> {
+        check_der_input(der)?;
         let pub_key_info =
             PublicKeyInfo::from_der(der).map_err(|_| errors::QuantCryptError::InvalidPublicKey)?;
         let pk_bytes = if let Some(pk_bytes) = pub_key_info.public_key.as_bytes() {
@@ -219,7 +285,7 @@ impl PublicKey {
         let oid = pub_key_info.algorithm.oid.to_string();
 
         // Check if oid is valid
-        if !is_valid_kem_or_dsa_oid(&oid) {
+        if !is_valid_kem_or_dsa_oid(&oid) && !is_verify_only_dsa_oid(&oid) {
             return Err(errors::QuantCryptError::InvalidPublicKey);
         }
 
@@ -248,17 +314,17 @@ impl PublicKey {
     /// `QuantCryptError::UnsupportedOperation` will be returned if the OID is not a DSA key
     pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<bool> {
         // Check if this is a DSA key
-        if !is_dsa_oid(&self.oid) {
+        if !is_dsa_oid(&self.oid) && !is_verify_only_dsa_oid(&self.oid) {
             return Err(errors::QuantCryptError::UnsupportedOperation);
         }
 
         let result = if let Some(dsa_type) = PrehashDsaType::from_oid(&self.oid) {
-            let dsa_manager = PrehashDsaManager::new(dsa_type)?;
+            let dsa_manager = get_prehash_dsa_manager(dsa_type)?;
             dsa_manager
                 .verify(self.get_key(), message, signature)
                 .unwrap_or(false)
         } else {
-            let dsa_manager = DsaManager::new_from_oid(&self.oid)?;
+            let dsa_manager = get_dsa_manager_from_oid(&self.oid)?;
             dsa_manager
                 .verify(self.get_key(), message, signature)
                 .unwrap_or(false)
@@ -267,23 +333,159 @@ impl PublicKey {
         Ok(result)
     }
 
+    /// Verify a signature, reporting the checks performed
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to verify
+    /// * `signature` - The signature
+    ///
+    /// # Returns
+    ///
+    /// A report with the outcome and the checks performed
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` will be returned if the OID is not a DSA key
+    pub fn verify_report(&self, message: &[u8], signature: &[u8]) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        let valid = self.verify(message, signature)?;
+        report.valid = report.check(&format!("signature ({})", self.oid), valid);
+        Ok(report)
+    }
+
+    /// Convert the public key to a compact CBOR encoding
+    ///
+    /// ML-DSA keys are encoded as a COSE_Key of type `AKP` (kty 7, alg -48, -49
+    /// or -50). Other keys have no COSE representation and are encoded as
+    /// `[111(oid), key]`, with the algorithm as an RFC 9090 tagged OID.
+    ///
+    /// # Returns
+    ///
+    /// The CBOR encoding
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        match get_cose_alg(&self.oid) {
+            Some(alg) => Ok(Value::Map(vec![
+                (Value::Int(COSE_KEY_KTY), Value::Int(COSE_KTY_AKP)),
+                (Value::Int(COSE_KEY_ALG), Value::Int(alg)),
+                (Value::Int(COSE_KEY_PUB), Value::Bytes(self.key.clone())),
+            ])
+            .to_vec()),
+            None => encode_with_oid(&self.oid, &self.key),
+        }
+    }
+
+    /// Create a new public key from its CBOR encoding
+    ///
+    /// # Arguments
+    ///
+    /// * `cbor` - A COSE_Key or `[111(oid), key]`, see `to_cbor`
+    ///
+    /// # Returns
+    ///
+    /// A new public key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCbor` will be returned if the encoding is malformed
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the algorithm is not supported
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self> {
+        if let Value::Map(entries) = Value::from_slice(cbor)? {
+            let get = |label: i64| {
+                entries
+                    .iter()
+                    .find(|(key, _)| *key == Value::Int(label))
+                    .map(|(_, value)| value)
+                    .ok_or(QuantCryptError::InvalidCbor)
+            };
+            if get(COSE_KEY_KTY)?.as_int()? != COSE_KTY_AKP {
+                return Err(QuantCryptError::InvalidPublicKey);
+            }
+            let oid = get_oid_from_cose_alg(get(COSE_KEY_ALG)?.as_int()?)
+                .ok_or(QuantCryptError::InvalidPublicKey)?;
+            return PublicKey::new(&oid, get(COSE_KEY_PUB)?.as_bytes()?);
+        }
+        let (oid, key) = decode_with_oid(cbor)?;
+        PublicKey::new(&oid, &key)
+    }
+
+    /// Verify a signature encoded with `PrivateKey::sign_cbor`
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to verify
+    /// * `signature` - The CBOR encoded signature
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the signature is valid and made with the algorithm of the key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCbor` will be returned if the encoding is malformed
+    pub fn verify_cbor(&self, message: &[u8], signature: &[u8]) -> Result<bool> {
+        let (oid, signature) = decode_with_oid(signature)?;
+        if oid != self.oid {
+            return Ok(false);
+        }
+        self.verify(message, &signature)
+    }
+
+    /// Verify a COSE_Sign1 message (RFC 9052) and return its payload
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The tagged COSE_Sign1 message, without external data
+    ///
+    /// # Returns
+    ///
+    /// The payload
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` will be returned if the key has no COSE algorithm
+    ///
+    /// `QuantCryptError::InvalidCbor` will be returned if the message is malformed
+    ///
+    /// `QuantCryptError::SignatureVerificationFailed` will be returned if the signature is invalid
+    /// or made with another algorithm
+    pub fn verify_cose_sign1(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let alg = get_cose_alg(&self.oid).ok_or(QuantCryptError::NotImplemented)?;
+        let Value::Tag(COSE_SIGN1_TAG, message) = Value::from_slice(message)? else {
+            return Err(QuantCryptError::InvalidCbor);
+        };
+        let [protected, Value::Map(_), payload, signature] = message.as_array()? else {
+            return Err(QuantCryptError::InvalidCbor);
+        };
+        let (protected, payload) = (protected.as_bytes()?, payload.as_bytes()?);
+        if protected != cose_protected_header(alg) {
+            return Err(QuantCryptError::SignatureVerificationFailed);
+        }
+        let to_be_signed = cose_sign1_to_be_signed(protected, payload);
+        if !self.verify(&to_be_signed, signature.as_bytes()?)? {
+            return Err(QuantCryptError::SignatureVerificationFailed);
+        }
+        Ok(payload.to_vec())
+    }
+
     /// Encapsulate to get a shared secret and a ciphertext based on this public key
     ///
     /// # Returns
     ///
-    /// A tuple containing the ciphertext and the shared secret (ct, ss)
-    pub fn encap(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+    /// A tuple containing the shared secret and the ciphertext (ss, ct)
+    pub fn encap(&self) -> Result<(SharedSecret, Ciphertext)> {
         // Check if this is a KEM key
         if !is_kem_oid(&self.oid) {
             return Err(errors::QuantCryptError::UnsupportedOperation);
         }
 
         let mut kem =
-            KemManager::new_from_oid(&self.oid).map_err(|_| errors::QuantCryptError::InvalidOid)?;
+            get_kem_manager_from_oid(&self.oid).map_err(|_| errors::QuantCryptError::InvalidOid)?;
 
-        let (ct, ss) = kem.encap(self.get_key())?;
+        let (ss, ct) = kem.encap(self.get_key())?;
 
-        Ok((ct, ss))
+        Ok((SharedSecret::new(ss), Ciphertext::from_oid(&self.oid, &ct)?))
     }
 
     /// Save the public key to a file in PEM format
@@ -332,7 +534,7 @@ impl PublicKey {
     /// A new public key
     pub fn from_file(path: &str) -> Result<Self> {
         // Read the contents of the file as bytes
-        let contents = std::fs::read(path).map_err(|_| QuantCryptError::FileReadError)?;
+        let contents = read_file_limited(path)?;
 
         // Try to interpret as DER
         let result = PublicKey::from_der(&contents);
@@ -382,7 +584,7 @@ mod test {
         );
 
         let key_bytes = pk.get_key();
-        let pk2 = CompositePublicKey::from_der(&pk.oid, &key_bytes).unwrap();
+        let pk2 = CompositePublicKey::from_der(&pk.oid, key_bytes).unwrap();
 
         assert_eq!(pk.oid, pk2.get_oid());
 
@@ -467,4 +669,49 @@ mod test {
         .unwrap();
         pk.encap().unwrap();
     }
+
+    #[test]
+    fn test_cbor_encodings() {
+        use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+        use crate::kems::{KemAlgorithm, KemKeyGenerator};
+
+        // ML-DSA keys are COSE_Keys: {1: 7, 3: -48, -1: pub}
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let cbor = pk.to_cbor().unwrap();
+        assert_eq!(cbor[..6], [0xa3, 0x01, 0x07, 0x03, 0x38, 0x2f]);
+        assert_eq!(PublicKey::from_cbor(&cbor).unwrap().get_key(), pk.get_key());
+
+        let message = sk.sign_cose_sign1(b"reading: 21.5C").unwrap();
+        assert_eq!(message[0], 0xd2);
+        assert_eq!(pk.verify_cose_sign1(&message).unwrap(), b"reading: 21.5C");
+        let mut tampered = message.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert_eq!(
+            pk.verify_cose_sign1(&tampered),
+            Err(QuantCryptError::SignatureVerificationFailed)
+        );
+
+        let signature = sk.sign_cbor(b"reading").unwrap();
+        assert!(pk.verify_cbor(b"reading", &signature).unwrap());
+        assert!(!pk.verify_cbor(b"other", &signature).unwrap());
+
+        // Other keys are tagged with their OID
+        let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768X25519)
+            .generate()
+            .unwrap();
+        let cbor = pk.to_cbor().unwrap();
+        assert_eq!(cbor[..3], [0x82, 0xd8, 0x6f]);
+        let pk = PublicKey::from_cbor(&cbor).unwrap();
+        let (ss, ct) = pk.encap().unwrap();
+        let ct = Ciphertext::from_cbor(&ct.to_cbor().unwrap()).unwrap();
+        assert_eq!(sk.decap(&ct).unwrap(), ss);
+        assert_eq!(
+            sk.sign_cose_sign1(b"reading"),
+            Err(QuantCryptError::NotImplemented)
+        );
+        assert!(PublicKey::from_cbor(&cbor[1..]).is_err());
+    }
 }
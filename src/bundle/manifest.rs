@@ -0,0 +1,493 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::asn1::private_key::PrivateKey;
+use crate::asn1::public_key::PublicKey;
+use crate::utils::resource_limits::{check_input_len, read_file_limited};
+use crate::utils::verification_report::unix_now;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The length of a SHA-256 digest in bytes
+const DIGEST_LEN: usize = 32;
+
+/// The reason a single file failed verification against a manifest
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FileFailure {
+    /// The file is listed in the manifest but is not present in the directory
+    Missing(String),
+    /// The file is present but its digest doesn't match the manifest
+    Modified(String),
+    /// The file is present in the directory but not listed in the manifest, or the
+    /// entry is a symbolic link or another entry that isn't a regular file
+    Unexpected(String),
+}
+
+/// The result of verifying a directory tree against a signed manifest
+///
+/// Like `VerificationReport`, it can be serialized with any serde format for audit logs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BundleVerificationReport {
+    /// True if the signature over the manifest is valid
+    pub signature_valid: bool,
+    /// The per-file failures found while comparing the tree to the manifest
+    pub failures: Vec<FileFailure>,
+    /// When the verification took place, in seconds since the Unix epoch
+    pub verified_at: u64,
+}
+
+impl BundleVerificationReport {
+    /// Check if the bundle verified successfully
+    ///
+    /// # Returns
+    ///
+    /// True if the signature is valid and no file failed verification
+    pub fn is_valid(&self) -> bool {
+        self.signature_valid && self.failures.is_empty()
+    }
+}
+
+/// A manifest of the SHA-256 digests of every regular file in a directory tree.
+///
+/// The manifest is serialized as UTF-8 text with one `<hex digest>  <path>` line
+/// per file, sorted by path. Paths are relative to the root of the tree and use
+/// `/` as the separator regardless of platform, so a manifest produced on one
+/// system verifies on another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirectoryManifest {
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl DirectoryManifest {
+    /// Create a manifest by hashing every regular file under a directory
+    ///
+    /// Symbolic links are not followed, so a link cycle can't make the walk recurse
+    /// forever and a link can't pull in files from outside the tree. A tree holding
+    /// a link, or another entry that isn't a regular file or a directory, is refused
+    /// rather than signed with the entry silently left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The root of the directory tree
+    ///
+    /// # Returns
+    ///
+    /// The manifest of the directory tree
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedDirectoryEntry` will be returned if the tree
+    /// contains a symbolic link or another entry that isn't a regular file
+    pub fn from_directory(path: &str) -> Result<Self> {
+        let (manifest, others) = Self::walk_directory(path)?;
+        if !others.is_empty() {
+            return Err(QuantCryptError::UnsupportedDirectoryEntry);
+        }
+        Ok(manifest)
+    }
+
+    /// Hash the regular files under a directory
+    ///
+    /// # Returns
+    ///
+    /// The manifest of the regular files and the paths of the other entries,
+    /// such as symbolic links, which are not followed
+    fn walk_directory(path: &str) -> Result<(Self, Vec<String>)> {
+        let root = Path::new(path);
+        if !root.is_dir() {
+            return Err(QuantCryptError::InvalidDirectoryPath);
+        }
+        let mut entries = BTreeMap::new();
+        let mut others = Vec::new();
+        Self::hash_tree(root, "", &mut entries, &mut others)?;
+        Ok((Self { entries }, others))
+    }
+
+    fn hash_tree(
+        dir: &Path,
+        prefix: &str,
+        entries: &mut BTreeMap<String, Vec<u8>>,
+        others: &mut Vec<String>,
+    ) -> Result<()> {
+        for entry in fs::read_dir(dir).map_err(|_| QuantCryptError::InvalidDirectoryPath)? {
+            let entry = entry.map_err(|_| QuantCryptError::InvalidDirectoryPath)?;
+            let name = entry
+                .file_name()
+                .into_string()
+                .map_err(|_| QuantCryptError::InvalidManifest)?;
+            let rel_path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+
+            // Newlines would break the line-based manifest format
+            if rel_path.contains('\n') || rel_path.contains('\r') {
+                return Err(QuantCryptError::InvalidManifest);
+            }
+
+            let path = entry.path();
+            let file_type = fs::symlink_metadata(&path)
+                .map_err(|_| QuantCryptError::InvalidDirectoryPath)?
+                .file_type();
+            if file_type.is_dir() {
+                Self::hash_tree(&path, &rel_path, entries, others)?;
+            } else if file_type.is_file() {
+                let data = fs::read(&path).map_err(|_| QuantCryptError::FileReadError)?;
+                entries.insert(rel_path, Sha256::digest(&data).to_vec());
+            } else {
+                others.push(rel_path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a manifest from its serialized form
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized manifest
+    ///
+    /// # Returns
+    ///
+    /// The parsed manifest
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_input_len(bytes.len())?;
+        let text = std::str::from_utf8(bytes).map_err(|_| QuantCryptError::InvalidManifest)?;
+        let mut entries = BTreeMap::new();
+        for line in text.lines() {
+            let (digest, path) = line
+                .split_once("  ")
+                .ok_or(QuantCryptError::InvalidManifest)?;
+            let digest = hex::decode(digest).map_err(|_| QuantCryptError::InvalidManifest)?;
+            if digest.len() != DIGEST_LEN || path.is_empty() {
+                return Err(QuantCryptError::InvalidManifest);
+            }
+            if entries.insert(path.to_string(), digest).is_some() {
+                return Err(QuantCryptError::InvalidManifest);
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Serialize the manifest
+    ///
+    /// # Returns
+    ///
+    /// The serialized manifest. These are the bytes that get signed.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = String::new();
+        for (path, digest) in &self.entries {
+            out.push_str(&hex::encode(digest));
+            out.push_str("  ");
+            out.push_str(path);
+            out.push('\n');
+        }
+        out.into_bytes()
+    }
+
+    /// Get the relative paths of the files listed in the manifest
+    ///
+    /// # Returns
+    ///
+    /// The paths, sorted
+    pub fn get_paths(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+
+    /// Get the digest recorded for a file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The relative path of the file
+    ///
+    /// # Returns
+    ///
+    /// The SHA-256 digest of the file or None if the file is not in the manifest
+    pub fn get_digest(&self, path: &str) -> Option<&[u8]> {
+        self.entries.get(path).map(|d| d.as_slice())
+    }
+
+    /// Compare a directory tree against the manifest
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The root of the directory tree
+    ///
+    /// # Returns
+    ///
+    /// The list of files that failed verification, in path order. Symbolic links
+    /// and other entries that aren't regular files are not followed and are
+    /// reported as unexpected, after the files.
+    pub fn compare_directory(&self, path: &str) -> Result<Vec<FileFailure>> {
+        let (actual, mut others) = DirectoryManifest::walk_directory(path)?;
+        let mut failures = Vec::new();
+
+        for (path, digest) in &self.entries {
+            match actual.entries.get(path) {
+                None => failures.push(FileFailure::Missing(path.clone())),
+                Some(d) if d != digest => failures.push(FileFailure::Modified(path.clone())),
+                _ => {}
+            }
+        }
+
+        for path in actual.entries.keys() {
+            if !self.entries.contains_key(path) {
+                failures.push(FileFailure::Unexpected(path.clone()));
+            }
+        }
+
+        others.sort();
+        failures.extend(others.into_iter().map(FileFailure::Unexpected));
+
+        Ok(failures)
+    }
+}
+
+/// A detached signature bundle for a directory tree.
+///
+/// The bundle consists of a `DirectoryManifest` and a signature over its
+/// serialized form. Any DSA key can be used, but ML-DSA and SLH-DSA keys are
+/// the intended use.
+///
+/// # Example
+/// ```
+/// use quantcrypt::bundles::SignedBundle;
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+///
+/// let bundle = SignedBundle::sign_directory("test/data/cms", &sk).unwrap();
+/// let report = bundle.verify_directory("test/data/cms", &pk).unwrap();
+/// assert!(report.is_valid());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignedBundle {
+    manifest: DirectoryManifest,
+    signature: Vec<u8>,
+}
+
+impl SignedBundle {
+    /// Hash a directory tree into a manifest and sign it
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The root of the directory tree
+    /// * `sk` - The DSA private key to sign the manifest with
+    ///
+    /// # Returns
+    ///
+    /// The signed bundle
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedDirectoryEntry` will be returned if the tree
+    /// contains a symbolic link or another entry that isn't a regular file
+    pub fn sign_directory(path: &str, sk: &PrivateKey) -> Result<Self> {
+        let manifest = DirectoryManifest::from_directory(path)?;
+        let signature = sk.sign(&manifest.to_bytes())?;
+        Ok(Self {
+            manifest,
+            signature,
+        })
+    }
+
+    /// Create a bundle from a serialized manifest and its signature
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest` - The serialized manifest
+    /// * `signature` - The signature over the manifest
+    ///
+    /// # Returns
+    ///
+    /// The signed bundle
+    pub fn from_bytes(manifest: &[u8], signature: &[u8]) -> Result<Self> {
+        let manifest = DirectoryManifest::from_bytes(manifest)?;
+        Ok(Self {
+            manifest,
+            signature: signature.to_vec(),
+        })
+    }
+
+    /// Get the manifest
+    pub fn get_manifest(&self) -> &DirectoryManifest {
+        &self.manifest
+    }
+
+    /// Get the signature over the serialized manifest
+    pub fn get_signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Verify a directory tree against the bundle
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The root of the directory tree
+    /// * `pk` - The DSA public key of the signer
+    ///
+    /// # Returns
+    ///
+    /// A report containing the signature status and the per-file failures
+    pub fn verify_directory(&self, path: &str, pk: &PublicKey) -> Result<BundleVerificationReport> {
+        let signature_valid = pk
+            .verify(&self.manifest.to_bytes(), &self.signature)
+            .unwrap_or(false);
+        let failures = self.manifest.compare_directory(path)?;
+        Ok(BundleVerificationReport {
+            signature_valid,
+            failures,
+            verified_at: unix_now(),
+        })
+    }
+
+    /// Write the manifest and the signature to files
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest_path` - The path to write the manifest to
+    /// * `signature_path` - The path to write the signature to
+    pub fn to_files(&self, manifest_path: &str, signature_path: &str) -> Result<()> {
+        fs::write(manifest_path, self.manifest.to_bytes())
+            .map_err(|_| QuantCryptError::FileWriteError)?;
+        fs::write(signature_path, &self.signature).map_err(|_| QuantCryptError::FileWriteError)
+    }
+
+    /// Read a bundle from a manifest file and a signature file
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest_path` - The path of the manifest
+    /// * `signature_path` - The path of the signature
+    ///
+    /// # Returns
+    ///
+    /// The signed bundle
+    pub fn from_files(manifest_path: &str, signature_path: &str) -> Result<Self> {
+        let manifest = read_file_limited(manifest_path)?;
+        let signature = read_file_limited(signature_path)?;
+        Self::from_bytes(&manifest, &signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+
+    fn make_tree(name: &str) -> String {
+        let root = std::env::temp_dir().join(format!("quantcrypt_bundle_{}", name));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        fs::write(root.join("a.txt"), b"alpha").unwrap();
+        fs::write(root.join("sub/b.txt"), b"beta").unwrap();
+        fs::write(root.join("sub/deeper/c.bin"), [0u8, 1, 2, 3]).unwrap();
+        root.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let root = make_tree("roundtrip");
+        let manifest = DirectoryManifest::from_directory(&root).unwrap();
+        assert_eq!(
+            manifest.get_paths(),
+            vec!["a.txt", "sub/b.txt", "sub/deeper/c.bin"]
+        );
+        assert_eq!(
+            manifest.get_digest("a.txt").unwrap(),
+            Sha256::digest(b"alpha").as_slice()
+        );
+
+        let parsed = DirectoryManifest::from_bytes(&manifest.to_bytes()).unwrap();
+        assert_eq!(parsed, manifest);
+
+        assert!(DirectoryManifest::from_bytes(b"zz  a.txt\n").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_manifest_rejects_symlinks() {
+        let root = make_tree("symlinks");
+        let manifest = DirectoryManifest::from_directory(&root).unwrap();
+
+        // A cycle back to the root and a link to a file
+        std::os::unix::fs::symlink(&root, Path::new(&root).join("sub/loop")).unwrap();
+        std::os::unix::fs::symlink(
+            Path::new(&root).join("a.txt"),
+            Path::new(&root).join("link.txt"),
+        )
+        .unwrap();
+
+        // The links are not followed, a manifest isn't created with them left out
+        assert_eq!(
+            DirectoryManifest::from_directory(&root),
+            Err(QuantCryptError::UnsupportedDirectoryEntry)
+        );
+        assert_eq!(
+            manifest.compare_directory(&root).unwrap(),
+            vec![
+                FileFailure::Unexpected("link.txt".to_string()),
+                FileFailure::Unexpected("sub/loop".to_string()),
+            ]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_signed_bundle() {
+        let root = make_tree("signed");
+        let algs = [DsaAlgorithm::MlDsa44, DsaAlgorithm::SlhDsaSha2_128f];
+        for alg in algs {
+            let (pk, sk) = DsaKeyGenerator::new(alg).generate().unwrap();
+            let bundle = SignedBundle::sign_directory(&root, &sk).unwrap();
+
+            let report = bundle.verify_directory(&root, &pk).unwrap();
+            assert!(report.is_valid());
+
+            let parsed =
+                SignedBundle::from_bytes(&bundle.get_manifest().to_bytes(), bundle.get_signature())
+                    .unwrap();
+            assert!(parsed.verify_directory(&root, &pk).unwrap().is_valid());
+
+            // A different key must not verify
+            let (pk2, _) = DsaKeyGenerator::new(alg).generate().unwrap();
+            let report = bundle.verify_directory(&root, &pk2).unwrap();
+            assert!(!report.signature_valid);
+            assert!(report.failures.is_empty());
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_bundle_file_failures() {
+        let root = make_tree("failures");
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let bundle = SignedBundle::sign_directory(&root, &sk).unwrap();
+
+        let root_path = Path::new(&root);
+        fs::write(root_path.join("a.txt"), b"tampered").unwrap();
+        fs::remove_file(root_path.join("sub/b.txt")).unwrap();
+        fs::write(root_path.join("sub/extra.txt"), b"extra").unwrap();
+
+        let report = bundle.verify_directory(&root, &pk).unwrap();
+        assert!(report.signature_valid);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.failures,
+            vec![
+                FileFailure::Modified("a.txt".to_string()),
+                FileFailure::Missing("sub/b.txt".to_string()),
+                FileFailure::Unexpected("sub/extra.txt".to_string()),
+            ]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
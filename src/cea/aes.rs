@@ -3,6 +3,7 @@ use crate::cea::common::cea_info::CeaInfo;
 use crate::cea::common::cea_trait::Cea;
 use crate::cea::common::cea_type::CeaType;
 use crate::cea::common::config::oids::Oid;
+use crate::utils::entropy::get_key_gen_rng;
 use crate::QuantCryptError;
 use cms::enveloped_data::EncryptedContentInfo;
 use const_oid::db::rfc5911::ID_DATA;
@@ -10,41 +11,130 @@ use der::asn1::{ObjectIdentifier, OctetString};
 use der::Decode;
 use der::Encode;
 use pkcs8::spki::AlgorithmIdentifierOwned;
+use rand_core::RngCore;
 
+#[cfg(feature = "openssl")]
 use openssl::symm::{decrypt, decrypt_aead, encrypt, encrypt_aead, Cipher};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// Get the OpenSSL cipher for the CEA type
+#[cfg(feature = "openssl")]
+fn get_cipher(cea_type: &CeaType) -> Cipher {
+    match cea_type {
+        CeaType::Aes128Gcm => Cipher::aes_128_gcm(),
+        CeaType::Aes192Gcm => Cipher::aes_192_gcm(),
+        CeaType::Aes256Gcm => Cipher::aes_256_gcm(),
+        CeaType::Aes128CbcPad => Cipher::aes_128_cbc(),
+        CeaType::Aes192CbcPad => Cipher::aes_192_cbc(),
+        CeaType::Aes256CbcPad => Cipher::aes_256_cbc(),
+    }
+}
+
+/// Encrypt with OpenSSL
+///
+/// # Returns
+///
+/// A tuple containing the ciphertext and the GCM tag, which is empty for CBC
+#[cfg(feature = "openssl")]
+fn aes_encrypt(
+    cea_type: &CeaType,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let cipher = get_cipher(cea_type);
+    let mut tag = [0u8; 16];
+    match cea_type {
+        CeaType::Aes128Gcm | CeaType::Aes192Gcm | CeaType::Aes256Gcm => Ok((
+            encrypt_aead(cipher, key, Some(nonce), aad, plaintext, &mut tag)
+                .map_err(|_| QuantCryptError::Unknown)?,
+            tag.to_vec(),
+        )),
+        CeaType::Aes128CbcPad | CeaType::Aes192CbcPad | CeaType::Aes256CbcPad => Ok((
+            encrypt(cipher, key, Some(nonce), plaintext).map_err(|_| QuantCryptError::Unknown)?,
+            Vec::new(),
+        )),
+    }
+}
+
+/// Decrypt with OpenSSL
+///
+/// # Returns
+///
+/// The plaintext
+#[cfg(feature = "openssl")]
+fn aes_decrypt(
+    cea_type: &CeaType,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ct: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>> {
+    let cipher = get_cipher(cea_type);
+    match cea_type {
+        CeaType::Aes128Gcm | CeaType::Aes192Gcm | CeaType::Aes256Gcm => {
+            decrypt_aead(cipher, key, Some(nonce), aad, ct, tag)
+        }
+        CeaType::Aes128CbcPad | CeaType::Aes192CbcPad | CeaType::Aes256CbcPad => {
+            decrypt(cipher, key, Some(nonce), ct)
+        }
+    }
+    .map_err(|_| QuantCryptError::InvalidCiphertext)
+}
+
+/// AES-GCM and AES-CBC are computed with OpenSSL
+#[cfg(not(feature = "openssl"))]
+fn aes_encrypt(
+    cea_type: &CeaType,
+    _key: &[u8],
+    _nonce: &[u8],
+    _aad: &[u8],
+    _plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    Err(QuantCryptError::BackendUnavailable {
+        component: format!("{:?}", cea_type),
+    })
+}
+
+/// AES-GCM and AES-CBC are computed with OpenSSL
+#[cfg(not(feature = "openssl"))]
+fn aes_decrypt(
+    cea_type: &CeaType,
+    _key: &[u8],
+    _nonce: &[u8],
+    _aad: &[u8],
+    _ct: &[u8],
+    _tag: &[u8],
+) -> Result<Vec<u8>> {
+    Err(QuantCryptError::BackendUnavailable {
+        component: format!("{:?}", cea_type),
+    })
+}
+
 #[derive(Clone)]
 pub struct Aes {
     cea_type: CeaType,
 }
 
 impl Aes {
-    /// Get the OpenSSL cipher for the CEA type
-    fn get_cipher(&self) -> Cipher {
-        match self.cea_type {
-            CeaType::Aes128Gcm => Cipher::aes_128_gcm(),
-            CeaType::Aes192Gcm => Cipher::aes_192_gcm(),
-            CeaType::Aes256Gcm => Cipher::aes_256_gcm(),
-            CeaType::Aes128CbcPad => Cipher::aes_128_cbc(),
-            CeaType::Aes192CbcPad => Cipher::aes_192_cbc(),
-            CeaType::Aes256CbcPad => Cipher::aes_256_cbc(),
-        }
-    }
-
     /// Get the nonce for the CEA type, or the provided nonce if it is valid
     ///
     /// # Arguments
     ///
-    /// * `cipher` - The OpenSSL cipher to use
     /// * `nonce` - The nonce to use, or None to generate a random nonce
     ///
     /// # Returns
     ///
     /// The nonce to use
-    fn get_nonce(&self, cipher: &Cipher, nonce: Option<&[u8]>) -> Result<Vec<u8>> {
-        let nonce_len = cipher.iv_len().unwrap_or(0);
+    fn get_nonce(&self, nonce: Option<&[u8]>) -> Result<Vec<u8>> {
+        // The 12 byte GCM nonce and the 16 byte CBC IV
+        let nonce_len = match self.cea_type {
+            CeaType::Aes128Gcm | CeaType::Aes192Gcm | CeaType::Aes256Gcm => 12,
+            CeaType::Aes128CbcPad | CeaType::Aes192CbcPad | CeaType::Aes256CbcPad => 16,
+        };
         let nonce = if let Some(nonce) = nonce {
             if nonce.len() != nonce_len {
                 return Err(QuantCryptError::Unknown);
@@ -52,7 +142,7 @@ impl Aes {
             nonce.to_vec()
         } else {
             let mut nonce = vec![0u8; nonce_len];
-            openssl::rand::rand_bytes(&mut nonce).map_err(|_| QuantCryptError::Unknown)?;
+            get_key_gen_rng()?.fill_bytes(&mut nonce);
             nonce
         };
         Ok(nonce)
@@ -177,6 +267,11 @@ impl Cea for Aes {
     where
         Self: Sized,
     {
+        if !cfg!(feature = "openssl") {
+            return Err(QuantCryptError::BackendUnavailable {
+                component: format!("{:?}", cea_type),
+            });
+        }
         Ok(Aes { cea_type })
     }
 
@@ -192,71 +287,36 @@ impl Cea for Aes {
         aad: Option<&[u8]>,
         content_type_oid: Option<&str>,
     ) -> Result<(Vec<u8>, Vec<u8>)> {
-        let cipher = self.get_cipher();
-        let nonce = self.get_nonce(&cipher, nonce)?;
-
-        let mut tag = [0u8; 16];
+        let nonce = self.get_nonce(nonce)?;
         let aad = aad.unwrap_or(&[]);
 
-        let (ct, tag) = match self.cea_type {
-            CeaType::Aes128Gcm | CeaType::Aes192Gcm | CeaType::Aes256Gcm => (
-                encrypt_aead(cipher, key, Some(&nonce), aad, plaintext, &mut tag)
-                    .map_err(|_| QuantCryptError::Unknown)?,
-                tag.to_vec(),
-            ),
-            CeaType::Aes128CbcPad | CeaType::Aes192CbcPad | CeaType::Aes256CbcPad => (
-                encrypt(cipher, key, Some(&nonce), plaintext)
-                    .map_err(|_| QuantCryptError::Unknown)?,
-                nonce.clone(),
-            ),
+        let (ct, tag) = aes_encrypt(&self.cea_type, key, &nonce, aad, plaintext)?;
+        // The CBC IV is returned in place of a tag
+        let tag = match self.cea_type {
+            CeaType::Aes128Gcm | CeaType::Aes192Gcm | CeaType::Aes256Gcm => tag,
+            CeaType::Aes128CbcPad | CeaType::Aes192CbcPad | CeaType::Aes256CbcPad => nonce.clone(),
         };
-        Ok((
-            tag.clone(),
-            self.to_content_info(&ct, &nonce, content_type_oid)?,
-        ))
+        Ok((tag, self.to_content_info(&ct, &nonce, content_type_oid)?))
     }
 
     fn decrypt(key: &[u8], tag: &[u8], ciphertext: &[u8], aad: Option<&[u8]>) -> Result<Vec<u8>> {
         let (cea_type, nonce, ct) = Aes::from_content_info(tag, ciphertext)?;
-        let cipher = match cea_type {
-            CeaType::Aes128Gcm => Cipher::aes_128_gcm(),
-            CeaType::Aes192Gcm => Cipher::aes_192_gcm(),
-            CeaType::Aes256Gcm => Cipher::aes_256_gcm(),
-            CeaType::Aes128CbcPad => Cipher::aes_128_cbc(),
-            CeaType::Aes192CbcPad => Cipher::aes_192_cbc(),
-            CeaType::Aes256CbcPad => Cipher::aes_256_cbc(),
-        };
-
         let aad = aad.unwrap_or(&[]);
-
-        let result = match cea_type {
-            CeaType::Aes128Gcm | CeaType::Aes192Gcm | CeaType::Aes256Gcm => {
-                decrypt_aead(cipher, key, Some(&nonce), aad, &ct, tag)
-            }
-            CeaType::Aes128CbcPad | CeaType::Aes192CbcPad | CeaType::Aes256CbcPad => {
-                decrypt(cipher, key, Some(&nonce), &ct)
-            }
-        }
-        .map_err(|_| QuantCryptError::InvalidCiphertext)?;
-        Ok(result)
+        aes_decrypt(&cea_type, key, &nonce, aad, &ct, tag)
     }
 
     fn key_gen(&mut self) -> Result<Vec<u8>> {
-        let cipher = self.get_cipher();
-        let key_len = cipher.key_len();
-        let mut key = vec![0u8; key_len];
-        openssl::rand::rand_bytes(&mut key).map_err(|_| QuantCryptError::Unknown)?;
+        let mut key = vec![0u8; self.get_cea_info().key_length];
+        get_key_gen_rng()?.fill_bytes(&mut key);
         Ok(key)
     }
 
     fn nonce_gen(&mut self) -> Result<Vec<u8>> {
-        let cipher = self.get_cipher();
-        let nonce = self.get_nonce(&cipher, None)?;
-        Ok(nonce)
+        self.get_nonce(None)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
     use super::*;
     use crate::cea::common::macros::test_cea;
@@ -74,7 +74,7 @@ impl Cea for CeaManager {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
     use super::*;
     use crate::cea::common::macros::test_cea;
@@ -11,6 +11,8 @@ pub struct CeaInfo {
     pub oid: String,
     /// Does it support AAD
     pub is_aad_supported: bool,
+    /// The length of the key in bytes
+    pub key_length: usize,
 }
 
 impl CeaInfo {
@@ -30,10 +32,17 @@ impl CeaInfo {
             CeaType::Aes128Gcm | CeaType::Aes192Gcm | CeaType::Aes256Gcm
         );
 
+        let key_length = match cea_type {
+            CeaType::Aes128Gcm | CeaType::Aes128CbcPad => 16,
+            CeaType::Aes192Gcm | CeaType::Aes192CbcPad => 24,
+            CeaType::Aes256Gcm | CeaType::Aes256CbcPad => 32,
+        };
+
         CeaInfo {
             cea_type,
             oid,
             is_aad_supported,
+            key_length,
         }
     }
 }
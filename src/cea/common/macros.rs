@@ -1,9 +1,10 @@
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 macro_rules! test_cea {
     ($cea:expr) => {{
         let is_aad_supported = $cea.get_cea_info().is_aad_supported;
         use crate::cea::cea_manager::CeaManager;
         let key = $cea.key_gen().unwrap();
+        assert_eq!(key.len(), $cea.get_cea_info().key_length);
         let plaintext = b"Hello, world!";
         let (tag, ciphertext) = $cea.encrypt(&key, None, plaintext, None, None).unwrap();
         let decrypted = CeaManager::decrypt(&key, &tag, &ciphertext, None).unwrap();
@@ -41,5 +42,5 @@ macro_rules! test_cea {
     }};
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 pub(crate) use test_cea;
@@ -1,9 +1,22 @@
 pub use crate::cms::asn1::auth_enveloped_data_builder::ContentEncryptionAlgorithmAead;
 pub use crate::cms::asn1::auth_enveloped_data_content::AuthEnvelopedDataContent;
+pub use crate::cms::asn1::certs_only_content::CertsOnlyContent;
+pub use crate::cms::asn1::compressed_data_content::CompressedDataContent;
 pub use crate::cms::asn1::enveloped_data_content::ContentEncryptionAlgorithm;
 pub use crate::cms::asn1::enveloped_data_content::EnvelopedDataContent;
+pub use crate::cms::asn1::signed_data_content::SignedAttributePolicy;
+pub use crate::cms::asn1::signed_data_content::SignedDataContent;
 pub use crate::cms::cert_store_trait::CertificateStore;
+pub use crate::cms::content_type_registry::ContentTypeRegistry;
 pub use crate::cms::directory_cert_store::DirectoryCertificateStore;
+pub use crate::cms::identity::Identity;
+pub use crate::cms::policy_verifier::{verify_cms_with_policy, CmsVerificationPolicy};
+pub use crate::cms::recipient_rewrapper::RecipientRewrapper;
+pub use crate::cms::signed_data_builder::CommitmentType;
+pub use crate::cms::signed_data_builder::SignedDataBuilder;
+pub use crate::cms::signed_data_builder::SignerAttributes;
+pub use crate::cms::stream_parser::StreamedEnvelopedData;
+pub use crate::cms::stream_parser::StreamedSignedData;
 pub use crate::kdf::api::KdfType;
 pub use crate::wrap::api::WrapType;
 pub use cms::content_info::CmsVersion;
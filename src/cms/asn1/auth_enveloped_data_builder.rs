@@ -9,6 +9,7 @@ use cms::enveloped_data::{EncryptedContentInfo, RecipientInfo, RecipientInfos};
 use cms::{builder::RecipientInfoBuilder, enveloped_data::OriginatorInfo};
 use const_oid::db::rfc5911::{ID_AES_128_GCM, ID_AES_192_GCM, ID_AES_256_GCM};
 use der::{Decode, Encode};
+use rand_core::CryptoRngCore;
 use spki::ObjectIdentifier;
 use x509_cert::attr::Attributes;
 use zeroize::Zeroize;
@@ -21,6 +22,9 @@ use crate::cms::asn1::auth_env_data::AuthEnvelopedData;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// The length of the GCM nonce, the 96 bits recommended by NIST SP 800-38D
+const GCM_NONCE_LEN: usize = 12;
+
 /// Content encryption algorithm for AuthEnvelopedData
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ContentEncryptionAlgorithmAead {
@@ -96,7 +100,9 @@ impl<'c> AuthEnvelopedDataBuilder<'c> {
 
     /// Generate an `AuthEnvelopedData` object according to RFC 5083 § 2.2 using a provided
     /// random number generator.
-    pub fn build(&mut self) -> Result<AuthEnvelopedData> {
+    ///
+    /// The content encryption key and the nonce are drawn from `rng`.
+    pub fn build_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<AuthEnvelopedData> {
         // DER encode authenticated attributes, if any
         // Generate content encryption key
         // Encrypt content and capture authentication tag
@@ -115,10 +121,12 @@ impl<'c> AuthEnvelopedDataBuilder<'c> {
         };
 
         // Create an instance of CEA
-        let mut cea = CeaManager::new(cea_type)?;
+        let cea = CeaManager::new(cea_type)?;
         // Generate a symmetric key
-        let mut cek = cea.key_gen()?;
-        let nonce = cea.nonce_gen()?;
+        let mut cek = vec![0u8; cea.get_cea_info().key_length];
+        rng.fill_bytes(&mut cek);
+        let mut nonce = [0u8; GCM_NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
 
         // Convert content id to string
         let content_id = self.content_id.map(|oid| oid.to_string());
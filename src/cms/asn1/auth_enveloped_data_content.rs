@@ -4,14 +4,21 @@ use cms::{
     enveloped_data::{OriginatorInfo, RecipientInfos},
 };
 use der::{Decode, Encode};
+use spki::ObjectIdentifier;
 use x509_cert::attr::Attributes;
 
+use crate::utils::resource_limits::{charge_der_input, read_file_limited, AllocationAccount};
 use crate::{certificates::Certificate, keys::PrivateKey, QuantCryptError};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+use crate::cms::asn1::compressed_data::ID_CT_COMPRESSED_DATA;
+use crate::cms::asn1::compressed_data_content::CompressedDataContent;
+use crate::cms::asn1::signed_data_content::SignedDataContent;
 use crate::cms::cms_util::CmsUtil;
+use crate::utils::verification_report::VerificationReport;
 use const_oid::db::rfc5911::ID_CT_AUTH_ENVELOPED_DATA;
+use const_oid::db::rfc5911::ID_SIGNED_DATA;
 
 use crate::cms::asn1::auth_enveloped_data_builder::ContentEncryptionAlgorithmAead;
 use crate::cms::enveloped_data_builder::EnvelopedDataBuilder;
@@ -24,6 +31,8 @@ use super::auth_env_data::AuthEnvelopedData;
 ///
 /// # Example
 /// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
 /// use quantcrypt::content::AuthEnvelopedDataContent;
 /// use quantcrypt::content::ContentEncryptionAlgorithmAead;
 /// use quantcrypt::certificates::Certificate;
@@ -90,6 +99,7 @@ use super::auth_env_data::AuthEnvelopedData;
 /// )
 /// .unwrap();
 /// assert_eq!(edc.get_content(), data);
+/// # }
 /// ```
 
 pub struct AuthEnvelopedDataContent {
@@ -99,12 +109,18 @@ pub struct AuthEnvelopedDataContent {
     originator_info: Option<OriginatorInfo>,
     /// The RecipientInfos
     recip_infos: RecipientInfos,
+    /// The content type of the content
+    content_type: ObjectIdentifier,
     /// The content
     content: Vec<u8>,
     /// The unprotected attributes
     unprotected_attrs: Option<Attributes>,
     /// The authenticated attributes
     auth_attrs: Option<Attributes>,
+    /// The certificate of the sender, for signed-then-encrypted content
+    sender_cert: Option<Certificate>,
+    /// The verification report of the signature of the sender
+    sender_report: Option<VerificationReport>,
 }
 
 impl AuthEnvelopedDataContent {
@@ -125,7 +141,7 @@ impl AuthEnvelopedDataContent {
         recipient_cert: &Certificate,
         recipient_private_key: &PrivateKey,
     ) -> Result<AuthEnvelopedDataContent> {
-        let data = std::fs::read(file).map_err(|_| QuantCryptError::FileReadError)?;
+        let data = read_file_limited(file)?;
         AuthEnvelopedDataContent::from_bytes_for_kem_recipient(
             &data,
             recipient_cert,
@@ -150,12 +166,78 @@ impl AuthEnvelopedDataContent {
         recipient_cert: &Certificate,
         recipient_private_key: &PrivateKey,
     ) -> Result<AuthEnvelopedDataContent> {
+        AuthEnvelopedDataContent::from_bytes_with(data, |data| {
+            CmsUtil::decrypt_kemri(data, recipient_private_key, recipient_cert)
+        })
+    }
+
+    /// Create a new AuthEnvelopedDataContent object from a file, as a key transport recipient
+    ///
+    /// This is for recipients that only have an RSA certificate. Both RSA-OAEP and, for
+    /// messages from legacy senders, PKCS #1 v1.5 encrypted keys are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file path to read the content from
+    /// * `recipient_cert` - The recipient certificate
+    /// * `recipient_private_key` - The RSA private key of the recipient, as PKCS #8 or PKCS #1 DER
+    ///
+    /// # Returns
+    ///
+    /// A new AuthEnvelopedDataContent object
+    pub fn from_file_for_ktri_recipient(
+        file: &str,
+        recipient_cert: &Certificate,
+        recipient_private_key: &[u8],
+    ) -> Result<AuthEnvelopedDataContent> {
+        let data = read_file_limited(file)?;
+        AuthEnvelopedDataContent::from_bytes_for_ktri_recipient(
+            &data,
+            recipient_cert,
+            recipient_private_key,
+        )
+    }
+
+    /// Create a new AuthEnvelopedDataContent object from bytes, as a key transport recipient
+    ///
+    /// This is for recipients that only have an RSA certificate. Both RSA-OAEP and, for
+    /// messages from legacy senders, PKCS #1 v1.5 encrypted keys are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes to read the content from
+    /// * `recipient_cert` - The recipient certificate
+    /// * `recipient_private_key` - The RSA private key of the recipient, as PKCS #8 or PKCS #1 DER
+    ///
+    /// # Returns
+    ///
+    /// A new AuthEnvelopedDataContent object
+    pub fn from_bytes_for_ktri_recipient(
+        data: &[u8],
+        recipient_cert: &Certificate,
+        recipient_private_key: &[u8],
+    ) -> Result<AuthEnvelopedDataContent> {
+        AuthEnvelopedDataContent::from_bytes_with(data, |data| {
+            CmsUtil::decrypt_ktri(data, recipient_private_key, recipient_cert)
+        })
+    }
+
+    /// Parse the content and decrypt it with the given function
+    fn from_bytes_with(
+        data: &[u8],
+        decrypt: impl FnOnce(&[u8]) -> Result<Vec<u8>>,
+    ) -> Result<AuthEnvelopedDataContent> {
+        // The input, the ContentInfo decoded from it and the content decoded again
+        // from a copy are all charged to the parse budget
+        let mut account = AllocationAccount::new();
+        charge_der_input(data, &mut account)?;
         // First try to read it as a der encoded ContentInfo
         let ci = if let Ok(content_info) = ContentInfo::from_der(data) {
             content_info
         } else {
             // If that fails, try to read it as a pem encoded ContentInfo
             let pem = pem::parse(data).map_err(|_| QuantCryptError::InvalidContent)?;
+            charge_der_input(pem.contents(), &mut account)?;
             ContentInfo::from_der(pem.contents()).map_err(|_| QuantCryptError::InvalidContent)?
         };
 
@@ -168,20 +250,43 @@ impl AuthEnvelopedDataContent {
             .content
             .to_der()
             .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
+        charge_der_input(&enveloped_data, &mut account)?;
 
         let ed = AuthEnvelopedData::from_der(&enveloped_data)
             .map_err(|_| QuantCryptError::InvalidContent)?;
 
         // try to decrypt the content
-        let pt = CmsUtil::decrypt_kemri(data, recipient_private_key, recipient_cert)?;
+        let mut pt = decrypt(data)?;
+        account.charge(pt.len())?;
+
+        // Unwrap compressed-then-encrypted content
+        let mut content_type = ed.auth_encrypted_content.content_type;
+        if content_type == ID_CT_COMPRESSED_DATA {
+            let cdc = CompressedDataContent::from_encapsulated_charged(&pt, &mut account)?;
+            content_type = cdc.get_content_type();
+            pt = cdc.get_content();
+        }
+
+        // Verify and unwrap signed-then-encrypted content
+        let mut sender = None;
+        if content_type == ID_SIGNED_DATA {
+            let sdc = SignedDataContent::from_encapsulated(&pt, &mut account)?;
+            sender = Some(sdc.verify_sender()?);
+            content_type = sdc.get_content_type();
+            pt = sdc.get_content().ok_or(QuantCryptError::InvalidContent)?;
+        }
+        let (sender_cert, sender_report) = sender.unzip();
 
         Ok(AuthEnvelopedDataContent {
             version: ed.version,
             originator_info: ed.originator_info,
             recip_infos: ed.recip_infos,
+            content_type,
             content: pt,
             unprotected_attrs: ed.unauth_attrs,
             auth_attrs: ed.auth_attrs,
+            sender_cert,
+            sender_report,
         })
     }
 
@@ -195,6 +300,13 @@ impl AuthEnvelopedDataContent {
         self.originator_info.clone()
     }
 
+    /// Get the content type of the content
+    ///
+    /// For compressed content, this is the content type of the decompressed content
+    pub fn get_content_type(&self) -> ObjectIdentifier {
+        self.content_type
+    }
+
     /// Get the content
     pub fn get_content(&self) -> Vec<u8> {
         self.content.clone()
@@ -215,6 +327,19 @@ impl AuthEnvelopedDataContent {
         self.recip_infos.clone()
     }
 
+    /// Get the certificate of the sender, if the content was signed before it was
+    /// encrypted. The signature has been verified when the content was decrypted.
+    /// The certificate itself is not validated.
+    pub fn get_sender_certificate(&self) -> Option<Certificate> {
+        self.sender_cert.clone()
+    }
+
+    /// Get the verification report of the signature of the sender, if the content
+    /// was signed before it was encrypted
+    pub fn get_sender_verification_report(&self) -> Option<VerificationReport> {
+        self.sender_report.clone()
+    }
+
     /// Get a builder for the AuthEnvelopedDataContent. This is used to create new AuthEnvelopedDataContent objects
     ///
     /// # Arguments
@@ -236,7 +361,7 @@ impl AuthEnvelopedDataContent {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
     use der::{asn1::SetOfVec, Tag, Tagged};
     use spki::ObjectIdentifier;
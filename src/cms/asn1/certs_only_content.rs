@@ -0,0 +1,247 @@
+use cms::cert::CertificateChoices;
+use cms::content_info::{CmsVersion, ContentInfo};
+use cms::signed_data::{CertificateSet, EncapsulatedContentInfo, SignedData, SignerInfos};
+use const_oid::db::rfc5911::{ID_DATA, ID_SIGNED_DATA};
+use der::asn1::SetOfVec;
+use der::{Any, Decode, Encode};
+
+use crate::utils::resource_limits::{charge_der_input, read_file_limited, AllocationAccount};
+use crate::{certificates::Certificate, QuantCryptError};
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The PEM label of a certs-only bundle, as written by OpenSSL and most enterprise tools
+const PKCS7_PEM_LABEL: &str = "PKCS7";
+
+/// Main interaction point for certs-only bundles (PKCS#7 / .p7b / .p7c files)
+///
+/// A certs-only bundle is a degenerate SignedData (RFC 5652 § 5.2): it has no
+/// content and no signers, and is only used to carry a set of certificates,
+/// typically a CA chain. This is how many enterprise tools distribute chains.
+///
+/// Bundles are read from DER or PEM. When reading, the certificates of any
+/// SignedData are accepted, including one that has signers; the signatures are
+/// not verified, use `SignedDataContent` for that. Only X.509 certificates are
+/// read, other certificate formats and CRLs are skipped.
+///
+/// DER encodes the certificates as a SET OF, so a written bundle lists them in
+/// the DER order rather than in the order they were given.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::Certificate;
+/// use quantcrypt::content::CertsOnlyContent;
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let cert = Certificate::from_file(cert_path).unwrap();
+///
+/// let bundle = CertsOnlyContent::new(vec![cert]).to_pem().unwrap();
+/// let certs = CertsOnlyContent::from_bytes(bundle.as_bytes()).unwrap();
+/// assert_eq!(certs.get_certificates().len(), 1);
+/// ```
+pub struct CertsOnlyContent {
+    /// The certificates of the bundle
+    certificates: Vec<Certificate>,
+}
+
+impl CertsOnlyContent {
+    /// Create a new certs-only bundle
+    ///
+    /// # Arguments
+    ///
+    /// * `certificates` - The certificates of the bundle
+    ///
+    /// # Returns
+    ///
+    /// The new bundle
+    pub fn new(certificates: Vec<Certificate>) -> CertsOnlyContent {
+        CertsOnlyContent { certificates }
+    }
+
+    /// Read a certs-only bundle from a file
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path to the DER or PEM encoded bundle
+    ///
+    /// # Returns
+    ///
+    /// The bundle
+    pub fn from_file(file: &str) -> Result<CertsOnlyContent> {
+        let data = read_file_limited(file)?;
+        Self::from_bytes(&data)
+    }
+
+    /// Read a certs-only bundle
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The DER or PEM encoded ContentInfo of the bundle
+    ///
+    /// # Returns
+    ///
+    /// The bundle
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidContent` if the data isn't a SignedData ContentInfo
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` if the data is larger than the parse budget
+    pub fn from_bytes(data: &[u8]) -> Result<CertsOnlyContent> {
+        // The input, the ContentInfo decoded from it and the content decoded again
+        // from a copy are all charged to the parse budget
+        let mut account = AllocationAccount::new();
+        charge_der_input(data, &mut account)?;
+        // First try to read it as a der encoded ContentInfo
+        let ci = if let Ok(content_info) = ContentInfo::from_der(data) {
+            content_info
+        } else {
+            // If that fails, try to read it as a pem encoded ContentInfo
+            let pem = pem::parse(data).map_err(|_| QuantCryptError::InvalidContent)?;
+            charge_der_input(pem.contents(), &mut account)?;
+            ContentInfo::from_der(pem.contents()).map_err(|_| QuantCryptError::InvalidContent)?
+        };
+
+        if ci.content_type != ID_SIGNED_DATA {
+            return Err(QuantCryptError::InvalidContent);
+        }
+
+        let signed_data = ci
+            .content
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidContent)?;
+        charge_der_input(&signed_data, &mut account)?;
+        let signed_data =
+            SignedData::from_der(&signed_data).map_err(|_| QuantCryptError::InvalidContent)?;
+
+        let mut certificates = Vec::new();
+        if let Some(set) = &signed_data.certificates {
+            for choice in set.0.iter() {
+                if let CertificateChoices::Certificate(cert) = choice {
+                    certificates.push(Certificate::new(cert.clone()));
+                }
+            }
+        }
+        Ok(CertsOnlyContent { certificates })
+    }
+
+    /// Get the certificates of the bundle
+    pub fn get_certificates(&self) -> Vec<Certificate> {
+        self.certificates.clone()
+    }
+
+    /// Write the bundle as a DER encoded ContentInfo
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded ContentInfo
+    pub fn to_der(&self) -> Result<Vec<u8>> {
+        let mut certificate_set = Vec::new();
+        for cert in self.certificates.iter() {
+            let choice = CertificateChoices::Certificate(cert.get_inner().clone());
+            // A duplicate certificate can't be encoded in a SET OF
+            if !certificate_set.contains(&choice) {
+                certificate_set.push(choice);
+            }
+        }
+
+        // RFC 5652 § 5.2: no content, no digest algorithms and no signers
+        let signed_data = SignedData {
+            version: CmsVersion::V1,
+            digest_algorithms: SetOfVec::new(),
+            encap_content_info: EncapsulatedContentInfo {
+                econtent_type: ID_DATA,
+                econtent: None,
+            },
+            certificates: Some(CertificateSet(
+                SetOfVec::try_from(certificate_set).map_err(|_| QuantCryptError::Unknown)?,
+            )),
+            crls: None,
+            signer_infos: SignerInfos(SetOfVec::new()),
+        };
+
+        let content_info = ContentInfo {
+            content_type: ID_SIGNED_DATA,
+            content: Any::from_der(&signed_data.to_der().map_err(|_| QuantCryptError::Unknown)?)
+                .map_err(|_| QuantCryptError::Unknown)?,
+        };
+        content_info.to_der().map_err(|_| QuantCryptError::Unknown)
+    }
+
+    /// Write the bundle as a PEM encoded ContentInfo, with the label `PKCS7`
+    ///
+    /// # Returns
+    ///
+    /// The PEM encoded ContentInfo
+    pub fn to_pem(&self) -> Result<String> {
+        let der = self.to_der()?;
+        Ok(pem::encode(&pem::Pem::new(PKCS7_PEM_LABEL, der)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::content::SignedDataContent;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+
+    #[test]
+    fn test_certs_only_content() {
+        let ta = Certificate::from_file(
+            "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der",
+        )
+        .unwrap();
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=example.com".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        // The duplicate is dropped
+        let bundle = CertsOnlyContent::new(vec![ta.clone(), cert.clone(), ta.clone()]);
+        let der = bundle.to_der().unwrap();
+
+        // OpenSSL reads the bundle as PKCS#7
+        #[cfg(feature = "openssl")]
+        assert!(openssl::pkcs7::Pkcs7::from_der(&der).is_ok());
+
+        let read = CertsOnlyContent::from_bytes(&der).unwrap();
+        let mut read = read
+            .get_certificates()
+            .iter()
+            .map(|c| c.to_der().unwrap())
+            .collect::<Vec<_>>();
+        let mut expected = vec![ta.to_der().unwrap(), cert.to_der().unwrap()];
+        read.sort();
+        expected.sort();
+        assert_eq!(read, expected);
+
+        // It is degenerate, there is no content and no signers
+        let sdc = SignedDataContent::from_bytes(&der).unwrap();
+        assert!(sdc.get_content().is_none());
+
+        // PEM encoded
+        let pem = bundle.to_pem().unwrap();
+        assert!(pem.starts_with("-----BEGIN PKCS7-----"));
+        let read = CertsOnlyContent::from_bytes(pem.as_bytes()).unwrap();
+        assert_eq!(read.get_certificates().len(), 2);
+
+        // Not a SignedData
+        let compressed = crate::content::CompressedDataContent::compress(b"abc", None).unwrap();
+        assert_eq!(
+            CertsOnlyContent::from_bytes(&compressed).err(),
+            Some(QuantCryptError::InvalidContent)
+        );
+    }
+}
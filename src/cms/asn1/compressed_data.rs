@@ -0,0 +1,31 @@
+//! `CompressedData`-related types
+
+use cms::{content_info::CmsVersion, signed_data::EncapsulatedContentInfo};
+use der::Sequence;
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+
+/// The content type of a CompressedData, id-ct-compressedData
+pub const ID_CT_COMPRESSED_DATA: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.1.9");
+
+/// The zlib compression algorithm, id-alg-zlibCompress
+pub const ID_ALG_ZLIB_COMPRESS: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.3.8");
+
+/// The `CompressedData` type is defined in [RFC 3274 Section 1.1].
+///
+/// ```text
+///      CompressedData ::= SEQUENCE {
+///         version CMSVersion,
+///         compressionAlgorithm CompressionAlgorithmIdentifier,
+///         encapContentInfo EncapsulatedContentInfo }
+/// ```
+///
+/// [RFC 3274 Section 1.1]: https://www.rfc-editor.org/rfc/rfc3274#section-1.1
+#[derive(Clone, Debug, Eq, PartialEq, Sequence)]
+#[allow(missing_docs)]
+pub struct CompressedData {
+    pub version: CmsVersion,
+    pub compression_alg: AlgorithmIdentifierOwned,
+    pub encap_content_info: EncapsulatedContentInfo,
+}
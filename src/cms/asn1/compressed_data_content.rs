@@ -0,0 +1,291 @@
+use std::io::{Read, Write};
+
+use cms::{
+    content_info::{CmsVersion, ContentInfo},
+    signed_data::EncapsulatedContentInfo,
+};
+use const_oid::db::rfc5911::ID_DATA;
+use der::{asn1::OctetString, Any, Decode, Encode, Tag, Tagged};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+
+use crate::cms::asn1::compressed_data::{
+    CompressedData, ID_ALG_ZLIB_COMPRESS, ID_CT_COMPRESSED_DATA,
+};
+use crate::utils::resource_limits::{charge_der_input, read_file_limited, AllocationAccount};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The maximum size of decompressed content, to guard against decompression bombs
+const MAX_DECOMPRESSED_LEN: u64 = 1024 * 1024 * 1024;
+
+/// Main interaction point for the CompressedData content (RFC 3274)
+///
+/// A CompressedData can be a message on its own, or the content of another
+/// content type: signed-and-compressed messages carry it as the encapsulated
+/// content of a SignedData, and compressed-then-encrypted messages as the
+/// encrypted content of an EnvelopedData or AuthEnvelopedData. The latter is
+/// unwrapped transparently by `EnvelopedDataContent` and `AuthEnvelopedDataContent`,
+/// and produced by their builders with `compress_content`.
+///
+/// Only zlib compression is supported, as it is the only algorithm defined by RFC 3274.
+///
+/// # Example
+/// ```
+/// use quantcrypt::content::CompressedDataContent;
+///
+/// let data = b"abcabcabcabcabcabcabcabcabcabc";
+/// let compressed = CompressedDataContent::compress(data, None).unwrap();
+///
+/// let cdc = CompressedDataContent::from_bytes(&compressed).unwrap();
+/// assert_eq!(cdc.get_content(), data);
+/// ```
+pub struct CompressedDataContent {
+    /// The version of the CompressedData
+    version: CmsVersion,
+    /// The content type of the compressed content
+    content_type: ObjectIdentifier,
+    /// The decompressed content
+    content: Vec<u8>,
+}
+
+impl CompressedDataContent {
+    /// Compress content into a CompressedData ContentInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content to compress
+    /// * `content_type` - The content type of the content, or None for id-data
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded ContentInfo
+    pub fn compress(content: &[u8], content_type: Option<ObjectIdentifier>) -> Result<Vec<u8>> {
+        let compressed_data = Self::encapsulate(content, content_type)?;
+        let ci = ContentInfo {
+            content_type: ID_CT_COMPRESSED_DATA,
+            content: Any::from_der(&compressed_data).map_err(|_| QuantCryptError::Unknown)?,
+        };
+        ci.to_der().map_err(|_| QuantCryptError::Unknown)
+    }
+
+    /// Compress content into a CompressedData, to be used as the content of another content type
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content to compress
+    /// * `content_type` - The content type of the content, or None for id-data
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded CompressedData
+    pub fn encapsulate(content: &[u8], content_type: Option<ObjectIdentifier>) -> Result<Vec<u8>> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(content)
+            .map_err(|_| QuantCryptError::Unknown)?;
+        let compressed = encoder.finish().map_err(|_| QuantCryptError::Unknown)?;
+
+        let econtent = OctetString::new(compressed)
+            .map_err(|_| QuantCryptError::Unknown)?
+            .to_der()
+            .map_err(|_| QuantCryptError::Unknown)?;
+
+        let compressed_data = CompressedData {
+            version: CmsVersion::V0,
+            compression_alg: AlgorithmIdentifierOwned {
+                oid: ID_ALG_ZLIB_COMPRESS,
+                parameters: None,
+            },
+            encap_content_info: EncapsulatedContentInfo {
+                econtent_type: content_type.unwrap_or(ID_DATA),
+                econtent: Some(Any::from_der(&econtent).map_err(|_| QuantCryptError::Unknown)?),
+            },
+        };
+        compressed_data
+            .to_der()
+            .map_err(|_| QuantCryptError::Unknown)
+    }
+
+    /// Read a CompressedData ContentInfo from a file
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path to the DER or PEM encoded ContentInfo
+    ///
+    /// # Returns
+    ///
+    /// The decompressed CompressedData
+    pub fn from_file(file: &str) -> Result<CompressedDataContent> {
+        let data = read_file_limited(file)?;
+        Self::from_bytes(&data)
+    }
+
+    /// Read a CompressedData ContentInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The DER or PEM encoded ContentInfo
+    ///
+    /// # Returns
+    ///
+    /// The decompressed CompressedData
+    pub fn from_bytes(data: &[u8]) -> Result<CompressedDataContent> {
+        // The input, the ContentInfo decoded from it and the content decoded again
+        // from a copy are all charged to the parse budget
+        let mut account = AllocationAccount::new();
+        charge_der_input(data, &mut account)?;
+        // First try to read it as a der encoded ContentInfo
+        let ci = if let Ok(content_info) = ContentInfo::from_der(data) {
+            content_info
+        } else {
+            // If that fails, try to read it as a pem encoded ContentInfo
+            let pem = pem::parse(data).map_err(|_| QuantCryptError::InvalidContent)?;
+            charge_der_input(pem.contents(), &mut account)?;
+            ContentInfo::from_der(pem.contents()).map_err(|_| QuantCryptError::InvalidContent)?
+        };
+
+        if ci.content_type != ID_CT_COMPRESSED_DATA {
+            return Err(QuantCryptError::InvalidContent);
+        }
+
+        let compressed_data = ci
+            .content
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidContent)?;
+        Self::from_encapsulated_charged(&compressed_data, &mut account)
+    }
+
+    /// Read a CompressedData that is the content of another content type
+    ///
+    /// # Arguments
+    ///
+    /// * `compressed_data` - The DER encoded CompressedData, e.g. the eContent of a SignedData
+    ///
+    /// # Returns
+    ///
+    /// The decompressed CompressedData
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidContent` if the CompressedData is malformed, uses another
+    /// compression algorithm than zlib, or decompresses to more than 1 GiB
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` if the CompressedData and its content
+    /// don't fit in the parse budget
+    pub fn from_encapsulated(compressed_data: &[u8]) -> Result<CompressedDataContent> {
+        Self::from_encapsulated_charged(compressed_data, &mut AllocationAccount::new())
+    }
+
+    /// Read a CompressedData that is the content of another content type, charging it
+    /// and its decompressed content to the account of the enclosing parse
+    ///
+    /// # Arguments
+    ///
+    /// * `compressed_data` - The DER encoded CompressedData
+    /// * `account` - The account of the parse
+    ///
+    /// # Returns
+    ///
+    /// The decompressed CompressedData
+    pub(crate) fn from_encapsulated_charged(
+        compressed_data: &[u8],
+        account: &mut AllocationAccount,
+    ) -> Result<CompressedDataContent> {
+        charge_der_input(compressed_data, account)?;
+        let cd = CompressedData::from_der(compressed_data)
+            .map_err(|_| QuantCryptError::InvalidContent)?;
+
+        if cd.compression_alg.oid != ID_ALG_ZLIB_COMPRESS {
+            return Err(QuantCryptError::InvalidContent);
+        }
+
+        let econtent = cd
+            .encap_content_info
+            .econtent
+            .ok_or(QuantCryptError::InvalidContent)?;
+        if econtent.tag() != Tag::OctetString {
+            return Err(QuantCryptError::InvalidContent);
+        }
+
+        // The decompressed content is charged to the parse budget as well
+        let max_len = MAX_DECOMPRESSED_LEN.min(account.remaining() as u64);
+        let mut content = Vec::new();
+        ZlibDecoder::new(econtent.value())
+            .take(max_len.saturating_add(1))
+            .read_to_end(&mut content)
+            .map_err(|_| QuantCryptError::InvalidContent)?;
+        if content.len() as u64 > MAX_DECOMPRESSED_LEN {
+            return Err(QuantCryptError::InvalidContent);
+        }
+        account.charge(content.len())?;
+
+        Ok(CompressedDataContent {
+            version: cd.version,
+            content_type: cd.encap_content_info.econtent_type,
+            content,
+        })
+    }
+
+    /// Get the version of the CompressedData
+    pub fn get_version(&self) -> CmsVersion {
+        self.version
+    }
+
+    /// Get the content type of the decompressed content
+    pub fn get_content_type(&self) -> ObjectIdentifier {
+        self.content_type
+    }
+
+    /// Get the decompressed content
+    pub fn get_content(&self) -> Vec<u8> {
+        self.content.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_data_content() {
+        let data = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+        let compressed = CompressedDataContent::compress(data.as_bytes(), None).unwrap();
+        assert!(compressed.len() < data.len());
+
+        let cdc = CompressedDataContent::from_bytes(&compressed).unwrap();
+        assert_eq!(cdc.get_content(), data.as_bytes());
+        assert_eq!(cdc.get_content_type(), ID_DATA);
+        assert_eq!(cdc.get_version(), CmsVersion::V0);
+
+        // PEM encoded
+        let pem = pem::encode(&pem::Pem::new("CMS", compressed.clone()));
+        let cdc = CompressedDataContent::from_bytes(pem.as_bytes()).unwrap();
+        assert_eq!(cdc.get_content(), data.as_bytes());
+
+        // Custom content type
+        let oid = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+        let compressed = CompressedDataContent::encapsulate(b"abc", Some(oid)).unwrap();
+        let cdc = CompressedDataContent::from_encapsulated(&compressed).unwrap();
+        assert_eq!(cdc.get_content(), b"abc");
+        assert_eq!(cdc.get_content_type(), oid);
+
+        // Corrupt zlib stream
+        let mut cd = CompressedData::from_der(&compressed).unwrap();
+        let econtent = OctetString::new(vec![1, 2, 3]).unwrap().to_der().unwrap();
+        cd.encap_content_info.econtent = Some(Any::from_der(&econtent).unwrap());
+        assert_eq!(
+            CompressedDataContent::from_encapsulated(&cd.to_der().unwrap()).err(),
+            Some(QuantCryptError::InvalidContent)
+        );
+
+        // Unsupported compression algorithm
+        let mut cd = CompressedData::from_der(&compressed).unwrap();
+        cd.compression_alg.oid = ID_DATA;
+        assert_eq!(
+            CompressedDataContent::from_encapsulated(&cd.to_der().unwrap()).err(),
+            Some(QuantCryptError::InvalidContent)
+        );
+    }
+}
@@ -4,14 +4,21 @@ use cms::{
     enveloped_data::{EnvelopedData, OriginatorInfo, RecipientInfos},
 };
 use der::{Decode, Encode};
+use spki::ObjectIdentifier;
 use x509_cert::attr::Attributes;
 
+use crate::utils::resource_limits::{charge_der_input, read_file_limited, AllocationAccount};
 use crate::{certificates::Certificate, keys::PrivateKey, QuantCryptError};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+use crate::cms::asn1::compressed_data::ID_CT_COMPRESSED_DATA;
+use crate::cms::asn1::compressed_data_content::CompressedDataContent;
+use crate::cms::asn1::signed_data_content::SignedDataContent;
 use crate::cms::cms_util::CmsUtil;
+use crate::utils::verification_report::VerificationReport;
 use const_oid::db::rfc5911::ID_ENVELOPED_DATA;
+use const_oid::db::rfc5911::ID_SIGNED_DATA;
 
 use crate::cms::enveloped_data_builder::EnvelopedDataBuilder;
 
@@ -32,6 +39,8 @@ pub enum ContentEncryptionAlgorithm {
 ///
 /// # Example
 /// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
 /// use quantcrypt::content::EnvelopedDataContent;
 /// use quantcrypt::content::ContentEncryptionAlgorithm;
 /// use quantcrypt::certificates::Certificate;
@@ -97,6 +106,7 @@ pub enum ContentEncryptionAlgorithm {
 /// )
 /// .unwrap();
 /// assert_eq!(edc.get_content(), data);
+/// # }
 /// ```
 
 pub struct EnvelopedDataContent {
@@ -106,10 +116,16 @@ pub struct EnvelopedDataContent {
     originator_info: Option<OriginatorInfo>,
     /// The recipient infos
     recip_infos: RecipientInfos,
+    /// The content type of the content
+    content_type: ObjectIdentifier,
     /// The content
     content: Vec<u8>,
     /// The unprotected attributes
     unprotected_attrs: Option<Attributes>,
+    /// The certificate of the sender, for signed-then-encrypted content
+    sender_cert: Option<Certificate>,
+    /// The verification report of the signature of the sender
+    sender_report: Option<VerificationReport>,
 }
 
 impl EnvelopedDataContent {
@@ -131,7 +147,7 @@ impl EnvelopedDataContent {
         recipient_cert: &Certificate,
         recipient_private_key: &PrivateKey,
     ) -> Result<EnvelopedDataContent> {
-        let data = std::fs::read(file).map_err(|_| QuantCryptError::FileReadError)?;
+        let data = read_file_limited(file)?;
         EnvelopedDataContent::from_bytes_for_kem_recipient(
             &data,
             recipient_cert,
@@ -157,12 +173,78 @@ impl EnvelopedDataContent {
         recipient_cert: &Certificate,
         recipient_private_key: &PrivateKey,
     ) -> Result<EnvelopedDataContent> {
+        EnvelopedDataContent::from_bytes_with(data, |data| {
+            CmsUtil::decrypt_kemri(data, recipient_private_key, recipient_cert)
+        })
+    }
+
+    /// Create a new EnvelopedDataContent object from a file, as a key transport recipient
+    ///
+    /// This is for recipients that only have an RSA certificate. Both RSA-OAEP and, for
+    /// messages from legacy senders, PKCS #1 v1.5 encrypted keys are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file path to read the content from
+    /// * `recipient_cert` - The recipient certificate
+    /// * `recipient_private_key` - The RSA private key of the recipient, as PKCS #8 or PKCS #1 DER
+    ///
+    /// # Returns
+    ///
+    /// A new EnvelopedDataContent object
+    pub fn from_file_for_ktri_recipient(
+        file: &str,
+        recipient_cert: &Certificate,
+        recipient_private_key: &[u8],
+    ) -> Result<EnvelopedDataContent> {
+        let data = read_file_limited(file)?;
+        EnvelopedDataContent::from_bytes_for_ktri_recipient(
+            &data,
+            recipient_cert,
+            recipient_private_key,
+        )
+    }
+
+    /// Create a new EnvelopedDataContent object from bytes, as a key transport recipient
+    ///
+    /// This is for recipients that only have an RSA certificate. Both RSA-OAEP and, for
+    /// messages from legacy senders, PKCS #1 v1.5 encrypted keys are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes to read the content from
+    /// * `recipient_cert` - The recipient certificate
+    /// * `recipient_private_key` - The RSA private key of the recipient, as PKCS #8 or PKCS #1 DER
+    ///
+    /// # Returns
+    ///
+    /// A new EnvelopedDataContent object
+    pub fn from_bytes_for_ktri_recipient(
+        data: &[u8],
+        recipient_cert: &Certificate,
+        recipient_private_key: &[u8],
+    ) -> Result<EnvelopedDataContent> {
+        EnvelopedDataContent::from_bytes_with(data, |data| {
+            CmsUtil::decrypt_ktri(data, recipient_private_key, recipient_cert)
+        })
+    }
+
+    /// Parse the content and decrypt it with the given function
+    fn from_bytes_with(
+        data: &[u8],
+        decrypt: impl FnOnce(&[u8]) -> Result<Vec<u8>>,
+    ) -> Result<EnvelopedDataContent> {
+        // The input, the ContentInfo decoded from it and the content decoded again
+        // from a copy are all charged to the parse budget
+        let mut account = AllocationAccount::new();
+        charge_der_input(data, &mut account)?;
         // First try to read it as a der encoded ContentInfo
         let ci = if let Ok(content_info) = ContentInfo::from_der(data) {
             content_info
         } else {
             // If that fails, try to read it as a pem encoded ContentInfo
             let pem = pem::parse(data).map_err(|_| QuantCryptError::InvalidContent)?;
+            charge_der_input(pem.contents(), &mut account)?;
             ContentInfo::from_der(pem.contents()).map_err(|_| QuantCryptError::InvalidContent)?
         };
 
@@ -175,19 +257,42 @@ impl EnvelopedDataContent {
             .content
             .to_der()
             .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
+        charge_der_input(&enveloped_data, &mut account)?;
 
         let ed = EnvelopedData::from_der(&enveloped_data)
             .map_err(|_| QuantCryptError::InvalidContent)?;
 
         // try to decrypt the content
-        let pt = CmsUtil::decrypt_kemri(data, recipient_private_key, recipient_cert)?;
+        let mut pt = decrypt(data)?;
+        account.charge(pt.len())?;
+
+        // Unwrap compressed-then-encrypted content
+        let mut content_type = ed.encrypted_content.content_type;
+        if content_type == ID_CT_COMPRESSED_DATA {
+            let cdc = CompressedDataContent::from_encapsulated_charged(&pt, &mut account)?;
+            content_type = cdc.get_content_type();
+            pt = cdc.get_content();
+        }
+
+        // Verify and unwrap signed-then-encrypted content
+        let mut sender = None;
+        if content_type == ID_SIGNED_DATA {
+            let sdc = SignedDataContent::from_encapsulated(&pt, &mut account)?;
+            sender = Some(sdc.verify_sender()?);
+            content_type = sdc.get_content_type();
+            pt = sdc.get_content().ok_or(QuantCryptError::InvalidContent)?;
+        }
+        let (sender_cert, sender_report) = sender.unzip();
 
         Ok(EnvelopedDataContent {
             version: ed.version,
             originator_info: ed.originator_info,
             recip_infos: ed.recip_infos,
+            content_type,
             content: pt,
             unprotected_attrs: ed.unprotected_attrs,
+            sender_cert,
+            sender_report,
         })
     }
 
@@ -201,6 +306,13 @@ impl EnvelopedDataContent {
         self.originator_info.clone()
     }
 
+    /// Get the content type of the content
+    ///
+    /// For compressed content, this is the content type of the decompressed content
+    pub fn get_content_type(&self) -> ObjectIdentifier {
+        self.content_type
+    }
+
     /// Get the content
     pub fn get_content(&self) -> Vec<u8> {
         self.content.clone()
@@ -216,6 +328,19 @@ impl EnvelopedDataContent {
         self.recip_infos.clone()
     }
 
+    /// Get the certificate of the sender, if the content was signed before it was
+    /// encrypted. The signature has been verified when the content was decrypted.
+    /// The certificate itself is not validated.
+    pub fn get_sender_certificate(&self) -> Option<Certificate> {
+        self.sender_cert.clone()
+    }
+
+    /// Get the verification report of the signature of the sender, if the content
+    /// was signed before it was encrypted
+    pub fn get_sender_verification_report(&self) -> Option<VerificationReport> {
+        self.sender_report.clone()
+    }
+
     /// Get a new EnvelopedDataContentBuilder
     ///
     /// # Arguments
@@ -237,7 +362,7 @@ impl EnvelopedDataContent {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
     use der::{asn1::SetOfVec, Tag, Tagged};
     use spki::ObjectIdentifier;
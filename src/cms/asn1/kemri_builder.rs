@@ -93,6 +93,7 @@ impl RecipientInfoBuilder for KemRecipientInfoBuilder {
 
         let kem_oid = self.kem.get_kem_info().oid;
         let kem_oid: ObjectIdentifier = kem_oid
+            .ok_or_else(|| Error::Builder("The KEM has no OID".to_string()))?
             .parse()
             .map_err(|_| Error::Builder("Error parsing KEM OID".to_string()))?;
 
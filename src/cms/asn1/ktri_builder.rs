@@ -0,0 +1,190 @@
+//! Key transport `RecipientInfo` with RSA-OAEP, for recipients that only have RSA certificates
+use cms::{
+    builder::{Error, RecipientInfoBuilder, RecipientInfoType},
+    content_info::CmsVersion,
+    enveloped_data::{KeyTransRecipientInfo, RecipientIdentifier, RecipientInfo},
+};
+use const_oid::db::rfc5912::{
+    ID_MGF_1, ID_P_SPECIFIED, ID_RSAES_OAEP, ID_SHA_256, ID_SHA_384, ID_SHA_512,
+};
+use der::{
+    asn1::{AnyRef, OctetString},
+    Any, Decode, Encode, Tag,
+};
+use rsa::{
+    pkcs1::{DecodeRsaPublicKey, RsaOaepParams},
+    RsaPublicKey,
+};
+use spki::{AlgorithmIdentifier, AlgorithmIdentifierOwned, AlgorithmIdentifierRef};
+
+use crate::certificates::Certificate;
+use crate::kem::common::kem_info::{OaepHash, OaepParams};
+use crate::kem::rsa_kem::oaep_padding;
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, cms::builder::Error>;
+
+/// Get the OID of an OAEP hash function
+fn hash_oid(hash: OaepHash) -> spki::ObjectIdentifier {
+    match hash {
+        OaepHash::Sha256 => ID_SHA_256,
+        OaepHash::Sha384 => ID_SHA_384,
+        OaepHash::Sha512 => ID_SHA_512,
+    }
+}
+
+/// Get the OAEP hash function of an OID
+fn hash_from_oid(oid: &spki::ObjectIdentifier) -> Option<OaepHash> {
+    match *oid {
+        ID_SHA_256 => Some(OaepHash::Sha256),
+        ID_SHA_384 => Some(OaepHash::Sha384),
+        ID_SHA_512 => Some(OaepHash::Sha512),
+        _ => None,
+    }
+}
+
+/// Create the id-RSAES-OAEP algorithm identifier for a set of OAEP parameters
+///
+/// The parameters are always present, as the defaults of RFC 4055 use SHA-1.
+///
+/// # Arguments
+///
+/// * `params` - The OAEP parameters
+///
+/// # Returns
+///
+/// The algorithm identifier
+pub(crate) fn oaep_algorithm_identifier(
+    params: &OaepParams,
+) -> der::Result<AlgorithmIdentifierOwned> {
+    let hash = |h: OaepHash| AlgorithmIdentifierRef {
+        oid: hash_oid(h),
+        parameters: Some(AnyRef::NULL),
+    };
+    let label = params.label.clone().unwrap_or_default();
+    let oaep_params = RsaOaepParams {
+        hash: hash(params.digest),
+        mask_gen: AlgorithmIdentifier {
+            oid: ID_MGF_1,
+            parameters: Some(hash(params.mgf_hash)),
+        },
+        p_source: AlgorithmIdentifierRef {
+            oid: ID_P_SPECIFIED,
+            parameters: Some(AnyRef::new(Tag::OctetString, label.as_bytes())?),
+        },
+    };
+    Ok(AlgorithmIdentifierOwned {
+        oid: ID_RSAES_OAEP,
+        parameters: Some(Any::from_der(&oaep_params.to_der()?)?),
+    })
+}
+
+/// Get the OAEP parameters of an id-RSAES-OAEP algorithm identifier
+///
+/// # Arguments
+///
+/// * `alg` - The algorithm identifier
+///
+/// # Returns
+///
+/// The OAEP parameters
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidRecipientInfo` if the parameters are malformed, or use
+/// SHA-1 or a label that is not UTF-8
+pub(crate) fn oaep_params_from_algorithm_identifier(
+    alg: &AlgorithmIdentifierOwned,
+) -> std::result::Result<OaepParams, QuantCryptError> {
+    let params_der = match &alg.parameters {
+        Some(params) => params
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidRecipientInfo)?,
+        // Absent parameters mean the RFC 4055 defaults, i.e. SHA-1
+        None => return Err(QuantCryptError::InvalidRecipientInfo),
+    };
+    let oaep_params =
+        RsaOaepParams::from_der(&params_der).map_err(|_| QuantCryptError::InvalidRecipientInfo)?;
+
+    let digest =
+        hash_from_oid(&oaep_params.hash.oid).ok_or(QuantCryptError::InvalidRecipientInfo)?;
+    let mgf_hash = match (oaep_params.mask_gen.oid, oaep_params.mask_gen.parameters) {
+        (ID_MGF_1, Some(mgf_hash)) => hash_from_oid(&mgf_hash.oid),
+        _ => None,
+    }
+    .ok_or(QuantCryptError::InvalidRecipientInfo)?;
+    let label = match oaep_params.p_source.parameters {
+        Some(label) if oaep_params.p_source.oid == ID_P_SPECIFIED => {
+            std::str::from_utf8(label.value())
+                .map_err(|_| QuantCryptError::InvalidRecipientInfo)?
+                .to_string()
+        }
+        _ => return Err(QuantCryptError::InvalidRecipientInfo),
+    };
+
+    Ok(OaepParams {
+        digest,
+        mgf_hash,
+        label: if label.is_empty() { None } else { Some(label) },
+    })
+}
+
+/// Builds a `KeyTransRecipientInfo` that encrypts the content encryption key with RSA-OAEP
+/// to the RSA key of a certificate
+pub struct RsaOaepRecipientInfoBuilder {
+    pub cert: Certificate,
+    pub params: OaepParams,
+    /// The RNG of the OAEP seed, or None to seed one for each encryption
+    pub rng: Option<SharedRng>,
+}
+
+impl RsaOaepRecipientInfoBuilder {
+    pub fn new(cert: &Certificate, params: OaepParams) -> Self {
+        Self {
+            cert: cert.clone(),
+            params,
+            rng: None,
+        }
+    }
+}
+
+impl RecipientInfoBuilder for RsaOaepRecipientInfoBuilder {
+    /// Returns the RecipientInfoType
+    fn recipient_info_type(&self) -> RecipientInfoType {
+        RecipientInfoType::Ktri
+    }
+
+    /// Returns the `CMSVersion` for this `RecipientInfo`
+    fn recipient_info_version(&self) -> CmsVersion {
+        // The recipient is identified by its subject key identifier
+        CmsVersion::V2
+    }
+
+    fn build(&mut self, content_encryption_key: &[u8]) -> Result<RecipientInfo> {
+        // The public key is an RSAPublicKey for both rsaEncryption and id-RSAES-OAEP
+        let spki = &self
+            .cert
+            .get_inner()
+            .tbs_certificate
+            .subject_public_key_info;
+        let pk = RsaPublicKey::from_pkcs1_der(spki.subject_public_key.raw_bytes())
+            .map_err(|_| Error::Builder("Error getting RSA public key from cert".to_string()))?;
+
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
+        let encrypted_key = pk
+            .encrypt(&mut rng, oaep_padding(&self.params), content_encryption_key)
+            .map_err(|_| Error::Builder("Error encrypting key".to_string()))?;
+
+        let skid = self.cert.get_subject_key_identifier().map_err(|_| {
+            Error::Builder("Error getting subject key identifier from cert".to_string())
+        })?;
+
+        Ok(RecipientInfo::Ktri(KeyTransRecipientInfo {
+            version: CmsVersion::V2,
+            rid: RecipientIdentifier::SubjectKeyIdentifier(skid),
+            key_enc_alg: oaep_algorithm_identifier(&self.params)?,
+            enc_key: OctetString::new(encrypted_key)?,
+        }))
+    }
+}
@@ -1,6 +1,11 @@
 pub mod auth_env_data;
 pub mod auth_enveloped_data_builder;
 pub mod auth_enveloped_data_content;
+pub mod certs_only_content;
+pub mod compressed_data;
+pub mod compressed_data_content;
 pub mod enveloped_data_content;
 pub mod kemri;
 pub mod kemri_builder;
+pub mod ktri_builder;
+pub mod signed_data_content;
@@ -0,0 +1,613 @@
+use chrono::{DateTime, Utc};
+use cms::cert::CertificateChoices;
+use cms::content_info::{CmsVersion, ContentInfo};
+use cms::signed_data::{SignedData, SignerInfo};
+use const_oid::db::rfc5911::{ID_CONTENT_TYPE, ID_MESSAGE_DIGEST, ID_SIGNED_DATA, ID_SIGNING_TIME};
+use const_oid::db::rfc6268::ID_AA_BINARY_SIGNING_TIME;
+use der::asn1::{OctetString, Uint};
+use der::{Decode, Encode, Tag, Tagged};
+use spki::ObjectIdentifier;
+use x509_cert::attr::Attributes;
+use x509_cert::time::Time;
+
+use crate::cms::signed_data_builder::SignedDataBuilder;
+use crate::cms::stream_parser::new_digest;
+use crate::utils::clock_skew::{get_clock_skew_tolerance, is_within};
+use crate::utils::resource_limits::{charge_der_input, read_file_limited, AllocationAccount};
+use crate::utils::verification_report::VerificationReport;
+use crate::{certificates::Certificate, QuantCryptError};
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Requirements on the signed attributes of every signer of a SignedData
+///
+/// The content-type and message-digest attributes are always checked. On top
+/// of that, a profile can require attributes to be present (e.g. signing-time
+/// or commitment-type) or forbid them.
+///
+/// # Example
+/// ```
+/// use quantcrypt::content::{ObjectIdentifier, SignedAttributePolicy};
+///
+/// let mut policy = SignedAttributePolicy::default();
+/// policy
+///     // signing-time
+///     .require(ObjectIdentifier::new("1.2.840.113549.1.9.5").unwrap())
+///     // binary-signing-time
+///     .forbid(ObjectIdentifier::new("1.2.840.113549.1.9.16.2.46").unwrap());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SignedAttributePolicy {
+    /// The attributes that must be present
+    required: Vec<ObjectIdentifier>,
+    /// The attributes that must not be present
+    forbidden: Vec<ObjectIdentifier>,
+}
+
+impl SignedAttributePolicy {
+    /// Require a signed attribute to be present
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The OID of the attribute
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the policy
+    pub fn require(&mut self, oid: ObjectIdentifier) -> &mut Self {
+        self.required.push(oid);
+        self
+    }
+
+    /// Forbid a signed attribute
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The OID of the attribute
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the policy
+    pub fn forbid(&mut self, oid: ObjectIdentifier) -> &mut Self {
+        self.forbidden.push(oid);
+        self
+    }
+
+    /// Check if the signed attributes of a signer satisfy the policy
+    pub(crate) fn is_satisfied_by(&self, attrs: Option<&Attributes>) -> bool {
+        let has = |oid: &ObjectIdentifier| attrs.map_or(false, |a| a.iter().any(|a| a.oid == *oid));
+        self.required.iter().all(has) && !self.forbidden.iter().any(has)
+    }
+
+    /// Describe the policy, for verification reports
+    pub(crate) fn describe(&self) -> String {
+        let list = |oids: &[ObjectIdentifier]| {
+            oids.iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        format!(
+            "signed attributes required: [{}], forbidden: [{}]",
+            list(&self.required),
+            list(&self.forbidden)
+        )
+    }
+}
+
+/// Get the single value of a signed attribute as DER
+fn get_attribute_value(attrs: &Attributes, oid: ObjectIdentifier) -> Option<Vec<u8>> {
+    let mut matching = attrs.iter().filter(|a| a.oid == oid);
+    let attribute = matching.next()?;
+    if matching.next().is_some() || attribute.values.len() != 1 {
+        return None;
+    }
+    attribute.values.get(0)?.to_der().ok()
+}
+
+/// Get the signing time of a signer from its signing-time or binary-signing-time attribute
+fn get_signing_time(attrs: &Attributes) -> Option<DateTime<Utc>> {
+    if let Some(time) = get_attribute_value(attrs, ID_SIGNING_TIME) {
+        let time = Time::from_der(&time).ok()?;
+        return Some(time.to_system_time().into());
+    }
+    let time = get_attribute_value(attrs, ID_AA_BINARY_SIGNING_TIME)?;
+    let time = Uint::from_der(&time).ok()?;
+    if time.as_bytes().len() > 8 {
+        return None;
+    }
+    let secs = time
+        .as_bytes()
+        .iter()
+        .fold(0i64, |acc, b| (acc << 8) | i64::from(*b));
+    DateTime::from_timestamp(secs, 0)
+}
+
+/// Main interaction point for the SignedData content
+///
+/// This struct is used to create, read and verify SignedData content
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{CertValidity, CertificateBuilder, Profile};
+/// use quantcrypt::content::{
+///     CommitmentType, SignedAttributePolicy, SignedDataContent, SignerAttributes,
+/// };
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+/// let cert = CertificateBuilder::new(Profile::Root, None, validity, "CN=signer".to_string(), pk, &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// let mut attributes = SignerAttributes::default();
+/// attributes.commitment_type(CommitmentType::ProofOfOrigin);
+///
+/// let mut builder = SignedDataContent::get_builder();
+/// builder
+///     .content(b"abc")
+///     .unwrap()
+///     .signer(&cert, &sk, &attributes)
+///     .unwrap();
+/// let signed = builder.build().unwrap();
+///
+/// let sdc = SignedDataContent::from_bytes(&signed).unwrap();
+/// assert_eq!(sdc.get_content().unwrap(), b"abc");
+/// let report = sdc.verify(None, &SignedAttributePolicy::default()).unwrap();
+/// assert!(report.is_valid());
+/// ```
+pub struct SignedDataContent {
+    /// The SignedData
+    signed_data: SignedData,
+    /// The encapsulated content, or None for a detached signature
+    content: Option<Vec<u8>>,
+    /// The certificates included in the SignedData
+    certificates: Vec<Certificate>,
+}
+
+impl SignedDataContent {
+    /// Create a new SignedDataContent object from a file containing a DER or PEM
+    /// encoded ContentInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file path to read the SignedData content from
+    ///
+    /// # Returns
+    ///
+    /// A new SignedDataContent object
+    pub fn from_file(file: &str) -> Result<SignedDataContent> {
+        let data = read_file_limited(file)?;
+        Self::from_bytes(&data)
+    }
+
+    /// Create a new SignedDataContent object from a DER or PEM encoded ContentInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The encoded ContentInfo
+    ///
+    /// # Returns
+    ///
+    /// A new SignedDataContent object
+    pub fn from_bytes(data: &[u8]) -> Result<SignedDataContent> {
+        // The input, the ContentInfo decoded from it and the content decoded again
+        // from a copy are all charged to the parse budget
+        let mut account = AllocationAccount::new();
+        charge_der_input(data, &mut account)?;
+        // First try to read it as a der encoded ContentInfo
+        let ci = if let Ok(content_info) = ContentInfo::from_der(data) {
+            content_info
+        } else {
+            // If that fails, try to read it as a pem encoded ContentInfo
+            let pem = pem::parse(data).map_err(|_| QuantCryptError::InvalidContent)?;
+            charge_der_input(pem.contents(), &mut account)?;
+            ContentInfo::from_der(pem.contents()).map_err(|_| QuantCryptError::InvalidContent)?
+        };
+
+        if ci.content_type != ID_SIGNED_DATA {
+            return Err(QuantCryptError::InvalidContent);
+        }
+
+        let signed_data = ci
+            .content
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidContent)?;
+        Self::from_encapsulated(&signed_data, &mut account)
+    }
+
+    /// Create a new SignedDataContent object from a DER encoded SignedData that is the
+    /// content of another content type, e.g. a signed-then-encrypted EnvelopedData
+    ///
+    /// # Arguments
+    ///
+    /// * `signed_data` - The DER encoded SignedData
+    /// * `account` - The account of the enclosing parse, charged with the SignedData
+    ///
+    /// # Returns
+    ///
+    /// A new SignedDataContent object
+    pub(crate) fn from_encapsulated(
+        signed_data: &[u8],
+        account: &mut AllocationAccount,
+    ) -> Result<SignedDataContent> {
+        charge_der_input(signed_data, account)?;
+        let signed_data =
+            SignedData::from_der(signed_data).map_err(|_| QuantCryptError::InvalidContent)?;
+
+        let content = match &signed_data.encap_content_info.econtent {
+            Some(econtent) => {
+                if econtent.tag() != Tag::OctetString {
+                    return Err(QuantCryptError::InvalidContent);
+                }
+                Some(econtent.value().to_vec())
+            }
+            None => None,
+        };
+
+        let mut certificates = Vec::new();
+        if let Some(set) = &signed_data.certificates {
+            for choice in set.0.iter() {
+                if let CertificateChoices::Certificate(cert) = choice {
+                    certificates.push(Certificate::new(cert.clone()));
+                }
+            }
+        }
+
+        Ok(SignedDataContent {
+            signed_data,
+            content,
+            certificates,
+        })
+    }
+
+    /// Get a builder for SignedData
+    pub fn get_builder<'a>() -> SignedDataBuilder<'a> {
+        SignedDataBuilder::new()
+    }
+
+    /// Get the version of the SignedData
+    pub fn get_version(&self) -> CmsVersion {
+        self.signed_data.version
+    }
+
+    /// Get the content type of the encapsulated content
+    pub fn get_content_type(&self) -> ObjectIdentifier {
+        self.signed_data.encap_content_info.econtent_type
+    }
+
+    /// Get the encapsulated content
+    ///
+    /// # Returns
+    ///
+    /// The content, or None for a detached signature
+    pub fn get_content(&self) -> Option<Vec<u8>> {
+        self.content.clone()
+    }
+
+    /// Get the certificates included in the SignedData
+    pub fn get_certificates(&self) -> Vec<Certificate> {
+        self.certificates.clone()
+    }
+
+    /// Get the signer infos
+    pub fn get_signer_infos(&self) -> Vec<SignerInfo> {
+        self.signed_data.signer_infos.0.iter().cloned().collect()
+    }
+
+    /// Verify the signatures of all signers
+    ///
+    /// The certificate of every signer must be included in the SignedData. The
+    /// certificates themselves are not validated.
+    ///
+    /// # Arguments
+    ///
+    /// * `detached_content` - The signed content, for a detached signature
+    /// * `policy` - The requirements on the signed attributes
+    ///
+    /// # Returns
+    ///
+    /// A report with the checks performed for every signer. It is valid if there
+    /// is at least one signer and all signatures are valid and satisfy the policy.
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidContent` if the signature is detached and no content is given
+    pub fn verify(
+        &self,
+        detached_content: Option<&[u8]>,
+        policy: &SignedAttributePolicy,
+    ) -> Result<VerificationReport> {
+        let content = match (&self.content, detached_content) {
+            (Some(content), _) => content.as_slice(),
+            (None, Some(content)) => content,
+            (None, None) => return Err(QuantCryptError::InvalidContent),
+        };
+
+        let mut report = VerificationReport::new();
+        report.policy = Some(policy.describe());
+        if !report.check(
+            "signers present",
+            !self.signed_data.signer_infos.0.is_empty(),
+        ) {
+            return Ok(report);
+        }
+        for (i, signer_info) in self.signed_data.signer_infos.0.iter().enumerate() {
+            if !self.verify_signer(i, signer_info, content, policy, &mut report)? {
+                return Ok(report);
+            }
+        }
+        report.valid = true;
+        Ok(report)
+    }
+
+    /// Verify the signature of the sender of a signed-then-encrypted message
+    ///
+    /// # Returns
+    ///
+    /// The certificate of the sender and the verification report
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidContent` if there is not exactly one signer or the
+    /// signature is detached, and `QuantCryptError::SignatureVerificationFailed` if
+    /// the signature is invalid
+    pub(crate) fn verify_sender(&self) -> Result<(Certificate, VerificationReport)> {
+        let signer_info = match self.signed_data.signer_infos.0.as_slice() {
+            [signer_info] if self.content.is_some() => signer_info,
+            _ => return Err(QuantCryptError::InvalidContent),
+        };
+        let report = self.verify(None, &SignedAttributePolicy::default())?;
+        if !report.is_valid() {
+            return Err(QuantCryptError::SignatureVerificationFailed);
+        }
+        let cert = self
+            .certificates
+            .iter()
+            .find(|c| c.is_identified_by_sid(&signer_info.sid))
+            .ok_or(QuantCryptError::InvalidContent)?;
+        Ok((cert.clone(), report))
+    }
+
+    /// Verify the signature of a single signer
+    fn verify_signer(
+        &self,
+        index: usize,
+        signer_info: &SignerInfo,
+        content: &[u8],
+        policy: &SignedAttributePolicy,
+        report: &mut VerificationReport,
+    ) -> Result<bool> {
+        let mut check =
+            |name: &str, passed: bool| report.check(&format!("signer {}: {}", index, name), passed);
+
+        let cert = self
+            .certificates
+            .iter()
+            .find(|c| c.is_identified_by_sid(&signer_info.sid));
+        let cert = match cert {
+            Some(cert) => cert,
+            None => return Ok(check("certificate present", false)),
+        };
+        check("certificate present", true);
+
+        let mut digest = match new_digest(&signer_info.digest_alg.oid) {
+            Some(digest) => digest,
+            None => return Ok(check("digest algorithm supported", false)),
+        };
+        check("digest algorithm supported", true);
+        digest.update(content);
+        let digest = digest.finalize().to_vec();
+
+        if !check(
+            "signed attribute policy",
+            policy.is_satisfied_by(signer_info.signed_attrs.as_ref()),
+        ) {
+            return Ok(false);
+        }
+
+        let message = match &signer_info.signed_attrs {
+            Some(attrs) => {
+                // RFC 5652 § 5.3: content-type and message-digest must be present
+                let content_type = get_attribute_value(attrs, ID_CONTENT_TYPE)
+                    .and_then(|v| ObjectIdentifier::from_der(&v).ok());
+                if !check(
+                    "content-type attribute",
+                    content_type == Some(self.get_content_type()),
+                ) {
+                    return Ok(false);
+                }
+                let message_digest = get_attribute_value(attrs, ID_MESSAGE_DIGEST)
+                    .and_then(|v| OctetString::from_der(&v).ok());
+                if !check(
+                    "message-digest attribute",
+                    message_digest.as_ref().map(|d| d.as_bytes()) == Some(digest.as_slice()),
+                ) {
+                    return Ok(false);
+                }
+                // A signing time in the future, beyond the clock-skew tolerance, is rejected
+                if let Some(signing_time) = get_signing_time(attrs) {
+                    if !check(
+                        "signing time not in the future",
+                        is_within(signing_time, None, get_clock_skew_tolerance()),
+                    ) {
+                        return Ok(false);
+                    }
+                }
+                attrs
+                    .to_der()
+                    .map_err(|_| QuantCryptError::InvalidContent)?
+            }
+            None => content.to_vec(),
+        };
+
+        let pk = cert.get_public_key()?;
+        if !check(
+            "signature algorithm matches key",
+            signer_info.signature_algorithm.oid.to_string() == pk.get_oid(),
+        ) {
+            return Ok(false);
+        }
+        let valid = pk.verify(&message, signer_info.signature.as_bytes())?;
+        Ok(check("signature", valid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::cms::cms_util::CmsUtil;
+    use crate::cms::signed_data_builder::{CommitmentType, SignerAttributes};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use const_oid::db::rfc5911::ID_SIGNING_TIME;
+    use const_oid::db::rfc6268::ID_AA_BINARY_SIGNING_TIME;
+    use der::asn1::SetOfVec;
+    use x509_cert::attr::{Attribute, AttributeValue};
+
+    #[test]
+    fn test_signed_attributes() {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=signer".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let custom_oid = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.22554.5.9");
+        let mut values = SetOfVec::new();
+        values
+            .insert(AttributeValue::new(Tag::Utf8String, b"invoice".to_vec()).unwrap())
+            .unwrap();
+        let custom = Attribute {
+            oid: custom_oid,
+            values,
+        };
+
+        let mut attributes = SignerAttributes::default();
+        attributes
+            .binary_signing_time(true)
+            .commitment_type(CommitmentType::ProofOfApproval)
+            .signed_attribute(&custom)
+            .unwrap()
+            .unsigned_attribute(&custom);
+
+        let mut builder = SignedDataContent::get_builder();
+        builder
+            .content(b"document")
+            .unwrap()
+            .signer(&cert, &sk, &attributes)
+            .unwrap();
+        let signed = builder.build().unwrap();
+
+        let sdc = SignedDataContent::from_bytes(&signed).unwrap();
+        assert_eq!(sdc.get_version(), CmsVersion::V1);
+        assert_eq!(sdc.get_certificates().len(), 1);
+        let signer_infos = sdc.get_signer_infos();
+        let signed_attrs = signer_infos[0].signed_attrs.as_ref().unwrap();
+        // content-type, message-digest, signing-time, binary-signing-time, commitment-type, custom
+        assert_eq!(signed_attrs.len(), 6);
+        assert_eq!(signer_infos[0].unsigned_attrs.as_ref().unwrap().len(), 1);
+
+        let mut policy = SignedAttributePolicy::default();
+        assert!(sdc.verify(None, &policy).unwrap().is_valid());
+        policy
+            .require(ID_SIGNING_TIME)
+            .require(ID_AA_BINARY_SIGNING_TIME)
+            .require(custom_oid);
+        assert!(sdc.verify(None, &policy).unwrap().is_valid());
+        policy.forbid(custom_oid);
+        let report = sdc.verify(None, &policy).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "signer 0: signed attribute policy"
+        );
+        assert!(report.policy.unwrap().contains("1.3.6.1.4.1.22554.5.9"));
+
+        // Without signing time, a policy requiring it fails
+        let mut attributes = SignerAttributes::default();
+        attributes.signing_time(false);
+        let mut builder = SignedDataContent::get_builder();
+        builder
+            .content(b"document")
+            .unwrap()
+            .detached(true)
+            .unwrap()
+            .signer(&cert, &sk, &attributes)
+            .unwrap();
+        let sdc = SignedDataContent::from_bytes(&builder.build().unwrap()).unwrap();
+        assert!(sdc.get_content().is_none());
+        assert!(sdc.verify(None, &SignedAttributePolicy::default()).is_err());
+        assert!(sdc
+            .verify(Some(b"document"), &SignedAttributePolicy::default())
+            .unwrap()
+            .is_valid());
+        let report = sdc
+            .verify(Some(b"tampered"), &SignedAttributePolicy::default())
+            .unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "signer 0: message-digest attribute"
+        );
+        let mut policy = SignedAttributePolicy::default();
+        policy.require(ID_SIGNING_TIME);
+        assert!(!sdc.verify(Some(b"document"), &policy).unwrap().is_valid());
+
+        // A signing time in the future beyond the clock-skew tolerance is rejected
+        for (offset, valid) in [(60, true), (3600, false)] {
+            let time = std::time::SystemTime::now() + std::time::Duration::from_secs(offset);
+            let mut attributes = SignerAttributes::default();
+            attributes
+                .signing_time(false)
+                .signed_attribute(&CmsUtil::create_signing_time_attribute(time).unwrap())
+                .unwrap();
+            let mut builder = SignedDataContent::get_builder();
+            builder
+                .content(b"document")
+                .unwrap()
+                .signer(&cert, &sk, &attributes)
+                .unwrap();
+            let sdc = SignedDataContent::from_bytes(&builder.build().unwrap()).unwrap();
+            let report = sdc.verify(None, &SignedAttributePolicy::default()).unwrap();
+            assert_eq!(report.is_valid(), valid);
+        }
+
+        // The builder computes content-type and message-digest itself
+        let mut attributes = SignerAttributes::default();
+        let message_digest = CmsUtil::create_message_digest_attribute(&[0u8; 64]).unwrap();
+        assert_eq!(
+            attributes.signed_attribute(&message_digest).err(),
+            Some(QuantCryptError::InvalidAttribute)
+        );
+
+        // Duplicate attribute types are rejected
+        let signing_time = Attribute {
+            oid: ID_SIGNING_TIME,
+            values: custom.values.clone(),
+        };
+        attributes.signed_attribute(&signing_time).unwrap();
+        let mut builder = SignedDataContent::get_builder();
+        builder
+            .content(b"document")
+            .unwrap()
+            .signer(&cert, &sk, &attributes)
+            .unwrap();
+        assert_eq!(
+            builder.build().err(),
+            Some(QuantCryptError::InvalidAttribute)
+        );
+    }
+}
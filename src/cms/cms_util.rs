@@ -6,26 +6,56 @@
 
 use crate::cea::common::cea_trait::Cea;
 use crate::certificates::Certificate;
+use crate::kem::api::ciphertext::Ciphertext;
 use crate::{
     cms::asn1::kemri::KemRecipientInfo, kdf::api::KdfManager, kdf::common::kdf_trait::Kdf,
     keys::PrivateKey, wrap::api::WrapManager, wrap::common::wrap_trait::Wrap, QuantCryptError,
 };
 use cms::content_info::ContentInfo;
-use cms::enveloped_data::{EnvelopedData, OtherRecipientInfo, RecipientInfo, UserKeyingMaterial};
+use cms::enveloped_data::{
+    EnvelopedData, KeyTransRecipientInfo, OtherRecipientInfo, RecipientInfo, RecipientInfos,
+    UserKeyingMaterial,
+};
 use const_oid::db::rfc5911::{ID_CT_AUTH_ENVELOPED_DATA, ID_ENVELOPED_DATA};
-use der::asn1::{OctetStringRef, SetOfVec};
+use const_oid::db::rfc5912::{ID_RSAES_OAEP, RSA_ENCRYPTION};
+use der::asn1::{OctetStringRef, SetOfVec, Uint};
+use der::Any;
 use der::Tag;
 use der::{asn1::OctetString, Decode, Encode};
 use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+use std::time::{SystemTime, UNIX_EPOCH};
 use x509_cert::attr::{Attribute, AttributeValue};
+use x509_cert::time::Time;
 
 use crate::cea::cea_manager::CeaManager;
 use crate::cms::asn1::kemri::CmsOriForKemOtherInfo;
+use crate::cms::asn1::ktri_builder::oaep_params_from_algorithm_identifier;
+use crate::kem::rsa_kem::oaep_padding;
+use hkdf::Hkdf;
+#[cfg(feature = "openssl")]
+use openssl::rsa::{Padding, Rsa};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+#[cfg(feature = "openssl")]
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::traits::PrivateKeyParts;
+use rsa::RsaPrivateKey;
+use sha2::{Digest, Sha256};
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroizing;
+
+/// The first OpenSSL version with implicit rejection for PKCS #1 v1.5 decryption
+#[cfg(feature = "openssl")]
+const OPENSSL_IMPLICIT_REJECTION_VERSION: i64 = 0x3020_0000;
 
 use crate::cms::asn1::auth_env_data::AuthEnvelopedData;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// The commitment-type-indication attribute, id-aa-ets-commitmentType (RFC 5126)
+pub(crate) const ID_AA_ETS_COMMITMENT_TYPE: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.2.16");
+
 /// A utility class for CMS operations
 pub(crate) struct CmsUtil {}
 
@@ -82,7 +112,7 @@ impl CmsUtil {
     /// # Returns
     ///
     /// The CEK as bytes
-    fn get_cek(
+    pub(crate) fn get_cek(
         ori: &OtherRecipientInfo,
         private_key: &PrivateKey,
         cert: &Certificate,
@@ -99,10 +129,11 @@ impl CmsUtil {
         }
 
         let kem_ct = kemri.kem_ct.as_bytes();
-        let ss = private_key.decap(kem_ct)?;
+        let kem_ct = Ciphertext::from_oid(private_key.get_oid(), kem_ct)?;
+        let ss = private_key.decap(&kem_ct)?;
 
         let kek = Self::get_kek(
-            &ss,
+            ss.expose_secret(),
             &kemri.wrap.oid.to_string(),
             &kemri.kdf.oid.to_string(),
             kemri.kek_length,
@@ -115,10 +146,160 @@ impl CmsUtil {
         Ok(key)
     }
 
-    fn decrypt_auth_enveloped_kemri(
-        auth_enveloped_data_der: &[u8],
-        private_key: &PrivateKey,
+    /// Get the content encryption key (CEK) from a KeyTransRecipientInfo
+    ///
+    /// RSA-OAEP is accepted with the SHA-2 hash functions, and PKCS #1 v1.5 for
+    /// messages produced by legacy senders. PKCS #1 v1.5 is never used to encrypt.
+    ///
+    /// To not act as a Bleichenbacher padding oracle, a PKCS #1 v1.5 encrypted key
+    /// with invalid padding, or of the wrong length, is not reported as such.
+    /// Instead, a pseudorandom CEK derived from the private key and the encrypted
+    /// key is returned, so that the failure only surfaces when the content is
+    /// decrypted, exactly like with a well-formed but wrong key.
+    ///
+    /// The decryption itself must not leak the padding check through its timing
+    /// either (the Marvin attack), so it is done by OpenSSL 3.2 or later, whose
+    /// PKCS #1 v1.5 decryption is constant time and returns a synthetic message
+    /// instead of failing on invalid padding (implicit rejection). The decrypted
+    /// and the pseudorandom CEK are then selected between in constant time.
+    ///
+    /// # Arguments
+    ///
+    /// * `ktri` - The KeyTransRecipientInfo
+    /// * `private_key` - The RSA private key of the recipient
+    /// * `cert` - The certificate of the recipient
+    /// * `cek_len` - The length of the CEK expected by the content encryption algorithm
+    ///
+    /// # Returns
+    ///
+    /// The CEK as bytes
+    pub(crate) fn get_cek_ktri(
+        ktri: &KeyTransRecipientInfo,
+        private_key: &RsaPrivateKey,
         cert: &Certificate,
+        cek_len: usize,
+    ) -> Result<Vec<u8>> {
+        if !cert.is_identified_by(&ktri.rid) {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+
+        let encrypted_key = ktri.enc_key.as_bytes();
+        if ktri.key_enc_alg.oid == ID_RSAES_OAEP {
+            let params = oaep_params_from_algorithm_identifier(&ktri.key_enc_alg)?;
+            private_key
+                .decrypt(oaep_padding(&params), encrypted_key)
+                .map_err(|_| QuantCryptError::DecapFailed)
+        } else if ktri.key_enc_alg.oid == RSA_ENCRYPTION {
+            // The synthetic key is always computed, so that the timing does not depend on the padding
+            let synthetic = Self::get_synthetic_cek(private_key, encrypted_key, cek_len)?;
+            let (decrypted, len) =
+                Self::decrypt_pkcs1v15_implicit_rejection(private_key, encrypted_key)?;
+            if decrypted.len() < cek_len {
+                return Err(QuantCryptError::InvalidRecipientInfo);
+            }
+            let valid = (len as u64).ct_eq(&(cek_len as u64));
+            Ok(synthetic
+                .iter()
+                .zip(decrypted.iter())
+                .map(|(s, d)| u8::conditional_select(s, d, valid))
+                .collect())
+        } else {
+            Err(QuantCryptError::InvalidRecipientInfo)
+        }
+    }
+
+    /// Decrypt a PKCS #1 v1.5 encrypted key in constant time, with implicit rejection
+    ///
+    /// OpenSSL 3.2 and later never fail on invalid padding: a synthetic message of
+    /// pseudorandom length, derived from the private key and the ciphertext, is
+    /// returned instead. Older versions are refused, since their padding check is
+    /// observable.
+    ///
+    /// # Arguments
+    ///
+    /// * `private_key` - The RSA private key of the recipient
+    /// * `encrypted_key` - The encrypted key
+    ///
+    /// # Returns
+    ///
+    /// A buffer of the size of the modulus, starting with the decrypted message, and
+    /// the length of the message
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::BackendUnavailable` if OpenSSL is older than 3.2,
+    /// `QuantCryptError::DecapFailed` if the encrypted key has the wrong size for the modulus
+    #[cfg(feature = "openssl")]
+    fn decrypt_pkcs1v15_implicit_rejection(
+        private_key: &RsaPrivateKey,
+        encrypted_key: &[u8],
+    ) -> Result<(Zeroizing<Vec<u8>>, usize)> {
+        if openssl::version::number() < OPENSSL_IMPLICIT_REJECTION_VERSION {
+            return Err(QuantCryptError::BackendUnavailable {
+                component: "RSA PKCS #1 v1.5 with implicit rejection".to_string(),
+            });
+        }
+        let der = private_key
+            .to_pkcs1_der()
+            .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+        let rsa = Rsa::private_key_from_der(der.as_bytes())
+            .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+        // Only the size of the ciphertext, which is public, can make the decryption fail
+        let mut decrypted = Zeroizing::new(vec![0u8; rsa.size() as usize]);
+        let len = rsa
+            .private_decrypt(encrypted_key, &mut decrypted, Padding::PKCS1)
+            .map_err(|_| QuantCryptError::DecapFailed)?;
+        Ok((decrypted, len))
+    }
+
+    /// PKCS #1 v1.5 decryption with implicit rejection is computed with OpenSSL
+    #[cfg(not(feature = "openssl"))]
+    fn decrypt_pkcs1v15_implicit_rejection(
+        _private_key: &RsaPrivateKey,
+        _encrypted_key: &[u8],
+    ) -> Result<(Zeroizing<Vec<u8>>, usize)> {
+        Err(QuantCryptError::BackendUnavailable {
+            component: "RSA PKCS #1 v1.5 with implicit rejection".to_string(),
+        })
+    }
+
+    /// Derive the pseudorandom CEK returned for an invalid PKCS #1 v1.5 encrypted key
+    ///
+    /// The key derivation key is the hash of the private exponent, so the same
+    /// encrypted key always yields the same CEK and nothing is learned by resending it.
+    ///
+    /// # Arguments
+    ///
+    /// * `private_key` - The RSA private key of the recipient
+    /// * `encrypted_key` - The encrypted key
+    /// * `cek_len` - The length of the CEK
+    ///
+    /// # Returns
+    ///
+    /// The synthetic CEK
+    fn get_synthetic_cek(
+        private_key: &RsaPrivateKey,
+        encrypted_key: &[u8],
+        cek_len: usize,
+    ) -> Result<Vec<u8>> {
+        let kdk = Zeroizing::new(Sha256::digest(private_key.d().to_bytes_be()).to_vec());
+        let mut cek = vec![0u8; cek_len];
+        Hkdf::<Sha256>::new(Some(&kdk), encrypted_key)
+            .expand(b"CMS KTRI implicit rejection", &mut cek)
+            .map_err(|_| QuantCryptError::DecapFailed)?;
+        Ok(cek)
+    }
+
+    /// Get the length of the content encryption key of a content encryption algorithm
+    pub(crate) fn get_cek_len(content_enc_alg: &ObjectIdentifier) -> Result<usize> {
+        let cea = CeaManager::new_from_oid(&content_enc_alg.to_string())
+            .map_err(|_| QuantCryptError::UnsupportedContentEncryptionAlgorithm)?;
+        Ok(cea.get_cea_info().key_length)
+    }
+
+    fn decrypt_auth_enveloped(
+        auth_enveloped_data_der: &[u8],
+        get_cek: &dyn Fn(&RecipientInfo, usize) -> Option<Vec<u8>>,
     ) -> Result<Vec<u8>> {
         let ed = AuthEnvelopedData::from_der(auth_enveloped_data_der)
             .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
@@ -136,29 +317,20 @@ impl CmsUtil {
         };
 
         let mac = ed.mac.as_bytes();
+        let cek_len = Self::get_cek_len(&ed.auth_encrypted_content.content_enc_alg.oid)?;
 
         for ri in ed.recip_infos.0.iter() {
-            match ri {
-                RecipientInfo::Ori(ori) => {
-                    if let Ok(key) = Self::get_cek(ori, private_key, cert) {
-                        let result = CeaManager::decrypt(&key, mac, &ct, Some(&aad))?;
-                        return Ok(result);
-                    } else {
-                        continue;
-                    }
-                }
-                _ => {
-                    continue;
-                }
-            };
+            if let Some(key) = get_cek(ri, cek_len) {
+                let result = CeaManager::decrypt(&key, mac, &ct, Some(&aad))?;
+                return Ok(result);
+            }
         }
         Err(QuantCryptError::InvalidEnvelopedData)
     }
 
-    fn decrypt_enveloped_kemri(
+    fn decrypt_enveloped(
         enveloped_data_der: &[u8],
-        private_key: &PrivateKey,
-        cert: &Certificate,
+        get_cek: &dyn Fn(&RecipientInfo, usize) -> Option<Vec<u8>>,
     ) -> Result<Vec<u8>> {
         let ed = EnvelopedData::from_der(enveloped_data_der)
             .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
@@ -168,6 +340,8 @@ impl CmsUtil {
             .to_der()
             .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
 
+        let cek_len = Self::get_cek_len(&ed.encrypted_content.content_enc_alg.oid)?;
+
         let params = match ed.encrypted_content.content_enc_alg.parameters {
             Some(p) => p,
             None => {
@@ -183,26 +357,20 @@ impl CmsUtil {
         let iv: &[u8] = os_iv.as_bytes();
 
         for ri in ed.recip_infos.0.iter() {
-            match ri {
-                RecipientInfo::Ori(ori) => {
-                    if let Ok(key) = Self::get_cek(ori, private_key, cert) {
-                        let result = CeaManager::decrypt(&key, iv, &ct, None)?;
-                        return Ok(result);
-                    } else {
-                        continue;
-                    }
-                }
-                _ => continue,
+            if let Some(key) = get_cek(ri, cek_len) {
+                let result = CeaManager::decrypt(&key, iv, &ct, None)?;
+                return Ok(result);
             }
         }
 
         Err(QuantCryptError::InvalidEnvelopedData)
     }
 
-    pub fn decrypt_kemri(
+    /// Decrypt an EnvelopedData or AuthEnvelopedData with the first recipient
+    /// info that yields a content encryption key
+    fn decrypt(
         data: &[u8],
-        private_key: &PrivateKey,
-        cert: &Certificate,
+        get_cek: &dyn Fn(&RecipientInfo, usize) -> Option<Vec<u8>>,
     ) -> Result<Vec<u8>> {
         let content_info: ContentInfo =
             ContentInfo::from_der(data).map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
@@ -213,17 +381,91 @@ impl CmsUtil {
             .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
 
         if oid == ID_ENVELOPED_DATA {
-            Self::decrypt_enveloped_kemri(&enveloped_data, private_key, cert)
+            Self::decrypt_enveloped(&enveloped_data, get_cek)
         } else if oid == ID_CT_AUTH_ENVELOPED_DATA {
-            Self::decrypt_auth_enveloped_kemri(&enveloped_data, private_key, cert)
+            Self::decrypt_auth_enveloped(&enveloped_data, get_cek)
         } else {
             Err(QuantCryptError::InvalidEnvelopedData)
         }
     }
 
+    pub fn decrypt_kemri(
+        data: &[u8],
+        private_key: &PrivateKey,
+        cert: &Certificate,
+    ) -> Result<Vec<u8>> {
+        Self::decrypt(data, &|ri, _| match ri {
+            RecipientInfo::Ori(ori) => Self::get_cek(ori, private_key, cert).ok(),
+            _ => None,
+        })
+    }
+
+    /// Get the content encryption key (CEK) from the first KEM recipient info that
+    /// belongs to a recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `recip_infos` - The recipient infos of an EnvelopedData or AuthEnvelopedData
+    /// * `private_key` - The private key of the recipient
+    /// * `cert` - The certificate of the recipient
+    ///
+    /// # Returns
+    ///
+    /// The CEK as bytes
+    pub(crate) fn get_cek_kemri(
+        recip_infos: &RecipientInfos,
+        private_key: &PrivateKey,
+        cert: &Certificate,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        recip_infos
+            .0
+            .iter()
+            .find_map(|ri| match ri {
+                RecipientInfo::Ori(ori) => Self::get_cek(ori, private_key, cert).ok(),
+                _ => None,
+            })
+            .map(Zeroizing::new)
+            .ok_or(QuantCryptError::InvalidEnvelopedData)
+    }
+
+    /// Decrypt an EnvelopedData or AuthEnvelopedData as a key transport (RSA) recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The DER encoded ContentInfo
+    /// * `private_key` - The RSA private key of the recipient, as PKCS #8 or PKCS #1 DER
+    /// * `cert` - The certificate of the recipient
+    ///
+    /// # Returns
+    ///
+    /// The decrypted content
+    pub fn decrypt_ktri(data: &[u8], private_key: &[u8], cert: &Certificate) -> Result<Vec<u8>> {
+        let private_key = Self::parse_rsa_private_key(private_key)?;
+        Self::decrypt_ktri_with_key(data, &private_key, cert)
+    }
+
+    /// Decrypt an EnvelopedData or AuthEnvelopedData as a key transport (RSA) recipient
+    /// whose private key has already been parsed
+    pub(crate) fn decrypt_ktri_with_key(
+        data: &[u8],
+        private_key: &RsaPrivateKey,
+        cert: &Certificate,
+    ) -> Result<Vec<u8>> {
+        Self::decrypt(data, &|ri, cek_len| match ri {
+            RecipientInfo::Ktri(ktri) => Self::get_cek_ktri(ktri, private_key, cert, cek_len).ok(),
+            _ => None,
+        })
+    }
+
+    /// Parse an RSA private key from PKCS #8 or PKCS #1 DER
+    pub(crate) fn parse_rsa_private_key(private_key: &[u8]) -> Result<RsaPrivateKey> {
+        RsaPrivateKey::from_pkcs8_der(private_key)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_der(private_key))
+            .map_err(|_| QuantCryptError::InvalidPrivateKey)
+    }
+
     /// Create a content-type attribute according to
     /// [RFC 5652 § 11.1](https://datatracker.ietf.org/doc/html/rfc5652#section-11.1)
-    #[allow(dead_code)]
     pub(crate) fn create_content_type_attribute(
         content_type: ObjectIdentifier,
     ) -> Result<Attribute> {
@@ -245,7 +487,6 @@ impl CmsUtil {
 
     /// Create a message digest attribute according to
     /// [RFC 5652 § 11.2](https://datatracker.ietf.org/doc/html/rfc5652#section-11.2)
-    #[allow(dead_code)]
     pub(crate) fn create_message_digest_attribute(message_digest: &[u8]) -> Result<Attribute> {
         let message_digest_der =
             OctetStringRef::new(message_digest).map_err(|_| QuantCryptError::InvalidAttribute)?;
@@ -263,8 +504,60 @@ impl CmsUtil {
         };
         Ok(attribute)
     }
+
+    /// Create an attribute with a single DER encoded value
+    fn create_single_value_attribute(oid: ObjectIdentifier, value_der: &[u8]) -> Result<Attribute> {
+        let value =
+            AttributeValue::from_der(value_der).map_err(|_| QuantCryptError::InvalidAttribute)?;
+        let mut values = SetOfVec::new();
+        values
+            .insert(value)
+            .map_err(|_| QuantCryptError::InvalidAttribute)?;
+        Ok(Attribute { oid, values })
+    }
+
+    /// Create a signing-time attribute according to
+    /// [RFC 5652 § 11.3](https://datatracker.ietf.org/doc/html/rfc5652#section-11.3)
+    pub(crate) fn create_signing_time_attribute(time: SystemTime) -> Result<Attribute> {
+        // UTCTime is used until 2049, GeneralizedTime afterwards
+        let time = Time::try_from(time)
+            .and_then(|t| t.to_der())
+            .map_err(|_| QuantCryptError::InvalidAttribute)?;
+        Self::create_single_value_attribute(const_oid::db::rfc5911::ID_SIGNING_TIME, &time)
+    }
+
+    /// Create a binary-signing-time attribute according to
+    /// [RFC 6019 § 2](https://datatracker.ietf.org/doc/html/rfc6019#section-2)
+    pub(crate) fn create_binary_signing_time_attribute(time: SystemTime) -> Result<Attribute> {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| QuantCryptError::InvalidAttribute)?
+            .as_secs();
+        let binary_time = Uint::new(&seconds.to_be_bytes())
+            .and_then(|t| t.to_der())
+            .map_err(|_| QuantCryptError::InvalidAttribute)?;
+        Self::create_single_value_attribute(
+            const_oid::db::rfc6268::ID_AA_BINARY_SIGNING_TIME,
+            &binary_time,
+        )
+    }
+
+    /// Create a commitment-type-indication attribute according to
+    /// [RFC 5126 § 5.11.1](https://datatracker.ietf.org/doc/html/rfc5126#section-5.11.1)
+    /// without qualifiers
+    pub(crate) fn create_commitment_type_attribute(
+        commitment_type: ObjectIdentifier,
+    ) -> Result<Attribute> {
+        let oid = commitment_type
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidAttribute)?;
+        let indication = Any::new(Tag::Sequence, oid)
+            .and_then(|i| i.to_der())
+            .map_err(|_| QuantCryptError::InvalidAttribute)?;
+        Self::create_single_value_attribute(ID_AA_ETS_COMMITMENT_TYPE, &indication)
+    }
 }
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
     use crate::certificates::Certificate;
 
@@ -280,7 +573,7 @@ mod tests {
 
         let ee = Certificate::from_der(ee_bytes).unwrap();
         let result = ta.verify_child(&ee).unwrap();
-        assert_eq!(result, true);
+        assert!(result);
 
         let enveloped = include_bytes!("../../test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_kemri_id-alg-hkdf-with-sha256_ukm.der");
 
@@ -0,0 +1,270 @@
+use std::any::Any;
+use std::collections::HashMap;
+
+use const_oid::db::rfc5911::{
+    ID_CT_AUTH_DATA, ID_CT_AUTH_ENVELOPED_DATA, ID_CT_FIRMWARE_PACKAGE, ID_DATA, ID_DIGESTED_DATA,
+    ID_ENCRYPTED_DATA, ID_ENVELOPED_DATA, ID_SIGNED_DATA,
+};
+use spki::ObjectIdentifier;
+
+use crate::cms::asn1::compressed_data::ID_CT_COMPRESSED_DATA;
+use crate::cms::asn1::compressed_data_content::CompressedDataContent;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The content type of a time-stamp token, id-ct-TSTInfo (RFC 3161)
+const ID_CT_TST_INFO: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.1.4");
+
+/// A type-erased parser for the content of a content type
+type ContentParser = Box<dyn Fn(&[u8]) -> Result<Box<dyn Any + Send>> + Send + Sync>;
+
+/// A registered content type
+struct ContentTypeEntry {
+    /// A human readable name
+    name: String,
+    /// The parser for the content, if any
+    parser: Option<ContentParser>,
+}
+
+/// A registry of the content types (eContentType / encrypted content type) an application accepts
+///
+/// The registry starts out with the content types defined by the CMS RFCs, and
+/// callers can register their own (e.g. EDI, time-stamping or firmware package
+/// content types), optionally with a parser that turns the content octets into
+/// a typed value.
+///
+/// Builders label content with a registered type through `content_type`; after
+/// decryption or parsing, `check` rejects content types the application does not
+/// know, and `parse` hands the content to the registered parser.
+///
+/// # Example
+/// ```
+/// use quantcrypt::content::ContentTypeRegistry;
+/// use quantcrypt::content::ObjectIdentifier;
+///
+/// let edi = ObjectIdentifier::new("1.3.6.1.4.1.22554.5.7").unwrap();
+///
+/// let mut registry = ContentTypeRegistry::default();
+/// registry.register_with_parser(edi, "edi-message", |content| {
+///     Ok(String::from_utf8_lossy(content).split('\'').count())
+/// });
+///
+/// assert!(registry.check(&edi).is_ok());
+/// let segments: usize = registry.parse(&edi, b"UNA:+.? 'UNB+UNOC:3'").unwrap();
+/// assert_eq!(segments, 3);
+/// ```
+pub struct ContentTypeRegistry {
+    entries: HashMap<ObjectIdentifier, ContentTypeEntry>,
+}
+
+impl Default for ContentTypeRegistry {
+    /// Create a registry with the content types defined by the CMS RFCs
+    ///
+    /// id-data is parsed to its raw octets (`Vec<u8>`) and id-ct-compressedData
+    /// to a `CompressedDataContent`.
+    fn default() -> Self {
+        let mut registry = ContentTypeRegistry::new();
+        registry
+            .register_with_parser(ID_DATA, "data", |content| Ok(content.to_vec()))
+            .register(ID_SIGNED_DATA, "signedData")
+            .register(ID_ENVELOPED_DATA, "envelopedData")
+            .register(ID_DIGESTED_DATA, "digestedData")
+            .register(ID_ENCRYPTED_DATA, "encryptedData")
+            .register(ID_CT_AUTH_DATA, "authData")
+            .register(ID_CT_AUTH_ENVELOPED_DATA, "authEnvelopedData")
+            .register_with_parser(ID_CT_COMPRESSED_DATA, "compressedData", |content| {
+                CompressedDataContent::from_encapsulated(content)
+            })
+            .register(ID_CT_TST_INFO, "TSTInfo")
+            .register(ID_CT_FIRMWARE_PACKAGE, "firmwarePackage");
+        registry
+    }
+}
+
+impl ContentTypeRegistry {
+    /// Create an empty registry
+    ///
+    /// # Returns
+    ///
+    /// A registry without any content types. Use `ContentTypeRegistry::default()`
+    /// to start with the content types defined by the CMS RFCs.
+    pub fn new() -> ContentTypeRegistry {
+        ContentTypeRegistry {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Register a content type without a parser
+    ///
+    /// Registering a content type again replaces the previous registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The OID of the content type
+    /// * `name` - A human readable name
+    ///
+    /// # Returns
+    ///
+    /// The registry, for chaining
+    pub fn register(&mut self, content_type: ObjectIdentifier, name: &str) -> &mut Self {
+        self.entries.insert(
+            content_type,
+            ContentTypeEntry {
+                name: name.to_string(),
+                parser: None,
+            },
+        );
+        self
+    }
+
+    /// Register a content type with a parser for its content
+    ///
+    /// Registering a content type again replaces the previous registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The OID of the content type
+    /// * `name` - A human readable name
+    /// * `parser` - Parses the content octets into a typed value
+    ///
+    /// # Returns
+    ///
+    /// The registry, for chaining
+    pub fn register_with_parser<T, F>(
+        &mut self,
+        content_type: ObjectIdentifier,
+        name: &str,
+        parser: F,
+    ) -> &mut Self
+    where
+        T: Any + Send,
+        F: Fn(&[u8]) -> Result<T> + Send + Sync + 'static,
+    {
+        let parser: ContentParser =
+            Box::new(move |content| parser(content).map(|v| Box::new(v) as Box<dyn Any + Send>));
+        self.entries.insert(
+            content_type,
+            ContentTypeEntry {
+                name: name.to_string(),
+                parser: Some(parser),
+            },
+        );
+        self
+    }
+
+    /// Check if a content type is registered
+    pub fn is_registered(&self, content_type: &ObjectIdentifier) -> bool {
+        self.entries.contains_key(content_type)
+    }
+
+    /// Get the name of a registered content type
+    pub fn get_name(&self, content_type: &ObjectIdentifier) -> Option<String> {
+        self.entries.get(content_type).map(|e| e.name.clone())
+    }
+
+    /// Check that a content type is registered
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The OID of the content type
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnknownContentType` if the content type is not registered
+    pub fn check(&self, content_type: &ObjectIdentifier) -> Result<()> {
+        if self.is_registered(content_type) {
+            Ok(())
+        } else {
+            Err(QuantCryptError::UnknownContentType)
+        }
+    }
+
+    /// Parse content with the parser registered for its content type
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The OID of the content type
+    /// * `content` - The content octets
+    ///
+    /// # Returns
+    ///
+    /// The parsed content
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnknownContentType` if the content type is not registered or
+    /// has no parser, `QuantCryptError::InvalidContent` if the parser produces another
+    /// type than `T`, and any error returned by the parser
+    pub fn parse<T: Any>(&self, content_type: &ObjectIdentifier, content: &[u8]) -> Result<T> {
+        let parser = self
+            .entries
+            .get(content_type)
+            .and_then(|e| e.parser.as_ref())
+            .ok_or(QuantCryptError::UnknownContentType)?;
+        let parsed: Box<dyn Any> = parser(content)?;
+        parsed
+            .downcast::<T>()
+            .map(|v| *v)
+            .map_err(|_| QuantCryptError::InvalidContent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_registry() {
+        let mut registry = ContentTypeRegistry::default();
+        assert!(registry.check(&ID_DATA).is_ok());
+        assert_eq!(registry.get_name(&ID_CT_TST_INFO).unwrap(), "TSTInfo");
+        let data: Vec<u8> = registry.parse(&ID_DATA, b"abc").unwrap();
+        assert_eq!(data, b"abc");
+
+        let compressed = CompressedDataContent::encapsulate(b"abc", None).unwrap();
+        let cdc: CompressedDataContent =
+            registry.parse(&ID_CT_COMPRESSED_DATA, &compressed).unwrap();
+        assert_eq!(cdc.get_content(), b"abc");
+
+        // Custom content types
+        let firmware = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.22554.5.8");
+        assert_eq!(
+            registry.check(&firmware),
+            Err(QuantCryptError::UnknownContentType)
+        );
+        registry.register_with_parser(firmware, "firmware", |content| {
+            if content.len() < 4 {
+                return Err(QuantCryptError::InvalidContent);
+            }
+            Ok(u32::from_be_bytes([
+                content[0], content[1], content[2], content[3],
+            ]))
+        });
+        assert!(registry.check(&firmware).is_ok());
+        assert_eq!(
+            registry.parse::<u32>(&firmware, &[0, 0, 1, 0]).unwrap(),
+            256
+        );
+        assert_eq!(
+            registry.parse::<u32>(&firmware, &[0]),
+            Err(QuantCryptError::InvalidContent)
+        );
+        // Wrong type requested
+        assert_eq!(
+            registry.parse::<String>(&firmware, &[0, 0, 1, 0]),
+            Err(QuantCryptError::InvalidContent)
+        );
+
+        // Registered without a parser
+        registry.register(firmware, "firmware");
+        assert!(registry.check(&firmware).is_ok());
+        assert_eq!(
+            registry.parse::<u32>(&firmware, &[0, 0, 1, 0]),
+            Err(QuantCryptError::UnknownContentType)
+        );
+
+        let empty = ContentTypeRegistry::new();
+        assert!(empty.check(&ID_DATA).is_err());
+    }
+}
@@ -3,6 +3,7 @@ use std::fs;
 use std::path::Path;
 
 use crate::cms::cert_store_trait::CertificateStore;
+use crate::keys::KeyIdMethod;
 use crate::{certificates::Certificate, QuantCryptError};
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
@@ -88,6 +89,30 @@ impl DirectoryCertificateStore {
         })
     }
 
+    /// Find a certificate by the key identifier of its public key.
+    ///
+    /// The key identifier is computed from the certificate's public key, so this
+    /// also finds certificates without a Subject Key Identifier extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method the key identifier was computed with.
+    /// * `key_id` - The key identifier.
+    ///
+    /// # Returns
+    ///
+    /// The first matching certificate, if any.
+    pub fn find_by_key_id(&self, method: KeyIdMethod, key_id: &[u8]) -> Option<Certificate> {
+        self.ta_certificates
+            .iter()
+            .chain(self.ee_certificates.iter())
+            .find(|cert| {
+                cert.get_key_id(method)
+                    .map_or(false, |cert_key_id| cert_key_id == key_id)
+            })
+            .cloned()
+    }
+
     /// Find the parent certificate of the given certificate.
     fn find_parent(&self, cert: &Certificate) -> Option<Certificate> {
         // First check if the cert is valid
@@ -317,5 +342,15 @@ mod tests {
         );
         let ee_cert = auth.find(ee_ri).unwrap();
         assert_eq!(ee_cert.get_subject().to_string(), "CN=ee.sub.example.com");
+
+        // Test finding the end-entity certificate, by RFC 7093 key identifier
+        let key_id = cert_ee.get_key_id(KeyIdMethod::SpkiSha256).unwrap();
+        let ee_cert = auth
+            .find_by_key_id(KeyIdMethod::SpkiSha256, &key_id)
+            .unwrap();
+        assert_eq!(ee_cert.get_subject().to_string(), "CN=ee.sub.example.com");
+        assert!(auth
+            .find_by_key_id(KeyIdMethod::Sha256Truncated, &key_id)
+            .is_none());
     }
 }
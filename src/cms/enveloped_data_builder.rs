@@ -9,17 +9,28 @@ use cms::builder::{
 };
 use cms::content_info::ContentInfo;
 use cms::enveloped_data::{OriginatorInfo, UserKeyingMaterial};
-use const_oid::db::rfc5911::{ID_CT_AUTH_ENVELOPED_DATA, ID_ENVELOPED_DATA};
+use const_oid::db::rfc5911::{
+    ID_CT_AUTH_ENVELOPED_DATA, ID_DATA, ID_ENVELOPED_DATA, ID_SIGNED_DATA,
+};
+use const_oid::db::rfc5912::{ID_RSAES_OAEP, RSA_ENCRYPTION};
 use der::{Decode, Encode};
-use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
+use spki::ObjectIdentifier;
 use x509_cert::attr::{Attribute, Attributes};
 
 use crate::{
     cea::common::cea_type::CeaType, certificates::Certificate, kem::kem_manager, QuantCryptError,
 };
 
+use crate::cms::asn1::compressed_data::ID_CT_COMPRESSED_DATA;
+use crate::cms::asn1::compressed_data_content::CompressedDataContent;
 use crate::cms::asn1::kemri_builder::KemRecipientInfoBuilder;
+use crate::cms::asn1::ktri_builder::RsaOaepRecipientInfoBuilder;
+use crate::cms::cms_util::CmsUtil;
+use crate::cms::signed_data_builder::{SignedDataBuilder, SignerAttributes};
+use crate::kem::common::kem_info::OaepParams;
+use crate::keys::PrivateKey;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 
 use super::asn1::auth_enveloped_data_builder::{
     AuthEnvelopedDataBuilder, ContentEncryptionAlgorithmAead,
@@ -54,6 +65,8 @@ pub struct EnvelopedDataBuilder<'a> {
     kek_builders: Vec<KekRecipientInfoBuilder>,
     /// The KeyTrans recipient info builders
     ktri_builders: Vec<KeyTransRecipientInfoBuilder<'a, ChaCha20Rng>>,
+    /// The RSA-OAEP KeyTrans recipient info builders
+    rsa_oaep_builders: Vec<RsaOaepRecipientInfoBuilder>,
     /// The KeyAgree recipient info builders
     kari_builders: Vec<KeyAgreeRecipientInfoBuilder>,
     /// The Password recipient info builders
@@ -62,6 +75,14 @@ pub struct EnvelopedDataBuilder<'a> {
     ori_builders: Vec<OtherRecipientInfoBuilder>,
     /// Whether this is an AuthEnvelopedData
     is_auth_enveloped: bool,
+    /// Whether the content is compressed before it is encrypted
+    compress_content: bool,
+    /// The content type of the content, or None for id-data
+    content_type: Option<ObjectIdentifier>,
+    /// The certificate and private key of the sender, for signed-then-encrypted content
+    sender: Option<(Certificate, &'a PrivateKey)>,
+    /// The RNG of the content encryption key and the recipients, or None to seed one
+    rng: Option<SharedRng>,
 }
 
 impl<'a> EnvelopedDataBuilder<'a> {
@@ -93,10 +114,15 @@ impl<'a> EnvelopedDataBuilder<'a> {
             kemri_builders: Vec::new(),
             kek_builders: Vec::new(),
             ktri_builders: Vec::new(),
+            rsa_oaep_builders: Vec::new(),
             kari_builders: Vec::new(),
             pwri_builders: Vec::new(),
             ori_builders: Vec::new(),
             is_auth_enveloped,
+            compress_content: false,
+            content_type: None,
+            sender: None,
+            rng: None,
         })
     }
 
@@ -172,6 +198,89 @@ impl<'a> EnvelopedDataBuilder<'a> {
         Ok(self)
     }
 
+    /// Set the content type of the content
+    ///
+    /// By default the content is labelled as id-data. Custom content types, e.g.
+    /// those registered in a `ContentTypeRegistry`, are recorded as the encrypted
+    /// content type, and for AuthEnvelopedData also in a content-type attribute.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The OID of the content type
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn content_type(&mut self, content_type: ObjectIdentifier) -> Result<&mut Self> {
+        self.content_type = Some(content_type);
+        Ok(self)
+    }
+
+    /// Compress the content before it is encrypted
+    ///
+    /// The content is wrapped in a zlib CompressedData (RFC 3274), which becomes the
+    /// encrypted content. `EnvelopedDataContent` and `AuthEnvelopedDataContent` unwrap
+    /// it transparently when decrypting.
+    ///
+    /// # Arguments
+    ///
+    /// * `compress` - Whether to compress the content
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn compress_content(&mut self, compress: bool) -> Result<&mut Self> {
+        self.compress_content = compress;
+        Ok(self)
+    }
+
+    /// Sign the content as the sender before it is encrypted
+    ///
+    /// The content is wrapped in a SignedData with a single signer (RFC 5652
+    /// sign-then-encrypt), so recipients can verify its origin when they decrypt it
+    /// instead of in a separate signature pass. Without a sender the message is
+    /// anonymous.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the sender
+    /// * `private_key` - The private key of the sender
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if the private key does not belong to the certificate
+    pub fn sender(&mut self, cert: &Certificate, private_key: &'a PrivateKey) -> Result<&mut Self> {
+        if cert.get_public_key_oid() != private_key.get_oid() {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        self.sender = Some((cert.clone(), private_key));
+        Ok(self)
+    }
+
+    /// Draw all the randomness of the message from a caller-supplied RNG
+    ///
+    /// The content encryption key, the IV or nonce, the encapsulations of the KEM
+    /// recipients, the OAEP seeds of the RSA-OAEP recipients and the signature of
+    /// the sender are drawn from this RNG. Recipients added with `ktri_recipient`,
+    /// `kari_recipient`, `kek_recipient`, `pwri_recipient` and `ori_recipient` are
+    /// built by the caller, with their own RNG. See `SharedRng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The RNG
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn rng(&mut self, rng: SharedRng) -> Result<&mut Self> {
+        self.rng = Some(rng);
+        Ok(self)
+    }
+
     /// Add a KEM recipient
     ///
     /// # Arguments
@@ -235,6 +344,36 @@ impl<'a> EnvelopedDataBuilder<'a> {
         Ok(self)
     }
 
+    /// Add an RSA-OAEP key transport recipient
+    ///
+    /// This is for recipients that only have an RSA certificate, so that a single
+    /// message can target both them and KEM recipients.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the recipient, with an rsaEncryption or id-RSAES-OAEP key
+    /// * `params` - The RSA-OAEP parameters
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn rsa_oaep_recipient(
+        &mut self,
+        cert: &Certificate,
+        params: &OaepParams,
+    ) -> Result<&mut Self> {
+        let key_oid = cert.get_public_key_oid();
+        if !cert.is_key_encipherment_enabled()
+            || (key_oid != RSA_ENCRYPTION.to_string() && key_oid != ID_RSAES_OAEP.to_string())
+        {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+
+        self.rsa_oaep_builders
+            .push(RsaOaepRecipientInfoBuilder::new(cert, params.clone()));
+        Ok(self)
+    }
+
     /// Add a KeyAgree recipient
     ///
     /// # Arguments
@@ -327,6 +466,12 @@ impl<'a> EnvelopedDataBuilder<'a> {
                 .map_err(|_| QuantCryptError::Unknown)?;
         }
 
+        for rsa_oaep_builder in self.rsa_oaep_builders {
+            builder
+                .add_recipient_info(rsa_oaep_builder)
+                .map_err(|_| QuantCryptError::Unknown)?;
+        }
+
         for kari_builder in self.kari_builders {
             builder
                 .add_recipient_info(kari_builder)
@@ -345,12 +490,17 @@ impl<'a> EnvelopedDataBuilder<'a> {
                 .map_err(|_| QuantCryptError::Unknown)?;
         }
 
-        let mut rng = ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
 
-        let enveloped_data = builder
+        let mut enveloped_data = builder
             .build_with_rng(&mut rng)
             .map_err(|_| QuantCryptError::Unknown)?;
 
+        // The RustCrypto builder always uses id-data as the encrypted content type
+        if let Some(content_type) = self.content_type {
+            enveloped_data.encrypted_content.content_type = content_type;
+        }
+
         enveloped_data
             .to_der()
             .map_err(|_| QuantCryptError::Unknown)
@@ -371,7 +521,7 @@ impl<'a> EnvelopedDataBuilder<'a> {
         // https://github.com/codespree/quantcrypt/issues/1
 
         let mut builder = AuthEnvelopedDataBuilder::new(
-            None,
+            self.content_type,
             self.originator_info.clone(),
             &self.plaintext,
             cea,
@@ -398,6 +548,12 @@ impl<'a> EnvelopedDataBuilder<'a> {
                 .map_err(|_| QuantCryptError::Unknown)?;
         }
 
+        for rsa_oaep_builder in self.rsa_oaep_builders {
+            builder
+                .add_recipient_info(rsa_oaep_builder)
+                .map_err(|_| QuantCryptError::Unknown)?;
+        }
+
         for kari_builder in self.kari_builders {
             builder
                 .add_recipient_info(kari_builder)
@@ -416,7 +572,8 @@ impl<'a> EnvelopedDataBuilder<'a> {
                 .map_err(|_| QuantCryptError::Unknown)?;
         }
 
-        let enveloped_data = builder.build()?;
+        let enveloped_data =
+            builder.build_with_rng(&mut ManagerRng::for_operation(self.rng.as_ref()))?;
 
         enveloped_data
             .to_der()
@@ -428,13 +585,57 @@ impl<'a> EnvelopedDataBuilder<'a> {
     /// # Returns
     ///
     /// The DER bytes of the EnvelopedData or AuthEnvelopedData
-    pub fn build(self) -> Result<Vec<u8>> {
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::EmptyContent` if no content is set, and
+    /// `QuantCryptError::NotImplemented` if an RNG is set and a KEM recipient or the
+    /// sender uses an algorithm provided by a registered backend
+    pub fn build(mut self) -> Result<Vec<u8>> {
         let is_auth_enveloped = self.is_auth_enveloped;
 
         if self.plaintext.is_empty() {
             return Err(QuantCryptError::EmptyContent);
         }
 
+        if let Some(rng) = &self.rng {
+            for kemri_builder in self.kemri_builders.iter_mut() {
+                kemri_builder.kem.set_rng(rng.clone())?;
+            }
+            for rsa_oaep_builder in self.rsa_oaep_builders.iter_mut() {
+                rsa_oaep_builder.rng = Some(rng.clone());
+            }
+        }
+
+        if let Some((cert, private_key)) = self.sender.take() {
+            let mut builder = SignedDataBuilder::new();
+            if let Some(rng) = &self.rng {
+                builder.rng(rng.clone())?;
+            }
+            builder
+                .content(&self.plaintext)?
+                .content_type(self.content_type.unwrap_or(ID_DATA))?
+                .signer(&cert, private_key, &SignerAttributes::default())?;
+            let ci = ContentInfo::from_der(&builder.build()?)
+                .map_err(|_| QuantCryptError::InvalidContent)?;
+            self.plaintext = ci.content.to_der().map_err(|_| QuantCryptError::Unknown)?;
+            self.content_type = Some(ID_SIGNED_DATA);
+        }
+
+        if self.compress_content {
+            self.plaintext =
+                CompressedDataContent::encapsulate(&self.plaintext, self.content_type)?;
+            self.content_type = Some(ID_CT_COMPRESSED_DATA);
+        }
+
+        // RFC 5083 requires a content-type attribute when the content type is not id-data
+        if let Some(content_type) = self.content_type {
+            if is_auth_enveloped && content_type != ID_DATA {
+                let attribute = CmsUtil::create_content_type_attribute(content_type)?;
+                self.auth_attribute(&attribute)?;
+            }
+        }
+
         let data = if !self.is_auth_enveloped {
             self.build_enveloped()?
         } else {
@@ -470,11 +671,14 @@ impl<'a> EnvelopedDataBuilder<'a> {
     }
 }
 
-#[cfg(test)]
+// Every content encryption algorithm needs OpenSSL
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
     use std::collections::HashMap;
     use std::vec;
 
+    use cms::enveloped_data::RecipientIdentifier;
+
     use crate::content::{AuthEnvelopedDataContent, EnvelopedDataContent};
     use crate::dsa::common::config::oids::Oid;
     use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
@@ -491,9 +695,316 @@ mod tests {
     use x509_cert::builder::Profile;
 
     use crate::{kdf::common::kdf_type::KdfType, wrap::api::WrapType};
+    use rand::SeedableRng;
 
     use super::*;
 
+    /// Create a self-signed RSA certificate for key transport, and its PKCS #8 private key
+    fn rsa_recipient() -> (Certificate, Vec<u8>) {
+        use openssl::x509::extension::{KeyUsage, SubjectKeyIdentifier};
+
+        let rsa = openssl::rsa::Rsa::generate(2048).unwrap();
+        let pkey = openssl::pkey::PKey::from_rsa(rsa).unwrap();
+        let mut name = openssl::x509::X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "rsa.test.com").unwrap();
+        let name = name.build();
+
+        let mut builder = openssl::x509::X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&pkey).unwrap();
+        let not_before = openssl::asn1::Asn1Time::days_from_now(0).unwrap();
+        let not_after = openssl::asn1::Asn1Time::days_from_now(365).unwrap();
+        builder.set_not_before(&not_before).unwrap();
+        builder.set_not_after(&not_after).unwrap();
+        let skid = SubjectKeyIdentifier::new()
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(skid).unwrap();
+        let key_usage = KeyUsage::new().key_encipherment().build().unwrap();
+        builder.append_extension(key_usage).unwrap();
+        builder
+            .sign(&pkey, openssl::hash::MessageDigest::sha256())
+            .unwrap();
+
+        let cert = Certificate::from_der(&builder.build().to_der().unwrap()).unwrap();
+        (cert, pkey.private_key_to_pkcs8().unwrap())
+    }
+
+    #[test]
+    fn test_enveloped_data_ktri() {
+        use crate::cms::asn1::ktri_builder::oaep_params_from_algorithm_identifier;
+        use crate::kem::common::kem_info::OaepHash;
+        use cms::enveloped_data::{EnvelopedData, RecipientInfo};
+        use rsa::pkcs1::DecodeRsaPublicKey;
+
+        let plaintext = b"Hello, World!".to_vec();
+        let (rsa_cert, rsa_sk) = rsa_recipient();
+        let kem_cert = Certificate::from_der(include_bytes!(
+            "../../test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der"
+        ))
+        .unwrap();
+        let kem_sk = PrivateKey::from_der(include_bytes!(
+            "../../test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der"
+        ))
+        .unwrap();
+        let params = OaepParams {
+            digest: OaepHash::Sha384,
+            mgf_hash: OaepHash::Sha256,
+            label: None,
+        };
+
+        // A KEM certificate is not an RSA recipient
+        let mut builder = EnvelopedDataBuilder::new(CeaType::Aes256CbcPad, false).unwrap();
+        assert!(matches!(
+            builder.rsa_oaep_recipient(&kem_cert, &params),
+            Err(QuantCryptError::InvalidCertificate)
+        ));
+
+        // A message for both an RSA and a KEM recipient
+        for (cea_type, is_auth_enveloped) in
+            [(CeaType::Aes256CbcPad, false), (CeaType::Aes256Gcm, true)]
+        {
+            let mut builder = EnvelopedDataBuilder::new(cea_type, is_auth_enveloped).unwrap();
+            builder
+                .content(&plaintext)
+                .unwrap()
+                .rsa_oaep_recipient(&rsa_cert, &params)
+                .unwrap()
+                .kem_recipient(&kem_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+                .unwrap();
+            let result = builder.build().unwrap();
+
+            if is_auth_enveloped {
+                let edc = AuthEnvelopedDataContent::from_bytes_for_ktri_recipient(
+                    &result, &rsa_cert, &rsa_sk,
+                )
+                .unwrap();
+                assert_eq!(edc.get_content(), plaintext);
+                let edc = AuthEnvelopedDataContent::from_bytes_for_kem_recipient(
+                    &result, &kem_cert, &kem_sk,
+                )
+                .unwrap();
+                assert_eq!(edc.get_content(), plaintext);
+            } else {
+                let edc = EnvelopedDataContent::from_bytes_for_ktri_recipient(
+                    &result, &rsa_cert, &rsa_sk,
+                )
+                .unwrap();
+                assert_eq!(edc.get_content(), plaintext);
+                let edc =
+                    EnvelopedDataContent::from_bytes_for_kem_recipient(&result, &kem_cert, &kem_sk)
+                        .unwrap();
+                assert_eq!(edc.get_content(), plaintext);
+
+                // The OAEP parameters are encoded explicitly
+                let ci = ContentInfo::from_der(&result).unwrap();
+                let ed = EnvelopedData::from_der(&ci.content.to_der().unwrap()).unwrap();
+                let ktri = ed
+                    .recip_infos
+                    .0
+                    .iter()
+                    .find_map(|ri| match ri {
+                        RecipientInfo::Ktri(ktri) => Some(ktri.clone()),
+                        _ => None,
+                    })
+                    .unwrap();
+                assert_eq!(ktri.key_enc_alg.oid, ID_RSAES_OAEP);
+                assert_eq!(
+                    oaep_params_from_algorithm_identifier(&ktri.key_enc_alg).unwrap(),
+                    params
+                );
+            }
+        }
+
+        // A legacy message with a PKCS #1 v1.5 encrypted key can still be decrypted
+        let spki = &rsa_cert.get_inner().tbs_certificate.subject_public_key_info;
+        let rsa_pk =
+            rsa::RsaPublicKey::from_pkcs1_der(spki.subject_public_key.raw_bytes()).unwrap();
+        let mut rng = ChaCha20Rng::from_seed([1u8; 32]);
+        let legacy = KeyTransRecipientInfoBuilder::new(
+            RecipientIdentifier::SubjectKeyIdentifier(
+                rsa_cert.get_subject_key_identifier().unwrap(),
+            ),
+            cms::builder::KeyEncryptionInfo::Rsa(rsa_pk),
+            &mut rng,
+        )
+        .unwrap();
+        let mut builder = EnvelopedDataBuilder::new(CeaType::Aes128CbcPad, false).unwrap();
+        builder
+            .content(&plaintext)
+            .unwrap()
+            .ktri_recipient(legacy)
+            .unwrap();
+        let result = builder.build().unwrap();
+        let edc = EnvelopedDataContent::from_bytes_for_ktri_recipient(&result, &rsa_cert, &rsa_sk)
+            .unwrap();
+        assert_eq!(edc.get_content(), plaintext);
+    }
+
+    #[test]
+    fn test_enveloped_data_rng() {
+        use crate::kem::common::kem_info::OaepHash;
+
+        let plaintext = b"Hello, World!".to_vec();
+        let (rsa_cert, rsa_sk) = rsa_recipient();
+        let kem_cert = Certificate::from_der(include_bytes!(
+            "../../test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der"
+        ))
+        .unwrap();
+        let kem_sk = PrivateKey::from_der(include_bytes!(
+            "../../test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der"
+        ))
+        .unwrap();
+        let params = OaepParams {
+            digest: OaepHash::Sha256,
+            mgf_hash: OaepHash::Sha256,
+            label: None,
+        };
+
+        for (cea_type, is_auth_enveloped) in
+            [(CeaType::Aes256CbcPad, false), (CeaType::Aes256Gcm, true)]
+        {
+            let build = |seed| {
+                let mut builder =
+                    EnvelopedDataBuilder::new(cea_type.clone(), is_auth_enveloped).unwrap();
+                builder
+                    .rng(SharedRng::new(ChaCha20Rng::from_seed([seed; 32])))
+                    .unwrap()
+                    .content(&plaintext)
+                    .unwrap()
+                    .rsa_oaep_recipient(&rsa_cert, &params)
+                    .unwrap()
+                    .kem_recipient(&kem_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+                    .unwrap();
+                builder.build().unwrap()
+            };
+
+            // The key, the IV or nonce and the recipients are drawn from the RNG
+            let result = build(1);
+            assert_eq!(build(1), result);
+            assert_ne!(build(2), result);
+
+            let content = if is_auth_enveloped {
+                AuthEnvelopedDataContent::from_bytes_for_kem_recipient(&result, &kem_cert, &kem_sk)
+                    .unwrap()
+                    .get_content()
+            } else {
+                EnvelopedDataContent::from_bytes_for_ktri_recipient(&result, &rsa_cert, &rsa_sk)
+                    .unwrap()
+                    .get_content()
+            };
+            assert_eq!(content, plaintext);
+        }
+    }
+
+    #[test]
+    fn test_ktri_implicit_rejection() {
+        use cms::content_info::CmsVersion;
+        use cms::enveloped_data::KeyTransRecipientInfo;
+        use rsa::pkcs8::DecodePrivateKey;
+        use spki::AlgorithmIdentifierOwned;
+
+        let (rsa_cert, rsa_sk) = rsa_recipient();
+        let rsa_sk = rsa::RsaPrivateKey::from_pkcs8_der(&rsa_sk).unwrap();
+        let rsa_pk = rsa_sk.to_public_key();
+        let ktri = |enc_key: Vec<u8>| KeyTransRecipientInfo {
+            version: CmsVersion::V2,
+            rid: RecipientIdentifier::SubjectKeyIdentifier(
+                rsa_cert.get_subject_key_identifier().unwrap(),
+            ),
+            key_enc_alg: AlgorithmIdentifierOwned {
+                oid: RSA_ENCRYPTION,
+                parameters: None,
+            },
+            enc_key: der::asn1::OctetString::new(enc_key).unwrap(),
+        };
+        let mut rng = ChaCha20Rng::from_seed([2u8; 32]);
+
+        // A valid encrypted key of the right length
+        let cek = [7u8; 32];
+        let valid = rsa_pk
+            .encrypt(&mut rng, rsa::Pkcs1v15Encrypt, &cek)
+            .unwrap();
+        let result = CmsUtil::get_cek_ktri(&ktri(valid), &rsa_sk, &rsa_cert, 32).unwrap();
+        assert_eq!(result, cek);
+
+        // A valid encrypted key of the wrong length, and invalid padding, give a
+        // deterministic pseudorandom key instead of an error
+        let short = rsa_pk
+            .encrypt(&mut rng, rsa::Pkcs1v15Encrypt, &cek[..16])
+            .unwrap();
+        let result = CmsUtil::get_cek_ktri(&ktri(short.clone()), &rsa_sk, &rsa_cert, 32).unwrap();
+        assert_eq!(result.len(), 32);
+        assert_ne!(&result[..16], &cek[..16]);
+        assert_eq!(
+            CmsUtil::get_cek_ktri(&ktri(short), &rsa_sk, &rsa_cert, 32).unwrap(),
+            result
+        );
+
+        let garbage = vec![0x5au8; 256];
+        let result = CmsUtil::get_cek_ktri(&ktri(garbage.clone()), &rsa_sk, &rsa_cert, 16).unwrap();
+        assert_eq!(result.len(), 16);
+        assert_eq!(
+            CmsUtil::get_cek_ktri(&ktri(garbage), &rsa_sk, &rsa_cert, 16).unwrap(),
+            result
+        );
+        let other = vec![0x5bu8; 256];
+        assert_ne!(
+            CmsUtil::get_cek_ktri(&ktri(other), &rsa_sk, &rsa_cert, 16).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn test_rewrap_ktri_recipient() {
+        use crate::cms::recipient_rewrapper::RecipientRewrapper;
+
+        let (rsa_cert, rsa_sk) = rsa_recipient();
+        let mut builder = EnvelopedDataBuilder::new(CeaType::Aes256CbcPad, false).unwrap();
+        builder
+            .rsa_oaep_recipient(&rsa_cert, &OaepParams::default())
+            .unwrap()
+            .content(b"RSA-only archive")
+            .unwrap();
+        let data = builder.build().unwrap();
+
+        let kem_cert = Certificate::from_der(include_bytes!(
+            "../../test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der"
+        ))
+        .unwrap();
+        let kem_sk = PrivateKey::from_der(include_bytes!(
+            "../../test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der"
+        ))
+        .unwrap();
+
+        // The key of another RSA recipient is rejected, whatever the padding
+        let (_, other_sk) = rsa_recipient();
+        let mut rewrapper =
+            RecipientRewrapper::new_for_ktri_recipient(&rsa_cert, &other_sk).unwrap();
+        rewrapper
+            .kem_recipient(&kem_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+            .unwrap();
+        assert!(rewrapper.rewrap(&data).is_err());
+
+        let mut rewrapper = RecipientRewrapper::new_for_ktri_recipient(&rsa_cert, &rsa_sk).unwrap();
+        rewrapper
+            .kem_recipient(&kem_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+            .unwrap();
+        let rewrapped = rewrapper.rewrap(&data).unwrap();
+
+        let edc =
+            EnvelopedDataContent::from_bytes_for_kem_recipient(&rewrapped, &kem_cert, &kem_sk)
+                .unwrap();
+        assert_eq!(edc.get_content(), b"RSA-only archive");
+        assert_eq!(edc.get_version(), cms::content_info::CmsVersion::V3);
+        assert!(EnvelopedDataContent::from_bytes_for_ktri_recipient(
+            &rewrapped, &rsa_cert, &rsa_sk
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_enveloped_data_kemri() {
         let plaintext = b"Hello, World!".to_vec();
@@ -556,9 +1067,13 @@ mod tests {
             .key_gen()
             .unwrap();
 
-        let ee_pk2 = PublicKey::new(&KemType::MlKem768BrainpoolP256r1.get_oid(), &ee_pk2).unwrap();
-        let ee_sk2 =
-            PrivateKey::new(&KemType::MlKem768BrainpoolP256r1.get_oid(), &ee_sk2.clone()).unwrap();
+        let ee_pk2 =
+            PublicKey::new(KemType::MlKem768BrainpoolP256r1.get_oid().unwrap(), &ee_pk2).unwrap();
+        let ee_sk2 = PrivateKey::new(
+            KemType::MlKem768BrainpoolP256r1.get_oid().unwrap(),
+            &ee_sk2.clone(),
+        )
+        .unwrap();
         //let spki = SubjectPublicKeyInfo::from_key(ee_pk2).unwrap();
         let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(); // Not before is now
         let serial_no = None; // This will generate a random serial number
@@ -660,9 +1175,13 @@ mod tests {
             .key_gen()
             .unwrap();
 
-        let ee_pk2 = PublicKey::new(&KemType::MlKem768BrainpoolP256r1.get_oid(), &ee_pk2).unwrap();
-        let ee_sk2 =
-            PrivateKey::new(&KemType::MlKem768BrainpoolP256r1.get_oid(), &ee_sk2.clone()).unwrap();
+        let ee_pk2 =
+            PublicKey::new(KemType::MlKem768BrainpoolP256r1.get_oid().unwrap(), &ee_pk2).unwrap();
+        let ee_sk2 = PrivateKey::new(
+            KemType::MlKem768BrainpoolP256r1.get_oid().unwrap(),
+            &ee_sk2.clone(),
+        )
+        .unwrap();
         //let spki = SubjectPublicKeyInfo::from_key(ee_pk2).unwrap();
         let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(); // Not before is now
         let serial_no = None; // This will generate a random serial number
@@ -702,11 +1221,280 @@ mod tests {
         assert_eq!(pt, plaintext);
     }
 
+    #[test]
+    fn test_compressed_content() {
+        use crate::content::{ContentEncryptionAlgorithm, ContentEncryptionAlgorithmAead};
+
+        let recipient_cert =
+            Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let private_key =
+            PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der")
+                .unwrap();
+        let data = "compress me, compress me, compress me. ".repeat(50);
+
+        // Compressed-then-encrypted EnvelopedData
+        let mut builder =
+            EnvelopedDataContent::get_builder(ContentEncryptionAlgorithm::Aes256Cbc).unwrap();
+        builder
+            .kem_recipient(
+                &recipient_cert,
+                &KdfType::HkdfWithSha256,
+                &WrapType::Aes256,
+                None,
+            )
+            .unwrap()
+            .content(data.as_bytes())
+            .unwrap()
+            .compress_content(true)
+            .unwrap();
+        let content = builder.build().unwrap();
+        assert!(content.len() < data.len());
+
+        let ci = ContentInfo::from_der(&content).unwrap();
+        let ed =
+            cms::enveloped_data::EnvelopedData::from_der(&ci.content.to_der().unwrap()).unwrap();
+        assert_eq!(ed.encrypted_content.content_type, ID_CT_COMPRESSED_DATA);
+
+        let edc = EnvelopedDataContent::from_bytes_for_kem_recipient(
+            &content,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(edc.get_content(), data.as_bytes());
+
+        // Compressed-then-encrypted AuthEnvelopedData
+        let mut builder =
+            AuthEnvelopedDataContent::get_builder(ContentEncryptionAlgorithmAead::Aes256Gcm)
+                .unwrap();
+        builder
+            .kem_recipient(
+                &recipient_cert,
+                &KdfType::HkdfWithSha256,
+                &WrapType::Aes256,
+                None,
+            )
+            .unwrap()
+            .content(data.as_bytes())
+            .unwrap()
+            .compress_content(true)
+            .unwrap();
+        let content = builder.build().unwrap();
+
+        let aedc = AuthEnvelopedDataContent::from_bytes_for_kem_recipient(
+            &content,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(aedc.get_content(), data.as_bytes());
+        let attrs = aedc.get_auth_attrs().unwrap();
+        assert_eq!(attrs.len(), 1);
+        assert_eq!(
+            attrs.get(0).unwrap().oid,
+            const_oid::db::rfc5911::ID_CONTENT_TYPE
+        );
+    }
+
+    #[test]
+    fn test_sender_authenticated() {
+        let recipient_cert =
+            Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let private_key =
+            PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der")
+                .unwrap();
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let sender_cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=sender".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let edi = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.22554.5.7");
+
+        // The private key must belong to the certificate
+        let mut builder = EnvelopedDataBuilder::new(CeaType::Aes256Gcm, true).unwrap();
+        assert!(matches!(
+            builder.sender(&sender_cert, &private_key),
+            Err(QuantCryptError::InvalidCertificate)
+        ));
+
+        for (cea_type, is_auth_enveloped) in
+            [(CeaType::Aes256CbcPad, false), (CeaType::Aes256Gcm, true)]
+        {
+            for compress in [false, true] {
+                let mut builder =
+                    EnvelopedDataBuilder::new(cea_type.clone(), is_auth_enveloped).unwrap();
+                builder
+                    .content(b"UNB+UNOC:3")
+                    .unwrap()
+                    .content_type(edi)
+                    .unwrap()
+                    .compress_content(compress)
+                    .unwrap()
+                    .sender(&sender_cert, &sk)
+                    .unwrap()
+                    .kem_recipient(
+                        &recipient_cert,
+                        &KdfType::HkdfWithSha256,
+                        &WrapType::Aes256,
+                        None,
+                    )
+                    .unwrap();
+                let result = builder.build().unwrap();
+
+                let (content, content_type, cert, report) = if is_auth_enveloped {
+                    let edc = AuthEnvelopedDataContent::from_bytes_for_kem_recipient(
+                        &result,
+                        &recipient_cert,
+                        &private_key,
+                    )
+                    .unwrap();
+                    (
+                        edc.get_content(),
+                        edc.get_content_type(),
+                        edc.get_sender_certificate(),
+                        edc.get_sender_verification_report(),
+                    )
+                } else {
+                    let edc = EnvelopedDataContent::from_bytes_for_kem_recipient(
+                        &result,
+                        &recipient_cert,
+                        &private_key,
+                    )
+                    .unwrap();
+                    (
+                        edc.get_content(),
+                        edc.get_content_type(),
+                        edc.get_sender_certificate(),
+                        edc.get_sender_verification_report(),
+                    )
+                };
+                assert_eq!(content, b"UNB+UNOC:3");
+                assert_eq!(content_type, edi);
+                assert_eq!(
+                    cert.unwrap().to_der().unwrap(),
+                    sender_cert.to_der().unwrap()
+                );
+                assert!(report.unwrap().is_valid());
+            }
+        }
+
+        // Without a sender the message is anonymous
+        let mut builder = EnvelopedDataBuilder::new(CeaType::Aes128CbcPad, false).unwrap();
+        builder
+            .content(b"UNB+UNOC:3")
+            .unwrap()
+            .kem_recipient(
+                &recipient_cert,
+                &KdfType::HkdfWithSha256,
+                &WrapType::Aes256,
+                None,
+            )
+            .unwrap();
+        let result = builder.build().unwrap();
+        let edc = EnvelopedDataContent::from_bytes_for_kem_recipient(
+            &result,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert!(edc.get_sender_certificate().is_none());
+    }
+
+    #[test]
+    fn test_custom_content_type() {
+        use crate::content::{
+            ContentEncryptionAlgorithm, ContentEncryptionAlgorithmAead, ContentTypeRegistry,
+        };
+
+        let recipient_cert =
+            Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let private_key =
+            PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der")
+                .unwrap();
+        let edi = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.22554.5.7");
+        let mut registry = ContentTypeRegistry::default();
+
+        let mut builder =
+            EnvelopedDataContent::get_builder(ContentEncryptionAlgorithm::Aes128Cbc).unwrap();
+        builder
+            .kem_recipient(
+                &recipient_cert,
+                &KdfType::HkdfWithSha256,
+                &WrapType::Aes256,
+                None,
+            )
+            .unwrap()
+            .content(b"UNB+UNOC:3")
+            .unwrap()
+            .content_type(edi)
+            .unwrap();
+        let content = builder.build().unwrap();
+
+        let edc = EnvelopedDataContent::from_bytes_for_kem_recipient(
+            &content,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(edc.get_content_type(), edi);
+        assert_eq!(
+            registry.check(&edc.get_content_type()),
+            Err(QuantCryptError::UnknownContentType)
+        );
+        registry.register_with_parser(edi, "edi", |content| {
+            Ok(String::from_utf8_lossy(content).to_string())
+        });
+        let parsed: String = registry
+            .parse(&edc.get_content_type(), &edc.get_content())
+            .unwrap();
+        assert_eq!(parsed, "UNB+UNOC:3");
+
+        // Compressed AuthEnvelopedData keeps the inner content type
+        let mut builder =
+            AuthEnvelopedDataContent::get_builder(ContentEncryptionAlgorithmAead::Aes128Gcm)
+                .unwrap();
+        builder
+            .kem_recipient(
+                &recipient_cert,
+                &KdfType::HkdfWithSha256,
+                &WrapType::Aes256,
+                None,
+            )
+            .unwrap()
+            .content(b"UNB+UNOC:3")
+            .unwrap()
+            .content_type(edi)
+            .unwrap()
+            .compress_content(true)
+            .unwrap();
+        let content = builder.build().unwrap();
+
+        let aedc = AuthEnvelopedDataContent::from_bytes_for_kem_recipient(
+            &content,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(aedc.get_content_type(), edi);
+        assert_eq!(aedc.get_content(), b"UNB+UNOC:3");
+        assert_eq!(aedc.get_auth_attrs().unwrap().len(), 1);
+    }
+
     #[test]
     fn gen_cms_artifacts() {
-        let ta_types = vec![DsaAlgorithm::MlDsa65, DsaAlgorithm::MlDsa87];
+        let ta_types = [DsaAlgorithm::MlDsa65, DsaAlgorithm::MlDsa87];
 
-        let kem_types = vec![
+        let kem_types = [
             vec![
                 KemAlgorithm::MlKem768,
                 KemAlgorithm::MlKem768Rsa2048,
@@ -893,8 +1681,7 @@ mod tests {
             ta_cert.to_der_file(&ta_cert_path).unwrap();
 
             let kem_types = &kem_types[i];
-            for j in 0..kem_types.len() {
-                let kem_type = kem_types[j];
+            for &kem_type in kem_types {
                 let kem_oid = kem_type.get_oid();
                 let kem_friendly_name = kem_type.to_string();
 
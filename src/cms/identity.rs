@@ -0,0 +1,212 @@
+use crate::certificates::Certificate;
+use crate::cms::signed_data_builder::{SignedDataBuilder, SignerAttributes};
+use crate::keys::PrivateKey;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A signing identity: a private key, its certificate and the chain of CA certificates
+///
+/// An identity produces standard signatures in one call, without assembling
+/// a `SignedDataBuilder` by hand. The certificates of the chain are included
+/// in every signature, so verifiers can build the path to their trust anchor.
+/// Signatures carry the content-type, message-digest and signing-time signed
+/// attributes unless `set_signer_attributes` says otherwise.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{CertValidity, CertificateBuilder, Profile};
+/// use quantcrypt::content::{Identity, SignedAttributePolicy, SignedDataContent};
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+/// let cert = CertificateBuilder::new(Profile::Root, None, validity, "CN=signer".to_string(), pk, &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// let identity = Identity::new(cert, sk, Vec::new()).unwrap();
+/// let signature = identity.sign_detached_pkcs7(b"release.tar.gz contents").unwrap();
+///
+/// let sdc = SignedDataContent::from_bytes(&signature).unwrap();
+/// let report = sdc
+///     .verify(Some(b"release.tar.gz contents"), &SignedAttributePolicy::default())
+///     .unwrap();
+/// assert!(report.is_valid());
+/// ```
+pub struct Identity {
+    cert: Certificate,
+    private_key: PrivateKey,
+    chain: Vec<Certificate>,
+    attributes: SignerAttributes,
+}
+
+impl Identity {
+    /// Create a new identity
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the signer
+    /// * `private_key` - The private key of the signer
+    /// * `chain` - The CA certificates above `cert`, up to and optionally including the trust anchor
+    ///
+    /// # Returns
+    ///
+    /// The identity
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if the private key does not belong to the certificate
+    pub fn new(
+        cert: Certificate,
+        private_key: PrivateKey,
+        chain: Vec<Certificate>,
+    ) -> Result<Self> {
+        if cert.get_public_key_oid() != private_key.get_oid() {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        Ok(Identity {
+            cert,
+            private_key,
+            chain,
+            attributes: SignerAttributes::default(),
+        })
+    }
+
+    /// Load an identity from a certificate file and a private key file, in DER or PEM format
+    ///
+    /// # Arguments
+    ///
+    /// * `cert_path` - The path to the certificate of the signer
+    /// * `key_path` - The path to the private key of the signer
+    /// * `chain_paths` - The paths to the CA certificates above the certificate
+    ///
+    /// # Returns
+    ///
+    /// The identity
+    pub fn from_files(cert_path: &str, key_path: &str, chain_paths: &[&str]) -> Result<Self> {
+        let chain = chain_paths
+            .iter()
+            .map(|path| Certificate::from_file(path))
+            .collect::<Result<Vec<_>>>()?;
+        Identity::new(
+            Certificate::from_file(cert_path)?,
+            PrivateKey::from_file(key_path)?,
+            chain,
+        )
+    }
+
+    /// Set the signed and unsigned attributes of the signatures
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - The attributes
+    ///
+    /// # Returns
+    ///
+    /// The identity, for chaining
+    pub fn set_signer_attributes(&mut self, attributes: SignerAttributes) -> &mut Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Get the certificate of the signer
+    pub fn get_certificate(&self) -> &Certificate {
+        &self.cert
+    }
+
+    /// Get the CA certificates above the certificate of the signer
+    pub fn get_chain(&self) -> &[Certificate] {
+        &self.chain
+    }
+
+    /// Create a detached PKCS#7 / CMS signature of data
+    ///
+    /// The signature is a SignedData without the content (RFC 5652), as
+    /// produced by `openssl cms -sign` and expected by `.p7s` consumers. It
+    /// holds the certificate of the signer and the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to sign
+    ///
+    /// # Returns
+    ///
+    /// The DER bytes of the ContentInfo
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::EmptyContent` if the data is empty, and
+    /// `QuantCryptError::PrivateKeyOutsideUsagePeriod` if the private key usage period
+    /// of the certificate doesn't include the current time
+    pub fn sign_detached_pkcs7(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut builder = SignedDataBuilder::new();
+        builder.content(data)?.detached(true)?.signer(
+            &self.cert,
+            &self.private_key,
+            &self.attributes,
+        )?;
+        for cert in &self.chain {
+            builder.certificate(cert)?;
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::cms::asn1::signed_data_content::{SignedAttributePolicy, SignedDataContent};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use const_oid::db::rfc5911::ID_SIGNING_TIME;
+
+    #[test]
+    fn test_sign_detached_pkcs7() {
+        let (ca_pk, ca_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let ca = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=ca".to_string(),
+            ca_pk,
+            &ca_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2030-01-01T00:00:00Z").unwrap();
+        let profile = Profile::Leaf {
+            issuer: ca.get_subject(),
+            enable_key_agreement: false,
+            enable_key_encipherment: false,
+        };
+        let cert =
+            CertificateBuilder::new(profile, None, validity, "CN=signer".to_string(), pk, &ca_sk)
+                .unwrap()
+                .build()
+                .unwrap();
+
+        // The key must match the certificate
+        assert!(Identity::new(cert.clone(), ca_sk, Vec::new()).is_err());
+
+        let identity = Identity::new(cert, sk, vec![ca]).unwrap();
+        assert_eq!(identity.get_chain().len(), 1);
+        let signature = identity.sign_detached_pkcs7(b"document").unwrap();
+        let sdc = SignedDataContent::from_bytes(&signature).unwrap();
+        assert!(sdc.get_content().is_none());
+        assert_eq!(sdc.get_certificates().len(), 2);
+        let mut policy = SignedAttributePolicy::default();
+        policy.require(ID_SIGNING_TIME);
+        assert!(sdc.verify(Some(b"document"), &policy).unwrap().is_valid());
+        assert!(!sdc.verify(Some(b"tampered"), &policy).unwrap().is_valid());
+        assert!(identity.sign_detached_pkcs7(b"").is_err());
+    }
+}
@@ -2,5 +2,11 @@ pub mod api;
 pub mod asn1;
 pub mod cert_store_trait;
 pub mod cms_util;
+pub mod content_type_registry;
 pub mod directory_cert_store;
 pub mod enveloped_data_builder;
+pub mod identity;
+pub mod policy_verifier;
+pub mod recipient_rewrapper;
+pub mod signed_data_builder;
+pub mod stream_parser;
@@ -0,0 +1,394 @@
+use crate::certificates::{Certificate, PathBuilder};
+use crate::cms::asn1::signed_data_content::{SignedAttributePolicy, SignedDataContent};
+use crate::pki::crypto_policy::CryptoPolicy;
+use crate::pki::revocation::{RevocationChecker, RevocationStatus};
+use crate::utils::verification_report::VerificationReport;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The requirements of `verify_cms_with_policy` beyond valid signatures
+///
+/// By default, the signed attributes are checked as by `SignedDataContent::verify`,
+/// the certificate of every signer must chain up to a trust anchor through the
+/// certificates embedded in the message, and revocation is not checked.
+///
+/// # Example
+/// ```
+/// use quantcrypt::content::CmsVerificationPolicy;
+/// use quantcrypt::revocation::CryptoPolicy;
+///
+/// let mut crypto_policy = CryptoPolicy::new();
+/// crypto_policy.set_min_quantum_security_level(3);
+///
+/// let mut policy = CmsVerificationPolicy::new();
+/// policy
+///     .set_require_quantum_safe(true)
+///     .set_crypto_policy(crypto_policy);
+/// ```
+#[derive(Clone, Default)]
+pub struct CmsVerificationPolicy<'a> {
+    signed_attributes: SignedAttributePolicy,
+    crypto_policy: Option<CryptoPolicy>,
+    require_quantum_safe: bool,
+    revocation_checker: Option<&'a RevocationChecker<'a>>,
+    require_revocation_status: bool,
+}
+
+impl<'a> CmsVerificationPolicy<'a> {
+    /// Create a new policy checking signatures and paths only
+    ///
+    /// # Returns
+    ///
+    /// A new policy
+    pub fn new() -> CmsVerificationPolicy<'a> {
+        CmsVerificationPolicy::default()
+    }
+
+    /// Set the requirements on the signed attributes of every signer
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The requirements
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_signed_attribute_policy(&mut self, policy: SignedAttributePolicy) -> &mut Self {
+        self.signed_attributes = policy;
+        self
+    }
+
+    /// Set the crypto policy the signature of every signer must satisfy
+    ///
+    /// The signature algorithm must match the key of the signer, must not be
+    /// forbidden, and must be as strong against quantum attackers as the
+    /// policy requires for the path of the signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The crypto policy
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_crypto_policy(&mut self, policy: CryptoPolicy) -> &mut Self {
+        self.crypto_policy = Some(policy);
+        self
+    }
+
+    /// Require every certificate of a path to be signed with a pure PQ or composite algorithm
+    ///
+    /// # Arguments
+    ///
+    /// * `require_quantum_safe` - True to require quantum-safe paths
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_require_quantum_safe(&mut self, require_quantum_safe: bool) -> &mut Self {
+        self.require_quantum_safe = require_quantum_safe;
+        self
+    }
+
+    /// Check the revocation status of the certificates on the path of every signer
+    ///
+    /// # Arguments
+    ///
+    /// * `checker` - The revocation checker
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_revocation_checker(&mut self, checker: &'a RevocationChecker<'a>) -> &mut Self {
+        self.revocation_checker = Some(checker);
+        self
+    }
+
+    /// Fail the verification when the revocation status of a certificate is unknown
+    ///
+    /// By default, an unknown status is reported as a warning only.
+    ///
+    /// # Arguments
+    ///
+    /// * `require_revocation_status` - True to fail closed on unknown statuses
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_require_revocation_status(&mut self, require_revocation_status: bool) -> &mut Self {
+        self.require_revocation_status = require_revocation_status;
+        self
+    }
+
+    /// Describe the policy, for verification reports
+    fn describe(&self) -> String {
+        format!(
+            "{}; quantum-safe paths: {}; crypto policy: {}; revocation: {}",
+            self.signed_attributes.describe(),
+            self.require_quantum_safe,
+            if self.crypto_policy.is_some() {
+                "enforced"
+            } else {
+                "none"
+            },
+            match (self.revocation_checker, self.require_revocation_status) {
+                (None, _) => "not checked",
+                (Some(_), false) => "checked, unknown allowed",
+                (Some(_), true) => "checked, status required",
+            }
+        )
+    }
+}
+
+/// Verify a SignedData message: the signatures, the paths of the signers, revocation and policy
+///
+/// This combines `SignedDataContent::verify`, `PathBuilder` and
+/// `RevocationChecker` in one call. Paths are built from the certificate of
+/// each signer to one of the trust anchors, through the certificates embedded
+/// in the message. The outcome of every step is recorded in a single report,
+/// whose checks are prefixed by the index of the signer.
+///
+/// # Arguments
+///
+/// * `blob` - The DER encoded ContentInfo of the SignedData
+/// * `detached_content` - The signed content, for a detached signature
+/// * `trust_store` - The trust anchors
+/// * `policy` - The requirements beyond valid signatures
+///
+/// # Returns
+///
+/// A report with the checks performed for every signer. It is valid if there
+/// is at least one signer and every signer passed every check.
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidContent` if the message can't be parsed or the
+/// signature is detached and no content is given
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{CertValidity, CertificateBuilder, Profile};
+/// use quantcrypt::content::{verify_cms_with_policy, CmsVerificationPolicy, Identity};
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+/// let cert = CertificateBuilder::new(Profile::Root, None, validity, "CN=signer".to_string(), pk, &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// let signature = Identity::new(cert.clone(), sk, Vec::new())
+///     .unwrap()
+///     .sign_detached_pkcs7(b"data")
+///     .unwrap();
+///
+/// let policy = CmsVerificationPolicy::new();
+/// let report = verify_cms_with_policy(&signature, Some(b"data"), &[cert], &policy).unwrap();
+/// assert!(report.is_valid());
+/// ```
+pub fn verify_cms_with_policy(
+    blob: &[u8],
+    detached_content: Option<&[u8]>,
+    trust_store: &[Certificate],
+    policy: &CmsVerificationPolicy,
+) -> Result<VerificationReport> {
+    let sdc = SignedDataContent::from_bytes(blob)?;
+    let mut report = sdc.verify(detached_content, &policy.signed_attributes)?;
+    report.policy = Some(policy.describe());
+    if !report.is_valid() {
+        return Ok(report);
+    }
+    report.valid = false;
+
+    let embedded = sdc.get_certificates();
+    let mut builder = PathBuilder::new(trust_store.to_vec());
+    builder.set_require_quantum_safe(policy.require_quantum_safe);
+    for cert in &embedded {
+        builder.add_intermediate(cert.clone());
+    }
+
+    for (i, signer_info) in sdc.get_signer_infos().iter().enumerate() {
+        let check = |report: &mut VerificationReport, name: &str, passed: bool| {
+            report.check(&format!("signer {}: {}", i, name), passed)
+        };
+
+        // The signature checks guarantee that the certificate is embedded
+        let cert = embedded
+            .iter()
+            .find(|c| c.is_identified_by_sid(&signer_info.sid))
+            .ok_or(QuantCryptError::InvalidContent)?;
+        if !check(&mut report, "certificate valid", cert.is_valid()) {
+            return Ok(report);
+        }
+        let path = match builder.build(cert) {
+            Ok(path) => path,
+            Err(_) => {
+                check(&mut report, "certification path", false);
+                return Ok(report);
+            }
+        };
+        check(&mut report, "certification path", true);
+
+        if let Some(crypto_policy) = &policy.crypto_policy {
+            let oids = [signer_info.signature_algorithm.oid.to_string()];
+            if let Err(QuantCryptError::CryptoPolicyViolation { reason }) =
+                crypto_policy.check_signature(&oids, cert, &path)
+            {
+                report.warnings.push(format!("signer {}: {}", i, reason));
+                check(&mut report, "crypto policy", false);
+                return Ok(report);
+            }
+            check(&mut report, "crypto policy", true);
+        }
+
+        if let Some(checker) = policy.revocation_checker {
+            let statuses = match checker.check_path(&path) {
+                Ok(statuses) => statuses,
+                Err(err) => {
+                    report.warnings.push(format!("signer {}: {}", i, err));
+                    check(&mut report, "revocation check", false);
+                    return Ok(report);
+                }
+            };
+            for (j, status) in statuses.iter().enumerate() {
+                let name = format!("certificate {} of the path not revoked", j);
+                match status {
+                    RevocationStatus::Good => {
+                        check(&mut report, &name, true);
+                    }
+                    RevocationStatus::Revoked { .. } => {
+                        check(&mut report, &name, false);
+                        return Ok(report);
+                    }
+                    RevocationStatus::Unknown if policy.require_revocation_status => {
+                        let name = format!("revocation status of certificate {} known", j);
+                        check(&mut report, &name, false);
+                        return Ok(report);
+                    }
+                    RevocationStatus::Unknown => report.warnings.push(format!(
+                        "signer {}: the revocation status of certificate {} of the path is unknown",
+                        i, j
+                    )),
+                }
+            }
+        }
+    }
+    report.valid = true;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::cms::identity::Identity;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::pki::fetcher::Fetcher;
+
+    struct Offline;
+
+    impl Fetcher for Offline {
+        fn get(&self, url: &str) -> Result<Vec<u8>> {
+            Err(QuantCryptError::FetchFailed {
+                url: url.to_string(),
+            })
+        }
+
+        fn post(&self, url: &str, _: &str, _: &[u8]) -> Result<Vec<u8>> {
+            self.get(url)
+        }
+    }
+
+    #[test]
+    fn test_verify_cms_with_policy() {
+        let (ca_pk, ca_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let ca = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=ca".to_string(),
+            ca_pk,
+            &ca_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2030-01-01T00:00:00Z").unwrap();
+        let profile = Profile::Leaf {
+            issuer: ca.get_subject(),
+            enable_key_agreement: false,
+            enable_key_encipherment: false,
+        };
+        let cert =
+            CertificateBuilder::new(profile, None, validity, "CN=signer".to_string(), pk, &ca_sk)
+                .unwrap()
+                .build()
+                .unwrap();
+        let identity = Identity::new(cert, sk, vec![ca.clone()]).unwrap();
+        let signature = identity.sign_detached_pkcs7(b"document").unwrap();
+
+        let policy = CmsVerificationPolicy::new();
+        let trust_store = [ca];
+        let report =
+            verify_cms_with_policy(&signature, Some(b"document"), &trust_store, &policy).unwrap();
+        assert!(report.is_valid());
+        assert!(report
+            .checks
+            .iter()
+            .any(|c| c.name == "signer 0: certification path" && c.passed));
+
+        // A tampered content fails the signature checks
+        let report =
+            verify_cms_with_policy(&signature, Some(b"tampered"), &trust_store, &policy).unwrap();
+        assert!(!report.is_valid());
+
+        // Without the trust anchor, no path can be built
+        let other_ta = Certificate::from_file(
+            "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der",
+        )
+        .unwrap();
+        let report =
+            verify_cms_with_policy(&signature, Some(b"document"), &[other_ta], &policy).unwrap();
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "signer 0: certification path"
+        );
+
+        // ML-DSA-44 is category 2, below the required category 5
+        let mut crypto_policy = CryptoPolicy::new();
+        crypto_policy.set_min_quantum_security_level(5);
+        let mut policy = CmsVerificationPolicy::new();
+        policy.set_crypto_policy(crypto_policy);
+        let report =
+            verify_cms_with_policy(&signature, Some(b"document"), &trust_store, &policy).unwrap();
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "signer 0: crypto policy"
+        );
+
+        // Unknown revocation statuses are warnings unless a status is required
+        let fetcher = Offline;
+        let checker = RevocationChecker::new(&fetcher);
+        let mut policy = CmsVerificationPolicy::new();
+        policy.set_revocation_checker(&checker);
+        let report =
+            verify_cms_with_policy(&signature, Some(b"document"), &trust_store, &policy).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 1);
+        policy.set_require_revocation_status(true);
+        let report =
+            verify_cms_with_policy(&signature, Some(b"document"), &trust_store, &policy).unwrap();
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "signer 0: revocation status of certificate 0 known"
+        );
+    }
+}
@@ -0,0 +1,430 @@
+use cms::builder::RecipientInfoBuilder;
+use cms::content_info::{CmsVersion, ContentInfo};
+use cms::enveloped_data::{EnvelopedData, RecipientInfo, RecipientInfos, UserKeyingMaterial};
+use const_oid::db::rfc5911::{ID_CT_AUTH_ENVELOPED_DATA, ID_ENVELOPED_DATA};
+use der::asn1::SetOfVec;
+use der::{Any, Decode, Encode};
+use rsa::RsaPrivateKey;
+use spki::ObjectIdentifier;
+use zeroize::Zeroizing;
+
+use crate::certificates::Certificate;
+use crate::cms::asn1::auth_env_data::AuthEnvelopedData;
+use crate::cms::asn1::kemri_builder::KemRecipientInfoBuilder;
+use crate::cms::cms_util::CmsUtil;
+use crate::kdf::api::KdfType;
+use crate::kdf::common::config::oids::Oid as _;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::kem_manager::KemManager;
+use crate::keys::PrivateKey;
+use crate::utils::resource_limits::read_file_limited;
+use crate::wrap::api::WrapType;
+use crate::wrap::common::config::oids::Oid as _;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Re-keys an EnvelopedData or AuthEnvelopedData for a new set of recipients
+///
+/// Only the content encryption key (CEK) is decrypted, with the key of one of the
+/// existing KEM or key transport (RSA) recipients. It is then wrapped for each of the new KEM recipients,
+/// whose recipient infos replace the existing ones. The encrypted content, and for
+/// AuthEnvelopedData the authenticated attributes and the MAC, are copied unchanged,
+/// so encrypted archives can be migrated to new recipient keys or algorithms without
+/// decrypting and re-encrypting their content.
+///
+/// As a PKCS #1 v1.5 key transport recipient cannot tell a wrong key from the right
+/// one, the content is decrypted once with the CEK of a key transport recipient to
+/// check it, but it is still not re-encrypted.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
+/// use quantcrypt::certificates::Certificate;
+/// use quantcrypt::content::{EnvelopedDataContent, RecipientRewrapper};
+/// use quantcrypt::kdfs::KdfType;
+/// use quantcrypt::keys::PrivateKey;
+/// use quantcrypt::wraps::WrapType;
+///
+/// let cert = Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+/// let sk = PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der").unwrap();
+/// let data = std::fs::read(
+///     "test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_kemri_id-alg-hkdf-with-sha256.der",
+/// )
+/// .unwrap();
+///
+/// // Re-wrap the CEK for the same recipient, with a different key wrap
+/// let mut rewrapper = RecipientRewrapper::new(&cert, &sk);
+/// rewrapper
+///     .kem_recipient(&cert, &KdfType::HkdfWithSha512, &WrapType::Aes128, None)
+///     .unwrap();
+/// let rewrapped = rewrapper.rewrap(&data).unwrap();
+///
+/// let edc = EnvelopedDataContent::from_bytes_for_kem_recipient(&rewrapped, &cert, &sk).unwrap();
+/// assert_eq!(edc.get_recipient_infos().0.len(), 1);
+/// # }
+/// ```
+pub struct RecipientRewrapper<'a> {
+    /// The certificate of the existing recipient
+    cert: &'a Certificate,
+    /// The private key of the existing recipient
+    private_key: RecipientKey<'a>,
+    /// The KEM recipient info builders for the new recipients
+    kemri_builders: Vec<KemRecipientInfoBuilder>,
+}
+
+/// The private key of the existing recipient
+enum RecipientKey<'a> {
+    /// The private key of a KEM recipient
+    Kem(&'a PrivateKey),
+    /// The RSA private key of a key transport recipient
+    Ktri(Box<RsaPrivateKey>),
+}
+
+impl<'a> RecipientRewrapper<'a> {
+    /// Create a new RecipientRewrapper
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of an existing KEM recipient
+    /// * `private_key` - The private key of that recipient
+    ///
+    /// # Returns
+    ///
+    /// A new RecipientRewrapper without any new recipients
+    pub fn new(cert: &'a Certificate, private_key: &'a PrivateKey) -> Self {
+        Self {
+            cert,
+            private_key: RecipientKey::Kem(private_key),
+            kemri_builders: Vec::new(),
+        }
+    }
+
+    /// Create a new RecipientRewrapper for a key transport (RSA) recipient
+    ///
+    /// This is how messages for recipients that only have an RSA certificate are
+    /// migrated to KEM recipients.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of an existing key transport recipient
+    /// * `private_key` - The RSA private key of that recipient, as PKCS #8 or PKCS #1 DER
+    ///
+    /// # Returns
+    ///
+    /// A new RecipientRewrapper without any new recipients
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` if the private key is not an RSA private key
+    pub fn new_for_ktri_recipient(cert: &'a Certificate, private_key: &[u8]) -> Result<Self> {
+        Ok(Self {
+            cert,
+            private_key: RecipientKey::Ktri(Box::new(CmsUtil::parse_rsa_private_key(private_key)?)),
+            kemri_builders: Vec::new(),
+        })
+    }
+
+    /// Add a new KEM recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the recipient
+    /// * `kdf` - The key derivation function to use
+    /// * `wrap_type` - The key wrap type to use
+    /// * `ukm` - The user keying material to use
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the rewrapper
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if the certificate does not allow key encipherment
+    pub fn kem_recipient(
+        &mut self,
+        cert: &Certificate,
+        kdf: &KdfType,
+        wrap_type: &WrapType,
+        ukm: Option<UserKeyingMaterial>,
+    ) -> Result<&mut Self> {
+        if !cert.is_key_encipherment_enabled() {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+
+        let kem_manager = KemManager::new_from_oid(&cert.get_public_key_oid())?;
+        self.kemri_builders.push(KemRecipientInfoBuilder::new(
+            cert,
+            kem_manager,
+            kdf.get_oid(),
+            wrap_type.get_oid(),
+            ukm,
+        ));
+        Ok(self)
+    }
+
+    /// Re-wrap the CEK of an EnvelopedData or AuthEnvelopedData for the new recipients
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The DER or PEM encoded ContentInfo
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded ContentInfo, with the recipient infos of the new recipients
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidRecipientInfo` if no new recipients were added
+    ///
+    /// `QuantCryptError::InvalidContent` if the data is not an EnvelopedData or AuthEnvelopedData
+    ///
+    /// `QuantCryptError::InvalidEnvelopedData` if the CEK could not be decrypted with the key
+    /// of the existing recipient
+    pub fn rewrap(&mut self, data: &[u8]) -> Result<Vec<u8>> {
+        if self.kemri_builders.is_empty() {
+            return Err(QuantCryptError::InvalidRecipientInfo);
+        }
+
+        let ci = if let Ok(content_info) = ContentInfo::from_der(data) {
+            content_info
+        } else {
+            let pem = pem::parse(data).map_err(|_| QuantCryptError::InvalidContent)?;
+            ContentInfo::from_der(pem.contents()).map_err(|_| QuantCryptError::InvalidContent)?
+        };
+
+        let ci_der = ci.to_der().map_err(|_| QuantCryptError::InvalidContent)?;
+        let content = ci
+            .content
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
+
+        let content = if ci.content_type == ID_ENVELOPED_DATA {
+            let mut ed =
+                EnvelopedData::from_der(&content).map_err(|_| QuantCryptError::InvalidContent)?;
+            ed.recip_infos = self.rewrap_recipient_infos(
+                &ci_der,
+                &ed.recip_infos,
+                &ed.encrypted_content.content_enc_alg.oid,
+            )?;
+            // KEM recipients are other recipient infos, which require version 3 (RFC 5652 § 6.1)
+            ed.version = CmsVersion::V3;
+            ed.to_der()
+        } else if ci.content_type == ID_CT_AUTH_ENVELOPED_DATA {
+            let mut ed = AuthEnvelopedData::from_der(&content)
+                .map_err(|_| QuantCryptError::InvalidContent)?;
+            ed.recip_infos = self.rewrap_recipient_infos(
+                &ci_der,
+                &ed.recip_infos,
+                &ed.auth_encrypted_content.content_enc_alg.oid,
+            )?;
+            ed.to_der()
+        } else {
+            return Err(QuantCryptError::InvalidContent);
+        }
+        .map_err(|_| QuantCryptError::Unknown)?;
+
+        ContentInfo {
+            content_type: ci.content_type,
+            content: Any::from_der(&content).map_err(|_| QuantCryptError::Unknown)?,
+        }
+        .to_der()
+        .map_err(|_| QuantCryptError::Unknown)
+    }
+
+    /// Re-wrap the CEK of an EnvelopedData or AuthEnvelopedData in a file for the new
+    /// recipients, and write the result to another file
+    ///
+    /// # Arguments
+    ///
+    /// * `in_path` - The path of the DER or PEM encoded ContentInfo
+    /// * `out_path` - The path to write the DER encoded ContentInfo to
+    pub fn rewrap_file(&mut self, in_path: &str, out_path: &str) -> Result<()> {
+        let data = read_file_limited(in_path)?;
+        let data = self.rewrap(&data)?;
+        std::fs::write(out_path, data).map_err(|_| QuantCryptError::FileWriteError)
+    }
+
+    /// Decrypt the CEK from the existing recipient infos and wrap it for the new recipients
+    fn rewrap_recipient_infos(
+        &mut self,
+        ci_der: &[u8],
+        recip_infos: &RecipientInfos,
+        content_enc_alg: &ObjectIdentifier,
+    ) -> Result<RecipientInfos> {
+        let cek = match &self.private_key {
+            RecipientKey::Kem(private_key) => {
+                CmsUtil::get_cek_kemri(recip_infos, private_key, self.cert)?
+            }
+            RecipientKey::Ktri(private_key) => {
+                CmsUtil::decrypt_ktri_with_key(ci_der, private_key, self.cert)?;
+                let cek_len = CmsUtil::get_cek_len(content_enc_alg)?;
+                recip_infos
+                    .0
+                    .iter()
+                    .find_map(|ri| match ri {
+                        RecipientInfo::Ktri(ktri) => {
+                            CmsUtil::get_cek_ktri(ktri, private_key, self.cert, cek_len).ok()
+                        }
+                        _ => None,
+                    })
+                    .map(Zeroizing::new)
+                    .ok_or(QuantCryptError::InvalidEnvelopedData)?
+            }
+        };
+
+        let mut new_infos = SetOfVec::new();
+        for builder in self.kemri_builders.iter_mut() {
+            let ri = builder
+                .build(&cek)
+                .map_err(|_| QuantCryptError::EncapFailed)?;
+            new_infos
+                .insert(ri)
+                .map_err(|_| QuantCryptError::InvalidRecipientInfo)?;
+        }
+        Ok(RecipientInfos(new_infos))
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::content::{
+        AuthEnvelopedDataContent, ContentEncryptionAlgorithmAead, EnvelopedDataContent,
+    };
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::kems::{KemAlgorithm, KemKeyGenerator};
+
+    fn new_recipient() -> (Certificate, PrivateKey) {
+        let (ta_pk, ta_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let ta_cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=test.com".to_string(),
+            ta_pk,
+            &ta_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768)
+            .generate()
+            .unwrap();
+        let cert = CertificateBuilder::new(
+            Profile::Leaf {
+                issuer: ta_cert.get_subject(),
+                enable_key_agreement: false,
+                enable_key_encipherment: true,
+            },
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=new.test.com".to_string(),
+            pk,
+            &ta_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        (cert, sk)
+    }
+
+    #[test]
+    fn test_rewrap_enveloped_data() {
+        let old_cert =
+            Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let old_sk =
+            PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der")
+                .unwrap();
+        let data = std::fs::read(
+            "test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_kemri_id-alg-hkdf-with-sha256_ukm.der",
+        )
+        .unwrap();
+        let original =
+            EnvelopedDataContent::from_bytes_for_kem_recipient(&data, &old_cert, &old_sk).unwrap();
+
+        let (new_cert, new_sk) = new_recipient();
+        let mut rewrapper = RecipientRewrapper::new(&old_cert, &old_sk);
+
+        // Without new recipients, the content would become undecryptable
+        assert!(matches!(
+            rewrapper.rewrap(&data),
+            Err(QuantCryptError::InvalidRecipientInfo)
+        ));
+
+        rewrapper
+            .kem_recipient(&new_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+            .unwrap();
+        let rewrapped = rewrapper.rewrap(&data).unwrap();
+
+        let edc =
+            EnvelopedDataContent::from_bytes_for_kem_recipient(&rewrapped, &new_cert, &new_sk)
+                .unwrap();
+        assert_eq!(edc.get_content(), original.get_content());
+        assert_eq!(edc.get_recipient_infos().0.len(), 1);
+        assert!(
+            EnvelopedDataContent::from_bytes_for_kem_recipient(&rewrapped, &old_cert, &old_sk)
+                .is_err()
+        );
+
+        // The encrypted content is preserved byte for byte
+        let encrypted_content = |data: &[u8]| {
+            let ci = ContentInfo::from_der(data).unwrap();
+            let ed = EnvelopedData::from_der(&ci.content.to_der().unwrap()).unwrap();
+            (ed.encrypted_content, ed.unprotected_attrs)
+        };
+        assert_eq!(encrypted_content(&data), encrypted_content(&rewrapped));
+
+        // The old recipient cannot re-key a message that is no longer addressed to it
+        let mut rewrapper = RecipientRewrapper::new(&old_cert, &old_sk);
+        rewrapper
+            .kem_recipient(&old_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+            .unwrap();
+        assert!(matches!(
+            rewrapper.rewrap(&rewrapped),
+            Err(QuantCryptError::InvalidEnvelopedData)
+        ));
+    }
+
+    #[test]
+    fn test_rewrap_auth_enveloped_data() {
+        let old_cert =
+            Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let old_sk =
+            PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der")
+                .unwrap();
+
+        let mut builder =
+            AuthEnvelopedDataContent::get_builder(ContentEncryptionAlgorithmAead::Aes256Gcm)
+                .unwrap();
+        builder
+            .kem_recipient(&old_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+            .unwrap()
+            .content(b"archived")
+            .unwrap();
+        let data = builder.build().unwrap();
+
+        let (new_cert, new_sk) = new_recipient();
+        let mut rewrapper = RecipientRewrapper::new(&old_cert, &old_sk);
+        rewrapper
+            .kem_recipient(&new_cert, &KdfType::HkdfWithSha512, &WrapType::Aes256, None)
+            .unwrap()
+            .kem_recipient(&old_cert, &KdfType::HkdfWithSha256, &WrapType::Aes128, None)
+            .unwrap();
+        let rewrapped = rewrapper.rewrap(&data).unwrap();
+
+        let aedc =
+            AuthEnvelopedDataContent::from_bytes_for_kem_recipient(&rewrapped, &new_cert, &new_sk)
+                .unwrap();
+        assert_eq!(aedc.get_content(), b"archived");
+        let aedc =
+            AuthEnvelopedDataContent::from_bytes_for_kem_recipient(&rewrapped, &old_cert, &old_sk)
+                .unwrap();
+        assert_eq!(aedc.get_content(), b"archived");
+    }
+}
@@ -0,0 +1,506 @@
+use std::time::SystemTime;
+
+use cms::cert::{CertificateChoices, IssuerAndSerialNumber};
+use cms::content_info::{CmsVersion, ContentInfo};
+use cms::signed_data::{
+    CertificateSet, EncapsulatedContentInfo, SignedData, SignerIdentifier, SignerInfo, SignerInfos,
+};
+use const_oid::db::rfc5911::{ID_CONTENT_TYPE, ID_DATA, ID_MESSAGE_DIGEST, ID_SIGNED_DATA};
+use der::asn1::{OctetString, SetOfVec};
+use der::{Any, Decode, Encode};
+use sha2::{Digest, Sha512};
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+use x509_cert::attr::{Attribute, Attributes};
+
+use crate::cms::cms_util::CmsUtil;
+use crate::utils::entropy::SharedRng;
+use crate::{certificates::Certificate, keys::PrivateKey, QuantCryptError};
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The OID of SHA-512, the digest algorithm used for signing
+const ID_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.3");
+
+/// A commitment type of a commitment-type-indication attribute (RFC 5126 § 5.11.1)
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommitmentType {
+    /// The signer recognizes to have created, approved and sent the data
+    ProofOfOrigin,
+    /// The signer recognizes to have received the content of the data
+    ProofOfReceipt,
+    /// A TSP that has delivered the message
+    ProofOfDelivery,
+    /// The entity signing has sent the data (but not necessarily created it)
+    ProofOfSender,
+    /// The signer has approved the content of the data
+    ProofOfApproval,
+    /// The signer has created the data (but not necessarily approved nor sent it)
+    ProofOfCreation,
+    /// Another commitment type
+    Other(ObjectIdentifier),
+}
+
+impl CommitmentType {
+    /// Get the OID of the commitment type
+    pub fn get_oid(&self) -> ObjectIdentifier {
+        match self {
+            CommitmentType::ProofOfOrigin => {
+                ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.6.1")
+            }
+            CommitmentType::ProofOfReceipt => {
+                ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.6.2")
+            }
+            CommitmentType::ProofOfDelivery => {
+                ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.6.3")
+            }
+            CommitmentType::ProofOfSender => {
+                ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.6.4")
+            }
+            CommitmentType::ProofOfApproval => {
+                ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.6.5")
+            }
+            CommitmentType::ProofOfCreation => {
+                ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.6.6")
+            }
+            CommitmentType::Other(oid) => *oid,
+        }
+    }
+}
+
+/// The signed and unsigned attributes of a signer
+///
+/// The content-type and message-digest attributes are always included, as
+/// required by RFC 5652, and cannot be overridden. By default a signing-time
+/// attribute is included as well; everything else is opt-in.
+///
+/// # Example
+/// ```
+/// use quantcrypt::content::{CommitmentType, SignerAttributes};
+///
+/// let mut attributes = SignerAttributes::default();
+/// attributes
+///     .signing_time(false)
+///     .binary_signing_time(true)
+///     .commitment_type(CommitmentType::ProofOfApproval);
+/// ```
+#[derive(Clone)]
+pub struct SignerAttributes {
+    /// Whether to include a signing-time attribute
+    signing_time: bool,
+    /// Whether to include a binary-signing-time attribute
+    binary_signing_time: bool,
+    /// The commitment type to indicate, if any
+    commitment_type: Option<CommitmentType>,
+    /// Additional signed attributes
+    signed: Vec<Attribute>,
+    /// Unsigned attributes
+    unsigned: Vec<Attribute>,
+}
+
+impl Default for SignerAttributes {
+    fn default() -> Self {
+        SignerAttributes {
+            signing_time: true,
+            binary_signing_time: false,
+            commitment_type: None,
+            signed: Vec::new(),
+            unsigned: Vec::new(),
+        }
+    }
+}
+
+impl SignerAttributes {
+    /// Include a signing-time attribute (RFC 5652 § 11.3). This is the default.
+    ///
+    /// # Arguments
+    ///
+    /// * `include` - Whether to include the attribute
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the attributes
+    pub fn signing_time(&mut self, include: bool) -> &mut Self {
+        self.signing_time = include;
+        self
+    }
+
+    /// Include a binary-signing-time attribute (RFC 6019)
+    ///
+    /// # Arguments
+    ///
+    /// * `include` - Whether to include the attribute
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the attributes
+    pub fn binary_signing_time(&mut self, include: bool) -> &mut Self {
+        self.binary_signing_time = include;
+        self
+    }
+
+    /// Include a commitment-type-indication attribute (RFC 5126 § 5.11.1)
+    ///
+    /// # Arguments
+    ///
+    /// * `commitment_type` - The commitment type to indicate
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the attributes
+    pub fn commitment_type(&mut self, commitment_type: CommitmentType) -> &mut Self {
+        self.commitment_type = Some(commitment_type);
+        self
+    }
+
+    /// Add a custom signed attribute
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - The attribute to add
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the attributes
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidAttribute` if the attribute is a content-type or
+    /// message-digest attribute, which are computed by the builder
+    pub fn signed_attribute(&mut self, attribute: &Attribute) -> Result<&mut Self> {
+        if attribute.oid == ID_CONTENT_TYPE || attribute.oid == ID_MESSAGE_DIGEST {
+            return Err(QuantCryptError::InvalidAttribute);
+        }
+        self.signed.push(attribute.clone());
+        Ok(self)
+    }
+
+    /// Add an unsigned attribute
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - The attribute to add
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the attributes
+    pub fn unsigned_attribute(&mut self, attribute: &Attribute) -> &mut Self {
+        self.unsigned.push(attribute.clone());
+        self
+    }
+
+    /// Build the signed attributes of a signer
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The content type of the signed content
+    /// * `digest` - The digest of the signed content
+    ///
+    /// # Returns
+    ///
+    /// The signed attributes
+    fn build_signed(&self, content_type: ObjectIdentifier, digest: &[u8]) -> Result<Attributes> {
+        let now = SystemTime::now();
+        let mut attributes = vec![
+            CmsUtil::create_content_type_attribute(content_type)?,
+            CmsUtil::create_message_digest_attribute(digest)?,
+        ];
+        if self.signing_time {
+            attributes.push(CmsUtil::create_signing_time_attribute(now)?);
+        }
+        if self.binary_signing_time {
+            attributes.push(CmsUtil::create_binary_signing_time_attribute(now)?);
+        }
+        if let Some(commitment_type) = &self.commitment_type {
+            attributes.push(CmsUtil::create_commitment_type_attribute(
+                commitment_type.get_oid(),
+            )?);
+        }
+        attributes.extend(self.signed.iter().cloned());
+
+        // An attribute type may only occur once (RFC 5652 § 5.3)
+        for (i, attribute) in attributes.iter().enumerate() {
+            if attributes[..i].iter().any(|a| a.oid == attribute.oid) {
+                return Err(QuantCryptError::InvalidAttribute);
+            }
+        }
+        SetOfVec::try_from(attributes).map_err(|_| QuantCryptError::InvalidAttribute)
+    }
+
+    /// Build the unsigned attributes of a signer
+    fn build_unsigned(&self) -> Result<Option<Attributes>> {
+        if self.unsigned.is_empty() {
+            return Ok(None);
+        }
+        SetOfVec::try_from(self.unsigned.clone())
+            .map(Some)
+            .map_err(|_| QuantCryptError::InvalidAttribute)
+    }
+}
+
+/// A signer to be added to a SignedData
+struct Signer<'a> {
+    cert: Certificate,
+    private_key: &'a PrivateKey,
+    attributes: SignerAttributes,
+}
+
+/// A builder for creating a SignedData
+pub struct SignedDataBuilder<'a> {
+    /// The content to sign
+    content: Vec<u8>,
+    /// The content type of the content
+    content_type: ObjectIdentifier,
+    /// Whether the content is left out of the SignedData
+    detached: bool,
+    /// Additional certificates to include
+    certificates: Vec<Certificate>,
+    /// The signers
+    signers: Vec<Signer<'a>>,
+    /// The RNG of the signing randomness, or None for the default of each algorithm
+    rng: Option<SharedRng>,
+}
+
+impl<'a> SignedDataBuilder<'a> {
+    /// Create a new SignedDataBuilder
+    pub(crate) fn new() -> Self {
+        SignedDataBuilder {
+            content: Vec::new(),
+            content_type: ID_DATA,
+            detached: false,
+            certificates: Vec::new(),
+            signers: Vec::new(),
+            rng: None,
+        }
+    }
+
+    /// Set the content of the SignedData
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content to sign
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn content(&mut self, content: &[u8]) -> Result<&mut Self> {
+        self.content = content.to_vec();
+        Ok(self)
+    }
+
+    /// Set the content type of the content. The default is id-data.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - The OID of the content type
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn content_type(&mut self, content_type: ObjectIdentifier) -> Result<&mut Self> {
+        self.content_type = content_type;
+        Ok(self)
+    }
+
+    /// Leave the content out of the SignedData (a detached signature)
+    ///
+    /// # Arguments
+    ///
+    /// * `detached` - Whether the signature is detached
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn detached(&mut self, detached: bool) -> Result<&mut Self> {
+        self.detached = detached;
+        Ok(self)
+    }
+
+    /// Add a certificate, e.g. an intermediate CA certificate. The certificates of the
+    /// signers are added automatically.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate to add
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn certificate(&mut self, cert: &Certificate) -> Result<&mut Self> {
+        self.certificates.push(cert.clone());
+        Ok(self)
+    }
+
+    /// Draw the signing randomness from a caller-supplied RNG
+    ///
+    /// The randomized signatures of the signers, such as the hedged ML-DSA
+    /// signatures, the ECDSA nonces and the RSA-PSS salts, are drawn from this
+    /// RNG. See `SharedRng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The RNG
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    pub fn rng(&mut self, rng: SharedRng) -> Result<&mut Self> {
+        self.rng = Some(rng);
+        Ok(self)
+    }
+
+    /// Add a signer
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the signer
+    /// * `private_key` - The private key of the signer
+    /// * `attributes` - The signed and unsigned attributes of the signer
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the builder
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if the private key does not belong to the certificate
+    pub fn signer(
+        &mut self,
+        cert: &Certificate,
+        private_key: &'a PrivateKey,
+        attributes: &SignerAttributes,
+    ) -> Result<&mut Self> {
+        if cert.get_public_key_oid() != private_key.get_oid() {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        self.signers.push(Signer {
+            cert: cert.clone(),
+            private_key,
+            attributes: attributes.clone(),
+        });
+        Ok(self)
+    }
+
+    /// Build the SignedData and return the DER bytes of its ContentInfo
+    ///
+    /// # Returns
+    ///
+    /// The DER bytes of the ContentInfo
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::EmptyContent` if no content is set,
+    /// `QuantCryptError::InvalidAttribute` if a signer has duplicate attributes,
+    /// `QuantCryptError::PrivateKeyOutsideUsagePeriod` if the private key usage period
+    /// of a signer's certificate doesn't include the current time, and
+    /// `QuantCryptError::NotImplemented` if an RNG is set and a signer's algorithm is
+    /// provided by a registered backend
+    pub fn build(self) -> Result<Vec<u8>> {
+        if self.content.is_empty() {
+            return Err(QuantCryptError::EmptyContent);
+        }
+        if self.signers.is_empty() {
+            return Err(QuantCryptError::UnsupportedOperation);
+        }
+
+        let digest = Sha512::digest(&self.content).to_vec();
+        let digest_alg = AlgorithmIdentifierOwned {
+            oid: ID_SHA512,
+            parameters: None,
+        };
+
+        let mut signer_infos = Vec::new();
+        let mut certificates = Vec::new();
+        for signer in &self.signers {
+            if !signer.cert.is_private_key_usable() {
+                return Err(QuantCryptError::PrivateKeyOutsideUsagePeriod);
+            }
+            let signed_attrs = signer.attributes.build_signed(self.content_type, &digest)?;
+            let to_sign = signed_attrs
+                .to_der()
+                .map_err(|_| QuantCryptError::InvalidAttribute)?;
+            let signature = signer
+                .private_key
+                .sign_with_rng(&to_sign, self.rng.as_ref())?;
+
+            let oid: ObjectIdentifier = signer
+                .private_key
+                .get_oid()
+                .parse()
+                .map_err(|_| QuantCryptError::InvalidOid)?;
+            signer_infos.push(SignerInfo {
+                version: CmsVersion::V1,
+                sid: SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+                    issuer: signer.cert.get_issuer(),
+                    serial_number: signer.cert.get_serial_number(),
+                }),
+                digest_alg: digest_alg.clone(),
+                signed_attrs: Some(signed_attrs),
+                signature_algorithm: AlgorithmIdentifierOwned {
+                    oid,
+                    parameters: None,
+                },
+                signature: OctetString::new(signature).map_err(|_| QuantCryptError::Unknown)?,
+                unsigned_attrs: signer.attributes.build_unsigned()?,
+            });
+            certificates.push(signer.cert.clone());
+        }
+        certificates.extend(self.certificates);
+
+        let mut certificate_set = Vec::new();
+        for cert in certificates {
+            let choice = CertificateChoices::Certificate(cert.get_inner().clone());
+            // The same certificate may be used by several signers
+            if !certificate_set.contains(&choice) {
+                certificate_set.push(choice);
+            }
+        }
+
+        let econtent = if self.detached {
+            None
+        } else {
+            let octets = OctetString::new(self.content.clone())
+                .and_then(|o| o.to_der())
+                .map_err(|_| QuantCryptError::Unknown)?;
+            Some(Any::from_der(&octets).map_err(|_| QuantCryptError::Unknown)?)
+        };
+
+        let signed_data = SignedData {
+            // RFC 5652 § 5.1: version 3 if the content type is not id-data
+            version: if self.content_type == ID_DATA {
+                CmsVersion::V1
+            } else {
+                CmsVersion::V3
+            },
+            digest_algorithms: SetOfVec::try_from(vec![digest_alg])
+                .map_err(|_| QuantCryptError::Unknown)?,
+            encap_content_info: EncapsulatedContentInfo {
+                econtent_type: self.content_type,
+                econtent,
+            },
+            certificates: Some(CertificateSet(
+                SetOfVec::try_from(certificate_set).map_err(|_| QuantCryptError::Unknown)?,
+            )),
+            crls: None,
+            signer_infos: SignerInfos(
+                SetOfVec::try_from(signer_infos).map_err(|_| QuantCryptError::Unknown)?,
+            ),
+        };
+
+        let content_info = ContentInfo {
+            content_type: ID_SIGNED_DATA,
+            content: Any::from_der(&signed_data.to_der().map_err(|_| QuantCryptError::Unknown)?)
+                .map_err(|_| QuantCryptError::Unknown)?,
+        };
+        content_info.to_der().map_err(|_| QuantCryptError::Unknown)
+    }
+
+    /// Build the SignedData and write it to a file
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to write the file to
+    pub fn build_to_file(self, file_path: &str) -> Result<()> {
+        let data = self.build()?;
+        std::fs::write(file_path, data).map_err(|_| QuantCryptError::FileWriteError)
+    }
+}
@@ -0,0 +1,1223 @@
+// https://datatracker.ietf.org/doc/html/rfc5652
+// https://www.itu.int/rec/T-REC-X.690 (BER encoding, including indefinite lengths)
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use aes::cipher::{BlockDecrypt, KeyInit};
+use aes::{Aes128, Aes192, Aes256, Block};
+
+use base64::engine::general_purpose::STANDARD;
+use cms::cert::CertificateChoices;
+use cms::content_info::CmsVersion;
+use cms::enveloped_data::{OriginatorInfo, RecipientInfo, RecipientInfos};
+use cms::signed_data::SignerInfo;
+use const_oid::db::rfc5911::{ID_ENVELOPED_DATA, ID_SIGNED_DATA};
+use der::asn1::OctetString;
+use der::{Decode, Encode};
+use sha2::digest::DynDigest;
+use spki::{AlgorithmIdentifierOwned, ObjectIdentifier};
+use x509_cert::attr::Attributes;
+
+use crate::cea::common::cea_type::CeaType;
+use crate::cms::cms_util::CmsUtil;
+use crate::utils::resource_limits::AllocationAccount;
+use crate::{certificates::Certificate, keys::PrivateKey, QuantCryptError};
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The size of the chunks in which content octets are passed to the sink
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// The maximum size of an element that is held in memory (recipient infos, certificates, ...)
+const MAX_ELEMENT_LEN: u64 = 16 * 1024 * 1024;
+
+/// The maximum nesting depth of indefinite length and constructed elements
+const MAX_DEPTH: usize = 32;
+
+/// Universal and context-specific tags used while walking the structures
+const TAG_EOC: u8 = 0x00;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0_PRIMITIVE: u8 = 0x80;
+const TAG_CONTEXT_0: u8 = 0xa0;
+const TAG_CONTEXT_1: u8 = 0xa1;
+
+/// The bit of the identifier octet marking a constructed encoding
+const CONSTRUCTED: u8 = 0x20;
+
+/// The identifier and length octets of a BER element
+struct Header {
+    /// The identifier octet
+    tag: u8,
+    /// The length of the contents, or None for the indefinite form
+    len: Option<u64>,
+}
+
+impl Header {
+    /// Check if the element uses the constructed encoding
+    fn is_constructed(&self) -> bool {
+        self.tag & CONSTRUCTED != 0
+    }
+
+    /// Check if the header is an end-of-contents marker
+    fn is_eoc(&self) -> bool {
+        self.tag == TAG_EOC && self.len == Some(0)
+    }
+}
+
+/// Where the contents of a constructed element end
+enum End {
+    /// At an absolute position in the stream
+    At(u64),
+    /// At the next end-of-contents marker
+    Eoc,
+}
+
+/// Encode the identifier and definite length octets of an element
+fn encode_header(tag: u8, len: usize) -> Vec<u8> {
+    let mut header = vec![tag];
+    if len < 0x80 {
+        header.push(len as u8);
+    } else {
+        let bytes = (len as u64).to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        header.push(0x80 | (bytes.len() - skip) as u8);
+        header.extend_from_slice(&bytes[skip..]);
+    }
+    header
+}
+
+/// A reader for BER encoded elements from a stream
+///
+/// Only the elements that are needed in memory are buffered. Indefinite length
+/// elements that are buffered are converted to the definite length form, so
+/// they can be decoded with the `der` crate afterwards. The buffered elements
+/// are charged to the parse budget.
+struct BerReader<R: Read> {
+    inner: R,
+    pos: u64,
+    peeked: Option<Header>,
+    account: AllocationAccount,
+}
+
+impl<R: Read> BerReader<R> {
+    fn new(inner: R) -> BerReader<R> {
+        BerReader {
+            inner,
+            pos: 0,
+            peeked: None,
+            account: AllocationAccount::new(),
+        }
+    }
+
+    /// Read exactly `buf.len()` bytes
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.inner
+            .read_exact(buf)
+            .map_err(|_| QuantCryptError::StreamIoError)?;
+        self.pos += buf.len() as u64;
+        Ok(())
+    }
+
+    /// Read a single byte
+    fn read_byte(&mut self) -> Result<u8> {
+        let mut byte = [0u8; 1];
+        self.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    /// Read the header of the next element
+    fn read_header(&mut self) -> Result<Header> {
+        if let Some(header) = self.peeked.take() {
+            return Ok(header);
+        }
+
+        let tag = self.read_byte()?;
+        // High tag numbers are not used by any of the structures we stream
+        if tag & 0x1f == 0x1f {
+            return Err(QuantCryptError::InvalidContent);
+        }
+
+        let first = self.read_byte()?;
+        let len = match first {
+            0x00..=0x7f => Some(first as u64),
+            0x80 => {
+                if tag & CONSTRUCTED == 0 {
+                    return Err(QuantCryptError::InvalidContent);
+                }
+                None
+            }
+            0x81..=0x88 => {
+                let mut len = 0u64;
+                for _ in 0..(first & 0x7f) {
+                    len = (len << 8) | self.read_byte()? as u64;
+                }
+                Some(len)
+            }
+            _ => return Err(QuantCryptError::InvalidContent),
+        };
+        Ok(Header { tag, len })
+    }
+
+    /// Peek at the header of the next element without consuming it
+    fn peek_header(&mut self) -> Result<&Header> {
+        if self.peeked.is_none() {
+            let header = self.read_header()?;
+            self.peeked = Some(header);
+        }
+        self.peeked.as_ref().ok_or(QuantCryptError::InvalidContent)
+    }
+
+    /// Read the header of the next element and check its tag
+    fn expect_header(&mut self, tag: u8) -> Result<Header> {
+        let header = self.read_header()?;
+        if header.tag != tag {
+            return Err(QuantCryptError::InvalidContent);
+        }
+        Ok(header)
+    }
+
+    /// Get where the contents of an element, whose header was just read, end
+    fn end_of(&self, header: &Header) -> End {
+        match header.len {
+            Some(len) => End::At(self.pos + len),
+            None => End::Eoc,
+        }
+    }
+
+    /// Check if the contents of a constructed element have been read completely
+    fn at_end(&mut self, end: &End) -> Result<bool> {
+        match end {
+            End::At(pos) => Ok(self.peeked.is_none() && self.pos >= *pos),
+            End::Eoc => Ok(self.peek_header()?.is_eoc()),
+        }
+    }
+
+    /// Consume the end of a constructed element
+    fn finish(&mut self, end: End) -> Result<()> {
+        match end {
+            End::At(pos) => {
+                if self.peeked.is_some() || self.pos != pos {
+                    return Err(QuantCryptError::InvalidContent);
+                }
+            }
+            End::Eoc => {
+                if !self.read_header()?.is_eoc() {
+                    return Err(QuantCryptError::InvalidContent);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the contents of an element into memory
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of the element, which has just been read
+    /// * `tag` - The tag to use when re-encoding the element
+    ///
+    /// # Returns
+    ///
+    /// The element with a definite length encoding
+    fn read_element_as(&mut self, header: Header, tag: u8) -> Result<Vec<u8>> {
+        let contents = self.read_contents(&header, 0)?;
+        let mut element = encode_header(tag, contents.len());
+        element.extend_from_slice(&contents);
+        Ok(element)
+    }
+
+    /// Read the next element into memory
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The expected tag of the element
+    ///
+    /// # Returns
+    ///
+    /// The element with a definite length encoding
+    fn read_element(&mut self, tag: u8) -> Result<Vec<u8>> {
+        let header = self.expect_header(tag)?;
+        self.read_element_as(header, tag)
+    }
+
+    /// Read the contents of an element into memory
+    fn read_contents(&mut self, header: &Header, depth: usize) -> Result<Vec<u8>> {
+        if depth > MAX_DEPTH {
+            return Err(QuantCryptError::InvalidContent);
+        }
+        match header.len {
+            Some(len) => {
+                if len > MAX_ELEMENT_LEN {
+                    return Err(QuantCryptError::InvalidContent);
+                }
+                self.account.charge(len as usize)?;
+                let mut contents = vec![0u8; len as usize];
+                self.read_exact(&mut contents)?;
+                Ok(contents)
+            }
+            None => {
+                let mut contents = Vec::new();
+                loop {
+                    let child = self.read_header()?;
+                    if child.is_eoc() {
+                        break;
+                    }
+                    let child_contents = self.read_contents(&child, depth + 1)?;
+                    contents.extend(encode_header(child.tag, child_contents.len()));
+                    contents.extend(child_contents);
+                    if contents.len() as u64 > MAX_ELEMENT_LEN {
+                        return Err(QuantCryptError::InvalidContent);
+                    }
+                }
+                Ok(contents)
+            }
+        }
+    }
+
+    /// Skip the contents of an element
+    fn skip_contents(&mut self, header: &Header) -> Result<()> {
+        self.stream_contents(header, 0, &mut |_| Ok(()))
+    }
+
+    /// Stream the contents of an element in chunks
+    ///
+    /// For primitive elements these are the content octets. For constructed
+    /// elements (e.g. a segmented OCTET STRING) the content octets of the
+    /// nested primitive elements are streamed, in order.
+    fn stream_contents(
+        &mut self,
+        header: &Header,
+        depth: usize,
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(QuantCryptError::InvalidContent);
+        }
+        if !header.is_constructed() {
+            let mut remaining = header.len.ok_or(QuantCryptError::InvalidContent)?;
+            let mut buf = vec![0u8; CHUNK_LEN];
+            while remaining > 0 {
+                let n = remaining.min(CHUNK_LEN as u64) as usize;
+                self.read_exact(&mut buf[..n])?;
+                sink(&buf[..n])?;
+                remaining -= n as u64;
+            }
+            return Ok(());
+        }
+
+        let end = self.end_of(header);
+        while !self.at_end(&end)? {
+            let child = self.read_header()?;
+            self.stream_contents(&child, depth + 1, sink)?;
+        }
+        self.finish(end)
+    }
+
+    /// Stream the content octets of an OCTET STRING, which may be segmented
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of the OCTET STRING, which has just been read
+    /// * `sink` - Receives the content octets in chunks
+    fn stream_octets(
+        &mut self,
+        header: &Header,
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        if !header.is_constructed() {
+            return self.stream_contents(header, 0, sink);
+        }
+        // The segments of a constructed OCTET STRING are OCTET STRINGs themselves
+        let end = self.end_of(header);
+        while !self.at_end(&end)? {
+            let segment = self.read_header()?;
+            if segment.tag & !CONSTRUCTED != TAG_OCTET_STRING {
+                return Err(QuantCryptError::InvalidContent);
+            }
+            self.stream_octets(&segment, sink)?;
+        }
+        self.finish(end)
+    }
+}
+
+/// A reader returning the base64 text between the boundaries of a PEM document
+struct PemBodyReader<R: BufRead> {
+    inner: R,
+    line: Vec<u8>,
+    offset: usize,
+    in_body: bool,
+    done: bool,
+}
+
+impl<R: BufRead> PemBodyReader<R> {
+    fn new(inner: R) -> PemBodyReader<R> {
+        PemBodyReader {
+            inner,
+            line: Vec::new(),
+            offset: 0,
+            in_body: false,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Read for PemBodyReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.offset == self.line.len() {
+            if self.done {
+                return Ok(0);
+            }
+            let mut line = Vec::new();
+            if self.inner.read_until(b'\n', &mut line)? == 0 {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            if line.starts_with(b"-----BEGIN ") {
+                self.in_body = true;
+                continue;
+            }
+            if !self.in_body {
+                continue;
+            }
+            if line.starts_with(b"-----END ") {
+                self.done = true;
+                continue;
+            }
+            line.retain(|b| !b.is_ascii_whitespace());
+            self.line = line;
+            self.offset = 0;
+        }
+        let n = buf.len().min(self.line.len() - self.offset);
+        buf[..n].copy_from_slice(&self.line[self.offset..self.offset + n]);
+        self.offset += n;
+        Ok(n)
+    }
+}
+
+/// Open a DER/BER or PEM encoded ContentInfo for streaming
+///
+/// # Arguments
+///
+/// * `reader` - The source of the encoded ContentInfo
+///
+/// # Returns
+///
+/// A BER reader positioned at the start of the ContentInfo
+fn open<'a, R: Read + 'a>(reader: R) -> Result<BerReader<Box<dyn Read + 'a>>> {
+    let mut reader = BufReader::with_capacity(CHUNK_LEN, reader);
+    let first = loop {
+        let buf = reader
+            .fill_buf()
+            .map_err(|_| QuantCryptError::StreamIoError)?;
+        match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(i) => {
+                let first = buf[i];
+                reader.consume(i);
+                break first;
+            }
+            None if buf.is_empty() => return Err(QuantCryptError::InvalidContent),
+            None => {
+                let len = buf.len();
+                reader.consume(len);
+            }
+        }
+    };
+
+    let reader: Box<dyn Read + 'a> = if first == TAG_SEQUENCE {
+        Box::new(reader)
+    } else if first == b'-' {
+        Box::new(base64::read::DecoderReader::new(
+            PemBodyReader::new(reader),
+            &STANDARD,
+        ))
+    } else {
+        return Err(QuantCryptError::InvalidContent);
+    };
+    Ok(BerReader::new(reader))
+}
+
+/// Read the header of a ContentInfo and check its content type
+///
+/// # Returns
+///
+/// Where the ContentInfo and the explicitly tagged content end
+fn read_content_info_header<R: Read>(
+    reader: &mut BerReader<R>,
+    content_type: ObjectIdentifier,
+) -> Result<(End, End)> {
+    let ci = reader.expect_header(TAG_SEQUENCE)?;
+    let ci_end = reader.end_of(&ci);
+    let oid = ObjectIdentifier::from_der(&reader.read_element(TAG_OID)?)
+        .map_err(|_| QuantCryptError::InvalidContent)?;
+    if oid != content_type {
+        return Err(QuantCryptError::InvalidContent);
+    }
+    let explicit = reader.expect_header(TAG_CONTEXT_0)?;
+    let explicit_end = reader.end_of(&explicit);
+    Ok((ci_end, explicit_end))
+}
+
+/// Read an element that is decoded with the `der` crate
+fn read_decoded<R: Read, T: for<'a> Decode<'a>>(reader: &mut BerReader<R>, tag: u8) -> Result<T> {
+    let der = reader.read_element(tag)?;
+    T::from_der(&der).map_err(|_| QuantCryptError::InvalidContent)
+}
+
+/// Split the contents of a buffered SET into its elements
+fn split_set(set: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut reader = BerReader::new(set);
+    let header = reader.read_header()?;
+    let end = reader.end_of(&header);
+    let mut elements = Vec::new();
+    while !reader.at_end(&end)? {
+        let header = reader.read_header()?;
+        let tag = header.tag;
+        elements.push(reader.read_element_as(header, tag)?);
+    }
+    reader.finish(end)?;
+    Ok(elements)
+}
+
+/// The AES key of a CBC decryptor
+enum AesKey {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+/// A streaming AES-CBC decryptor removing the PKCS #7 padding
+///
+/// The last decrypted block is held back until `finalize`, since it holds the padding.
+struct CbcDecryptor {
+    /// The AES key
+    key: AesKey,
+    /// The previous ciphertext block, initially the IV
+    chain: Block,
+    /// The bytes of an incomplete ciphertext block
+    pending: Vec<u8>,
+    /// The last decrypted block
+    last: Option<Block>,
+}
+
+impl CbcDecryptor {
+    /// Decrypt a block, chaining it with the previous one
+    fn decrypt_block(&mut self, ct: &[u8]) -> Block {
+        let ct = Block::clone_from_slice(ct);
+        let mut block = ct;
+        match &self.key {
+            AesKey::Aes128(key) => key.decrypt_block(&mut block),
+            AesKey::Aes192(key) => key.decrypt_block(&mut block),
+            AesKey::Aes256(key) => key.decrypt_block(&mut block),
+        }
+        for (b, c) in block.iter_mut().zip(self.chain.iter()) {
+            *b ^= c;
+        }
+        self.chain = ct;
+        block
+    }
+
+    /// Decrypt a chunk of the ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The chunk of the ciphertext
+    /// * `out` - The buffer for the plaintext, at least a block longer than the chunk
+    ///
+    /// # Returns
+    ///
+    /// The number of plaintext bytes written to the buffer
+    fn update(&mut self, chunk: &[u8], out: &mut [u8]) -> usize {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(chunk);
+        let full = pending.len() - pending.len() % 16;
+        let mut n = 0;
+        for ct in pending[..full].chunks_exact(16) {
+            let pt = self.decrypt_block(ct);
+            if let Some(last) = self.last.replace(pt) {
+                out[n..n + 16].copy_from_slice(&last);
+                n += 16;
+            }
+        }
+        pending.drain(..full);
+        self.pending = pending;
+        n
+    }
+
+    /// Remove the padding of the last block
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The buffer for the plaintext, at least a block long
+    ///
+    /// # Returns
+    ///
+    /// The number of plaintext bytes written to the buffer
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEnvelopedData` if the ciphertext is not a whole number
+    /// of blocks or the padding is invalid
+    fn finalize(&mut self, out: &mut [u8]) -> Result<usize> {
+        let last = self
+            .last
+            .take()
+            .ok_or(QuantCryptError::InvalidEnvelopedData)?;
+        if !self.pending.is_empty() {
+            return Err(QuantCryptError::InvalidEnvelopedData);
+        }
+        let pad = last[15] as usize;
+        let valid = (1..=16).contains(&pad) && last[16 - pad..].iter().all(|b| *b as usize == pad);
+        if !valid {
+            return Err(QuantCryptError::InvalidEnvelopedData);
+        }
+        let n = 16 - pad;
+        out[..n].copy_from_slice(&last[..n]);
+        Ok(n)
+    }
+}
+
+/// Create a decryptor for the content encryption algorithm of an EnvelopedData
+fn get_decryptor(alg: &AlgorithmIdentifierOwned, key: &[u8]) -> Result<CbcDecryptor> {
+    let cea_type = CeaType::from_oid(&alg.oid.to_string())
+        .ok_or(QuantCryptError::UnsupportedContentEncryptionAlgorithm)?;
+    let key = match cea_type {
+        CeaType::Aes128CbcPad => Aes128::new_from_slice(key).map(AesKey::Aes128),
+        CeaType::Aes192CbcPad => Aes192::new_from_slice(key).map(AesKey::Aes192),
+        CeaType::Aes256CbcPad => Aes256::new_from_slice(key).map(AesKey::Aes256),
+        // Authenticated encryption is only used in AuthEnvelopedData
+        _ => return Err(QuantCryptError::UnsupportedContentEncryptionAlgorithm),
+    }
+    .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
+    let params = alg
+        .parameters
+        .as_ref()
+        .ok_or(QuantCryptError::InvalidEnvelopedData)?
+        .to_der()
+        .map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
+    let iv = OctetString::from_der(&params).map_err(|_| QuantCryptError::InvalidEnvelopedData)?;
+    if iv.as_bytes().len() != 16 {
+        return Err(QuantCryptError::InvalidEnvelopedData);
+    }
+    Ok(CbcDecryptor {
+        key,
+        chain: Block::clone_from_slice(iv.as_bytes()),
+        pending: Vec::new(),
+        last: None,
+    })
+}
+
+/// An EnvelopedData whose content has been decrypted to a sink
+///
+/// This is the streaming counterpart of `EnvelopedDataContent`, for content
+/// that is too large to be held in memory. Only the small parts of the
+/// structure (recipient infos, attributes) are buffered; the encrypted content
+/// is decrypted in chunks of 64 KiB and written to the sink as it is read.
+///
+/// Both DER and BER encodings are supported, including the indefinite length
+/// and segmented OCTET STRING encodings produced by streaming encoders. PEM
+/// input is decoded on the fly.
+///
+/// AES-CBC does not authenticate the content, so the padding can only be
+/// checked once the last block has been read. If an error is returned, the
+/// sink may already have received part of the plaintext, which must be discarded.
+///
+/// Compressed content (a content type of id-ct-compressedData) is written to the
+/// sink as is; it can be read with `CompressedDataContent::from_encapsulated`.
+///
+/// # Example
+/// ```
+/// use std::fs::File;
+/// use quantcrypt::certificates::Certificate;
+/// use quantcrypt::content::ContentEncryptionAlgorithm;
+/// use quantcrypt::content::EnvelopedDataContent;
+/// use quantcrypt::content::StreamedEnvelopedData;
+/// use quantcrypt::kdfs::KdfType;
+/// use quantcrypt::keys::PrivateKey;
+/// use quantcrypt::wraps::WrapType;
+///
+/// let recipient_cert =
+///     Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+/// let private_key =
+///     PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der").unwrap();
+///
+/// let mut builder =
+///     EnvelopedDataContent::get_builder(ContentEncryptionAlgorithm::Aes256Cbc).unwrap();
+/// builder
+///     .kem_recipient(&recipient_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+///     .unwrap()
+///     .content(b"abc")
+///     .unwrap();
+/// let content = builder.build().unwrap();
+///
+/// // Any `Read` works as the source, e.g. a `File`, and any `Write` as the sink
+/// let mut plaintext = Vec::new();
+/// let streamed = StreamedEnvelopedData::decrypt_for_kem_recipient(
+///     content.as_slice(),
+///     &mut plaintext,
+///     &recipient_cert,
+///     &private_key,
+/// )
+/// .unwrap();
+/// assert_eq!(plaintext, b"abc");
+/// assert_eq!(streamed.get_content_len(), 3);
+/// ```
+pub struct StreamedEnvelopedData {
+    /// The version of the EnvelopedData
+    version: CmsVersion,
+    /// The originator info
+    originator_info: Option<OriginatorInfo>,
+    /// The recipient infos
+    recip_infos: RecipientInfos,
+    /// The content type of the encrypted content
+    content_type: ObjectIdentifier,
+    /// The number of plaintext bytes written to the sink
+    content_len: u64,
+    /// The unprotected attributes
+    unprotected_attrs: Option<Attributes>,
+}
+
+impl StreamedEnvelopedData {
+    /// Decrypt an EnvelopedData from a stream, writing the content to a sink
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the DER, BER or PEM encoded ContentInfo
+    /// * `sink` - Receives the decrypted content
+    /// * `recipient_cert` - The certificate of the recipient
+    /// * `recipient_private_key` - The private key of the recipient
+    ///
+    /// # Returns
+    ///
+    /// The EnvelopedData, without its content
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidContent` if the stream is not a well formed EnvelopedData,
+    /// `QuantCryptError::InvalidEnvelopedData` if no recipient info can be decrypted or the
+    /// content cannot be decrypted, and `QuantCryptError::StreamIoError` if reading from the
+    /// stream or writing to the sink fails
+    pub fn decrypt_for_kem_recipient<R: Read, W: Write>(
+        reader: R,
+        sink: &mut W,
+        recipient_cert: &Certificate,
+        recipient_private_key: &PrivateKey,
+    ) -> Result<StreamedEnvelopedData> {
+        let mut reader = open(reader)?;
+        let (ci_end, explicit_end) = read_content_info_header(&mut reader, ID_ENVELOPED_DATA)?;
+
+        let ed = reader.expect_header(TAG_SEQUENCE)?;
+        let ed_end = reader.end_of(&ed);
+        let version: CmsVersion = read_decoded(&mut reader, TAG_INTEGER)?;
+
+        // originatorInfo [0] IMPLICIT OriginatorInfo OPTIONAL
+        let originator_info = if reader.peek_header()?.tag == TAG_CONTEXT_0 {
+            let header = reader.read_header()?;
+            let der = reader.read_element_as(header, TAG_SEQUENCE)?;
+            Some(OriginatorInfo::from_der(&der).map_err(|_| QuantCryptError::InvalidContent)?)
+        } else {
+            None
+        };
+
+        let recip_infos: RecipientInfos = read_decoded(&mut reader, TAG_SET)?;
+
+        // The CEK is needed before the encrypted content is read
+        let key = recip_infos
+            .0
+            .iter()
+            .find_map(|ri| match ri {
+                RecipientInfo::Ori(ori) => {
+                    CmsUtil::get_cek(ori, recipient_private_key, recipient_cert).ok()
+                }
+                _ => None,
+            })
+            .ok_or(QuantCryptError::InvalidEnvelopedData)?;
+
+        let eci = reader.expect_header(TAG_SEQUENCE)?;
+        let eci_end = reader.end_of(&eci);
+        let content_type: ObjectIdentifier = read_decoded(&mut reader, TAG_OID)?;
+        let alg: AlgorithmIdentifierOwned = read_decoded(&mut reader, TAG_SEQUENCE)?;
+        let mut decryptor = get_decryptor(&alg, &key)?;
+
+        // encryptedContent [0] IMPLICIT OCTET STRING OPTIONAL
+        let mut content_len = 0u64;
+        let mut buf = vec![0u8; CHUNK_LEN + 16];
+        if !reader.at_end(&eci_end)? {
+            let header = reader.read_header()?;
+            if header.tag & !CONSTRUCTED != TAG_CONTEXT_0_PRIMITIVE {
+                return Err(QuantCryptError::InvalidContent);
+            }
+            reader.stream_octets(&header, &mut |chunk| {
+                let n = decryptor.update(chunk, &mut buf);
+                sink.write_all(&buf[..n])
+                    .map_err(|_| QuantCryptError::StreamIoError)?;
+                content_len += n as u64;
+                Ok(())
+            })?;
+        }
+        reader.finish(eci_end)?;
+
+        let n = decryptor.finalize(&mut buf)?;
+        sink.write_all(&buf[..n])
+            .and_then(|_| sink.flush())
+            .map_err(|_| QuantCryptError::StreamIoError)?;
+        content_len += n as u64;
+
+        // unprotectedAttrs [1] IMPLICIT UnprotectedAttributes OPTIONAL
+        let unprotected_attrs = if !reader.at_end(&ed_end)? {
+            let header = reader.expect_header(TAG_CONTEXT_1)?;
+            let der = reader.read_element_as(header, TAG_SET)?;
+            Some(Attributes::from_der(&der).map_err(|_| QuantCryptError::InvalidContent)?)
+        } else {
+            None
+        };
+
+        reader.finish(ed_end)?;
+        reader.finish(explicit_end)?;
+        reader.finish(ci_end)?;
+
+        Ok(StreamedEnvelopedData {
+            version,
+            originator_info,
+            recip_infos,
+            content_type,
+            content_len,
+            unprotected_attrs,
+        })
+    }
+
+    /// Get the version of the EnvelopedData
+    pub fn get_version(&self) -> CmsVersion {
+        self.version
+    }
+
+    /// Get the originator info
+    pub fn get_originator_info(&self) -> Option<OriginatorInfo> {
+        self.originator_info.clone()
+    }
+
+    /// Get the recipient infos
+    pub fn get_recipient_infos(&self) -> RecipientInfos {
+        self.recip_infos.clone()
+    }
+
+    /// Get the content type of the encrypted content
+    pub fn get_content_type(&self) -> ObjectIdentifier {
+        self.content_type
+    }
+
+    /// Get the number of content bytes written to the sink
+    pub fn get_content_len(&self) -> u64 {
+        self.content_len
+    }
+
+    /// Get the unprotected attributes
+    pub fn get_unprotected_attrs(&self) -> Option<Attributes> {
+        self.unprotected_attrs.clone()
+    }
+}
+
+/// Create a digest for a digest algorithm of a SignedData, if it is supported
+pub(crate) fn new_digest(oid: &ObjectIdentifier) -> Option<Box<dyn DynDigest>> {
+    match oid.to_string().as_str() {
+        "2.16.840.1.101.3.4.2.1" => Some(Box::new(sha2::Sha256::default())),
+        "2.16.840.1.101.3.4.2.2" => Some(Box::new(sha2::Sha384::default())),
+        "2.16.840.1.101.3.4.2.3" => Some(Box::new(sha2::Sha512::default())),
+        "2.16.840.1.101.3.4.2.8" => Some(Box::new(sha3::Sha3_256::default())),
+        "2.16.840.1.101.3.4.2.9" => Some(Box::new(sha3::Sha3_384::default())),
+        "2.16.840.1.101.3.4.2.10" => Some(Box::new(sha3::Sha3_512::default())),
+        _ => None,
+    }
+}
+
+/// A SignedData whose encapsulated content has been streamed to a sink
+///
+/// The encapsulated content is written to the sink as it is read, and hashed
+/// on the fly with every supported digest algorithm listed in the SignedData
+/// (SHA-2 and SHA-3). The digests can then be compared to the message-digest
+/// signed attribute of the signer infos without a second pass over the content.
+///
+/// Certificates and signer infos are buffered. CRLs are skipped.
+pub struct StreamedSignedData {
+    /// The version of the SignedData
+    version: CmsVersion,
+    /// The digest algorithms
+    digest_algorithms: Vec<AlgorithmIdentifierOwned>,
+    /// The content type of the encapsulated content
+    econtent_type: ObjectIdentifier,
+    /// The number of content bytes written to the sink, or None for detached content
+    content_len: Option<u64>,
+    /// The digests of the content, per digest algorithm
+    content_digests: Vec<(ObjectIdentifier, Vec<u8>)>,
+    /// The certificates
+    certificates: Vec<Certificate>,
+    /// The signer infos
+    signer_infos: Vec<SignerInfo>,
+}
+
+impl StreamedSignedData {
+    /// Parse a SignedData from a stream, writing the encapsulated content to a sink
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the DER, BER or PEM encoded ContentInfo
+    /// * `sink` - Receives the encapsulated content
+    ///
+    /// # Returns
+    ///
+    /// The SignedData, without its content
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidContent` if the stream is not a well formed SignedData,
+    /// and `QuantCryptError::StreamIoError` if reading from the stream or writing to
+    /// the sink fails
+    pub fn parse<R: Read, W: Write>(reader: R, sink: &mut W) -> Result<StreamedSignedData> {
+        let mut reader = open(reader)?;
+        let (ci_end, explicit_end) = read_content_info_header(&mut reader, ID_SIGNED_DATA)?;
+
+        let sd = reader.expect_header(TAG_SEQUENCE)?;
+        let sd_end = reader.end_of(&sd);
+        let version: CmsVersion = read_decoded(&mut reader, TAG_INTEGER)?;
+
+        let mut digest_algorithms = Vec::new();
+        for der in split_set(&reader.read_element(TAG_SET)?)? {
+            digest_algorithms.push(
+                AlgorithmIdentifierOwned::from_der(&der)
+                    .map_err(|_| QuantCryptError::InvalidContent)?,
+            );
+        }
+        let mut digests: Vec<(ObjectIdentifier, Box<dyn DynDigest>)> = digest_algorithms
+            .iter()
+            .filter_map(|alg| new_digest(&alg.oid).map(|digest| (alg.oid, digest)))
+            .collect();
+
+        let eci = reader.expect_header(TAG_SEQUENCE)?;
+        let eci_end = reader.end_of(&eci);
+        let econtent_type: ObjectIdentifier = read_decoded(&mut reader, TAG_OID)?;
+
+        // eContent [0] EXPLICIT OCTET STRING OPTIONAL
+        let content_len = if !reader.at_end(&eci_end)? {
+            let explicit = reader.expect_header(TAG_CONTEXT_0)?;
+            let econtent_end = reader.end_of(&explicit);
+            let header = reader.read_header()?;
+            if header.tag & !CONSTRUCTED != TAG_OCTET_STRING {
+                return Err(QuantCryptError::InvalidContent);
+            }
+            let mut content_len = 0u64;
+            reader.stream_octets(&header, &mut |chunk| {
+                for (_, digest) in digests.iter_mut() {
+                    digest.update(chunk);
+                }
+                sink.write_all(chunk)
+                    .map_err(|_| QuantCryptError::StreamIoError)?;
+                content_len += chunk.len() as u64;
+                Ok(())
+            })?;
+            reader.finish(econtent_end)?;
+            sink.flush().map_err(|_| QuantCryptError::StreamIoError)?;
+            Some(content_len)
+        } else {
+            None
+        };
+        reader.finish(eci_end)?;
+
+        // certificates [0] IMPLICIT CertificateSet OPTIONAL
+        let mut certificates = Vec::new();
+        if reader.peek_header()?.tag == TAG_CONTEXT_0 {
+            let header = reader.read_header()?;
+            for der in split_set(&reader.read_element_as(header, TAG_SET)?)? {
+                // Only X.509 certificates are of interest, other choices are ignored
+                if let Ok(CertificateChoices::Certificate(cert)) =
+                    CertificateChoices::from_der(&der)
+                {
+                    let der = cert.to_der().map_err(|_| QuantCryptError::InvalidContent)?;
+                    certificates.push(Certificate::from_der(&der)?);
+                }
+            }
+        }
+
+        // crls [1] IMPLICIT RevocationInfoChoices OPTIONAL
+        if reader.peek_header()?.tag == TAG_CONTEXT_1 {
+            let header = reader.read_header()?;
+            reader.skip_contents(&header)?;
+        }
+
+        let mut signer_infos = Vec::new();
+        for der in split_set(&reader.read_element(TAG_SET)?)? {
+            signer_infos
+                .push(SignerInfo::from_der(&der).map_err(|_| QuantCryptError::InvalidContent)?);
+        }
+
+        reader.finish(sd_end)?;
+        reader.finish(explicit_end)?;
+        reader.finish(ci_end)?;
+
+        let content_digests = match content_len {
+            Some(_) => digests
+                .into_iter()
+                .map(|(oid, digest)| (oid, digest.finalize().to_vec()))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(StreamedSignedData {
+            version,
+            digest_algorithms,
+            econtent_type,
+            content_len,
+            content_digests,
+            certificates,
+            signer_infos,
+        })
+    }
+
+    /// Get the version of the SignedData
+    pub fn get_version(&self) -> CmsVersion {
+        self.version
+    }
+
+    /// Get the digest algorithms
+    pub fn get_digest_algorithms(&self) -> Vec<AlgorithmIdentifierOwned> {
+        self.digest_algorithms.clone()
+    }
+
+    /// Get the content type of the encapsulated content
+    pub fn get_econtent_type(&self) -> ObjectIdentifier {
+        self.econtent_type
+    }
+
+    /// Get the number of content bytes written to the sink
+    ///
+    /// # Returns
+    ///
+    /// The length of the content, or None if the content is detached
+    pub fn get_content_len(&self) -> Option<u64> {
+        self.content_len
+    }
+
+    /// Get the digest of the content for a digest algorithm
+    ///
+    /// # Arguments
+    ///
+    /// * `digest_algorithm` - The OID of the digest algorithm
+    ///
+    /// # Returns
+    ///
+    /// The digest, or None if the algorithm is not listed in the SignedData,
+    /// is not supported, or the content is detached
+    pub fn get_content_digest(&self, digest_algorithm: &ObjectIdentifier) -> Option<Vec<u8>> {
+        self.content_digests
+            .iter()
+            .find(|(oid, _)| oid == digest_algorithm)
+            .map(|(_, digest)| digest.clone())
+    }
+
+    /// Get the certificates included in the SignedData
+    pub fn get_certificates(&self) -> Vec<Certificate> {
+        self.certificates.clone()
+    }
+
+    /// Get the signer infos
+    pub fn get_signer_infos(&self) -> Vec<SignerInfo> {
+        self.signer_infos.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{ContentEncryptionAlgorithm, EnvelopedDataContent};
+    use crate::{kdf::common::kdf_type::KdfType, wrap::common::wrap_type::WrapType};
+    use cms::content_info::ContentInfo;
+    use cms::enveloped_data::EnvelopedData;
+    use const_oid::db::rfc5911::ID_DATA;
+    use sha2::{Digest, Sha256};
+
+    /// Encode an element with the indefinite length form
+    fn indefinite(tag: u8, children: &[Vec<u8>]) -> Vec<u8> {
+        let mut element = vec![tag, 0x80];
+        for child in children {
+            element.extend_from_slice(child);
+        }
+        element.extend_from_slice(&[0, 0]);
+        element
+    }
+
+    /// Encode an OCTET STRING as segments of a constructed, indefinite length element
+    fn segmented(tag: u8, data: &[u8], segment_len: usize) -> Vec<u8> {
+        let segments: Vec<Vec<u8>> = data
+            .chunks(segment_len)
+            .map(|chunk| [encode_header(TAG_OCTET_STRING, chunk.len()), chunk.to_vec()].concat())
+            .collect();
+        indefinite(tag, &segments)
+    }
+
+    #[test]
+    fn test_streamed_enveloped_data() {
+        let recipient_cert =
+            Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let private_key =
+            PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der")
+                .unwrap();
+
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let mut builder =
+            EnvelopedDataContent::get_builder(ContentEncryptionAlgorithm::Aes256Cbc).unwrap();
+        builder
+            .kem_recipient(
+                &recipient_cert,
+                &KdfType::HkdfWithSha256,
+                &WrapType::Aes256,
+                None,
+            )
+            .unwrap()
+            .content(&data)
+            .unwrap();
+        let der = builder.build().unwrap();
+
+        // DER
+        let mut plaintext = Vec::new();
+        let streamed = StreamedEnvelopedData::decrypt_for_kem_recipient(
+            der.as_slice(),
+            &mut plaintext,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(plaintext, data);
+        assert_eq!(streamed.get_content_len(), data.len() as u64);
+        assert_eq!(streamed.get_content_type(), ID_DATA);
+        assert_eq!(streamed.get_version(), CmsVersion::V3);
+        assert_eq!(streamed.get_recipient_infos().0.len(), 1);
+        assert!(streamed.get_unprotected_attrs().is_none());
+
+        // PEM
+        let pem = pem::encode(&pem::Pem::new("CMS", der.clone()));
+        let mut plaintext = Vec::new();
+        StreamedEnvelopedData::decrypt_for_kem_recipient(
+            pem.as_bytes(),
+            &mut plaintext,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(plaintext, data);
+
+        // BER with indefinite lengths and a segmented encrypted content, as
+        // produced by streaming encoders
+        let ci = ContentInfo::from_der(&der).unwrap();
+        let ed = EnvelopedData::from_der(&ci.content.to_der().unwrap()).unwrap();
+        let eci = &ed.encrypted_content;
+        let ct = eci.encrypted_content.as_ref().unwrap().as_bytes();
+        let ber = indefinite(
+            TAG_SEQUENCE,
+            &[
+                ID_ENVELOPED_DATA.to_der().unwrap(),
+                indefinite(
+                    TAG_CONTEXT_0,
+                    &[indefinite(
+                        TAG_SEQUENCE,
+                        &[
+                            ed.version.to_der().unwrap(),
+                            ed.recip_infos.to_der().unwrap(),
+                            indefinite(
+                                TAG_SEQUENCE,
+                                &[
+                                    eci.content_type.to_der().unwrap(),
+                                    eci.content_enc_alg.to_der().unwrap(),
+                                    segmented(TAG_CONTEXT_0, ct, 1000),
+                                ],
+                            ),
+                        ],
+                    )],
+                ),
+            ],
+        );
+        let mut plaintext = Vec::new();
+        StreamedEnvelopedData::decrypt_for_kem_recipient(
+            ber.as_slice(),
+            &mut plaintext,
+            &recipient_cert,
+            &private_key,
+        )
+        .unwrap();
+        assert_eq!(plaintext, data);
+
+        // Truncated streams are rejected
+        let mut plaintext = Vec::new();
+        assert!(StreamedEnvelopedData::decrypt_for_kem_recipient(
+            &ber[..ber.len() - 2],
+            &mut plaintext,
+            &recipient_cert,
+            &private_key,
+        )
+        .is_err());
+
+        // An EnvelopedData is not a SignedData
+        assert_eq!(
+            StreamedSignedData::parse(der.as_slice(), &mut Vec::new()).err(),
+            Some(QuantCryptError::InvalidContent)
+        );
+    }
+
+    #[test]
+    fn test_streamed_signed_data() {
+        let cert_der =
+            std::fs::read("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 241) as u8).collect();
+        let sha256 = AlgorithmIdentifierOwned {
+            oid: "2.16.840.1.101.3.4.2.1".parse().unwrap(),
+            parameters: None,
+        };
+
+        let ber = indefinite(
+            TAG_SEQUENCE,
+            &[
+                ID_SIGNED_DATA.to_der().unwrap(),
+                indefinite(
+                    TAG_CONTEXT_0,
+                    &[indefinite(
+                        TAG_SEQUENCE,
+                        &[
+                            CmsVersion::V1.to_der().unwrap(),
+                            [
+                                encode_header(TAG_SET, sha256.to_der().unwrap().len()),
+                                sha256.to_der().unwrap(),
+                            ]
+                            .concat(),
+                            indefinite(
+                                TAG_SEQUENCE,
+                                &[
+                                    ID_DATA.to_der().unwrap(),
+                                    indefinite(
+                                        TAG_CONTEXT_0,
+                                        &[segmented(TAG_OCTET_STRING | CONSTRUCTED, &data, 4096)],
+                                    ),
+                                ],
+                            ),
+                            [encode_header(TAG_CONTEXT_0, cert_der.len()), cert_der].concat(),
+                            encode_header(TAG_SET, 0),
+                        ],
+                    )],
+                ),
+            ],
+        );
+
+        let mut content = Vec::new();
+        let streamed = StreamedSignedData::parse(ber.as_slice(), &mut content).unwrap();
+        assert_eq!(content, data);
+        assert_eq!(streamed.get_content_len(), Some(data.len() as u64));
+        assert_eq!(streamed.get_econtent_type(), ID_DATA);
+        assert_eq!(streamed.get_version(), CmsVersion::V1);
+        assert_eq!(streamed.get_digest_algorithms().len(), 1);
+        assert_eq!(
+            streamed.get_content_digest(&sha256.oid).unwrap(),
+            Sha256::digest(&data).to_vec()
+        );
+        assert_eq!(streamed.get_certificates().len(), 1);
+        assert!(streamed.get_signer_infos().is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::dsa::common::{config::oids::Oid, dsa_type::DsaType, prehash_dsa_type::PrehashDsaType};
+use crate::utils::manager_pool::{get_dsa_manager, get_prehash_dsa_manager};
+use crate::QuantCryptError;
 
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
@@ -64,6 +66,36 @@ impl DsaAlgorithm {
         DsaAlgorithm::iter().collect()
     }
 
+    /// Get the DSA algorithms whose backends are available
+    ///
+    /// Distribution builds of OpenSSL may lack curves such as the Brainpool
+    /// curves, and the composites using them can't be constructed. Filtering
+    /// the algorithms at startup keeps them out of negotiation, instead of
+    /// failing when a peer picks them.
+    ///
+    /// # Returns
+    ///
+    /// The available algorithms
+    pub fn available() -> Vec<DsaAlgorithm> {
+        DsaAlgorithm::iter().filter(|a| a.is_available()).collect()
+    }
+
+    /// Check if the backends of the algorithm, and of all its components, are available
+    ///
+    /// Algorithms provided by a registered backend are always available.
+    ///
+    /// # Returns
+    ///
+    /// True if the algorithm can be used, false otherwise
+    pub fn is_available(&self) -> bool {
+        let result = match (self.get_dsa_type(), self.get_prehash_dsa_type()) {
+            (Some(dsa_type), _) => get_dsa_manager(dsa_type).map(|_| ()),
+            (None, Some(dsa_type)) => get_prehash_dsa_manager(dsa_type).map(|_| ()),
+            (None, None) => Ok(()),
+        };
+        !matches!(result, Err(QuantCryptError::BackendUnavailable { .. }))
+    }
+
     /// Get the corresponding `DsaType` for the algorithm
     pub(crate) fn get_dsa_type(&self) -> Option<DsaType> {
         match self {
@@ -152,6 +184,44 @@ impl DsaAlgorithm {
         )
     }
 
+    /// Get the NIST security category of the algorithm against quantum attackers
+    ///
+    /// For composite algorithms, this is the category of the ML-DSA component, as the
+    /// classical component offers no protection against a quantum attacker.
+    ///
+    /// # Returns
+    ///
+    /// The NIST security category, from 1 to 5
+    pub fn get_quantum_security_level(&self) -> u8 {
+        match self {
+            DsaAlgorithm::SlhDsaSha2_128s
+            | DsaAlgorithm::SlhDsaSha2_128f
+            | DsaAlgorithm::SlhDsaShake128s
+            | DsaAlgorithm::SlhDsaShake128f => 1,
+            DsaAlgorithm::MlDsa44
+            | DsaAlgorithm::MlDsa44Rsa2048Pss
+            | DsaAlgorithm::MlDsa44Rsa2048Pkcs15
+            | DsaAlgorithm::MlDsa44Ed25519
+            | DsaAlgorithm::MlDsa44EcdsaP256
+            | DsaAlgorithm::MlDsa44Rsa2048PssSha256
+            | DsaAlgorithm::MlDsa44Rsa2048Pkcs15Sha256
+            | DsaAlgorithm::MlDsa44Ed25519Sha512
+            | DsaAlgorithm::MlDsa44EcdsaP256Sha256 => 2,
+            DsaAlgorithm::MlDsa87
+            | DsaAlgorithm::MlDsa87EcdsaP384
+            | DsaAlgorithm::MlDsa87EcdsaBrainpoolP384r1
+            | DsaAlgorithm::MlDsa87Ed448
+            | DsaAlgorithm::MlDsa87EcdsaP384Sha512
+            | DsaAlgorithm::MlDsa87EcdsaBrainpoolP384r1Sha512
+            | DsaAlgorithm::MlDsa87Ed448Sha512
+            | DsaAlgorithm::SlhDsaSha2_256s
+            | DsaAlgorithm::SlhDsaSha2_256f
+            | DsaAlgorithm::SlhDsaShake256s
+            | DsaAlgorithm::SlhDsaShake256f => 5,
+            _ => 3,
+        }
+    }
+
     /// Get the OID for the algorithm
     ///
     /// # Returns
@@ -0,0 +1,97 @@
+use crate::dsa::api::algorithm::DsaAlgorithm;
+use crate::dsa::api::verify_after_sign::{sign_dsa, sign_prehash_dsa};
+use crate::dsa::common::dsa_trait::Dsa;
+use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+use crate::utils::manager_pool::{get_dsa_manager, get_prehash_dsa_manager};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Sign a message with a raw private key
+///
+/// # Arguments
+///
+/// * `algorithm` - The DSA algorithm
+/// * `sk` - The raw private key bytes
+/// * `msg` - The message to sign
+///
+/// # Returns
+///
+/// The signature
+///
+/// # Example
+/// ```
+/// use quantcrypt::dsas::{self, DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65).generate().unwrap();
+///
+/// let msg = b"Hello, world!";
+/// let sig = sk.sign(msg).unwrap();
+/// assert!(dsas::verify(DsaAlgorithm::MlDsa65, pk.get_key(), msg, &sig).unwrap());
+/// ```
+pub fn sign(algorithm: DsaAlgorithm, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+    if let Some(dsa_type) = algorithm.get_dsa_type() {
+        sign_dsa(&get_dsa_manager(dsa_type)?, sk, msg)
+    } else {
+        let dsa_type = algorithm
+            .get_prehash_dsa_type()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        sign_prehash_dsa(&get_prehash_dsa_manager(dsa_type)?, sk, msg)
+    }
+}
+
+/// Verify a signature with a raw public key
+///
+/// # Arguments
+///
+/// * `algorithm` - The DSA algorithm
+/// * `pk` - The raw public key bytes
+/// * `msg` - The message that was signed
+/// * `signature` - The signature
+///
+/// # Returns
+///
+/// True if the signature is valid, false otherwise
+pub fn verify(algorithm: DsaAlgorithm, pk: &[u8], msg: &[u8], signature: &[u8]) -> Result<bool> {
+    let result = if let Some(dsa_type) = algorithm.get_dsa_type() {
+        get_dsa_manager(dsa_type)?
+            .verify(pk, msg, signature)
+            .unwrap_or(false)
+    } else {
+        let dsa_type = algorithm
+            .get_prehash_dsa_type()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        get_prehash_dsa_manager(dsa_type)?
+            .verify(pk, msg, signature)
+            .unwrap_or(false)
+    };
+    Ok(result)
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oneshot_sign_verify() {
+        let algs = [
+            DsaAlgorithm::MlDsa44,
+            DsaAlgorithm::MlDsa65EcdsaP384,
+            DsaAlgorithm::SlhDsaShake128f,
+        ];
+        let msg = b"Hello, world!";
+        for alg in algs {
+            let (pk, sk) = if let Some(dsa_type) = alg.get_dsa_type() {
+                get_dsa_manager(dsa_type).unwrap().key_gen().unwrap()
+            } else {
+                get_prehash_dsa_manager(alg.get_prehash_dsa_type().unwrap())
+                    .unwrap()
+                    .key_gen()
+                    .unwrap()
+            };
+            let sig = sign(alg, &sk, msg).unwrap();
+            assert!(verify(alg, &pk, msg, &sig).unwrap());
+            assert!(!verify(alg, &pk, b"Goodbye", &sig).unwrap());
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::asn1::keygen_attestation::KeyGenAttestation;
 use crate::asn1::private_key::PrivateKey;
 use crate::asn1::public_key::PublicKey;
 use crate::dsa::common::config::oids::Oid;
@@ -25,6 +26,7 @@ type Result<T> = std::result::Result<T, errors::QuantCryptError>;
 /// let sig = sk.sign(msg).unwrap();
 /// assert!(pk.verify(msg, &sig).unwrap());
 /// ```
+#[derive(Clone)]
 pub struct DsaKeyGenerator {
     /// The algorithm to use for key generation
     algorithm: DsaAlgorithm,
@@ -67,9 +69,31 @@ impl DsaKeyGenerator {
             Ok((pk, sk))
         }
     }
+
+    /// Generate a keypair and attest that it was generated by quantcrypt
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - The signing key of the device or platform, see `KeyGenAttestation`
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys and the attestation (pk, sk, attestation)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` will be returned if the identity key can't sign
+    pub fn generate_attested(
+        &mut self,
+        identity: &PrivateKey,
+    ) -> Result<(PublicKey, PrivateKey, KeyGenAttestation)> {
+        let (pk, sk) = self.generate()?;
+        let attestation = KeyGenAttestation::attest(&pk, identity)?;
+        Ok((pk, sk, attestation))
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod test {
     use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
 
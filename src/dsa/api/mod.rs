@@ -1,2 +1,4 @@
 pub mod algorithm;
+pub mod functions;
 pub mod key_generator;
+pub mod verify_after_sign;
@@ -0,0 +1,170 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::dsa::common::dsa_trait::Dsa;
+use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+use crate::dsa::dsa_manager::{DsaManager, PrehashDsaManager};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Which signatures are verified before they are returned
+///
+/// A fault injected while signing (e.g. a voltage glitch on an embedded signer)
+/// can produce a faulty signature from which the private key can be recovered.
+/// Verifying the signature with the public key derived from the private key
+/// catches such faults before the signature leaves the signer, at the cost of
+/// a verification per signature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerifyAfterSign {
+    /// Signatures are returned without verification (the default)
+    Disabled,
+    /// The hardened profile: ML-DSA, SLH-DSA and composite signatures are verified
+    Hardened,
+    /// All signatures are verified
+    All,
+}
+
+/// The mode applied to all signatures, as a `u8`
+static VERIFY_AFTER_SIGN: AtomicU8 = AtomicU8::new(0);
+
+/// Set which signatures are verified before they are returned
+///
+/// This applies to every signature produced with a `PrivateKey` (certificates,
+/// CRLs, OCSP responses, CMS, bundles) and with `dsas::sign`. A signature failing
+/// verification is discarded and `QuantCryptError::SignatureFaultDetected` is
+/// returned instead. The setting is process-wide.
+///
+/// # Arguments
+///
+/// * `mode` - The signatures to verify
+pub fn set_verify_after_sign(mode: VerifyAfterSign) {
+    let value = match mode {
+        VerifyAfterSign::Disabled => 0,
+        VerifyAfterSign::Hardened => 1,
+        VerifyAfterSign::All => 2,
+    };
+    VERIFY_AFTER_SIGN.store(value, Ordering::Relaxed);
+}
+
+/// Get which signatures are verified before they are returned
+///
+/// # Returns
+///
+/// The mode, `VerifyAfterSign::Disabled` unless changed with `set_verify_after_sign`
+pub fn get_verify_after_sign() -> VerifyAfterSign {
+    match VERIFY_AFTER_SIGN.load(Ordering::Relaxed) {
+        0 => VerifyAfterSign::Disabled,
+        1 => VerifyAfterSign::Hardened,
+        _ => VerifyAfterSign::All,
+    }
+}
+
+/// Check a fresh signature, if the mode requires it
+///
+/// # Arguments
+///
+/// * `mode` - The signatures to verify
+/// * `is_hardened` - True if the algorithm is covered by the hardened profile
+/// * `verify` - A function verifying the signature with the derived public key
+///
+/// # Errors
+///
+/// `QuantCryptError::SignatureFaultDetected` if the signature doesn't verify
+fn check_signature(
+    mode: VerifyAfterSign,
+    is_hardened: bool,
+    verify: impl FnOnce() -> Result<bool>,
+) -> Result<()> {
+    let required = match mode {
+        VerifyAfterSign::Disabled => false,
+        VerifyAfterSign::Hardened => is_hardened,
+        VerifyAfterSign::All => true,
+    };
+    if required && !verify().unwrap_or(false) {
+        return Err(QuantCryptError::SignatureFaultDetected);
+    }
+    Ok(())
+}
+
+/// Sign a message, verifying the signature if the mode requires it
+///
+/// # Arguments
+///
+/// * `dsa` - The DSA manager
+/// * `sk` - The secret key
+/// * `msg` - The message to sign
+///
+/// # Returns
+///
+/// The signature
+pub(crate) fn sign_dsa(dsa: &DsaManager, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+    let sig = dsa.sign(sk, msg)?;
+    let is_hardened = dsa.get_dsa_info().dsa_type.is_slh_dsa();
+    check_signature(get_verify_after_sign(), is_hardened, || {
+        dsa.verify(&dsa.get_public_key(sk)?, msg, &sig)
+    })?;
+    Ok(sig)
+}
+
+/// Sign a message with a pre-hash DSA, verifying the signature if the mode requires it
+///
+/// # Arguments
+///
+/// * `dsa` - The pre-hash DSA manager
+/// * `sk` - The secret key
+/// * `msg` - The message to sign
+///
+/// # Returns
+///
+/// The signature
+pub(crate) fn sign_prehash_dsa(dsa: &PrehashDsaManager, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+    let sig = dsa.sign(sk, msg)?;
+    // All pre-hash DSAs are ML-DSA or composites containing ML-DSA
+    check_signature(get_verify_after_sign(), true, || {
+        dsa.verify(&dsa.get_public_key(sk)?, msg, &sig)
+    })?;
+    Ok(sig)
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::dsa::common::dsa_type::DsaType;
+    use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+
+    #[test]
+    fn test_verify_after_sign() {
+        // The global mode is left untouched, as other tests sign concurrently
+        assert_eq!(get_verify_after_sign(), VerifyAfterSign::Disabled);
+
+        let faulty = || Ok(false);
+        let failing = || Err(QuantCryptError::InvalidPublicKey);
+        assert!(check_signature(VerifyAfterSign::Disabled, true, faulty).is_ok());
+        assert!(check_signature(VerifyAfterSign::Hardened, false, faulty).is_ok());
+        assert_eq!(
+            check_signature(VerifyAfterSign::Hardened, true, faulty),
+            Err(QuantCryptError::SignatureFaultDetected)
+        );
+        assert_eq!(
+            check_signature(VerifyAfterSign::All, false, failing),
+            Err(QuantCryptError::SignatureFaultDetected)
+        );
+        assert!(check_signature(VerifyAfterSign::All, false, || Ok(true)).is_ok());
+
+        // Valid signatures pass the check
+        let mut dsa = DsaManager::new(DsaType::SlhDsaSha2_128f).unwrap();
+        let (pk, sk) = dsa.key_gen().unwrap();
+        let sig = sign_dsa(&dsa, &sk, b"message").unwrap();
+        assert!(dsa.verify(&pk, b"message", &sig).unwrap());
+        assert_eq!(dsa.get_public_key(&sk).unwrap(), pk);
+
+        let mut dsa = PrehashDsaManager::new(PrehashDsaType::MlDsa44Ed25519).unwrap();
+        let (pk, sk) = dsa.key_gen().unwrap();
+        assert_eq!(dsa.get_public_key(&sk).unwrap(), pk);
+        let sig = sign_prehash_dsa(&dsa, &sk, b"message").unwrap();
+        assert!(check_signature(VerifyAfterSign::All, true, || {
+            dsa.verify(&dsa.get_public_key(&sk)?, b"message", &sig)
+        })
+        .is_ok());
+    }
+}
@@ -47,6 +47,14 @@ impl Oid for DsaType {
             DsaType::SlhDsaShake192f => "2.16.840.1.101.3.4.3.29",
             DsaType::SlhDsaShake256s => "2.16.840.1.101.3.4.3.30",
             DsaType::SlhDsaShake256f => "2.16.840.1.101.3.4.3.31",
+
+            // The round 3 OIDs of the OQS provider
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium2 => "1.3.6.1.4.1.2.267.7.4.4",
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium3 => "1.3.6.1.4.1.2.267.7.6.5",
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium5 => "1.3.6.1.4.1.2.267.7.8.7",
         }
         .to_string()
     }
@@ -36,6 +36,12 @@ impl PKLen for DsaType {
             DsaType::SlhDsaShake192f => Some(48),
             DsaType::SlhDsaShake256s => Some(64),
             DsaType::SlhDsaShake256f => Some(64),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium2 => Some(1312),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium3 => Some(1952),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium5 => Some(2592),
 
             DsaType::EcdsaP384SHA384 => Some(97),
             DsaType::EcdsaBrainpoolP384r1SHA384 => Some(97),
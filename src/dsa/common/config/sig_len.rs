@@ -37,6 +37,12 @@ impl SigLen for DsaType {
             DsaType::SlhDsaShake192f => Some(35664),
             DsaType::SlhDsaShake256s => Some(29792),
             DsaType::SlhDsaShake256f => Some(49856),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium2 => Some(2420),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium3 => Some(3293),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium5 => Some(4595),
 
             // P256 and P384 variations do not have a fixed sig_len
             DsaType::EcdsaP384SHA384 => None,
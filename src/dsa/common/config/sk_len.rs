@@ -37,6 +37,12 @@ impl SKLen for DsaType {
             DsaType::SlhDsaShake192f => Some(48 * 2),
             DsaType::SlhDsaShake256s => Some(64 * 2),
             DsaType::SlhDsaShake256f => Some(64 * 2),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium2 => Some(2528),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium3 => Some(4000),
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium5 => Some(4864),
 
             DsaType::EcdsaP384SHA384 => Some(48),
             DsaType::EcdsaBrainpoolP384r1SHA384 => Some(48),
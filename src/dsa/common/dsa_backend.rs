@@ -0,0 +1,157 @@
+use rand_core::CryptoRngCore;
+
+use crate::dsa::common::dsa_info::DsaInfo;
+use crate::dsa::common::prehash_dsa_info::PrehashDsaInfo;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// An external implementation of a DSA, such as a vendor HSM
+///
+/// A backend registered with [`register_dsa_backend`](crate::dsas::register_dsa_backend)
+/// replaces the built-in implementation of its DSA type everywhere in the crate,
+/// including as the traditional component of composite signatures.
+///
+/// Unlike [`Dsa`](crate::dsas::Dsa), this trait is object safe and shared between
+/// threads, so all methods take `&self`.
+pub trait DsaBackend: Send + Sync {
+    /// Get the metadata of the DSA. The DSA type of the metadata is the type
+    /// the backend is registered for.
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the DSA
+    fn get_dsa_info(&self) -> DsaInfo;
+
+    /// Generate a keypair using the default random number generator of the backend
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&self) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Generate a keypair using the specified random number generator
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&self, rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Sign a message
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to sign the message
+    /// * `msg` - The message to sign
+    ///
+    /// # Returns
+    ///
+    /// The signature of the message
+    fn sign(&self, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>>;
+
+    /// Verify a signature
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to verify the signature
+    /// * `msg` - The message to verify
+    /// * `signature` - The signature to verify
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the signature is valid
+    fn verify(&self, pk: &[u8], msg: &[u8], signature: &[u8]) -> Result<bool>;
+
+    /// Get the public key from a secret key
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key
+    ///
+    /// # Returns
+    ///
+    /// The public key
+    fn get_public_key(&self, sk: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// An external implementation of a pre-hash DSA (ML-DSA or a composite), such as a vendor HSM
+///
+/// A backend registered with
+/// [`register_prehash_dsa_backend`](crate::dsas::register_prehash_dsa_backend)
+/// replaces the built-in implementation of its DSA type everywhere in the crate,
+/// including as the ML-DSA component of composite signatures.
+pub trait PrehashDsaBackend: Send + Sync {
+    /// Get the metadata of the DSA. The DSA type of the metadata is the type
+    /// the backend is registered for.
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the DSA
+    fn get_dsa_info(&self) -> PrehashDsaInfo;
+
+    /// Generate a keypair using the default random number generator of the backend
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&self) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Generate a keypair using the specified random number generator
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&self, rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Sign a message
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to sign the message
+    /// * `msg` - The message to sign
+    /// * `ctx` - The context to sign
+    ///
+    /// # Returns
+    ///
+    /// The signature of the message
+    fn sign_with_ctx(&self, sk: &[u8], msg: &[u8], ctx: Option<&[u8]>) -> Result<Vec<u8>>;
+
+    /// Verify a signature
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to verify the signature
+    /// * `msg` - The message to verify
+    /// * `signature` - The signature to verify
+    /// * `ctx` - The context to verify
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the signature is valid
+    fn verify_with_ctx(
+        &self,
+        pk: &[u8],
+        msg: &[u8],
+        signature: &[u8],
+        ctx: Option<&[u8]>,
+    ) -> Result<bool>;
+
+    /// Get the public key from a secret key
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key
+    ///
+    /// # Returns
+    ///
+    /// The public key
+    fn get_public_key(&self, sk: &[u8]) -> Result<Vec<u8>>;
+}
@@ -45,7 +45,7 @@ pub trait Dsa {
         Err(QuantCryptError::InvalidOid)
     }
 
-    /// Generate a keypair using the default RNG of OpenSSL
+    /// Generate a keypair using the default RNG, seeded from the health tested system entropy
     ///
     /// # Returns
     ///
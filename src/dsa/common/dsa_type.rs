@@ -3,7 +3,13 @@ use strum_macros::EnumIter;
 
 use super::config::oids::Oid;
 
-#[derive(Clone, Debug, PartialEq, EnumIter)]
+/// The signature algorithms
+///
+/// The enum is non-exhaustive: the legacy variants only exist with the `legacy`
+/// feature, and matches outside of the crate need a wildcard arm so that they
+/// compile whichever features are enabled.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[non_exhaustive]
 pub enum DsaType {
     // RSA
     Rsa2048PssSha256,
@@ -34,6 +40,17 @@ pub enum DsaType {
     SlhDsaShake192f,
     SlhDsaShake256s,
     SlhDsaShake256f,
+
+    // Legacy pre-standard algorithms, verify only:
+    /// Dilithium2 from round 3 of the NIST process
+    #[cfg(feature = "legacy")]
+    Dilithium2,
+    /// Dilithium3 from round 3 of the NIST process
+    #[cfg(feature = "legacy")]
+    Dilithium3,
+    /// Dilithium5 from round 3 of the NIST process
+    #[cfg(feature = "legacy")]
+    Dilithium5,
 }
 
 impl DsaType {
@@ -46,6 +63,43 @@ impl DsaType {
         false
     }
 
+    pub fn is_slh_dsa(&self) -> bool {
+        matches!(
+            self,
+            DsaType::SlhDsaSha2_128s
+                | DsaType::SlhDsaSha2_128f
+                | DsaType::SlhDsaSha2_192s
+                | DsaType::SlhDsaSha2_192f
+                | DsaType::SlhDsaSha2_256s
+                | DsaType::SlhDsaSha2_256f
+                | DsaType::SlhDsaShake128s
+                | DsaType::SlhDsaShake128f
+                | DsaType::SlhDsaShake192s
+                | DsaType::SlhDsaShake192f
+                | DsaType::SlhDsaShake256s
+                | DsaType::SlhDsaShake256f
+        )
+    }
+
+    /// Check if the DSA is a pre-standard algorithm of the `legacy` feature
+    ///
+    /// Legacy DSAs are verify only: they aren't part of `DsaAlgorithm`, so keys
+    /// can't be generated and nothing can be signed or issued with them.
+    ///
+    /// # Returns
+    ///
+    /// True if the DSA is a legacy algorithm, false otherwise
+    pub fn is_legacy(&self) -> bool {
+        #[cfg(feature = "legacy")]
+        if matches!(
+            self,
+            DsaType::Dilithium2 | DsaType::Dilithium3 | DsaType::Dilithium5
+        ) {
+            return true;
+        }
+        false
+    }
+
     pub fn from_oid(oid: &str) -> Option<DsaType> {
         let all_dsa_types = DsaType::all();
         all_dsa_types
@@ -1,8 +1,8 @@
 pub mod config;
+pub mod dsa_backend;
 pub mod dsa_info;
 pub mod dsa_trait;
 pub mod dsa_type;
-pub mod macros;
 pub mod prehash_dsa_info;
 pub mod prehash_dsa_trait;
 pub mod prehash_dsa_type;
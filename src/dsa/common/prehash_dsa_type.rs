@@ -3,7 +3,7 @@ use strum_macros::EnumIter;
 
 use super::config::oids::Oid;
 
-#[derive(Clone, Debug, PartialEq, EnumIter)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, EnumIter)]
 pub enum PrehashDsaType {
     // ML DSA
     MlDsa44,
@@ -7,6 +7,7 @@ use crate::dsa::dsa_manager::{DsaManager, PrehashDsaManager};
 
 use crate::hash::common::hash_trait::Hash;
 use crate::hash::hash_manager::HashManager;
+use crate::utils::entropy::SharedRng;
 use crate::QuantCryptError;
 
 use der::asn1::OctetString;
@@ -31,6 +32,34 @@ pub struct CompositeDsaManager {
 }
 
 impl CompositeDsaManager {
+    /// Create a new DSA instance drawing its randomness from a caller-supplied RNG
+    ///
+    /// The traditional and post-quantum components share the RNG.
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    /// * `rng` - The RNG used by key generation and signing
+    ///
+    /// # Returns
+    ///
+    /// A new DSA instance
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if a component is provided by a registered backend
+    pub fn new_with_rng(dsa_type: PrehashDsaType, rng: SharedRng) -> Result<Self> {
+        let mut dsa = Self::new(dsa_type)?;
+        dsa.set_rng(rng)?;
+        Ok(dsa)
+    }
+
+    /// Draw the randomness of the components from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) -> Result<()> {
+        self.trad_dsa.set_rng(rng.clone())?;
+        self.pq_dsa.set_rng(rng)
+    }
+
     /// Get's the message that is to be signed after pre-hashing and adding the domain
     /// as may be required by the DSA algorithm
     ///
@@ -304,9 +333,9 @@ impl PrehashDsa for CompositeDsaManager {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "openssl"))]
 mod tests {
-    use crate::dsa::common::macros::test_prehash_dsa;
+    use crate::test_prehash_dsa;
 
     use super::*;
 
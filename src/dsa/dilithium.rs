@@ -0,0 +1,482 @@
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Shake128, Shake256};
+
+use crate::dsa::common::dsa_info::DsaInfo;
+use crate::dsa::common::dsa_trait::Dsa;
+use crate::dsa::common::dsa_type::DsaType;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+// Dilithium as submitted to round 3 of the NIST process (version 3.1) shares
+// its lattice arithmetic with ML-DSA (FIPS 204), but hashes differently:
+//
+// * tr = H(pk) is 32 bytes instead of 64
+// * mu = H(tr || M), without the domain separator and context of ML-DSA
+// * The challenge seed c~ is always 32 bytes, making the Dilithium3 and
+//   Dilithium5 signatures 16 and 32 bytes shorter than ML-DSA's
+//
+// Only verification is implemented, for artifacts signed before FIPS 204.
+
+/// The modulus q
+const Q: i64 = 8380417;
+
+/// The number of dropped bits of t
+const D: u32 = 13;
+
+/// The number of coefficients of a polynomial
+const N: usize = 256;
+
+/// The length of rho, tr and the challenge seed c~
+const SEED_LEN: usize = 32;
+
+/// The length of mu
+const MU_LEN: usize = 64;
+
+type Poly = [i64; N];
+
+/// The parameters of a Dilithium security level
+struct Params {
+    k: usize,
+    l: usize,
+    tau: usize,
+    beta: i64,
+    gamma1: i64,
+    gamma2: i64,
+    omega: usize,
+}
+
+impl Params {
+    fn get(dsa_type: &DsaType) -> Option<Params> {
+        match dsa_type {
+            DsaType::Dilithium2 => Some(Params {
+                k: 4,
+                l: 4,
+                tau: 39,
+                beta: 78,
+                gamma1: 1 << 17,
+                gamma2: (Q - 1) / 88,
+                omega: 80,
+            }),
+            DsaType::Dilithium3 => Some(Params {
+                k: 6,
+                l: 5,
+                tau: 49,
+                beta: 196,
+                gamma1: 1 << 19,
+                gamma2: (Q - 1) / 32,
+                omega: 55,
+            }),
+            DsaType::Dilithium5 => Some(Params {
+                k: 8,
+                l: 7,
+                tau: 60,
+                beta: 120,
+                gamma1: 1 << 19,
+                gamma2: (Q - 1) / 32,
+                omega: 75,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The number of bits of a coefficient of z
+    fn z_bits(&self) -> usize {
+        if self.gamma1 == 1 << 17 {
+            18
+        } else {
+            20
+        }
+    }
+
+    /// The number of bits of a coefficient of w1
+    fn w1_bits(&self) -> usize {
+        if self.gamma2 == (Q - 1) / 88 {
+            6
+        } else {
+            4
+        }
+    }
+
+    fn pk_len(&self) -> usize {
+        SEED_LEN + self.k * N * 10 / 8
+    }
+
+    fn sig_len(&self) -> usize {
+        SEED_LEN + self.l * N * self.z_bits() / 8 + self.omega + self.k
+    }
+}
+
+/// Reduce to [0, q)
+fn reduce(a: i64) -> i64 {
+    a.rem_euclid(Q)
+}
+
+/// The powers of the 512th root of unity 1753, in bit-reversed order
+fn zetas() -> [i64; N] {
+    let mut zetas = [0i64; N];
+    for (i, zeta) in zetas.iter_mut().enumerate() {
+        let exp = (i as u8).reverse_bits();
+        *zeta = (0..exp).fold(1, |acc, _| acc * 1753 % Q);
+    }
+    zetas
+}
+
+/// The number theoretic transform (FIPS 204, Algorithm 41)
+fn ntt(w: &mut Poly, zetas: &[i64; N]) {
+    let mut m = 0;
+    let mut len = 128;
+    while len >= 1 {
+        for start in (0..N).step_by(2 * len) {
+            m += 1;
+            let z = zetas[m];
+            for j in start..start + len {
+                let t = z * w[j + len] % Q;
+                w[j + len] = reduce(w[j] - t);
+                w[j] = reduce(w[j] + t);
+            }
+        }
+        len /= 2;
+    }
+}
+
+/// The inverse number theoretic transform (FIPS 204, Algorithm 42)
+fn inv_ntt(w: &mut Poly, zetas: &[i64; N]) {
+    let mut m = N;
+    let mut len = 1;
+    while len < N {
+        for start in (0..N).step_by(2 * len) {
+            m -= 1;
+            let z = Q - zetas[m];
+            for j in start..start + len {
+                let t = w[j];
+                w[j] = reduce(t + w[j + len]);
+                w[j + len] = z * reduce(t - w[j + len]) % Q;
+            }
+        }
+        len *= 2;
+    }
+    // 256^-1 mod q
+    for c in w.iter_mut() {
+        *c = *c * 8347681 % Q;
+    }
+}
+
+/// Unpack coefficients of `bits` bits each, least significant bit first
+fn unpack_bits(bytes: &[u8], bits: usize) -> Poly {
+    let mut poly = [0i64; N];
+    for (i, c) in poly.iter_mut().enumerate() {
+        for b in 0..bits {
+            let pos = i * bits + b;
+            *c |= i64::from((bytes[pos / 8] >> (pos % 8)) & 1) << b;
+        }
+    }
+    poly
+}
+
+/// Pack coefficients of `bits` bits each, least significant bit first
+fn pack_bits(poly: &Poly, bits: usize, out: &mut Vec<u8>) {
+    let start = out.len();
+    out.resize(start + N * bits / 8, 0);
+    for (i, c) in poly.iter().enumerate() {
+        for b in 0..bits {
+            let pos = i * bits + b;
+            out[start + pos / 8] |= (((c >> b) & 1) as u8) << (pos % 8);
+        }
+    }
+}
+
+/// Sample a polynomial of the matrix A in the NTT domain
+fn expand_a(rho: &[u8], i: usize, j: usize) -> Poly {
+    let mut shake = Shake128::default();
+    shake.update(rho);
+    shake.update(&[j as u8, i as u8]);
+    let mut reader = shake.finalize_xof();
+    let mut poly = [0i64; N];
+    let mut n = 0;
+    let mut buf = [0u8; 3];
+    while n < N {
+        reader.read(&mut buf);
+        let c = i64::from(buf[0]) | i64::from(buf[1]) << 8 | i64::from(buf[2] & 0x7f) << 16;
+        if c < Q {
+            poly[n] = c;
+            n += 1;
+        }
+    }
+    poly
+}
+
+/// Sample the challenge polynomial with `tau` coefficients of +-1
+fn sample_in_ball(seed: &[u8], tau: usize) -> Poly {
+    let mut shake = Shake256::default();
+    shake.update(seed);
+    let mut reader = shake.finalize_xof();
+    let mut signs = [0u8; 8];
+    reader.read(&mut signs);
+    let mut signs = u64::from_le_bytes(signs);
+    let mut c = [0i64; N];
+    for i in N - tau..N {
+        let mut b = [0u8; 1];
+        loop {
+            reader.read(&mut b);
+            if usize::from(b[0]) <= i {
+                break;
+            }
+        }
+        let b = usize::from(b[0]);
+        c[i] = c[b];
+        c[b] = if signs & 1 == 1 { Q - 1 } else { 1 };
+        signs >>= 1;
+    }
+    c
+}
+
+/// Recover the high bits of r with the hint (FIPS 204, Algorithms 36 and 40)
+fn use_hint(r: i64, hint: bool, gamma2: i64) -> i64 {
+    let m = (Q - 1) / (2 * gamma2);
+    let mut r0 = r % (2 * gamma2);
+    if r0 > gamma2 {
+        r0 -= 2 * gamma2;
+    }
+    let r1 = if r - r0 == Q - 1 {
+        r0 -= 1;
+        0
+    } else {
+        (r - r0) / (2 * gamma2)
+    };
+    match (hint, r0 > 0) {
+        (false, _) => r1,
+        (true, true) => (r1 + 1).rem_euclid(m),
+        (true, false) => (r1 - 1).rem_euclid(m),
+    }
+}
+
+/// Unpack the hint, rejecting any non-canonical encoding
+fn unpack_hint(bytes: &[u8], params: &Params) -> Option<Vec<[bool; N]>> {
+    let omega = params.omega;
+    let mut hint = vec![[false; N]; params.k];
+    let mut k = 0;
+    for (i, h) in hint.iter_mut().enumerate() {
+        let end = usize::from(bytes[omega + i]);
+        if end < k || end > omega {
+            return None;
+        }
+        for j in k..end {
+            // Indices are strictly increasing, for strong unforgeability
+            if j > k && bytes[j] <= bytes[j - 1] {
+                return None;
+            }
+            h[usize::from(bytes[j])] = true;
+        }
+        k = end;
+    }
+    if bytes[k..omega].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(hint)
+}
+
+/// Verify a signature on a precomputed mu
+fn verify_mu(params: &Params, pk: &[u8], mu: &[u8], sig: &[u8]) -> bool {
+    let (rho, t1_bytes) = pk.split_at(SEED_LEN);
+    let z_len = N * params.z_bits() / 8;
+    let (c_tilde, rest) = sig.split_at(SEED_LEN);
+    let (z_bytes, hint_bytes) = rest.split_at(params.l * z_len);
+
+    let hint = match unpack_hint(hint_bytes, params) {
+        Some(hint) => hint,
+        None => return false,
+    };
+    let zetas = zetas();
+
+    // z, which must be short
+    let mut z = Vec::with_capacity(params.l);
+    for chunk in z_bytes.chunks(z_len) {
+        let mut poly = unpack_bits(chunk, params.z_bits());
+        for c in poly.iter_mut() {
+            *c = params.gamma1 - *c;
+            if c.abs() >= params.gamma1 - params.beta {
+                return false;
+            }
+            *c = reduce(*c);
+        }
+        ntt(&mut poly, &zetas);
+        z.push(poly);
+    }
+
+    let mut c = sample_in_ball(c_tilde, params.tau);
+    ntt(&mut c, &zetas);
+
+    // w1' = UseHint(h, A * z - c * t1 * 2^d)
+    let mut w1 = Vec::with_capacity(params.k * N * params.w1_bits() / 8);
+    for (i, t1_chunk) in t1_bytes.chunks(N * 10 / 8).enumerate() {
+        let mut t1 = unpack_bits(t1_chunk, 10);
+        for coeff in t1.iter_mut() {
+            *coeff <<= D;
+        }
+        ntt(&mut t1, &zetas);
+
+        let mut w = [0i64; N];
+        for (j, z_j) in z.iter().enumerate() {
+            let a = expand_a(rho, i, j);
+            for (n, w_n) in w.iter_mut().enumerate() {
+                *w_n = (*w_n + a[n] * z_j[n]) % Q;
+            }
+        }
+        for (n, w_n) in w.iter_mut().enumerate() {
+            *w_n = reduce(*w_n - c[n] * t1[n] % Q);
+        }
+        inv_ntt(&mut w, &zetas);
+        for (w_n, h) in w.iter_mut().zip(hint[i].iter()) {
+            *w_n = use_hint(*w_n, *h, params.gamma2);
+        }
+        pack_bits(&w, params.w1_bits(), &mut w1);
+    }
+
+    let mut shake = Shake256::default();
+    shake.update(mu);
+    shake.update(&w1);
+    let mut c_tilde_prime = [0u8; SEED_LEN];
+    shake.finalize_xof().read(&mut c_tilde_prime);
+    c_tilde_prime == c_tilde
+}
+
+#[derive(Clone)]
+/// A verify-only DSA manager for Dilithium2, Dilithium3 and Dilithium5 as
+/// submitted to round 3 of the NIST process (version 3.1)
+///
+/// This keeps signatures made before FIPS 204, e.g. during a pilot with the
+/// OQS provider, verifiable. New keys and signatures are never produced:
+/// key generation and signing fail with `QuantCryptError::UnsupportedOperation`.
+pub struct DilithiumDsaManager {
+    pub dsa_info: DsaInfo,
+}
+
+impl Dsa for DilithiumDsaManager {
+    /// Create a new DSA instance
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    fn new(dsa_type: DsaType) -> Result<Self> {
+        if Params::get(&dsa_type).is_none() {
+            return Err(QuantCryptError::NotImplemented);
+        }
+        Ok(Self {
+            dsa_info: DsaInfo::new(dsa_type),
+        })
+    }
+
+    /// Key generation is not supported for round 3 Dilithium
+    fn key_gen_with_rng(
+        &mut self,
+        _rng: &mut impl rand_core::CryptoRngCore,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        Err(QuantCryptError::UnsupportedOperation)
+    }
+
+    /// Key generation is not supported for round 3 Dilithium
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        Err(QuantCryptError::UnsupportedOperation)
+    }
+
+    /// Signing is not supported for round 3 Dilithium
+    fn sign(&self, _sk: &[u8], _msg: &[u8]) -> Result<Vec<u8>> {
+        Err(QuantCryptError::UnsupportedOperation)
+    }
+
+    /// Verify a signature
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key
+    /// * `msg` - The message to verify
+    /// * `signature` - The signature
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating if the signature is valid
+    fn verify(&self, pk: &[u8], msg: &[u8], signature: &[u8]) -> Result<bool> {
+        let params = Params::get(&self.dsa_info.dsa_type).ok_or(QuantCryptError::NotImplemented)?;
+        if pk.len() != params.pk_len() {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+        if signature.len() != params.sig_len() {
+            return Err(QuantCryptError::InvalidSignature);
+        }
+
+        // mu = H(H(pk) || M)
+        let mut tr = [0u8; SEED_LEN];
+        let mut shake = Shake256::default();
+        shake.update(pk);
+        shake.finalize_xof().read(&mut tr);
+        let mut mu = [0u8; MU_LEN];
+        let mut shake = Shake256::default();
+        shake.update(&tr);
+        shake.update(msg);
+        shake.finalize_xof().read(&mut mu);
+
+        Ok(verify_mu(&params, pk, &mu, signature))
+    }
+
+    /// Get DSA metadata information such as the key lengths,
+    /// size of signature, etc.
+    fn get_dsa_info(&self) -> DsaInfo {
+        self.dsa_info.clone()
+    }
+
+    /// Secret keys are not supported for round 3 Dilithium
+    fn get_public_key(&self, _sk: &[u8]) -> Result<Vec<u8>> {
+        Err(QuantCryptError::UnsupportedOperation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dilithium_r3_verify() {
+        // Signed with the round 3 reference implementation (version 3.1)
+        let msg = b"archived artifact";
+        let vectors: [(DsaType, &[u8], &[u8]); 3] = [
+            (
+                DsaType::Dilithium2,
+                include_bytes!("../../test/data/dilithium_r3/dilithium2.pk"),
+                include_bytes!("../../test/data/dilithium_r3/dilithium2.sig"),
+            ),
+            (
+                DsaType::Dilithium3,
+                include_bytes!("../../test/data/dilithium_r3/dilithium3.pk"),
+                include_bytes!("../../test/data/dilithium_r3/dilithium3.sig"),
+            ),
+            (
+                DsaType::Dilithium5,
+                include_bytes!("../../test/data/dilithium_r3/dilithium5.pk"),
+                include_bytes!("../../test/data/dilithium_r3/dilithium5.sig"),
+            ),
+        ];
+        for (dsa_type, pk, sig) in vectors {
+            let mut dsa = DilithiumDsaManager::new(dsa_type).unwrap();
+            assert!(dsa.verify(pk, msg, sig).unwrap());
+            assert!(!dsa.verify(pk, b"another artifact", sig).unwrap());
+            let mut tampered = sig.to_vec();
+            tampered[SEED_LEN + 1] ^= 1;
+            assert!(!dsa.verify(pk, msg, &tampered).unwrap());
+            assert!(dsa.verify(pk, msg, &sig[1..]).is_err());
+
+            // Archived keys and signatures are verifiable by OID
+            let oid = dsa.get_dsa_info().oid;
+            let public_key = crate::asn1::public_key::PublicKey::new(&oid, pk).unwrap();
+            assert!(public_key.verify(msg, sig).unwrap());
+
+            // Verify only, there are no private keys to issue with
+            assert!(crate::asn1::private_key::PrivateKey::new(&oid, &[0u8; 32]).is_err());
+            assert_eq!(dsa.key_gen(), Err(QuantCryptError::UnsupportedOperation));
+            assert_eq!(
+                dsa.sign(&[0u8; 32], msg),
+                Err(QuantCryptError::UnsupportedOperation)
+            );
+        }
+    }
+}
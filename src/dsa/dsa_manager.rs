@@ -1,13 +1,23 @@
+use std::sync::Arc;
+
 use rand_core::CryptoRngCore;
 
+use crate::dsa::common::dsa_backend::{DsaBackend, PrehashDsaBackend};
 use crate::dsa::common::dsa_trait::Dsa;
 use crate::dsa::common::dsa_type::DsaType;
 use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
 use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
 use crate::dsa::composite_dsa::CompositeDsaManager;
+#[cfg(feature = "legacy")]
+use crate::dsa::dilithium::DilithiumDsaManager;
+#[cfg(feature = "openssl")]
 use crate::dsa::ec_dsa::EcDsaManager;
 use crate::dsa::ml_dsa::MlDsaManager;
+#[cfg(feature = "openssl")]
 use crate::dsa::rsa_dsa::RsaDsaManager;
+use crate::utils::deprecation::{report_deprecation, DeprecationWarning};
+use crate::utils::entropy::SharedRng;
+use crate::utils::manager_pool::{get_dsa_backend, get_prehash_dsa_backend};
 use crate::QuantCryptError;
 
 use crate::dsa::slh_dsa::SlhDsaManager;
@@ -87,13 +97,24 @@ const SLH_DSA_TYPES: [DsaType; 12] = [
 // Implement clone
 #[derive(Clone)]
 /// Enum to representthe different types of KEM managers
+///
+/// Like `DsaType`, the enum is non-exhaustive since its variants depend on the
+/// `openssl` and `legacy` features.
+#[non_exhaustive]
 pub enum DsaManager {
     /// RSA DSA manager
+    #[cfg(feature = "openssl")]
     Rsa(RsaDsaManager),
     /// EC DSA manager
+    #[cfg(feature = "openssl")]
     Ec(EcDsaManager),
     /// SLH DSA manager
     Slh(SlhDsaManager),
+    /// Legacy round 3 Dilithium manager, verify only
+    #[cfg(feature = "legacy")]
+    Dilithium(DilithiumDsaManager),
+    /// An external backend registered for the DSA type
+    Custom(Arc<dyn DsaBackend>),
 }
 
 // Implement clone
@@ -104,6 +125,85 @@ pub enum PrehashDsaManager {
     Ml(MlDsaManager),
     /// Composite DSA manager
     Composite(CompositeDsaManager),
+    /// An external backend registered for the DSA type
+    Custom(Arc<dyn PrehashDsaBackend>),
+}
+
+impl DsaManager {
+    /// Create a new DSA manager drawing its randomness from a caller-supplied RNG
+    ///
+    /// Key generation and randomized signing, including the ECDSA nonces and
+    /// the RSA-PSS salts, use the RNG instead of seeding their own. See `SharedRng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    /// * `rng` - The RNG used by key generation and signing
+    ///
+    /// # Returns
+    ///
+    /// A new DSA manager
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the DSA is provided by a registered backend,
+    /// which draws its own randomness
+    pub fn new_with_rng(dsa_type: DsaType, rng: SharedRng) -> Result<Self> {
+        let mut dsa = Self::new(dsa_type)?;
+        dsa.set_rng(rng)?;
+        Ok(dsa)
+    }
+
+    /// Draw the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) -> Result<()> {
+        match self {
+            #[cfg(feature = "openssl")]
+            DsaManager::Rsa(dsa) => dsa.set_rng(rng),
+            #[cfg(feature = "openssl")]
+            DsaManager::Ec(dsa) => dsa.set_rng(rng),
+            DsaManager::Slh(dsa) => dsa.set_rng(rng),
+            // Verify only, nothing is random
+            #[cfg(feature = "legacy")]
+            DsaManager::Dilithium(_) => {}
+            DsaManager::Custom(_) => return Err(QuantCryptError::NotImplemented),
+        }
+        Ok(())
+    }
+}
+
+impl PrehashDsaManager {
+    /// Create a new pre-hash DSA manager drawing its randomness from a caller-supplied RNG
+    ///
+    /// See `DsaManager::new_with_rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    /// * `rng` - The RNG used by key generation and signing
+    ///
+    /// # Returns
+    ///
+    /// A new DSA manager
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the DSA, or a component of it, is provided by
+    /// a registered backend
+    pub fn new_with_rng(dsa_type: PrehashDsaType, rng: SharedRng) -> Result<Self> {
+        let mut dsa = Self::new(dsa_type)?;
+        dsa.set_rng(rng)?;
+        Ok(dsa)
+    }
+
+    /// Draw the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) -> Result<()> {
+        match self {
+            PrehashDsaManager::Ml(dsa) => dsa.set_rng(rng),
+            PrehashDsaManager::Composite(dsa) => dsa.set_rng(rng)?,
+            PrehashDsaManager::Custom(_) => return Err(QuantCryptError::NotImplemented),
+        }
+        Ok(())
+    }
 }
 
 impl Dsa for DsaManager {
@@ -111,14 +211,31 @@ impl Dsa for DsaManager {
     where
         Self: Sized,
     {
+        report_deprecation(DeprecationWarning::for_dsa(&dsa_type));
+        if let Some(backend) = get_dsa_backend(&dsa_type) {
+            return Ok(DsaManager::Custom(backend));
+        }
         let result = match dsa_type {
+            #[cfg(feature = "openssl")]
             _ if RSA_DSA_TYPES.contains(&dsa_type) => {
                 DsaManager::Rsa(RsaDsaManager::new(dsa_type)?)
             }
             _ if SLH_DSA_TYPES.contains(&dsa_type) => {
                 DsaManager::Slh(SlhDsaManager::new(dsa_type)?)
             }
+            #[cfg(feature = "openssl")]
             _ if EC_DSA_TYPES.contains(&dsa_type) => DsaManager::Ec(EcDsaManager::new(dsa_type)?),
+            // RSA, ECDSA and EdDSA are computed with OpenSSL
+            #[cfg(not(feature = "openssl"))]
+            _ if RSA_DSA_TYPES.contains(&dsa_type) || EC_DSA_TYPES.contains(&dsa_type) => {
+                return Err(QuantCryptError::BackendUnavailable {
+                    component: format!("{:?}", dsa_type),
+                });
+            }
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium2 | DsaType::Dilithium3 | DsaType::Dilithium5 => {
+                DsaManager::Dilithium(DilithiumDsaManager::new(dsa_type)?)
+            }
             _ => {
                 panic!("Not implemented");
             }
@@ -128,49 +245,79 @@ impl Dsa for DsaManager {
 
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
         match self {
+            #[cfg(feature = "openssl")]
             DsaManager::Rsa(rsa) => rsa.key_gen(),
+            #[cfg(feature = "openssl")]
             DsaManager::Ec(ec) => ec.key_gen(),
             DsaManager::Slh(slh) => slh.key_gen(),
+            #[cfg(feature = "legacy")]
+            DsaManager::Dilithium(dsa) => dsa.key_gen(),
+            DsaManager::Custom(dsa) => dsa.key_gen(),
         }
     }
 
     fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
         match self {
+            #[cfg(feature = "openssl")]
             DsaManager::Rsa(rsa) => rsa.key_gen_with_rng(rng),
+            #[cfg(feature = "openssl")]
             DsaManager::Ec(ec) => ec.key_gen_with_rng(rng),
             DsaManager::Slh(slh) => slh.key_gen_with_rng(rng),
+            #[cfg(feature = "legacy")]
+            DsaManager::Dilithium(dsa) => dsa.key_gen_with_rng(rng),
+            DsaManager::Custom(dsa) => dsa.key_gen_with_rng(rng),
         }
     }
 
     fn sign(&self, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
         match self {
+            #[cfg(feature = "openssl")]
             DsaManager::Rsa(rsa) => rsa.sign(sk, msg),
+            #[cfg(feature = "openssl")]
             DsaManager::Ec(ec) => ec.sign(sk, msg),
             DsaManager::Slh(slh) => slh.sign(sk, msg),
+            #[cfg(feature = "legacy")]
+            DsaManager::Dilithium(dsa) => dsa.sign(sk, msg),
+            DsaManager::Custom(dsa) => dsa.sign(sk, msg),
         }
     }
 
     fn verify(&self, pk: &[u8], msg: &[u8], sig: &[u8]) -> Result<bool> {
         match self {
+            #[cfg(feature = "openssl")]
             DsaManager::Rsa(rsa) => rsa.verify(pk, msg, sig),
+            #[cfg(feature = "openssl")]
             DsaManager::Ec(ec) => ec.verify(pk, msg, sig),
             DsaManager::Slh(slh) => slh.verify(pk, msg, sig),
+            #[cfg(feature = "legacy")]
+            DsaManager::Dilithium(dsa) => dsa.verify(pk, msg, sig),
+            DsaManager::Custom(dsa) => dsa.verify(pk, msg, sig),
         }
     }
 
     fn get_dsa_info(&self) -> super::common::dsa_info::DsaInfo {
         match self {
+            #[cfg(feature = "openssl")]
             DsaManager::Rsa(rsa) => rsa.get_dsa_info(),
+            #[cfg(feature = "openssl")]
             DsaManager::Ec(ec) => ec.get_dsa_info(),
             DsaManager::Slh(slh) => slh.get_dsa_info(),
+            #[cfg(feature = "legacy")]
+            DsaManager::Dilithium(dsa) => dsa.get_dsa_info(),
+            DsaManager::Custom(dsa) => dsa.get_dsa_info(),
         }
     }
 
     fn get_public_key(&self, sk: &[u8]) -> Result<Vec<u8>> {
         match self {
+            #[cfg(feature = "openssl")]
             DsaManager::Rsa(rsa) => rsa.get_public_key(sk),
+            #[cfg(feature = "openssl")]
             DsaManager::Ec(ec) => ec.get_public_key(sk),
             DsaManager::Slh(slh) => slh.get_public_key(sk),
+            #[cfg(feature = "legacy")]
+            DsaManager::Dilithium(dsa) => dsa.get_public_key(sk),
+            DsaManager::Custom(dsa) => dsa.get_public_key(sk),
         }
     }
 }
@@ -180,6 +327,10 @@ impl PrehashDsa for PrehashDsaManager {
     where
         Self: Sized,
     {
+        report_deprecation(DeprecationWarning::for_prehash_dsa(&dsa_type));
+        if let Some(backend) = get_prehash_dsa_backend(&dsa_type) {
+            return Ok(PrehashDsaManager::Custom(backend));
+        }
         let result = match dsa_type {
             _ if ML_DSA_TYPES.contains(&dsa_type) => {
                 PrehashDsaManager::Ml(MlDsaManager::new(dsa_type)?)
@@ -198,6 +349,7 @@ impl PrehashDsa for PrehashDsaManager {
         match self {
             PrehashDsaManager::Ml(ml) => ml.key_gen(),
             PrehashDsaManager::Composite(composite) => composite.key_gen(),
+            PrehashDsaManager::Custom(dsa) => dsa.key_gen(),
         }
     }
 
@@ -205,6 +357,7 @@ impl PrehashDsa for PrehashDsaManager {
         match self {
             PrehashDsaManager::Ml(ml) => ml.key_gen_with_rng(rng),
             PrehashDsaManager::Composite(composite) => composite.key_gen_with_rng(rng),
+            PrehashDsaManager::Custom(dsa) => dsa.key_gen_with_rng(rng),
         }
     }
 
@@ -212,6 +365,7 @@ impl PrehashDsa for PrehashDsaManager {
         match self {
             PrehashDsaManager::Ml(ml) => ml.sign_with_ctx(sk, msg, ctx),
             PrehashDsaManager::Composite(composite) => composite.sign_with_ctx(sk, msg, ctx),
+            PrehashDsaManager::Custom(dsa) => dsa.sign_with_ctx(sk, msg, ctx),
         }
     }
 
@@ -225,6 +379,7 @@ impl PrehashDsa for PrehashDsaManager {
         match self {
             PrehashDsaManager::Ml(ml) => ml.verify_with_ctx(pk, msg, sig, ctx),
             PrehashDsaManager::Composite(composite) => composite.verify_with_ctx(pk, msg, sig, ctx),
+            PrehashDsaManager::Custom(dsa) => dsa.verify_with_ctx(pk, msg, sig, ctx),
         }
     }
 
@@ -232,6 +387,7 @@ impl PrehashDsa for PrehashDsaManager {
         match self {
             PrehashDsaManager::Ml(ml) => ml.get_dsa_info(),
             PrehashDsaManager::Composite(composite) => composite.get_dsa_info(),
+            PrehashDsaManager::Custom(dsa) => dsa.get_dsa_info(),
         }
     }
 
@@ -239,6 +395,7 @@ impl PrehashDsa for PrehashDsaManager {
         match self {
             PrehashDsaManager::Ml(ml) => ml.get_public_key(sk),
             PrehashDsaManager::Composite(composite) => composite.get_public_key(sk),
+            PrehashDsaManager::Custom(dsa) => dsa.get_public_key(sk),
         }
     }
 }
@@ -246,8 +403,69 @@ impl PrehashDsa for PrehashDsaManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "openssl")]
     use crate::dsa::common::dsa_type::DsaType;
+    #[cfg(feature = "openssl")]
+    use rand_chacha::ChaCha20Rng;
+    #[cfg(feature = "openssl")]
+    use rand_core::SeedableRng;
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_new_with_rng() {
+        let msg = b"message";
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let rng = SharedRng::new(ChaCha20Rng::from_seed([5u8; 32]));
+            let mut dsa = DsaManager::new_with_rng(DsaType::Ed25519, rng.clone()).unwrap();
+            let (pk, _) = dsa.key_gen().unwrap();
+            let mut prehash_dsa =
+                PrehashDsaManager::new_with_rng(PrehashDsaType::MlDsa44Ed25519, rng).unwrap();
+            let (c_pk, c_sk) = prehash_dsa.key_gen().unwrap();
+            let sig = prehash_dsa.sign(&c_sk, msg).unwrap();
+            assert!(prehash_dsa.verify(&c_pk, msg, &sig).unwrap());
+            outputs.push((pk, c_pk, sig));
+        }
+        // The same RNG stream yields the same keys and hedged signatures
+        assert_eq!(outputs[0], outputs[1]);
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_new_with_rng_signing_randomness() {
+        let msg = b"message";
+        for dsa_type in [
+            DsaType::EcdsaP256SHA256,
+            DsaType::EcdsaP384SHA384,
+            DsaType::Rsa2048PssSha256,
+        ] {
+            let mut outputs = Vec::new();
+            for _ in 0..2 {
+                let rng = SharedRng::new(ChaCha20Rng::from_seed([6u8; 32]));
+                let mut dsa = DsaManager::new_with_rng(dsa_type.clone(), rng).unwrap();
+                let (pk, sk) = dsa.key_gen().unwrap();
+                let sig = dsa.sign(&sk, msg).unwrap();
+                assert!(dsa.verify(&pk, msg, &sig).unwrap());
+                // The default manager verifies it too
+                assert!(DsaManager::new(dsa_type.clone())
+                    .unwrap()
+                    .verify(&pk, msg, &sig)
+                    .unwrap());
+                outputs.push((pk, sig));
+            }
+            // The ECDSA nonces and the PSS salts are drawn from the RNG
+            assert_eq!(outputs[0], outputs[1]);
+        }
+    }
+
+    #[test]
+    fn test_dsa_managers_are_clone_send_sync() {
+        fn assert_clone_send_sync<T: Clone + Send + Sync>() {}
+        assert_clone_send_sync::<DsaManager>();
+        assert_clone_send_sync::<PrehashDsaManager>();
+    }
 
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_dsa_manager() {
         let mut all_dsas: Vec<DsaType> = Vec::new();
@@ -262,6 +480,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_prehash_dsa_manager() {
         let mut all_dsas: Vec<PrehashDsaType> = Vec::new();
@@ -7,9 +7,15 @@ use rand_core::CryptoRngCore;
 use crate::dsa::common::dsa_info::DsaInfo;
 use crate::dsa::common::dsa_trait::Dsa;
 use crate::dsa::common::dsa_type::DsaType;
+use crate::utils::curve448::{
+    ed448_key_gen, ed448_public_key, ed448_sign, ed448_verify, openssl_supports,
+};
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::utils::openssl_utils::get_pk_from_sk_ec_based;
 use crate::utils::openssl_utils::get_pk_from_sk_pkey_based;
+use crate::utils::openssl_utils::openssl_supports_curve;
 use crate::utils::openssl_utils::sign_ec_based;
+use crate::utils::openssl_utils::sign_ec_based_with_rng;
 use crate::utils::openssl_utils::sign_pkey_based;
 use crate::utils::openssl_utils::verify_ec_based;
 use crate::utils::openssl_utils::verify_pkey_based;
@@ -26,6 +32,39 @@ pub struct EcDsaManager {
     ec_based_nid: Option<Nid>,
     pk_based_id: Option<Id>,
     digest: Option<MessageDigest>,
+    rng: Option<SharedRng>,
+}
+
+impl EcDsaManager {
+    /// Create a new DSA instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// Key generation and the ECDSA nonces draw from the RNG. EdDSA signatures
+    /// are deterministic.
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    /// * `rng` - The RNG used by key generation and ECDSA signing
+    ///
+    /// # Returns
+    ///
+    /// A new DSA instance
+    pub fn new_with_rng(dsa_type: DsaType, rng: SharedRng) -> Result<Self> {
+        let mut dsa = Self::new(dsa_type)?;
+        dsa.set_rng(rng);
+        Ok(dsa)
+    }
+
+    /// Draw the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.rng = Some(rng);
+    }
+
+    /// Check if the pure-Rust Ed448 implementation has to be used, because OpenSSL lacks Ed448
+    fn use_fallback(&self) -> bool {
+        self.pk_based_id
+            .map_or(false, |id| id == Id::ED448 && !openssl_supports(id))
+    }
 }
 
 impl Dsa for EcDsaManager {
@@ -57,16 +96,30 @@ impl Dsa for EcDsaManager {
                 return Err(QuantCryptError::NotImplemented);
             }
         };
+        // Ed448 falls back to pure Rust when OpenSSL lacks it, the EC curves can't
+        if ec_based_nid.is_some_and(|nid| !openssl_supports_curve(nid)) {
+            return Err(QuantCryptError::BackendUnavailable {
+                component: format!("{:?}", dsa_type),
+            });
+        }
 
         Ok(Self {
             dsa_info,
             ec_based_nid,
             pk_based_id,
             digest,
+            rng: None,
         })
     }
 
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        if self.rng.is_some() {
+            let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
+            return self.key_gen_with_rng(&mut rng);
+        }
+        if self.use_fallback() {
+            return ed448_key_gen(&mut ManagerRng::for_key_gen(None)?);
+        }
         let result = if let Some(nid) = self.ec_based_nid {
             get_key_pair_ec_based(nid)
         } else if let Some(id) = self.pk_based_id {
@@ -108,8 +161,16 @@ impl Dsa for EcDsaManager {
     }
 
     fn sign(&self, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        if self.use_fallback() {
+            return ed448_sign(sk, msg);
+        }
         let result = if let Some(nid) = self.ec_based_nid {
-            sign_ec_based(nid, sk, msg, self.digest.unwrap())
+            match &self.rng {
+                Some(rng) => {
+                    sign_ec_based_with_rng(&mut rng.clone(), nid, sk, msg, self.digest.unwrap())
+                }
+                None => sign_ec_based(nid, sk, msg, self.digest.unwrap()),
+            }
         } else if let Some(id) = self.pk_based_id {
             sign_pkey_based(id, sk, msg)
         } else {
@@ -120,6 +181,10 @@ impl Dsa for EcDsaManager {
     }
 
     fn verify(&self, pk: &[u8], msg: &[u8], signature: &[u8]) -> Result<bool> {
+        if self.use_fallback() {
+            return ed448_verify(pk, msg, signature)
+                .map_err(|_| QuantCryptError::SignatureVerificationFailed);
+        }
         let result = if let Some(nid) = self.ec_based_nid {
             verify_ec_based(nid, pk, msg, signature, self.digest.unwrap())
         } else if let Some(id) = self.pk_based_id {
@@ -136,6 +201,9 @@ impl Dsa for EcDsaManager {
     }
 
     fn get_public_key(&self, sk: &[u8]) -> Result<Vec<u8>> {
+        if self.use_fallback() {
+            return ed448_public_key(sk);
+        }
         if let Some(nid) = self.ec_based_nid {
             get_pk_from_sk_ec_based(sk, nid).map_err(|_| QuantCryptError::InvalidPrivateKey)
         } else if let Some(id) = self.pk_based_id {
@@ -149,7 +217,8 @@ impl Dsa for EcDsaManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dsa::common::{dsa_type::DsaType, macros::test_dsa};
+    use crate::dsa::common::dsa_type::DsaType;
+    use crate::test_dsa;
 
     #[test]
     fn test_ecdsa_p256_sha256() {
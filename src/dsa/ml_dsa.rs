@@ -1,10 +1,9 @@
 use crate::dsa::common::prehash_dsa_info::PrehashDsaInfo;
 use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
 use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::QuantCryptError;
 
-use rand_core::SeedableRng;
-
 // When IPD feature is not enabled
 use fips204::ml_dsa_44;
 use fips204::ml_dsa_65;
@@ -36,8 +35,56 @@ macro_rules! sign_ml {
         let sig: Vec<u8> = sig.to_vec();
         Ok(sig)
     }};
+    ($ml_type:ident, $sk:expr, $msg:expr, $ctx:expr, $rng:expr) => {{
+        if $sk.len() != $ml_type::SK_LEN {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let mut sk_buf = [0u8; $ml_type::SK_LEN];
+        sk_buf.copy_from_slice($sk);
+        let sk = $ml_type::PrivateKey::try_from_bytes(sk_buf)
+            .map_err(|_| QuantCryptError::SignatureFailed)?;
+        let sig = sk
+            .try_sign_with_rng($rng, $msg, $ctx)
+            .map_err(|_| QuantCryptError::SignatureFailed)?;
+        Ok(sig.to_vec())
+    }};
+}
+
+/// The length of the per-signature randomness rnd of ML-DSA
+pub const ML_DSA_SIGNING_SEED_LEN: usize = 32;
+
+/// An RNG handing out the randomness rnd of a single ML-DSA signature
+struct SigningSeedRng<'a> {
+    seed: Option<&'a [u8]>,
+}
+
+impl rand_core::RngCore for SigningSeedRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("Signing seed already used")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+        match self.seed.take() {
+            Some(seed) if seed.len() == dest.len() => {
+                dest.copy_from_slice(seed);
+                Ok(())
+            }
+            _ => Err(rand_core::Error::new("Signing seed already used")),
+        }
+    }
 }
 
+impl rand_core::CryptoRng for SigningSeedRng<'_> {}
+
 macro_rules! verify_ml {
     ($ml_type:ident, $pk: expr, $msg: expr, $signature: expr, $ctx:expr) => {{
         if $pk.len() != $ml_type::PK_LEN {
@@ -80,6 +127,32 @@ macro_rules! get_public_key {
 #[derive(Clone)]
 pub struct MlDsaManager {
     pub dsa_info: PrehashDsaInfo,
+    rng: Option<SharedRng>,
+}
+
+impl MlDsaManager {
+    /// Create a new DSA instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// The RNG provides the key generation seeds and the per-signature randomness rnd.
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    /// * `rng` - The RNG used by key generation and signing
+    ///
+    /// # Returns
+    ///
+    /// A new DSA instance
+    pub fn new_with_rng(dsa_type: PrehashDsaType, rng: SharedRng) -> Result<Self> {
+        let mut dsa = Self::new(dsa_type)?;
+        dsa.set_rng(rng);
+        Ok(dsa)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.rng = Some(rng);
+    }
 }
 
 impl PrehashDsa for MlDsaManager {
@@ -90,7 +163,10 @@ impl PrehashDsa for MlDsaManager {
     /// * `dsa_type` - The type of DSA to create
     fn new(dsa_type: PrehashDsaType) -> Result<Self> {
         let dsa_info = PrehashDsaInfo::new(dsa_type);
-        Ok(Self { dsa_info })
+        Ok(Self {
+            dsa_info,
+            rng: None,
+        })
     }
 
     /// Generate a keypair using the specified RNG
@@ -132,13 +208,13 @@ impl PrehashDsa for MlDsaManager {
         }
     }
 
-    /// Generate a keypair using the default RNG ChaCha20Rng
+    /// Generate a keypair using the RNG of the manager, or else the default RNG ChaCha20Rng
     ///
     /// # Returns
     ///
     /// A tuple containing the public and secret keys (pk, sk)
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-        let mut rng = rand_chacha::ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
         self.key_gen_with_rng(&mut rng)
     }
 
@@ -155,6 +231,15 @@ impl PrehashDsa for MlDsaManager {
     /// The signature
     fn sign_with_ctx(&self, sk: &[u8], msg: &[u8], ctx: Option<&[u8]>) -> Result<Vec<u8>> {
         let ctx = ctx.unwrap_or(&[]);
+        if let Some(rng) = &self.rng {
+            let rng = &mut rng.clone();
+            return match self.dsa_info.dsa_type {
+                PrehashDsaType::MlDsa44 => sign_ml!(ml_dsa_44, sk, msg, ctx, rng),
+                PrehashDsaType::MlDsa65 => sign_ml!(ml_dsa_65, sk, msg, ctx, rng),
+                PrehashDsaType::MlDsa87 => sign_ml!(ml_dsa_87, sk, msg, ctx, rng),
+                _ => Err(QuantCryptError::NotImplemented),
+            };
+        }
         match self.dsa_info.dsa_type {
             PrehashDsaType::MlDsa44 => sign_ml!(ml_dsa_44, sk, msg, ctx),
             PrehashDsaType::MlDsa65 => sign_ml!(ml_dsa_65, sk, msg, ctx),
@@ -213,11 +298,71 @@ impl PrehashDsa for MlDsaManager {
     }
 }
 
+impl MlDsaManager {
+    /// Sign a message with the given randomness rnd instead of fresh randomness
+    ///
+    /// This is for reproducing known answer tests, such as the ACVP sigGen test
+    /// cases, never use it otherwise. An all-zero seed gives the deterministic
+    /// variant of FIPS 204. The message is signed with the external interface,
+    /// prefixed with the context.
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key
+    /// * `msg` - The message to sign
+    /// * `seed` - The 32 byte randomness rnd
+    /// * `ctx` - The context
+    ///
+    /// # Returns
+    ///
+    /// The signature
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidSigningSeedLength` if the seed is not 32 bytes
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn sign_deterministic_with_seed(
+        &self,
+        sk: &[u8],
+        msg: &[u8],
+        seed: &[u8],
+        ctx: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        self.sign_with_seed(sk, msg, seed, ctx)
+    }
+
+    /// Sign a message with the given randomness rnd, see `sign_deterministic_with_seed`
+    ///
+    /// The known answer tests of `run_dsa_kat` need it without the `test-utils` feature.
+    pub(crate) fn sign_with_seed(
+        &self,
+        sk: &[u8],
+        msg: &[u8],
+        seed: &[u8],
+        ctx: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        if seed.len() != ML_DSA_SIGNING_SEED_LEN {
+            return Err(QuantCryptError::InvalidSigningSeedLength {
+                expected: ML_DSA_SIGNING_SEED_LEN,
+                actual: seed.len(),
+            });
+        }
+        let ctx = ctx.unwrap_or(&[]);
+        let rng = &mut SigningSeedRng { seed: Some(seed) };
+        match self.dsa_info.dsa_type {
+            PrehashDsaType::MlDsa44 => sign_ml!(ml_dsa_44, sk, msg, ctx, rng),
+            PrehashDsaType::MlDsa65 => sign_ml!(ml_dsa_65, sk, msg, ctx, rng),
+            PrehashDsaType::MlDsa87 => sign_ml!(ml_dsa_87, sk, msg, ctx, rng),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dsa::common::macros::test_prehash_dsa;
     use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+    use crate::test_prehash_dsa;
 
     #[test]
     fn test_ml_dsa_44() {
@@ -236,4 +381,38 @@ mod tests {
         let dsa = MlDsaManager::new(PrehashDsaType::MlDsa87);
         test_prehash_dsa!(dsa);
     }
+
+    #[test]
+    fn test_ml_dsa_sign_deterministic_with_seed() {
+        let mut dsa = MlDsaManager::new(PrehashDsaType::MlDsa65).unwrap();
+        let (pk, sk) = dsa.key_gen().unwrap();
+        let msg = b"message";
+        let ctx = Some(&b"ctx"[..]);
+
+        let zero = [0u8; ML_DSA_SIGNING_SEED_LEN];
+        let sig = dsa
+            .sign_deterministic_with_seed(&sk, msg, &zero, ctx)
+            .unwrap();
+        assert!(dsa.verify_with_ctx(&pk, msg, &sig, ctx).unwrap());
+        assert_eq!(
+            dsa.sign_deterministic_with_seed(&sk, msg, &zero, ctx)
+                .unwrap(),
+            sig
+        );
+
+        // The seed is the randomness of the signature
+        let other = dsa
+            .sign_deterministic_with_seed(&sk, msg, &[1u8; ML_DSA_SIGNING_SEED_LEN], ctx)
+            .unwrap();
+        assert_ne!(other, sig);
+        assert!(dsa.verify_with_ctx(&pk, msg, &other, ctx).unwrap());
+
+        assert!(matches!(
+            dsa.sign_deterministic_with_seed(&sk, msg, &[0u8; 31], ctx),
+            Err(QuantCryptError::InvalidSigningSeedLength {
+                expected: 32,
+                actual: 31
+            })
+        ));
+    }
 }
@@ -2,8 +2,12 @@ pub mod api;
 pub mod asn1;
 pub mod common;
 pub mod composite_dsa;
+#[cfg(feature = "legacy")]
+pub mod dilithium;
 pub mod dsa_manager;
+#[cfg(feature = "openssl")]
 pub mod ec_dsa;
 pub mod ml_dsa;
+#[cfg(feature = "openssl")]
 pub mod rsa_dsa;
 pub mod slh_dsa;
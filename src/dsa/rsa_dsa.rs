@@ -1,14 +1,13 @@
 use openssl::hash::MessageDigest;
 use openssl::rsa::Padding;
 use openssl::sign::RsaPssSaltlen;
-use rand::SeedableRng;
-use rand_chacha::ChaCha20Rng;
 use rsa::pkcs1::{EncodeRsaPrivateKey, EncodeRsaPublicKey};
 use rsa::RsaPrivateKey;
 
 use crate::dsa::common::dsa_info::DsaInfo;
 use crate::dsa::common::dsa_trait::Dsa;
 use crate::dsa::common::dsa_type::DsaType;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::QuantCryptError;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
@@ -16,9 +15,109 @@ type Result<T> = std::result::Result<T, QuantCryptError>;
 #[derive(Clone)]
 pub struct RsaDsaManager {
     pub dsa_info: DsaInfo,
+    rng: Option<SharedRng>,
 }
 
 impl RsaDsaManager {
+    /// Create a new DSA instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// Key generation and the PSS salts draw from the RNG. PKCS #1 v1.5
+    /// signatures are deterministic.
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    /// * `rng` - The RNG used by key generation and PSS signing
+    ///
+    /// # Returns
+    ///
+    /// A new DSA instance
+    pub fn new_with_rng(dsa_type: DsaType, rng: SharedRng) -> Result<Self> {
+        let mut dsa = Self::new(dsa_type)?;
+        dsa.set_rng(rng);
+        Ok(dsa)
+    }
+
+    /// Draw the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.rng = Some(rng);
+    }
+
+    /// Sign a message with RSASSA-PSS, drawing the salt from an RNG
+    ///
+    /// The message is encoded with EMSA-PSS (RFC 8017 § 9.1.1), with MGF1 and a
+    /// salt as long as the digest, as OpenSSL does, and signed with the raw RSA
+    /// operation of OpenSSL.
+    ///
+    /// # Arguments
+    ///
+    /// * `rsa_sk` - The RSA private key
+    /// * `hash` - The digest of the message and of MGF1
+    /// * `msg` - The message to sign
+    /// * `rng` - The RNG of the salt
+    ///
+    /// # Returns
+    ///
+    /// The signature of the message
+    fn sign_pss_with_rng(
+        rsa_sk: &openssl::rsa::Rsa<openssl::pkey::Private>,
+        hash: MessageDigest,
+        msg: &[u8],
+        rng: &mut impl rand_core::CryptoRngCore,
+    ) -> Result<Vec<u8>> {
+        let digest = |data: &[u8]| {
+            openssl::hash::hash(hash, data).map_err(|_| QuantCryptError::SignatureFailed)
+        };
+        let h_len = hash.size();
+        let s_len = h_len;
+        let em_bits = rsa_sk.n().num_bits() as usize - 1;
+        let em_len = em_bits.div_ceil(8);
+        if em_len < h_len + s_len + 2 {
+            return Err(QuantCryptError::SignatureFailed);
+        }
+
+        let mut salt = vec![0u8; s_len];
+        rng.fill_bytes(&mut salt);
+
+        // H = Hash(0x00 * 8 || mHash || salt)
+        let mut m_prime = vec![0u8; 8];
+        m_prime.extend_from_slice(&digest(msg)?);
+        m_prime.extend_from_slice(&salt);
+        let h = digest(&m_prime)?;
+
+        // DB = PS || 0x01 || salt, masked with MGF1(H)
+        let db_len = em_len - h_len - 1;
+        let mut db = vec![0u8; db_len - s_len - 1];
+        db.push(0x01);
+        db.extend_from_slice(&salt);
+        let mut counter = 0u32;
+        let mut offset = 0;
+        while offset < db_len {
+            let mut seed = h.to_vec();
+            seed.extend_from_slice(&counter.to_be_bytes());
+            for (byte, mask) in db[offset..].iter_mut().zip(digest(&seed)?.iter()) {
+                *byte ^= mask;
+            }
+            offset += h_len;
+            counter += 1;
+        }
+        db[0] &= 0xff >> (8 * em_len - em_bits);
+
+        // EM = maskedDB || H || 0xbc, left padded to the length of the modulus
+        let k = rsa_sk.size() as usize;
+        let mut em = vec![0u8; k - em_len];
+        em.extend_from_slice(&db);
+        em.extend_from_slice(&h);
+        em.push(0xbc);
+
+        let mut signature = vec![0u8; k];
+        let len = rsa_sk
+            .private_encrypt(&em, &mut signature, Padding::NONE)
+            .map_err(|_| QuantCryptError::SignatureFailed)?;
+        signature.truncate(len);
+        Ok(signature)
+    }
+
     fn get_hash_and_padding(&self) -> (MessageDigest, Padding) {
         match self.dsa_info.dsa_type {
             DsaType::Rsa2048Pkcs15Sha256 => (
@@ -67,16 +166,19 @@ impl Dsa for RsaDsaManager {
         Self: Sized,
     {
         let dsa_info = DsaInfo::new(dsa_type);
-        Ok(RsaDsaManager { dsa_info })
+        Ok(RsaDsaManager {
+            dsa_info,
+            rng: None,
+        })
     }
 
-    /// Generate a keypair using the default RNG (ChaCha20)
+    /// Generate a keypair using the RNG of the manager, or else the default RNG (ChaCha20)
     ///
     /// # Returns
     ///
     /// A tuple containing the public and secret keys (pk, sk)
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-        let mut rng = ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
         self.key_gen_with_rng(&mut rng)
     }
 
@@ -137,11 +239,16 @@ impl Dsa for RsaDsaManager {
     fn sign(&self, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
         let rsa_sk = openssl::rsa::Rsa::private_key_from_der(sk)
             .map_err(|_| QuantCryptError::SerializationFailed)?;
-        let pkey =
-            openssl::pkey::PKey::from_rsa(rsa_sk).map_err(|_| QuantCryptError::SignatureFailed)?;
 
         let (hash, padding) = self.get_hash_and_padding();
 
+        if let (Padding::PKCS1_PSS, Some(rng)) = (padding, &self.rng) {
+            return Self::sign_pss_with_rng(&rsa_sk, hash, msg, &mut rng.clone());
+        }
+
+        let pkey =
+            openssl::pkey::PKey::from_rsa(rsa_sk).map_err(|_| QuantCryptError::SignatureFailed)?;
+
         // Createa a signer
         let mut signer = openssl::sign::Signer::new(hash, &pkey)
             .map_err(|_| QuantCryptError::SignatureFailed)?;
@@ -239,7 +346,7 @@ impl Dsa for RsaDsaManager {
 mod tests {
     use super::*;
     use crate::dsa::common::dsa_type::DsaType;
-    use crate::dsa::common::macros::test_dsa;
+    use crate::test_dsa;
 
     #[test]
     fn test_rsa_2048_pkcs15_sha256() {
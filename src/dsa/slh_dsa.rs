@@ -1,10 +1,9 @@
 use crate::dsa::common::dsa_info::DsaInfo;
 use crate::dsa::common::dsa_trait::Dsa;
 use crate::dsa::common::dsa_type::DsaType;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::QuantCryptError;
 
-use rand_core::SeedableRng;
-
 // When IPD feature is not enabled
 use fips205::slh_dsa_sha2_128f;
 use fips205::slh_dsa_sha2_128s;
@@ -23,7 +22,7 @@ use fips205::traits::{SerDes, Signer, Verifier};
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
 macro_rules! sign_slh {
-    ($sig_type:ident, $sk:expr, $msg:expr) => {{
+    ($sig_type:ident, $sk:expr, $msg:expr, $rng:expr) => {{
         if $sk.len() != $sig_type::SK_LEN {
             return Err(QuantCryptError::InvalidPrivateKey);
         }
@@ -38,7 +37,7 @@ macro_rules! sign_slh {
 
         // Try signing the message
         let sig = sk
-            .try_sign($msg, &[], true) // Empty context
+            .try_sign_with_rng($rng, $msg, &[], true) // Empty context
             .map_err(|_| QuantCryptError::SignatureFailed)?;
 
         // Convert the signature to a Vec<u8> and return it
@@ -100,6 +99,32 @@ macro_rules! get_public_key {
 #[derive(Clone)]
 pub struct SlhDsaManager {
     pub dsa_info: DsaInfo,
+    rng: Option<SharedRng>,
+}
+
+impl SlhDsaManager {
+    /// Create a new DSA instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// The RNG provides the key generation seeds and the randomizer of each signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `dsa_type` - The type of DSA to create
+    /// * `rng` - The RNG used by key generation and signing
+    ///
+    /// # Returns
+    ///
+    /// A new DSA instance
+    pub fn new_with_rng(dsa_type: DsaType, rng: SharedRng) -> Result<Self> {
+        let mut dsa = Self::new(dsa_type)?;
+        dsa.set_rng(rng);
+        Ok(dsa)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.rng = Some(rng);
+    }
 }
 
 impl Dsa for SlhDsaManager {
@@ -110,7 +135,10 @@ impl Dsa for SlhDsaManager {
     /// * `dsa_type` - The type of DSA to create
     fn new(dsa_type: DsaType) -> Result<Self> {
         let dsa_info = DsaInfo::new(dsa_type);
-        Ok(Self { dsa_info })
+        Ok(Self {
+            dsa_info,
+            rng: None,
+        })
     }
 
     /// Generate a keypair using the specified RNG
@@ -143,13 +171,13 @@ impl Dsa for SlhDsaManager {
         }
     }
 
-    /// Generate a keypair using the default RNG ChaCha20Rng
+    /// Generate a keypair using the RNG of the manager, or else the default RNG ChaCha20Rng
     ///
     /// # Returns
     ///
     /// A tuple containing the public and secret keys (pk, sk)
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-        let mut rng = rand_chacha::ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
         self.key_gen_with_rng(&mut rng)
     }
 
@@ -164,19 +192,20 @@ impl Dsa for SlhDsaManager {
     ///
     /// The signature
     fn sign(&self, sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        let rng = &mut ManagerRng::for_operation(self.rng.as_ref());
         match self.dsa_info.dsa_type {
-            DsaType::SlhDsaSha2_128s => sign_slh!(slh_dsa_sha2_128s, &sk, msg),
-            DsaType::SlhDsaSha2_128f => sign_slh!(slh_dsa_sha2_128f, &sk, msg),
-            DsaType::SlhDsaSha2_192s => sign_slh!(slh_dsa_sha2_192s, &sk, msg),
-            DsaType::SlhDsaSha2_192f => sign_slh!(slh_dsa_sha2_192f, &sk, msg),
-            DsaType::SlhDsaSha2_256s => sign_slh!(slh_dsa_sha2_256s, &sk, msg),
-            DsaType::SlhDsaSha2_256f => sign_slh!(slh_dsa_sha2_256f, &sk, msg),
-            DsaType::SlhDsaShake128s => sign_slh!(slh_dsa_shake_128s, &sk, msg),
-            DsaType::SlhDsaShake128f => sign_slh!(slh_dsa_shake_128f, &sk, msg),
-            DsaType::SlhDsaShake192s => sign_slh!(slh_dsa_shake_192s, &sk, msg),
-            DsaType::SlhDsaShake192f => sign_slh!(slh_dsa_shake_192f, &sk, msg),
-            DsaType::SlhDsaShake256s => sign_slh!(slh_dsa_shake_256s, &sk, msg),
-            DsaType::SlhDsaShake256f => sign_slh!(slh_dsa_shake_256f, &sk, msg),
+            DsaType::SlhDsaSha2_128s => sign_slh!(slh_dsa_sha2_128s, &sk, msg, rng),
+            DsaType::SlhDsaSha2_128f => sign_slh!(slh_dsa_sha2_128f, &sk, msg, rng),
+            DsaType::SlhDsaSha2_192s => sign_slh!(slh_dsa_sha2_192s, &sk, msg, rng),
+            DsaType::SlhDsaSha2_192f => sign_slh!(slh_dsa_sha2_192f, &sk, msg, rng),
+            DsaType::SlhDsaSha2_256s => sign_slh!(slh_dsa_sha2_256s, &sk, msg, rng),
+            DsaType::SlhDsaSha2_256f => sign_slh!(slh_dsa_sha2_256f, &sk, msg, rng),
+            DsaType::SlhDsaShake128s => sign_slh!(slh_dsa_shake_128s, &sk, msg, rng),
+            DsaType::SlhDsaShake128f => sign_slh!(slh_dsa_shake_128f, &sk, msg, rng),
+            DsaType::SlhDsaShake192s => sign_slh!(slh_dsa_shake_192s, &sk, msg, rng),
+            DsaType::SlhDsaShake192f => sign_slh!(slh_dsa_shake_192f, &sk, msg, rng),
+            DsaType::SlhDsaShake256s => sign_slh!(slh_dsa_shake_256s, &sk, msg, rng),
+            DsaType::SlhDsaShake256f => sign_slh!(slh_dsa_shake_256f, &sk, msg, rng),
             _ => Err(QuantCryptError::NotImplemented),
         }
     }
@@ -240,7 +269,7 @@ mod tests {
     use super::*;
     use crate::certificates::Certificate;
     use crate::dsa::common::dsa_type::DsaType;
-    use crate::dsa::common::macros::test_dsa;
+    use crate::test_dsa;
 
     #[test]
     fn test_slh_dsa_sha2_128s() {
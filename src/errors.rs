@@ -91,6 +91,8 @@ pub enum QuantCryptError {
     NotImplemented,
     #[error("Invalid ciphertext")]
     InvalidCiphertext,
+    #[error("Invalid ciphertext length. Expected {expected} bytes, got {actual}")]
+    InvalidCiphertextLength { expected: usize, actual: usize },
     #[error("Encap failed")]
     EncapFailed,
     #[error("Decap failed")]
@@ -101,4 +103,72 @@ pub enum QuantCryptError {
     InvalidContent,
     #[error("Unsupported Content Encryption Algorithm")]
     UnsupportedContentEncryptionAlgorithm,
+    #[error("Invalid manifest")]
+    InvalidManifest,
+    #[error("The directory contains a symbolic link or another entry that isn't a regular file")]
+    UnsupportedDirectoryEntry,
+    #[error("Invalid paper key encoding")]
+    InvalidPaperKey,
+    #[error("Invalid CBOR encoding")]
+    InvalidCbor,
+    #[error("Invalid base64 or hex encoding")]
+    InvalidEncoding,
+    #[error("Invalid KAT file")]
+    InvalidKatFile,
+    #[error("Known answer test failed for count = {count}: {field} doesn't match")]
+    KatMismatch { count: usize, field: &'static str },
+    #[error("Failed to fetch {url}")]
+    FetchFailed { url: String },
+    #[error("Invalid CRL")]
+    InvalidCrl,
+    #[error("Invalid OCSP response")]
+    InvalidOcspResponse,
+    #[error("No certification path found")]
+    PathNotFound,
+    #[error("Failed to read from or write to a stream")]
+    StreamIoError,
+    #[error("Unknown content type")]
+    UnknownContentType,
+    #[error("Invalid group operation")]
+    InvalidGroupOperation,
+    #[error("Serial number has already been issued")]
+    DuplicateSerialNumber,
+    #[error("Issuance policy violated: {reason}")]
+    PolicyViolation { reason: String },
+    #[error("The private key is outside of its usage period")]
+    PrivateKeyOutsideUsagePeriod,
+    #[error(
+        "Resource limit exceeded: the input needs more than the parse budget of {limit} bytes"
+    )]
+    ResourceLimitExceeded { limit: usize },
+    #[error("Crypto policy violated: {reason}")]
+    CryptoPolicyViolation { reason: String },
+    #[error("Entropy health test failed")]
+    EntropyHealthTestFailed,
+    #[error("The signature failed verification after signing, it was discarded")]
+    SignatureFaultDetected,
+    #[error("Invalid encapsulation coins length. Expected {expected} bytes, got {actual}")]
+    InvalidCoinsLength { expected: usize, actual: usize },
+    #[error("Output buffer too small. Needed {needed} bytes, got {actual}")]
+    BufferTooSmall { needed: usize, actual: usize },
+    #[error("Index {index} is out of range of a Merkle tree of {size} leaves")]
+    MerkleIndexOutOfRange { index: u64, size: u64 },
+    #[error("The algorithm is not approved by FIPS and the FIPS mode is enabled")]
+    NotFipsApproved,
+    #[error("Invalid signing seed length. Expected {expected} bytes, got {actual}")]
+    InvalidSigningSeedLength { expected: usize, actual: usize },
+    #[error("Invalid secret share")]
+    InvalidShare,
+    #[error("Invalid threshold {threshold} for {shares} shares")]
+    InvalidThreshold { threshold: usize, shares: usize },
+    #[error("The escrow envelope is outside of its recovery window")]
+    OutsideRecoveryWindow,
+    #[error("Invalid certificate signing request")]
+    InvalidCsr,
+    #[error("Invalid attestation evidence")]
+    InvalidEvidence,
+    #[error("The backend of the {component} component is unavailable")]
+    BackendUnavailable { component: String },
+    #[error("The BLAKE3 context string is not valid UTF-8")]
+    InvalidBlake3Context,
 }
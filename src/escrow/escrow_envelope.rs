@@ -0,0 +1,647 @@
+use chrono::{DateTime, Utc};
+use rand_core::RngCore;
+use zeroize::Zeroizing;
+
+use crate::aead::aead_manager::AeadManager;
+use crate::aead::common::aead_trait::Aead;
+use crate::aead::common::aead_type::AeadType;
+use crate::asn1::certificate::Certificate;
+use crate::asn1::key_id::KeyIdMethod;
+use crate::asn1::private_key::PrivateKey;
+use crate::escrow::shamir::{combine_shares, split_secret, SecretShare};
+use crate::kdf::common::kdf_type::KdfType;
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::shared_secret::SharedSecret;
+use crate::utils::cbor::Value;
+use crate::utils::entropy::ManagerRng;
+use crate::wrap::api::WrapManager;
+use crate::wrap::common::wrap_trait::Wrap;
+use crate::wrap::common::wrap_type::WrapType;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The version of the escrow envelope structure
+const ESCROW_VERSION: i64 = 1;
+
+/// The label of the key encryption key derivation, followed by the share index
+const KEK_LABEL: &[u8] = b"quantcrypt escrow share ";
+
+/// The length of the AES-256 key encryption key
+const KEK_LEN: usize = 32;
+
+/// The length of the data encryption key, which is split between the custodians
+const DEK_LEN: usize = 32;
+
+/// The nonce of the sealed key. Each envelope is sealed under a fresh key, so
+/// the nonce never repeats for a key.
+const DEK_NONCE: [u8; 12] = [0u8; 12];
+
+/// The key identifier of the custodian certificates recorded in an envelope
+const CUSTODIAN_KEY_ID: KeyIdMethod = KeyIdMethod::SpkiSha256;
+
+/// The share of a custodian, encrypted to its KEM certificate
+#[derive(Clone, Debug, PartialEq)]
+struct CustodianShare {
+    key_id: Vec<u8>,
+    kem_oid: String,
+    kem_ct: Vec<u8>,
+    wrapped_share: Vec<u8>,
+}
+
+/// Derive the key encryption key of the share with the given index
+fn derive_kek(ss: &SharedSecret, index: u8) -> Result<Zeroizing<Vec<u8>>> {
+    let mut label = KEK_LABEL.to_vec();
+    label.push(index);
+    ss.expand_to_key(KdfType::HkdfWithSha256, &label, KEK_LEN)
+}
+
+/// A private key escrowed to custodians with a k-of-n recovery policy
+///
+/// The private key is encrypted with AES-256-GCM under a random data
+/// encryption key (DEK), which is split with Shamir's secret sharing
+/// (`split_secret`) into one share per custodian. As with `wrap_key_for`,
+/// each share is wrapped with AES-256 key wrap (RFC 3394) under a key derived
+/// with HKDF-SHA256 from an encapsulation to the KEM certificate of its
+/// custodian. Any `threshold` custodians can recover the key together, fewer
+/// learn nothing about it.
+///
+/// The envelope is time-boxed: shares are only released and the key is only
+/// recovered between `not_before` and `not_after`. The window and the
+/// recovery policy are authenticated by the sealed key, so they can't be
+/// changed without failing the recovery. The window is enforced by this
+/// library, not by the cryptography: custodians colluding with other software
+/// can recover the key at any time.
+///
+/// Recovery takes two steps, so custodians never hand over their private keys:
+///
+/// 1. Each custodian decrypts its share with `decrypt_share` and passes it,
+///    encoded with `SecretShare::to_bytes`, to the recovery officer.
+/// 2. The recovery officer calls `recover` with at least `threshold` shares.
+///
+/// The envelope is encoded as a compact CBOR structure, where the times are
+/// seconds since the Unix epoch and the key identifiers are the SHA-256 of the
+/// SubjectPublicKeyInfo of the custodian certificates:
+///
+/// ```text
+/// [ version: 1, threshold: uint, not_before: int, not_after: int,
+///   [* [ key_id: bstr, kem_oid: tstr, kem_ct: bstr, wrapped_share: bstr ] ],
+///   sealed_key: bstr ]
+/// ```
+///
+/// The sealed key is authenticated with the encoding of the array of the
+/// first five elements as additional data.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
+/// use chrono::{Duration, Utc};
+/// use quantcrypt::certificates::{CertValidity, CertificateBuilder, Profile};
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+/// use quantcrypt::key_escrow::EscrowEnvelope;
+/// use quantcrypt::kems::{KemAlgorithm, KemKeyGenerator};
+///
+/// let (ca_pk, ca_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+/// let ca = CertificateBuilder::new(
+///     Profile::Root, None, validity.clone(), "CN=ca".to_string(), ca_pk, &ca_sk,
+/// )
+/// .unwrap()
+/// .build()
+/// .unwrap();
+/// let custodians: Vec<_> = (0..3)
+///     .map(|i| {
+///         let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768).generate().unwrap();
+///         let profile = Profile::Leaf {
+///             issuer: ca.get_subject(),
+///             enable_key_agreement: false,
+///             enable_key_encipherment: true,
+///         };
+///         let subject = format!("CN=custodian {}", i);
+///         let cert = CertificateBuilder::new(profile, None, validity.clone(), subject, pk, &ca_sk)
+///             .unwrap()
+///             .build()
+///             .unwrap();
+///         (cert, sk)
+///     })
+///     .collect();
+/// let certs: Vec<_> = custodians.iter().map(|(cert, _)| cert.clone()).collect();
+///
+/// // Escrow a signing key for a year, recoverable by any 2 of the 3 custodians
+/// let (_, signing_key) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65).generate().unwrap();
+/// let now = Utc::now();
+/// let envelope =
+///     EscrowEnvelope::seal(&signing_key, &certs, 2, now, now + Duration::days(365)).unwrap();
+/// let envelope = EscrowEnvelope::from_bytes(&envelope.to_bytes()).unwrap();
+///
+/// let shares = [
+///     envelope.decrypt_share(&custodians[0].0, &custodians[0].1).unwrap(),
+///     envelope.decrypt_share(&custodians[2].0, &custodians[2].1).unwrap(),
+/// ];
+/// let recovered = envelope.recover(&shares).unwrap();
+/// assert_eq!(recovered.to_der().unwrap(), signing_key.to_der().unwrap());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct EscrowEnvelope {
+    threshold: usize,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+    custodians: Vec<CustodianShare>,
+    sealed_key: Vec<u8>,
+}
+
+impl EscrowEnvelope {
+    /// Escrow a private key to custodians
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The private key to escrow
+    /// * `custodians` - The KEM certificates of the custodians, with key encipherment enabled
+    /// * `threshold` - The number of custodians needed to recover the key
+    /// * `not_before` - The start of the recovery window
+    /// * `not_after` - The end of the recovery window
+    ///
+    /// # Returns
+    ///
+    /// The envelope
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidThreshold` will be returned if `threshold` is 0 or
+    /// greater than the number of custodians, or there are more than 255 custodians
+    ///
+    /// `QuantCryptError::InvalidNotAfter` will be returned if the window ends
+    /// before it starts or is in the past
+    ///
+    /// `QuantCryptError::InvalidCertificate` will be returned if a certificate doesn't
+    /// have key encipherment enabled or appears twice, and
+    /// `QuantCryptError::UnsupportedOperation` if it doesn't have a KEM key
+    ///
+    /// `QuantCryptError::EntropyHealthTestFailed` will be returned if the health
+    /// tests of the entropy source failed
+    pub fn seal(
+        sk: &PrivateKey,
+        custodians: &[Certificate],
+        threshold: usize,
+        not_before: DateTime<Utc>,
+        not_after: DateTime<Utc>,
+    ) -> Result<EscrowEnvelope> {
+        // The window is stored with a precision of a second
+        let not_before = to_timestamp(not_before)?;
+        let not_after = to_timestamp(not_after)?;
+        if not_after <= not_before || not_after <= Utc::now() {
+            return Err(QuantCryptError::InvalidNotAfter);
+        }
+
+        let mut key_ids: Vec<Vec<u8>> = Vec::with_capacity(custodians.len());
+        for cert in custodians {
+            let key_id = cert.get_key_id(CUSTODIAN_KEY_ID)?;
+            if !cert.is_key_encipherment_enabled() || key_ids.contains(&key_id) {
+                return Err(QuantCryptError::InvalidCertificate);
+            }
+            key_ids.push(key_id);
+        }
+
+        let mut dek = Zeroizing::new(vec![0u8; DEK_LEN]);
+        ManagerRng::for_key_gen(None)?.fill_bytes(&mut dek);
+        let shares = split_secret(&dek, threshold, custodians.len())?;
+
+        let wrap = WrapManager::new(WrapType::Aes256)?;
+        let mut entries = Vec::with_capacity(custodians.len());
+        for ((cert, key_id), share) in custodians.iter().zip(key_ids).zip(shares) {
+            let pk = cert.get_public_key()?;
+            let (ss, ct) = pk.encap()?;
+            let kek = derive_kek(&ss, share.get_index())?;
+            entries.push(CustodianShare {
+                key_id,
+                kem_oid: pk.get_oid().to_string(),
+                kem_ct: ct.as_bytes().to_vec(),
+                wrapped_share: wrap.wrap(&kek, share.get_value())?,
+            });
+        }
+
+        let mut envelope = EscrowEnvelope {
+            threshold,
+            not_before,
+            not_after,
+            custodians: entries,
+            sealed_key: Vec::new(),
+        };
+        let sk_der = Zeroizing::new(sk.to_der()?);
+        let aead = AeadManager::new(AeadType::AesGcm256)?;
+        envelope.sealed_key =
+            aead.seal_committing(&dek, &DEK_NONCE, &envelope.encode_header(), &sk_der)?;
+        Ok(envelope)
+    }
+
+    /// Decode an envelope encoded with `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCbor` will be returned if the structure is malformed
+    pub fn from_bytes(bytes: &[u8]) -> Result<EscrowEnvelope> {
+        let value = Value::from_slice(bytes)?;
+        let [version, threshold, not_before, not_after, custodians, sealed_key] =
+            value.as_array()?
+        else {
+            return Err(QuantCryptError::InvalidCbor);
+        };
+        if version.as_int()? != ESCROW_VERSION {
+            return Err(QuantCryptError::InvalidCbor);
+        }
+
+        let mut entries = Vec::new();
+        for custodian in custodians.as_array()? {
+            let [key_id, kem_oid, kem_ct, wrapped_share] = custodian.as_array()? else {
+                return Err(QuantCryptError::InvalidCbor);
+            };
+            entries.push(CustodianShare {
+                key_id: key_id.as_bytes()?.to_vec(),
+                kem_oid: kem_oid.as_text()?.to_string(),
+                kem_ct: kem_ct.as_bytes()?.to_vec(),
+                wrapped_share: wrapped_share.as_bytes()?.to_vec(),
+            });
+        }
+        let threshold =
+            usize::try_from(threshold.as_int()?).map_err(|_| QuantCryptError::InvalidCbor)?;
+        if threshold == 0 || threshold > entries.len() || entries.len() > u8::MAX as usize {
+            return Err(QuantCryptError::InvalidCbor);
+        }
+
+        Ok(EscrowEnvelope {
+            threshold,
+            not_before: from_timestamp(not_before.as_int()?)?,
+            not_after: from_timestamp(not_after.as_int()?)?,
+            custodians: entries,
+            sealed_key: sealed_key.as_bytes()?.to_vec(),
+        })
+    }
+
+    /// Encode the envelope as CBOR
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut fields = self.header_fields();
+        fields.push(Value::Bytes(self.sealed_key.clone()));
+        Value::Array(fields).to_vec()
+    }
+
+    /// Encode the fields authenticated by the sealed key
+    fn encode_header(&self) -> Vec<u8> {
+        Value::Array(self.header_fields()).to_vec()
+    }
+
+    /// Get the fields authenticated by the sealed key
+    fn header_fields(&self) -> Vec<Value> {
+        let custodians = self
+            .custodians
+            .iter()
+            .map(|c| {
+                Value::Array(vec![
+                    Value::Bytes(c.key_id.clone()),
+                    Value::Text(c.kem_oid.clone()),
+                    Value::Bytes(c.kem_ct.clone()),
+                    Value::Bytes(c.wrapped_share.clone()),
+                ])
+            })
+            .collect();
+        vec![
+            Value::Int(ESCROW_VERSION),
+            Value::Int(self.threshold as i64),
+            Value::Int(self.not_before.timestamp()),
+            Value::Int(self.not_after.timestamp()),
+            Value::Array(custodians),
+        ]
+    }
+
+    /// Get the number of custodians needed to recover the key
+    pub fn get_threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Get the number of custodians
+    pub fn get_custodian_count(&self) -> usize {
+        self.custodians.len()
+    }
+
+    /// Get the start of the recovery window
+    pub fn get_not_before(&self) -> DateTime<Utc> {
+        self.not_before
+    }
+
+    /// Get the end of the recovery window
+    pub fn get_not_after(&self) -> DateTime<Utc> {
+        self.not_after
+    }
+
+    /// Check if the current time is in the recovery window
+    pub fn is_in_recovery_window(&self) -> bool {
+        let now = Utc::now();
+        self.not_before <= now && now <= self.not_after
+    }
+
+    /// Check if a certificate is the certificate of a custodian
+    pub fn is_custodian(&self, cert: &Certificate) -> bool {
+        self.find_custodian(cert).is_some()
+    }
+
+    /// Get the index of the custodian with the given certificate
+    fn find_custodian(&self, cert: &Certificate) -> Option<usize> {
+        let key_id = cert.get_key_id(CUSTODIAN_KEY_ID).ok()?;
+        self.custodians.iter().position(|c| c.key_id == key_id)
+    }
+
+    /// Decrypt the share of a custodian
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the custodian
+    /// * `sk` - The private key of the custodian
+    ///
+    /// # Returns
+    ///
+    /// The share of the custodian
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::OutsideRecoveryWindow` will be returned if the current time
+    /// is outside of the recovery window
+    ///
+    /// `QuantCryptError::KeyUnwrapFailed` will be returned if the certificate isn't
+    /// one of a custodian, or the share can't be unwrapped with the private key
+    pub fn decrypt_share(&self, cert: &Certificate, sk: &PrivateKey) -> Result<SecretShare> {
+        if !self.is_in_recovery_window() {
+            return Err(QuantCryptError::OutsideRecoveryWindow);
+        }
+        let position = self
+            .find_custodian(cert)
+            .ok_or(QuantCryptError::KeyUnwrapFailed)?;
+        let custodian = &self.custodians[position];
+        if custodian.kem_oid != sk.get_oid() {
+            return Err(QuantCryptError::KeyUnwrapFailed);
+        }
+
+        // The shares are numbered from 1, in the order of the custodians
+        let index = position as u8 + 1;
+        let ct = Ciphertext::from_oid(&custodian.kem_oid, &custodian.kem_ct)
+            .map_err(|_| QuantCryptError::KeyUnwrapFailed)?;
+        let kek = derive_kek(&sk.decap(&ct)?, index)?;
+        let value = Zeroizing::new(
+            WrapManager::new(WrapType::Aes256)?
+                .unwrap(&kek, &custodian.wrapped_share)
+                .map_err(|_| QuantCryptError::KeyUnwrapFailed)?,
+        );
+        SecretShare::new(index, &value)
+    }
+
+    /// Recover the escrowed private key from the shares of the custodians
+    ///
+    /// # Arguments
+    ///
+    /// * `shares` - The shares decrypted by at least `threshold` custodians
+    ///
+    /// # Returns
+    ///
+    /// The escrowed private key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::OutsideRecoveryWindow` will be returned if the current time
+    /// is outside of the recovery window
+    ///
+    /// `QuantCryptError::InvalidThreshold` will be returned if there are fewer shares
+    /// than the threshold, and `QuantCryptError::InvalidShare` if two shares have the
+    /// same index or a share has the wrong length
+    ///
+    /// `QuantCryptError::DecryptionFailed` will be returned if a share is wrong or
+    /// the envelope has been tampered with
+    pub fn recover(&self, shares: &[SecretShare]) -> Result<PrivateKey> {
+        if !self.is_in_recovery_window() {
+            return Err(QuantCryptError::OutsideRecoveryWindow);
+        }
+        if shares.len() < self.threshold {
+            return Err(QuantCryptError::InvalidThreshold {
+                threshold: self.threshold,
+                shares: shares.len(),
+            });
+        }
+        if shares.iter().any(|s| s.get_value().len() != DEK_LEN) {
+            return Err(QuantCryptError::InvalidShare);
+        }
+
+        let dek = combine_shares(shares)?;
+        let aead = AeadManager::new(AeadType::AesGcm256)?;
+        let sk_der = Zeroizing::new(
+            aead.open_committing(&dek, &DEK_NONCE, &self.encode_header(), &self.sealed_key)
+                .map_err(|_| QuantCryptError::DecryptionFailed)?,
+        );
+        PrivateKey::from_der(&sk_der)
+    }
+}
+
+/// Truncate a time to a second
+fn to_timestamp(time: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp(time.timestamp(), 0).ok_or(QuantCryptError::InvalidNotAfter)
+}
+
+/// Decode a time from seconds since the Unix epoch
+fn from_timestamp(secs: i64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp(secs, 0).ok_or(QuantCryptError::InvalidCbor)
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::kems::{KemAlgorithm, KemKeyGenerator};
+    use chrono::Duration;
+
+    fn new_custodians(algs: &[KemAlgorithm]) -> Vec<(Certificate, PrivateKey)> {
+        let (ta_pk, ta_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let ta_cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=test.com".to_string(),
+            ta_pk,
+            &ta_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        algs.iter()
+            .enumerate()
+            .map(|(i, alg)| {
+                let (pk, sk) = KemKeyGenerator::new(*alg).generate().unwrap();
+                let cert = CertificateBuilder::new(
+                    Profile::Leaf {
+                        issuer: ta_cert.get_subject(),
+                        enable_key_agreement: false,
+                        enable_key_encipherment: true,
+                    },
+                    None,
+                    CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+                    format!("CN=custodian{}.test.com", i),
+                    pk,
+                    &ta_sk,
+                )
+                .unwrap()
+                .build()
+                .unwrap();
+                (cert, sk)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_escrow_envelope() {
+        let custodians = new_custodians(&[
+            KemAlgorithm::MlKem768,
+            KemAlgorithm::MlKem1024,
+            KemAlgorithm::MlKem768X25519,
+            KemAlgorithm::MlKem512,
+        ]);
+        let certs: Vec<Certificate> = custodians.iter().map(|(c, _)| c.clone()).collect();
+        let (_, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+
+        let now = Utc::now();
+        let envelope = EscrowEnvelope::seal(&sk, &certs, 3, now, now + Duration::days(1)).unwrap();
+        let envelope = EscrowEnvelope::from_bytes(&envelope.to_bytes()).unwrap();
+        assert_eq!(envelope.get_threshold(), 3);
+        assert_eq!(envelope.get_custodian_count(), 4);
+        assert_eq!(envelope.get_not_before().timestamp(), now.timestamp());
+        assert!(envelope.is_in_recovery_window());
+        assert!(certs.iter().all(|cert| envelope.is_custodian(cert)));
+
+        let shares: Vec<SecretShare> = custodians
+            .iter()
+            .map(|(cert, sk)| {
+                let share = envelope.decrypt_share(cert, sk).unwrap();
+                SecretShare::from_bytes(&share.to_bytes()).unwrap()
+            })
+            .collect();
+
+        // Any 3 custodians recover the key
+        for subset in [[0, 1, 2], [3, 1, 0], [1, 2, 3]] {
+            let subset: Vec<SecretShare> = subset.iter().map(|i| shares[*i].clone()).collect();
+            let recovered = envelope.recover(&subset).unwrap();
+            assert_eq!(recovered.to_der().unwrap(), sk.to_der().unwrap());
+        }
+        assert_eq!(
+            envelope.recover(&shares).unwrap().to_der().unwrap(),
+            sk.to_der().unwrap()
+        );
+
+        // 2 don't
+        assert!(matches!(
+            envelope.recover(&shares[..2]),
+            Err(QuantCryptError::InvalidThreshold {
+                threshold: 3,
+                shares: 2
+            })
+        ));
+
+        // A wrong share fails instead of recovering a wrong key
+        let wrong = SecretShare::new(4, &[0u8; DEK_LEN]).unwrap();
+        assert!(matches!(
+            envelope.recover(&[shares[0].clone(), shares[1].clone(), wrong]),
+            Err(QuantCryptError::DecryptionFailed)
+        ));
+
+        // A custodian can't decrypt the share of another
+        let outsider = new_custodians(&[KemAlgorithm::MlKem768]);
+        assert_eq!(
+            envelope.decrypt_share(&outsider[0].0, &outsider[0].1),
+            Err(QuantCryptError::KeyUnwrapFailed)
+        );
+        assert!(envelope
+            .decrypt_share(&custodians[0].0, &outsider[0].1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_escrow_envelope_tampering() {
+        let custodians = new_custodians(&[KemAlgorithm::MlKem512, KemAlgorithm::MlKem512]);
+        let certs: Vec<Certificate> = custodians.iter().map(|(c, _)| c.clone()).collect();
+        let (_, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let now = Utc::now();
+        let envelope = EscrowEnvelope::seal(&sk, &certs, 1, now, now + Duration::days(1)).unwrap();
+        let share = envelope
+            .decrypt_share(&custodians[1].0, &custodians[1].1)
+            .unwrap();
+        assert!(envelope.recover(&[share.clone()]).is_ok());
+
+        // Extending the window or changing the policy fails the recovery
+        let mut extended = envelope.clone();
+        extended.not_after += Duration::days(365);
+        assert!(matches!(
+            extended.recover(&[share.clone()]),
+            Err(QuantCryptError::DecryptionFailed)
+        ));
+        let mut changed = envelope.clone();
+        changed.threshold = 2;
+        let other = changed
+            .decrypt_share(&custodians[0].0, &custodians[0].1)
+            .unwrap();
+        assert!(matches!(
+            changed.recover(&[share, other]),
+            Err(QuantCryptError::DecryptionFailed)
+        ));
+        assert!(EscrowEnvelope::from_bytes(&envelope.to_bytes()[1..]).is_err());
+    }
+
+    #[test]
+    fn test_escrow_envelope_window() {
+        let custodians = new_custodians(&[KemAlgorithm::MlKem512, KemAlgorithm::MlKem512]);
+        let certs: Vec<Certificate> = custodians.iter().map(|(c, _)| c.clone()).collect();
+        let (_, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let now = Utc::now();
+        let day = Duration::days(1);
+
+        assert_eq!(
+            EscrowEnvelope::seal(&sk, &certs, 2, now - day * 2, now - day),
+            Err(QuantCryptError::InvalidNotAfter)
+        );
+        assert_eq!(
+            EscrowEnvelope::seal(&sk, &certs, 2, now + day, now + day),
+            Err(QuantCryptError::InvalidNotAfter)
+        );
+        assert!(matches!(
+            EscrowEnvelope::seal(&sk, &certs, 3, now, now + day),
+            Err(QuantCryptError::InvalidThreshold { .. })
+        ));
+        assert_eq!(
+            EscrowEnvelope::seal(
+                &sk,
+                &[certs[0].clone(), certs[0].clone()],
+                2,
+                now,
+                now + day
+            ),
+            Err(QuantCryptError::InvalidCertificate)
+        );
+
+        // Not yet open for recovery
+        let envelope = EscrowEnvelope::seal(&sk, &certs, 1, now + day, now + day * 2).unwrap();
+        assert!(!envelope.is_in_recovery_window());
+        assert_eq!(
+            envelope.decrypt_share(&custodians[0].0, &custodians[0].1),
+            Err(QuantCryptError::OutsideRecoveryWindow)
+        );
+        let share = SecretShare::new(1, &[0u8; DEK_LEN]).unwrap();
+        assert!(matches!(
+            envelope.recover(&[share]),
+            Err(QuantCryptError::OutsideRecoveryWindow)
+        ));
+    }
+}
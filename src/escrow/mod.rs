@@ -0,0 +1,2 @@
+pub mod escrow_envelope;
+pub mod shamir;
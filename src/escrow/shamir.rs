@@ -0,0 +1,319 @@
+use rand_core::RngCore;
+use zeroize::Zeroizing;
+
+use crate::utils::ct_eq::ct_eq;
+use crate::utils::entropy::ManagerRng;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// One share of a secret split with `split_secret`
+///
+/// The index is the x-coordinate of the share, from 1 to 255. The value has
+/// the length of the secret and is zeroized on drop. Shares compare in constant
+/// time, and their `Debug` output doesn't reveal the value.
+#[derive(Clone)]
+pub struct SecretShare {
+    index: u8,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl SecretShare {
+    /// Create a share from its index and value
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the share, from 1 to 255
+    /// * `value` - The value of the share
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidShare` will be returned if the index is 0 or the value is empty
+    pub fn new(index: u8, value: &[u8]) -> Result<SecretShare> {
+        if index == 0 || value.is_empty() {
+            return Err(QuantCryptError::InvalidShare);
+        }
+        Ok(SecretShare {
+            index,
+            value: Zeroizing::new(value.to_vec()),
+        })
+    }
+
+    /// Get the index of the share
+    pub fn get_index(&self) -> u8 {
+        self.index
+    }
+
+    /// Get the value of the share
+    pub fn get_value(&self) -> &[u8] {
+        &self.value
+    }
+
+    /// Encode the share as its index followed by its value
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut out = Zeroizing::new(Vec::with_capacity(1 + self.value.len()));
+        out.push(self.index);
+        out.extend_from_slice(&self.value);
+        out
+    }
+
+    /// Decode a share encoded with `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidShare` will be returned if the encoding is malformed
+    pub fn from_bytes(bytes: &[u8]) -> Result<SecretShare> {
+        match bytes.split_first() {
+            Some((index, value)) => SecretShare::new(*index, value),
+            None => Err(QuantCryptError::InvalidShare),
+        }
+    }
+}
+
+impl PartialEq for SecretShare {
+    fn eq(&self, other: &Self) -> bool {
+        (self.index == other.index) & ct_eq(&self.value, &other.value)
+    }
+}
+
+impl Eq for SecretShare {}
+
+impl std::fmt::Debug for SecretShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretShare")
+            .field("index", &self.index)
+            .field("len", &self.value.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Multiply in GF(2^8) with the AES polynomial, without branching on the operands
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    product
+}
+
+/// Invert a non-zero element of GF(2^8), as a^254
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut power = a;
+    let mut exp = 254u8;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = gf_mul(result, power);
+        }
+        power = gf_mul(power, power);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Split a secret into shares, any `threshold` of which recover it
+///
+/// This is Shamir's secret sharing over GF(2^8), byte by byte: each byte of
+/// the secret is the constant term of a random polynomial of degree
+/// `threshold - 1`, and share `i` holds the evaluations at `x = i`. Fewer than
+/// `threshold` shares reveal nothing about the secret.
+///
+/// The shares are not authenticated. Combining a wrong or corrupted share
+/// silently yields a wrong secret, so protect the secret with an integrity
+/// check, as `EscrowEnvelope` does.
+///
+/// # Arguments
+///
+/// * `secret` - The secret to split
+/// * `threshold` - The number of shares needed to recover the secret
+/// * `shares` - The number of shares to create, at most 255
+///
+/// # Returns
+///
+/// The shares, with the indices 1 to `shares`
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidThreshold` will be returned if `threshold` is 0 or
+/// greater than `shares`, or `shares` is greater than 255
+///
+/// `QuantCryptError::InvalidShare` will be returned if the secret is empty
+///
+/// `QuantCryptError::EntropyHealthTestFailed` will be returned if the health tests
+/// of the entropy source failed
+///
+/// # Example
+/// ```
+/// use quantcrypt::key_escrow::{combine_shares, split_secret};
+///
+/// let shares = split_secret(b"secret", 2, 3).unwrap();
+/// let recovered = combine_shares(&[shares[2].clone(), shares[0].clone()]).unwrap();
+/// assert_eq!(*recovered, b"secret");
+/// ```
+pub fn split_secret(secret: &[u8], threshold: usize, shares: usize) -> Result<Vec<SecretShare>> {
+    if threshold == 0 || threshold > shares || shares > u8::MAX as usize {
+        return Err(QuantCryptError::InvalidThreshold { threshold, shares });
+    }
+    if secret.is_empty() {
+        return Err(QuantCryptError::InvalidShare);
+    }
+
+    // The coefficients of degree 1 and above of the polynomial of each byte
+    let mut coefficients = Zeroizing::new(vec![0u8; secret.len() * (threshold - 1)]);
+    ManagerRng::for_key_gen(None)?.fill_bytes(&mut coefficients);
+
+    let mut out = Vec::with_capacity(shares);
+    for x in 1..=shares as u8 {
+        let mut value = Zeroizing::new(Vec::with_capacity(secret.len()));
+        for (i, byte) in secret.iter().enumerate() {
+            // Horner's rule, from the highest degree coefficient
+            let mut y = 0u8;
+            for c in coefficients[i * (threshold - 1)..(i + 1) * (threshold - 1)]
+                .iter()
+                .rev()
+            {
+                y = gf_mul(y, x) ^ c;
+            }
+            value.push(gf_mul(y, x) ^ byte);
+        }
+        out.push(SecretShare { index: x, value });
+    }
+    Ok(out)
+}
+
+/// Recover a secret from shares created with `split_secret`
+///
+/// All the shares given are used, so they must number at least the threshold
+/// of the split. Passing fewer yields a wrong secret rather than an error, as
+/// the threshold isn't recorded in the shares.
+///
+/// # Arguments
+///
+/// * `shares` - The shares, in any order
+///
+/// # Returns
+///
+/// The secret
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidShare` will be returned if there are no shares, two
+/// shares have the same index, or the shares have different lengths
+pub fn combine_shares(shares: &[SecretShare]) -> Result<Zeroizing<Vec<u8>>> {
+    let Some(first) = shares.first() else {
+        return Err(QuantCryptError::InvalidShare);
+    };
+    let len = first.value.len();
+    for (i, share) in shares.iter().enumerate() {
+        if share.value.len() != len || shares[..i].iter().any(|s| s.index == share.index) {
+            return Err(QuantCryptError::InvalidShare);
+        }
+    }
+
+    // Lagrange interpolation at x = 0. In GF(2^8), subtraction is XOR.
+    let mut secret = Zeroizing::new(vec![0u8; len]);
+    for share in shares {
+        let mut numerator = 1u8;
+        let mut denominator = 1u8;
+        for other in shares.iter().filter(|s| s.index != share.index) {
+            numerator = gf_mul(numerator, other.index);
+            denominator = gf_mul(denominator, other.index ^ share.index);
+        }
+        let basis = gf_mul(numerator, gf_inv(denominator));
+        for (s, v) in secret.iter_mut().zip(share.value.iter()) {
+            *s ^= gf_mul(basis, *v);
+        }
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf_arithmetic() {
+        // FIPS 197, section 4.2
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_split_and_combine() {
+        let secret = [0x42u8; 32];
+        let shares = split_secret(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert_eq!(
+            shares.iter().map(|s| s.get_index()).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5]
+        );
+
+        // Any 3 shares recover the secret
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<SecretShare> = subset.iter().map(|i| shares[*i].clone()).collect();
+            assert_eq!(*combine_shares(&subset).unwrap(), secret);
+        }
+        assert_eq!(*combine_shares(&shares).unwrap(), secret);
+
+        // 2 shares don't
+        assert_ne!(*combine_shares(&shares[..2]).unwrap(), secret);
+
+        // A threshold of 1 gives out the secret in every share
+        let shares = split_secret(&secret, 1, 2).unwrap();
+        assert_eq!(shares[1].get_value(), secret.as_slice());
+
+        let encoded = shares[0].to_bytes();
+        assert_eq!(SecretShare::from_bytes(&encoded).unwrap(), shares[0]);
+        assert_ne!(shares[0], shares[1]);
+
+        // The value isn't printed
+        let share = SecretShare::new(7, &[0xab; 4]).unwrap();
+        assert_eq!(
+            format!("{:?}", share),
+            "SecretShare { index: 7, len: 4, .. }"
+        );
+    }
+
+    #[test]
+    fn test_invalid_shares() {
+        assert!(matches!(
+            split_secret(b"secret", 0, 3),
+            Err(QuantCryptError::InvalidThreshold { .. })
+        ));
+        assert!(matches!(
+            split_secret(b"secret", 4, 3),
+            Err(QuantCryptError::InvalidThreshold { .. })
+        ));
+        assert!(matches!(
+            split_secret(b"secret", 2, 256),
+            Err(QuantCryptError::InvalidThreshold { .. })
+        ));
+        assert_eq!(split_secret(b"", 2, 3), Err(QuantCryptError::InvalidShare));
+
+        let shares = split_secret(b"secret", 2, 3).unwrap();
+        assert_eq!(combine_shares(&[]), Err(QuantCryptError::InvalidShare));
+        assert_eq!(
+            combine_shares(&[shares[0].clone(), shares[0].clone()]),
+            Err(QuantCryptError::InvalidShare)
+        );
+        let short = SecretShare::new(3, b"short").unwrap();
+        assert_eq!(
+            combine_shares(&[shares[0].clone(), short]),
+            Err(QuantCryptError::InvalidShare)
+        );
+        assert_eq!(
+            SecretShare::new(0, b"x"),
+            Err(QuantCryptError::InvalidShare)
+        );
+        assert_eq!(
+            SecretShare::from_bytes(&[]),
+            Err(QuantCryptError::InvalidShare)
+        );
+    }
+}
@@ -0,0 +1,957 @@
+use hkdf::Hkdf;
+use rand_core::RngCore;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::aead::aead_manager::AeadManager;
+use crate::aead::common::aead_trait::Aead;
+use crate::aead::common::aead_type::AeadType;
+use crate::kem::common::config::pk_len::PKLen;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::ml_kem::MlKemManager;
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The KEM used for all nodes of the tree
+const NODE_KEM: KemType = KemType::MlKem768;
+
+/// The length of path secrets, epoch secrets and derived keys
+const SECRET_LEN: usize = 32;
+
+/// The nonce used when sealing secrets. Each seal uses a fresh key.
+const SEAL_NONCE: [u8; 12] = [0u8; 12];
+
+/// Derive a secret from another secret with HKDF-SHA256
+fn derive_secret(secret: &[u8], label: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    let mut info = b"quantcrypt treekem ".to_vec();
+    info.extend_from_slice(label);
+    let mut out = Zeroizing::new(vec![0u8; SECRET_LEN]);
+    Hkdf::<Sha256>::new(None, secret)
+        .expand(&info, &mut out)
+        .map_err(|_| QuantCryptError::KdfError)?;
+    Ok(out)
+}
+
+/// Generate a random secret, from which node key pairs are derived
+///
+/// # Arguments
+///
+/// * `rng` - The RNG of the member, or None for the health tested system entropy
+fn random_secret(rng: Option<&SharedRng>) -> Result<Zeroizing<Vec<u8>>> {
+    let mut secret = Zeroizing::new(vec![0u8; SECRET_LEN]);
+    ManagerRng::for_key_gen(rng)?.fill_bytes(&mut secret);
+    Ok(secret)
+}
+
+/// Derive the ML-KEM key pair of a node from its path secret
+///
+/// # Returns
+///
+/// A tuple containing the public and private keys (pk, sk)
+fn node_key_pair(path_secret: &[u8]) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>)> {
+    let d = derive_secret(path_secret, b"node d")?;
+    let z = derive_secret(path_secret, b"node z")?;
+    let kem = MlKemManager::new(NODE_KEM)?;
+    let (pk, sk) = kem.key_gen_deterministic(
+        d.as_slice()
+            .try_into()
+            .map_err(|_| QuantCryptError::KdfError)?,
+        z.as_slice()
+            .try_into()
+            .map_err(|_| QuantCryptError::KdfError)?,
+    )?;
+    Ok((pk, Zeroizing::new(sk)))
+}
+
+/// Seal a secret to the public key of a node
+fn seal(pk: &[u8], aad: &[u8], secret: &[u8], rng: Option<&SharedRng>) -> Result<SealedSecret> {
+    let mut kem = MlKemManager::new(NODE_KEM)?;
+    if let Some(rng) = rng {
+        kem.set_rng(rng.clone());
+    }
+    let (ss, kem_ct) = kem.encap(pk)?;
+    let key = derive_secret(&Zeroizing::new(ss), b"seal")?;
+    let ciphertext =
+        AeadManager::new(AeadType::AesGcm256)?.seal_committing(&key, &SEAL_NONCE, aad, secret)?;
+    Ok(SealedSecret { kem_ct, ciphertext })
+}
+
+/// Open a secret sealed to a node
+fn open(sk: &[u8], aad: &[u8], sealed: &SealedSecret) -> Result<Zeroizing<Vec<u8>>> {
+    let ss = Zeroizing::new(MlKemManager::new(NODE_KEM)?.decap(sk, &sealed.kem_ct)?);
+    let key = derive_secret(&ss, b"seal")?;
+    let secret = AeadManager::new(AeadType::AesGcm256)?.open_committing(
+        &key,
+        &SEAL_NONCE,
+        aad,
+        &sealed.ciphertext,
+    )?;
+    Ok(Zeroizing::new(secret))
+}
+
+// Array representation of a left-balanced binary tree (RFC 9420, Appendix C).
+// Leaf i is node 2i, and the number of leaves is always a power of two.
+
+/// The level of a node, 0 for leaves
+fn level(x: usize) -> u32 {
+    x.trailing_ones()
+}
+
+fn left(x: usize) -> usize {
+    x ^ (1 << (level(x) - 1))
+}
+
+fn right(x: usize) -> usize {
+    x ^ (3 << (level(x) - 1))
+}
+
+fn parent(x: usize) -> usize {
+    let k = level(x);
+    let b = (x >> (k + 1)) & 1;
+    (x | (1 << k)) ^ (b << (k + 1))
+}
+
+/// Check if `x` is `a` or one of its descendants
+fn is_in_subtree(a: usize, x: usize) -> bool {
+    let span = (1usize << level(a)) - 1;
+    a - span <= x && x <= a + span
+}
+
+/// The child of `p` whose subtree does not contain `x`
+fn child_away(p: usize, x: usize) -> usize {
+    if x < p {
+        right(p)
+    } else {
+        left(p)
+    }
+}
+
+/// A secret sealed to the public key of a node
+#[derive(Clone, Debug)]
+struct SealedSecret {
+    kem_ct: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+/// A node of the ratchet tree
+#[derive(Clone)]
+struct Node {
+    public_key: Vec<u8>,
+    /// The private key, if this member knows it
+    private_key: Option<Zeroizing<Vec<u8>>>,
+}
+
+/// The new public key of a node on the direct path of the committer, and its
+/// path secret sealed to the resolution of the copath child
+#[derive(Clone, Debug)]
+struct UpdatePathNode {
+    public_key: Vec<u8>,
+    sealed_path_secrets: Vec<SealedSecret>,
+}
+
+/// The public key that a new member publishes so that it can be added to a group
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyPackage {
+    public_key: Vec<u8>,
+}
+
+impl KeyPackage {
+    /// Get the ML-KEM-768 public key of the new member
+    pub fn get_public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+/// A member waiting to be added to a group
+pub struct PendingMember {
+    key_package: KeyPackage,
+    private_key: Zeroizing<Vec<u8>>,
+    rng: Option<SharedRng>,
+}
+
+impl PendingMember {
+    /// Create a new pending member with a fresh leaf key pair
+    ///
+    /// # Returns
+    ///
+    /// The pending member
+    pub fn new() -> Result<Self> {
+        Self::new_member(None)
+    }
+
+    /// Create a new pending member drawing all its randomness from a caller-supplied RNG
+    ///
+    /// The RNG is kept by the group that the member joins. See `SharedRng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The RNG
+    ///
+    /// # Returns
+    ///
+    /// The pending member
+    pub fn new_with_rng(rng: SharedRng) -> Result<Self> {
+        Self::new_member(Some(rng))
+    }
+
+    fn new_member(rng: Option<SharedRng>) -> Result<Self> {
+        let (public_key, private_key) = node_key_pair(&random_secret(rng.as_ref())?)?;
+        Ok(PendingMember {
+            key_package: KeyPackage { public_key },
+            private_key,
+            rng,
+        })
+    }
+
+    /// Get the key package to send to a member of the group
+    pub fn get_key_package(&self) -> KeyPackage {
+        self.key_package.clone()
+    }
+}
+
+/// A change to the membership of a group
+#[derive(Clone, Debug)]
+pub enum Proposal {
+    /// Add a new member
+    Add(KeyPackage),
+    /// Remove the member at a leaf index
+    Remove(u32),
+    /// Refresh the key material of the committer
+    Update,
+}
+
+/// A proposal and the new key material of the committer, moving a group to the
+/// next epoch. Sent to all other members of the group.
+#[derive(Clone, Debug)]
+pub struct Commit {
+    epoch: u64,
+    committer: u32,
+    proposal: Proposal,
+    leaf_public_key: Vec<u8>,
+    path: Vec<UpdatePathNode>,
+}
+
+impl Commit {
+    /// Get the epoch that the commit applies to
+    pub fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Get the leaf index of the committer
+    pub fn get_committer(&self) -> u32 {
+        self.committer
+    }
+
+    /// Get the proposal of the commit
+    pub fn get_proposal(&self) -> &Proposal {
+        &self.proposal
+    }
+}
+
+/// The state of a group for a new member. Sent to the new member only.
+#[derive(Clone, Debug)]
+pub struct Welcome {
+    group_id: Vec<u8>,
+    epoch: u64,
+    leaf: u32,
+    committer: u32,
+    tree: Vec<Option<Vec<u8>>>,
+    /// The epoch secret followed by the path secret of the lowest common ancestor
+    /// of the new member and the committer
+    sealed_secrets: SealedSecret,
+}
+
+/// An experimental continuous group key agreement based on TreeKEM and ML-KEM-768
+///
+/// Every member holds a copy of a binary tree with the members at the leaves. Each
+/// node has an ML-KEM key pair, and members know the private keys of the nodes on
+/// the path from their leaf to the root. Adding, removing or updating a member
+/// refreshes the keys on the path of the committing member, which costs a number
+/// of encapsulations logarithmic in the size of the group, and moves the group to a
+/// new epoch with a new epoch secret.
+///
+/// This is a research prototype loosely following RFC 9420 (MLS). It has no wire
+/// format, no authentication of members or commits, and no protection against
+/// concurrent commits. All members must process commits in the same order, and a
+/// committer applies its own commit immediately.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
+/// use quantcrypt::groups::{PendingMember, TreeKemGroup};
+///
+/// let mut alice = TreeKemGroup::create(b"group").unwrap();
+/// let bob = PendingMember::new().unwrap();
+/// let (_, welcome) = alice.add(&bob.get_key_package()).unwrap();
+/// let mut bob = TreeKemGroup::join(bob, &welcome).unwrap();
+///
+/// let commit = bob.update().unwrap();
+/// alice.process_commit(&commit).unwrap();
+/// assert_eq!(
+///     alice.export_secret(b"app", 32).unwrap(),
+///     bob.export_secret(b"app", 32).unwrap()
+/// );
+/// # }
+/// ```
+pub struct TreeKemGroup {
+    group_id: Vec<u8>,
+    epoch: u64,
+    leaf: u32,
+    nodes: Vec<Option<Node>>,
+    epoch_secret: Zeroizing<Vec<u8>>,
+    rng: Option<SharedRng>,
+}
+
+impl TreeKemGroup {
+    /// Create a new group with a single member
+    ///
+    /// # Arguments
+    ///
+    /// * `group_id` - The identifier of the group, which is bound to all sealed secrets
+    ///
+    /// # Returns
+    ///
+    /// The group, at epoch 0
+    pub fn create(group_id: &[u8]) -> Result<Self> {
+        Self::create_group(group_id, None)
+    }
+
+    /// Create a new group with a single member, drawing all the randomness of the
+    /// member from a caller-supplied RNG
+    ///
+    /// The leaf secrets of every commit and the coins of every encapsulation are
+    /// drawn from this RNG. See `SharedRng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `group_id` - The identifier of the group, which is bound to all sealed secrets
+    /// * `rng` - The RNG
+    ///
+    /// # Returns
+    ///
+    /// The group, at epoch 0
+    pub fn create_with_rng(group_id: &[u8], rng: SharedRng) -> Result<Self> {
+        Self::create_group(group_id, Some(rng))
+    }
+
+    fn create_group(group_id: &[u8], rng: Option<SharedRng>) -> Result<Self> {
+        let (public_key, private_key) = node_key_pair(&random_secret(rng.as_ref())?)?;
+        Ok(TreeKemGroup {
+            group_id: group_id.to_vec(),
+            epoch: 0,
+            leaf: 0,
+            nodes: vec![Some(Node {
+                public_key,
+                private_key: Some(private_key),
+            })],
+            epoch_secret: random_secret(rng.as_ref())?,
+            rng,
+        })
+    }
+
+    /// Join a group
+    ///
+    /// The group draws its randomness from the RNG of the member, if it was created
+    /// with `PendingMember::new_with_rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `member` - The pending member whose key package was added
+    /// * `welcome` - The welcome created when the member was added
+    ///
+    /// # Returns
+    ///
+    /// The group
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidGroupOperation` if the welcome is not for this member or is
+    /// inconsistent, and `QuantCryptError::DecryptionFailed` if its secrets can't be opened
+    pub fn join(member: PendingMember, welcome: &Welcome) -> Result<Self> {
+        let leaf_node = 2 * welcome.leaf as usize;
+        let committer_node = 2 * welcome.committer as usize;
+        match welcome.tree.get(leaf_node) {
+            Some(Some(pk)) if *pk == member.key_package.public_key => {}
+            _ => return Err(QuantCryptError::InvalidGroupOperation),
+        }
+        if !matches!(welcome.tree.get(committer_node), Some(Some(_)))
+            || !(welcome.tree.len() + 1).is_power_of_two()
+        {
+            return Err(QuantCryptError::InvalidGroupOperation);
+        }
+
+        let mut group = TreeKemGroup {
+            group_id: welcome.group_id.clone(),
+            epoch: welcome.epoch,
+            leaf: welcome.leaf,
+            nodes: welcome
+                .tree
+                .iter()
+                .map(|pk| {
+                    pk.as_ref().map(|pk| Node {
+                        public_key: pk.clone(),
+                        private_key: None,
+                    })
+                })
+                .collect(),
+            epoch_secret: Zeroizing::new(Vec::new()),
+            rng: member.rng.clone(),
+        };
+
+        let secrets = open(
+            &member.private_key,
+            &group.group_context(),
+            &welcome.sealed_secrets,
+        )?;
+        if secrets.len() != 2 * SECRET_LEN {
+            return Err(QuantCryptError::InvalidGroupOperation);
+        }
+        group.epoch_secret = Zeroizing::new(secrets[..SECRET_LEN].to_vec());
+        if let Some(Some(node)) = group.nodes.get_mut(leaf_node) {
+            node.private_key = Some(member.private_key);
+        }
+
+        let ancestor = group
+            .direct_path(committer_node)
+            .into_iter()
+            .find(|p| is_in_subtree(*p, leaf_node))
+            .ok_or(QuantCryptError::InvalidGroupOperation)?;
+        let path: Vec<usize> = group
+            .direct_path(leaf_node)
+            .into_iter()
+            .skip_while(|p| *p != ancestor)
+            .collect();
+        let expected = path
+            .iter()
+            .map(|x| group.nodes[*x].as_ref().map(|n| n.public_key.clone()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(QuantCryptError::InvalidGroupOperation)?;
+        group.apply_path_secrets(&path, &secrets[SECRET_LEN..], &expected)?;
+        Ok(group)
+    }
+
+    /// Add a member to the group
+    ///
+    /// # Arguments
+    ///
+    /// * `key_package` - The key package of the new member
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the commit for the other members and the welcome for the
+    /// new member (commit, welcome)
+    pub fn add(&mut self, key_package: &KeyPackage) -> Result<(Commit, Welcome)> {
+        if Some(key_package.public_key.len()) != NODE_KEM.get_pk_len() {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+        let (commit, welcome) = self.commit(Proposal::Add(key_package.clone()))?;
+        Ok((commit, welcome.ok_or(QuantCryptError::Unknown)?))
+    }
+
+    /// Remove a member from the group
+    ///
+    /// # Arguments
+    ///
+    /// * `leaf` - The leaf index of the member to remove
+    ///
+    /// # Returns
+    ///
+    /// The commit for the other members
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidGroupOperation` if there is no member at the leaf index,
+    /// or if it is this member
+    pub fn remove(&mut self, leaf: u32) -> Result<Commit> {
+        Ok(self.commit(Proposal::Remove(leaf))?.0)
+    }
+
+    /// Refresh the key material of this member, e.g. after a suspected compromise
+    ///
+    /// # Returns
+    ///
+    /// The commit for the other members
+    pub fn update(&mut self) -> Result<Commit> {
+        Ok(self.commit(Proposal::Update)?.0)
+    }
+
+    /// Process a commit of another member, moving the group to the next epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The commit
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidGroupOperation` if the commit is for another epoch, is
+    /// inconsistent with the tree, or removes this member, and
+    /// `QuantCryptError::DecryptionFailed` if the path secret can't be opened
+    pub fn process_commit(&mut self, commit: &Commit) -> Result<()> {
+        let committer_node = 2 * commit.committer as usize;
+        if commit.epoch != self.epoch
+            || commit.committer == self.leaf
+            || !matches!(self.nodes.get(committer_node), Some(Some(_)))
+            || matches!(commit.proposal, Proposal::Remove(leaf) if leaf == self.leaf)
+        {
+            return Err(QuantCryptError::InvalidGroupOperation);
+        }
+
+        // Work on a copy, so that a bad commit leaves the group unchanged
+        let mut next = TreeKemGroup {
+            group_id: self.group_id.clone(),
+            epoch: self.epoch,
+            leaf: self.leaf,
+            nodes: self.nodes.clone(),
+            epoch_secret: self.epoch_secret.clone(),
+            rng: self.rng.clone(),
+        };
+        let aad = next.group_context();
+        next.apply_proposal(&commit.proposal)?;
+
+        let direct_path = next.direct_path(committer_node);
+        if direct_path.len() != commit.path.len() {
+            return Err(QuantCryptError::InvalidGroupOperation);
+        }
+
+        // The lowest node on the path of the committer that is an ancestor of this member
+        let own_node = 2 * next.leaf as usize;
+        let index = direct_path
+            .iter()
+            .position(|p| is_in_subtree(*p, own_node))
+            .ok_or(QuantCryptError::InvalidGroupOperation)?;
+        let resolution = next.resolution(child_away(direct_path[index], committer_node));
+        let sealed = &commit.path[index].sealed_path_secrets;
+        if sealed.len() != resolution.len() {
+            return Err(QuantCryptError::InvalidGroupOperation);
+        }
+        let (sealed, sk) = resolution
+            .iter()
+            .zip(sealed)
+            .find_map(|(x, sealed)| {
+                let node = next.nodes[*x].as_ref()?;
+                match (&node.private_key, is_in_subtree(*x, own_node)) {
+                    (Some(sk), true) => Some((sealed, sk.clone())),
+                    _ => None,
+                }
+            })
+            .ok_or(QuantCryptError::DecryptionFailed)?;
+        let path_secret = open(&sk, &aad, sealed)?;
+
+        next.nodes[committer_node] = Some(Node {
+            public_key: commit.leaf_public_key.clone(),
+            private_key: None,
+        });
+        for (x, path_node) in direct_path.iter().zip(&commit.path).take(index) {
+            next.nodes[*x] = Some(Node {
+                public_key: path_node.public_key.clone(),
+                private_key: None,
+            });
+        }
+        let expected: Vec<Vec<u8>> = commit.path[index..]
+            .iter()
+            .map(|n| n.public_key.clone())
+            .collect();
+        let commit_secret =
+            next.apply_path_secrets(&direct_path[index..], &path_secret, &expected)?;
+        next.next_epoch(&commit_secret)?;
+
+        *self = next;
+        Ok(())
+    }
+
+    /// Export a secret from the current epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - A label binding the secret to its purpose
+    /// * `len` - The length of the secret in bytes
+    ///
+    /// # Returns
+    ///
+    /// The secret, which is the same for all members in the same epoch
+    pub fn export_secret(&self, label: &[u8], len: usize) -> Result<Vec<u8>> {
+        let exporter = derive_secret(&self.epoch_secret, b"exporter")?;
+        let mut out = vec![0u8; len];
+        Hkdf::<Sha256>::new(None, &exporter)
+            .expand(label, &mut out)
+            .map_err(|_| QuantCryptError::InvalidHkdfLength)?;
+        Ok(out)
+    }
+
+    /// Get the identifier of the group
+    pub fn get_group_id(&self) -> &[u8] {
+        &self.group_id
+    }
+
+    /// Get the current epoch
+    pub fn get_epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Get the leaf index of this member
+    pub fn get_leaf_index(&self) -> u32 {
+        self.leaf
+    }
+
+    /// Get the number of members of the group
+    pub fn get_member_count(&self) -> usize {
+        self.nodes.iter().step_by(2).filter(|n| n.is_some()).count()
+    }
+
+    /// The data that sealed secrets are bound to
+    fn group_context(&self) -> Vec<u8> {
+        let mut context = self.group_id.clone();
+        context.extend_from_slice(&self.epoch.to_be_bytes());
+        context
+    }
+
+    /// The index of the root node
+    fn root(&self) -> usize {
+        self.nodes.len() / 2
+    }
+
+    /// The ancestors of a node, from its parent to the root
+    fn direct_path(&self, mut x: usize) -> Vec<usize> {
+        let root = self.root();
+        let mut path = Vec::new();
+        while x != root {
+            x = parent(x);
+            path.push(x);
+        }
+        path
+    }
+
+    /// The non-blank nodes that together cover all members below a node
+    fn resolution(&self, x: usize) -> Vec<usize> {
+        match &self.nodes[x] {
+            Some(_) => vec![x],
+            None if level(x) == 0 => Vec::new(),
+            None => {
+                let mut nodes = self.resolution(left(x));
+                nodes.extend(self.resolution(right(x)));
+                nodes
+            }
+        }
+    }
+
+    /// Remove a node and the nodes above it
+    fn blank_direct_path(&mut self, x: usize) {
+        for p in self.direct_path(x) {
+            self.nodes[p] = None;
+        }
+    }
+
+    /// Apply the membership change of a proposal to the tree
+    ///
+    /// # Returns
+    ///
+    /// The leaf index of the added member, for an add
+    fn apply_proposal(&mut self, proposal: &Proposal) -> Result<Option<u32>> {
+        match proposal {
+            Proposal::Add(key_package) => {
+                let node = match (0..self.nodes.len())
+                    .step_by(2)
+                    .find(|x| self.nodes[*x].is_none())
+                {
+                    Some(node) => node,
+                    None => {
+                        // Double the number of leaves, the new root is blank
+                        let node = self.nodes.len() + 1;
+                        self.nodes.resize(2 * self.nodes.len() + 1, None);
+                        node
+                    }
+                };
+                self.nodes[node] = Some(Node {
+                    public_key: key_package.public_key.clone(),
+                    private_key: None,
+                });
+                // The new member doesn't know the private keys above it
+                self.blank_direct_path(node);
+                Ok(Some((node / 2) as u32))
+            }
+            Proposal::Remove(leaf) => {
+                let node = 2 * *leaf as usize;
+                if *leaf == self.leaf || !matches!(self.nodes.get(node), Some(Some(_))) {
+                    return Err(QuantCryptError::InvalidGroupOperation);
+                }
+                self.nodes[node] = None;
+                self.blank_direct_path(node);
+                Ok(None)
+            }
+            Proposal::Update => Ok(None),
+        }
+    }
+
+    /// Set the key pairs of a path of nodes from the path secret of the first one
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The nodes, from the lowest to the root
+    /// * `path_secret` - The path secret of the first node
+    /// * `expected` - The public keys that the derived key pairs must match
+    ///
+    /// # Returns
+    ///
+    /// The commit secret derived from the path secret of the root
+    fn apply_path_secrets(
+        &mut self,
+        path: &[usize],
+        path_secret: &[u8],
+        expected: &[Vec<u8>],
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        let mut path_secret = Zeroizing::new(path_secret.to_vec());
+        for (x, expected) in path.iter().zip(expected) {
+            let (public_key, private_key) = node_key_pair(&path_secret)?;
+            if *expected != public_key {
+                return Err(QuantCryptError::InvalidGroupOperation);
+            }
+            self.nodes[*x] = Some(Node {
+                public_key,
+                private_key: Some(private_key),
+            });
+            path_secret = derive_secret(&path_secret, b"path")?;
+        }
+        Ok(path_secret)
+    }
+
+    /// Move to the next epoch
+    fn next_epoch(&mut self, commit_secret: &[u8]) -> Result<()> {
+        let init_secret = derive_secret(&self.epoch_secret, b"init")?;
+        let mut epoch_secret = Zeroizing::new(vec![0u8; SECRET_LEN]);
+        Hkdf::<Sha256>::new(Some(&init_secret), commit_secret)
+            .expand(b"quantcrypt treekem epoch", &mut epoch_secret)
+            .map_err(|_| QuantCryptError::KdfError)?;
+        self.epoch_secret = epoch_secret;
+        self.epoch += 1;
+        Ok(())
+    }
+
+    /// Apply a proposal, refresh the path of this member and move to the next epoch
+    fn commit(&mut self, proposal: Proposal) -> Result<(Commit, Option<Welcome>)> {
+        let aad = self.group_context();
+        let mut next = TreeKemGroup {
+            group_id: self.group_id.clone(),
+            epoch: self.epoch,
+            leaf: self.leaf,
+            nodes: self.nodes.clone(),
+            epoch_secret: self.epoch_secret.clone(),
+            rng: self.rng.clone(),
+        };
+        let added = next.apply_proposal(&proposal)?;
+
+        let own_node = 2 * next.leaf as usize;
+        let leaf_secret = random_secret(next.rng.as_ref())?;
+        let (leaf_public_key, leaf_private_key) = node_key_pair(&leaf_secret)?;
+        next.nodes[own_node] = Some(Node {
+            public_key: leaf_public_key.clone(),
+            private_key: Some(leaf_private_key),
+        });
+
+        let mut path = Vec::new();
+        let mut path_secrets = Vec::new();
+        let mut path_secret = derive_secret(&leaf_secret, b"path")?;
+        for x in next.direct_path(own_node) {
+            let (public_key, private_key) = node_key_pair(&path_secret)?;
+            let sealed_path_secrets = next
+                .resolution(child_away(x, own_node))
+                .into_iter()
+                .filter_map(|r| next.nodes[r].as_ref())
+                .map(|r| seal(&r.public_key, &aad, &path_secret, next.rng.as_ref()))
+                .collect::<Result<Vec<_>>>()?;
+            next.nodes[x] = Some(Node {
+                public_key: public_key.clone(),
+                private_key: Some(private_key),
+            });
+            path.push(UpdatePathNode {
+                public_key,
+                sealed_path_secrets,
+            });
+            path_secrets.push((x, path_secret.clone()));
+            path_secret = derive_secret(&path_secret, b"path")?;
+        }
+        next.next_epoch(&path_secret)?;
+
+        let welcome = match added {
+            Some(leaf) => {
+                let new_node = 2 * leaf as usize;
+                let (_, ancestor_secret) = path_secrets
+                    .iter()
+                    .find(|(x, _)| is_in_subtree(*x, new_node))
+                    .ok_or(QuantCryptError::Unknown)?;
+                let mut secrets = Zeroizing::new(next.epoch_secret.to_vec());
+                secrets.extend_from_slice(ancestor_secret);
+                let new_pk = &next.nodes[new_node]
+                    .as_ref()
+                    .ok_or(QuantCryptError::Unknown)?
+                    .public_key;
+                Some(Welcome {
+                    group_id: next.group_id.clone(),
+                    epoch: next.epoch,
+                    leaf,
+                    committer: next.leaf,
+                    tree: next
+                        .nodes
+                        .iter()
+                        .map(|n| n.as_ref().map(|n| n.public_key.clone()))
+                        .collect(),
+                    sealed_secrets: seal(
+                        new_pk,
+                        &next.group_context(),
+                        &secrets,
+                        next.rng.as_ref(),
+                    )?,
+                })
+            }
+            None => None,
+        };
+
+        let commit = Commit {
+            epoch: self.epoch,
+            committer: self.leaf,
+            proposal,
+            leaf_public_key,
+            path,
+        };
+        *self = next;
+        Ok((commit, welcome))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_math() {
+        // A tree with 4 leaves: nodes 0..=6, root 3
+        assert_eq!(level(3), 2);
+        assert_eq!(parent(0), 1);
+        assert_eq!(parent(4), 5);
+        assert_eq!(parent(5), 3);
+        assert_eq!(parent(1), 3);
+        assert_eq!((left(3), right(3)), (1, 5));
+        assert!(is_in_subtree(5, 6));
+        assert!(!is_in_subtree(5, 2));
+        assert_eq!(child_away(3, 0), 5);
+        assert_eq!(child_away(3, 6), 1);
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_tree_kem_group() {
+        let mut members = vec![TreeKemGroup::create(b"research").unwrap()];
+
+        // Grow the group to 5 members, extending the tree twice
+        for _ in 0..4 {
+            let pending = PendingMember::new().unwrap();
+            let (commit, welcome) = members[0].add(&pending.get_key_package()).unwrap();
+            for member in members.iter_mut().skip(1) {
+                member.process_commit(&commit).unwrap();
+            }
+            members.push(TreeKemGroup::join(pending, &welcome).unwrap());
+        }
+        let secret = members[0].export_secret(b"app", 32).unwrap();
+        for member in &members {
+            assert_eq!(member.get_epoch(), 4);
+            assert_eq!(member.get_member_count(), 5);
+            assert_eq!(member.export_secret(b"app", 32).unwrap(), secret);
+        }
+        assert_ne!(members[0].export_secret(b"other", 32).unwrap(), secret);
+
+        // An update by another member
+        let commit = members[3].update().unwrap();
+        for (i, member) in members.iter_mut().enumerate() {
+            if i != 3 {
+                member.process_commit(&commit).unwrap();
+            }
+        }
+        let updated = members[3].export_secret(b"app", 32).unwrap();
+        assert_ne!(updated, secret);
+        for member in &members {
+            assert_eq!(member.export_secret(b"app", 32).unwrap(), updated);
+        }
+        // A commit can't be processed twice
+        assert_eq!(
+            members[0].process_commit(&commit),
+            Err(QuantCryptError::InvalidGroupOperation)
+        );
+
+        // Removing a member locks it out
+        let mut removed = members.remove(2);
+        let commit = members[1].remove(removed.get_leaf_index()).unwrap();
+        assert_eq!(
+            removed.process_commit(&commit),
+            Err(QuantCryptError::InvalidGroupOperation)
+        );
+        for (i, member) in members.iter_mut().enumerate() {
+            if i != 1 {
+                member.process_commit(&commit).unwrap();
+            }
+        }
+        let secret = members[0].export_secret(b"app", 32).unwrap();
+        for member in &members {
+            assert_eq!(member.get_member_count(), 4);
+            assert_eq!(member.export_secret(b"app", 32).unwrap(), secret);
+        }
+        assert_ne!(removed.export_secret(b"app", 32).unwrap(), secret);
+
+        // The blank leaf of the removed member is reused
+        let pending = PendingMember::new().unwrap();
+        let (commit, welcome) = members[3].add(&pending.get_key_package()).unwrap();
+        for (i, member) in members.iter_mut().enumerate() {
+            if i != 3 {
+                member.process_commit(&commit).unwrap();
+            }
+        }
+        let joined = TreeKemGroup::join(pending, &welcome).unwrap();
+        assert_eq!(joined.get_leaf_index(), 2);
+        assert_eq!(
+            joined.export_secret(b"app", 32).unwrap(),
+            members[0].export_secret(b"app", 32).unwrap()
+        );
+
+        // A tampered commit is rejected and leaves the group unchanged
+        let mut commit = members[0].update().unwrap();
+        commit.path[0].public_key[0] ^= 1;
+        let epoch = members[1].get_epoch();
+        assert!(members[1].process_commit(&commit).is_err());
+        assert_eq!(members[1].get_epoch(), epoch);
+        let own_leaf = members[0].get_leaf_index();
+        assert_eq!(
+            members[0].remove(own_leaf).unwrap_err(),
+            QuantCryptError::InvalidGroupOperation
+        );
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_tree_kem_group_with_rng() {
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        // The same RNG streams give the same keys and secrets
+        let run = || {
+            let mut alice = TreeKemGroup::create_with_rng(
+                b"research",
+                SharedRng::new(ChaCha20Rng::from_seed([1u8; 32])),
+            )
+            .unwrap();
+            let bob =
+                PendingMember::new_with_rng(SharedRng::new(ChaCha20Rng::from_seed([2u8; 32])))
+                    .unwrap();
+            let key_package = bob.get_key_package();
+            let (_, welcome) = alice.add(&key_package).unwrap();
+            let mut bob = TreeKemGroup::join(bob, &welcome).unwrap();
+            let commit = bob.update().unwrap();
+            alice.process_commit(&commit).unwrap();
+            let secret = alice.export_secret(b"app", 32).unwrap();
+            assert_eq!(bob.export_secret(b"app", 32).unwrap(), secret);
+            (key_package, secret)
+        };
+        assert_eq!(run(), run());
+    }
+}
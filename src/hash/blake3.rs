@@ -0,0 +1,127 @@
+use crate::hash::common::hash_info::HashInfo;
+use crate::hash::common::hash_trait::Hash;
+use crate::hash::common::hash_type::HashType;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// BLAKE3 with a 32 byte output, computed with the blake3 crate. It is not
+/// approved by FIPS, so it can't be created while the FIPS mode is enabled.
+#[derive(Clone)]
+pub struct Blake3Hash {
+    hash_type: HashType,
+}
+
+impl Hash for Blake3Hash {
+    fn new(hash_type: HashType) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        match hash_type {
+            HashType::Blake3 => Ok(Blake3Hash { hash_type }),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    fn get_hash_info(&self) -> HashInfo {
+        HashInfo::new(self.hash_type.clone())
+    }
+
+    fn hash(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(blake3::hash(message).as_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kdf::blake3::Blake3Kdf;
+    use crate::kdf::common::kdf_trait::Kdf;
+    use crate::kdf::common::kdf_type::KdfType;
+
+    #[test]
+    fn test_blake3() {
+        let blake3 = Blake3Hash::new(HashType::Blake3).unwrap();
+        assert_eq!(
+            hex::encode(blake3.hash(b"").unwrap()),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            hex::encode(blake3.hash(b"abc").unwrap()),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        assert!(Blake3Hash::new(HashType::Sha256).is_err());
+    }
+
+    /// The input of a case of the official test vectors: the bytes 0, 1, ..., 250, 0, 1, ...
+    fn test_vector_input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_blake3_bao_test_vectors() {
+        // Computed with the Python implementation of Bao, whose root hash is the
+        // BLAKE3 hash, see test/data/kat/README.md
+        let vectors =
+            std::fs::read_to_string("test/data/kat/blake3_bao_hash_vectors.json").unwrap();
+        let vectors: serde_json::Value = serde_json::from_str(&vectors).unwrap();
+        let cases = vectors["hash"].as_array().unwrap();
+        assert!(!cases.is_empty());
+
+        let blake3 = Blake3Hash::new(HashType::Blake3).unwrap();
+        for case in cases {
+            // A 4 byte little-endian counter starting at 1, truncated to the length
+            let input_len = case["input_len"].as_u64().unwrap() as usize;
+            let input: Vec<u8> = (1u32..)
+                .flat_map(u32::to_le_bytes)
+                .take(input_len)
+                .collect();
+            assert_eq!(
+                hex::encode(blake3.hash(&input).unwrap()),
+                case["bao_hash"].as_str().unwrap(),
+                "input_len {}",
+                input_len
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "the official BLAKE3 test vectors are not vendored, see test/data/kat/README.md"]
+    fn test_blake3_official_test_vectors() {
+        let vectors = std::fs::read_to_string("test/data/kat/blake3_test_vectors.json").unwrap();
+        let vectors: serde_json::Value = serde_json::from_str(&vectors).unwrap();
+        let key: [u8; 32] = vectors["key"]
+            .as_str()
+            .unwrap()
+            .as_bytes()
+            .try_into()
+            .unwrap();
+        let context = vectors["context_string"].as_str().unwrap().as_bytes();
+
+        let blake3 = Blake3Hash::new(HashType::Blake3).unwrap();
+        let kdf = Blake3Kdf::new(KdfType::Blake3).unwrap();
+        let cases = vectors["cases"].as_array().unwrap();
+        assert!(!cases.is_empty());
+        for case in cases {
+            let input = test_vector_input(case["input_len"].as_u64().unwrap() as usize);
+            let field = |name: &str| hex::decode(case[name].as_str().unwrap()).unwrap();
+
+            // The vectors give the extended output, the hash is its first 32 bytes
+            let hash = field("hash");
+            assert_eq!(blake3.hash(&input).unwrap(), hash[..32]);
+            // The keyed mode isn't used by quantcrypt, it checks the crate linked in
+            let keyed_hash = field("keyed_hash");
+            let mut output = vec![0u8; keyed_hash.len()];
+            blake3::Hasher::new_keyed(&key)
+                .update(&input)
+                .finalize_xof()
+                .fill(&mut output);
+            assert_eq!(output, keyed_hash);
+            let derive_key = field("derive_key");
+            assert_eq!(
+                kdf.derive(&input, context, derive_key.len(), None).unwrap(),
+                derive_key
+            );
+        }
+    }
+}
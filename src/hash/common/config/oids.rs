@@ -20,6 +20,8 @@ impl Oid for HashType {
         match self {
             HashType::Sha256 => "2.16.840.1.101.3.4.2.1",
             HashType::Sha512 => "2.16.840.1.101.3.4.2.3",
+            // BLAKE3 has no registered OID
+            HashType::Blake3 => "",
         }
         .to_string()
     }
@@ -7,4 +7,18 @@ pub enum HashType {
     Sha256,
     /// SHA512
     Sha512,
+    /// BLAKE3 with a 32 byte output, not approved by FIPS
+    Blake3,
+}
+
+impl HashType {
+    /// Check if the hash is approved by FIPS. Non-approved hashes can't be
+    /// created while the FIPS mode is enabled.
+    ///
+    /// # Returns
+    ///
+    /// True if the hash is approved by FIPS
+    pub fn is_fips_approved(&self) -> bool {
+        !matches!(self, HashType::Blake3)
+    }
 }
@@ -1,6 +1,9 @@
+#[cfg(feature = "blake3")]
+use crate::hash::blake3::Blake3Hash;
 use crate::hash::common::hash_trait::Hash;
 use crate::hash::common::hash_type::HashType;
 use crate::hash::sha_hash::ShaHash;
+use crate::utils::fips::check_fips_approved;
 use crate::QuantCryptError;
 
 use crate::hash::common::hash_info::HashInfo;
@@ -13,6 +16,9 @@ type Result<T> = std::result::Result<T, QuantCryptError>;
 pub enum HashManager {
     /// AES CEA implementation
     Sha(ShaHash),
+    /// BLAKE3 implementation
+    #[cfg(feature = "blake3")]
+    Blake3(Blake3Hash),
 }
 
 impl Hash for HashManager {
@@ -20,20 +26,31 @@ impl Hash for HashManager {
     where
         Self: Sized,
     {
+        check_fips_approved(hash_type.is_fips_approved())?;
         match hash_type {
             HashType::Sha256 | HashType::Sha512 => Ok(HashManager::Sha(ShaHash::new(hash_type)?)),
+            #[cfg(feature = "blake3")]
+            HashType::Blake3 => Ok(HashManager::Blake3(Blake3Hash::new(hash_type)?)),
+            #[cfg(not(feature = "blake3"))]
+            HashType::Blake3 => Err(QuantCryptError::BackendUnavailable {
+                component: format!("{:?}", hash_type),
+            }),
         }
     }
 
     fn get_hash_info(&self) -> HashInfo {
         match self {
             HashManager::Sha(hash) => hash.get_hash_info(),
+            #[cfg(feature = "blake3")]
+            HashManager::Blake3(hash) => hash.get_hash_info(),
         }
     }
 
     fn hash(&self, message: &[u8]) -> Result<Vec<u8>> {
         match self {
             HashManager::Sha(hash) => hash.hash(message),
+            #[cfg(feature = "blake3")]
+            HashManager::Blake3(hash) => hash.hash(message),
         }
     }
 }
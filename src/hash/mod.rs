@@ -1,3 +1,5 @@
+#[cfg(feature = "blake3")]
+pub mod blake3;
 pub mod common;
 pub mod hash_manager;
 pub mod sha_hash;
@@ -1,4 +1,4 @@
-use openssl::hash::{Hasher, MessageDigest};
+use sha2::{Digest, Sha256, Sha512};
 
 use crate::hash::common::hash_info::HashInfo;
 use crate::hash::common::hash_trait::Hash;
@@ -11,7 +11,6 @@ type Result<T> = std::result::Result<T, QuantCryptError>;
 #[derive(Clone)]
 pub struct ShaHash {
     hash_type: HashType,
-    digest: MessageDigest,
 }
 
 impl Hash for ShaHash {
@@ -19,12 +18,10 @@ impl Hash for ShaHash {
     where
         Self: Sized,
     {
-        let digest = match hash_type {
-            HashType::Sha256 => MessageDigest::sha256(),
-            HashType::Sha512 => MessageDigest::sha512(),
-        };
-
-        Ok(ShaHash { hash_type, digest })
+        match hash_type {
+            HashType::Sha256 | HashType::Sha512 => Ok(ShaHash { hash_type }),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
     }
 
     fn get_hash_info(&self) -> HashInfo {
@@ -32,11 +29,10 @@ impl Hash for ShaHash {
     }
 
     fn hash(&self, message: &[u8]) -> Result<Vec<u8>> {
-        let mut hasher = Hasher::new(self.digest).map_err(|_| QuantCryptError::Unknown)?;
-        hasher
-            .update(message)
-            .map_err(|_| QuantCryptError::Unknown)?;
-        let msg = hasher.finish().map_err(|_| QuantCryptError::Unknown)?;
-        Ok(msg.to_vec())
+        match self.hash_type {
+            HashType::Sha256 => Ok(Sha256::digest(message).to_vec()),
+            HashType::Sha512 => Ok(Sha512::digest(message).to_vec()),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
     }
 }
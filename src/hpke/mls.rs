@@ -0,0 +1,328 @@
+use crate::certificates::Certificate;
+use crate::dsa::api::algorithm::DsaAlgorithm;
+use crate::hpke::suite::HpkeSuite;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The prefix of all labels used by MLS 1.0
+const MLS_LABEL_PREFIX: &[u8] = b"MLS 1.0 ";
+
+/// The CredentialType of a basic credential
+const CREDENTIAL_TYPE_BASIC: u16 = 0x0001;
+
+/// The CredentialType of an X.509 credential
+const CREDENTIAL_TYPE_X509: u16 = 0x0002;
+
+/// The largest length of a variable-length vector
+const MAX_VECTOR_LEN: usize = (1 << 30) - 1;
+
+/// Encode a variable-length vector (`opaque data<V>`, RFC 9420 section 2.1.2)
+///
+/// # Arguments
+///
+/// * `data` - The content of the vector
+///
+/// # Returns
+///
+/// The length, as a variable-size integer in its shortest form, followed by the data
+///
+/// # Errors
+///
+/// `QuantCryptError::SerializationFailed` if the data is longer than 2^30 - 1 bytes
+pub fn encode_opaque(data: &[u8]) -> Result<Vec<u8>> {
+    let len = data.len();
+    let mut out = match len {
+        0..=0x3f => vec![len as u8],
+        0x40..=0x3fff => ((len as u16) | 0x4000).to_be_bytes().to_vec(),
+        0x4000..=MAX_VECTOR_LEN => ((len as u32) | 0x8000_0000).to_be_bytes().to_vec(),
+        _ => return Err(QuantCryptError::SerializationFailed),
+    };
+    out.extend_from_slice(data);
+    Ok(out)
+}
+
+/// Decode a variable-length vector (`opaque data<V>`)
+///
+/// # Arguments
+///
+/// * `data` - The encoded vector, possibly followed by more data
+///
+/// # Returns
+///
+/// A tuple containing the content of the vector and the remaining data (content, rest)
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidContent` if the vector is truncated, or its length is not
+/// in the shortest form
+pub fn decode_opaque(data: &[u8]) -> Result<(Vec<u8>, &[u8])> {
+    let first = *data.first().ok_or(QuantCryptError::InvalidContent)?;
+    let prefix_len = match first >> 6 {
+        0 => 1,
+        1 => 2,
+        2 => 4,
+        _ => return Err(QuantCryptError::InvalidContent),
+    };
+    if data.len() < prefix_len {
+        return Err(QuantCryptError::InvalidContent);
+    }
+    let len = data[1..prefix_len]
+        .iter()
+        .fold((first & 0x3f) as usize, |len, b| (len << 8) | *b as usize);
+    let min_len = match prefix_len {
+        1 => 0,
+        2 => 0x40,
+        _ => 0x4000,
+    };
+    if len < min_len || data.len() - prefix_len < len {
+        return Err(QuantCryptError::InvalidContent);
+    }
+    let (content, rest) = data[prefix_len..].split_at(len);
+    Ok((content.to_vec(), rest))
+}
+
+/// Get the TLS SignatureScheme that MLS uses for a signature algorithm
+///
+/// # Arguments
+///
+/// * `algorithm` - The signature algorithm
+///
+/// # Returns
+///
+/// The code point (mldsa44 = 0x0904, mldsa65 = 0x0905, mldsa87 = 0x0906), or None
+/// if the algorithm has none
+pub fn get_signature_scheme(algorithm: DsaAlgorithm) -> Option<u16> {
+    match algorithm {
+        DsaAlgorithm::MlDsa44 => Some(0x0904),
+        DsaAlgorithm::MlDsa65 => Some(0x0905),
+        DsaAlgorithm::MlDsa87 => Some(0x0906),
+        _ => None,
+    }
+}
+
+/// Encode a signature public key (`SignaturePublicKey`)
+///
+/// # Arguments
+///
+/// * `pk` - The public key
+///
+/// # Returns
+///
+/// The raw public key as a variable-length vector
+pub fn encode_signature_public_key(pk: &PublicKey) -> Result<Vec<u8>> {
+    encode_opaque(pk.get_key())
+}
+
+/// Encode an HPKE public key (`HPKEPublicKey`)
+///
+/// # Arguments
+///
+/// * `pk` - The raw HPKE public key
+///
+/// # Returns
+///
+/// The public key as a variable-length vector
+pub fn encode_hpke_public_key(pk: &[u8]) -> Result<Vec<u8>> {
+    encode_opaque(pk)
+}
+
+/// Encode a basic `Credential`
+///
+/// # Arguments
+///
+/// * `identity` - The identity of the member
+///
+/// # Returns
+///
+/// The encoded credential
+pub fn encode_basic_credential(identity: &[u8]) -> Result<Vec<u8>> {
+    let mut out = CREDENTIAL_TYPE_BASIC.to_be_bytes().to_vec();
+    out.extend(encode_opaque(identity)?);
+    Ok(out)
+}
+
+/// Encode an X.509 `Credential`
+///
+/// # Arguments
+///
+/// * `chain` - The certificate of the member first, followed by its issuers
+///
+/// # Returns
+///
+/// The encoded credential
+pub fn encode_x509_credential(chain: &[Certificate]) -> Result<Vec<u8>> {
+    let mut certificates = Vec::new();
+    for cert in chain {
+        certificates.extend(encode_opaque(&cert.to_der()?)?);
+    }
+    let mut out = CREDENTIAL_TYPE_X509.to_be_bytes().to_vec();
+    out.extend(encode_opaque(&certificates)?);
+    Ok(out)
+}
+
+/// Build the labeled content that is signed or encrypted
+fn labeled_content(label: &[u8], content: &[u8]) -> Result<Vec<u8>> {
+    let mut full_label = MLS_LABEL_PREFIX.to_vec();
+    full_label.extend_from_slice(label);
+    let mut out = encode_opaque(&full_label)?;
+    out.extend(encode_opaque(content)?);
+    Ok(out)
+}
+
+/// Sign content with a label (`SignWithLabel`)
+///
+/// # Arguments
+///
+/// * `sk` - The signature private key
+/// * `label` - The label, without the "MLS 1.0 " prefix
+/// * `content` - The content
+///
+/// # Returns
+///
+/// The signature
+pub fn sign_with_label(sk: &PrivateKey, label: &[u8], content: &[u8]) -> Result<Vec<u8>> {
+    sk.sign(&labeled_content(label, content)?)
+}
+
+/// Verify a signature created with `sign_with_label` (`VerifyWithLabel`)
+///
+/// # Arguments
+///
+/// * `pk` - The signature public key
+/// * `label` - The label, without the "MLS 1.0 " prefix
+/// * `content` - The content
+/// * `signature` - The signature
+///
+/// # Returns
+///
+/// True if the signature is valid
+pub fn verify_with_label(
+    pk: &PublicKey,
+    label: &[u8],
+    content: &[u8],
+    signature: &[u8],
+) -> Result<bool> {
+    pk.verify(&labeled_content(label, content)?, signature)
+}
+
+/// Encrypt to an HPKE public key with a label (`EncryptWithLabel`)
+///
+/// # Arguments
+///
+/// * `suite` - The HPKE ciphersuite
+/// * `pk` - The HPKE public key
+/// * `label` - The label, without the "MLS 1.0 " prefix
+/// * `context` - The context bound to the ciphertext
+/// * `plaintext` - The plaintext
+///
+/// # Returns
+///
+/// A tuple containing the encapsulated key and the ciphertext (kem_output, ciphertext)
+pub fn encrypt_with_label(
+    suite: &HpkeSuite,
+    pk: &[u8],
+    label: &[u8],
+    context: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    suite.seal_base(pk, &labeled_content(label, context)?, b"", plaintext)
+}
+
+/// Decrypt a ciphertext created with `encrypt_with_label` (`DecryptWithLabel`)
+///
+/// # Arguments
+///
+/// * `suite` - The HPKE ciphersuite
+/// * `sk` - The HPKE private key
+/// * `label` - The label, without the "MLS 1.0 " prefix
+/// * `context` - The context bound to the ciphertext
+/// * `kem_output` - The encapsulated key
+/// * `ciphertext` - The ciphertext
+///
+/// # Returns
+///
+/// The plaintext
+pub fn decrypt_with_label(
+    suite: &HpkeSuite,
+    sk: &[u8],
+    label: &[u8],
+    context: &[u8],
+    kem_output: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>> {
+    suite.open_base(
+        kem_output,
+        sk,
+        &labeled_content(label, context)?,
+        b"",
+        ciphertext,
+    )
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::dsas::DsaKeyGenerator;
+    use crate::hpke::suite::{HpkeAead, HpkeKdf, HpkeKem};
+
+    #[test]
+    fn test_mls_encoding() {
+        assert_eq!(encode_opaque(&[0u8; 37]).unwrap()[..1], [0x25]);
+        assert_eq!(encode_opaque(&[0u8; 15293]).unwrap()[..2], [0x7b, 0xbd]);
+        assert_eq!(
+            encode_opaque(&[0u8; 16384]).unwrap()[..4],
+            [0x80, 0x00, 0x40, 0x00]
+        );
+        for len in [0, 63, 64, 16383, 16384] {
+            let data = vec![7u8; len];
+            let mut encoded = encode_opaque(&data).unwrap();
+            encoded.push(1);
+            assert_eq!(decode_opaque(&encoded).unwrap(), (data, &[1u8][..]));
+        }
+        // Non-minimal and truncated encodings are rejected
+        assert!(decode_opaque(&[0x40, 0x01, 0x00]).is_err());
+        assert!(decode_opaque(&[0x03, 0x00]).is_err());
+        assert!(decode_opaque(&[0xc0]).is_err());
+
+        assert_eq!(
+            encode_basic_credential(b"alice").unwrap(),
+            [0x00, 0x01, 0x05, b'a', b'l', b'i', b'c', b'e']
+        );
+        assert_eq!(get_signature_scheme(DsaAlgorithm::MlDsa65), Some(0x0905));
+        assert_eq!(get_signature_scheme(DsaAlgorithm::MlDsa44Ed25519), None);
+
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let encoded = encode_signature_public_key(&pk).unwrap();
+        let (spk, rest) = decode_opaque(&encoded).unwrap();
+        assert_eq!((spk.as_slice(), rest), (pk.get_key(), &[][..]));
+        let signature = sign_with_label(&sk, b"LeafNodeTBS", b"content").unwrap();
+        assert!(verify_with_label(&pk, b"LeafNodeTBS", b"content", &signature).unwrap());
+        assert!(!verify_with_label(&pk, b"KeyPackageTBS", b"content", &signature).unwrap_or(false));
+
+        let suite = HpkeSuite::new(HpkeKem::XWing, HpkeKdf::HkdfSha256, HpkeAead::Aes128Gcm);
+        let (hpk, hsk) = suite.derive_key_pair(b"path secret").unwrap();
+        let (kem_output, ct) =
+            encrypt_with_label(&suite, &hpk, b"UpdatePathNode", b"group context", b"secret")
+                .unwrap();
+        assert_eq!(
+            decrypt_with_label(
+                &suite,
+                &hsk,
+                b"UpdatePathNode",
+                b"group context",
+                &kem_output,
+                &ct
+            )
+            .unwrap(),
+            b"secret"
+        );
+        assert!(
+            decrypt_with_label(&suite, &hsk, b"Welcome", b"group context", &kem_output, &ct)
+                .is_err()
+        );
+    }
+}
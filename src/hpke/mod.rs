@@ -0,0 +1,2 @@
+pub mod mls;
+pub mod suite;
@@ -0,0 +1,779 @@
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha384, Sha512};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use zeroize::Zeroizing;
+
+use crate::aead::aead_manager::AeadManager;
+use crate::aead::common::aead_trait::Aead;
+use crate::aead::common::aead_type::AeadType;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::ec_kem::EcKemManager;
+use crate::kem::ml_kem::MlKemManager;
+use crate::kem::xwing::XWingKemManager;
+use crate::utils::manager_pool::{get_kem_backend, get_kem_manager};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The mode_base of RFC 9180
+const MODE_BASE: u8 = 0x00;
+
+/// The length of the nonce of all supported AEADs
+const NONCE_LEN: usize = 12;
+
+/// Compute SHAKE256 of the input
+fn shake256(input: &[u8], len: usize) -> Zeroizing<Vec<u8>> {
+    let mut shake = sha3::Shake256::default();
+    shake.update(input);
+    let mut out = Zeroizing::new(vec![0u8; len]);
+    shake.finalize_xof().read(&mut out);
+    out
+}
+
+/// LabeledExtract of RFC 9180, for the given suite_id
+fn labeled_extract(
+    kdf: HpkeKdf,
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[u8],
+) -> Zeroizing<Vec<u8>> {
+    let mut labeled_ikm = Zeroizing::new(b"HPKE-v1".to_vec());
+    labeled_ikm.extend_from_slice(suite_id);
+    labeled_ikm.extend_from_slice(label);
+    labeled_ikm.extend_from_slice(ikm);
+    kdf.extract(salt, &labeled_ikm)
+}
+
+/// LabeledExpand of RFC 9180, for the given suite_id
+fn labeled_expand(
+    kdf: HpkeKdf,
+    suite_id: &[u8],
+    prk: &[u8],
+    label: &[u8],
+    info: &[u8],
+    len: usize,
+) -> Result<Zeroizing<Vec<u8>>> {
+    let len_bytes = u16::try_from(len).map_err(|_| QuantCryptError::InvalidHkdfLength)?;
+    let mut labeled_info = len_bytes.to_be_bytes().to_vec();
+    labeled_info.extend_from_slice(b"HPKE-v1");
+    labeled_info.extend_from_slice(suite_id);
+    labeled_info.extend_from_slice(label);
+    labeled_info.extend_from_slice(info);
+    kdf.expand(prk, &labeled_info, len)
+}
+
+/// An HPKE KEM, with its code point in the IANA HPKE KEM identifiers registry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HpkeKem {
+    /// ML-KEM-512 (0x0040)
+    MlKem512,
+    /// ML-KEM-768 (0x0041)
+    MlKem768,
+    /// ML-KEM-1024 (0x0042)
+    MlKem1024,
+    /// X-Wing (0x647a)
+    XWing,
+    /// DHKEM(X25519, HKDF-SHA256) (0x0020), for interoperability with classical peers
+    DhKemX25519,
+}
+
+impl HpkeKem {
+    /// Get the KEM identifier
+    pub fn get_id(&self) -> u16 {
+        match self {
+            HpkeKem::MlKem512 => 0x0040,
+            HpkeKem::MlKem768 => 0x0041,
+            HpkeKem::MlKem1024 => 0x0042,
+            HpkeKem::XWing => 0x647a,
+            HpkeKem::DhKemX25519 => 0x0020,
+        }
+    }
+
+    fn get_kem_type(&self) -> KemType {
+        match self {
+            HpkeKem::MlKem512 => KemType::MlKem512,
+            HpkeKem::MlKem768 => KemType::MlKem768,
+            HpkeKem::MlKem1024 => KemType::MlKem1024,
+            HpkeKem::XWing => KemType::XWing,
+            HpkeKem::DhKemX25519 => KemType::X25519,
+        }
+    }
+
+    /// The suite_id of the KEM, which DHKEM uses for its labels
+    fn kem_suite_id(&self) -> Vec<u8> {
+        let mut suite_id = b"KEM".to_vec();
+        suite_id.extend_from_slice(&self.get_id().to_be_bytes());
+        suite_id
+    }
+
+    /// ExtractAndExpand of DHKEM (RFC 9180 § 4.1), turning the Diffie-Hellman
+    /// output into the shared secret
+    fn extract_and_expand(&self, dh: &[u8], kem_context: &[u8]) -> Result<Vec<u8>> {
+        let suite_id = self.kem_suite_id();
+        let eae_prk = labeled_extract(HpkeKdf::HkdfSha256, &suite_id, b"", b"eae_prk", dh);
+        Ok(labeled_expand(
+            HpkeKdf::HkdfSha256,
+            &suite_id,
+            &eae_prk,
+            b"shared_secret",
+            kem_context,
+            32,
+        )?
+        .to_vec())
+    }
+
+    /// DeriveKeyPair of DHKEM(X25519) (RFC 9180 § 7.1.3)
+    fn derive_dhkem_key_pair(&self, ikm: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let suite_id = self.kem_suite_id();
+        let dkp_prk = labeled_extract(HpkeKdf::HkdfSha256, &suite_id, b"", b"dkp_prk", ikm);
+        let sk = labeled_expand(HpkeKdf::HkdfSha256, &suite_id, &dkp_prk, b"sk", b"", 32)?;
+        let pk = EcKemManager::new(KemType::X25519)?.get_public_key(&sk)?;
+        Ok((pk, sk.to_vec()))
+    }
+
+    /// Encap of DHKEM with the given ephemeral private key
+    fn dhkem_encap(&self, pk: &[u8], esk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (dh, enc) = EcKemManager::new(KemType::X25519)?.encap_deterministic(pk, esk)?;
+        let dh = Zeroizing::new(dh);
+        let mut kem_context = enc.clone();
+        kem_context.extend_from_slice(pk);
+        Ok((self.extract_and_expand(&dh, &kem_context)?, enc))
+    }
+
+    /// Encapsulate with an ephemeral key pair derived from `ikm_e`, as the RFC 9180
+    /// test vectors do
+    #[cfg(all(test, feature = "openssl"))]
+    fn encap_with_ikm(&self, pk: &[u8], ikm_e: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (_, esk) = self.derive_dhkem_key_pair(ikm_e)?;
+        self.dhkem_encap(pk, &esk)
+    }
+
+    fn key_gen(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self {
+            HpkeKem::XWing if get_kem_backend(&KemType::XWing).is_none() => {
+                XWingKemManager::new(KemType::XWing)?.key_gen()
+            }
+            _ => get_kem_manager(self.get_kem_type())?.key_gen(),
+        }
+    }
+
+    fn encap(&self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self {
+            HpkeKem::DhKemX25519 => {
+                let (_, esk) = self.key_gen()?;
+                let esk = Zeroizing::new(esk);
+                self.dhkem_encap(pk, &esk)
+            }
+            HpkeKem::XWing if get_kem_backend(&KemType::XWing).is_none() => {
+                XWingKemManager::new(KemType::XWing)?.encap(pk)
+            }
+            _ => get_kem_manager(self.get_kem_type())?.encap(pk),
+        }
+    }
+
+    fn decap(&self, sk: &[u8], enc: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            HpkeKem::DhKemX25519 => {
+                let ec_kem = EcKemManager::new(KemType::X25519)?;
+                let dh = Zeroizing::new(ec_kem.decap(sk, enc)?);
+                let mut kem_context = enc.to_vec();
+                kem_context.extend_from_slice(&ec_kem.get_public_key(sk)?);
+                self.extract_and_expand(&dh, &kem_context)
+            }
+            HpkeKem::XWing if get_kem_backend(&KemType::XWing).is_none() => {
+                XWingKemManager::new(KemType::XWing)?.decap(sk, enc)
+            }
+            _ => get_kem_manager(self.get_kem_type())?.decap(sk, enc),
+        }
+    }
+}
+
+/// An HPKE KDF, with its code point in the IANA HPKE KDF identifiers registry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HpkeKdf {
+    /// HKDF-SHA256 (0x0001)
+    HkdfSha256,
+    /// HKDF-SHA384 (0x0002)
+    HkdfSha384,
+    /// HKDF-SHA512 (0x0003)
+    HkdfSha512,
+}
+
+impl HpkeKdf {
+    /// Get the KDF identifier
+    pub fn get_id(&self) -> u16 {
+        match self {
+            HpkeKdf::HkdfSha256 => 0x0001,
+            HpkeKdf::HkdfSha384 => 0x0002,
+            HpkeKdf::HkdfSha512 => 0x0003,
+        }
+    }
+
+    /// Get the output length of the hash function (Nh)
+    pub fn get_hash_len(&self) -> usize {
+        match self {
+            HpkeKdf::HkdfSha256 => 32,
+            HpkeKdf::HkdfSha384 => 48,
+            HpkeKdf::HkdfSha512 => 64,
+        }
+    }
+
+    fn extract(&self, salt: &[u8], ikm: &[u8]) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            HpkeKdf::HkdfSha256 => Hkdf::<Sha256>::extract(Some(salt), ikm).0.to_vec(),
+            HpkeKdf::HkdfSha384 => Hkdf::<Sha384>::extract(Some(salt), ikm).0.to_vec(),
+            HpkeKdf::HkdfSha512 => Hkdf::<Sha512>::extract(Some(salt), ikm).0.to_vec(),
+        })
+    }
+
+    fn expand(&self, prk: &[u8], info: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>> {
+        let mut out = Zeroizing::new(vec![0u8; len]);
+        match self {
+            HpkeKdf::HkdfSha256 => Hkdf::<Sha256>::from_prk(prk)
+                .map_err(|_| QuantCryptError::KdfError)?
+                .expand(info, &mut out),
+            HpkeKdf::HkdfSha384 => Hkdf::<Sha384>::from_prk(prk)
+                .map_err(|_| QuantCryptError::KdfError)?
+                .expand(info, &mut out),
+            HpkeKdf::HkdfSha512 => Hkdf::<Sha512>::from_prk(prk)
+                .map_err(|_| QuantCryptError::KdfError)?
+                .expand(info, &mut out),
+        }
+        .map_err(|_| QuantCryptError::InvalidHkdfLength)?;
+        Ok(out)
+    }
+}
+
+/// An HPKE AEAD, with its code point in the IANA HPKE AEAD identifiers registry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HpkeAead {
+    /// AES-128-GCM (0x0001)
+    Aes128Gcm,
+    /// AES-256-GCM (0x0002)
+    Aes256Gcm,
+    /// Export-only (0xffff), for contexts that are only used to export secrets
+    ExportOnly,
+}
+
+impl HpkeAead {
+    /// Get the AEAD identifier
+    pub fn get_id(&self) -> u16 {
+        match self {
+            HpkeAead::Aes128Gcm => 0x0001,
+            HpkeAead::Aes256Gcm => 0x0002,
+            HpkeAead::ExportOnly => 0xffff,
+        }
+    }
+
+    /// Get the key length (Nk)
+    fn get_key_len(&self) -> usize {
+        match self {
+            HpkeAead::Aes128Gcm => 16,
+            HpkeAead::Aes256Gcm => 32,
+            HpkeAead::ExportOnly => 0,
+        }
+    }
+
+    fn get_aead(&self) -> Result<AeadManager> {
+        match self {
+            HpkeAead::Aes128Gcm => AeadManager::new(AeadType::AesGcm128),
+            HpkeAead::Aes256Gcm => AeadManager::new(AeadType::AesGcm256),
+            HpkeAead::ExportOnly => Err(QuantCryptError::UnsupportedOperation),
+        }
+    }
+}
+
+/// An HPKE ciphersuite (RFC 9180) with a post-quantum KEM
+///
+/// As in draft-ietf-hpke-pq and draft-connolly-cfrg-xwing-kem, the shared secret
+/// of the KEM is used directly. Private keys are in the format of this crate: the
+/// expanded decapsulation key for ML-KEM and the 32 byte seed for X-Wing. Only the
+/// base mode is supported.
+///
+/// DHKEM(X25519, HKDF-SHA256) is also available, and checks the key schedule
+/// against the RFC 9180 test vectors.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
+/// use quantcrypt::mls::{HpkeAead, HpkeKdf, HpkeKem, HpkeSuite};
+///
+/// let suite = HpkeSuite::new(HpkeKem::XWing, HpkeKdf::HkdfSha256, HpkeAead::Aes128Gcm);
+/// let (pk, sk) = suite.generate_key_pair().unwrap();
+///
+/// let (enc, ct) = suite.seal_base(&pk, b"info", b"aad", b"hello").unwrap();
+/// let pt = suite.open_base(&enc, &sk, b"info", b"aad", &ct).unwrap();
+/// assert_eq!(pt, b"hello");
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HpkeSuite {
+    kem: HpkeKem,
+    kdf: HpkeKdf,
+    aead: HpkeAead,
+}
+
+impl HpkeSuite {
+    /// Create a new HPKE ciphersuite
+    ///
+    /// # Arguments
+    ///
+    /// * `kem` - The KEM
+    /// * `kdf` - The KDF
+    /// * `aead` - The AEAD
+    pub fn new(kem: HpkeKem, kdf: HpkeKdf, aead: HpkeAead) -> Self {
+        HpkeSuite { kem, kdf, aead }
+    }
+
+    /// Get the KEM of the ciphersuite
+    pub fn get_kem(&self) -> HpkeKem {
+        self.kem
+    }
+
+    /// Get the KDF of the ciphersuite
+    pub fn get_kdf(&self) -> HpkeKdf {
+        self.kdf
+    }
+
+    /// Get the AEAD of the ciphersuite
+    pub fn get_aead(&self) -> HpkeAead {
+        self.aead
+    }
+
+    /// Get the suite_id used to separate the labels of different ciphersuites
+    fn suite_id(&self) -> Vec<u8> {
+        let mut suite_id = b"HPKE".to_vec();
+        suite_id.extend_from_slice(&self.kem.get_id().to_be_bytes());
+        suite_id.extend_from_slice(&self.kdf.get_id().to_be_bytes());
+        suite_id.extend_from_slice(&self.aead.get_id().to_be_bytes());
+        suite_id
+    }
+
+    fn labeled_extract(&self, salt: &[u8], label: &[u8], ikm: &[u8]) -> Zeroizing<Vec<u8>> {
+        labeled_extract(self.kdf, &self.suite_id(), salt, label, ikm)
+    }
+
+    fn labeled_expand(
+        &self,
+        prk: &[u8],
+        label: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        labeled_expand(self.kdf, &self.suite_id(), prk, label, info, len)
+    }
+
+    /// Generate a random key pair for the KEM
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and private keys (pk, sk)
+    pub fn generate_key_pair(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.kem.key_gen()
+    }
+
+    /// Derive a key pair for the KEM from input keying material, as MLS does for
+    /// the nodes of the ratchet tree
+    ///
+    /// For ML-KEM the seed (d, z) is SHAKE256(ikm, 64), and for X-Wing the private
+    /// key is SHAKE256(ikm, 32). DHKEM(X25519) uses the DeriveKeyPair of RFC 9180.
+    ///
+    /// # Arguments
+    ///
+    /// * `ikm` - The input keying material
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and private keys (pk, sk)
+    pub fn derive_key_pair(&self, ikm: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self.kem {
+            HpkeKem::DhKemX25519 => self.kem.derive_dhkem_key_pair(ikm),
+            HpkeKem::XWing => {
+                XWingKemManager::new(KemType::XWing)?.key_gen_deterministic(&shake256(ikm, 32))
+            }
+            _ => {
+                let seed = shake256(ikm, 64);
+                let d = seed[..32]
+                    .try_into()
+                    .map_err(|_| QuantCryptError::KdfError)?;
+                let z = seed[32..]
+                    .try_into()
+                    .map_err(|_| QuantCryptError::KdfError)?;
+                MlKemManager::new(self.kem.get_kem_type())?.key_gen_deterministic(d, z)
+            }
+        }
+    }
+
+    /// Derive the context from the shared secret
+    fn key_schedule(&self, shared_secret: &[u8], info: &[u8]) -> Result<HpkeContext> {
+        let psk_id_hash = self.labeled_extract(b"", b"psk_id_hash", b"");
+        let info_hash = self.labeled_extract(b"", b"info_hash", info);
+        let mut context = vec![MODE_BASE];
+        context.extend_from_slice(&psk_id_hash);
+        context.extend_from_slice(&info_hash);
+
+        let secret = self.labeled_extract(shared_secret, b"secret", b"");
+        let (key, base_nonce) = match self.aead {
+            HpkeAead::ExportOnly => (Zeroizing::new(Vec::new()), Vec::new()),
+            _ => (
+                self.labeled_expand(&secret, b"key", &context, self.aead.get_key_len())?,
+                self.labeled_expand(&secret, b"base_nonce", &context, NONCE_LEN)?
+                    .to_vec(),
+            ),
+        };
+        let exporter_secret =
+            self.labeled_expand(&secret, b"exp", &context, self.kdf.get_hash_len())?;
+        Ok(HpkeContext {
+            suite: *self,
+            key,
+            base_nonce,
+            exporter_secret,
+            seq: 0,
+        })
+    }
+
+    /// Set up a context to encrypt to a public key (SetupBaseS)
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key of the recipient
+    /// * `info` - Application-supplied information bound to the context
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the encapsulated key and the context (enc, context)
+    pub fn setup_base_sender(&self, pk: &[u8], info: &[u8]) -> Result<(Vec<u8>, HpkeContext)> {
+        let (ss, enc) = self.kem.encap(pk)?;
+        let ss = Zeroizing::new(ss);
+        Ok((enc, self.key_schedule(&ss, info)?))
+    }
+
+    /// Set up a context to decrypt with a private key (SetupBaseR)
+    ///
+    /// # Arguments
+    ///
+    /// * `enc` - The encapsulated key
+    /// * `sk` - The private key of the recipient
+    /// * `info` - Application-supplied information bound to the context
+    ///
+    /// # Returns
+    ///
+    /// The context
+    pub fn setup_base_receiver(&self, enc: &[u8], sk: &[u8], info: &[u8]) -> Result<HpkeContext> {
+        let ss = Zeroizing::new(self.kem.decap(sk, enc)?);
+        self.key_schedule(&ss, info)
+    }
+
+    /// Encrypt a single message to a public key (SealBase)
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the encapsulated key and the ciphertext (enc, ct)
+    pub fn seal_base(
+        &self,
+        pk: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (enc, mut context) = self.setup_base_sender(pk, info)?;
+        Ok((enc, context.seal(aad, plaintext)?))
+    }
+
+    /// Decrypt a single message encrypted with `seal_base` (OpenBase)
+    ///
+    /// # Returns
+    ///
+    /// The plaintext
+    pub fn open_base(
+        &self,
+        enc: &[u8],
+        sk: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        self.setup_base_receiver(enc, sk, info)?
+            .open(aad, ciphertext)
+    }
+}
+
+/// An HPKE encryption context, for a sequence of messages in one direction
+pub struct HpkeContext {
+    suite: HpkeSuite,
+    key: Zeroizing<Vec<u8>>,
+    base_nonce: Vec<u8>,
+    exporter_secret: Zeroizing<Vec<u8>>,
+    seq: u64,
+}
+
+impl HpkeContext {
+    /// The nonce for the current sequence number
+    fn nonce(&self) -> Vec<u8> {
+        let mut nonce = self.base_nonce.clone();
+        for (n, s) in nonce[NONCE_LEN - 8..]
+            .iter_mut()
+            .zip(self.seq.to_be_bytes())
+        {
+            *n ^= s;
+        }
+        nonce
+    }
+
+    /// Encrypt the next message
+    ///
+    /// # Arguments
+    ///
+    /// * `aad` - The additional authenticated data
+    /// * `plaintext` - The plaintext
+    ///
+    /// # Returns
+    ///
+    /// The ciphertext
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` for an export-only context
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        let ct = self
+            .suite
+            .aead
+            .get_aead()?
+            .seal(&self.key, &self.nonce(), aad, plaintext)?;
+        self.seq = self
+            .seq
+            .checked_add(1)
+            .ok_or(QuantCryptError::UnsupportedOperation)?;
+        Ok(ct)
+    }
+
+    /// Decrypt the next message
+    ///
+    /// # Arguments
+    ///
+    /// * `aad` - The additional authenticated data
+    /// * `ciphertext` - The ciphertext
+    ///
+    /// # Returns
+    ///
+    /// The plaintext
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` for an export-only context
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let pt = self
+            .suite
+            .aead
+            .get_aead()?
+            .open(&self.key, &self.nonce(), aad, ciphertext)?;
+        self.seq = self
+            .seq
+            .checked_add(1)
+            .ok_or(QuantCryptError::UnsupportedOperation)?;
+        Ok(pt)
+    }
+
+    /// Export a secret from the context
+    ///
+    /// # Arguments
+    ///
+    /// * `exporter_context` - A context binding the secret to its purpose
+    /// * `len` - The length of the secret in bytes
+    ///
+    /// # Returns
+    ///
+    /// The secret
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> Result<Vec<u8>> {
+        Ok(self
+            .suite
+            .labeled_expand(&self.exporter_secret, b"sec", exporter_context, len)?
+            .to_vec())
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+
+    fn hex_field(value: &serde_json::Value, field: &str) -> Vec<u8> {
+        hex::decode(value[field].as_str().unwrap()).unwrap()
+    }
+
+    fn kdf_from_id(id: u64) -> HpkeKdf {
+        match id {
+            0x0001 => HpkeKdf::HkdfSha256,
+            0x0002 => HpkeKdf::HkdfSha384,
+            _ => HpkeKdf::HkdfSha512,
+        }
+    }
+
+    fn aead_from_id(id: u64) -> HpkeAead {
+        match id {
+            0x0001 => HpkeAead::Aes128Gcm,
+            0x0002 => HpkeAead::Aes256Gcm,
+            _ => HpkeAead::ExportOnly,
+        }
+    }
+
+    #[test]
+    fn test_hpke_base() {
+        for kem in [
+            HpkeKem::MlKem512,
+            HpkeKem::MlKem768,
+            HpkeKem::MlKem1024,
+            HpkeKem::XWing,
+            HpkeKem::DhKemX25519,
+        ] {
+            for (kdf, aead) in [
+                (HpkeKdf::HkdfSha256, HpkeAead::Aes128Gcm),
+                (HpkeKdf::HkdfSha384, HpkeAead::Aes256Gcm),
+                (HpkeKdf::HkdfSha512, HpkeAead::ExportOnly),
+            ] {
+                let suite = HpkeSuite::new(kem, kdf, aead);
+                let (pk, sk) = suite.derive_key_pair(b"ikm").unwrap();
+                assert_eq!(
+                    suite.derive_key_pair(b"ikm").unwrap(),
+                    (pk.clone(), sk.clone())
+                );
+
+                let (enc, mut sender) = suite.setup_base_sender(&pk, b"info").unwrap();
+                let mut receiver = suite.setup_base_receiver(&enc, &sk, b"info").unwrap();
+                assert_eq!(
+                    sender.export(b"ctx", 42).unwrap(),
+                    receiver.export(b"ctx", 42).unwrap()
+                );
+                if aead == HpkeAead::ExportOnly {
+                    assert_eq!(
+                        sender.seal(b"", b"msg"),
+                        Err(QuantCryptError::UnsupportedOperation)
+                    );
+                    continue;
+                }
+
+                // The nonce changes with every message
+                let ct1 = sender.seal(b"aad", b"msg").unwrap();
+                let ct2 = sender.seal(b"aad", b"msg").unwrap();
+                assert_ne!(ct1, ct2);
+                assert_eq!(receiver.open(b"aad", &ct1).unwrap(), b"msg");
+                assert_eq!(receiver.open(b"aad", &ct2).unwrap(), b"msg");
+
+                // The info is bound to the context
+                let mut other = suite.setup_base_receiver(&enc, &sk, b"other").unwrap();
+                assert!(other.open(b"aad", &ct1).is_err());
+            }
+        }
+
+        // A different suite derives different keys from the same shared secret
+        let a = HpkeSuite::new(HpkeKem::MlKem768, HpkeKdf::HkdfSha256, HpkeAead::Aes128Gcm);
+        let b = HpkeSuite::new(HpkeKem::MlKem768, HpkeKdf::HkdfSha256, HpkeAead::Aes256Gcm);
+        assert_ne!(
+            a.key_schedule(&[0u8; 32], b"")
+                .unwrap()
+                .export(b"", 32)
+                .unwrap(),
+            b.key_schedule(&[0u8; 32], b"")
+                .unwrap()
+                .export(b"", 32)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hpke_rfc9180_vectors() {
+        let vectors = std::fs::read_to_string("test/data/hpke/rfc9180.json").unwrap();
+        let vectors: serde_json::Value = serde_json::from_str(&vectors).unwrap();
+        for v in vectors.as_array().unwrap() {
+            assert_eq!(v["kem_id"], 0x0020);
+            let suite = HpkeSuite::new(
+                HpkeKem::DhKemX25519,
+                kdf_from_id(v["kdf_id"].as_u64().unwrap()),
+                aead_from_id(v["aead_id"].as_u64().unwrap()),
+            );
+            let info = hex_field(v, "info");
+            let pk_r = hex_field(v, "pkRm");
+            let sk_r = hex_field(v, "skRm");
+            let enc = hex_field(v, "enc");
+            let shared_secret = hex_field(v, "shared_secret");
+
+            // DeriveKeyPair
+            assert_eq!(
+                suite.derive_key_pair(&hex_field(v, "ikmR")).unwrap(),
+                (pk_r.clone(), sk_r.clone())
+            );
+
+            // Encap with the ephemeral key of the vector, and Decap
+            assert_eq!(
+                suite
+                    .kem
+                    .encap_with_ikm(&pk_r, &hex_field(v, "ikmE"))
+                    .unwrap(),
+                (shared_secret.clone(), enc.clone())
+            );
+            assert_eq!(suite.kem.decap(&sk_r, &enc).unwrap(), shared_secret);
+
+            // The key schedule, from both sides
+            let mut sender = suite.key_schedule(&shared_secret, &info).unwrap();
+            let mut receiver = suite.setup_base_receiver(&enc, &sk_r, &info).unwrap();
+            for e in v["encryptions"].as_array().unwrap() {
+                let aad = hex_field(e, "aad");
+                let pt = hex_field(e, "pt");
+                let ct = hex_field(e, "ct");
+                assert_eq!(sender.seal(&aad, &pt).unwrap(), ct);
+                assert_eq!(receiver.open(&aad, &ct).unwrap(), pt);
+            }
+            for e in v["exports"].as_array().unwrap() {
+                let len = e["L"].as_u64().unwrap() as usize;
+                let value = receiver
+                    .export(&hex_field(e, "exporter_context"), len)
+                    .unwrap();
+                assert_eq!(value, hex_field(e, "exported_value"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hpke_pq_interop_vectors() {
+        // Produced by the HPKE of pyca/cryptography, see test/data/hpke/README.md
+        let vectors = std::fs::read_to_string("test/data/hpke/pq-interop.json").unwrap();
+        let vectors: serde_json::Value = serde_json::from_str(&vectors).unwrap();
+        for v in vectors.as_array().unwrap() {
+            let kem = match v["kem_id"].as_u64().unwrap() {
+                0x0041 => HpkeKem::MlKem768,
+                0x0042 => HpkeKem::MlKem1024,
+                _ => HpkeKem::XWing,
+            };
+            let suite = HpkeSuite::new(
+                kem,
+                kdf_from_id(v["kdf_id"].as_u64().unwrap()),
+                aead_from_id(v["aead_id"].as_u64().unwrap()),
+            );
+
+            // The vectors carry the private key as a seed: (d, z) for ML-KEM
+            let seed = hex_field(v, "skRm");
+            let sk = match kem {
+                HpkeKem::XWing => seed,
+                _ => {
+                    let ml_kem = MlKemManager::new(kem.get_kem_type()).unwrap();
+                    let d = seed[..32].try_into().unwrap();
+                    let z = seed[32..].try_into().unwrap();
+                    ml_kem.key_gen_deterministic(d, z).unwrap().1
+                }
+            };
+
+            let pt = suite
+                .open_base(
+                    &hex_field(v, "enc"),
+                    &sk,
+                    &hex_field(v, "info"),
+                    &hex_field(v, "aad"),
+                    &hex_field(v, "ct"),
+                )
+                .unwrap();
+            assert_eq!(pt, hex_field(v, "pt"));
+        }
+    }
+}
@@ -25,6 +25,8 @@ impl Oid for KdfType {
             KdfType::Kmac256 => "2.16.840.1.101.3.4.2.22",
             KdfType::Shake128 => "2.16.840.1.101.3.4.2.11",
             KdfType::Shake256 => "2.16.840.1.101.3.4.2.12",
+            // BLAKE3 has no registered OID
+            KdfType::Blake3 => "",
         }
         .to_string()
     }
@@ -21,7 +21,7 @@ pub trait Kdf {
     {
         let all_kdf_types = KdfType::all();
         for kdf_type in all_kdf_types {
-            if kdf_type.get_oid() == oid {
+            if !oid.is_empty() && kdf_type.get_oid() == oid {
                 let kdf = Self::new(kdf_type)?;
                 return Ok(kdf);
             }
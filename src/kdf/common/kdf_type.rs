@@ -19,6 +19,8 @@ pub enum KdfType {
     Shake128,
     /// Shake 256
     Shake256,
+    /// BLAKE3 in its key derivation mode, not approved by FIPS
+    Blake3,
 }
 
 impl KdfType {
@@ -32,6 +34,16 @@ impl KdfType {
         let all_kdf_types = KdfType::all();
         all_kdf_types
             .into_iter()
-            .find(|kdf_type| kdf_type.get_oid() == oid)
+            .find(|kdf_type| !oid.is_empty() && kdf_type.get_oid() == oid)
+    }
+
+    /// Check if the KDF is approved by FIPS. Non-approved KDFs can't be
+    /// created while the FIPS mode is enabled.
+    ///
+    /// # Returns
+    ///
+    /// True if the KDF is approved by FIPS
+    pub fn is_fips_approved(&self) -> bool {
+        !matches!(self, KdfType::Blake3)
     }
 }
@@ -1,8 +1,11 @@
+#[cfg(feature = "blake3")]
+use crate::kdf::blake3::Blake3Kdf;
 use crate::kdf::common::kdf_trait::Kdf;
 use crate::kdf::common::kdf_type::KdfType;
 use crate::kdf::hkdf::Hkdf;
 use crate::kdf::kmac::Kmac;
 use crate::kdf::sha3::Sha3Kdf;
+use crate::utils::fips::check_fips_approved;
 use crate::QuantCryptError;
 
 use crate::kdf::common::kdf_info::KdfInfo;
@@ -26,6 +29,9 @@ pub enum KdfManager {
     /// Kmac implementation
     Kmac(Kmac),
     Sha3(Sha3Kdf),
+    /// BLAKE3 implementation
+    #[cfg(feature = "blake3")]
+    Blake3(Blake3Kdf),
 }
 
 impl Kdf for KdfManager {
@@ -33,10 +39,19 @@ impl Kdf for KdfManager {
     where
         Self: Sized,
     {
+        check_fips_approved(kdf_type.is_fips_approved())?;
         let result = match kdf_type {
             _ if HKDF_TYPES.contains(&kdf_type) => KdfManager::Hkdf(Hkdf::new(kdf_type)?),
             _ if KMAC_TYPES.contains(&kdf_type) => KdfManager::Kmac(Kmac::new(kdf_type)?),
             _ if SHA3_TYPES.contains(&kdf_type) => KdfManager::Sha3(Sha3Kdf::new(kdf_type)?),
+            #[cfg(feature = "blake3")]
+            KdfType::Blake3 => KdfManager::Blake3(Blake3Kdf::new(kdf_type)?),
+            #[cfg(not(feature = "blake3"))]
+            KdfType::Blake3 => {
+                return Err(QuantCryptError::BackendUnavailable {
+                    component: format!("{:?}", kdf_type),
+                });
+            }
             _ => {
                 return Err(QuantCryptError::NotImplemented);
             }
@@ -49,6 +64,8 @@ impl Kdf for KdfManager {
             KdfManager::Hkdf(hkdf) => hkdf.get_kdf_info(),
             KdfManager::Kmac(kmac) => kmac.get_kdf_info(),
             KdfManager::Sha3(sha3) => sha3.get_kdf_info(),
+            #[cfg(feature = "blake3")]
+            KdfManager::Blake3(blake3) => blake3.get_kdf_info(),
         }
     }
 
@@ -63,6 +80,8 @@ impl Kdf for KdfManager {
             KdfManager::Hkdf(hkdf) => hkdf.derive(ikm, info, length, salt),
             KdfManager::Kmac(kmac) => kmac.derive(ikm, info, length, salt),
             KdfManager::Sha3(sha3) => sha3.derive(ikm, info, length, salt),
+            #[cfg(feature = "blake3")]
+            KdfManager::Blake3(blake3) => blake3.derive(ikm, info, length, salt),
         }
     }
 }
@@ -72,6 +91,12 @@ mod tests {
     use super::*;
     use crate::kdf::common::kdf_type::KdfType;
 
+    #[test]
+    fn test_kdf_manager_is_clone_send_sync() {
+        fn assert_clone_send_sync<T: Clone + Send + Sync>() {}
+        assert_clone_send_sync::<KdfManager>();
+    }
+
     #[test]
     fn test_hkdf_256() {
         let hkdf = KdfManager::new(KdfType::HkdfWithSha256).unwrap();
@@ -1,4 +1,6 @@
 pub mod api;
+#[cfg(feature = "blake3")]
+pub mod blake3;
 pub mod common;
 pub mod hkdf;
 pub mod kdf_manager;
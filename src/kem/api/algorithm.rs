@@ -1,4 +1,6 @@
 use crate::kem::common::{config::oids::Oid, kem_type::KemType};
+use crate::utils::manager_pool::get_kem_manager;
+use crate::QuantCryptError;
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
 
@@ -27,6 +29,34 @@ impl KemAlgorithm {
         KemAlgorithm::iter().collect()
     }
 
+    /// Get the KEM algorithms whose backends are available
+    ///
+    /// Distribution builds of OpenSSL may lack curves such as the Brainpool
+    /// curves, and the composites using them can't be constructed. Filtering
+    /// the algorithms at startup keeps them out of negotiation, instead of
+    /// failing when a peer picks them.
+    ///
+    /// # Returns
+    ///
+    /// The available algorithms
+    pub fn available() -> Vec<KemAlgorithm> {
+        KemAlgorithm::iter().filter(|a| a.is_available()).collect()
+    }
+
+    /// Check if the backends of the algorithm, and of all its components, are available
+    ///
+    /// Algorithms provided by a registered backend are always available.
+    ///
+    /// # Returns
+    ///
+    /// True if the algorithm can be used, false otherwise
+    pub fn is_available(&self) -> bool {
+        !matches!(
+            get_kem_manager(self.get_kem_type()),
+            Err(QuantCryptError::BackendUnavailable { .. })
+        )
+    }
+
     /// Get the corresponding `DsaType` for the algorithm
     pub(crate) fn get_kem_type(&self) -> KemType {
         match self {
@@ -59,13 +89,54 @@ impl KemAlgorithm {
         )
     }
 
+    /// Get the components of a composite algorithm
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the ML-KEM algorithm and the traditional KEM type (pq, trad),
+    /// or `None` if the algorithm is not composite
+    pub fn get_components(&self) -> Option<(KemAlgorithm, KemType)> {
+        match self {
+            KemAlgorithm::MlKem512 | KemAlgorithm::MlKem768 | KemAlgorithm::MlKem1024 => None,
+            KemAlgorithm::MlKem768Rsa2048 => Some((KemAlgorithm::MlKem768, KemType::RsaOAEP2048)),
+            KemAlgorithm::MlKem768Rsa3072 => Some((KemAlgorithm::MlKem768, KemType::RsaOAEP3072)),
+            KemAlgorithm::MlKem768Rsa4096 => Some((KemAlgorithm::MlKem768, KemType::RsaOAEP4096)),
+            KemAlgorithm::MlKem768X25519 => Some((KemAlgorithm::MlKem768, KemType::X25519)),
+            KemAlgorithm::MlKem768P384 => Some((KemAlgorithm::MlKem768, KemType::P384)),
+            KemAlgorithm::MlKem768BrainpoolP256r1 => {
+                Some((KemAlgorithm::MlKem768, KemType::BrainpoolP256r1))
+            }
+            KemAlgorithm::MlKem1024P384 => Some((KemAlgorithm::MlKem1024, KemType::P384)),
+            KemAlgorithm::MlKem1024BrainpoolP384r1 => {
+                Some((KemAlgorithm::MlKem1024, KemType::BrainpoolP384r1))
+            }
+            KemAlgorithm::MlKem1024X448 => Some((KemAlgorithm::MlKem1024, KemType::X448)),
+        }
+    }
+
+    /// Get the NIST security category of the algorithm against quantum attackers
+    ///
+    /// For composite algorithms, this is the category of the ML-KEM component, as the
+    /// classical component offers no protection against a quantum attacker.
+    ///
+    /// # Returns
+    ///
+    /// The NIST security category, from 1 to 5
+    pub fn get_quantum_security_level(&self) -> u8 {
+        self.get_kem_type().get_quantum_security_level()
+    }
+
     /// Get the OID for the algorithm
     ///
     /// # Returns
     ///
     /// The OID for the algorithm
     pub fn get_oid(&self) -> String {
-        self.get_kem_type().get_oid()
+        // Only KEMs with an assigned OID are algorithms of the high level API
+        self.get_kem_type()
+            .get_oid()
+            .expect("every KEM algorithm has an OID")
+            .to_string()
     }
 
     /// Get the KEM algorithm from an OID
@@ -0,0 +1,170 @@
+use crate::kem::api::algorithm::KemAlgorithm;
+use crate::kem::common::config::ct_len::CTLen;
+use crate::utils::cbor::{decode_with_oid, encode_with_oid};
+use crate::utils::ct_eq::ct_eq;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A KEM ciphertext tagged with the algorithm that produced it.
+///
+/// The length of the ciphertext is validated against the algorithm when the
+/// ciphertext is constructed, so malformed input is rejected before it reaches
+/// decapsulation.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{Ciphertext, KemAlgorithm, KemKeyGenerator};
+///
+/// let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem512).generate().unwrap();
+/// let (ss, ct) = pk.encap().unwrap();
+///
+/// // Parse the ciphertext bytes received from the peer
+/// let ct = Ciphertext::new(KemAlgorithm::MlKem512, ct.as_bytes()).unwrap();
+/// let ss2 = sk.decap(&ct).unwrap();
+/// assert_eq!(ss, ss2);
+///
+/// assert!(Ciphertext::new(KemAlgorithm::MlKem512, &[0u8; 10]).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ciphertext {
+    algorithm: KemAlgorithm,
+    ct: Vec<u8>,
+}
+
+impl Ciphertext {
+    /// Create a new ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The KEM algorithm the ciphertext belongs to
+    /// * `ct` - The ciphertext bytes
+    ///
+    /// # Returns
+    ///
+    /// The ciphertext
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCiphertextLength` will be returned if the length
+    /// doesn't match the algorithm
+    pub fn new(algorithm: KemAlgorithm, ct: &[u8]) -> Result<Self> {
+        if let Some(expected) = algorithm.get_kem_type().get_ct_len() {
+            if ct.len() != expected {
+                return Err(QuantCryptError::InvalidCiphertextLength {
+                    expected,
+                    actual: ct.len(),
+                });
+            }
+        }
+        Ok(Self {
+            algorithm,
+            ct: ct.to_vec(),
+        })
+    }
+
+    /// Create a new ciphertext for the KEM algorithm identified by an OID
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The OID of the KEM algorithm
+    /// * `ct` - The ciphertext bytes
+    ///
+    /// # Returns
+    ///
+    /// The ciphertext
+    pub fn from_oid(oid: &str, ct: &[u8]) -> Result<Self> {
+        let algorithm = KemAlgorithm::from_oid(oid).ok_or(QuantCryptError::InvalidOid)?;
+        Self::new(algorithm, ct)
+    }
+
+    /// Get the KEM algorithm the ciphertext belongs to
+    pub fn get_algorithm(&self) -> KemAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the ciphertext bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.ct
+    }
+
+    /// Consume the ciphertext and return its bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.ct
+    }
+
+    /// Compare two ciphertexts in constant time
+    ///
+    /// Unlike `==`, the comparison doesn't stop at the first differing byte,
+    /// for comparing a received ciphertext with a re-encrypted one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The ciphertext to compare with
+    ///
+    /// # Returns
+    ///
+    /// True if both ciphertexts have the same algorithm and bytes
+    pub fn ct_eq(&self, other: &Ciphertext) -> bool {
+        self.algorithm == other.algorithm && ct_eq(&self.ct, &other.ct)
+    }
+
+    /// Convert the ciphertext to a compact CBOR encoding, `[111(oid), ct]`
+    ///
+    /// # Returns
+    ///
+    /// The CBOR encoding, with the algorithm as an RFC 9090 tagged OID
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        encode_with_oid(&self.algorithm.get_oid(), &self.ct)
+    }
+
+    /// Create a new ciphertext from its CBOR encoding
+    ///
+    /// # Arguments
+    ///
+    /// * `cbor` - The CBOR encoding, see `to_cbor`
+    ///
+    /// # Returns
+    ///
+    /// The ciphertext
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCbor` will be returned if the encoding is malformed
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self> {
+        let (oid, ct) = decode_with_oid(cbor)?;
+        Ciphertext::from_oid(&oid, &ct)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ciphertext_length_validation() {
+        for alg in KemAlgorithm::all() {
+            let len = alg.get_kem_type().get_ct_len().unwrap();
+            let ct = Ciphertext::new(alg, &vec![0u8; len]).unwrap();
+            assert_eq!(ct.get_algorithm(), alg);
+            assert_eq!(ct.as_bytes().len(), len);
+            assert!(ct.ct_eq(&ct.clone()));
+            let mut other = vec![0u8; len];
+            other[len - 1] = 1;
+            assert!(!ct.ct_eq(&Ciphertext::new(alg, &other).unwrap()));
+
+            let result = Ciphertext::from_oid(&alg.get_oid(), &vec![0u8; len - 1]);
+            assert_eq!(
+                result,
+                Err(QuantCryptError::InvalidCiphertextLength {
+                    expected: len,
+                    actual: len - 1
+                })
+            );
+        }
+        assert_eq!(
+            Ciphertext::from_oid("1.2.3.4", &[0u8; 32]),
+            Err(QuantCryptError::InvalidOid)
+        );
+    }
+}
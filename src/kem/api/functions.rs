@@ -0,0 +1,93 @@
+use crate::kem::api::algorithm::KemAlgorithm;
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::shared_secret::SharedSecret;
+use crate::kem::common::kem_trait::Kem;
+use crate::utils::manager_pool::get_kem_manager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Encapsulate a shared secret to a raw public key
+///
+/// # Arguments
+///
+/// * `algorithm` - The KEM algorithm
+/// * `pk` - The raw public key bytes
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and the ciphertext (ss, ct)
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{self, KemAlgorithm, KemKeyGenerator};
+///
+/// let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768).generate().unwrap();
+///
+/// let (ss, ct) = kems::encap(KemAlgorithm::MlKem768, pk.get_key()).unwrap();
+/// let ss2 = sk.decap(&ct).unwrap();
+/// assert_eq!(ss, ss2);
+/// ```
+pub fn encap(algorithm: KemAlgorithm, pk: &[u8]) -> Result<(SharedSecret, Ciphertext)> {
+    let mut kem = get_kem_manager(algorithm.get_kem_type())?;
+    let (ss, ct) = kem.encap(pk)?;
+    Ok((SharedSecret::new(ss), Ciphertext::new(algorithm, &ct)?))
+}
+
+/// Decapsulate a shared secret using a raw private key
+///
+/// # Arguments
+///
+/// * `algorithm` - The KEM algorithm
+/// * `sk` - The raw private key bytes
+/// * `ct` - The ciphertext
+///
+/// # Returns
+///
+/// The shared secret
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidCiphertext` will be returned if the ciphertext belongs to a different algorithm
+pub fn decap(algorithm: KemAlgorithm, sk: &[u8], ct: &Ciphertext) -> Result<SharedSecret> {
+    if ct.get_algorithm() != algorithm {
+        return Err(QuantCryptError::InvalidCiphertext);
+    }
+    let kem = get_kem_manager(algorithm.get_kem_type())?;
+    let ss = kem.decap(sk, ct.as_bytes())?;
+    Ok(SharedSecret::new(ss))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oneshot_encap_decap() {
+        let algs = [
+            KemAlgorithm::MlKem512,
+            KemAlgorithm::MlKem1024,
+            KemAlgorithm::MlKem768X25519,
+            KemAlgorithm::MlKem1024P384,
+        ];
+        for alg in algs {
+            let (pk, sk) = get_kem_manager(alg.get_kem_type())
+                .unwrap()
+                .key_gen()
+                .unwrap();
+            let (ss, ct) = encap(alg, &pk).unwrap();
+            let ss2 = decap(alg, &sk, &ct).unwrap();
+            assert_eq!(ss, ss2);
+        }
+
+        let (pk, sk) = get_kem_manager(KemAlgorithm::MlKem512.get_kem_type())
+            .unwrap()
+            .key_gen()
+            .unwrap();
+        let (_, ct) = encap(KemAlgorithm::MlKem512, &pk).unwrap();
+        assert_eq!(
+            decap(KemAlgorithm::MlKem768, &sk, &ct),
+            Err(QuantCryptError::InvalidCiphertext)
+        );
+    }
+}
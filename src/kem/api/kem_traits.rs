@@ -0,0 +1,196 @@
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::private_key::KemPrivateKey;
+use crate::kem::api::public_key::KemPublicKey;
+use crate::kem::api::shared_secret::SharedSecret;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::kem_manager::KemManager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// An encapsulation key of any `KemType`, implementing `kem::Encapsulate`
+///
+/// The KEMs of quantcrypt draw the randomness of encapsulation from the
+/// operating system, so the RNG passed to `encapsulate` is not used.
+///
+/// # Example
+/// ```
+/// use kem::{Decapsulate, Encapsulate};
+/// use quantcrypt::kems::{generate_key_pair, KemType};
+///
+/// let (ek, dk) = generate_key_pair(KemType::MlKem768P384, &mut rand::thread_rng()).unwrap();
+/// let (ct, ss) = ek.encapsulate(&mut rand::thread_rng()).unwrap();
+/// assert_eq!(dk.decapsulate(&ct).unwrap(), ss);
+/// ```
+#[derive(Clone)]
+pub struct EncapsulationKey {
+    kem: KemManager,
+    pk: Vec<u8>,
+}
+
+/// A decapsulation key of any `KemType`, implementing `kem::Decapsulate`
+///
+/// The key bytes are zeroized when the key is dropped.
+#[derive(Clone)]
+pub struct DecapsulationKey {
+    kem: KemManager,
+    sk: Zeroizing<Vec<u8>>,
+}
+
+impl EncapsulationKey {
+    /// Create a new encapsulation key
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The KEM the key belongs to
+    /// * `pk` - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// The encapsulation key
+    pub fn new(kem_type: KemType, pk: &[u8]) -> Result<Self> {
+        Ok(Self {
+            kem: KemManager::new(kem_type)?,
+            pk: pk.to_vec(),
+        })
+    }
+
+    /// Get the KEM the key belongs to
+    pub fn get_kem_type(&self) -> KemType {
+        self.kem.get_kem_info().kem_type
+    }
+
+    /// Get the public key bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.pk
+    }
+}
+
+impl DecapsulationKey {
+    /// Create a new decapsulation key
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The KEM the key belongs to
+    /// * `sk` - The private key bytes
+    ///
+    /// # Returns
+    ///
+    /// The decapsulation key
+    pub fn new(kem_type: KemType, sk: &[u8]) -> Result<Self> {
+        Ok(Self {
+            kem: KemManager::new(kem_type)?,
+            sk: Zeroizing::new(sk.to_vec()),
+        })
+    }
+
+    /// Get the KEM the key belongs to
+    pub fn get_kem_type(&self) -> KemType {
+        self.kem.get_kem_info().kem_type
+    }
+}
+
+/// Generate a key pair of any `KemType` for use with the `kem` traits
+///
+/// # Arguments
+///
+/// * `kem_type` - The KEM to generate a key pair for
+/// * `rng` - The random number generator to use
+///
+/// # Returns
+///
+/// A tuple containing the encapsulation and decapsulation keys (ek, dk)
+pub fn generate_key_pair(
+    kem_type: KemType,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(EncapsulationKey, DecapsulationKey)> {
+    let mut kem = KemManager::new(kem_type)?;
+    let (pk, sk) = kem.key_gen_with_rng(rng)?;
+    let dk = DecapsulationKey {
+        kem: kem.clone(),
+        sk: Zeroizing::new(sk),
+    };
+    Ok((EncapsulationKey { kem, pk }, dk))
+}
+
+impl kem::Encapsulate<Vec<u8>, SharedSecret> for EncapsulationKey {
+    type Error = QuantCryptError;
+
+    fn encapsulate(&self, _rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, SharedSecret)> {
+        let (ss, ct) = self.kem.clone().encap(&self.pk)?;
+        Ok((ct, SharedSecret::new(ss)))
+    }
+}
+
+impl kem::Decapsulate<Vec<u8>, SharedSecret> for DecapsulationKey {
+    type Error = QuantCryptError;
+
+    fn decapsulate(&self, ct: &Vec<u8>) -> Result<SharedSecret> {
+        Ok(SharedSecret::new(self.kem.decap(&self.sk, ct)?))
+    }
+}
+
+impl kem::Encapsulate<Ciphertext, SharedSecret> for KemPublicKey {
+    type Error = QuantCryptError;
+
+    fn encapsulate(&self, _rng: &mut impl CryptoRngCore) -> Result<(Ciphertext, SharedSecret)> {
+        let (ss, ct) = self.encap()?;
+        Ok((ct, ss))
+    }
+}
+
+impl kem::Decapsulate<Ciphertext, SharedSecret> for KemPrivateKey {
+    type Error = QuantCryptError;
+
+    fn decapsulate(&self, ct: &Ciphertext) -> Result<SharedSecret> {
+        self.decap(ct)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::api::algorithm::KemAlgorithm;
+    use kem::{Decapsulate, Encapsulate};
+
+    /// Generic code written against the `kem` traits
+    fn round_trip<EK, DK, C, S>(ek: &EK, dk: &DK) -> bool
+    where
+        EK: Encapsulate<C, S>,
+        DK: Decapsulate<C, S>,
+        S: PartialEq,
+    {
+        let (ct, ss) = ek.encapsulate(&mut rand::thread_rng()).unwrap();
+        dk.decapsulate(&ct).unwrap() == ss
+    }
+
+    #[test]
+    fn test_kem_traits() {
+        for kem_type in [
+            KemType::MlKem512,
+            KemType::RsaOAEP2048,
+            KemType::P256,
+            KemType::X448,
+            KemType::MlKem768X25519,
+            KemType::MlKem768X25519P256,
+        ] {
+            let (ek, dk) = generate_key_pair(kem_type.clone(), &mut rand::thread_rng()).unwrap();
+            assert_eq!(ek.get_kem_type(), kem_type);
+            assert_eq!(dk.get_kem_type(), kem_type);
+            assert!(round_trip(&ek, &dk));
+        }
+
+        let alg = KemAlgorithm::MlKem768;
+        let (ek, dk) = generate_key_pair(alg.get_kem_type(), &mut rand::thread_rng()).unwrap();
+        let pk = KemPublicKey::new(alg, ek.as_bytes()).unwrap();
+        let sk = KemPrivateKey::new(alg, &dk.sk).unwrap();
+        assert!(round_trip(&pk, &sk));
+
+        // A ciphertext of the wrong length
+        assert!(dk.decapsulate(&vec![0u8; 10]).is_err());
+    }
+}
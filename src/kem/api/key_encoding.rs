@@ -0,0 +1,149 @@
+use der::asn1::BitString;
+use der::{Decode, Encode};
+use pem::EncodeConfig;
+use pkcs8::spki::{AlgorithmIdentifier, AlgorithmIdentifierWithOid};
+use pkcs8::{ObjectIdentifier, PrivateKeyInfo};
+use zeroize::Zeroizing;
+
+use crate::asn1::public_key_info::PublicKeyInfo;
+use crate::utils::resource_limits::{check_der_input, check_input_len};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The PEM label of a SubjectPublicKeyInfo
+const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+
+/// The PEM label of a PKCS#8 OneAsymmetricKey
+const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+
+/// Wrap a raw public key in a SubjectPublicKeyInfo, without algorithm parameters
+///
+/// # Arguments
+///
+/// * `oid` - The OID of the algorithm
+/// * `pk` - The raw public key
+///
+/// # Returns
+///
+/// The DER encoded SubjectPublicKeyInfo
+pub(crate) fn encode_spki(oid: &str, pk: &[u8]) -> Result<Vec<u8>> {
+    let oid: ObjectIdentifier = oid.parse().map_err(|_| QuantCryptError::InvalidOid)?;
+    PublicKeyInfo {
+        algorithm: AlgorithmIdentifierWithOid {
+            oid,
+            parameters: None,
+        },
+        public_key: BitString::from_bytes(pk).map_err(|_| QuantCryptError::InvalidPublicKey)?,
+    }
+    .to_der()
+    .map_err(|_| QuantCryptError::InvalidPublicKey)
+}
+
+/// Unwrap the raw public key of a SubjectPublicKeyInfo
+///
+/// # Arguments
+///
+/// * `der` - The DER encoded SubjectPublicKeyInfo
+///
+/// # Returns
+///
+/// A tuple containing the OID of the algorithm and the raw public key (oid, pk)
+pub(crate) fn decode_spki(der: &[u8]) -> Result<(String, Vec<u8>)> {
+    check_der_input(der)?;
+    let spki = PublicKeyInfo::from_der(der).map_err(|_| QuantCryptError::InvalidPublicKey)?;
+    if spki.algorithm.parameters.is_some() {
+        return Err(QuantCryptError::InvalidPublicKey);
+    }
+    let pk = spki
+        .public_key
+        .as_bytes()
+        .ok_or(QuantCryptError::InvalidPublicKey)?;
+    Ok((spki.algorithm.oid.to_string(), pk.to_vec()))
+}
+
+/// Wrap a private key in a PKCS#8 OneAsymmetricKey, without algorithm parameters
+///
+/// # Arguments
+///
+/// * `oid` - The OID of the algorithm
+/// * `sk` - The contents of the privateKey OCTET STRING
+///
+/// # Returns
+///
+/// The DER encoded OneAsymmetricKey
+pub(crate) fn encode_pkcs8(oid: &str, sk: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    let oid: ObjectIdentifier = oid.parse().map_err(|_| QuantCryptError::InvalidOid)?;
+    let info = PrivateKeyInfo {
+        algorithm: AlgorithmIdentifier {
+            oid,
+            parameters: None,
+        },
+        private_key: sk,
+        public_key: None,
+    };
+    Ok(Zeroizing::new(
+        info.to_der()
+            .map_err(|_| QuantCryptError::InvalidPrivateKey)?,
+    ))
+}
+
+/// Unwrap the private key of a PKCS#8 OneAsymmetricKey
+///
+/// # Arguments
+///
+/// * `der` - The DER encoded OneAsymmetricKey
+///
+/// # Returns
+///
+/// A tuple containing the OID of the algorithm and the contents of the
+/// privateKey OCTET STRING (oid, sk)
+pub(crate) fn decode_pkcs8(der: &[u8]) -> Result<(String, Zeroizing<Vec<u8>>)> {
+    check_der_input(der)?;
+    let info = PrivateKeyInfo::from_der(der).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    if info.algorithm.parameters.is_some() {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    Ok((
+        info.algorithm.oid.to_string(),
+        Zeroizing::new(info.private_key.to_vec()),
+    ))
+}
+
+/// Encode a SubjectPublicKeyInfo as PEM
+pub(crate) fn spki_to_pem(der: &[u8]) -> String {
+    to_pem(PUBLIC_KEY_LABEL, der)
+}
+
+/// Decode a PEM encoded SubjectPublicKeyInfo
+pub(crate) fn spki_from_pem(pem: &str) -> Result<Vec<u8>> {
+    from_pem(PUBLIC_KEY_LABEL, pem, QuantCryptError::InvalidPublicKey)
+}
+
+/// Encode a PKCS#8 OneAsymmetricKey as PEM
+pub(crate) fn pkcs8_to_pem(der: &[u8]) -> Zeroizing<String> {
+    Zeroizing::new(to_pem(PRIVATE_KEY_LABEL, der))
+}
+
+/// Decode a PEM encoded PKCS#8 OneAsymmetricKey
+pub(crate) fn pkcs8_from_pem(pem: &str) -> Result<Zeroizing<Vec<u8>>> {
+    Ok(Zeroizing::new(from_pem(
+        PRIVATE_KEY_LABEL,
+        pem,
+        QuantCryptError::InvalidPrivateKey,
+    )?))
+}
+
+fn to_pem(label: &str, der: &[u8]) -> String {
+    let pem_obj = pem::Pem::new(label, der);
+    let encode_conf = EncodeConfig::default().set_line_ending(pem::LineEnding::LF);
+    pem::encode_config(&pem_obj, encode_conf)
+}
+
+fn from_pem(label: &str, pem: &str, error: QuantCryptError) -> Result<Vec<u8>> {
+    check_input_len(pem.len())?;
+    match pem::parse(pem) {
+        Ok(pem) if pem.tag() == label => Ok(pem.into_contents()),
+        _ => Err(error),
+    }
+}
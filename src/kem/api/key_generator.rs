@@ -1,7 +1,7 @@
+use crate::asn1::keygen_attestation::KeyGenAttestation;
 use crate::asn1::private_key::PrivateKey;
 use crate::asn1::public_key::PublicKey;
 use crate::errors;
-use crate::kem::common::config::oids::Oid;
 use crate::kem::{api::algorithm::KemAlgorithm, common::kem_trait::Kem, kem_manager::KemManager};
 
 // Change the alias to use `Box<dyn error::Error>`.
@@ -17,6 +17,7 @@ type Result<T> = std::result::Result<T, errors::QuantCryptError>;
 /// let mut key_generator = KemKeyGenerator::new(KemAlgorithm::MlKem768);
 /// let (pk, sk) = key_generator.generate().unwrap();
 /// ```
+#[derive(Clone)]
 pub struct KemKeyGenerator {
     /// The algorithm to use for key generation
     algorithm: KemAlgorithm,
@@ -47,11 +48,33 @@ impl KemKeyGenerator {
         let (pk, sk) = kem_manager
             .key_gen()
             .map_err(|_| errors::QuantCryptError::KeyPairGenerationFailed)?;
-        let oid = kem_type.get_oid();
+        let oid = self.algorithm.get_oid();
         let pk = PublicKey::new(&oid, &pk)
             .map_err(|_| errors::QuantCryptError::KeyPairGenerationFailed)?;
         let sk = PrivateKey::new(&oid, &sk)
             .map_err(|_| errors::QuantCryptError::KeyPairGenerationFailed)?;
         Ok((pk, sk))
     }
+
+    /// Generate a keypair and attest that it was generated by quantcrypt
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - The signing key of the device or platform, see `KeyGenAttestation`
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys and the attestation (pk, sk, attestation)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` will be returned if the identity key can't sign
+    pub fn generate_attested(
+        &mut self,
+        identity: &PrivateKey,
+    ) -> Result<(PublicKey, PrivateKey, KeyGenAttestation)> {
+        let (pk, sk) = self.generate()?;
+        let attestation = KeyGenAttestation::attest(&pk, identity)?;
+        Ok((pk, sk, attestation))
+    }
 }
@@ -0,0 +1,178 @@
+use zeroize::Zeroizing;
+
+use crate::kdf::api::KdfType;
+use crate::kem::api::algorithm::KemAlgorithm;
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::private_key::KemPrivateKey;
+use crate::kem::api::public_key::KemPublicKey;
+use crate::kem::api::shared_secret::SharedSecret;
+use crate::utils::cbor::Value;
+use crate::wrap::api::WrapManager;
+use crate::wrap::common::wrap_trait::Wrap;
+use crate::wrap::common::wrap_type::WrapType;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The version of the key wrap structure
+const KEY_WRAP_VERSION: i64 = 1;
+
+/// The label of the key encryption key derivation, followed by the KEM OID
+const KEK_LABEL: &[u8] = b"quantcrypt key wrap ";
+
+/// The length of the AES-256 key encryption key
+const KEK_LEN: usize = 32;
+
+/// Derive the key encryption key of a recipient from its shared secret
+fn derive_kek(ss: &SharedSecret, algorithm: KemAlgorithm) -> Result<Zeroizing<Vec<u8>>> {
+    let label = [KEK_LABEL, algorithm.get_oid().as_bytes()].concat();
+    ss.expand_to_key(KdfType::HkdfWithSha256, &label, KEK_LEN)
+}
+
+/// Wrap a data encryption key (DEK) to several KEM public keys
+///
+/// For each recipient, a shared secret is encapsulated to its public key and a
+/// key encryption key is derived from it with HKDF-SHA256, bound to the KEM
+/// algorithm. The DEK is then wrapped with AES-256 key wrap (RFC 3394). The
+/// result is a compact CBOR structure rather than CMS:
+///
+/// ```text
+/// [ version: 1, [* [ kem_oid: tstr, kem_ct: bstr, wrapped_dek: bstr ] ] ]
+/// ```
+///
+/// Recipients are not identified in the structure: `unwrap_key` tries the
+/// entries of its algorithm until the key wrap integrity check passes.
+///
+/// # Arguments
+///
+/// * `pks` - The public keys of the recipients
+/// * `dek` - The key to wrap, at least 16 bytes and a multiple of 8 bytes
+///
+/// # Returns
+///
+/// The CBOR encoded structure
+///
+/// # Errors
+///
+/// `QuantCryptError::KeyWrapFailed` will be returned if the DEK has an invalid length
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{
+///     unwrap_key, wrap_key_for, Kem, KemAlgorithm, KemManager, KemPrivateKey, KemPublicKey,
+///     KemType,
+/// };
+///
+/// let (pk, sk) = KemManager::new(KemType::MlKem768).unwrap().key_gen().unwrap();
+/// let pk = KemPublicKey::new(KemAlgorithm::MlKem768, &pk).unwrap();
+/// let sk = KemPrivateKey::new(KemAlgorithm::MlKem768, &sk).unwrap();
+///
+/// let dek = [7u8; 32];
+/// let blob = wrap_key_for(&[pk], &dek).unwrap();
+/// assert_eq!(unwrap_key(&sk, &blob).unwrap(), dek);
+/// ```
+pub fn wrap_key_for(pks: &[KemPublicKey], dek: &[u8]) -> Result<Vec<u8>> {
+    let wrap = WrapManager::new(WrapType::Aes256)?;
+    let mut recipients = Vec::with_capacity(pks.len());
+    for pk in pks {
+        let (ss, ct) = pk.encap()?;
+        let kek = derive_kek(&ss, pk.get_algorithm())?;
+        let wrapped = wrap.wrap(&kek, dek)?;
+        recipients.push(Value::Array(vec![
+            Value::Text(pk.get_algorithm().get_oid()),
+            Value::Bytes(ct.as_bytes().to_vec()),
+            Value::Bytes(wrapped),
+        ]));
+    }
+    Ok(Value::Array(vec![Value::Int(KEY_WRAP_VERSION), Value::Array(recipients)]).to_vec())
+}
+
+/// Unwrap a data encryption key wrapped with `wrap_key_for`
+///
+/// # Arguments
+///
+/// * `sk` - The private key of the recipient
+/// * `blob` - The CBOR encoded structure
+///
+/// # Returns
+///
+/// The data encryption key
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidCbor` will be returned if the structure is malformed
+///
+/// `QuantCryptError::KeyUnwrapFailed` will be returned if no entry can be unwrapped with the key
+pub fn unwrap_key(sk: &KemPrivateKey, blob: &[u8]) -> Result<Vec<u8>> {
+    let value = Value::from_slice(blob)?;
+    let [version, recipients] = value.as_array()? else {
+        return Err(QuantCryptError::InvalidCbor);
+    };
+    if version.as_int()? != KEY_WRAP_VERSION {
+        return Err(QuantCryptError::InvalidCbor);
+    }
+
+    let wrap = WrapManager::new(WrapType::Aes256)?;
+    let oid = sk.get_algorithm().get_oid();
+    for recipient in recipients.as_array()? {
+        let [kem_oid, ct, wrapped] = recipient.as_array()? else {
+            return Err(QuantCryptError::InvalidCbor);
+        };
+        if kem_oid.as_text()? != oid {
+            continue;
+        }
+        let Ok(ct) = Ciphertext::new(sk.get_algorithm(), ct.as_bytes()?) else {
+            continue;
+        };
+        let kek = derive_kek(&sk.decap(&ct)?, sk.get_algorithm())?;
+        if let Ok(dek) = wrap.unwrap(&kek, wrapped.as_bytes()?) {
+            return Ok(dek);
+        }
+    }
+    Err(QuantCryptError::KeyUnwrapFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::common::kem_trait::Kem;
+    use crate::utils::manager_pool::get_kem_manager;
+
+    fn gen_keys(alg: KemAlgorithm) -> (KemPublicKey, KemPrivateKey) {
+        let (pk, sk) = get_kem_manager(alg.get_kem_type())
+            .unwrap()
+            .key_gen()
+            .unwrap();
+        (
+            KemPublicKey::new(alg, &pk).unwrap(),
+            KemPrivateKey::new(alg, &sk).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_wrap_key_for() {
+        let recipients = [
+            gen_keys(KemAlgorithm::MlKem768),
+            gen_keys(KemAlgorithm::MlKem768),
+            gen_keys(KemAlgorithm::MlKem1024X448),
+        ];
+        let pks: Vec<KemPublicKey> = recipients.iter().map(|(pk, _)| pk.clone()).collect();
+        let dek = [42u8; 32];
+        let blob = wrap_key_for(&pks, &dek).unwrap();
+        for (_, sk) in recipients.iter() {
+            assert_eq!(unwrap_key(sk, &blob).unwrap(), dek);
+        }
+
+        // Not a recipient
+        let (_, outsider) = gen_keys(KemAlgorithm::MlKem768);
+        assert_eq!(
+            unwrap_key(&outsider, &blob),
+            Err(QuantCryptError::KeyUnwrapFailed)
+        );
+        assert_eq!(
+            unwrap_key(&recipients[0].1, &blob[1..]),
+            Err(QuantCryptError::InvalidCbor)
+        );
+        assert!(wrap_key_for(&pks, &[1u8; 15]).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::aead::aead_manager::AeadManager;
+use crate::aead::common::aead_trait::Aead;
+use crate::aead::common::aead_type::AeadType;
+use crate::kdf::common::kdf_type::KdfType;
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The label used when deriving the key of a layer from the KEM shared secret
+const LAYER_KEY_LABEL: &[u8] = b"quantcrypt onion layer";
+
+/// The length of the key of a layer
+const LAYER_KEY_LEN: usize = 32;
+
+/// The nonce of every layer. Each layer is encrypted under a fresh key, so the
+/// nonce never repeats for a key.
+const LAYER_NONCE: [u8; 12] = [0u8; 12];
+
+/// Marks a plaintext that contains the next layer
+const TAG_NEXT: u8 = 0;
+
+/// Marks a plaintext that contains the payload
+const TAG_PAYLOAD: u8 = 1;
+
+/// The result of peeling one layer of an onion
+#[derive(Clone, Debug, PartialEq)]
+pub enum PeeledLayer {
+    /// The layer for the next hop, to be forwarded
+    Next(Vec<u8>),
+    /// The payload, if this was the last hop
+    Payload(Vec<u8>),
+}
+
+/// Encrypt data in nested layers, one per hop
+///
+/// Each layer is a KEM encapsulation to the public key of a hop, followed by the
+/// inner layer (or the payload, for the last hop) encrypted with AES-256-GCM under
+/// a key derived from the shared secret. The ciphertext commits to the key, and the
+/// KEM ciphertext is authenticated as additional data. Each hop peels its layer
+/// with `decrypt_layer` and forwards the result.
+///
+/// A layer is `len(ct) (2 bytes, big endian) || ct || commitment || AEAD ciphertext`.
+/// Layers are not padded, so their size reveals how many hops are left.
+///
+/// # Arguments
+///
+/// * `recipients` - The KEM public keys of the hops, the first hop first
+/// * `data` - The payload for the last hop
+///
+/// # Returns
+///
+/// The outermost layer, for the first hop
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidRecipientInfo` will be returned if there are no recipients,
+/// and `QuantCryptError::UnsupportedOperation` if a public key is not a KEM key
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "openssl")]
+/// # {
+/// use quantcrypt::kems::{self, KemAlgorithm, KemKeyGenerator, PeeledLayer};
+///
+/// let (pk1, sk1) = KemKeyGenerator::new(KemAlgorithm::MlKem768).generate().unwrap();
+/// let (pk2, sk2) = KemKeyGenerator::new(KemAlgorithm::MlKem768X25519).generate().unwrap();
+///
+/// let onion = kems::encrypt_layered(&[pk1, pk2], b"hello").unwrap();
+/// let PeeledLayer::Next(onion) = kems::decrypt_layer(&sk1, &onion).unwrap() else {
+///     panic!("expected another layer");
+/// };
+/// let payload = kems::decrypt_layer(&sk2, &onion).unwrap();
+/// assert_eq!(payload, PeeledLayer::Payload(b"hello".to_vec()));
+/// # }
+/// ```
+pub fn encrypt_layered(recipients: &[PublicKey], data: &[u8]) -> Result<Vec<u8>> {
+    if recipients.is_empty() {
+        return Err(QuantCryptError::InvalidRecipientInfo);
+    }
+
+    let aead = AeadManager::new(AeadType::AesGcm256)?;
+    let mut layer = data.to_vec();
+    let mut tag = TAG_PAYLOAD;
+    for pk in recipients.iter().rev() {
+        let (ss, ct) = pk.encap()?;
+        let key = ss.expand_to_key(KdfType::HkdfWithSha256, LAYER_KEY_LABEL, LAYER_KEY_LEN)?;
+        let ct = ct.as_bytes();
+        let ct_len = u16::try_from(ct.len()).map_err(|_| QuantCryptError::InvalidCiphertext)?;
+
+        let mut plaintext = Vec::with_capacity(layer.len() + 1);
+        plaintext.push(tag);
+        plaintext.extend_from_slice(&layer);
+
+        let mut next = Vec::with_capacity(2 + ct.len() + plaintext.len() + 64);
+        next.extend_from_slice(&ct_len.to_be_bytes());
+        next.extend_from_slice(ct);
+        next.extend(aead.seal_committing(&key, &LAYER_NONCE, ct, &plaintext)?);
+        layer = next;
+        tag = TAG_NEXT;
+    }
+    Ok(layer)
+}
+
+/// Peel one layer of data encrypted with `encrypt_layered`
+///
+/// # Arguments
+///
+/// * `sk` - The KEM private key of this hop
+/// * `layer` - The layer received by this hop
+///
+/// # Returns
+///
+/// The layer for the next hop, or the payload if this is the last hop
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidCiphertext` will be returned if the layer is malformed or
+/// not for the algorithm of the key, and `QuantCryptError::DecryptionFailed` if it was
+/// not encrypted for this key or has been tampered with
+pub fn decrypt_layer(sk: &PrivateKey, layer: &[u8]) -> Result<PeeledLayer> {
+    if layer.len() < 2 {
+        return Err(QuantCryptError::InvalidCiphertext);
+    }
+    let ct_len = u16::from_be_bytes([layer[0], layer[1]]) as usize;
+    if layer.len() < 2 + ct_len {
+        return Err(QuantCryptError::InvalidCiphertext);
+    }
+    let (ct, sealed) = layer[2..].split_at(ct_len);
+
+    let ciphertext =
+        Ciphertext::from_oid(sk.get_oid(), ct).map_err(|_| QuantCryptError::InvalidCiphertext)?;
+    let ss = sk.decap(&ciphertext)?;
+    let key = ss.expand_to_key(KdfType::HkdfWithSha256, LAYER_KEY_LABEL, LAYER_KEY_LEN)?;
+
+    let aead = AeadManager::new(AeadType::AesGcm256)?;
+    let plaintext = aead.open_committing(&key, &LAYER_NONCE, ct, sealed)?;
+    match plaintext.split_first() {
+        Some((&TAG_NEXT, inner)) => Ok(PeeledLayer::Next(inner.to_vec())),
+        Some((&TAG_PAYLOAD, payload)) => Ok(PeeledLayer::Payload(payload.to_vec())),
+        _ => Err(QuantCryptError::InvalidContent),
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::kems::{KemAlgorithm, KemKeyGenerator};
+
+    #[test]
+    fn test_layered_encryption() {
+        let algs = [
+            KemAlgorithm::MlKem512,
+            KemAlgorithm::MlKem768X25519,
+            KemAlgorithm::MlKem1024,
+        ];
+        let keys: Vec<(PublicKey, PrivateKey)> = algs
+            .iter()
+            .map(|alg| KemKeyGenerator::new(*alg).generate().unwrap())
+            .collect();
+        let pks: Vec<PublicKey> = keys.iter().map(|(pk, _)| pk.clone()).collect();
+
+        let mut layer = encrypt_layered(&pks, b"payload").unwrap();
+        for (i, (_, sk)) in keys.iter().enumerate() {
+            // A hop can't peel the layer of another hop
+            if i + 1 < keys.len() {
+                assert!(decrypt_layer(&keys[i + 1].1, &layer).is_err());
+            }
+            match decrypt_layer(sk, &layer).unwrap() {
+                PeeledLayer::Next(next) => {
+                    assert!(i + 1 < keys.len());
+                    assert!(next.len() < layer.len());
+                    layer = next;
+                }
+                PeeledLayer::Payload(payload) => {
+                    assert_eq!(i + 1, keys.len());
+                    assert_eq!(payload, b"payload");
+                }
+            }
+        }
+
+        // Tampering is detected
+        let mut onion = encrypt_layered(&pks[..1], b"payload").unwrap();
+        let last = onion.len() - 1;
+        onion[last] ^= 1;
+        assert_eq!(
+            decrypt_layer(&keys[0].1, &onion),
+            Err(QuantCryptError::DecryptionFailed)
+        );
+        assert_eq!(
+            decrypt_layer(&keys[0].1, &onion[..1]),
+            Err(QuantCryptError::InvalidCiphertext)
+        );
+        assert_eq!(
+            encrypt_layered(&[], b"payload"),
+            Err(QuantCryptError::InvalidRecipientInfo)
+        );
+    }
+}
@@ -1,2 +1,15 @@
 pub mod algorithm;
+pub mod ciphertext;
+pub mod functions;
+#[cfg(feature = "kem-traits")]
+pub mod kem_traits;
+pub(crate) mod key_encoding;
 pub mod key_generator;
+pub mod key_wrap;
+pub mod layered;
+pub mod pake;
+pub mod prepared_key;
+pub mod private_key;
+pub mod public_key;
+pub mod shared_secret;
+pub mod traditional_key;
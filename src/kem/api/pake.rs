@@ -0,0 +1,180 @@
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::shared_secret::SharedSecret;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The domain separator of the combiner
+const COMBINER_LABEL: &[u8] = b"quantcrypt pake-kem combiner v1";
+
+/// The length of the derived session key
+const SESSION_KEY_LEN: usize = 32;
+
+/// The PAKE whose output is combined with a KEM shared secret
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PakeType {
+    /// CPace (draft-irtf-cfrg-cpace), using its ISK
+    Cpace,
+    /// OPAQUE (draft-irtf-cfrg-opaque), using its session_key
+    Opaque,
+}
+
+impl PakeType {
+    fn get_label(&self) -> &'static [u8] {
+        match self {
+            PakeType::Cpace => b"CPace",
+            PakeType::Opaque => b"OPAQUE",
+        }
+    }
+}
+
+/// Append a value prefixed with its length as a 4 byte big-endian integer
+fn append_prefixed(out: &mut Vec<u8>, value: &[u8]) {
+    out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    out.extend_from_slice(value);
+}
+
+/// Combine the key of a PAKE with a KEM shared secret
+///
+/// The session key is HKDF-SHA256 with the PAKE key and the shared secret as the
+/// input keying material, and the PAKE type, the OID of the KEM, a hash of the KEM
+/// ciphertext and the context as the info, all length-prefixed. It stays secret as
+/// long as either the PAKE or the KEM is unbroken.
+fn combine(
+    pake: PakeType,
+    pake_key: &[u8],
+    ss: &SharedSecret,
+    ct: &Ciphertext,
+    context: &[u8],
+) -> Result<Zeroizing<Vec<u8>>> {
+    if pake_key.is_empty() {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    let mut ikm = Zeroizing::new(Vec::new());
+    append_prefixed(&mut ikm, pake_key);
+    append_prefixed(&mut ikm, ss.expose_secret());
+
+    let mut info = Vec::new();
+    append_prefixed(&mut info, COMBINER_LABEL);
+    append_prefixed(&mut info, pake.get_label());
+    append_prefixed(&mut info, ct.get_algorithm().get_oid().as_bytes());
+    append_prefixed(&mut info, &Sha256::digest(ct.as_bytes()));
+    append_prefixed(&mut info, context);
+
+    let mut key = Zeroizing::new(vec![0u8; SESSION_KEY_LEN]);
+    Hkdf::<Sha256>::new(None, &ikm)
+        .expand(&info, &mut key)
+        .map_err(|_| QuantCryptError::KdfError)?;
+    Ok(key)
+}
+
+/// Encapsulate to the KEM public key of the peer and combine the shared secret with
+/// the key of a completed PAKE run
+///
+/// Password-authenticated sessions get post-quantum confidentiality this way: the
+/// PAKE authenticates the peers, and the KEM protects the session key against
+/// harvest-now-decrypt-later. The KEM public key is not authenticated by itself, so
+/// the peers must confirm the session key (e.g. with a MAC over the transcript)
+/// before using it. A man in the middle who replaced the public key doesn't know the
+/// PAKE key and derives a different session key.
+///
+/// # Arguments
+///
+/// * `pake` - The PAKE that was run
+/// * `pake_key` - The key output by the PAKE (the ISK of CPace or the session_key of OPAQUE)
+/// * `pk` - The KEM public key of the peer
+/// * `context` - Application context bound to the session key, e.g. the session identifier
+///
+/// # Returns
+///
+/// A tuple containing the 32 byte session key, zeroized on drop, and the ciphertext
+/// for the peer (key, ct)
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{self, KemAlgorithm, KemKeyGenerator, PakeType};
+///
+/// // The output of a CPace run, known to both peers
+/// let isk = [7u8; 64];
+///
+/// let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768).generate().unwrap();
+/// let (key, ct) = kems::pake_kem_encap(PakeType::Cpace, &isk, &pk, b"session 1").unwrap();
+/// let key2 = kems::pake_kem_decap(PakeType::Cpace, &isk, &sk, &ct, b"session 1").unwrap();
+/// assert_eq!(key, key2);
+/// ```
+pub fn pake_kem_encap(
+    pake: PakeType,
+    pake_key: &[u8],
+    pk: &PublicKey,
+    context: &[u8],
+) -> Result<(Zeroizing<Vec<u8>>, Ciphertext)> {
+    let (ss, ct) = pk.encap()?;
+    Ok((combine(pake, pake_key, &ss, &ct, context)?, ct))
+}
+
+/// Decapsulate a ciphertext from `pake_kem_encap` and combine the shared secret with
+/// the key of a completed PAKE run
+///
+/// # Arguments
+///
+/// * `pake` - The PAKE that was run
+/// * `pake_key` - The key output by the PAKE
+/// * `sk` - The KEM private key
+/// * `ct` - The ciphertext from the peer
+/// * `context` - Application context bound to the session key
+///
+/// # Returns
+///
+/// The 32 byte session key, zeroized on drop
+pub fn pake_kem_decap(
+    pake: PakeType,
+    pake_key: &[u8],
+    sk: &PrivateKey,
+    ct: &Ciphertext,
+    context: &[u8],
+) -> Result<Zeroizing<Vec<u8>>> {
+    let ss = sk.decap(ct)?;
+    combine(pake, pake_key, &ss, ct, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kems::{KemAlgorithm, KemKeyGenerator};
+
+    #[test]
+    fn test_pake_kem() {
+        let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768X25519)
+            .generate()
+            .unwrap();
+        let pake_key = [1u8; 32];
+        let (key, ct) = pake_kem_encap(PakeType::Opaque, &pake_key, &pk, b"ctx").unwrap();
+        assert_eq!(key.len(), SESSION_KEY_LEN);
+        assert_eq!(
+            pake_kem_decap(PakeType::Opaque, &pake_key, &sk, &ct, b"ctx").unwrap(),
+            key
+        );
+
+        // The PAKE key, the PAKE type and the context are all bound to the session key
+        for (pake, pake_key, context) in [
+            (PakeType::Opaque, [2u8; 32], &b"ctx"[..]),
+            (PakeType::Cpace, pake_key, b"ctx"),
+            (PakeType::Opaque, pake_key, b"other"),
+        ] {
+            assert_ne!(
+                pake_kem_decap(pake, &pake_key, &sk, &ct, context).unwrap(),
+                key
+            );
+        }
+
+        assert_eq!(
+            pake_kem_decap(PakeType::Opaque, &[], &sk, &ct, b"ctx"),
+            Err(QuantCryptError::InvalidPrivateKey)
+        );
+    }
+}
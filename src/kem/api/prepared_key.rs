@@ -0,0 +1,225 @@
+use crate::kem::api::algorithm::KemAlgorithm;
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::public_key::KemPublicKey;
+use crate::kem::api::shared_secret::SharedSecret;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::composite_kem::CompositeKemManager;
+use crate::kem::kem_manager::KemManager;
+use crate::kem::ml_kem::PreparedMlKemKey;
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::utils::manager_pool::get_kem_manager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// How a prepared key is encapsulated to
+enum Prepared {
+    /// An ML-KEM key
+    MlKem(PreparedMlKemKey),
+    /// A composite key, with its traditional component
+    Composite {
+        pq_pk: PreparedMlKemKey,
+        t_pk: Vec<u8>,
+        kem: CompositeKemManager,
+    },
+    /// A key of a KEM provided by a registered backend, encapsulated to as is
+    Backend(KemManager),
+}
+
+/// A KEM encapsulation key parsed once, for repeated encapsulations
+///
+/// Encapsulating to a `KemPublicKey` decodes the key on every call. A prepared
+/// key decodes it once: the NTT-domain vector of the ML-KEM key, and for
+/// composite keys the DER structure splitting the ML-KEM and traditional
+/// components. This suits senders that encapsulate to the same recipients
+/// over and over, such as a mail gateway.
+///
+/// The ML-KEM matrix is still sampled by every encapsulation, and the
+/// traditional component is still parsed by its own backend (OpenSSL for
+/// RSA and the NIST and Brainpool curves). Keys of a KEM with a registered
+/// backend (see `register_kem_backend`) are passed to the backend as is.
+///
+/// The encapsulations are the same as with `KemPublicKey::encap`.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{KemAlgorithm, KemKeyGenerator, KemPublicKey, PreparedEncapsulationKey};
+///
+/// let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768X25519).generate().unwrap();
+/// let pk = KemPublicKey::new(KemAlgorithm::MlKem768X25519, pk.get_key()).unwrap();
+///
+/// let prepared = PreparedEncapsulationKey::new(&pk).unwrap();
+/// for _ in 0..3 {
+///     let (ss, ct) = prepared.encap().unwrap();
+///     assert_eq!(sk.decap(&ct).unwrap(), ss);
+/// }
+/// ```
+pub struct PreparedEncapsulationKey {
+    pk: KemPublicKey,
+    prepared: Prepared,
+    rng: Option<SharedRng>,
+}
+
+impl PreparedEncapsulationKey {
+    /// Prepare a public key for repeated encapsulations
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key
+    ///
+    /// # Returns
+    ///
+    /// The prepared key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the key can't be decoded
+    pub fn new(pk: &KemPublicKey) -> Result<Self> {
+        let prepared = match get_kem_manager(pk.get_kem_type())? {
+            KemManager::Ml(_) => {
+                Prepared::MlKem(PreparedMlKemKey::new(&pk.get_kem_type(), pk.as_bytes())?)
+            }
+            KemManager::Composite(kem) => match kem.prepare(pk.as_bytes())? {
+                Some((pq_pk, t_pk)) => Prepared::Composite { pq_pk, t_pk, kem },
+                None => Prepared::Backend(KemManager::Composite(kem)),
+            },
+            kem => Prepared::Backend(kem),
+        };
+        Ok(PreparedEncapsulationKey {
+            pk: pk.clone(),
+            prepared,
+            rng: None,
+        })
+    }
+
+    /// Prepare a public key for repeated encapsulations, drawing the coins of every
+    /// encapsulation from a caller-supplied RNG
+    ///
+    /// See `SharedRng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key
+    /// * `rng` - The RNG
+    ///
+    /// # Returns
+    ///
+    /// The prepared key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the key can't be decoded
+    ///
+    /// `QuantCryptError::NotImplemented` will be returned if the KEM is provided by a
+    /// registered backend
+    pub fn new_with_rng(pk: &KemPublicKey, rng: SharedRng) -> Result<Self> {
+        let mut key = Self::new(pk)?;
+        match &mut key.prepared {
+            Prepared::MlKem(_) => {}
+            Prepared::Composite { kem, .. } => kem.set_rng(rng.clone())?,
+            Prepared::Backend(kem) => kem.set_rng(rng.clone())?,
+        }
+        key.rng = Some(rng);
+        Ok(key)
+    }
+
+    /// Get the algorithm of the key
+    pub fn get_algorithm(&self) -> KemAlgorithm {
+        self.pk.get_algorithm()
+    }
+
+    /// Get the public key that was prepared
+    pub fn get_public_key(&self) -> &KemPublicKey {
+        &self.pk
+    }
+
+    /// Encapsulate a shared secret to the key
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and the ciphertext (ss, ct)
+    pub fn encap(&self) -> Result<(SharedSecret, Ciphertext)> {
+        let (ss, ct) = match &self.prepared {
+            Prepared::MlKem(pk) => pk.encap(&mut ManagerRng::for_operation(self.rng.as_ref()))?,
+            Prepared::Composite { pq_pk, t_pk, kem } => kem.clone().encap_prepared(pq_pk, t_pk)?,
+            Prepared::Backend(kem) => kem.clone().encap(self.pk.as_bytes())?,
+        };
+        Ok((
+            SharedSecret::new(ss),
+            Ciphertext::new(self.get_algorithm(), &ct)?,
+        ))
+    }
+}
+
+impl TryFrom<&KemPublicKey> for PreparedEncapsulationKey {
+    type Error = QuantCryptError;
+
+    fn try_from(pk: &KemPublicKey) -> Result<Self> {
+        PreparedEncapsulationKey::new(pk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::api::private_key::KemPrivateKey;
+
+    #[test]
+    fn test_prepared_encapsulation_key() {
+        for alg in [
+            KemAlgorithm::MlKem512,
+            KemAlgorithm::MlKem768,
+            KemAlgorithm::MlKem1024,
+            KemAlgorithm::MlKem768X25519,
+            KemAlgorithm::MlKem768P384,
+            KemAlgorithm::MlKem768Rsa2048,
+            KemAlgorithm::MlKem1024X448,
+        ] {
+            let (pk, sk) = get_kem_manager(alg.get_kem_type())
+                .unwrap()
+                .key_gen()
+                .unwrap();
+            let pk = KemPublicKey::new(alg, &pk).unwrap();
+            let sk = KemPrivateKey::new(alg, &sk).unwrap();
+
+            let prepared = PreparedEncapsulationKey::try_from(&pk).unwrap();
+            assert_eq!(prepared.get_algorithm(), alg);
+            assert_eq!(prepared.get_public_key(), &pk);
+            let (ss1, ct1) = prepared.encap().unwrap();
+            let (ss2, ct2) = prepared.encap().unwrap();
+            assert_eq!(sk.decap(&ct1).unwrap(), ss1);
+            assert_eq!(sk.decap(&ct2).unwrap(), ss2);
+            assert_ne!(ct1, ct2);
+            assert!(matches!(
+                prepared.prepared,
+                Prepared::MlKem(_) | Prepared::Composite { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_prepared_encapsulation_key_with_rng() {
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        for alg in [KemAlgorithm::MlKem768, KemAlgorithm::MlKem768X25519] {
+            let (pk, sk) = get_kem_manager(alg.get_kem_type())
+                .unwrap()
+                .key_gen()
+                .unwrap();
+            let pk = KemPublicKey::new(alg, &pk).unwrap();
+            let sk = KemPrivateKey::new(alg, &sk).unwrap();
+
+            // The same RNG stream gives the same encapsulations
+            let mut outputs = Vec::new();
+            for _ in 0..2 {
+                let rng = SharedRng::new(ChaCha20Rng::from_seed([4u8; 32]));
+                let prepared = PreparedEncapsulationKey::new_with_rng(&pk, rng).unwrap();
+                let (ss, ct) = prepared.encap().unwrap();
+                assert_eq!(sk.decap(&ct).unwrap(), ss);
+                outputs.push((ss, ct));
+            }
+            assert_eq!(outputs[0], outputs[1]);
+        }
+    }
+}
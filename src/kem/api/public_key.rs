@@ -0,0 +1,426 @@
+use std::io::{ErrorKind, Read};
+use std::ops::Range;
+
+use crate::asn1::composite_public_key::CompositePublicKey;
+use crate::kem::api::algorithm::KemAlgorithm;
+use crate::kem::api::ciphertext::Ciphertext;
+use crate::kem::api::key_encoding;
+use crate::kem::api::shared_secret::SharedSecret;
+use crate::kem::api::traditional_key::TraditionalKemPublicKey;
+use crate::kem::common::config::pk_len::PKLen;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::utils::manager_pool::get_kem_manager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The number of bytes read from the source at a time
+const CHUNK_LEN: usize = 4096;
+
+/// The ML-KEM modulus
+const Q: u16 = 3329;
+
+/// The length of the SEQUENCE header, BIT STRING header and unused bits byte
+/// in front of the ML-KEM key of a composite public key
+const COMPOSITE_PQ_OFFSET: usize = 9;
+
+/// A KEM encapsulation key tagged with its algorithm.
+///
+/// The key is validated on construction: its length must match the algorithm,
+/// every coefficient of the ML-KEM key must be reduced modulo q (the modulus
+/// check of FIPS 203) and a composite key must be well-formed.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{KemAlgorithm, KemKeyGenerator, KemPublicKey};
+///
+/// let (pk, _) = KemKeyGenerator::new(KemAlgorithm::MlKem768).generate().unwrap();
+///
+/// // Parse the key from a file, socket or any other `Read` source
+/// let source = std::io::Cursor::new(pk.get_key().to_vec());
+/// let pk = KemPublicKey::parse_streaming(KemAlgorithm::MlKem768, source).unwrap();
+/// assert_eq!(pk.as_bytes().len(), 1184);
+///
+/// assert!(KemPublicKey::new(KemAlgorithm::MlKem768, &[0u8; 10]).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct KemPublicKey {
+    algorithm: KemAlgorithm,
+    pk: Vec<u8>,
+}
+
+/// The incremental modulus check of the ML-KEM key in a public key
+struct ModulusCheck {
+    /// The position of the encoded coefficients in the public key
+    range: Range<usize>,
+    /// The bytes of the current group of two coefficients
+    group: [u8; 3],
+    /// The number of bytes in the current group
+    group_len: usize,
+}
+
+impl ModulusCheck {
+    fn new(algorithm: KemAlgorithm) -> ModulusCheck {
+        // The encoded coefficients are 384 * k bytes, followed by the 32 byte seed
+        let range = match algorithm {
+            KemAlgorithm::MlKem512 => 0..768,
+            KemAlgorithm::MlKem768 => 0..1152,
+            KemAlgorithm::MlKem1024 => 0..1536,
+            KemAlgorithm::MlKem768Rsa2048
+            | KemAlgorithm::MlKem768Rsa3072
+            | KemAlgorithm::MlKem768Rsa4096
+            | KemAlgorithm::MlKem768X25519
+            | KemAlgorithm::MlKem768P384
+            | KemAlgorithm::MlKem768BrainpoolP256r1 => {
+                COMPOSITE_PQ_OFFSET..COMPOSITE_PQ_OFFSET + 1152
+            }
+            KemAlgorithm::MlKem1024P384
+            | KemAlgorithm::MlKem1024BrainpoolP384r1
+            | KemAlgorithm::MlKem1024X448 => COMPOSITE_PQ_OFFSET..COMPOSITE_PQ_OFFSET + 1536,
+        };
+        ModulusCheck {
+            range,
+            group: [0; 3],
+            group_len: 0,
+        }
+    }
+
+    /// Check the coefficients in a chunk starting at `offset` in the public key
+    fn update(&mut self, offset: usize, chunk: &[u8]) -> Result<()> {
+        let start = self.range.start.max(offset);
+        let end = self.range.end.min(offset + chunk.len());
+        if start >= end {
+            return Ok(());
+        }
+        for byte in &chunk[start - offset..end - offset] {
+            self.group[self.group_len] = *byte;
+            self.group_len += 1;
+            if self.group_len == 3 {
+                // ByteDecode_12 of two coefficients
+                let [b0, b1, b2] = self.group.map(u16::from);
+                let d1 = b0 | ((b1 & 0x0f) << 8);
+                let d2 = (b1 >> 4) | (b2 << 4);
+                if d1 >= Q || d2 >= Q {
+                    return Err(QuantCryptError::InvalidPublicKey);
+                }
+                self.group_len = 0;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl KemPublicKey {
+    /// Create a new public key
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The KEM algorithm the key belongs to
+    /// * `pk` - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// The public key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the key is invalid
+    pub fn new(algorithm: KemAlgorithm, pk: &[u8]) -> Result<Self> {
+        Self::parse_streaming(algorithm, pk)
+    }
+
+    /// Parse a public key from a `Read` source, validating it chunk by chunk
+    ///
+    /// Only the expected length of the key is ever buffered: a source that
+    /// yields more data, or an ML-KEM coefficient that isn't reduced, is
+    /// rejected as soon as it is read, without consuming the rest of the source.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The KEM algorithm the key belongs to
+    /// * `reader` - The source of the public key bytes
+    ///
+    /// # Returns
+    ///
+    /// The public key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the key is invalid
+    ///
+    /// `QuantCryptError::StreamIoError` will be returned if reading the source fails
+    pub fn parse_streaming(algorithm: KemAlgorithm, mut reader: impl Read) -> Result<Self> {
+        let kem_type = algorithm.get_kem_type();
+        let expected = kem_type
+            .get_pk_len()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        let mut check = ModulusCheck::new(algorithm);
+
+        let mut pk = Vec::with_capacity(expected);
+        let mut chunk = [0u8; CHUNK_LEN];
+        loop {
+            // Never read past the end of the expected key, plus one byte to detect trailing data
+            let want = (expected + 1 - pk.len()).min(CHUNK_LEN);
+            let n = match reader.read(&mut chunk[..want]) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return Err(QuantCryptError::StreamIoError),
+            };
+            if pk.len() + n > expected {
+                return Err(QuantCryptError::InvalidPublicKey);
+            }
+            check.update(pk.len(), &chunk[..n])?;
+            pk.extend_from_slice(&chunk[..n]);
+        }
+        if pk.len() != expected {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+
+        if algorithm.is_composite() {
+            // The checked coefficients must be the ML-KEM component
+            let c_pk = CompositePublicKey::from_der(&algorithm.get_oid(), &pk)?;
+            let pq_pk = c_pk.get_pq_pk();
+            if !pk[COMPOSITE_PQ_OFFSET..].starts_with(&pq_pk)
+                || pq_pk.len() != check.range.len() + 32
+            {
+                return Err(QuantCryptError::InvalidPublicKey);
+            }
+        }
+
+        Ok(Self { algorithm, pk })
+    }
+
+    /// Get the KEM algorithm the key belongs to
+    pub fn get_algorithm(&self) -> KemAlgorithm {
+        self.algorithm
+    }
+
+    /// Get the KEM type the key belongs to
+    pub fn get_kem_type(&self) -> KemType {
+        self.algorithm.get_kem_type()
+    }
+
+    /// Get the public key bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.pk
+    }
+
+    /// Consume the public key and return its bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.pk
+    }
+
+    /// Split a composite public key into its ML-KEM and traditional components
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the ML-KEM and traditional public keys (pq, trad)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` will be returned if the key is not composite
+    pub fn split(&self) -> Result<(KemPublicKey, TraditionalKemPublicKey)> {
+        let (pq_alg, trad_type) = self
+            .algorithm
+            .get_components()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        let c_pk = CompositePublicKey::from_der(&self.algorithm.get_oid(), &self.pk)?;
+        Ok((
+            KemPublicKey::new(pq_alg, &c_pk.get_pq_pk())?,
+            TraditionalKemPublicKey::new(trad_type, &c_pk.get_trad_pk())?,
+        ))
+    }
+
+    /// Assemble a composite public key from its ML-KEM and traditional components
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The composite KEM algorithm
+    /// * `pq` - The ML-KEM public key
+    /// * `trad` - The traditional public key
+    ///
+    /// # Returns
+    ///
+    /// The composite public key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` will be returned if the algorithm is not composite
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the components don't belong to the algorithm
+    pub fn from_parts(
+        algorithm: KemAlgorithm,
+        pq: &KemPublicKey,
+        trad: &TraditionalKemPublicKey,
+    ) -> Result<Self> {
+        let (pq_alg, trad_type) = algorithm
+            .get_components()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        if pq.get_algorithm() != pq_alg || trad.get_kem_type() != trad_type {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+        let c_pk = CompositePublicKey::new(&algorithm.get_oid(), pq.as_bytes(), trad.as_bytes());
+        KemPublicKey::new(algorithm, &c_pk.to_der()?)
+    }
+
+    /// Encapsulate a shared secret to the key
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and the ciphertext (ss, ct)
+    pub fn encap(&self) -> Result<(SharedSecret, Ciphertext)> {
+        let mut kem = get_kem_manager(self.get_kem_type())?;
+        let (ss, ct) = kem.encap(&self.pk)?;
+        Ok((SharedSecret::new(ss), Ciphertext::new(self.algorithm, &ct)?))
+    }
+
+    /// Encode the key as a DER SubjectPublicKeyInfo
+    ///
+    /// The algorithm is identified by its OID, without parameters, and the
+    /// subjectPublicKey holds the key bytes.
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded SubjectPublicKeyInfo
+    pub fn to_der(&self) -> Result<Vec<u8>> {
+        key_encoding::encode_spki(&self.algorithm.get_oid(), &self.pk)
+    }
+
+    /// Decode a key from a DER SubjectPublicKeyInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `der` - The DER encoded SubjectPublicKeyInfo
+    ///
+    /// # Returns
+    ///
+    /// The public key, validated as in `new`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the encoding is invalid,
+    /// the OID isn't a known KEM algorithm or the key is invalid
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        let (oid, pk) = key_encoding::decode_spki(der)?;
+        let algorithm = KemAlgorithm::from_oid(&oid).ok_or(QuantCryptError::InvalidPublicKey)?;
+        KemPublicKey::new(algorithm, &pk)
+    }
+
+    /// Encode the key as a PEM SubjectPublicKeyInfo, labelled "PUBLIC KEY"
+    ///
+    /// # Returns
+    ///
+    /// The PEM encoded SubjectPublicKeyInfo
+    pub fn to_pem(&self) -> Result<String> {
+        Ok(key_encoding::spki_to_pem(&self.to_der()?))
+    }
+
+    /// Decode a key from a PEM SubjectPublicKeyInfo, labelled "PUBLIC KEY"
+    ///
+    /// # Arguments
+    ///
+    /// * `pem` - The PEM encoded SubjectPublicKeyInfo
+    ///
+    /// # Returns
+    ///
+    /// The public key, validated as in `new`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the encoding or the key is invalid
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        KemPublicKey::from_der(&key_encoding::spki_from_pem(pem)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::api::key_generator::KemKeyGenerator;
+
+    /// A source returning a few bytes at a time
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(7).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_parse_streaming() {
+        for alg in [
+            KemAlgorithm::MlKem512,
+            KemAlgorithm::MlKem1024,
+            KemAlgorithm::MlKem768X25519,
+            KemAlgorithm::MlKem768Rsa2048,
+        ] {
+            let (pk, _) = KemKeyGenerator::new(alg).generate().unwrap();
+            let pk = pk.get_key();
+            let parsed = KemPublicKey::parse_streaming(alg, Trickle(pk)).unwrap();
+            assert_eq!(parsed.as_bytes(), pk);
+            assert_eq!(parsed.get_algorithm(), alg);
+
+            // Truncated or followed by more data
+            assert!(KemPublicKey::new(alg, &pk[1..]).is_err());
+            let longer = [pk, &[0u8]].concat();
+            assert!(KemPublicKey::parse_streaming(alg, Trickle(&longer)).is_err());
+
+            // A coefficient of 4095 isn't reduced modulo q
+            let offset = ModulusCheck::new(alg).range.start;
+            let mut bad = pk.to_vec();
+            bad[offset] = 0xff;
+            bad[offset + 1] |= 0x0f;
+            assert_eq!(
+                KemPublicKey::new(alg, &bad),
+                Err(QuantCryptError::InvalidPublicKey)
+            );
+        }
+
+        // Reading stops after the expected length
+        let mut source = std::io::repeat(0);
+        assert!(KemPublicKey::parse_streaming(KemAlgorithm::MlKem768, &mut source).is_err());
+    }
+
+    #[test]
+    fn test_spki_roundtrip() {
+        for alg in [
+            KemAlgorithm::MlKem512,
+            KemAlgorithm::MlKem1024,
+            KemAlgorithm::MlKem768X25519,
+            KemAlgorithm::MlKem768Rsa2048,
+        ] {
+            let (pk, _) = KemKeyGenerator::new(alg).generate().unwrap();
+            let pk = KemPublicKey::new(alg, pk.get_key()).unwrap();
+
+            let der = pk.to_der().unwrap();
+            assert_eq!(KemPublicKey::from_der(&der).unwrap(), pk);
+            let pem = pk.to_pem().unwrap();
+            assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\n"));
+            assert_eq!(KemPublicKey::from_pem(&pem).unwrap(), pk);
+
+            // The OID is that of the algorithm
+            let (oid, raw) = key_encoding::decode_spki(&der).unwrap();
+            assert_eq!(oid, alg.get_oid());
+            assert_eq!(raw, pk.as_bytes());
+        }
+
+        // An unknown OID, a wrong label or trailing data
+        let der = key_encoding::encode_spki("1.2.3.4", &[0u8; 800]).unwrap();
+        assert!(KemPublicKey::from_der(&der).is_err());
+        let (pk, _) = KemKeyGenerator::new(KemAlgorithm::MlKem512)
+            .generate()
+            .unwrap();
+        let pk = KemPublicKey::new(KemAlgorithm::MlKem512, pk.get_key()).unwrap();
+        let pem = pk.to_pem().unwrap().replace("PUBLIC KEY", "PRIVATE KEY");
+        assert!(KemPublicKey::from_pem(&pem).is_err());
+        let mut der = pk.to_der().unwrap();
+        der.push(0);
+        assert!(KemPublicKey::from_der(&der).is_err());
+    }
+}
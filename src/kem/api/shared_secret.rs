@@ -0,0 +1,287 @@
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::kdf::api::KdfManager;
+use crate::kdf::common::kdf_trait::Kdf;
+use crate::kdf::common::kdf_type::KdfType;
+use crate::utils::ct_eq::ct_eq;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The label used when deriving an AEAD key from a shared secret
+const AEAD_KEY_LABEL: &[u8] = b"quantcrypt aead key";
+
+/// The length of the AEAD key derived by `into_aead_key`
+const AEAD_KEY_LEN: usize = 32;
+
+/// The exporter label for channel bindings (RFC 9266, tls-exporter)
+pub const CHANNEL_BINDING_LABEL: &str = "EXPORTER-Channel-Binding";
+
+/// The prefix of the labels used by `export_channel_binding`
+const EXPORTER_LABEL_PREFIX: &[u8] = b"quantcrypt ";
+
+/// HKDF-Expand-Label with SHA-256 (RFC 8446 section 7.1), with this crate's prefix
+fn expand_label(
+    secret: &[u8],
+    label: &[u8],
+    context: &[u8],
+    len: usize,
+) -> Result<Zeroizing<Vec<u8>>> {
+    let full_len = EXPORTER_LABEL_PREFIX.len() + label.len();
+    if label.is_empty() || full_len > 255 || context.len() > 255 {
+        return Err(QuantCryptError::InvalidContext);
+    }
+    let len_bytes = u16::try_from(len).map_err(|_| QuantCryptError::InvalidHkdfLength)?;
+    let mut info = len_bytes.to_be_bytes().to_vec();
+    info.push(full_len as u8);
+    info.extend_from_slice(EXPORTER_LABEL_PREFIX);
+    info.extend_from_slice(label);
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+
+    let mut out = Zeroizing::new(vec![0u8; len]);
+    Hkdf::<Sha256>::from_prk(secret)
+        .map_err(|_| QuantCryptError::KdfError)?
+        .expand(&info, &mut out)
+        .map_err(|_| QuantCryptError::InvalidHkdfLength)?;
+    Ok(out)
+}
+
+/// A shared secret established by a KEM.
+///
+/// The raw bytes are not exposed by default. Use `expand_to_key` or
+/// `into_aead_key` to derive keys from the secret instead of using the KEM
+/// output directly. The bytes are zeroized when the secret is dropped.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{KemAlgorithm, KemKeyGenerator};
+/// use quantcrypt::kdfs::KdfType;
+///
+/// let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768).generate().unwrap();
+/// let (ss, ct) = pk.encap().unwrap();
+///
+/// let key = ss.expand_to_key(KdfType::HkdfWithSha256, b"my protocol", 32).unwrap();
+/// assert_eq!(key.len(), 32);
+/// ```
+#[derive(Clone)]
+pub struct SharedSecret {
+    secret: Vec<u8>,
+}
+
+impl SharedSecret {
+    /// Create a new shared secret from the raw KEM output
+    pub(crate) fn new(secret: Vec<u8>) -> Self {
+        Self { secret }
+    }
+
+    /// Get the length of the shared secret in bytes
+    pub fn len(&self) -> usize {
+        self.secret.len()
+    }
+
+    /// Check if the shared secret is empty
+    pub fn is_empty(&self) -> bool {
+        self.secret.is_empty()
+    }
+
+    /// Derive a key of the given length from the shared secret
+    ///
+    /// # Arguments
+    ///
+    /// * `kdf` - The KDF to use
+    /// * `label` - A label binding the derived key to its purpose
+    /// * `len` - The length of the derived key in bytes
+    ///
+    /// # Returns
+    ///
+    /// The derived key, zeroized on drop
+    pub fn expand_to_key(
+        &self,
+        kdf: KdfType,
+        label: &[u8],
+        len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        let kdf = KdfManager::new(kdf)?;
+        Ok(Zeroizing::new(kdf.derive(
+            &self.secret,
+            label,
+            len,
+            None,
+        )?))
+    }
+
+    /// Consume the shared secret and derive a 256-bit AEAD key from it
+    /// using HKDF-SHA256
+    ///
+    /// # Returns
+    ///
+    /// The AEAD key, zeroized on drop
+    pub fn into_aead_key(self) -> Result<Zeroizing<Vec<u8>>> {
+        self.expand_to_key(KdfType::HkdfWithSha256, AEAD_KEY_LABEL, AEAD_KEY_LEN)
+    }
+
+    /// Compute an exporter value, e.g. for channel binding, from the shared secret
+    /// and the hash of the handshake transcript
+    ///
+    /// This follows the TLS 1.3 exporter (RFC 8446 section 7.5) with SHA-256: the
+    /// exporter secret is HKDF-Extract(transcript_hash, ss), and the value is
+    /// `HKDF-Expand-Label(Derive-Secret(exporter_secret, label, ""), "exporter",
+    /// SHA-256(context), len)`. The labels are prefixed with "quantcrypt " instead of
+    /// "tls13 ". Use `CHANNEL_BINDING_LABEL` with an empty context for a tls-exporter
+    /// style channel binding (RFC 9266).
+    ///
+    /// # Arguments
+    ///
+    /// * `transcript_hash` - The hash of the handshake messages exchanged so far
+    /// * `label` - The exporter label, at most 244 bytes
+    /// * `context` - The exporter context, possibly empty
+    /// * `len` - The length of the value in bytes
+    ///
+    /// # Returns
+    ///
+    /// The exporter value
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidContext` will be returned if the label is empty or too long
+    ///
+    /// # Example
+    /// ```
+    /// use quantcrypt::kems::{KemAlgorithm, KemKeyGenerator, CHANNEL_BINDING_LABEL};
+    ///
+    /// let (pk, sk) = KemKeyGenerator::new(KemAlgorithm::MlKem768).generate().unwrap();
+    /// let (ss, ct) = pk.encap().unwrap();
+    /// let transcript_hash = [0u8; 32];
+    ///
+    /// let cb = ss
+    ///     .export_channel_binding(&transcript_hash, CHANNEL_BINDING_LABEL, b"", 32)
+    ///     .unwrap();
+    /// let cb2 = sk
+    ///     .decap(&ct)
+    ///     .unwrap()
+    ///     .export_channel_binding(&transcript_hash, CHANNEL_BINDING_LABEL, b"", 32)
+    ///     .unwrap();
+    /// assert_eq!(cb, cb2);
+    /// ```
+    pub fn export_channel_binding(
+        &self,
+        transcript_hash: &[u8],
+        label: &str,
+        context: &[u8],
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let (exporter_secret, _) = Hkdf::<Sha256>::extract(Some(transcript_hash), &self.secret);
+        let exporter_secret = Zeroizing::new(exporter_secret.to_vec());
+        let derived = expand_label(
+            &exporter_secret,
+            label.as_bytes(),
+            &Sha256::digest(b""),
+            Sha256::output_size(),
+        )?;
+        Ok(expand_label(&derived, b"exporter", &Sha256::digest(context), len)?.to_vec())
+    }
+
+    /// Get the raw bytes of the shared secret.
+    ///
+    /// Prefer `expand_to_key` unless a protocol requires the raw KEM output.
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.secret
+    }
+}
+
+impl Drop for SharedSecret {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl PartialEq for SharedSecret {
+    /// Compare two shared secrets without short-circuiting on the first
+    /// differing byte
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq(&self.secret, &other.secret)
+    }
+}
+
+impl Eq for SharedSecret {}
+
+impl std::fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSecret")
+            .field("len", &self.secret.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_secret() {
+        let ss = SharedSecret::new(vec![1u8; 32]);
+        assert_eq!(ss.len(), 32);
+        assert!(!format!("{:?}", ss).contains("1, 1"));
+
+        let k1 = ss.expand_to_key(KdfType::HkdfWithSha256, b"a", 16).unwrap();
+        let k2 = ss.expand_to_key(KdfType::HkdfWithSha256, b"b", 16).unwrap();
+        assert_eq!(k1.len(), 16);
+        assert_ne!(k1, k2);
+        assert_ne!(*k1, ss.expose_secret()[..16]);
+
+        assert_eq!(ss, ss.clone());
+        assert_ne!(ss, SharedSecret::new(vec![2u8; 32]));
+
+        let aead_key = ss.clone().into_aead_key().unwrap();
+        assert_eq!(aead_key.len(), 32);
+        assert_ne!(aead_key.as_slice(), ss.expose_secret());
+    }
+
+    #[test]
+    fn test_export_channel_binding() {
+        let ss = SharedSecret::new(vec![1u8; 32]);
+        let th = [2u8; 32];
+        let cb = ss
+            .export_channel_binding(&th, CHANNEL_BINDING_LABEL, b"", 32)
+            .unwrap();
+        assert_eq!(cb.len(), 32);
+        assert_eq!(
+            cb,
+            ss.export_channel_binding(&th, CHANNEL_BINDING_LABEL, b"", 32)
+                .unwrap()
+        );
+
+        // Each input is bound to the value
+        for other in [
+            ss.export_channel_binding(&[3u8; 32], CHANNEL_BINDING_LABEL, b"", 32),
+            ss.export_channel_binding(&th, "EXPORTER-other", b"", 32),
+            ss.export_channel_binding(&th, CHANNEL_BINDING_LABEL, b"ctx", 32),
+            SharedSecret::new(vec![4u8; 32]).export_channel_binding(
+                &th,
+                CHANNEL_BINDING_LABEL,
+                b"",
+                32,
+            ),
+        ] {
+            assert_ne!(other.unwrap(), cb);
+        }
+        assert_eq!(
+            ss.export_channel_binding(&th, CHANNEL_BINDING_LABEL, b"", 48)
+                .unwrap()
+                .len(),
+            48
+        );
+
+        assert_eq!(
+            ss.export_channel_binding(&th, "", b"", 32),
+            Err(QuantCryptError::InvalidContext)
+        );
+        assert_eq!(
+            ss.export_channel_binding(&th, &"a".repeat(245), b"", 32),
+            Err(QuantCryptError::InvalidContext)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use zeroize::Zeroizing;
+
+use crate::kem::common::kem_type::KemType;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Check that a KEM type can be the traditional component of a composite KEM
+fn check_traditional(kem_type: &KemType) -> Result<()> {
+    match kem_type {
+        KemType::RsaOAEP2048
+        | KemType::RsaOAEP3072
+        | KemType::RsaOAEP4096
+        | KemType::P256
+        | KemType::P384
+        | KemType::X25519
+        | KemType::BrainpoolP256r1
+        | KemType::BrainpoolP384r1
+        | KemType::X448 => Ok(()),
+        _ => Err(QuantCryptError::NotImplemented),
+    }
+}
+
+/// The traditional component of a composite KEM public key
+///
+/// The key is encoded as for the traditional KEM of `KemManager`: a PKCS#1
+/// `RSAPublicKey` for RSA-OAEP, an uncompressed point for the NIST and
+/// Brainpool curves and the raw u-coordinate for X25519 and X448.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraditionalKemPublicKey {
+    kem_type: KemType,
+    pk: Vec<u8>,
+}
+
+impl TraditionalKemPublicKey {
+    /// Create a new traditional public key
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The traditional KEM the key belongs to
+    /// * `pk` - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// The public key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` will be returned if the KEM is not a traditional KEM
+    pub fn new(kem_type: KemType, pk: &[u8]) -> Result<Self> {
+        check_traditional(&kem_type)?;
+        Ok(Self {
+            kem_type,
+            pk: pk.to_vec(),
+        })
+    }
+
+    /// Get the traditional KEM the key belongs to
+    pub fn get_kem_type(&self) -> KemType {
+        self.kem_type.clone()
+    }
+
+    /// Get the public key bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.pk
+    }
+}
+
+/// The traditional component of a composite KEM private key
+///
+/// The private key is encoded as for the traditional KEM of `KemManager`: a
+/// PKCS#1 `RSAPrivateKey` for RSA-OAEP and the raw secret scalar for the
+/// curves. The public key is kept along with it, as the composite KEM
+/// combiner needs it to decapsulate. The private key bytes are zeroized when
+/// the key is dropped.
+#[derive(Clone)]
+pub struct TraditionalKemPrivateKey {
+    kem_type: KemType,
+    sk: Zeroizing<Vec<u8>>,
+    pk: Vec<u8>,
+}
+
+impl TraditionalKemPrivateKey {
+    /// Create a new traditional private key
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The traditional KEM the key belongs to
+    /// * `sk` - The private key bytes
+    /// * `pk` - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// The private key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` will be returned if the KEM is not a traditional KEM
+    pub fn new(kem_type: KemType, sk: &[u8], pk: &[u8]) -> Result<Self> {
+        check_traditional(&kem_type)?;
+        Ok(Self {
+            kem_type,
+            sk: Zeroizing::new(sk.to_vec()),
+            pk: pk.to_vec(),
+        })
+    }
+
+    /// Get the traditional KEM the key belongs to
+    pub fn get_kem_type(&self) -> KemType {
+        self.kem_type.clone()
+    }
+
+    /// Get the private key bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.sk
+    }
+
+    /// Get the public key
+    pub fn get_public_key(&self) -> TraditionalKemPublicKey {
+        TraditionalKemPublicKey {
+            kem_type: self.kem_type.clone(),
+            pk: self.pk.clone(),
+        }
+    }
+}
@@ -0,0 +1,1018 @@
+use rand_core::{CryptoRngCore, RngCore};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_384, Shake256};
+use zeroize::Zeroizing;
+
+use crate::kem::common::combined_kem::{Combiner, CombinerInput, KdfCombiner};
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::ec_kem::EcKemManager;
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The number of iterations of the BGF decoder
+const NB_ITER: usize = 5;
+
+/// The gray zone of the BGF decoder, below the threshold
+const TAU: u32 = 3;
+
+/// The scale of the fixed-point coefficients of the decoder threshold
+const THRESHOLD_SCALE: u64 = 100_000_000;
+
+/// The length of the message m, the secret sigma and the shared secret
+const ELL_LEN: usize = 32;
+
+/// The length of the randomness drawn for key generation (the seed of h0 and h1, then sigma)
+/// and for encapsulation (m, followed by 32 unused bytes, as in the reference implementation)
+const SEEDS_LEN: usize = 2 * ELL_LEN;
+
+/// The parameters of a BIKE security level (round 4 specification, version 5.1)
+#[derive(Clone, Copy)]
+struct BikeParams {
+    /// The block length r
+    r: usize,
+    /// The weight of each half of the secret key
+    d: usize,
+    /// The weight of the error vector
+    t: usize,
+    /// The threshold of the decoder is max(floor(a * |s| + b), min) for a syndrome s,
+    /// with a and b scaled by `THRESHOLD_SCALE` so that it is computed exactly
+    threshold_a: u64,
+    threshold_b: u64,
+    threshold_min: u32,
+}
+
+const BIKE_L1: BikeParams = BikeParams {
+    r: 12323,
+    d: 71,
+    t: 134,
+    threshold_a: 697_220,
+    threshold_b: 1_353_000_000,
+    threshold_min: 36,
+};
+
+const BIKE_L3: BikeParams = BikeParams {
+    r: 24659,
+    d: 103,
+    t: 199,
+    threshold_a: 526_500,
+    threshold_b: 1_525_880_000,
+    threshold_min: 52,
+};
+
+const BIKE_L5: BikeParams = BikeParams {
+    r: 40973,
+    d: 137,
+    t: 264,
+    threshold_a: 402_312,
+    threshold_b: 1_787_850_000,
+    threshold_min: 69,
+};
+
+impl BikeParams {
+    /// The number of words of a polynomial of R = F2[x]/(x^r - 1)
+    fn words(&self) -> usize {
+        self.r.div_ceil(64)
+    }
+
+    /// The length of an encoded polynomial, which is the public key
+    fn r_bytes(&self) -> usize {
+        self.r.div_ceil(8)
+    }
+
+    fn pk_len(&self) -> usize {
+        self.r_bytes()
+    }
+
+    /// The secret key is the supports of h0 and h1 (32-bit little-endian
+    /// indices), h0, h1, the public key and sigma
+    fn sk_len(&self) -> usize {
+        2 * 4 * self.d + 3 * self.r_bytes() + ELL_LEN
+    }
+
+    /// The ciphertext is c0 followed by c1
+    fn ct_len(&self) -> usize {
+        self.r_bytes() + ELL_LEN
+    }
+
+    /// Get the threshold of a decoder iteration from the weight of the syndrome
+    ///
+    /// The division by the constant scale compiles to a multiplication and a
+    /// shift, and the maximum is taken with a mask, so the time doesn't depend
+    /// on the weight.
+    fn threshold(&self, syndrome_weight: u32) -> u32 {
+        let t = ((self.threshold_a * syndrome_weight as u64 + self.threshold_b) / THRESHOLD_SCALE)
+            as u32;
+        let mask = ge_mask(t, self.threshold_min);
+        (t & mask) | (self.threshold_min & !mask)
+    }
+}
+
+// ----- constant-time helpers
+
+/// All ones if a == b, 0 otherwise
+fn eq_mask(a: u32, b: u32) -> u32 {
+    0u32.wrapping_sub((((a ^ b) as u64).wrapping_sub(1) >> 63) as u32)
+}
+
+/// All ones if a >= b, 0 otherwise
+fn ge_mask(a: u32, b: u32) -> u32 {
+    ((((a as u64).wrapping_sub(b as u64)) >> 63) as u32).wrapping_sub(1)
+}
+
+/// Widen a mask of all ones or 0 to a word
+fn word_mask(mask: u32) -> u64 {
+    0u64.wrapping_sub((mask & 1) as u64)
+}
+
+// ----- polynomials of R = F2[x]/(x^r - 1), stored as little-endian 64-bit words
+
+/// Clear the bits of the last word beyond r
+fn clear_top(a: &mut [u64], r: usize) {
+    if r % 64 != 0 {
+        if let Some(last) = a.last_mut() {
+            *last &= (1u64 << (r % 64)) - 1;
+        }
+    }
+}
+
+/// Shift the bits of a towards the top, dropping the bits shifted out of the words
+fn shl(a: &[u64], k: usize) -> Vec<u64> {
+    let (ws, bs) = (k / 64, k % 64);
+    let mut out = vec![0u64; a.len()];
+    for (i, o) in out.iter_mut().enumerate().skip(ws) {
+        *o = a[i - ws] << bs;
+        if bs > 0 && i > ws {
+            *o |= a[i - ws - 1] >> (64 - bs);
+        }
+    }
+    out
+}
+
+/// Shift the bits of a towards the bottom
+fn shr(a: &[u64], k: usize) -> Vec<u64> {
+    let (ws, bs) = (k / 64, k % 64);
+    let mut out = vec![0u64; a.len()];
+    for (i, o) in out.iter_mut().enumerate().take(a.len().saturating_sub(ws)) {
+        *o = a[i + ws] >> bs;
+        if bs > 0 && i + ws + 1 < a.len() {
+            *o |= a[i + ws + 1] << (64 - bs);
+        }
+    }
+    out
+}
+
+/// x^k * a, for a public 0 < k < r
+fn rotate(a: &[u64], k: usize, r: usize) -> Vec<u64> {
+    let mut out = shl(a, k);
+    clear_top(&mut out, r);
+    for (o, x) in out.iter_mut().zip(shr(a, r - k)) {
+        *o |= x;
+    }
+    out
+}
+
+/// x^q * a, for a secret q <= r
+///
+/// The rotation is composed of the rotations by the powers of two of the bits
+/// of q, each selected with a mask, so the memory accesses don't depend on q.
+/// As r isn't a power of two, every bit of q <= r stands for less than r.
+fn rotate_ct(a: &[u64], q: u32, r: usize) -> Vec<u64> {
+    let mut out = a.to_vec();
+    let mut k = 1;
+    let mut bit = 0;
+    while k < r {
+        let rotated = rotate(&out, k, r);
+        let mask = 0u64.wrapping_sub(((q >> bit) & 1) as u64);
+        for (o, x) in out.iter_mut().zip(rotated) {
+            *o ^= (*o ^ x) & mask;
+        }
+        k <<= 1;
+        bit += 1;
+    }
+    out
+}
+
+/// a * h for a sparse h, given by its support
+fn mul_sparse(a: &[u64], support: &[u32], r: usize) -> Vec<u64> {
+    let mut out = vec![0u64; a.len()];
+    for &q in support {
+        for (o, x) in out.iter_mut().zip(rotate_ct(a, q, r)) {
+            *o ^= x;
+        }
+    }
+    out
+}
+
+/// The polynomial of nbits bits with the given support, set in constant time
+fn from_support(support: &[u32], nbits: usize) -> Vec<u64> {
+    let mut out = vec![0u64; nbits.div_ceil(64)];
+    for &p in support {
+        let bit = 1u64 << (p % 64);
+        for (i, o) in out.iter_mut().enumerate() {
+            *o |= bit & word_mask(eq_mask(i as u32, p / 64));
+        }
+    }
+    out
+}
+
+/// The Hamming weight
+fn weight(a: &[u64]) -> u32 {
+    a.iter().map(|w| w.count_ones()).sum()
+}
+
+fn poly_to_bytes(a: &[u64], params: &BikeParams) -> Vec<u8> {
+    a.iter()
+        .flat_map(|w| w.to_le_bytes())
+        .take(params.r_bytes())
+        .collect()
+}
+
+/// Decode a polynomial, rejecting bits set beyond r
+fn poly_from_bytes(bytes: &[u8], params: &BikeParams) -> Option<Vec<u64>> {
+    if bytes.len() != params.r_bytes() {
+        return None;
+    }
+    let mut a = vec![0u64; params.words()];
+    for (i, b) in bytes.iter().enumerate() {
+        a[i / 8] |= (*b as u64) << (8 * (i % 8));
+    }
+    let mut cleared = a.clone();
+    clear_top(&mut cleared, params.r);
+    (cleared == a).then_some(a)
+}
+
+/// Invert h0 in R, with the constant-time divsteps of Bernstein and Yang
+///
+/// h0 has odd weight d < r, so it is coprime with x^r - 1 = (x - 1) * Phi_r.
+/// f starts as x^r - 1 and g as h0, both reversed, and the 2r - 1 divsteps
+/// leave the reversed inverse in v.
+fn invert(h0: &[u64], params: &BikeParams) -> Vec<u64> {
+    let r = params.r;
+    // r + 1 bits, for the coefficients 0..=r of the reversed polynomials
+    let n = (r + 1).div_ceil(64);
+    let bit = |a: &[u64], i: usize| (a[i / 64] >> (i % 64)) & 1;
+
+    let mut f = vec![0u64; n];
+    f[0] = 1;
+    f[r / 64] |= 1u64 << (r % 64);
+    let mut g = vec![0u64; n];
+    for i in 0..r {
+        g[(r - 1 - i) / 64] |= bit(h0, i) << ((r - 1 - i) % 64);
+    }
+    let mut v = vec![0u64; n];
+    let mut w = vec![0u64; n];
+    w[0] = 1;
+    let mut delta: i64 = 1;
+
+    for _ in 0..2 * r - 1 {
+        v = shl(&v, 1);
+        let g0 = g[0] & 1;
+        // Swap if delta > 0 and g(0) = 1
+        let swap = (((-delta) >> 63) as u64) & g0.wrapping_neg();
+        delta ^= (swap as i64) & (delta ^ -delta);
+        delta += 1;
+        for (a, b) in f.iter_mut().zip(g.iter_mut()) {
+            let t = swap & (*a ^ *b);
+            *a ^= t;
+            *b ^= t;
+        }
+        for (a, b) in v.iter_mut().zip(w.iter_mut()) {
+            let t = swap & (*a ^ *b);
+            *a ^= t;
+            *b ^= t;
+        }
+        // f(0) is always 1, so g(0) is cancelled by adding g(0) * f
+        let mask = g0.wrapping_neg();
+        for (a, b) in g.iter_mut().zip(&f) {
+            *a ^= mask & b;
+        }
+        for (a, b) in w.iter_mut().zip(&v) {
+            *a ^= mask & b;
+        }
+        g = shr(&g, 1);
+    }
+    debug_assert_eq!(delta, 0);
+
+    let mut out = vec![0u64; params.words()];
+    for i in 0..r {
+        out[i / 64] |= bit(&v, r - 1 - i) << (i % 64);
+    }
+    out
+}
+
+// ----- hashes and sampling
+
+/// Sample wt distinct indices below z from a SHAKE256 stream, in constant time
+///
+/// From the last index down, pos_i = i + floor(s * (z - i) / 2^32) for the next
+/// 32-bit word s of the stream, and an index equal to a later one is replaced
+/// by i, which no later index can be.
+fn sample_indices(xof: &mut impl XofReader, wt: usize, z: usize) -> Zeroizing<Vec<u32>> {
+    let mut out = Zeroizing::new(vec![0u32; wt]);
+    let mut s = Zeroizing::new([0u8; 4]);
+    for i in (0..wt).rev() {
+        xof.read(s.as_mut_slice());
+        let pos = i as u32 + ((u32::from_le_bytes(*s) as u64 * (z - i) as u64) >> 32) as u32;
+        let mut dup = 0u32;
+        for j in i + 1..wt {
+            dup |= eq_mask(pos, out[j]);
+        }
+        out[i] = (pos & !dup) | (i as u32 & dup);
+    }
+    out
+}
+
+fn shake256(seed: &[u8]) -> impl XofReader {
+    let mut shake = Shake256::default();
+    shake.update(seed);
+    shake.finalize_xof()
+}
+
+/// The error vector of weight t of a message, H(m) = (e0, e1)
+///
+/// # Returns
+///
+/// e0, e1 and the indices of the error vector, in 0..2r
+#[allow(clippy::type_complexity)]
+fn function_h(m: &[u8], params: &BikeParams) -> (Vec<u64>, Vec<u64>, Zeroizing<Vec<u32>>) {
+    let support = sample_indices(&mut shake256(m), params.t, 2 * params.r);
+    let e = from_support(&support, 2 * params.r);
+    let mut e0 = e[..params.words()].to_vec();
+    clear_top(&mut e0, params.r);
+    let mut e1 = shr(&e, params.r);
+    e1.truncate(params.words());
+    (e0, e1, support)
+}
+
+/// L(e0, e1), the first 256 bits of SHA3-384(e0 || e1)
+fn function_l(e0: &[u64], e1: &[u64], params: &BikeParams) -> [u8; ELL_LEN] {
+    let input = Zeroizing::new([poly_to_bytes(e0, params), poly_to_bytes(e1, params)].concat());
+    let mut out = [0u8; ELL_LEN];
+    out.copy_from_slice(&Sha3_384::digest(&input)[..ELL_LEN]);
+    out
+}
+
+/// K(m, c0, c1), the first 256 bits of SHA3-384(m || c0 || c1)
+fn function_k(m: &[u8], c0: &[u8], c1: &[u8]) -> Vec<u8> {
+    let input = Zeroizing::new([m, c0, c1].concat());
+    Sha3_384::digest(&input)[..ELL_LEN].to_vec()
+}
+
+// ----- decoder
+
+/// The syndrome s0 + e0 * h0 + e1 * h1
+fn syndrome(s0: &[u64], e0: &[u64], e1: &[u64], supports: [&[u32]; 2], r: usize) -> Vec<u64> {
+    let mut s = s0.to_vec();
+    for (e, support) in [e0, e1].into_iter().zip(supports) {
+        for (o, x) in s.iter_mut().zip(mul_sparse(e, support, r)) {
+            *o ^= x;
+        }
+    }
+    s
+}
+
+/// The number of unsatisfied parity checks of every position of a half of the
+/// error vector, |s AND x^j * h| for j in 0..r
+fn counters(s: &[u64], support: &[u32], r: usize) -> Vec<u8> {
+    let mut ctr = vec![0u8; r];
+    for &q in support {
+        // (x^-q * s)_j = s_(j+q)
+        let rotated = rotate_ct(s, r as u32 - q, r);
+        for (j, c) in ctr.iter_mut().enumerate() {
+            *c += ((rotated[j / 64] >> (j % 64)) & 1) as u8;
+        }
+    }
+    ctr
+}
+
+/// The flips of a bit flipping step, (ctr_j >= threshold) masked by mask
+fn flips(ctr: &[u8], threshold: u32, mask: Option<&[u64]>) -> Vec<u64> {
+    let mut out = vec![0u64; ctr.len().div_ceil(64)];
+    for (j, c) in ctr.iter().enumerate() {
+        out[j / 64] |= ((ge_mask(*c as u32, threshold) & 1) as u64) << (j % 64);
+    }
+    if let Some(mask) = mask {
+        for (o, m) in out.iter_mut().zip(mask) {
+            *o &= m;
+        }
+    }
+    out
+}
+
+/// The Black-Gray-Flip decoder
+///
+/// The first iteration flips the positions over the threshold (black) and
+/// remembers those just below it (gray), then runs a bit flipping step with
+/// the threshold (d + 1) / 2 + 1 on each of them. The decoder always runs all
+/// NB_ITER iterations, and the caller checks the result against H(m').
+///
+/// # Returns
+///
+/// The error vector (e0, e1)
+fn bgf_decode(s0: &[u64], supports: [&[u32]; 2], params: &BikeParams) -> (Vec<u64>, Vec<u64>) {
+    let r = params.r;
+    let mut e = [vec![0u64; params.words()], vec![0u64; params.words()]];
+    for iteration in 0..NB_ITER {
+        let s = syndrome(s0, &e[0], &e[1], supports, r);
+        let threshold = params.threshold(weight(&s));
+        let mut black = Vec::with_capacity(2);
+        let mut gray = Vec::with_capacity(2);
+        for (e_half, support) in e.iter_mut().zip(supports) {
+            let ctr = counters(&s, support, r);
+            let flipped = flips(&ctr, threshold, None);
+            let over_gray = flips(&ctr, threshold.saturating_sub(TAU), None);
+            gray.push(
+                over_gray
+                    .iter()
+                    .zip(&flipped)
+                    .map(|(g, b)| g & !b)
+                    .collect::<Vec<u64>>(),
+            );
+            for (x, f) in e_half.iter_mut().zip(&flipped) {
+                *x ^= f;
+            }
+            black.push(flipped);
+        }
+
+        if iteration == 0 {
+            let masked_threshold = (params.d as u32 + 1) / 2 + 1;
+            for masks in [&black, &gray] {
+                let s = syndrome(s0, &e[0], &e[1], supports, r);
+                for ((e_half, support), mask) in e.iter_mut().zip(supports).zip(masks) {
+                    let ctr = counters(&s, support, r);
+                    for (x, f) in
+                        e_half
+                            .iter_mut()
+                            .zip(flips(&ctr, masked_threshold, Some(mask.as_slice())))
+                    {
+                        *x ^= f;
+                    }
+                }
+            }
+        }
+    }
+    let [e0, e1] = e;
+    (e0, e1)
+}
+
+// ----- the KEM
+
+/// Generate a key pair from the key generation randomness
+///
+/// # Arguments
+///
+/// * `seeds` - The seed of h0 and h1, followed by sigma
+///
+/// # Returns
+///
+/// A tuple containing the public and secret keys (pk, sk)
+fn bike_key_gen(seeds: &[u8; SEEDS_LEN], params: &BikeParams) -> (Vec<u8>, Vec<u8>) {
+    let (seed, sigma) = seeds.split_at(ELL_LEN);
+    let mut xof = shake256(seed);
+    let w0 = sample_indices(&mut xof, params.d, params.r);
+    let w1 = sample_indices(&mut xof, params.d, params.r);
+    let h0 = Zeroizing::new(from_support(&w0, params.r));
+    let h1 = Zeroizing::new(from_support(&w1, params.r));
+
+    // h = h1 * h0^-1
+    let h0_inv = Zeroizing::new(invert(&h0, params));
+    let pk = poly_to_bytes(&mul_sparse(&h0_inv, &w1, params.r), params);
+
+    let mut sk = Vec::with_capacity(params.sk_len());
+    for idx in w0.iter().chain(w1.iter()) {
+        sk.extend_from_slice(&idx.to_le_bytes());
+    }
+    sk.extend_from_slice(&poly_to_bytes(&h0, params));
+    sk.extend_from_slice(&poly_to_bytes(&h1, params));
+    sk.extend_from_slice(&pk);
+    sk.extend_from_slice(sigma);
+    (pk, sk)
+}
+
+/// Encapsulate to a public key with the message m
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and ciphertext (ss, ct)
+fn bike_encap(pk: &[u8], m: &[u8], params: &BikeParams) -> Result<(Vec<u8>, Vec<u8>)> {
+    let h = poly_from_bytes(pk, params).ok_or(QuantCryptError::InvalidPublicKey)?;
+    if m.len() != ELL_LEN {
+        return Err(QuantCryptError::InvalidCoinsLength {
+            expected: ELL_LEN,
+            actual: m.len(),
+        });
+    }
+    let r = params.r as u32;
+    let (e0, e1, support) = function_h(m, params);
+    let (e0, e1) = (Zeroizing::new(e0), Zeroizing::new(e1));
+
+    // c0 = e0 + e1 * h, with the indices of e1 offset by r
+    let mut c0 = e0.to_vec();
+    for &p in support.iter() {
+        let in_e1 = ge_mask(p, r);
+        let rotated = rotate_ct(&h, p.wrapping_sub(r) & in_e1, params.r);
+        for (c, x) in c0.iter_mut().zip(rotated) {
+            *c ^= x & word_mask(in_e1);
+        }
+    }
+    let c0 = poly_to_bytes(&c0, params);
+    let c1: Vec<u8> = function_l(&e0, &e1, params)
+        .iter()
+        .zip(m)
+        .map(|(l, m)| l ^ m)
+        .collect();
+
+    let ss = function_k(m, &c0, &c1);
+    Ok((ss, [c0, c1].concat()))
+}
+
+/// Decapsulate a ciphertext
+///
+/// A ciphertext that fails to decode, or whose error vector isn't H(m'), is
+/// implicitly rejected with the shared secret K(sigma, c0, c1).
+fn bike_decap(sk: &[u8], ct: &[u8], params: &BikeParams) -> Result<Vec<u8>> {
+    if sk.len() != params.sk_len() {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    if ct.len() != params.ct_len() {
+        return Err(QuantCryptError::InvalidCiphertext);
+    }
+    let (wlist, rest) = sk.split_at(2 * 4 * params.d);
+    let sigma = &rest[rest.len() - ELL_LEN..];
+    let wlist = Zeroizing::new(
+        wlist
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect::<Vec<u32>>(),
+    );
+    if wlist.iter().any(|&q| q as usize >= params.r) {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    let (w0, w1) = wlist.split_at(params.d);
+
+    let (c0_bytes, c1) = ct.split_at(params.r_bytes());
+    let c0 = poly_from_bytes(c0_bytes, params).ok_or(QuantCryptError::InvalidCiphertext)?;
+
+    let s0 = Zeroizing::new(mul_sparse(&c0, w0, params.r));
+    let (e0, e1) = bgf_decode(&s0, [w0, w1], params);
+    let (e0, e1) = (Zeroizing::new(e0), Zeroizing::new(e1));
+    let m: Zeroizing<Vec<u8>> = Zeroizing::new(
+        function_l(&e0, &e1, params)
+            .iter()
+            .zip(c1)
+            .map(|(l, c)| l ^ c)
+            .collect(),
+    );
+
+    // Select m' if (e0', e1') = H(m'), sigma otherwise
+    let (h0, h1, _) = function_h(&m, params);
+    let diff = e0
+        .iter()
+        .chain(e1.iter())
+        .zip(h0.iter().chain(h1.iter()))
+        .fold(0u64, |acc, (a, b)| acc | (a ^ b));
+    let valid = (((diff | diff.wrapping_neg()) >> 63) as u8).wrapping_sub(1);
+    let key: Zeroizing<Vec<u8>> = Zeroizing::new(
+        m.iter()
+            .zip(sigma)
+            .map(|(m, s)| (m & valid) | (s & !valid))
+            .collect(),
+    );
+    Ok(function_k(&key, c0_bytes, c1))
+}
+
+// Implement clone
+#[derive(Clone)]
+/// A KEM manager for BIKE, the code-based KEM of the fourth round of the NIST
+/// process, and its hybrids with a traditional KEM
+///
+/// This is a port of the round 4 specification (version 5.1), with the key
+/// and ciphertext formats of the reference implementation. Decoding uses the
+/// Black-Gray-Flip decoder with its fixed number of iterations.
+///
+/// The hybrids concatenate the components, BIKE first:
+///
+/// * pk = pk_BIKE || pk_T
+/// * sk = sk_BIKE || sk_T || pk_T
+/// * ct = ct_BIKE || ct_T
+///
+/// and combine the shared secrets as the composite KEMs do:
+/// `SHA3-256(ss_BIKE || ss_T || ct_T || pk_T || label)`.
+pub struct BikeKemManager {
+    kem_info: KemInfo,
+    params: BikeParams,
+    /// The traditional KEM of a hybrid and its combiner
+    trad: Option<(EcKemManager, KdfCombiner)>,
+    rng: Option<SharedRng>,
+}
+
+impl BikeKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        if let Some((trad, _)) = &mut self.trad {
+            trad.set_rng(rng.clone());
+        }
+        self.rng = Some(rng);
+    }
+
+    /// Get the lengths of the public key, secret key and ciphertext of the traditional KEM
+    fn trad_lens(trad: &EcKemManager) -> (usize, usize, usize) {
+        let info = trad.get_kem_info();
+        (
+            info.pk_byte_len.unwrap_or(0),
+            info.sk_byte_len.unwrap_or(0),
+            info.ct_byte_len.unwrap_or(0),
+        )
+    }
+
+    /// Combine the shared secrets of a hybrid
+    fn combine(
+        combiner: &KdfCombiner,
+        ss_pq: &[u8],
+        ss_t: &[u8],
+        ct_t: &[u8],
+        pk_t: &[u8],
+    ) -> Result<Vec<u8>> {
+        combiner.combine(&CombinerInput {
+            ss_pq,
+            ss_t,
+            ct_pq: &[],
+            ct_t,
+            pk_pq: &[],
+            pk_t,
+        })
+    }
+
+    /// Encapsulate with the message m and, for a hybrid, the coins of the traditional KEM
+    fn encap_with(
+        &mut self,
+        pk: &[u8],
+        m: &[u8],
+        trad_coins: Option<&[u8]>,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let params = self.params;
+        match &mut self.trad {
+            Some((trad, combiner)) => {
+                let (pk_t_len, _, _) = Self::trad_lens(trad);
+                if pk.len() != params.pk_len() + pk_t_len {
+                    return Err(QuantCryptError::InvalidPublicKey);
+                }
+                let (pk_b, pk_t) = pk.split_at(params.pk_len());
+                let (ss_b, ct_b) = bike_encap(pk_b, m, &params)?;
+                let (ss_t, ct_t) = match trad_coins {
+                    Some(coins) => trad.encap_deterministic(pk_t, coins)?,
+                    None => trad.encap(pk_t)?,
+                };
+                let (ss_b, ss_t) = (Zeroizing::new(ss_b), Zeroizing::new(ss_t));
+                let ss = Self::combine(combiner, &ss_b, &ss_t, &ct_t, pk_t)?;
+                Ok((ss, [ct_b, ct_t].concat()))
+            }
+            None => bike_encap(pk, m, &params),
+        }
+    }
+}
+
+impl Kem for BikeKemManager {
+    /// Create a new KEM instance
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    fn new(kem_type: KemType) -> Result<Self> {
+        let (params, trad_type) = match kem_type {
+            KemType::BikeL1 => (BIKE_L1, None),
+            KemType::BikeL3 => (BIKE_L3, None),
+            KemType::BikeL5 => (BIKE_L5, None),
+            KemType::BikeL1X25519 => (BIKE_L1, Some(KemType::X25519)),
+            KemType::BikeL3P384 => (BIKE_L3, Some(KemType::P384)),
+            KemType::BikeL5X448 => (BIKE_L5, Some(KemType::X448)),
+            _ => return Err(QuantCryptError::NotImplemented),
+        };
+        let kem_info = KemInfo::new(kem_type);
+        let trad = match trad_type {
+            Some(trad_type) => {
+                let combiner_info = kem_info
+                    .combiner
+                    .as_ref()
+                    .ok_or(QuantCryptError::NotImplemented)?;
+                Some((
+                    EcKemManager::new(trad_type)?,
+                    KdfCombiner::from_info(combiner_info)?,
+                ))
+            }
+            None => None,
+        };
+        Ok(BikeKemManager {
+            kem_info,
+            params,
+            trad,
+            rng: None,
+        })
+    }
+
+    /// Generate a keypair using the RNG of the manager, or else the default RNG
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.key_gen_with_rng(&mut ManagerRng::for_key_gen(self.rng.as_ref())?)
+    }
+
+    /// Generate a keypair
+    ///
+    /// BIKE draws 64 bytes from the RNG, the seed of the secret key followed
+    /// by sigma, then the traditional KEM of a hybrid generates its key pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut seeds = Zeroizing::new([0u8; SEEDS_LEN]);
+        rng.try_fill_bytes(&mut seeds[..])
+            .map_err(|_| QuantCryptError::KeyPairGenerationFailed)?;
+        let (pk, sk) = bike_key_gen(&seeds, &self.params);
+        match &mut self.trad {
+            Some((trad, _)) => {
+                let (pk_t, sk_t) = trad.key_gen_with_rng(rng)?;
+                Ok(([pk, pk_t.clone()].concat(), [sk, sk_t, pk_t].concat()))
+            }
+            None => Ok((pk, sk)),
+        }
+    }
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut m = Zeroizing::new([0u8; ELL_LEN]);
+        ManagerRng::for_operation(self.rng.as_ref()).fill_bytes(&mut m[..]);
+        self.encap_with(pk, &m[..], None)
+    }
+
+    /// Encapsulate a public key with the given randomness
+    ///
+    /// The coins are the 32 byte message m, followed by the coins of the
+    /// traditional KEM for a hybrid.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `coins` - The randomness of the encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if self.trad.is_none() {
+            return self.encap_with(pk, coins, None);
+        }
+        if coins.len() < ELL_LEN {
+            return Err(QuantCryptError::InvalidCoinsLength {
+                expected: ELL_LEN,
+                actual: coins.len(),
+            });
+        }
+        let (m, trad_coins) = coins.split_at(ELL_LEN);
+        self.encap_with(pk, m, Some(trad_coins))
+    }
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        let params = &self.params;
+        match &self.trad {
+            Some((trad, combiner)) => {
+                let (pk_t_len, sk_t_len, ct_t_len) = Self::trad_lens(trad);
+                if sk.len() != params.sk_len() + sk_t_len + pk_t_len {
+                    return Err(QuantCryptError::InvalidPrivateKey);
+                }
+                if ct.len() != params.ct_len() + ct_t_len {
+                    return Err(QuantCryptError::InvalidCiphertext);
+                }
+                let (sk_b, rest) = sk.split_at(params.sk_len());
+                let (sk_t, pk_t) = rest.split_at(sk_t_len);
+                let (ct_b, ct_t) = ct.split_at(params.ct_len());
+                let ss_b = Zeroizing::new(bike_decap(sk_b, ct_b, params)?);
+                let ss_t = Zeroizing::new(trad.decap(sk_t, ct_t)?);
+                Self::combine(combiner, &ss_b, &ss_t, ct_t, pk_t)
+            }
+            None => bike_decap(sk, ct, params),
+        }
+    }
+
+    /// Get KEM metadata information such as the key lengths
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem_info.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_kem;
+    use crate::utils::ctr_drbg::CtrDrbg;
+    use crate::utils::kat::RspFile;
+
+    #[test]
+    fn test_bike_l1() {
+        let kem = BikeKemManager::new(KemType::BikeL1);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_bike_l3_l5() {
+        for (kem_type, params) in [(KemType::BikeL3, BIKE_L3), (KemType::BikeL5, BIKE_L5)] {
+            let mut kem = BikeKemManager::new(kem_type).unwrap();
+            let (pk, sk) = kem.key_gen().unwrap();
+            assert_eq!(pk.len(), params.pk_len());
+            assert_eq!(sk.len(), params.sk_len());
+            let (ss, ct) = kem.encap(&pk).unwrap();
+            assert_eq!(ct.len(), params.ct_len());
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        }
+    }
+
+    #[test]
+    fn test_bike_reference_vector() {
+        // The first record of the vendored round 4 KAT file. The implicit rejection
+        // of its ciphertext with the first bit flipped was computed with the
+        // reference implementation in liboqs 0.12.0.
+        let params = BIKE_L1;
+        let rsp = RspFile::from_file("test/data/kat/bike_l1.rsp").unwrap();
+        let record = &rsp.get_records()[0];
+        let mut drbg = CtrDrbg::new(&record.get_seed().unwrap(), None);
+        let seeds: [u8; SEEDS_LEN] = drbg.randombytes_vec(SEEDS_LEN).try_into().unwrap();
+        let (pk, sk) = bike_key_gen(&seeds, &params);
+        assert_eq!(pk, record.get_bytes("pk").unwrap());
+        assert_eq!(sk, record.get_bytes("sk").unwrap());
+        let m = drbg.randombytes_vec(64);
+        let (ss, mut ct) = bike_encap(&pk, &m[..32], &params).unwrap();
+        assert_eq!(ct, record.get_bytes("ct").unwrap());
+        assert_eq!(ss, record.get_bytes("ss").unwrap());
+        assert_eq!(bike_decap(&sk, &ct, &params).unwrap(), ss);
+
+        // Implicit rejection, with K(sigma, c0, c1)
+        ct[0] ^= 1;
+        assert_eq!(
+            hex::encode(bike_decap(&sk, &ct, &params).unwrap()),
+            "2f3492f5d7e75f23a30c7db522807aabf6146657eb016d5207923df0d4637fcc"
+        );
+    }
+
+    #[test]
+    fn test_bike_threshold() {
+        // The syndrome weights at which the threshold leaves its minimum, and the
+        // threshold at the largest weight r
+        for (params, last_min, max) in [
+            (BIKE_L1, 3366, 99),
+            (BIKE_L3, 7168, 145),
+            (BIKE_L5, 12955, 182),
+        ] {
+            assert_eq!(params.threshold(0), params.threshold_min);
+            assert_eq!(params.threshold(last_min), params.threshold_min);
+            assert_eq!(params.threshold(last_min + 1), params.threshold_min + 1);
+            assert_eq!(params.threshold(params.r as u32), max);
+        }
+    }
+
+    #[test]
+    fn test_bike_arithmetic() {
+        let params = BIKE_L1;
+        let seeds = [3u8; SEEDS_LEN];
+        let (pk, sk) = bike_key_gen(&seeds, &params);
+
+        // h * h0 = h1
+        let w0: Vec<u32> = sk[..4 * params.d]
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let h = poly_from_bytes(&pk, &params).unwrap();
+        let h1_offset = 8 * params.d + params.r_bytes();
+        assert_eq!(
+            poly_to_bytes(&mul_sparse(&h, &w0, params.r), &params),
+            sk[h1_offset..h1_offset + params.r_bytes()]
+        );
+
+        // The supports have the expected weights
+        let (e0, e1, _) = function_h(&[5u8; ELL_LEN], &params);
+        assert_eq!(weight(&e0) + weight(&e1), params.t as u32);
+        assert_eq!(weight(&from_support(&w0, params.r)), params.d as u32);
+
+        // The constant-time rotation matches the public one, also by r
+        let a = poly_from_bytes(&pk, &params).unwrap();
+        for q in [1, 63, 64, 65, 4096, params.r - 1] {
+            assert_eq!(rotate_ct(&a, q as u32, params.r), rotate(&a, q, params.r));
+        }
+        assert_eq!(rotate_ct(&a, params.r as u32, params.r), a);
+    }
+
+    #[test]
+    fn test_bike_invalid_inputs() {
+        let mut kem = BikeKemManager::new(KemType::BikeL1).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (_, ct) = kem.encap(&pk).unwrap();
+
+        assert!(kem.encap(&pk[1..]).is_err());
+        assert!(kem.decap(&sk[1..], &ct).is_err());
+        assert!(kem.decap(&sk, &ct[1..]).is_err());
+
+        // Bits beyond r
+        let mut bad = pk.clone();
+        *bad.last_mut().unwrap() |= 0x80;
+        assert_eq!(kem.encap(&bad), Err(QuantCryptError::InvalidPublicKey));
+
+        // An index of the secret key out of range
+        let mut bad = sk.clone();
+        bad[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            kem.decap(&bad, &ct),
+            Err(QuantCryptError::InvalidPrivateKey)
+        );
+
+        assert!(kem.encap_deterministic(&pk, &[0u8; 31]).is_err());
+    }
+
+    #[test]
+    fn test_bike_hybrids() {
+        let kem = BikeKemManager::new(KemType::BikeL1X25519);
+        test_kem!(kem);
+
+        let mut kem = BikeKemManager::new(KemType::BikeL1X25519).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        let l1 = BIKE_L1;
+        let ss_b = bike_decap(&sk[..l1.sk_len()], &ct[..l1.ct_len()], &l1).unwrap();
+        let ss_x = EcKemManager::new(KemType::X25519)
+            .unwrap()
+            .decap(&sk[l1.sk_len()..l1.sk_len() + 32], &ct[l1.ct_len()..])
+            .unwrap();
+        let combiner = KdfCombiner::sha3_256(b"BIKE-L1+X25519");
+        let expected = BikeKemManager::combine(
+            &combiner,
+            &ss_b,
+            &ss_x,
+            &ct[l1.ct_len()..],
+            &pk[l1.pk_len()..],
+        )
+        .unwrap();
+        assert_eq!(ss, expected);
+
+        for kem_type in [KemType::BikeL3P384, KemType::BikeL5X448] {
+            let mut kem = BikeKemManager::new(kem_type).unwrap();
+            let info = kem.get_kem_info();
+            let (pk, sk) = kem.key_gen().unwrap();
+            assert_eq!(Some(pk.len()), info.pk_byte_len);
+            assert_eq!(Some(sk.len()), info.sk_byte_len);
+            let (ss, ct) = kem.encap(&pk).unwrap();
+            assert_eq!(Some(ct.len()), info.ct_byte_len);
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::kem::common::combined_kem::{ChempatCombiner, Combiner, CombinerInput};
+use crate::kem::common::config::pk_len::PKLen;
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::utils::entropy::SharedRng;
+use crate::QuantCryptError;
+
+use crate::kem::ec_kem::EcKemManager;
+use crate::kem::ml_kem::MlKemManager;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+// Implement clone
+#[derive(Clone)]
+/// A KEM manager for the Chempat generic hybrid KEM (draft-josefsson-chempat)
+///
+/// Keys and ciphertexts are the concatenation of the traditional and the
+/// post-quantum components, traditional first:
+///
+/// * pk = pk_T || pk_PQ
+/// * sk = sk_T || sk_PQ
+/// * ct = ct_T || ct_PQ
+///
+/// The shared secret is
+/// `SHA3-256(ss_T || ss_PQ || SHA3-256(ct_T || ct_PQ) || SHA3-256(pk_T || pk_PQ) || label)`.
+pub struct ChempatKemManager {
+    kem_info: KemInfo,
+    trad_kem: EcKemManager,
+    trad_kem_type: KemType,
+    pq_kem: MlKemManager,
+    pq_kem_type: KemType,
+    combiner: ChempatCombiner,
+}
+
+impl ChempatKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.trad_kem.set_rng(rng.clone());
+        self.pq_kem.set_rng(rng);
+    }
+
+    /// Split a concatenated value into its traditional and post-quantum parts
+    fn split<'a>(&self, value: &'a [u8], trad_len: usize) -> Result<(&'a [u8], &'a [u8])> {
+        if value.len() < trad_len {
+            return Err(QuantCryptError::InvalidCiphertext);
+        }
+        Ok(value.split_at(trad_len))
+    }
+
+    /// Get the traditional public key from the traditional secret key
+    fn get_trad_pk(&self, sk_t: &[u8]) -> Result<Vec<u8>> {
+        self.trad_kem
+            .get_public_key(sk_t)
+            .map_err(|_| QuantCryptError::InvalidPrivateKey)
+    }
+
+    /// Get the ML-KEM encapsulation key embedded in an ML-KEM decapsulation key
+    ///
+    /// As per FIPS 203, dk = dk_PKE || ek || H(ek) || z, where dk_PKE is 384 * k bytes
+    fn get_pq_pk<'a>(&self, sk_pq: &'a [u8]) -> Result<&'a [u8]> {
+        let pk_len = self
+            .pq_kem_type
+            .get_pk_len()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        let dk_pke_len = pk_len - 32;
+        sk_pq
+            .get(dk_pke_len..dk_pke_len + pk_len)
+            .ok_or(QuantCryptError::InvalidPrivateKey)
+    }
+}
+
+impl Kem for ChempatKemManager {
+    /// Create a new KEM instance
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    fn new(kem_type: KemType) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let (trad_kem_type, pq_kem_type) = match kem_type {
+            KemType::ChempatX25519MlKem768 => (KemType::X25519, KemType::MlKem768),
+            KemType::ChempatX448MlKem1024 => (KemType::X448, KemType::MlKem1024),
+            KemType::ChempatP256MlKem768 => (KemType::P256, KemType::MlKem768),
+            KemType::ChempatP384MlKem1024 => (KemType::P384, KemType::MlKem1024),
+            _ => return Err(QuantCryptError::NotImplemented),
+        };
+        let kem_info = KemInfo::new(kem_type);
+        // The label of the Chempat instance, e.g. `Chempat-X25519-ML-KEM-768`
+        let label = kem_info
+            .combiner
+            .as_ref()
+            .map(|info| info.label.clone())
+            .ok_or(QuantCryptError::NotImplemented)?;
+        Ok(ChempatKemManager {
+            kem_info,
+            trad_kem: EcKemManager::new(trad_kem_type.clone())?,
+            trad_kem_type,
+            pq_kem: MlKemManager::new(pq_kem_type.clone())?,
+            pq_kem_type,
+            combiner: ChempatCombiner::new(&label),
+        })
+    }
+
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem_info.clone()
+    }
+
+    /// Generate a keypair using the default RNG
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_t, sk_t) = self.trad_kem.key_gen()?;
+        let (pk_pq, sk_pq) = self.pq_kem.key_gen()?;
+        Ok(([pk_t, pk_pq].concat(), [sk_t, sk_pq].concat()))
+    }
+
+    /// Generate a keypair
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(
+        &mut self,
+        rng: &mut impl rand_core::CryptoRngCore,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_t, sk_t) = self.trad_kem.key_gen_with_rng(rng)?;
+        let (pk_pq, sk_pq) = self.pq_kem.key_gen_with_rng(rng)?;
+        Ok(([pk_t, pk_pq].concat(), [sk_t, sk_pq].concat()))
+    }
+
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.encap_with_context(pk, &[])
+    }
+
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// The context is appended to the input of SHA3-256, after the label, so an
+    /// empty context derives the Chempat shared secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `context` - The context to bind the shared secret to, e.g. the protocol transcript
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if Some(pk.len()) != self.kem_info.pk_byte_len {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+        let trad_pk_len = self
+            .trad_kem_type
+            .get_pk_len()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        let (pk_t, pk_pq) = pk.split_at(trad_pk_len);
+
+        let (ss_t, ct_t) = self.trad_kem.encap(pk_t)?;
+        let (ss_pq, ct_pq) = self.pq_kem.encap(pk_pq)?;
+
+        let input = CombinerInput {
+            ss_pq: &ss_pq,
+            ss_t: &ss_t,
+            ct_pq: &ct_pq,
+            ct_t: &ct_t,
+            pk_pq,
+            pk_t,
+        };
+        let ss = self.combiner.combine_with_context(&input, context)?;
+        Ok((ss, [ct_t, ct_pq].concat()))
+    }
+
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        self.decap_with_context(sk, ct, &[])
+    }
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `context` - The context given to `encap_with_context`
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+        if Some(ct.len()) != self.kem_info.ct_byte_len {
+            return Err(QuantCryptError::InvalidCiphertext);
+        }
+        if Some(sk.len()) != self.kem_info.sk_byte_len {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        // Traditional ciphertexts are ephemeral public keys
+        let trad_len = self
+            .trad_kem_type
+            .get_pk_len()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        let (ct_t, ct_pq) = self.split(ct, trad_len)?;
+        let trad_sk_len = sk.len() - self.pq_kem.get_kem_info().sk_byte_len.unwrap_or(0);
+        let (sk_t, sk_pq) = sk.split_at(trad_sk_len);
+
+        let pk_t = self.get_trad_pk(sk_t)?;
+        let pk_pq = self.get_pq_pk(sk_pq)?;
+
+        let ss_t = self.trad_kem.decap(sk_t, ct_t)?;
+        let ss_pq = self.pq_kem.decap(sk_pq, ct_pq)?;
+
+        let input = CombinerInput {
+            ss_pq: &ss_pq,
+            ss_t: &ss_t,
+            ct_pq,
+            ct_t,
+            pk_pq,
+            pk_t: &pk_t,
+        };
+        self.combiner.combine_with_context(&input, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::common::kem_trait::Kem;
+    use crate::kem::common::kem_type::KemType;
+    use crate::test_kem;
+
+    #[test]
+    fn test_chempat_x25519_ml_kem_768() {
+        let kem = ChempatKemManager::new(KemType::ChempatX25519MlKem768);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_chempat_x448_ml_kem_1024() {
+        let kem = ChempatKemManager::new(KemType::ChempatX448MlKem1024);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_chempat_p256_ml_kem_768() {
+        let kem = ChempatKemManager::new(KemType::ChempatP256MlKem768);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_chempat_p384_ml_kem_1024() {
+        let kem = ChempatKemManager::new(KemType::ChempatP384MlKem1024);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_chempat_binds_ciphertext() {
+        let mut kem = ChempatKemManager::new(KemType::ChempatX25519MlKem768).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, mut ct) = kem.encap(&pk).unwrap();
+
+        // Tampering with the traditional ciphertext must change the shared secret
+        ct[0] ^= 1;
+        let ss2 = kem.decap(&sk, &ct).unwrap();
+        assert_ne!(ss, ss2);
+
+        assert!(kem.decap(&sk, &ct[1..]).is_err());
+        assert!(kem.encap(&pk[1..]).is_err());
+    }
+
+    #[test]
+    fn test_chempat_binds_context() {
+        let mut kem = ChempatKemManager::new(KemType::ChempatX25519MlKem768).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap_with_context(&pk, b"context").unwrap();
+        assert_eq!(kem.decap_with_context(&sk, &ct, b"context").unwrap(), ss);
+        assert_ne!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // An empty context derives the Chempat shared secret
+        let (ss, ct) = kem.encap_with_context(&pk, &[]).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+    }
+}
@@ -0,0 +1,175 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use zeroize::Zeroizing;
+
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::utils::blocking_pool::BlockingPool;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// An asynchronous KEM, for keys that live on a network HSM or another remote service
+///
+/// This mirrors [`Kem`], with the operations returning futures instead of
+/// blocking the calling thread. The futures are `Send`, so they can be
+/// awaited on multi-threaded runtimes. Use [`BlockingKem`] to run a
+/// synchronous `Kem`, including one backed by a registered
+/// [`KemBackend`](crate::kems::KemBackend), on a pool of blocking threads.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::AsyncKem;
+///
+/// async fn receive(kem: &impl AsyncKem, sk: &[u8], ct: &[u8]) -> Vec<u8> {
+///     kem.decap(sk, ct).await.unwrap()
+/// }
+/// ```
+pub trait AsyncKem: Send + Sync {
+    /// Get the metadata of the KEM
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo;
+
+    /// Generate a keypair using the default RNG
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&self) -> impl Future<Output = Result<(Vec<u8>, Vec<u8>)>> + Send;
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&self, pk: &[u8]) -> impl Future<Output = Result<(Vec<u8>, Vec<u8>)>> + Send;
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> impl Future<Output = Result<Vec<u8>>> + Send;
+}
+
+/// An `AsyncKem` running a synchronous `Kem` on a `BlockingPool`
+///
+/// Each operation clones the KEM and runs it on a worker of the pool with a
+/// copy of its arguments. The clones of a manager created with `new_with_rng`
+/// share its RNG, so concurrent operations take turns drawing from it. The
+/// futures don't depend on a particular async runtime.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{AsyncKem, BlockingKem, Kem, KemManager, KemType};
+///
+/// async fn encap_to(pk: &[u8]) -> (Vec<u8>, Vec<u8>) {
+///     let kem = BlockingKem::new(KemManager::new(KemType::MlKem768).unwrap());
+///     kem.encap(pk).await.unwrap()
+/// }
+/// ```
+#[derive(Clone)]
+pub struct BlockingKem<K> {
+    /// The synchronous KEM
+    kem: K,
+    /// The pool running the operations
+    pool: Arc<BlockingPool>,
+}
+
+impl<K: Kem + Clone + Send + Sync + 'static> BlockingKem<K> {
+    /// Create a new adapter running on the default pool
+    ///
+    /// # Arguments
+    ///
+    /// * `kem` - The synchronous KEM
+    ///
+    /// # Returns
+    ///
+    /// A new adapter
+    pub fn new(kem: K) -> Self {
+        Self::with_pool(kem, BlockingPool::get_default())
+    }
+
+    /// Create a new adapter running on the given pool
+    ///
+    /// # Arguments
+    ///
+    /// * `kem` - The synchronous KEM
+    /// * `pool` - The pool running the operations, e.g. one sized to the
+    ///   number of concurrent sessions the HSM allows
+    ///
+    /// # Returns
+    ///
+    /// A new adapter
+    pub fn with_pool(kem: K, pool: Arc<BlockingPool>) -> Self {
+        Self { kem, pool }
+    }
+
+    /// Get the synchronous KEM
+    ///
+    /// # Returns
+    ///
+    /// The synchronous KEM
+    pub fn get_kem(&self) -> &K {
+        &self.kem
+    }
+}
+
+impl<K: Kem + Clone + Send + Sync + 'static> AsyncKem for BlockingKem<K> {
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem.get_kem_info()
+    }
+
+    fn key_gen(&self) -> impl Future<Output = Result<(Vec<u8>, Vec<u8>)>> + Send {
+        let mut kem = self.kem.clone();
+        self.pool.spawn(move || kem.key_gen())
+    }
+
+    fn encap(&self, pk: &[u8]) -> impl Future<Output = Result<(Vec<u8>, Vec<u8>)>> + Send {
+        let mut kem = self.kem.clone();
+        let pk = pk.to_vec();
+        self.pool.spawn(move || kem.encap(&pk))
+    }
+
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        let kem = self.kem.clone();
+        let sk = Zeroizing::new(sk.to_vec());
+        let ct = ct.to_vec();
+        self.pool.spawn(move || kem.decap(&sk, &ct))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::common::kem_type::KemType;
+    use crate::kem::kem_manager::KemManager;
+    use crate::utils::blocking_pool::block_on;
+
+    #[test]
+    fn test_blocking_kem() {
+        let pool = Arc::new(BlockingPool::new(2));
+        let kem = BlockingKem::with_pool(KemManager::new(KemType::MlKem768).unwrap(), pool);
+        assert_eq!(kem.get_kem_info().kem_type, KemType::MlKem768);
+
+        let (pk, sk) = block_on(kem.key_gen()).unwrap();
+        let (ss, ct) = block_on(kem.encap(&pk)).unwrap();
+        assert_eq!(block_on(kem.decap(&sk, &ct)).unwrap(), ss);
+
+        // Errors of the synchronous KEM are returned by the future
+        assert!(block_on(kem.encap(&pk[1..])).is_err());
+    }
+}
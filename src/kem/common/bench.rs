@@ -0,0 +1,259 @@
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::kem::chempat::ChempatKemManager;
+use crate::kem::common::dyn_kem::DynKem;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::kem_manager::KemManager;
+use crate::kem::xwing::XWingKemManager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The default number of measured iterations per KEM
+const DEFAULT_ITERATIONS: u32 = 100;
+
+/// The default number of iterations run before measuring
+const DEFAULT_WARMUP_ITERATIONS: u32 = 5;
+
+/// The mean duration of the operations of a KEM
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KemBenchmarkResult {
+    /// The KEM that was measured
+    pub kem_type: KemType,
+    /// The number of measured iterations
+    pub iterations: u32,
+    /// The mean duration of a key generation
+    pub key_gen: Duration,
+    /// The mean duration of an encapsulation
+    pub encap: Duration,
+    /// The mean duration of a decapsulation
+    pub decap: Duration,
+}
+
+impl fmt::Display for KemBenchmarkResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<28} keygen {:>12.3?}  encap {:>12.3?}  decap {:>12.3?}",
+            format!("{:?}", self.kem_type),
+            self.key_gen,
+            self.encap,
+            self.decap
+        )
+    }
+}
+
+/// Measures key generation, encapsulation and decapsulation of KEMs
+///
+/// The KEMs are created with `KemManager` where it supports them, so a backend
+/// registered with `register_kem_backend` is measured instead of the built-in
+/// implementation. Every round trip is checked, so a broken backend fails the
+/// run rather than reporting a misleading time. Run it on the target hardware
+/// with an optimized build, e.g. `cargo bench --features bench`.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{KemBenchmarkRunner, KemType};
+///
+/// let mut runner = KemBenchmarkRunner::new();
+/// runner
+///     .set_kem_types(vec![KemType::MlKem768, KemType::X25519])
+///     .set_iterations(3);
+/// for result in runner.run().unwrap() {
+///     println!("{}", result);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct KemBenchmarkRunner {
+    kem_types: Vec<KemType>,
+    iterations: u32,
+    warmup_iterations: u32,
+}
+
+impl Default for KemBenchmarkRunner {
+    fn default() -> Self {
+        KemBenchmarkRunner::new()
+    }
+}
+
+impl KemBenchmarkRunner {
+    /// Create a runner measuring every `KemType`
+    ///
+    /// # Returns
+    ///
+    /// The runner, doing 100 measured iterations per KEM after 5 warm-up iterations
+    pub fn new() -> KemBenchmarkRunner {
+        KemBenchmarkRunner {
+            kem_types: KemType::all(),
+            iterations: DEFAULT_ITERATIONS,
+            warmup_iterations: DEFAULT_WARMUP_ITERATIONS,
+        }
+    }
+
+    /// Set the KEMs to measure
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_types` - The KEMs, measured in order
+    ///
+    /// # Returns
+    ///
+    /// The runner, for chaining
+    pub fn set_kem_types(&mut self, kem_types: Vec<KemType>) -> &mut Self {
+        self.kem_types = kem_types;
+        self
+    }
+
+    /// Set the number of measured iterations per KEM
+    ///
+    /// # Arguments
+    ///
+    /// * `iterations` - The number of iterations, at least 1
+    ///
+    /// # Returns
+    ///
+    /// The runner, for chaining
+    pub fn set_iterations(&mut self, iterations: u32) -> &mut Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Set the number of iterations run before measuring, to warm up caches
+    ///
+    /// # Arguments
+    ///
+    /// * `warmup_iterations` - The number of iterations
+    ///
+    /// # Returns
+    ///
+    /// The runner, for chaining
+    pub fn set_warmup_iterations(&mut self, warmup_iterations: u32) -> &mut Self {
+        self.warmup_iterations = warmup_iterations;
+        self
+    }
+
+    /// Measure every KEM of the runner
+    ///
+    /// # Returns
+    ///
+    /// The results, in the order of the KEMs
+    ///
+    /// # Errors
+    ///
+    /// The error of the first operation that failed, and
+    /// `QuantCryptError::DecapFailed` if a decapsulated secret differs from the encapsulated one
+    pub fn run(&self) -> Result<Vec<KemBenchmarkResult>> {
+        self.kem_types
+            .iter()
+            .map(|kem_type| self.run_kem(kem_type.clone()))
+            .collect()
+    }
+
+    /// Measure a single KEM
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The KEM to measure
+    ///
+    /// # Returns
+    ///
+    /// The result
+    ///
+    /// # Errors
+    ///
+    /// The error of the first operation that failed, and
+    /// `QuantCryptError::DecapFailed` if a decapsulated secret differs from the encapsulated one
+    pub fn run_kem(&self, kem_type: KemType) -> Result<KemBenchmarkResult> {
+        let mut kem = Self::new_kem(kem_type.clone())?;
+        for _ in 0..self.warmup_iterations {
+            Self::round_trip(&mut kem)?;
+        }
+        let mut totals = [Duration::ZERO; 3];
+        for _ in 0..self.iterations {
+            for (total, elapsed) in totals.iter_mut().zip(Self::round_trip(&mut kem)?) {
+                *total += elapsed;
+            }
+        }
+        let [key_gen, encap, decap] = totals.map(|total| total / self.iterations);
+        Ok(KemBenchmarkResult {
+            kem_type,
+            iterations: self.iterations,
+            key_gen,
+            encap,
+            decap,
+        })
+    }
+
+    /// Create the KEM of a type, including those `KemManager` doesn't dispatch to
+    ///
+    /// This is the KEM measured by `run_kem`, for harnesses such as criterion
+    /// that do their own timing.
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The KEM to create
+    ///
+    /// # Returns
+    ///
+    /// The KEM, or the error of its construction, such as `BackendUnavailable`
+    pub fn new_kem(kem_type: KemType) -> Result<Box<dyn DynKem>> {
+        Ok(match kem_type {
+            KemType::XWing | KemType::MlKem1024X448Shake => {
+                Box::new(XWingKemManager::new(kem_type)?)
+            }
+            KemType::ChempatX25519MlKem768
+            | KemType::ChempatX448MlKem1024
+            | KemType::ChempatP256MlKem768
+            | KemType::ChempatP384MlKem1024 => Box::new(ChempatKemManager::new(kem_type)?),
+            _ => Box::new(KemManager::new(kem_type)?),
+        })
+    }
+
+    /// Time a key generation, an encapsulation and a decapsulation
+    fn round_trip(kem: &mut Box<dyn DynKem>) -> Result<[Duration; 3]> {
+        let start = Instant::now();
+        let (pk, sk) = black_box(kem.key_gen()?);
+        let key_gen = start.elapsed();
+
+        let start = Instant::now();
+        let (ss, ct) = black_box(kem.encap(&pk)?);
+        let encap = start.elapsed();
+
+        let start = Instant::now();
+        let decapsulated = black_box(kem.decap(&sk, &ct)?);
+        let decap = start.elapsed();
+
+        if decapsulated != ss {
+            return Err(QuantCryptError::DecapFailed);
+        }
+        Ok([key_gen, encap, decap])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kem_benchmark_runner() {
+        let mut runner = KemBenchmarkRunner::new();
+        assert_eq!(runner.kem_types.len(), KemType::all().len());
+        runner
+            .set_kem_types(vec![KemType::MlKem512, KemType::X25519])
+            .set_iterations(0)
+            .set_warmup_iterations(1);
+        let results = runner.run().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].kem_type, KemType::MlKem512);
+        assert_eq!(results[1].iterations, 1);
+        assert!(results[0].to_string().starts_with("MlKem512"));
+
+        // Every type can be created, including those outside KemManager
+        for kem_type in KemType::all() {
+            assert!(KemBenchmarkRunner::new_kem(kem_type).is_ok());
+        }
+    }
+}
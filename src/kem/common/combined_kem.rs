@@ -0,0 +1,628 @@
+use rand_core::CryptoRngCore;
+use sha3::{Digest, Sha3_256};
+
+use crate::kdf::common::kdf_trait::Kdf as _;
+use crate::kdf::common::kdf_type::KdfType as KmacType;
+use crate::kdf::kmac::Kmac;
+use crate::kem::common::kdf::{Kdf, KdfType};
+use crate::kem::common::kem_info::{CombinerInfo, KemInfo};
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The label of X-Wing, `\./` followed by `/^\`
+pub(crate) const XWING_LABEL: &[u8] = b"\\.//^\\";
+
+/// The values a combiner can bind the shared secret to
+///
+/// Every combiner uses the two shared secrets. Which of the ciphertexts and
+/// public keys it binds depends on the construction.
+pub struct CombinerInput<'a> {
+    /// The shared secret of the post-quantum KEM
+    pub ss_pq: &'a [u8],
+    /// The shared secret of the traditional KEM
+    pub ss_t: &'a [u8],
+    /// The ciphertext of the post-quantum KEM
+    pub ct_pq: &'a [u8],
+    /// The ciphertext of the traditional KEM
+    pub ct_t: &'a [u8],
+    /// The public key of the post-quantum KEM
+    pub pk_pq: &'a [u8],
+    /// The public key of the traditional KEM
+    pub pk_t: &'a [u8],
+}
+
+/// A strategy combining the shared secrets of the components of a hybrid KEM
+pub trait Combiner: Clone {
+    /// Derive the shared secret of the hybrid KEM
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The shared secrets, ciphertexts and public keys of the components
+    ///
+    /// # Returns
+    ///
+    /// The combined shared secret
+    fn combine(&self, input: &CombinerInput) -> Result<Vec<u8>>;
+
+    /// Derive the shared secret of the hybrid KEM, binding it to a context
+    ///
+    /// The built-in combiners append the context to the last input of their
+    /// hash or KDF, and an empty context derives the same secret as `combine`.
+    /// A combiner that doesn't override this refuses a non-empty context
+    /// rather than ignoring it.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The shared secrets, ciphertexts and public keys of the components
+    /// * `context` - The context to bind the shared secret to, e.g. a protocol transcript
+    ///
+    /// # Returns
+    ///
+    /// The combined shared secret
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the context isn't empty and the
+    /// combiner can't bind it
+    fn combine_with_context(&self, input: &CombinerInput, context: &[u8]) -> Result<Vec<u8>> {
+        if !context.is_empty() {
+            return Err(QuantCryptError::NotImplemented);
+        }
+        self.combine(input)
+    }
+
+    /// Get the length of the combined shared secret in bytes
+    ///
+    /// # Returns
+    ///
+    /// The length of the shared secret
+    fn get_ss_len(&self) -> usize;
+}
+
+/// The combiner of the composite KEM drafts
+///
+/// `ss = KDF(ss_pq || ss_t || ct_t || pk_t || label)`, where the label is the
+/// DER encoded OID of the composite KEM.
+#[derive(Clone)]
+pub struct KdfCombiner {
+    kdf: Kdf,
+    ss_len: usize,
+    label: Vec<u8>,
+}
+
+impl KdfCombiner {
+    /// Create a combiner with a KDF of the KEM module
+    pub(crate) fn new(kdf_type: KdfType, label: &[u8]) -> KdfCombiner {
+        let ss_len = match kdf_type {
+            KdfType::HkdfSha256 | KdfType::Sha3_256 => 32,
+            KdfType::HkdfSha384 | KdfType::Sha3_384 => 48,
+            KdfType::Sha3_512 => 64,
+        };
+        KdfCombiner {
+            kdf: Kdf::new(kdf_type),
+            ss_len,
+            label: label.to_vec(),
+        }
+    }
+
+    /// Create the combiner described by the KEM info of a hybrid KEM
+    pub(crate) fn from_info(info: &CombinerInfo) -> Result<KdfCombiner> {
+        let kdf_type = KdfType::from_name(&info.kdf).ok_or(QuantCryptError::NotImplemented)?;
+        Ok(KdfCombiner::new(kdf_type, &info.label))
+    }
+
+    /// Create a combiner hashing the input with SHA3-256
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The domain separator
+    ///
+    /// # Returns
+    ///
+    /// The combiner
+    pub fn sha3_256(label: &[u8]) -> KdfCombiner {
+        KdfCombiner::new(KdfType::Sha3_256, label)
+    }
+
+    /// Create a combiner deriving the shared secret with HKDF-SHA256
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The domain separator
+    ///
+    /// # Returns
+    ///
+    /// The combiner
+    pub fn hkdf_sha256(label: &[u8]) -> KdfCombiner {
+        KdfCombiner::new(KdfType::HkdfSha256, label)
+    }
+}
+
+impl Combiner for KdfCombiner {
+    fn combine(&self, input: &CombinerInput) -> Result<Vec<u8>> {
+        self.combine_with_context(input, &[])
+    }
+
+    /// `ss = KDF(ss_pq || ss_t || ct_t || pk_t || label || context)`. The other
+    /// inputs have fixed lengths for a KEM, so the context needs no length prefix.
+    fn combine_with_context(&self, input: &CombinerInput, context: &[u8]) -> Result<Vec<u8>> {
+        let ikm = [
+            input.ss_pq,
+            input.ss_t,
+            input.ct_t,
+            input.pk_t,
+            self.label.as_slice(),
+            context,
+        ]
+        .concat();
+        Ok(self.kdf.kdf(&ikm))
+    }
+
+    fn get_ss_len(&self) -> usize {
+        self.ss_len
+    }
+}
+
+/// A combiner based on KMAC256
+///
+/// `ss = KMAC256(K = ss_pq || ss_t, X = ct_t || pk_t, L = 256, S = label)`
+#[derive(Clone)]
+pub struct KmacCombiner {
+    label: Vec<u8>,
+}
+
+impl KmacCombiner {
+    /// Create a KMAC256 combiner
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The customization string
+    ///
+    /// # Returns
+    ///
+    /// The combiner
+    pub fn new(label: &[u8]) -> KmacCombiner {
+        KmacCombiner {
+            label: label.to_vec(),
+        }
+    }
+}
+
+impl Combiner for KmacCombiner {
+    fn combine(&self, input: &CombinerInput) -> Result<Vec<u8>> {
+        self.combine_with_context(input, &[])
+    }
+
+    /// The context is appended to X: `X = ct_t || pk_t || context`
+    fn combine_with_context(&self, input: &CombinerInput, context: &[u8]) -> Result<Vec<u8>> {
+        let key = [input.ss_pq, input.ss_t].concat();
+        let data = [input.ct_t, input.pk_t, context].concat();
+        Kmac::new(KmacType::Kmac256)?.derive(&key, &data, 32, Some(&self.label))
+    }
+
+    fn get_ss_len(&self) -> usize {
+        32
+    }
+}
+
+/// The combiner of X-Wing (draft-connolly-cfrg-xwing-kem)
+///
+/// `ss = SHA3-256(ss_pq || ss_t || ct_t || pk_t || XWingLabel)`
+#[derive(Clone, Default)]
+pub struct XWingCombiner;
+
+impl Combiner for XWingCombiner {
+    fn combine(&self, input: &CombinerInput) -> Result<Vec<u8>> {
+        self.combine_with_context(input, &[])
+    }
+
+    /// The context follows the label: `SHA3-256(... || XWingLabel || context)`
+    fn combine_with_context(&self, input: &CombinerInput, context: &[u8]) -> Result<Vec<u8>> {
+        let mut sha3 = Sha3_256::new();
+        sha3.update(input.ss_pq);
+        sha3.update(input.ss_t);
+        sha3.update(input.ct_t);
+        sha3.update(input.pk_t);
+        sha3.update(XWING_LABEL);
+        sha3.update(context);
+        Ok(sha3.finalize().to_vec())
+    }
+
+    fn get_ss_len(&self) -> usize {
+        32
+    }
+}
+
+/// The combiner of Chempat (draft-josefsson-chempat)
+///
+/// `ss = SHA3-256(ss_t || ss_pq || SHA3-256(ct_t || ct_pq) || SHA3-256(pk_t || pk_pq) || label)`
+#[derive(Clone)]
+pub struct ChempatCombiner {
+    label: Vec<u8>,
+}
+
+impl ChempatCombiner {
+    /// Create a Chempat combiner
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label of the Chempat instance, e.g. `Chempat-X25519-ML-KEM-768`
+    ///
+    /// # Returns
+    ///
+    /// The combiner
+    pub fn new(label: &[u8]) -> ChempatCombiner {
+        ChempatCombiner {
+            label: label.to_vec(),
+        }
+    }
+}
+
+impl Combiner for ChempatCombiner {
+    fn combine(&self, input: &CombinerInput) -> Result<Vec<u8>> {
+        self.combine_with_context(input, &[])
+    }
+
+    /// The context follows the label: `SHA3-256(... || label || context)`
+    fn combine_with_context(&self, input: &CombinerInput, context: &[u8]) -> Result<Vec<u8>> {
+        let mut h_ct = Sha3_256::new();
+        h_ct.update(input.ct_t);
+        h_ct.update(input.ct_pq);
+
+        let mut h_pk = Sha3_256::new();
+        h_pk.update(input.pk_t);
+        h_pk.update(input.pk_pq);
+
+        let mut sha3 = Sha3_256::new();
+        sha3.update(input.ss_t);
+        sha3.update(input.ss_pq);
+        sha3.update(h_ct.finalize());
+        sha3.update(h_pk.finalize());
+        sha3.update(&self.label);
+        sha3.update(context);
+        Ok(sha3.finalize().to_vec())
+    }
+
+    fn get_ss_len(&self) -> usize {
+        32
+    }
+}
+
+/// A hybrid of a post-quantum and a traditional KEM with a pluggable combiner
+///
+/// This allows defining hybrid KEMs that aren't built in. The post-quantum KEM
+/// must have fixed key and ciphertext lengths. Keys and ciphertexts are
+/// concatenations, post-quantum first:
+///
+/// * pk = pk_pq || pk_t
+/// * sk = sk_pq || pk_pq || len(pk_t) || pk_t || sk_t, with a 2 byte big-endian length
+/// * ct = ct_pq || ct_t
+///
+/// A combined KEM has no OID. Its KEM info carries `KemType::Combined` and no
+/// OID, so it can't be mistaken for its post-quantum component.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{CombinedKem, Kem, KemManager, KemType, KmacCombiner};
+///
+/// let pq_kem = KemManager::new(KemType::MlKem768).unwrap();
+/// let trad_kem = KemManager::new(KemType::P256).unwrap();
+/// let combiner = KmacCombiner::new(b"ML-KEM-768+P256");
+/// let mut kem = CombinedKem::new(pq_kem, trad_kem, combiner).unwrap();
+///
+/// let (pk, sk) = kem.key_gen().unwrap();
+/// let (ss, ct) = kem.encap(&pk).unwrap();
+/// assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+/// ```
+#[derive(Clone)]
+pub struct CombinedKem<P, T, C> {
+    kem_info: KemInfo,
+    pq_kem: P,
+    trad_kem: T,
+    combiner: C,
+}
+
+impl<P: Kem, T: Kem, C: Combiner> CombinedKem<P, T, C> {
+    /// Combine two KEMs
+    ///
+    /// # Arguments
+    ///
+    /// * `pq_kem` - The post-quantum KEM
+    /// * `trad_kem` - The traditional KEM
+    /// * `combiner` - The combiner of the shared secrets
+    ///
+    /// # Returns
+    ///
+    /// The hybrid KEM
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the lengths of the post-quantum KEM aren't fixed
+    pub fn new(pq_kem: P, trad_kem: T, combiner: C) -> Result<Self> {
+        let pq_info = pq_kem.get_kem_info();
+        let t_info = trad_kem.get_kem_info();
+        if pq_info.pk_byte_len.is_none()
+            || pq_info.sk_byte_len.is_none()
+            || pq_info.ct_byte_len.is_none()
+        {
+            return Err(QuantCryptError::NotImplemented);
+        }
+        let sum = |a: Option<usize>, b: Option<usize>| Some(a? + b?);
+        let kem_info = KemInfo {
+            kem_type: KemType::Combined,
+            ss_byte_len: combiner.get_ss_len(),
+            pk_byte_len: sum(pq_info.pk_byte_len, t_info.pk_byte_len),
+            sk_byte_len: sum(
+                sum(pq_info.sk_byte_len, pq_info.pk_byte_len),
+                sum(t_info.pk_byte_len, t_info.sk_byte_len).map(|len| len + 2),
+            ),
+            ct_byte_len: sum(pq_info.ct_byte_len, t_info.ct_byte_len),
+            oid: None,
+            point_encoding: t_info.point_encoding,
+            oaep_params: t_info.oaep_params,
+            combiner: None,
+        };
+        Ok(CombinedKem {
+            kem_info,
+            pq_kem,
+            trad_kem,
+            combiner,
+        })
+    }
+
+    /// Get the fixed lengths of the public key, secret key and ciphertext of the post-quantum KEM
+    fn get_pq_lens(&self) -> (usize, usize, usize) {
+        let info = self.pq_kem.get_kem_info();
+        (
+            info.pk_byte_len.unwrap_or(0),
+            info.sk_byte_len.unwrap_or(0),
+            info.ct_byte_len.unwrap_or(0),
+        )
+    }
+
+    /// Encode the key pairs of the components
+    fn encode_keys(
+        &self,
+        (pk_pq, sk_pq): (Vec<u8>, Vec<u8>),
+        (pk_t, sk_t): (Vec<u8>, Vec<u8>),
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let pk_t_len =
+            u16::try_from(pk_t.len()).map_err(|_| QuantCryptError::KeyPairGenerationFailed)?;
+        let pk = [pk_pq.as_slice(), &pk_t].concat();
+        let sk = [
+            sk_pq.as_slice(),
+            &pk_pq,
+            &pk_t_len.to_be_bytes(),
+            &pk_t,
+            &sk_t,
+        ]
+        .concat();
+        Ok((pk, sk))
+    }
+}
+
+impl<P: Kem, T: Kem, C: Combiner> Kem for CombinedKem<P, T, C> {
+    /// A combined KEM can't be created from a KEM type, use `CombinedKem::new`
+    fn new(_kem_type: KemType) -> Result<Self> {
+        Err(QuantCryptError::NotImplemented)
+    }
+
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        let pq = self.pq_kem.key_gen()?;
+        let t = self.trad_kem.key_gen()?;
+        self.encode_keys(pq, t)
+    }
+
+    fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        let pq = self.pq_kem.key_gen_with_rng(rng)?;
+        let t = self.trad_kem.key_gen_with_rng(rng)?;
+        self.encode_keys(pq, t)
+    }
+
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.encap_with_context(pk, &[])
+    }
+
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the context isn't empty and the
+    /// combiner can't bind it
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_pq_len, _, _) = self.get_pq_lens();
+        if pk.len() < pk_pq_len {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+        let (pk_pq, pk_t) = pk.split_at(pk_pq_len);
+
+        let (ss_pq, ct_pq) = self.pq_kem.encap(pk_pq)?;
+        let (ss_t, ct_t) = self.trad_kem.encap(pk_t)?;
+
+        let input = CombinerInput {
+            ss_pq: &ss_pq,
+            ss_t: &ss_t,
+            ct_pq: &ct_pq,
+            ct_t: &ct_t,
+            pk_pq,
+            pk_t,
+        };
+        let ss = self.combiner.combine_with_context(&input, context)?;
+        Ok((ss, [ct_pq, ct_t].concat()))
+    }
+
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        self.decap_with_context(sk, ct, &[])
+    }
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the context isn't empty and the
+    /// combiner can't bind it
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+        let (pk_pq_len, sk_pq_len, ct_pq_len) = self.get_pq_lens();
+        if ct.len() < ct_pq_len {
+            return Err(QuantCryptError::InvalidCiphertext);
+        }
+        let (ct_pq, ct_t) = ct.split_at(ct_pq_len);
+
+        if sk.len() < sk_pq_len + pk_pq_len + 2 {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let (sk_pq, rest) = sk.split_at(sk_pq_len);
+        let (pk_pq, rest) = rest.split_at(pk_pq_len);
+        let (pk_t_len, rest) = rest.split_at(2);
+        let pk_t_len = u16::from_be_bytes([pk_t_len[0], pk_t_len[1]]) as usize;
+        if rest.len() < pk_t_len {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let (pk_t, sk_t) = rest.split_at(pk_t_len);
+
+        let ss_pq = self.pq_kem.decap(sk_pq, ct_pq)?;
+        let ss_t = self.trad_kem.decap(sk_t, ct_t)?;
+
+        let input = CombinerInput {
+            ss_pq: &ss_pq,
+            ss_t: &ss_t,
+            ct_pq,
+            ct_t,
+            pk_pq,
+            pk_t,
+        };
+        self.combiner.combine_with_context(&input, context)
+    }
+
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem_info.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::ec_kem::EcKemManager;
+    use crate::kem::ml_kem::MlKemManager;
+    use crate::kem::rsa_kem::RsaKemManager;
+    use crate::test_utils::conformance::check_kem;
+
+    /// A combiner with no context binding
+    #[derive(Clone)]
+    struct ConcatCombiner;
+
+    impl Combiner for ConcatCombiner {
+        fn combine(&self, input: &CombinerInput) -> Result<Vec<u8>> {
+            Ok([input.ss_pq, input.ss_t].concat())
+        }
+
+        fn get_ss_len(&self) -> usize {
+            64
+        }
+    }
+
+    #[test]
+    fn test_combined_kem() {
+        let ml_kem = MlKemManager::new(KemType::MlKem768).unwrap();
+        let x25519 = EcKemManager::new(KemType::X25519).unwrap();
+
+        let mut kem = CombinedKem::new(ml_kem.clone(), x25519.clone(), XWingCombiner).unwrap();
+        check_kem(&mut kem);
+        assert_eq!(kem.get_kem_info().pk_byte_len, Some(1184 + 32));
+        assert!(kem.get_kem_info().sk_byte_len.is_some());
+
+        // The combination has its own type, not that of ML-KEM-768
+        assert_eq!(kem.get_kem_info().kem_type, KemType::Combined);
+        assert!(kem.get_kem_info().oid.is_none());
+        assert!(!KemType::all().contains(&KemType::Combined));
+        assert_eq!(
+            crate::kem::kem_manager::KemManager::new(KemType::Combined).err(),
+            Some(QuantCryptError::NotImplemented)
+        );
+
+        let mut kem =
+            CombinedKem::new(ml_kem.clone(), x25519, KmacCombiner::new(b"my-hybrid")).unwrap();
+        check_kem(&mut kem);
+
+        // The context is bound to the shared secret
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap_with_context(&pk, b"transcript").unwrap();
+        assert_eq!(kem.decap_with_context(&sk, &ct, b"transcript").unwrap(), ss);
+        assert_ne!(kem.decap_with_context(&sk, &ct, b"other").unwrap(), ss);
+        assert_ne!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // Any traditional KEM, here with the combiner of the composite drafts
+        let rsa = RsaKemManager::new(KemType::RsaOAEP2048).unwrap();
+        let mut kem = CombinedKem::new(ml_kem, rsa, KdfCombiner::hkdf_sha256(b"label")).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        assert!(kem.decap(&sk[..100], &ct).is_err());
+
+        // The X-Wing combiner is the SHA3-256 combiner with the X-Wing label
+        let input = CombinerInput {
+            ss_pq: b"a",
+            ss_t: b"b",
+            ct_pq: b"c",
+            ct_t: b"d",
+            pk_pq: b"e",
+            pk_t: b"f",
+        };
+        let sha3 = KdfCombiner::sha3_256(XWING_LABEL).combine(&input).unwrap();
+        assert_eq!(sha3, XWingCombiner.combine(&input).unwrap());
+        assert_eq!(
+            KdfCombiner::sha3_256(XWING_LABEL)
+                .combine_with_context(&input, b"g")
+                .unwrap(),
+            XWingCombiner.combine_with_context(&input, b"g").unwrap()
+        );
+
+        // A combiner that can't bind a context refuses one
+        assert_eq!(
+            ConcatCombiner.combine_with_context(&input, b"g"),
+            Err(QuantCryptError::NotImplemented)
+        );
+        assert_eq!(
+            ConcatCombiner.combine_with_context(&input, &[]).unwrap(),
+            b"ab"
+        );
+        assert_ne!(
+            ChempatCombiner::new(b"x").combine(&input).unwrap(),
+            KmacCombiner::new(b"x").combine(&input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_chempat_combiner_vector() {
+        // The X25519 shares are the RFC 7748 section 6.1 exchange: Alice's public
+        // key is the recipient key, Bob's the ciphertext. The ML-KEM-768 sized
+        // shares are fixed patterns. The expected outputs were computed from the
+        // draft's formula with an independent SHA3-256 (Python's hashlib).
+        let ss_t = hex::decode("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742")
+            .unwrap();
+        let pk_t = hex::decode("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+            .unwrap();
+        let ct_t = hex::decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+            .unwrap();
+        let input = CombinerInput {
+            ss_pq: &[0x5a; 32],
+            ss_t: &ss_t,
+            ct_pq: &[0xc3; 1088],
+            ct_t: &ct_t,
+            pk_pq: &[0x3c; 1184],
+            pk_t: &pk_t,
+        };
+        let combiner = ChempatCombiner::new(b"Chempat-X25519-ML-KEM-768");
+        assert_eq!(
+            hex::encode(combiner.combine(&input).unwrap()),
+            "2d64f1a2becd7c0a2e9079e814852ecb658082cac6d7162f3a40f322a99e85bd"
+        );
+        assert_eq!(
+            hex::encode(combiner.combine_with_context(&input, b"context").unwrap()),
+            "d4dbb4bc6318c51e63cff74f5fa4af4b4f1e9821f193d30e95fde1c1eb0cdf07"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::asn1::asn_util::oid_to_der;
+use crate::kem::common::combined_kem::XWING_LABEL;
+use crate::kem::common::config::oids::Oid;
+use crate::kem::common::kdf::KdfType;
+use crate::kem::common::kem_info::CombinerInfo;
+use crate::kem::common::kem_type::KemType;
+
+/// A trait to get the domain separation of the combiner of a hybrid KEM
+pub trait CombinerConfig {
+    /// Get the KDF and label combining the shared secrets of the KEM
+    ///
+    /// # Returns
+    ///
+    /// The combiner info, or `None` if the KEM is not a hybrid
+    fn get_combiner_info(&self) -> Option<CombinerInfo>;
+}
+
+impl CombinerConfig for KemType {
+    /// Get the KDF and label combining the shared secrets of the KEM
+    ///
+    /// # Returns
+    ///
+    /// The combiner info, or `None` if the KEM is not a hybrid
+    fn get_combiner_info(&self) -> Option<CombinerInfo> {
+        let (kdf, label): (KdfType, &[u8]) = match self {
+            // Composite KEMs: the label is the DER encoded OID
+            KemType::MlKem768Rsa2048
+            | KemType::MlKem768Rsa3072
+            | KemType::MlKem768Rsa4096
+            | KemType::MlKem768P384
+            | KemType::MlKem768BrainpoolP256r1 => {
+                return composite_info(KdfType::HkdfSha256, self.get_oid())
+            }
+            KemType::MlKem768X25519
+            | KemType::MlKem1024P384
+            | KemType::MlKem1024BrainpoolP384r1
+            | KemType::MlKem1024X448 => return composite_info(KdfType::Sha3_256, self.get_oid()),
+
+            KemType::XWing => (KdfType::Sha3_256, XWING_LABEL),
+            KemType::MlKem1024X448Shake => (KdfType::Sha3_256, b"QSF-MLKEM1024-X448-SHAKE"),
+
+            KemType::ChempatX25519MlKem768 => (KdfType::Sha3_256, b"Chempat-X25519-ML-KEM-768"),
+            KemType::ChempatX448MlKem1024 => (KdfType::Sha3_256, b"Chempat-X448-ML-KEM-1024"),
+            KemType::ChempatP256MlKem768 => (KdfType::Sha3_256, b"Chempat-P256-ML-KEM-768"),
+            KemType::ChempatP384MlKem1024 => (KdfType::Sha3_256, b"Chempat-P384-ML-KEM-1024"),
+
+            KemType::MlKem768X25519P256 => (KdfType::Sha3_256, b"ML-KEM-768+X25519+P-256"),
+            KemType::MlKem1024X448P384 => (KdfType::Sha3_256, b"ML-KEM-1024+X448+P-384"),
+
+            KemType::BikeL1X25519 => (KdfType::Sha3_256, b"BIKE-L1+X25519"),
+            KemType::BikeL3P384 => (KdfType::Sha3_256, b"BIKE-L3+P-384"),
+            KemType::BikeL5X448 => (KdfType::Sha3_256, b"BIKE-L5+X448"),
+
+            _ => return None,
+        };
+        Some(CombinerInfo {
+            kdf: kdf.get_name().to_string(),
+            label: label.to_vec(),
+            label_oid: None,
+        })
+    }
+}
+
+/// Get the combiner info of a composite KEM, labelled with its DER encoded OID
+fn composite_info(kdf: KdfType, oid: Option<&str>) -> Option<CombinerInfo> {
+    let oid = oid?;
+    Some(CombinerInfo {
+        kdf: kdf.get_name().to_string(),
+        label: oid_to_der(oid).ok()?,
+        label_oid: Some(oid.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combiner_info() {
+        let info = KemType::MlKem768Rsa2048.get_combiner_info().unwrap();
+        assert_eq!(info.kdf, "HKDF-SHA256");
+        assert_eq!(
+            info.label_oid.as_deref(),
+            Some("2.16.840.1.114027.80.5.2.21")
+        );
+        // OBJECT IDENTIFIER tag and length
+        assert_eq!(info.label[..2], [0x06, 0x0b]);
+
+        let info = KemType::XWing.get_combiner_info().unwrap();
+        assert_eq!(info.kdf, "SHA3-256");
+        assert_eq!(info.label, b"\\.//^\\");
+        assert_eq!(info.label_oid, None);
+
+        assert!(KemType::MlKem768.get_combiner_info().is_none());
+        assert!(KemType::X25519.get_combiner_info().is_none());
+    }
+}
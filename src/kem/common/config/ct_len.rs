@@ -39,6 +39,37 @@ impl CTLen for KemType {
             KemType::MlKem768Rsa4096 => Some(1088 + 512 + 12),
             KemType::MlKem768P384 => Some(1088 + 97 + 10),
             KemType::XWing => Some(1120),
+            KemType::MlKem1024X448Shake => Some(1624),
+
+            // Trad CT + KEM CT
+            KemType::ChempatX25519MlKem768 => Some(32 + 1088),
+            KemType::ChempatX448MlKem1024 => Some(56 + 1568),
+            KemType::ChempatP256MlKem768 => Some(65 + 1088),
+            KemType::ChempatP384MlKem1024 => Some(97 + 1568),
+
+            // PQ Ct + Trad Cts
+            KemType::MlKem768X25519P256 => Some(1088 + 32 + 65),
+            KemType::MlKem1024X448P384 => Some(1568 + 56 + 97),
+            KemType::Sntrup761 => Some(1039),
+            KemType::Sntrup761X25519 => Some(1039 + 32),
+            KemType::BikeL1 => Some(1541 + 32),
+            KemType::BikeL3 => Some(3083 + 32),
+            KemType::BikeL5 => Some(5122 + 32),
+            KemType::BikeL1X25519 => Some(1573 + 32),
+            KemType::BikeL3P384 => Some(3115 + 97),
+            KemType::BikeL5X448 => Some(5154 + 56),
+
+            // Trad Ct + Kyber Ct
+            #[cfg(feature = "legacy")]
+            KemType::X25519Kyber768Draft00 => Some(32 + 1088),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 => Some(768),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber768 => Some(1088),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber1024 => Some(1568),
+            // The lengths of a combined KEM depend on its components
+            KemType::Combined => None,
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod combiner;
 pub mod ct_len;
 pub mod oids;
 pub mod pk_len;
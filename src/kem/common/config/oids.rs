@@ -6,8 +6,8 @@ pub trait Oid {
     ///
     /// # Returns
     ///
-    /// The OID for the KEM as a string
-    fn get_oid(&self) -> String;
+    /// The OID for the KEM as a string, or None if no OID has been assigned to it
+    fn get_oid(&self) -> Option<&'static str>;
 }
 
 impl Oid for KemType {
@@ -15,38 +15,71 @@ impl Oid for KemType {
     ///
     /// # Returns
     ///
-    /// The OID for the KEM
-    fn get_oid(&self) -> String {
+    /// The OID for the KEM, or None if no OID has been assigned to it
+    fn get_oid(&self) -> Option<&'static str> {
         match self {
-            KemType::MlKem768Rsa2048 => "2.16.840.1.114027.80.5.2.21",
-            KemType::MlKem768Rsa3072 => "2.16.840.1.114027.80.5.2.22",
-            KemType::MlKem768Rsa4096 => "2.16.840.1.114027.80.5.2.23",
-            KemType::MlKem768X25519 => "2.16.840.1.114027.80.5.2.24",
-            KemType::MlKem768P384 => "2.16.840.1.114027.80.5.2.25",
-            KemType::MlKem768BrainpoolP256r1 => "2.16.840.1.114027.80.5.2.26",
-            KemType::MlKem1024P384 => "2.16.840.1.114027.80.5.2.27",
-            KemType::MlKem1024BrainpoolP384r1 => "2.16.840.1.114027.80.5.2.28",
-            KemType::MlKem1024X448 => "2.16.840.1.114027.80.5.2.29",
+            KemType::MlKem768Rsa2048 => Some("2.16.840.1.114027.80.5.2.21"),
+            KemType::MlKem768Rsa3072 => Some("2.16.840.1.114027.80.5.2.22"),
+            KemType::MlKem768Rsa4096 => Some("2.16.840.1.114027.80.5.2.23"),
+            KemType::MlKem768X25519 => Some("2.16.840.1.114027.80.5.2.24"),
+            KemType::MlKem768P384 => Some("2.16.840.1.114027.80.5.2.25"),
+            KemType::MlKem768BrainpoolP256r1 => Some("2.16.840.1.114027.80.5.2.26"),
+            KemType::MlKem1024P384 => Some("2.16.840.1.114027.80.5.2.27"),
+            KemType::MlKem1024BrainpoolP384r1 => Some("2.16.840.1.114027.80.5.2.28"),
+            KemType::MlKem1024X448 => Some("2.16.840.1.114027.80.5.2.29"),
 
             // EC Types:
-            KemType::P256 => "1.2.840.10045.3.1.7",
-            KemType::P384 => "1.3.132.0.34",
-            KemType::X25519 => "1.3.101.110", // RFC 8410
-            KemType::X448 => "1.3.101.111",
-            KemType::BrainpoolP256r1 => "1.3.36.3.3.2.8.1.7", // RFC 5639
-            KemType::BrainpoolP384r1 => "1.3.36.3.3.2.8.1.11",
+            KemType::P256 => Some("1.2.840.10045.3.1.7"),
+            KemType::P384 => Some("1.3.132.0.34"),
+            KemType::X25519 => Some("1.3.101.110"), // RFC 8410
+            KemType::X448 => Some("1.3.101.111"),
+            KemType::BrainpoolP256r1 => Some("1.3.36.3.3.2.8.1.7"), // RFC 5639
+            KemType::BrainpoolP384r1 => Some("1.3.36.3.3.2.8.1.11"),
             // RSA Types:
-            KemType::RsaOAEP2048 => "1.2.840.113549.1.1.7",
-            KemType::RsaOAEP3072 => "1.2.840.113549.1.1.7",
-            KemType::RsaOAEP4096 => "1.2.840.113549.1.1.7",
+            KemType::RsaOAEP2048 => Some("1.2.840.113549.1.1.7"),
+            KemType::RsaOAEP3072 => Some("1.2.840.113549.1.1.7"),
+            KemType::RsaOAEP4096 => Some("1.2.840.113549.1.1.7"),
             // ML Types:
-            KemType::MlKem512 => "2.16.840.1.101.3.4.4.1",
-            KemType::MlKem768 => "2.16.840.1.101.3.4.4.2",
-            KemType::MlKem1024 => "2.16.840.1.101.3.4.4.3",
+            KemType::MlKem512 => Some("2.16.840.1.101.3.4.4.1"),
+            KemType::MlKem768 => Some("2.16.840.1.101.3.4.4.2"),
+            KemType::MlKem1024 => Some("2.16.840.1.101.3.4.4.3"),
 
             // XWing:
-            KemType::XWing => "1.3.6.1.4.1.62253.25722",
+            KemType::XWing => Some("1.3.6.1.4.1.62253.25722"),
+            // No OID has been assigned to the category 5 analogue of X-Wing
+            KemType::MlKem1024X448Shake => None,
+
+            // Chempat: no OIDs have been assigned by the draft yet
+            KemType::ChempatX25519MlKem768 => None,
+            KemType::ChempatX448MlKem1024 => None,
+            KemType::ChempatP256MlKem768 => None,
+            KemType::ChempatP384MlKem1024 => None,
+
+            // Three-way hybrids: no OIDs have been assigned
+            KemType::MlKem768X25519P256 => None,
+            KemType::MlKem1024X448P384 => None,
+
+            // Streamlined NTRU Prime: used in SSH, which names algorithms by string
+            KemType::Sntrup761 => None,
+            KemType::Sntrup761X25519 => None,
+
+            // BIKE: a round 4 candidate, no OIDs have been assigned
+            KemType::BikeL1 => None,
+            KemType::BikeL3 => None,
+            KemType::BikeL5 => None,
+            KemType::BikeL1X25519 => None,
+            KemType::BikeL3P384 => None,
+            KemType::BikeL5X448 => None,
+            #[cfg(feature = "legacy")]
+            KemType::X25519Kyber768Draft00 => None,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 => None,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber768 => None,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber1024 => None,
+            // A combined KEM has no OID
+            KemType::Combined => None,
         }
-        .to_string()
     }
 }
@@ -42,6 +42,37 @@ impl PKLen for KemType {
             KemType::MlKem768P384 => Some(1184 + 97 + 12),
 
             KemType::XWing => Some(1216),
+            KemType::MlKem1024X448Shake => Some(1624),
+
+            // Trad Pk + KEM Pk
+            KemType::ChempatX25519MlKem768 => Some(32 + 1184),
+            KemType::ChempatX448MlKem1024 => Some(56 + 1568),
+            KemType::ChempatP256MlKem768 => Some(65 + 1184),
+            KemType::ChempatP384MlKem1024 => Some(97 + 1568),
+
+            // PQ Pk + Trad Pks
+            KemType::MlKem768X25519P256 => Some(1184 + 32 + 65),
+            KemType::MlKem1024X448P384 => Some(1568 + 56 + 97),
+            KemType::Sntrup761 => Some(1158),
+            KemType::Sntrup761X25519 => Some(1158 + 32),
+            KemType::BikeL1 => Some(1541),
+            KemType::BikeL3 => Some(3083),
+            KemType::BikeL5 => Some(5122),
+            KemType::BikeL1X25519 => Some(1541 + 32),
+            KemType::BikeL3P384 => Some(3083 + 97),
+            KemType::BikeL5X448 => Some(5122 + 56),
+
+            // Trad Pk + Kyber Pk
+            #[cfg(feature = "legacy")]
+            KemType::X25519Kyber768Draft00 => Some(32 + 1184),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 => Some(800),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber768 => Some(1184),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber1024 => Some(1568),
+            // The lengths of a combined KEM depend on its components
+            KemType::Combined => None,
         }
     }
 }
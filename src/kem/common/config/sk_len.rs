@@ -49,6 +49,39 @@ impl SKLen for KemType {
             KemType::MlKem768Rsa4096 => None,
             KemType::MlKem768P384 => Some(2400 + 48 + 24 + (97 + 10) + 16 + 4),
             KemType::XWing => Some(32),
+            KemType::MlKem1024X448Shake => Some(32),
+
+            // Trad Sk + KEM Sk
+            KemType::ChempatX25519MlKem768 => Some(32 + 2400),
+            KemType::ChempatX448MlKem1024 => Some(56 + 3168),
+            KemType::ChempatP256MlKem768 => Some(32 + 2400),
+            KemType::ChempatP384MlKem1024 => Some(48 + 3168),
+
+            // PQ Sk + Trad Sks + Trad Pks
+            KemType::MlKem768X25519P256 => Some(2400 + 32 + 32 + 32 + 65),
+            KemType::MlKem1024X448P384 => Some(3168 + 56 + 48 + 56 + 97),
+            KemType::Sntrup761 => Some(1763),
+            KemType::Sntrup761X25519 => Some(1763 + 32),
+            // Supports of h0 and h1 + h0 + h1 + pk + sigma
+            KemType::BikeL1 => Some(568 + 3 * 1541 + 32),
+            KemType::BikeL3 => Some(824 + 3 * 3083 + 32),
+            KemType::BikeL5 => Some(1096 + 3 * 5122 + 32),
+            // BIKE Sk + Trad Sk + Trad Pk
+            KemType::BikeL1X25519 => Some(5223 + 32 + 32),
+            KemType::BikeL3P384 => Some(10105 + 48 + 97),
+            KemType::BikeL5X448 => Some(16494 + 56 + 56),
+
+            // Trad Sk + Kyber Sk
+            #[cfg(feature = "legacy")]
+            KemType::X25519Kyber768Draft00 => Some(32 + 2400),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 => Some(1632),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber768 => Some(2400),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber1024 => Some(3168),
+            // The lengths of a combined KEM depend on its components
+            KemType::Combined => None,
         }
     }
 }
@@ -47,6 +47,30 @@ impl SSLen for KemType {
             KemType::MlKem768Rsa4096 => 32,
             KemType::MlKem768P384 => 32,
             KemType::XWing => 32,
+            KemType::MlKem1024X448Shake => 32,
+            KemType::ChempatX25519MlKem768 => 32,
+            KemType::ChempatX448MlKem1024 => 32,
+            KemType::ChempatP256MlKem768 => 32,
+            KemType::ChempatP384MlKem1024 => 32,
+
+            KemType::MlKem768X25519P256 => 32,
+            KemType::MlKem1024X448P384 => 32,
+            KemType::Sntrup761 => 32,
+            // SHA-512 over the sntrup761 and X25519 secrets
+            KemType::Sntrup761X25519 => 64,
+            KemType::BikeL1 | KemType::BikeL3 | KemType::BikeL5 => 32,
+            KemType::BikeL1X25519 | KemType::BikeL3P384 | KemType::BikeL5X448 => 32,
+            // The concatenation of the X25519 and Kyber768 secrets
+            #[cfg(feature = "legacy")]
+            KemType::X25519Kyber768Draft00 => 64,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 => 32,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber768 => 32,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber1024 => 32,
+            // The length is that of the combiner, set by `CombinedKem::new`
+            KemType::Combined => 0,
         }
     }
 }
@@ -0,0 +1,192 @@
+use rand_core::CryptoRngCore;
+
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// An object safe version of [`Kem`](crate::kems::Kem)
+///
+/// `Kem` has a constructor and takes generic random number generators, so it
+/// can't be used as a trait object. Every `Kem` implements `DynKem`, which
+/// takes a `dyn CryptoRngCore` instead, so KEMs of different types can be
+/// stored together, e.g. to negotiate an algorithm with a peer.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{DynKem, Kem, KemManager, KemType, XWingKemManager};
+///
+/// let mut kems: Vec<Box<dyn DynKem>> = vec![
+///     Box::new(KemManager::new(KemType::MlKem768).unwrap()),
+///     Box::new(XWingKemManager::new(KemType::XWing).unwrap()),
+/// ];
+/// for kem in kems.iter_mut() {
+///     let (pk, sk) = kem.key_gen().unwrap();
+///     let (ss, ct) = kem.encap(&pk).unwrap();
+///     assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+/// }
+/// ```
+pub trait DynKem {
+    /// Get the metadata of the KEM
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo;
+
+    /// Generate a keypair using the default random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Generate a keypair with a specified random number generator
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to use
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&mut self, rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Encapsulate a public key with the given randomness, for test vectors
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `coins` - The randomness of the encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>>;
+
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `context` - The context to bind the shared secret to
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `context` - The context given to `encap_with_context`
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>>;
+}
+
+impl<T: Kem> DynKem for T {
+    fn get_kem_info(&self) -> KemInfo {
+        Kem::get_kem_info(self)
+    }
+
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        Kem::key_gen(self)
+    }
+
+    fn key_gen_with_rng(&mut self, mut rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        Kem::key_gen_with_rng(self, &mut rng)
+    }
+
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        Kem::encap(self, pk)
+    }
+
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        Kem::encap_deterministic(self, pk, coins)
+    }
+
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        Kem::decap(self, sk, ct)
+    }
+
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        Kem::encap_with_context(self, pk, context)
+    }
+
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+        Kem::decap_with_context(self, sk, ct, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::common::kem_type::KemType;
+    use crate::kem::composite_kem::CompositeKemManager;
+    use crate::kem::kem_manager::KemManager;
+    use crate::kem::xwing::XWingKemManager;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn test_dyn_kem() {
+        let mut kems: Vec<Box<dyn DynKem>> = vec![
+            Box::new(KemManager::new(KemType::MlKem512).unwrap()),
+            Box::new(XWingKemManager::new(KemType::XWing).unwrap()),
+            Box::new(CompositeKemManager::new(KemType::MlKem768X25519).unwrap()),
+        ];
+        let types: Vec<KemType> = kems.iter().map(|kem| kem.get_kem_info().kem_type).collect();
+        assert_eq!(
+            types,
+            [KemType::MlKem512, KemType::XWing, KemType::MlKem768X25519]
+        );
+
+        for kem in kems.iter_mut() {
+            let (pk, sk) = kem.key_gen().unwrap();
+            let (ss, ct) = kem.encap(&pk).unwrap();
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+
+            // The context goes through the trait object, ML-KEM passes it through
+            let (ss, ct) = kem.encap_with_context(&pk, b"transcript").unwrap();
+            assert_eq!(kem.decap_with_context(&sk, &ct, b"transcript").unwrap(), ss);
+            let plain = kem.decap(&sk, &ct).unwrap() == ss;
+            assert_eq!(plain, kem.get_kem_info().kem_type == KemType::MlKem512);
+
+            // The RNG is used through the trait object
+            let mut rng = ChaCha20Rng::from_seed([1u8; 32]);
+            let first = kem.key_gen_with_rng(&mut rng).unwrap();
+            let mut rng = ChaCha20Rng::from_seed([1u8; 32]);
+            assert_eq!(kem.key_gen_with_rng(&mut rng).unwrap().0, first.0);
+        }
+    }
+}
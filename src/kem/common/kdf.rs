@@ -14,6 +14,34 @@ pub enum KdfType {
     Sha3_384,
 }
 
+impl KdfType {
+    /// Get the name of the KDF, e.g. `HKDF-SHA256`
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            KdfType::HkdfSha256 => "HKDF-SHA256",
+            KdfType::Sha3_256 => "SHA3-256",
+            KdfType::HkdfSha384 => "HKDF-SHA384",
+            KdfType::Sha3_512 => "SHA3-512",
+            KdfType::Sha3_384 => "SHA3-384",
+        }
+    }
+
+    /// Get the KDF with a name returned by `get_name`
+    pub fn from_name(name: &str) -> Option<KdfType> {
+        [
+            KdfType::HkdfSha256,
+            KdfType::Sha3_256,
+            KdfType::HkdfSha384,
+            KdfType::Sha3_512,
+            KdfType::Sha3_384,
+        ]
+        .into_iter()
+        .find(|kdf_type| kdf_type.get_name() == name)
+    }
+}
+
+// Implement clone
+#[derive(Clone)]
 /// The Key Derivation Function (KDF)
 pub struct Kdf {
     kdf_type: KdfType,
@@ -107,6 +135,9 @@ mod tests {
 
             let output3 = kdf.kdf(b"input2");
             assert_ne!(output1, output3);
+
+            let name = kdf_type.get_name();
+            assert_eq!(KdfType::from_name(name).unwrap().get_name(), name);
         }
     }
 }
@@ -0,0 +1,65 @@
+use rand_core::CryptoRngCore;
+
+use crate::kem::common::kem_info::KemInfo;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// An external implementation of a KEM, such as a vendor HSM
+///
+/// A backend registered with [`register_kem_backend`](crate::kems::register_kem_backend)
+/// replaces the built-in implementation of its KEM type everywhere in the crate:
+/// keys, certificates, CMS, HPKE and as a component of composite KEMs.
+///
+/// Unlike [`Kem`](crate::kems::Kem), this trait is object safe and shared between
+/// threads, so all methods take `&self`.
+pub trait KemBackend: Send + Sync {
+    /// Get the metadata of the KEM. The KEM type of the metadata is the type
+    /// the backend is registered for.
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo;
+
+    /// Generate a keypair using the default random number generator of the backend
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&self) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Generate a keypair with a specified random number generator
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to use
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&self, rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)>;
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>>;
+}
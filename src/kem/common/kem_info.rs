@@ -1,3 +1,4 @@
+use crate::kem::common::config::combiner::CombinerConfig;
 use crate::kem::common::config::ct_len::CTLen;
 use crate::kem::common::config::oids::Oid;
 use crate::kem::common::config::pk_len::PKLen;
@@ -5,6 +6,77 @@ use crate::kem::common::config::sk_len::SKLen;
 use crate::kem::common::config::ss_len::SSLen;
 use crate::kem::common::kem_type::KemType;
 
+/// The encoding of the EC points in the public keys and ciphertexts of a DH-based KEM
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(dead_code)]
+pub enum PointEncoding {
+    /// The uncompressed SEC1 form (0x04 || x || y), used by RFC 9180 and the composite drafts
+    Uncompressed,
+    /// The compressed SEC1 form (0x02 or 0x03 || x)
+    Compressed,
+}
+
+/// A hash function used by RSA-OAEP, as the digest or in MGF1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OaepHash {
+    /// SHA-256
+    Sha256,
+    /// SHA-384
+    Sha384,
+    /// SHA-512
+    Sha512,
+}
+
+/// The parameters of an RSA-OAEP KEM
+///
+/// The default is the profile of the composite KEM drafts: SHA-256 as both the
+/// digest and the MGF1 hash, and an empty label.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OaepParams {
+    /// The hash function used to hash the label
+    pub digest: OaepHash,
+    /// The hash function used by MGF1
+    pub mgf_hash: OaepHash,
+    /// The label (`None` for the empty label)
+    pub label: Option<String>,
+}
+
+impl Default for OaepParams {
+    fn default() -> Self {
+        OaepParams {
+            digest: OaepHash::Sha256,
+            mgf_hash: OaepHash::Sha256,
+            label: None,
+        }
+    }
+}
+
+/// The domain separation of the shared secret combiner of a hybrid KEM
+#[derive(Clone, Debug, PartialEq)]
+pub struct CombinerInfo {
+    /// The KDF or hash deriving the shared secret, e.g. `HKDF-SHA256` or `SHA3-256`
+    pub kdf: String,
+    /// The domain separation label, the last input to the KDF
+    pub label: Vec<u8>,
+    /// The OID whose DER encoding is the label (`None` if the label is not a DER context)
+    pub label_oid: Option<String>,
+}
+
+/// The lengths in bytes of the keys, ciphertext and shared secret of a KEM
+///
+/// A length is `None` if it is not fixed, such as the length of an RSA private key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KemLengths {
+    /// The length of the public key
+    pub pk: Option<usize>,
+    /// The length of the secret key
+    pub sk: Option<usize>,
+    /// The length of the ciphertext
+    pub ct: Option<usize>,
+    /// The length of the shared secret
+    pub ss: usize,
+}
+
 /// A structure to represent metadata about a KEM
 ///
 /// This is also used to test the correctness of the KEM
@@ -21,8 +93,15 @@ pub struct KemInfo {
     pub sk_byte_len: Option<usize>,
     /// The length of the ciphertext in bytes (if fixed size, otherwise `None`)
     pub ct_byte_len: Option<usize>,
-    /// The OID of the KEM
-    pub oid: String,
+    /// The OID of the KEM (`None` if no OID has been assigned to it)
+    pub oid: Option<String>,
+    /// The encoding of EC points emitted by the KEM (`None` if it is not based on a Weierstrass curve).
+    /// Both encodings are accepted as input.
+    pub point_encoding: Option<PointEncoding>,
+    /// The RSA-OAEP parameters (`None` if it is not an RSA-OAEP KEM)
+    pub oaep_params: Option<OaepParams>,
+    /// The domain separation of the combiner (`None` if it is not a hybrid KEM with a known combiner)
+    pub combiner: Option<CombinerInfo>,
 }
 
 impl KemInfo {
@@ -38,7 +117,78 @@ impl KemInfo {
             pk_byte_len: kem_type.get_pk_len(),
             sk_byte_len: kem_type.get_sk_len(),
             ct_byte_len: kem_type.get_ct_len(),
-            oid: kem_type.get_oid(),
+            oid: kem_type.get_oid().map(str::to_string),
+            point_encoding: match kem_type {
+                KemType::P256
+                | KemType::P384
+                | KemType::BrainpoolP256r1
+                | KemType::BrainpoolP384r1 => Some(PointEncoding::Uncompressed),
+                _ => None,
+            },
+            oaep_params: match kem_type {
+                KemType::RsaOAEP2048 | KemType::RsaOAEP3072 | KemType::RsaOAEP4096 => {
+                    Some(OaepParams::default())
+                }
+                _ => None,
+            },
+            combiner: kem_type.get_combiner_info(),
         }
     }
+
+    /// Get the lengths of the keys, ciphertext and shared secret
+    ///
+    /// The lengths account for the point encoding, so they can be used to
+    /// allocate the buffers of `Kem::encap_into` and `Kem::decap_into`.
+    ///
+    /// # Returns
+    ///
+    /// The lengths in bytes
+    ///
+    /// # Example
+    /// ```
+    /// use quantcrypt::kems::{KemInfo, KemType};
+    ///
+    /// let lengths = KemInfo::new(KemType::XWing).lengths();
+    /// assert_eq!(lengths.pk, Some(1216));
+    /// assert_eq!(lengths.ct, Some(1120));
+    /// assert_eq!(lengths.ss, 32);
+    /// ```
+    pub fn lengths(&self) -> KemLengths {
+        KemLengths {
+            pk: self.pk_byte_len,
+            sk: self.sk_byte_len,
+            ct: self.ct_byte_len,
+            ss: self.ss_byte_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::common::kem_trait::Kem;
+    use crate::kem::ec_kem::EcKemManager;
+
+    #[test]
+    fn test_kem_lengths() {
+        for kem_type in KemType::all() {
+            let info = KemInfo::new(kem_type);
+            let lengths = info.lengths();
+            assert_eq!(lengths.pk, info.pk_byte_len);
+            assert_eq!(lengths.sk, info.sk_byte_len);
+            assert_eq!(lengths.ct, info.ct_byte_len);
+            assert_eq!(lengths.ss, info.ss_byte_len);
+        }
+
+        // The lengths follow the point encoding
+        let mut kem = EcKemManager::new(KemType::P256).unwrap();
+        kem.set_point_encoding(PointEncoding::Compressed).unwrap();
+        let lengths = kem.get_kem_info().lengths();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        assert_eq!(lengths.pk, Some(pk.len()));
+        assert_eq!(lengths.sk, Some(sk.len()));
+        assert_eq!(lengths.ct, Some(ct.len()));
+        assert_eq!(lengths.ss, ss.len());
+    }
 }
@@ -1,11 +1,10 @@
 use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
 
 use crate::kem::common::kem_info::KemInfo;
 use crate::kem::common::kem_type::KemType;
 use crate::QuantCryptError;
 
-use crate::kem::common::config::oids::Oid;
-
 // Change the alias to use `Box<dyn error::Error>`.
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
@@ -55,6 +54,51 @@ pub trait Kem {
     /// A tuple containing the ciphertext and shared secret (ss, ct)
     fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)>;
 
+    /// Encapsulate a batch of public keys
+    ///
+    /// ML-KEM and composite KEMs amortize the per-call setup over the batch
+    /// and, with the `rayon` feature, encapsulate in parallel.
+    ///
+    /// # Arguments
+    ///
+    /// * `pks` - The public keys to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct) for each public key, in order
+    ///
+    /// # Errors
+    ///
+    /// The error of the first public key that fails to encapsulate
+    fn encap_batch(&mut self, pks: &[&[u8]]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        pks.iter().map(|pk| self.encap(pk)).collect()
+    }
+
+    /// Encapsulate a public key with the given randomness instead of fresh coins
+    ///
+    /// This is for reproducing known answer tests, never use it with
+    /// predictable coins otherwise. The coins are the message m for ML-KEM,
+    /// the ephemeral secret key for the EC KEMs, the 64 byte `eseed` for
+    /// X-Wing, and the coins of the ML-KEM component followed by those of the
+    /// traditional component for composite KEMs.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `coins` - The randomness of the encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCoinsLength` if the coins have the wrong length,
+    /// `QuantCryptError::NotImplemented` if the KEM doesn't support deterministic encapsulation
+    fn encap_deterministic(&mut self, _pk: &[u8], _coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        Err(QuantCryptError::NotImplemented)
+    }
+
     /// Decapsulate a ciphertext
     ///
     /// # Arguments
@@ -67,6 +111,99 @@ pub trait Kem {
     /// The shared secret
     fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>>;
 
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// The context, e.g. the protocol transcript or the identifiers of the
+    /// negotiated algorithms, is mixed into the combiner of composite, triple,
+    /// X-Wing, Chempat and combined KEMs, so that a peer tricked into another
+    /// algorithm derives another secret. An empty context derives the same secret
+    /// as `encap`. KEMs without a combiner pass through to `encap` and derive the
+    /// same secret whatever the context; their protocol has to bind it in its own
+    /// key schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `context` - The context to bind the shared secret to
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_with_context(&mut self, pk: &[u8], _context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.encap(pk)
+    }
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// The context must be the one given to `encap_with_context`, see there.
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `context` - The context to bind the shared secret to
+    ///
+    /// # Returns
+    ///
+    /// The shared secret
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], _context: &[u8]) -> Result<Vec<u8>> {
+        self.decap(sk, ct)
+    }
+
+    /// Encapsulate a public key, writing the outputs into caller-provided buffers
+    ///
+    /// ML-KEM writes the outputs directly, without allocating on the heap.
+    /// Other KEMs encapsulate with `encap` and copy the outputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `ss_out` - The buffer receiving the shared secret
+    /// * `ct_out` - The buffer receiving the ciphertext
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written to each buffer (ss_len, ct_len)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::BufferTooSmall` if a buffer can't hold its output
+    fn encap_into(
+        &mut self,
+        pk: &[u8],
+        ss_out: &mut [u8],
+        ct_out: &mut [u8],
+    ) -> Result<(usize, usize)> {
+        let (ss, ct) = self.encap(pk)?;
+        let ss = Zeroizing::new(ss);
+        check_buffer_len(ct.len(), ct_out)?;
+        let ss_len = copy_into(&ss, ss_out)?;
+        Ok((ss_len, copy_into(&ct, ct_out)?))
+    }
+
+    /// Decapsulate a ciphertext, writing the shared secret into a caller-provided buffer
+    ///
+    /// ML-KEM writes the shared secret directly, without allocating on the
+    /// heap. Other KEMs decapsulate with `decap` and copy the shared secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `out` - The buffer receiving the shared secret
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written to `out`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::BufferTooSmall` if `out` can't hold the shared secret
+    fn decap_into(&self, sk: &[u8], ct: &[u8], out: &mut [u8]) -> Result<usize> {
+        let ss = Zeroizing::new(self.decap(sk, ct)?);
+        copy_into(&ss, out)
+    }
+
     /// Get KEM metadata information such as the key lengths,
     /// size of ciphertext, etc.
     ///
@@ -90,13 +227,47 @@ pub trait Kem {
     where
         Self: Sized,
     {
-        let all_kem_types = KemType::all();
-        for kem_type in all_kem_types {
-            if kem_type.get_oid() == oid {
-                let kem = Self::new(kem_type)?;
-                return Ok(kem);
-            }
-        }
-        Err(QuantCryptError::InvalidOid)
+        let kem_type = KemType::from_oid(oid).ok_or(QuantCryptError::InvalidOid)?;
+        Self::new(kem_type)
     }
 }
+
+/// Check that a buffer can hold an output
+///
+/// # Arguments
+///
+/// * `needed` - The length of the output
+/// * `out` - The buffer
+///
+/// # Errors
+///
+/// `QuantCryptError::BufferTooSmall` if the buffer is shorter than the output
+pub(crate) fn check_buffer_len(needed: usize, out: &[u8]) -> Result<()> {
+    if out.len() < needed {
+        return Err(QuantCryptError::BufferTooSmall {
+            needed,
+            actual: out.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Copy an output to the start of a buffer
+///
+/// # Arguments
+///
+/// * `data` - The output
+/// * `out` - The buffer
+///
+/// # Returns
+///
+/// The number of bytes written
+///
+/// # Errors
+///
+/// `QuantCryptError::BufferTooSmall` if the buffer is shorter than the output
+pub(crate) fn copy_into(data: &[u8], out: &mut [u8]) -> Result<usize> {
+    check_buffer_len(data.len(), out)?;
+    out[..data.len()].copy_from_slice(data);
+    Ok(data.len())
+}
@@ -3,7 +3,13 @@ use strum_macros::EnumIter;
 
 use crate::kem::common::config::oids::Oid;
 
-#[derive(Clone, Debug, PartialEq, EnumIter)]
+/// The KEM algorithms
+///
+/// The enum is non-exhaustive: the legacy variants only exist with the `legacy`
+/// feature, and matches outside of the crate need a wildcard arm so that they
+/// compile whichever features are enabled.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[non_exhaustive]
 pub enum KemType {
     /// NIST P-256 key encapsulation mechanism
     P256,
@@ -52,6 +58,65 @@ pub enum KemType {
 
     /// XWing
     XWing,
+    /// X-Wing analogue with ML-KEM-1024 and X448, for security category 5
+    MlKem1024X448Shake,
+
+    // Chempat generic hybrid KEMs (draft-josefsson-chempat):
+    /// Chempat-X25519-ML-KEM-768
+    ChempatX25519MlKem768,
+    /// Chempat-X448-ML-KEM-1024
+    ChempatX448MlKem1024,
+    /// Chempat-P256-ML-KEM-768
+    ChempatP256MlKem768,
+    /// Chempat-P384-ML-KEM-1024
+    ChempatP384MlKem1024,
+
+    // Three-way hybrids of ML-KEM and two traditional KEMs:
+    /// ML-KEM-768 + X25519 + P-256
+    MlKem768X25519P256,
+    /// ML-KEM-1024 + X448 + P-384
+    MlKem1024X448P384,
+
+    // Streamlined NTRU Prime, as used by OpenSSH:
+    /// sntrup761
+    Sntrup761,
+    /// sntrup761 + X25519, the KEM of the sntrup761x25519-sha512 SSH key exchange
+    Sntrup761X25519,
+
+    // BIKE, the code-based KEM of the fourth round of the NIST process:
+    /// BIKE-L1
+    BikeL1,
+    /// BIKE-L3
+    BikeL3,
+    /// BIKE-L5
+    BikeL5,
+    /// BIKE-L1 + X25519
+    BikeL1X25519,
+    /// BIKE-L3 + P-384
+    BikeL3P384,
+    /// BIKE-L5 + X448
+    BikeL5X448,
+
+    /// A hybrid defined at runtime with `CombinedKem`
+    ///
+    /// It has no OID and can't be created from its type, so it isn't part of
+    /// `KemType::all()`.
+    #[strum(disabled)]
+    Combined,
+
+    // Legacy pre-standard constructions:
+    /// X25519Kyber768Draft00, with Kyber768 from round 3 of the NIST process
+    #[cfg(feature = "legacy")]
+    X25519Kyber768Draft00,
+    /// Kyber512 from round 3 of the NIST process
+    #[cfg(feature = "legacy")]
+    Kyber512,
+    /// Kyber768 from round 3 of the NIST process
+    #[cfg(feature = "legacy")]
+    Kyber768,
+    /// Kyber1024 from round 3 of the NIST process
+    #[cfg(feature = "legacy")]
+    Kyber1024,
 }
 
 impl KemType {
@@ -60,16 +125,90 @@ impl KemType {
     }
 
     pub fn is_composite(&self) -> bool {
-        !matches!(
+        match self {
+            KemType::MlKem512
+            | KemType::MlKem768
+            | KemType::MlKem1024
+            | KemType::Sntrup761
+            | KemType::BikeL1
+            | KemType::BikeL3
+            | KemType::BikeL5 => false,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 | KemType::Kyber768 | KemType::Kyber1024 => false,
+            _ => true,
+        }
+    }
+
+    /// Check if the KEM is a pre-standard construction of the `legacy` feature
+    ///
+    /// Legacy KEMs have no OID and aren't part of `KemAlgorithm`, so they can't
+    /// appear in keys, certificates or CMS, and policies checking OIDs never
+    /// accept them. They are only meant for talking to old peers or decrypting
+    /// old data.
+    ///
+    /// # Returns
+    ///
+    /// True if the KEM is a legacy construction, false otherwise
+    pub fn is_legacy(&self) -> bool {
+        #[cfg(feature = "legacy")]
+        if matches!(
             self,
-            KemType::MlKem512 | KemType::MlKem768 | KemType::MlKem1024
-        )
+            KemType::X25519Kyber768Draft00
+                | KemType::Kyber512
+                | KemType::Kyber768
+                | KemType::Kyber1024
+        ) {
+            return true;
+        }
+        false
+    }
+
+    /// Get the NIST security category of the KEM against quantum attackers
+    ///
+    /// For hybrid KEMs, this is the category of the ML-KEM component, as the
+    /// traditional component offers no protection against a quantum attacker.
+    ///
+    /// # Returns
+    ///
+    /// The NIST security category, or 0 for traditional and combined KEMs
+    pub fn get_quantum_security_level(&self) -> u8 {
+        match self {
+            KemType::P256
+            | KemType::P384
+            | KemType::X25519
+            | KemType::BrainpoolP256r1
+            | KemType::BrainpoolP384r1
+            | KemType::X448
+            | KemType::RsaOAEP2048
+            | KemType::RsaOAEP3072
+            | KemType::RsaOAEP4096 => 0,
+            // Unknown, it depends on the components
+            KemType::Combined => 0,
+            KemType::MlKem512 | KemType::BikeL1 | KemType::BikeL1X25519 => 1,
+            // The category claimed by the round 3 NTRU Prime submission
+            KemType::Sntrup761 | KemType::Sntrup761X25519 => 2,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 => 1,
+            #[cfg(feature = "legacy")]
+            KemType::Kyber1024 => 5,
+            KemType::MlKem1024
+            | KemType::MlKem1024P384
+            | KemType::MlKem1024BrainpoolP384r1
+            | KemType::MlKem1024X448
+            | KemType::MlKem1024X448Shake
+            | KemType::ChempatX448MlKem1024
+            | KemType::ChempatP384MlKem1024
+            | KemType::MlKem1024X448P384
+            | KemType::BikeL5
+            | KemType::BikeL5X448 => 5,
+            _ => 3,
+        }
     }
 
     pub fn from_oid(oid: &str) -> Option<KemType> {
         let all_kem_types = KemType::all();
         all_kem_types
             .into_iter()
-            .find(|kem_type| kem_type.get_oid() == oid)
+            .find(|kem_type| kem_type.get_oid() == Some(oid))
     }
 }
@@ -1,6 +1,12 @@
+pub mod async_kem;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod combined_kem;
 pub mod config;
+pub mod dyn_kem;
 pub mod kdf;
+pub mod kem_backend;
 pub mod kem_info;
 pub mod kem_trait;
 pub mod kem_type;
-pub mod macros;
+pub mod multi_recipient;
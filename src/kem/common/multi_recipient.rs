@@ -0,0 +1,242 @@
+use rand_core::RngCore;
+use zeroize::Zeroizing;
+
+use crate::kdf::api::KdfManager;
+use crate::kdf::common::kdf_trait::Kdf;
+use crate::kdf::common::kdf_type::KdfType;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::kem_manager::KemManager;
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::wrap::api::WrapManager;
+use crate::wrap::common::wrap_trait::Wrap;
+use crate::wrap::common::wrap_type::WrapType;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The label of the key encryption key derivation, followed by the KEM ciphertext
+const KEK_LABEL: &[u8] = b"quantcrypt mkem ";
+
+/// The length of the AES-256 key encryption key
+const KEK_LEN: usize = 32;
+
+/// The length of the shared secret encapsulated to all recipients
+const SHARED_SECRET_LEN: usize = 32;
+
+/// Encapsulates a single shared secret to several public keys of the same KEM
+///
+/// This is the generic mKEM construction: a random 32 byte shared secret is
+/// wrapped with AES-256 key wrap (RFC 3394) under a key encryption key derived
+/// with HKDF-SHA256 from the shared secret of a regular encapsulation to each
+/// recipient. The key encryption key is bound to the KEM ciphertext, and the
+/// key wrap integrity check makes a modified ciphertext fail to decapsulate
+/// rather than yield a related secret.
+///
+/// The recipients are encapsulated to with `encap_batch`, which draws the
+/// entropy once and, with the `rayon` feature, encapsulates in parallel. The
+/// randomness of the encapsulations is not shared between recipients: the
+/// mKEM schemes that reuse it for lattice KEMs require the recipients to share
+/// a public matrix, which independently generated ML-KEM keys do not.
+///
+/// # Example
+/// ```
+/// use quantcrypt::kems::{Kem, KemManager, KemType, MultiRecipientKem};
+///
+/// let mut kem = KemManager::new(KemType::MlKem768).unwrap();
+/// let (pk1, sk1) = kem.key_gen().unwrap();
+/// let (pk2, sk2) = kem.key_gen().unwrap();
+///
+/// let mut mkem = MultiRecipientKem::new(KemType::MlKem768).unwrap();
+/// let (ss, cts) = mkem.encap(&[&pk1, &pk2]).unwrap();
+/// assert_eq!(mkem.decap(&sk1, &cts[0]).unwrap(), ss);
+/// assert_eq!(mkem.decap(&sk2, &cts[1]).unwrap(), ss);
+/// ```
+pub struct MultiRecipientKem {
+    /// The KEM of the recipients
+    kem: KemManager,
+    /// The key wrap of the shared secret
+    wrap: WrapManager,
+    /// The derivation of the key encryption keys
+    kdf: KdfManager,
+    /// The RNG of the shared secret, the health-tested OS RNG if None
+    rng: Option<SharedRng>,
+}
+
+impl MultiRecipientKem {
+    /// Create a new multi-recipient KEM
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The KEM of the recipients
+    ///
+    /// # Returns
+    ///
+    /// A new multi-recipient KEM
+    pub fn new(kem_type: KemType) -> Result<Self> {
+        Ok(Self {
+            kem: KemManager::new(kem_type)?,
+            wrap: WrapManager::new(WrapType::Aes256)?,
+            kdf: KdfManager::new(KdfType::HkdfWithSha256)?,
+            rng: None,
+        })
+    }
+
+    /// Create a new multi-recipient KEM drawing all its randomness from a
+    /// caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The KEM of the recipients
+    /// * `rng` - The RNG used by the shared secret and the encapsulations
+    ///
+    /// # Returns
+    ///
+    /// A new multi-recipient KEM
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM, or a component of it, is provided by
+    /// a registered backend, which draws its own randomness
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut mkem = Self::new(kem_type)?;
+        mkem.kem.set_rng(rng.clone())?;
+        mkem.rng = Some(rng);
+        Ok(mkem)
+    }
+
+    /// Get the KEM of the recipients
+    ///
+    /// # Returns
+    ///
+    /// The KEM type
+    pub fn get_kem_type(&self) -> KemType {
+        self.kem.get_kem_info().kem_type
+    }
+
+    /// Derive the key encryption key of a recipient from its shared secret
+    fn derive_kek(&self, ss: &[u8], ct: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        let label = [KEK_LABEL, ct].concat();
+        Ok(Zeroizing::new(self.kdf.derive(ss, &label, KEK_LEN, None)?))
+    }
+
+    /// Encapsulate a single shared secret to several public keys
+    ///
+    /// # Arguments
+    ///
+    /// * `pks` - The public keys of the recipients
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret, zeroized on drop, and the
+    /// ciphertext of each recipient, in the order of the public keys
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::EntropyHealthTestFailed` will be returned if the health tests
+    /// of the entropy source failed, otherwise the error of the first public key
+    /// that fails to encapsulate
+    #[allow(clippy::type_complexity)]
+    pub fn encap(&mut self, pks: &[&[u8]]) -> Result<(Zeroizing<Vec<u8>>, Vec<Vec<u8>>)> {
+        let mut ss = Zeroizing::new(vec![0u8; SHARED_SECRET_LEN]);
+        ManagerRng::for_key_gen(self.rng.as_ref())?.fill_bytes(&mut ss);
+
+        let mut cts = Vec::with_capacity(pks.len());
+        for (kem_ss, kem_ct) in self.kem.encap_batch(pks)? {
+            let kem_ss = Zeroizing::new(kem_ss);
+            let kek = self.derive_kek(&kem_ss, &kem_ct)?;
+            let wrapped = self.wrap.wrap(&kek, &ss)?;
+            cts.push([kem_ct, wrapped].concat());
+        }
+        Ok((ss, cts))
+    }
+
+    /// Decapsulate the shared secret from the ciphertext of a recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key of the recipient
+    /// * `ct` - The ciphertext of the recipient
+    ///
+    /// # Returns
+    ///
+    /// The shared secret, zeroized on drop
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCiphertext` will be returned if the ciphertext is too short
+    ///
+    /// `QuantCryptError::DecapFailed` will be returned if the shared secret can't be unwrapped
+    pub fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        let kem_ct_len = match self.kem.get_kem_info().lengths().ct {
+            Some(len) => len,
+            None => return Err(QuantCryptError::NotImplemented),
+        };
+        if ct.len() <= kem_ct_len {
+            return Err(QuantCryptError::InvalidCiphertext);
+        }
+        let (kem_ct, wrapped) = ct.split_at(kem_ct_len);
+        let kem_ss = Zeroizing::new(self.kem.decap(sk, kem_ct)?);
+        let kek = self.derive_kek(&kem_ss, kem_ct)?;
+        self.wrap
+            .unwrap(&kek, wrapped)
+            .map(Zeroizing::new)
+            .map_err(|_| QuantCryptError::DecapFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn test_multi_recipient_kem() {
+        for kem_type in [KemType::MlKem512, KemType::X25519, KemType::MlKem768X25519] {
+            let mut kem = KemManager::new(kem_type.clone()).unwrap();
+            let keys: Vec<_> = (0..3).map(|_| kem.key_gen().unwrap()).collect();
+            let pks: Vec<&[u8]> = keys.iter().map(|(pk, _)| pk.as_slice()).collect();
+
+            let mut mkem = MultiRecipientKem::new(kem_type.clone()).unwrap();
+            assert_eq!(mkem.get_kem_type(), kem_type);
+            let (ss, cts) = mkem.encap(&pks).unwrap();
+            assert_eq!(ss.len(), SHARED_SECRET_LEN);
+            assert_eq!(cts.len(), keys.len());
+            for ((_, sk), ct) in keys.iter().zip(&cts) {
+                assert_eq!(mkem.decap(sk, ct).unwrap(), ss);
+            }
+
+            // A modified wrapped secret fails instead of decapsulating a related secret
+            let mut ct = cts[0].clone();
+            *ct.last_mut().unwrap() ^= 1;
+            assert!(matches!(
+                mkem.decap(&keys[0].1, &ct),
+                Err(QuantCryptError::DecapFailed)
+            ));
+
+            // Another recipient's ciphertext doesn't decapsulate
+            assert!(mkem.decap(&keys[0].1, &cts[1]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_multi_recipient_kem_new_with_rng() {
+        let mut kem = KemManager::new(KemType::MlKem768).unwrap();
+        let keys: Vec<_> = (0..2).map(|_| kem.key_gen().unwrap()).collect();
+        let pks: Vec<&[u8]> = keys.iter().map(|(pk, _)| pk.as_slice()).collect();
+
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let rng = SharedRng::new(ChaCha20Rng::from_seed([5u8; 32]));
+            let mut mkem = MultiRecipientKem::new_with_rng(KemType::MlKem768, rng).unwrap();
+            let (ss, cts) = mkem.encap(&pks).unwrap();
+            for ((_, sk), ct) in keys.iter().zip(&cts) {
+                assert_eq!(mkem.decap(sk, ct).unwrap(), ss);
+            }
+            outputs.push((ss, cts));
+        }
+        // The same RNG stream yields the same secret and ciphertexts
+        assert_eq!(outputs[0], outputs[1]);
+    }
+}
@@ -1,12 +1,13 @@
-use crate::asn1::asn_util::oid_to_der;
 use crate::asn1::composite_private_key::CompositePrivateKey;
 use crate::asn1::composite_public_key::CompositePublicKey;
 use crate::kem::asn1::composite_kem_primitives::CompositeCiphertextValue;
-use crate::kem::common::kdf::{Kdf, KdfType};
+use crate::kem::common::combined_kem::{Combiner, CombinerInput, KdfCombiner};
 use crate::kem::common::kem_info::KemInfo;
 use crate::kem::common::kem_trait::Kem;
 use crate::kem::common::kem_type::KemType;
 use crate::kem::kem_manager::KemManager;
+use crate::kem::ml_kem::PreparedMlKemKey;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::QuantCryptError;
 use der::{Decode, Encode};
 use pkcs8::{AlgorithmIdentifierRef, ObjectIdentifier, PrivateKeyInfo};
@@ -14,6 +15,8 @@ use rand_core::CryptoRngCore;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+// Implement clone
+#[derive(Clone)]
 /// A KEM manager for the composite KEM method
 pub struct CompositeKemManager {
     /// The KEM metadata information
@@ -22,11 +25,43 @@ pub struct CompositeKemManager {
     trad_kem: Box<KemManager>,
     /// The post-quantum KEM manager
     pq_kem: Box<KemManager>,
-    /// The key derivation function
-    kdf: Kdf,
+    /// The combiner of the shared secrets
+    combiner: KdfCombiner,
+    /// The RNG supplied by the caller, also used by the components
+    rng: Option<SharedRng>,
 }
 
 impl CompositeKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// The traditional and post-quantum components share the RNG.
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if a component is provided by a registered backend
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng)?;
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager and its components from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) -> Result<()> {
+        self.trad_kem.set_rng(rng.clone())?;
+        self.pq_kem.set_rng(rng.clone())?;
+        self.rng = Some(rng);
+        Ok(())
+    }
+
     /// See the combiner function in the RFC:
     /// https://lamps-wg.github.io/draft-composite-kem/draft-ietf-lamps-pq-composite-kem.html
     ///
@@ -49,18 +84,101 @@ impl CompositeKemManager {
         trad_ct: &[u8],
         trad_pk: &[u8],
     ) -> Result<Vec<u8>> {
-        let mut combined_ss: Vec<u8> = Vec::new();
-        combined_ss.extend_from_slice(pq_kem_ss);
-        combined_ss.extend_from_slice(trad_kem_ss);
-        combined_ss.extend_from_slice(trad_ct);
-        combined_ss.extend_from_slice(trad_pk);
+        self.combine_with_context(pq_kem_ss, trad_kem_ss, trad_ct, trad_pk, &[])
+    }
 
-        let dom_sep = oid_to_der(&self.kem_info.oid)?;
-        combined_ss.extend_from_slice(&dom_sep);
+    /// Apply the combiner function, binding the shared secret to a context
+    ///
+    /// The context is appended to the input of the KDF, after the domain separator.
+    fn combine_with_context(
+        &self,
+        pq_kem_ss: &[u8],
+        trad_kem_ss: &[u8],
+        trad_ct: &[u8],
+        trad_pk: &[u8],
+        context: &[u8],
+    ) -> Result<Vec<u8>> {
+        // The post-quantum ciphertext and public key aren't bound
+        let input = CombinerInput {
+            ss_pq: pq_kem_ss,
+            ss_t: trad_kem_ss,
+            ct_pq: &[],
+            ct_t: trad_ct,
+            pk_pq: &[],
+            pk_t: trad_pk,
+        };
+        self.combiner.combine_with_context(&input, context)
+    }
 
-        let ss = self.kdf.kdf(&combined_ss);
+    /// Replace the domain separation label of the combiner
+    ///
+    /// The shared secrets are then no longer interoperable with other
+    /// implementations of the composite KEM.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label to use instead of the DER encoded OID
+    pub(crate) fn set_combiner_label(&mut self, label: &[u8]) -> Result<()> {
+        let info = self
+            .kem_info
+            .combiner
+            .as_mut()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        info.label = label.to_vec();
+        info.label_oid = None;
+        self.combiner = KdfCombiner::from_info(info)?;
+        Ok(())
+    }
 
-        Ok(ss)
+    /// Get the OID of the composite KEM, which keys and ciphertexts are encoded with
+    fn oid(&self) -> Result<&str> {
+        self.kem_info
+            .oid
+            .as_deref()
+            .ok_or(QuantCryptError::InvalidOid)
+    }
+
+    /// Decode a composite public key once, for repeated encapsulations with `encap_prepared`
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The composite public key
+    ///
+    /// # Returns
+    ///
+    /// The decoded ML-KEM key and the traditional public key, or `None` if the
+    /// ML-KEM component is provided by a registered backend
+    pub(crate) fn prepare(&self, pk: &[u8]) -> Result<Option<(PreparedMlKemKey, Vec<u8>)>> {
+        if !matches!(self.pq_kem.as_ref(), KemManager::Ml(_)) {
+            return Ok(None);
+        }
+        let c_pk = CompositePublicKey::from_der(self.oid()?, pk)?;
+        let pq_pk = PreparedMlKemKey::new(&self.pq_kem.get_kem_info().kem_type, &c_pk.get_pq_pk())?;
+        Ok(Some((pq_pk, c_pk.get_trad_pk())))
+    }
+
+    /// Encapsulate a composite public key decoded with `prepare`
+    ///
+    /// # Arguments
+    ///
+    /// * `pq_pk` - The decoded ML-KEM public key
+    /// * `t_pk` - The traditional public key
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    pub(crate) fn encap_prepared(
+        &mut self,
+        pq_pk: &PreparedMlKemKey,
+        t_pk: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (t_ss, t_ct) = self.trad_kem.encap(t_pk)?;
+        let (pq_ss, pq_ct) = pq_pk.encap(&mut ManagerRng::for_operation(self.rng.as_ref()))?;
+
+        let ct = CompositeCiphertextValue::new(&pq_ct, &t_ct);
+        let ct = ct.to_der().map_err(|_| QuantCryptError::EncapFailed)?;
+        let ss = self.combiner(&pq_ss, &t_ss, &t_ct, t_pk)?;
+        Ok((ss, ct))
     }
 
     /// Generate a composite KEM keypair from constituent keys
@@ -76,7 +194,7 @@ impl CompositeKemManager {
     ///
     /// A tuple containing the composite public key and secret key. It is CompositeKEMPublicKey, CompositeKEMPrivateKey
     /// objects in ASN.1 format converted to DER
-    fn key_gen_composite(
+    pub(crate) fn key_gen_composite(
         &self,
         t_pk: &[u8],
         t_sk: &[u8],
@@ -84,12 +202,11 @@ impl CompositeKemManager {
         pq_sk: &[u8],
     ) -> Result<(Vec<u8>, Vec<u8>)> {
         // Create the composite public key
-        let c_pk = CompositePublicKey::new(&self.kem_info.oid, pq_pk, t_pk);
+        let c_pk = CompositePublicKey::new(self.oid()?, pq_pk, t_pk);
         let pk = c_pk.to_der()?;
 
         let oid: ObjectIdentifier = self
-            .kem_info
-            .oid
+            .oid()?
             .parse()
             .map_err(|_| QuantCryptError::InvalidOid)?;
 
@@ -117,6 +234,7 @@ impl CompositeKemManager {
             .pq_kem
             .get_kem_info()
             .oid
+            .ok_or(QuantCryptError::InvalidOid)?
             .parse()
             .map_err(|_| QuantCryptError::InvalidOid)?;
 
@@ -131,11 +249,79 @@ impl CompositeKemManager {
         };
 
         // Create the composite secret key
-        let c_sk = CompositePrivateKey::new_kem(&self.kem_info.oid, &pq_sk_pkcs8, &t_sk_pkcs8)?;
+        let c_sk = CompositePrivateKey::new_kem(self.oid()?, &pq_sk_pkcs8, &t_sk_pkcs8)?;
         let sk = c_sk.to_der()?;
 
         Ok((pk, sk))
     }
+
+    /// Generate a composite KEM keypair from existing component keys
+    ///
+    /// This is meant for migrations, where a deployed traditional key, such as
+    /// an X25519 key, is kept and paired with a post-quantum key. Each
+    /// component keypair is checked by encapsulating to its public key and
+    /// decapsulating with its secret key.
+    ///
+    /// # Arguments
+    ///
+    /// * `t_pk` - The existing traditional public key
+    /// * `t_sk` - The existing traditional secret key
+    /// * `pq_keys` - The existing post-quantum keypair (pk, sk), or None to generate a fresh one
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the composite public key and secret key (pk, sk),
+    /// encoded as by `key_gen`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` if a secret key doesn't match its public key
+    ///
+    /// # Example
+    /// ```
+    /// use quantcrypt::kems::{CompositeKemManager, Kem, KemManager, KemType};
+    ///
+    /// // The deployed X25519 key
+    /// let (t_pk, t_sk) = KemManager::new(KemType::X25519).unwrap().key_gen().unwrap();
+    ///
+    /// let mut kem = CompositeKemManager::new(KemType::MlKem768X25519).unwrap();
+    /// let (pk, sk) = kem.key_gen_from_parts(&t_pk, &t_sk, None).unwrap();
+    /// let (ss, ct) = kem.encap(&pk).unwrap();
+    /// assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+    /// ```
+    pub fn key_gen_from_parts(
+        &mut self,
+        t_pk: &[u8],
+        t_sk: &[u8],
+        pq_keys: Option<(&[u8], &[u8])>,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        check_key_pair(&mut self.trad_kem, t_pk, t_sk)?;
+
+        let (pq_pk, pq_sk) = match pq_keys {
+            Some((pq_pk, pq_sk)) => {
+                check_key_pair(&mut self.pq_kem, pq_pk, pq_sk)?;
+                (pq_pk.to_vec(), pq_sk.to_vec())
+            }
+            None => self.pq_kem.key_gen()?,
+        };
+
+        self.key_gen_composite(t_pk, t_sk, &pq_pk, &pq_sk)
+    }
+}
+
+/// Check that a secret key matches a public key by encapsulating and decapsulating
+///
+/// # Arguments
+///
+/// * `kem` - The KEM of the keys
+/// * `pk` - The public key
+/// * `sk` - The secret key
+fn check_key_pair(kem: &mut KemManager, pk: &[u8], sk: &[u8]) -> Result<()> {
+    let (ss, ct) = kem.encap(pk)?;
+    match kem.decap(sk, &ct) {
+        Ok(decapped) if decapped == ss => Ok(()),
+        _ => Err(QuantCryptError::InvalidPrivateKey),
+    }
 }
 
 impl Kem for CompositeKemManager {
@@ -149,67 +335,33 @@ impl Kem for CompositeKemManager {
     ///
     /// A new KEM instance
     fn new(kem_type: KemType) -> Result<Self> {
-        let kem_info = KemInfo::new(kem_type.clone());
-        let result = match kem_type {
-            KemType::MlKem768Rsa2048 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::RsaOAEP2048)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem768)?),
-                kdf: Kdf::new(KdfType::HkdfSha256),
-            },
-            KemType::MlKem768Rsa3072 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::RsaOAEP3072)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem768)?),
-                kdf: Kdf::new(KdfType::HkdfSha256),
-            },
-            KemType::MlKem768Rsa4096 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::RsaOAEP4096)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem768)?),
-                kdf: Kdf::new(KdfType::HkdfSha256),
-            },
-            KemType::MlKem768X25519 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::X25519)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem768)?),
-                kdf: Kdf::new(KdfType::Sha3_256),
-            },
-            KemType::MlKem768P384 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::P384)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem768)?),
-                kdf: Kdf::new(KdfType::HkdfSha256),
-            },
-            KemType::MlKem768BrainpoolP256r1 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::BrainpoolP256r1)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem768)?),
-                kdf: Kdf::new(KdfType::HkdfSha256),
-            },
-            KemType::MlKem1024P384 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::P384)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem1024)?),
-                kdf: Kdf::new(KdfType::Sha3_256),
-            },
-            KemType::MlKem1024BrainpoolP384r1 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::BrainpoolP384r1)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem1024)?),
-                kdf: Kdf::new(KdfType::Sha3_256),
-            },
-            KemType::MlKem1024X448 => Self {
-                kem_info,
-                trad_kem: Box::new(KemManager::new(KemType::X448)?),
-                pq_kem: Box::new(KemManager::new(KemType::MlKem1024)?),
-                kdf: Kdf::new(KdfType::Sha3_256),
-            },
+        let (trad, pq) = match kem_type {
+            KemType::MlKem768Rsa2048 => (KemType::RsaOAEP2048, KemType::MlKem768),
+            KemType::MlKem768Rsa3072 => (KemType::RsaOAEP3072, KemType::MlKem768),
+            KemType::MlKem768Rsa4096 => (KemType::RsaOAEP4096, KemType::MlKem768),
+            KemType::MlKem768X25519 => (KemType::X25519, KemType::MlKem768),
+            KemType::MlKem768P384 => (KemType::P384, KemType::MlKem768),
+            KemType::MlKem768BrainpoolP256r1 => (KemType::BrainpoolP256r1, KemType::MlKem768),
+            KemType::MlKem1024P384 => (KemType::P384, KemType::MlKem1024),
+            KemType::MlKem1024BrainpoolP384r1 => (KemType::BrainpoolP384r1, KemType::MlKem1024),
+            KemType::MlKem1024X448 => (KemType::X448, KemType::MlKem1024),
             _ => {
                 return Err(QuantCryptError::NotImplemented);
             }
         };
-        Ok(result)
+        let kem_info = KemInfo::new(kem_type);
+        // The KDF and the domain separator (the DER encoded OID) of the combiner
+        let combiner_info = kem_info
+            .combiner
+            .as_ref()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        Ok(Self {
+            combiner: KdfCombiner::from_info(combiner_info)?,
+            trad_kem: Box::new(KemManager::new(trad)?),
+            pq_kem: Box::new(KemManager::new(pq)?),
+            kem_info,
+            rng: None,
+        })
     }
 
     /// Generate a composite KEM keypair using the default RNGs of the
@@ -276,8 +428,22 @@ impl Kem for CompositeKemManager {
     /// The shared secret is the result of the combiner function, and the
     /// ciphertext is the CompositeCiphertextValue in ASN.1 format converted to DER
     fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.encap_with_context(pk, &[])
+    }
+
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The composite public key to encapsulate
+    /// * `context` - The context mixed into the combiner, e.g. the protocol transcript
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
         // Deserialize the composite public key
-        let c_pk = CompositePublicKey::from_der(&self.kem_info.oid, pk)?;
+        let c_pk = CompositePublicKey::from_der(self.oid()?, pk)?;
 
         // Encapsulate the public key for the traditional KEM
         let (t_ss, t_ct) = self.trad_kem.encap(&c_pk.get_trad_pk())?;
@@ -289,12 +455,76 @@ impl Kem for CompositeKemManager {
         let ct = CompositeCiphertextValue::new(&pq_ct, &t_ct);
         let ct = ct.to_der().map_err(|_| QuantCryptError::EncapFailed)?;
 
+        // Get the shared secret using the combiner
+        let ss = self.combine_with_context(&pq_ss, &t_ss, &t_ct, &c_pk.get_trad_pk(), context)?;
+
+        Ok((ss, ct))
+    }
+
+    /// Encapsulate a public key with the given randomness
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The composite public key to encapsulate
+    /// * `coins` - The 32 byte ML-KEM message followed by the coins of the traditional KEM
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if coins.len() < 32 {
+            return Err(QuantCryptError::InvalidCoinsLength {
+                expected: 32,
+                actual: coins.len(),
+            });
+        }
+        let (pq_coins, t_coins) = coins.split_at(32);
+
+        // Deserialize the composite public key
+        let c_pk = CompositePublicKey::from_der(self.oid()?, pk)?;
+
+        // Encapsulate the public keys of the components with their coins
+        let (t_ss, t_ct) = self
+            .trad_kem
+            .encap_deterministic(&c_pk.get_trad_pk(), t_coins)?;
+        let (pq_ss, pq_ct) = self
+            .pq_kem
+            .encap_deterministic(&c_pk.get_pq_pk(), pq_coins)?;
+
+        // Create the composite ciphertext
+        let ct = CompositeCiphertextValue::new(&pq_ct, &t_ct);
+        let ct = ct.to_der().map_err(|_| QuantCryptError::EncapFailed)?;
+
         // Get the shared secret using the combiner
         let ss = self.combiner(&pq_ss, &t_ss, &t_ct, &c_pk.get_trad_pk())?;
 
         Ok((ss, ct))
     }
 
+    /// Encapsulate a batch of composite public keys
+    ///
+    /// # Arguments
+    ///
+    /// * `pks` - The composite public keys to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct) for each public key
+    fn encap_batch(&mut self, pks: &[&[u8]]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            let kem = &*self;
+            pks.par_iter()
+                .map_init(|| kem.clone(), |kem, pk| kem.encap(pk))
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            pks.iter().map(|pk| self.encap(pk)).collect()
+        }
+    }
+
     /// Decapsulate a ciphertext
     ///
     /// # Arguments
@@ -306,8 +536,23 @@ impl Kem for CompositeKemManager {
     ///
     /// The shared secret after applying the combiner function
     fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        self.decap_with_context(sk, ct, &[])
+    }
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The composite secret key to decapsulate
+    /// * `ct` - The composite ciphertext to decapsulate
+    /// * `context` - The context given to `encap_with_context`
+    ///
+    /// # Returns
+    ///
+    /// The shared secret after applying the combiner function
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>> {
         // Deserialize the composite secret key
-        let c_sk = CompositePrivateKey::from_der(&self.kem_info.oid, sk)?;
+        let c_sk = CompositePrivateKey::from_der(self.oid()?, sk)?;
 
         // Deserialize the composite ciphertext
         let c_ct =
@@ -330,7 +575,7 @@ impl Kem for CompositeKemManager {
             .ok_or(QuantCryptError::DecapFailed)?;
 
         // Get the shared secret using the combiner
-        let ss = self.combiner(&pq_ss, &t_ss, &c_ct.get_trad_ct(), t_pk)?;
+        let ss = self.combine_with_context(&pq_ss, &t_ss, &c_ct.get_trad_ct(), t_pk, context)?;
 
         Ok(ss)
     }
@@ -350,7 +595,7 @@ impl Kem for CompositeKemManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::kem::common::macros::test_kem;
+    use crate::test_kem;
 
     #[test]
     fn test_mlkem_768_rsa2048() {
@@ -382,6 +627,7 @@ mod tests {
         test_kem!(kem);
     }
 
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_mlkem_768_brainpool_p256r1() {
         let kem = CompositeKemManager::new(KemType::MlKem768BrainpoolP256r1);
@@ -394,6 +640,7 @@ mod tests {
         test_kem!(kem);
     }
 
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_mlkem_1024_brainpool_p384r1() {
         let kem = CompositeKemManager::new(KemType::MlKem1024BrainpoolP384r1);
@@ -405,4 +652,81 @@ mod tests {
         let kem = CompositeKemManager::new(KemType::MlKem1024X448);
         test_kem!(kem);
     }
+
+    #[test]
+    fn test_encap_deterministic() {
+        for (kem_type, t_coins_len) in [
+            (KemType::MlKem768X25519, 32),
+            (KemType::MlKem768P384, 48),
+            (KemType::MlKem1024X448, 56),
+        ] {
+            let mut kem = CompositeKemManager::new(kem_type).unwrap();
+            let (pk, sk) = kem.key_gen().unwrap();
+            let coins = vec![7u8; 32 + t_coins_len];
+            let (ss, ct) = kem.encap_deterministic(&pk, &coins).unwrap();
+            assert_eq!(
+                kem.encap_deterministic(&pk, &coins).unwrap(),
+                (ss.clone(), ct.clone())
+            );
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+            assert!(kem.encap_deterministic(&pk, &coins[1..]).is_err());
+        }
+
+        // RSA-KEM has no deterministic encapsulation
+        let mut kem = CompositeKemManager::new(KemType::MlKem768Rsa2048).unwrap();
+        let (pk, _) = kem.key_gen().unwrap();
+        assert_eq!(
+            kem.encap_deterministic(&pk, &[0u8; 64]),
+            Err(QuantCryptError::NotImplemented)
+        );
+    }
+
+    #[test]
+    fn test_encap_with_context() {
+        let mut kem = CompositeKemManager::new(KemType::MlKem768X25519).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let context = b"client hello || server hello";
+        let (ss, ct) = kem.encap_with_context(&pk, context).unwrap();
+        assert_eq!(kem.decap_with_context(&sk, &ct, context).unwrap(), ss);
+
+        // A peer with another transcript derives another secret
+        assert_ne!(kem.decap_with_context(&sk, &ct, b"downgraded").unwrap(), ss);
+        assert_ne!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // An empty context derives the secret of the plain combiner
+        let (ss, ct) = kem.encap_with_context(&pk, &[]).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn test_key_gen_from_parts() {
+        let mut x25519 = KemManager::new(KemType::X25519).unwrap();
+        let (t_pk, t_sk) = x25519.key_gen().unwrap();
+        let mut ml_kem = KemManager::new(KemType::MlKem768).unwrap();
+        let (pq_pk, pq_sk) = ml_kem.key_gen().unwrap();
+
+        let mut kem = CompositeKemManager::new(KemType::MlKem768X25519).unwrap();
+        for pq_keys in [None, Some((pq_pk.as_slice(), pq_sk.as_slice()))] {
+            let (pk, sk) = kem.key_gen_from_parts(&t_pk, &t_sk, pq_keys).unwrap();
+            let c_pk = CompositePublicKey::from_der(kem.oid().unwrap(), &pk).unwrap();
+            assert_eq!(c_pk.get_trad_pk(), t_pk);
+            if pq_keys.is_some() {
+                assert_eq!(c_pk.get_pq_pk(), pq_pk);
+            }
+            let (ss, ct) = kem.encap(&pk).unwrap();
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        }
+
+        // Mismatched component keys are rejected
+        let (other_pk, _) = x25519.key_gen().unwrap();
+        assert_eq!(
+            kem.key_gen_from_parts(&other_pk, &t_sk, None),
+            Err(QuantCryptError::InvalidPrivateKey)
+        );
+        let (other_pq_pk, _) = ml_kem.key_gen().unwrap();
+        assert_eq!(
+            kem.key_gen_from_parts(&t_pk, &t_sk, Some((&other_pq_pk, &pq_sk))),
+            Err(QuantCryptError::InvalidPrivateKey)
+        );
+    }
 }
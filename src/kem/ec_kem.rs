@@ -1,23 +1,316 @@
 // use the macros to generate the encapsulate functio
-use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_info::{KemInfo, PointEncoding};
 use crate::kem::common::kem_trait::Kem;
 use crate::kem::common::kem_type::KemType;
+#[cfg(feature = "pure-rust")]
+use crate::utils::curve25519::{x25519_derive, x25519_encap, x25519_key_gen, x25519_public_key};
+#[cfg(feature = "openssl")]
+use crate::utils::curve448::openssl_supports;
+use crate::utils::curve448::{x448_derive, x448_encap, x448_key_gen, x448_public_key};
+use crate::utils::entropy::{ManagerRng, SharedRng};
+#[cfg(feature = "pure-rust")]
+use crate::utils::nist_curves::{
+    nist_convert_point, nist_derive, nist_encap, nist_key_gen, nist_public_key, NistCurve,
+};
+#[cfg(feature = "openssl")]
 use crate::utils::openssl_utils::{
-    decaps_ec_based, decaps_pkey_based, encaps_ec_based, encaps_pkey_based, get_key_pair_ec_based,
-    get_key_pair_ec_based_with_rng, get_key_pair_pkey_based, get_keypair_pkey_based_with_rng,
+    convert_ec_point, decaps_ec_based, decaps_pkey_based, encaps_ec_based, encaps_pkey_based,
+    get_key_pair_ec_based, get_key_pair_ec_based_with_rng, get_key_pair_pkey_based,
+    get_keypair_pkey_based_with_rng, get_pk_from_sk_ec_based, get_pk_from_sk_pkey_based,
+    openssl_supports_curve,
 };
 use crate::QuantCryptError;
+#[cfg(feature = "openssl")]
 use openssl::nid::Nid;
+#[cfg(feature = "openssl")]
 use openssl::pkey::Id;
 use rand_core::CryptoRngCore;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// Check if the linked OpenSSL computes a curve
+///
+/// Older distribution builds of OpenSSL lack X448 or the Brainpool curves.
+/// Without the `openssl` feature no curve is computed by OpenSSL.
+#[cfg(feature = "openssl")]
+fn openssl_computes(kem_type: &KemType) -> bool {
+    match kem_type {
+        KemType::BrainpoolP256r1 => openssl_supports_curve(Nid::BRAINPOOL_P256R1),
+        KemType::BrainpoolP384r1 => openssl_supports_curve(Nid::BRAINPOOL_P384R1),
+        KemType::X448 => openssl_supports(Id::X448),
+        _ => true,
+    }
+}
+
+/// Check if the linked OpenSSL computes a curve
+///
+/// Older distribution builds of OpenSSL lack X448 or the Brainpool curves.
+/// Without the `openssl` feature no curve is computed by OpenSSL.
+#[cfg(not(feature = "openssl"))]
+fn openssl_computes(_kem_type: &KemType) -> bool {
+    false
+}
+
+/// Check if a curve has a pure-Rust implementation
+///
+/// X448 always has one, X25519, P-256 and P-384 with the `pure-rust` feature.
+/// The Brainpool curves have none.
+fn has_fallback(kem_type: &KemType) -> bool {
+    *kem_type == KemType::X448
+        || (cfg!(feature = "pure-rust")
+            && matches!(kem_type, KemType::X25519 | KemType::P256 | KemType::P384))
+}
+
+// Implement clone
+#[derive(Clone)]
 /// A KEM manager for the DhKem method
 pub struct EcKemManager {
     kem_info: KemInfo,
-    ec_based_nid: Option<Nid>,
-    pk_based_id: Option<Id>,
+    kem_type: KemType,
+    rng: Option<SharedRng>,
+}
+
+impl EcKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// Key pairs and ephemeral keys are then derived from the RNG, instead of
+    /// being generated by OpenSSL.
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.rng = Some(rng);
+    }
+
+    /// Set the encoding of the EC points in the public keys and ciphertexts this manager emits
+    ///
+    /// Public keys and ciphertexts are always accepted in both encodings. The
+    /// uncompressed encoding is the default, as required by RFC 9180 and the
+    /// composite KEM drafts; the compressed one is for constrained peers.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoding` - The point encoding
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` for X25519 and X448, which have a single encoding
+    #[allow(dead_code)]
+    pub fn set_point_encoding(&mut self, encoding: PointEncoding) -> Result<()> {
+        if matches!(self.kem_type, KemType::X25519 | KemType::X448) {
+            return Err(QuantCryptError::UnsupportedOperation);
+        }
+        if self.kem_info.point_encoding != Some(encoding) {
+            // The uncompressed form is 1 + 2 * field length bytes, the compressed form 1 + field length
+            let len = |l: usize| match encoding {
+                PointEncoding::Compressed => (l - 1) / 2 + 1,
+                PointEncoding::Uncompressed => (l - 1) * 2 + 1,
+            };
+            self.kem_info.pk_byte_len = self.kem_info.pk_byte_len.map(len);
+            self.kem_info.ct_byte_len = self.kem_info.ct_byte_len.map(len);
+            self.kem_info.point_encoding = Some(encoding);
+        }
+        Ok(())
+    }
+
+    /// Check if the pure-Rust implementation of the curve is used instead of OpenSSL
+    ///
+    /// X448 falls back to it if OpenSSL lacks X448. With the `pure-rust`
+    /// feature X25519, X448, P-256 and P-384 always use it, and without the
+    /// `openssl` feature every curve does.
+    #[cfg(any(feature = "openssl", feature = "pure-rust"))]
+    fn use_fallback(&self) -> bool {
+        has_fallback(&self.kem_type)
+            && (cfg!(feature = "pure-rust") || !openssl_computes(&self.kem_type))
+    }
+
+    /// Get the NIST curve of P-256 and P-384
+    #[cfg(feature = "pure-rust")]
+    fn nist_curve(&self) -> NistCurve {
+        match self.kem_type {
+            KemType::P256 => NistCurve::P256,
+            _ => NistCurve::P384,
+        }
+    }
+
+    /// Generate a key pair with the pure-Rust implementation of the curve
+    fn fallback_key_gen(&self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self.kem_type {
+            #[cfg(feature = "pure-rust")]
+            KemType::P256 | KemType::P384 => nist_key_gen(self.nist_curve(), rng),
+            #[cfg(feature = "pure-rust")]
+            KemType::X25519 => x25519_key_gen(rng),
+            _ => x448_key_gen(rng),
+        }
+    }
+
+    /// Encapsulate with the pure-Rust implementation of the curve
+    fn fallback_encap(&self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self.kem_type {
+            #[cfg(feature = "pure-rust")]
+            KemType::P256 | KemType::P384 => nist_encap(self.nist_curve(), pk),
+            #[cfg(feature = "pure-rust")]
+            KemType::X25519 => x25519_encap(pk),
+            _ => x448_encap(pk),
+        }
+    }
+
+    /// Compute the public key with the pure-Rust implementation of the curve
+    fn fallback_public_key(&self, sk: &[u8]) -> Result<Vec<u8>> {
+        match self.kem_type {
+            #[cfg(feature = "pure-rust")]
+            KemType::P256 | KemType::P384 => nist_public_key(self.nist_curve(), sk),
+            #[cfg(feature = "pure-rust")]
+            KemType::X25519 => x25519_public_key(sk),
+            _ => x448_public_key(sk),
+        }
+    }
+
+    /// Derive the shared secret with the pure-Rust implementation of the curve
+    fn fallback_derive(&self, sk: &[u8], pk: &[u8]) -> Result<Vec<u8>> {
+        match self.kem_type {
+            #[cfg(feature = "pure-rust")]
+            KemType::P256 | KemType::P384 => nist_derive(self.nist_curve(), sk, pk),
+            #[cfg(feature = "pure-rust")]
+            KemType::X25519 => x25519_derive(sk, pk),
+            _ => x448_derive(sk, pk),
+        }
+    }
+
+    /// Compute the public key of a secret key
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key
+    ///
+    /// # Returns
+    ///
+    /// The public key, in the configured point encoding
+    pub(crate) fn get_public_key(&self, sk: &[u8]) -> Result<Vec<u8>> {
+        #[cfg(feature = "openssl")]
+        if !self.use_fallback() {
+            return self.openssl_public_key(sk);
+        }
+        let point = self.fallback_public_key(sk)?;
+        self.encode_point(point)
+    }
+
+    /// Encode an uncompressed point in the configured encoding
+    fn encode_point(&self, point: Vec<u8>) -> Result<Vec<u8>> {
+        if self.kem_info.point_encoding != Some(PointEncoding::Compressed) {
+            return Ok(point);
+        }
+        #[cfg(feature = "pure-rust")]
+        if self.use_fallback() {
+            return nist_convert_point(self.nist_curve(), &point, true);
+        }
+        #[cfg(feature = "openssl")]
+        if let Some(nid) = self.nid() {
+            return convert_ec_point(&point, nid, true)
+                .map_err(|_| QuantCryptError::InvalidPublicKey);
+        }
+        Ok(point)
+    }
+}
+
+/// The OpenSSL implementation of the curves
+#[cfg(feature = "openssl")]
+impl EcKemManager {
+    /// Get the OpenSSL NID of the P and Brainpool curves
+    fn nid(&self) -> Option<Nid> {
+        match self.kem_type {
+            KemType::P256 => Some(Nid::X9_62_PRIME256V1),
+            KemType::P384 => Some(Nid::SECP384R1),
+            KemType::BrainpoolP256r1 => Some(Nid::BRAINPOOL_P256R1),
+            KemType::BrainpoolP384r1 => Some(Nid::BRAINPOOL_P384R1),
+            _ => None,
+        }
+    }
+
+    /// Get the OpenSSL ID of X25519 and X448
+    fn id(&self) -> Option<Id> {
+        match self.kem_type {
+            KemType::X25519 => Some(Id::X25519),
+            KemType::X448 => Some(Id::X448),
+            _ => None,
+        }
+    }
+
+    /// Generate a key pair with OpenSSL
+    fn openssl_key_gen(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        if let Some(nid) = self.nid() {
+            let (pk, sk) =
+                get_key_pair_ec_based(nid).map_err(|_| QuantCryptError::KeyPairGenerationFailed)?;
+            Ok((self.encode_point(pk)?, sk))
+        } else if let Some(id) = self.id() {
+            get_key_pair_pkey_based(id).map_err(|_| QuantCryptError::KeyPairGenerationFailed)
+        } else {
+            Err(QuantCryptError::NotImplemented)
+        }
+    }
+
+    /// Generate a key pair with OpenSSL from a random number generator
+    fn openssl_key_gen_with_rng(&self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        if let Some(nid) = self.nid() {
+            let (pk, sk) = get_key_pair_ec_based_with_rng(rng, nid)
+                .map_err(|_| QuantCryptError::KeyPairGenerationFailed)?;
+            Ok((self.encode_point(pk)?, sk))
+        } else if let Some(id) = self.id() {
+            get_keypair_pkey_based_with_rng(rng, id)
+                .map_err(|_| QuantCryptError::KeyPairGenerationFailed)
+        } else {
+            Err(QuantCryptError::NotImplemented)
+        }
+    }
+
+    /// Encapsulate with OpenSSL
+    fn openssl_encap(&self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if let Some(nid) = self.nid() {
+            let (ss, ct) = encaps_ec_based(pk, nid).map_err(|_| QuantCryptError::EncapFailed)?;
+            Ok((ss, self.encode_point(ct)?))
+        } else if let Some(id) = self.id() {
+            encaps_pkey_based(pk, id).map_err(|_| QuantCryptError::EncapFailed)
+        } else {
+            Err(QuantCryptError::NotImplemented)
+        }
+    }
+
+    /// Compute the public key with OpenSSL
+    fn openssl_public_key(&self, sk: &[u8]) -> Result<Vec<u8>> {
+        if let Some(nid) = self.nid() {
+            let point =
+                get_pk_from_sk_ec_based(sk, nid).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+            self.encode_point(point)
+        } else if let Some(id) = self.id() {
+            get_pk_from_sk_pkey_based(sk, id).map_err(|_| QuantCryptError::InvalidPrivateKey)
+        } else {
+            Err(QuantCryptError::NotImplemented)
+        }
+    }
+
+    /// Derive the shared secret with OpenSSL
+    fn openssl_derive(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        if let Some(nid) = self.nid() {
+            decaps_ec_based(sk, ct, nid).map_err(|_| QuantCryptError::DecapFailed)
+        } else if let Some(id) = self.id() {
+            decaps_pkey_based(sk, ct, id).map_err(|_| QuantCryptError::DecapFailed)
+        } else {
+            Err(QuantCryptError::NotImplemented)
+        }
+    }
 }
 
 impl Kem for EcKemManager {
@@ -28,25 +321,31 @@ impl Kem for EcKemManager {
     /// * `kem_type` - The type of KEM to create
     fn new(kem_type: KemType) -> Result<Self> {
         let kem_info = KemInfo::new(kem_type.clone());
-        let (ec_based_nid, pk_based_id) = match kem_type {
-            KemType::P256 => (Some(Nid::X9_62_PRIME256V1), None),
-            KemType::P384 => (Some(Nid::SECP384R1), None),
-            KemType::BrainpoolP256r1 => (Some(Nid::BRAINPOOL_P256R1), None),
-            KemType::BrainpoolP384r1 => (Some(Nid::BRAINPOOL_P384R1), None),
-            KemType::X25519 => (None, Some(Id::X25519)),
-            KemType::X448 => (None, Some(Id::X448)),
-            _ => {
-                return Err(QuantCryptError::NotImplemented);
-            }
-        };
+        if !matches!(
+            kem_type,
+            KemType::P256
+                | KemType::P384
+                | KemType::BrainpoolP256r1
+                | KemType::BrainpoolP384r1
+                | KemType::X25519
+                | KemType::X448
+        ) {
+            return Err(QuantCryptError::NotImplemented);
+        }
+        // A curve is computed by OpenSSL or else by its pure-Rust implementation
+        if !openssl_computes(&kem_type) && !has_fallback(&kem_type) {
+            return Err(QuantCryptError::BackendUnavailable {
+                component: format!("{:?}", kem_type),
+            });
+        }
         Ok(Self {
             kem_info,
-            ec_based_nid,
-            pk_based_id,
+            kem_type,
+            rng: None,
         })
     }
 
-    /// Generate a keypair using the default RNG of OpenSSL
+    /// Generate a keypair using the RNG of the manager, or else the health tested system entropy
     ///
     /// # Returns
     ///
@@ -55,13 +354,12 @@ impl Kem for EcKemManager {
     /// pk, sk, ct, lengths are all in accordance with RFC9180.
     /// ss length is different from RFC9180 as it is not hashed.
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-        if let Some(nid) = self.ec_based_nid {
-            get_key_pair_ec_based(nid).map_err(|_| QuantCryptError::KeyPairGenerationFailed)
-        } else if let Some(id) = self.pk_based_id {
-            get_key_pair_pkey_based(id).map_err(|_| QuantCryptError::KeyPairGenerationFailed)
-        } else {
-            return Err(QuantCryptError::NotImplemented);
+        #[cfg(feature = "openssl")]
+        if self.rng.is_none() && !self.use_fallback() {
+            return self.openssl_key_gen();
         }
+        let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
+        self.key_gen_with_rng(&mut rng)
     }
 
     /// Generate a keypair
@@ -77,15 +375,12 @@ impl Kem for EcKemManager {
     /// pk, sk, ct, lengths are all in accordance with RFC9180.
     /// ss length is different from RFC9180 as it is not hashed.
     fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
-        if let Some(nid) = self.ec_based_nid {
-            get_key_pair_ec_based_with_rng(rng, nid)
-                .map_err(|_| QuantCryptError::KeyPairGenerationFailed)
-        } else if let Some(id) = self.pk_based_id {
-            get_keypair_pkey_based_with_rng(rng, id)
-                .map_err(|_| QuantCryptError::KeyPairGenerationFailed)
-        } else {
-            return Err(QuantCryptError::NotImplemented);
+        #[cfg(feature = "openssl")]
+        if !self.use_fallback() {
+            return self.openssl_key_gen_with_rng(rng);
         }
+        let (pk, sk) = self.fallback_key_gen(rng)?;
+        Ok((self.encode_point(pk)?, sk))
     }
 
     /// Encapsulate a public key
@@ -98,13 +393,48 @@ impl Kem for EcKemManager {
     ///
     /// A tuple containing the shared secret and ciphertext (ss, ct)
     fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
-        if let Some(nid) = self.ec_based_nid {
-            encaps_ec_based(pk, nid).map_err(|_| QuantCryptError::EncapFailed)
-        } else if let Some(id) = self.pk_based_id {
-            encaps_pkey_based(pk, id).map_err(|_| QuantCryptError::EncapFailed)
-        } else {
-            return Err(QuantCryptError::NotImplemented);
+        if self.rng.is_some() {
+            // The ephemeral key is drawn from the RNG of the manager
+            let mut rng = ManagerRng::for_operation(self.rng.as_ref());
+            let (_, esk) = self
+                .key_gen_with_rng(&mut rng)
+                .map_err(|_| QuantCryptError::EncapFailed)?;
+            return self.encap_deterministic(pk, &esk);
+        }
+        #[cfg(feature = "openssl")]
+        if !self.use_fallback() {
+            return self.openssl_encap(pk);
+        }
+        let (ss, ct) = self
+            .fallback_encap(pk)
+            .map_err(|_| QuantCryptError::EncapFailed)?;
+        Ok((ss, self.encode_point(ct)?))
+    }
+
+    /// Encapsulate a public key with a given ephemeral secret key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `coins` - The ephemeral secret key
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let expected = self.kem_info.sk_byte_len.unwrap_or(0);
+        if coins.len() != expected {
+            return Err(QuantCryptError::InvalidCoinsLength {
+                expected,
+                actual: coins.len(),
+            });
         }
+        // The ciphertext is the ephemeral public key
+        let ct = self.get_public_key(coins)?;
+        let ss = self
+            .decap(coins, pk)
+            .map_err(|_| QuantCryptError::EncapFailed)?;
+        Ok((ss, ct))
     }
 
     /// Decapsulate a ciphertext
@@ -118,13 +448,12 @@ impl Kem for EcKemManager {
     ///
     /// The shared secret (ss)
     fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
-        if let Some(nid) = self.ec_based_nid {
-            decaps_ec_based(sk, ct, nid).map_err(|_| QuantCryptError::DecapFailed)
-        } else if let Some(id) = self.pk_based_id {
-            decaps_pkey_based(sk, ct, id).map_err(|_| QuantCryptError::DecapFailed)
-        } else {
-            panic!("Not implemented");
+        #[cfg(feature = "openssl")]
+        if !self.use_fallback() {
+            return self.openssl_derive(sk, ct);
         }
+        self.fallback_derive(sk, ct)
+            .map_err(|_| QuantCryptError::DecapFailed)
     }
 
     /// Get KEM metadata information such as the key lengths,
@@ -143,28 +472,64 @@ impl Kem for EcKemManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kem::api::algorithm::KemAlgorithm;
     use crate::kem::common::kem_trait::Kem;
     use crate::kem::common::kem_type::KemType;
-    use crate::kem::common::macros::test_kem;
+    use crate::kem::composite_kem::CompositeKemManager;
+    use crate::test_kem;
 
+    #[test]
+    fn test_ec_kem_backend_unavailable() {
+        for (kem_type, alg) in [
+            (
+                KemType::BrainpoolP256r1,
+                KemAlgorithm::MlKem768BrainpoolP256r1,
+            ),
+            (
+                KemType::BrainpoolP384r1,
+                KemAlgorithm::MlKem1024BrainpoolP384r1,
+            ),
+        ] {
+            // The composite fails on construction, naming the missing component
+            let expected = QuantCryptError::BackendUnavailable {
+                component: format!("{:?}", kem_type),
+            };
+            let supported = openssl_computes(&kem_type);
+            match CompositeKemManager::new(alg.get_kem_type()) {
+                Ok(_) => assert!(supported),
+                Err(e) => assert_eq!(e, expected),
+            }
+            assert_eq!(alg.is_available(), supported);
+            assert_eq!(KemAlgorithm::available().contains(&alg), supported);
+        }
+        let computed = cfg!(any(feature = "openssl", feature = "pure-rust"));
+        assert_eq!(KemAlgorithm::MlKem768X25519.is_available(), computed);
+        assert_eq!(KemAlgorithm::MlKem768P384.is_available(), computed);
+        assert!(KemAlgorithm::MlKem1024X448.is_available());
+    }
+
+    #[cfg(any(feature = "openssl", feature = "pure-rust"))]
     #[test]
     fn test_ec_kem_p256() {
         let kem = EcKemManager::new(KemType::P256);
         test_kem!(kem);
     }
 
+    #[cfg(any(feature = "openssl", feature = "pure-rust"))]
     #[test]
     fn test_ec_kem_p384() {
         let kem = EcKemManager::new(KemType::P384);
         test_kem!(kem);
     }
 
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_ec_kem_brainpool_p256r1() {
         let kem = EcKemManager::new(KemType::BrainpoolP256r1);
         test_kem!(kem);
     }
 
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_ec_kem_brainpool_p384r1() {
         let kem = EcKemManager::new(KemType::BrainpoolP384r1);
@@ -177,9 +542,79 @@ mod tests {
         test_kem!(kem);
     }
 
+    #[cfg(any(feature = "openssl", feature = "pure-rust"))]
     #[test]
     fn test_ec_kem_x25519() {
         let kem = EcKemManager::new(KemType::X25519);
         test_kem!(kem);
     }
+
+    #[cfg(all(feature = "pure-rust", feature = "openssl"))]
+    #[test]
+    fn test_ec_kem_pure_rust_matches_openssl() {
+        for kem_type in [KemType::X25519, KemType::X448, KemType::P256, KemType::P384] {
+            let mut kem = EcKemManager::new(kem_type.clone()).unwrap();
+            assert!(kem.use_fallback());
+            let (pk, sk) = kem.key_gen().unwrap();
+            let (ss, ct) = kem.encap(&pk).unwrap();
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+            if openssl_computes(&kem_type) {
+                assert_eq!(kem.openssl_public_key(&sk).unwrap(), pk);
+                assert_eq!(kem.openssl_derive(&sk, &ct).unwrap(), ss);
+            }
+        }
+        assert!(!EcKemManager::new(KemType::BrainpoolP256r1)
+            .unwrap()
+            .use_fallback());
+    }
+
+    #[test]
+    fn test_ec_kem_compressed_points() {
+        for kem_type in [
+            KemType::P256,
+            KemType::P384,
+            KemType::BrainpoolP256r1,
+            KemType::BrainpoolP384r1,
+        ]
+        .into_iter()
+        .filter(|kem_type| openssl_computes(kem_type) || has_fallback(kem_type))
+        {
+            let mut compressed = EcKemManager::new(kem_type.clone()).unwrap();
+            compressed
+                .set_point_encoding(PointEncoding::Compressed)
+                .unwrap();
+            let kem = Ok::<_, QuantCryptError>(compressed.clone());
+            test_kem!(kem);
+
+            let info = compressed.get_kem_info();
+            assert_eq!(info.point_encoding, Some(PointEncoding::Compressed));
+            let (pk, sk) = compressed.key_gen().unwrap();
+            assert!(pk[0] == 0x02 || pk[0] == 0x03);
+
+            // Uncompressed peers accept compressed public keys and ciphertexts, and vice versa
+            let mut uncompressed = EcKemManager::new(kem_type).unwrap();
+            let (ss, ct) = uncompressed.encap(&pk).unwrap();
+            assert_eq!(ct[0], 0x04);
+            assert_eq!(compressed.decap(&sk, &ct).unwrap(), ss);
+            let (pk, sk) = uncompressed.key_gen().unwrap();
+            let (ss, ct) = compressed.encap(&pk).unwrap();
+            assert_eq!(ct.len(), info.ct_byte_len.unwrap());
+            assert_eq!(uncompressed.decap(&sk, &ct).unwrap(), ss);
+
+            // Switching back restores the uncompressed lengths
+            compressed
+                .set_point_encoding(PointEncoding::Uncompressed)
+                .unwrap();
+            assert_eq!(
+                compressed.get_kem_info().pk_byte_len,
+                uncompressed.get_kem_info().pk_byte_len
+            );
+        }
+
+        let mut x448 = EcKemManager::new(KemType::X448).unwrap();
+        assert_eq!(
+            x448.set_point_encoding(PointEncoding::Compressed),
+            Err(QuantCryptError::UnsupportedOperation)
+        );
+    }
 }
@@ -1,12 +1,28 @@
+use std::sync::Arc;
+
 use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
 
+use crate::kem::bike::BikeKemManager;
+use crate::kem::chempat::ChempatKemManager;
+use crate::kem::common::kem_backend::KemBackend;
 use crate::kem::common::kem_info::KemInfo;
-use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_trait::{check_buffer_len, copy_into, Kem};
 use crate::kem::common::kem_type::KemType;
 use crate::kem::composite_kem::CompositeKemManager;
 use crate::kem::ec_kem::EcKemManager;
+#[cfg(feature = "legacy")]
+use crate::kem::kyber::KyberKemManager;
 use crate::kem::ml_kem::MlKemManager;
 use crate::kem::rsa_kem::RsaKemManager;
+use crate::kem::sntrup::SntrupKemManager;
+use crate::kem::triple_kem::TripleKemManager;
+#[cfg(feature = "legacy")]
+use crate::kem::x25519_kyber::X25519Kyber768Draft00Manager;
+use crate::kem::xwing::XWingKemManager;
+use crate::utils::deprecation::{report_deprecation, DeprecationWarning};
+use crate::utils::entropy::SharedRng;
+use crate::utils::manager_pool::get_kem_backend;
 use crate::QuantCryptError;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
@@ -40,7 +56,21 @@ const COMPOSITE_KEM_TYPES: [KemType; 9] = [
     KemType::MlKem1024X448,
 ];
 
+const TRIPLE_KEM_TYPES: [KemType; 2] = [KemType::MlKem768X25519P256, KemType::MlKem1024X448P384];
+
+// Implement clone
+#[derive(Clone)]
 /// Enum to representthe different types of KEM managers
+///
+/// Managers are `Clone + Send + Sync`, so they can be shared across threads or
+/// stored in a global registry. By default each operation seeds its own RNG.
+/// A manager created with `new_with_rng` holds the `SharedRng`, whose RNG sits
+/// behind a mutex: its clones draw from the same stream, taking turns on the
+/// lock, so the output of one depends on what the others have drawn.
+///
+/// Like `KemType`, the enum is non-exhaustive since its legacy variants depend
+/// on the `legacy` feature.
+#[non_exhaustive]
 pub enum KemManager {
     /// ML KEM manager
     Ml(MlKemManager),
@@ -50,6 +80,24 @@ pub enum KemManager {
     Ec(EcKemManager),
     /// Composite KEM manager
     Composite(CompositeKemManager),
+    /// Three-way hybrid KEM manager
+    Triple(TripleKemManager),
+    /// X-Wing KEM manager, also used for ML-KEM-1024 with X448
+    XWing(Box<XWingKemManager>),
+    /// Chempat generic hybrid KEM manager
+    Chempat(ChempatKemManager),
+    /// Streamlined NTRU Prime KEM manager
+    Sntrup(SntrupKemManager),
+    /// BIKE KEM manager
+    Bike(BikeKemManager),
+    /// Legacy X25519Kyber768Draft00 KEM manager
+    #[cfg(feature = "legacy")]
+    X25519Kyber(Box<X25519Kyber768Draft00Manager>),
+    /// Legacy Kyber round 3 KEM manager
+    #[cfg(feature = "legacy")]
+    Kyber(KyberKemManager),
+    /// An external backend registered for the KEM type
+    Custom(Arc<dyn KemBackend>),
 }
 
 impl Kem for KemManager {
@@ -61,11 +109,15 @@ impl Kem for KemManager {
     ///
     /// # Returns
     ///
-    /// A new KEM manager
+    /// A new KEM manager, using the backend registered for the type if there is one
     fn new(kem_type: KemType) -> Result<Self>
     where
         Self: Sized,
     {
+        report_deprecation(DeprecationWarning::for_kem(&kem_type));
+        if let Some(backend) = get_kem_backend(&kem_type) {
+            return Ok(KemManager::Custom(backend));
+        }
         Ok(match kem_type {
             _ if ML_KEM_TYPES.contains(&kem_type) => KemManager::Ml(MlKemManager::new(kem_type)?),
             _ if RSA_KEM_TYPES.contains(&kem_type) => {
@@ -75,9 +127,36 @@ impl Kem for KemManager {
             _ if COMPOSITE_KEM_TYPES.contains(&kem_type) => {
                 KemManager::Composite(CompositeKemManager::new(kem_type)?)
             }
-            _ => {
-                panic!("Not implemented");
+            _ if TRIPLE_KEM_TYPES.contains(&kem_type) => {
+                KemManager::Triple(TripleKemManager::new(kem_type)?)
+            }
+            KemType::XWing | KemType::MlKem1024X448Shake => {
+                KemManager::XWing(Box::new(XWingKemManager::new(kem_type)?))
+            }
+            KemType::ChempatX25519MlKem768
+            | KemType::ChempatX448MlKem1024
+            | KemType::ChempatP256MlKem768
+            | KemType::ChempatP384MlKem1024 => {
+                KemManager::Chempat(ChempatKemManager::new(kem_type)?)
             }
+            KemType::Sntrup761 | KemType::Sntrup761X25519 => {
+                KemManager::Sntrup(SntrupKemManager::new(kem_type)?)
+            }
+            KemType::BikeL1
+            | KemType::BikeL3
+            | KemType::BikeL5
+            | KemType::BikeL1X25519
+            | KemType::BikeL3P384
+            | KemType::BikeL5X448 => KemManager::Bike(BikeKemManager::new(kem_type)?),
+            #[cfg(feature = "legacy")]
+            KemType::X25519Kyber768Draft00 => {
+                KemManager::X25519Kyber(Box::new(X25519Kyber768Draft00Manager::new(kem_type)?))
+            }
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 | KemType::Kyber768 | KemType::Kyber1024 => {
+                KemManager::Kyber(KyberKemManager::new(kem_type)?)
+            }
+            _ => return Err(QuantCryptError::NotImplemented),
         })
     }
 
@@ -93,6 +172,16 @@ impl Kem for KemManager {
             KemManager::Rsa(kem) => kem.get_kem_info(),
             KemManager::Ec(kem) => kem.get_kem_info(),
             KemManager::Composite(kem) => kem.get_kem_info(),
+            KemManager::Triple(kem) => kem.get_kem_info(),
+            KemManager::XWing(kem) => kem.get_kem_info(),
+            KemManager::Chempat(kem) => kem.get_kem_info(),
+            KemManager::Sntrup(kem) => kem.get_kem_info(),
+            KemManager::Bike(kem) => kem.get_kem_info(),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.get_kem_info(),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.get_kem_info(),
+            KemManager::Custom(kem) => kem.get_kem_info(),
         }
     }
 
@@ -111,6 +200,16 @@ impl Kem for KemManager {
             KemManager::Rsa(kem) => kem.key_gen_with_rng(rng),
             KemManager::Ec(kem) => kem.key_gen_with_rng(rng),
             KemManager::Composite(kem) => kem.key_gen_with_rng(rng),
+            KemManager::Triple(kem) => kem.key_gen_with_rng(rng),
+            KemManager::XWing(kem) => kem.key_gen_with_rng(rng),
+            KemManager::Chempat(kem) => kem.key_gen_with_rng(rng),
+            KemManager::Sntrup(kem) => kem.key_gen_with_rng(rng),
+            KemManager::Bike(kem) => kem.key_gen_with_rng(rng),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.key_gen_with_rng(rng),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.key_gen_with_rng(rng),
+            KemManager::Custom(kem) => kem.key_gen_with_rng(rng),
         }
     }
 
@@ -126,6 +225,16 @@ impl Kem for KemManager {
             KemManager::Rsa(kem) => kem.key_gen(),
             KemManager::Ec(kem) => kem.key_gen(),
             KemManager::Composite(kem) => kem.key_gen(),
+            KemManager::Triple(kem) => kem.key_gen(),
+            KemManager::XWing(kem) => kem.key_gen(),
+            KemManager::Chempat(kem) => kem.key_gen(),
+            KemManager::Sntrup(kem) => kem.key_gen(),
+            KemManager::Bike(kem) => kem.key_gen(),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.key_gen(),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.key_gen(),
+            KemManager::Custom(kem) => kem.key_gen(),
         }
     }
 
@@ -144,6 +253,74 @@ impl Kem for KemManager {
             KemManager::Rsa(kem) => kem.encap(pk),
             KemManager::Ec(kem) => kem.encap(pk),
             KemManager::Composite(kem) => kem.encap(pk),
+            KemManager::Triple(kem) => kem.encap(pk),
+            KemManager::XWing(kem) => kem.encap(pk),
+            KemManager::Chempat(kem) => kem.encap(pk),
+            KemManager::Sntrup(kem) => kem.encap(pk),
+            KemManager::Bike(kem) => kem.encap(pk),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.encap(pk),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.encap(pk),
+            KemManager::Custom(kem) => kem.encap(pk),
+        }
+    }
+
+    /// Encapsulate a batch of public keys
+    ///
+    /// # Arguments
+    ///
+    /// * `pks` - The public keys to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct) for each public key
+    fn encap_batch(&mut self, pks: &[&[u8]]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        match self {
+            KemManager::Ml(kem) => kem.encap_batch(pks),
+            KemManager::Rsa(kem) => kem.encap_batch(pks),
+            KemManager::Ec(kem) => kem.encap_batch(pks),
+            KemManager::Composite(kem) => kem.encap_batch(pks),
+            KemManager::Triple(kem) => kem.encap_batch(pks),
+            KemManager::XWing(kem) => kem.encap_batch(pks),
+            KemManager::Chempat(kem) => kem.encap_batch(pks),
+            KemManager::Sntrup(kem) => kem.encap_batch(pks),
+            KemManager::Bike(kem) => kem.encap_batch(pks),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.encap_batch(pks),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.encap_batch(pks),
+            KemManager::Custom(kem) => pks.iter().map(|pk| kem.encap(pk)).collect(),
+        }
+    }
+
+    /// Encapsulate a public key with the given randomness
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `coins` - The randomness of the encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self {
+            KemManager::Ml(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::Rsa(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::Ec(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::Composite(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::Triple(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::XWing(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::Chempat(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::Sntrup(kem) => kem.encap_deterministic(pk, coins),
+            KemManager::Bike(kem) => kem.encap_deterministic(pk, coins),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.encap_deterministic(pk, coins),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.encap_deterministic(pk, coins),
+            // Registered backends only expose randomized encapsulation
+            KemManager::Custom(_) => Err(QuantCryptError::NotImplemented),
         }
     }
 
@@ -163,15 +340,356 @@ impl Kem for KemManager {
             KemManager::Rsa(kem) => kem.decap(ct, sk),
             KemManager::Ec(kem) => kem.decap(ct, sk),
             KemManager::Composite(kem) => kem.decap(ct, sk),
+            KemManager::Triple(kem) => kem.decap(ct, sk),
+            KemManager::XWing(kem) => kem.decap(ct, sk),
+            KemManager::Chempat(kem) => kem.decap(ct, sk),
+            KemManager::Sntrup(kem) => kem.decap(ct, sk),
+            KemManager::Bike(kem) => kem.decap(ct, sk),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.decap(ct, sk),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.decap(ct, sk),
+            KemManager::Custom(kem) => kem.decap(ct, sk),
+        }
+    }
+
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// Composite, triple, X-Wing and Chempat KEMs mix the context into their combiner.
+    /// The other KEMs pass through to `encap`, and derive the same secret whatever
+    /// the context.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `context` - The context to bind the shared secret to
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self {
+            KemManager::Composite(kem) => kem.encap_with_context(pk, context),
+            KemManager::Triple(kem) => kem.encap_with_context(pk, context),
+            KemManager::XWing(kem) => kem.encap_with_context(pk, context),
+            KemManager::Chempat(kem) => kem.encap_with_context(pk, context),
+            _ => self.encap(pk),
+        }
+    }
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `context` - The context given to `encap_with_context`
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            KemManager::Composite(kem) => kem.decap_with_context(sk, ct, context),
+            KemManager::Triple(kem) => kem.decap_with_context(sk, ct, context),
+            KemManager::XWing(kem) => kem.decap_with_context(sk, ct, context),
+            KemManager::Chempat(kem) => kem.decap_with_context(sk, ct, context),
+            _ => self.decap(sk, ct),
+        }
+    }
+
+    /// Encapsulate a public key into caller-provided buffers
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `ss_out` - The buffer receiving the shared secret
+    /// * `ct_out` - The buffer receiving the ciphertext
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written to each buffer (ss_len, ct_len)
+    fn encap_into(
+        &mut self,
+        pk: &[u8],
+        ss_out: &mut [u8],
+        ct_out: &mut [u8],
+    ) -> Result<(usize, usize)> {
+        match self {
+            KemManager::Ml(kem) => kem.encap_into(pk, ss_out, ct_out),
+            // The other KEMs allocate their outputs, which are copied
+            _ => {
+                let (ss, ct) = self.encap(pk)?;
+                let ss = Zeroizing::new(ss);
+                check_buffer_len(ct.len(), ct_out)?;
+                Ok((copy_into(&ss, ss_out)?, copy_into(&ct, ct_out)?))
+            }
+        }
+    }
+
+    /// Decapsulate a ciphertext into a caller-provided buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `out` - The buffer receiving the shared secret
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written to `out`
+    fn decap_into(&self, sk: &[u8], ct: &[u8], out: &mut [u8]) -> Result<usize> {
+        match self {
+            KemManager::Ml(kem) => kem.decap_into(sk, ct, out),
+            // The other KEMs allocate the shared secret, which is copied
+            _ => copy_into(&Zeroizing::new(self.decap(sk, ct)?), out),
         }
     }
 }
 
+impl KemManager {
+    /// Create a new KEM manager drawing all its randomness from a caller-supplied RNG
+    ///
+    /// Key generation, encapsulation and the components of hybrid KEMs use the
+    /// RNG instead of seeding their own, e.g. to draw every random byte from
+    /// the DRBG of a FIPS deployment. See `SharedRng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM manager
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM, or a component of it, is provided by
+    /// a registered backend, which draws its own randomness
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng)?;
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) -> Result<()> {
+        match self {
+            KemManager::Ml(kem) => kem.set_rng(rng),
+            KemManager::Rsa(kem) => kem.set_rng(rng),
+            KemManager::Ec(kem) => kem.set_rng(rng),
+            KemManager::Composite(kem) => kem.set_rng(rng)?,
+            KemManager::Triple(kem) => kem.set_rng(rng)?,
+            KemManager::XWing(kem) => kem.set_rng(rng),
+            KemManager::Chempat(kem) => kem.set_rng(rng),
+            KemManager::Sntrup(kem) => kem.set_rng(rng),
+            KemManager::Bike(kem) => kem.set_rng(rng),
+            #[cfg(feature = "legacy")]
+            KemManager::X25519Kyber(kem) => kem.set_rng(rng),
+            #[cfg(feature = "legacy")]
+            KemManager::Kyber(kem) => kem.set_rng(rng),
+            KemManager::Custom(_) => return Err(QuantCryptError::NotImplemented),
+        }
+        Ok(())
+    }
+
+    /// Replace the domain separation label of the combiner of a hybrid KEM
+    ///
+    /// This is an expert option for private deployments that need their own
+    /// domain separation. The shared secrets are no longer interoperable with
+    /// other implementations, and the label is not carried in keys, certificates
+    /// or CMS messages, so both parties must configure it. The label in effect
+    /// is reported by `get_kem_info().combiner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label to use instead of the standard one
+    ///
+    /// # Returns
+    ///
+    /// The KEM manager
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM is not a composite or three-way hybrid KEM
+    pub fn set_combiner_label(&mut self, label: &[u8]) -> Result<&mut Self> {
+        match self {
+            KemManager::Composite(kem) => kem.set_combiner_label(label)?,
+            KemManager::Triple(kem) => kem.set_combiner_label(label)?,
+            _ => return Err(QuantCryptError::NotImplemented),
+        }
+        Ok(self)
+    }
+
+    /// Generate an ML-KEM keypair in the seed format
+    ///
+    /// The private key is the 64 byte seed d || z, which is what many HSMs
+    /// and the private key encoding drafts store. See `expand_seed` to get
+    /// the expanded key.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public key and the seed (pk, seed)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM is not ML-KEM
+    ///
+    /// # Example
+    /// ```
+    /// use quantcrypt::kems::{Kem, KemManager, KemType};
+    ///
+    /// let mut kem = KemManager::new(KemType::MlKem768).unwrap();
+    /// let (pk, seed) = kem.key_gen_seed().unwrap();
+    /// assert_eq!(seed.len(), 64);
+    ///
+    /// let (ss, ct) = kem.encap(&pk).unwrap();
+    /// assert_eq!(kem.decap_seed(&seed, &ct).unwrap(), ss);
+    ///
+    /// // The expanded key is 2400 bytes
+    /// let (_, sk) = kem.expand_seed(&seed).unwrap();
+    /// assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+    /// ```
+    pub fn key_gen_seed(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self {
+            KemManager::Ml(kem) => kem.key_gen_seed(),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Expand an ML-KEM seed into the public key and the expanded private key
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The 64 byte seed d || z
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and expanded secret keys (pk, sk)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` will be returned if the seed is not 64 bytes
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM is not ML-KEM
+    pub fn expand_seed(&self, seed: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self {
+            KemManager::Ml(kem) => kem.expand_seed(seed),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Decapsulate a ciphertext with an ML-KEM private key in the seed format
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The 64 byte seed d || z
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM is not ML-KEM
+    pub fn decap_seed(&self, seed: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            KemManager::Ml(kem) => kem.decap_seed(seed, ct),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Enforce the input checks of FIPS 203 on every call of an ML-KEM manager
+    ///
+    /// Encapsulation keys must then pass the modulus check and decapsulation
+    /// keys the hash check, otherwise `QuantCryptError::InvalidPublicKey` or
+    /// `QuantCryptError::InvalidPrivateKey` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict_fips` - True to check the keys on every call
+    ///
+    /// # Returns
+    ///
+    /// The KEM manager
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM is not ML-KEM
+    pub fn set_strict_fips(&mut self, strict_fips: bool) -> Result<&mut Self> {
+        match self {
+            KemManager::Ml(kem) => kem.set_strict_fips(strict_fips),
+            _ => return Err(QuantCryptError::NotImplemented),
+        }
+        Ok(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::kem::common::kem_type::KemType;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn test_encap_with_context() {
+        let context = b"ML-KEM-768";
+
+        // Plain KEMs have no combiner and pass it through
+        let mut kem = KemManager::new(KemType::MlKem768).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap_with_context(&pk, context).unwrap();
+        assert_eq!(kem.decap_with_context(&sk, &ct, context).unwrap(), ss);
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // Triple KEMs bind it
+        let mut kem = KemManager::new(KemType::MlKem768X25519P256).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap_with_context(&pk, context).unwrap();
+        assert_eq!(kem.decap_with_context(&sk, &ct, context).unwrap(), ss);
+        assert_ne!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // So do Chempat KEMs
+        let mut kem = KemManager::new(KemType::ChempatX25519MlKem768).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap_with_context(&pk, context).unwrap();
+        assert_eq!(kem.decap_with_context(&sk, &ct, context).unwrap(), ss);
+        assert_ne!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // Hybrids whose combiner has no room for it pass it through
+        let mut kem = KemManager::new(KemType::Sntrup761X25519).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap_with_context(&pk, context).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+    }
 
+    #[test]
+    fn test_new_with_rng() {
+        for kem_type in [
+            KemType::MlKem768,
+            KemType::X25519,
+            KemType::MlKem768X25519,
+            KemType::XWing,
+            KemType::Sntrup761X25519,
+        ] {
+            let mut outputs = Vec::new();
+            for _ in 0..2 {
+                let rng = SharedRng::new(ChaCha20Rng::from_seed([3u8; 32]));
+                let mut kem = KemManager::new_with_rng(kem_type.clone(), rng).unwrap();
+                let (pk, sk) = kem.key_gen().unwrap();
+                let (ss, ct) = kem.encap(&pk).unwrap();
+                assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+                outputs.push((pk, ct, ss));
+            }
+            // The same RNG stream yields the same keys and ciphertexts
+            assert_eq!(outputs[0], outputs[1]);
+        }
+    }
+
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_kem_manager() {
         let mut all_kems: Vec<KemType> = Vec::new();
@@ -179,6 +697,30 @@ mod tests {
         all_kems.extend_from_slice(&RSA_KEM_TYPES);
         all_kems.extend_from_slice(&EC_KEM_TYPES);
         all_kems.extend_from_slice(&COMPOSITE_KEM_TYPES);
+        all_kems.extend_from_slice(&TRIPLE_KEM_TYPES);
+        all_kems.extend_from_slice(&[KemType::XWing, KemType::MlKem1024X448Shake]);
+        all_kems.extend_from_slice(&[
+            KemType::ChempatX25519MlKem768,
+            KemType::ChempatX448MlKem1024,
+            KemType::ChempatP256MlKem768,
+            KemType::ChempatP384MlKem1024,
+        ]);
+        all_kems.extend_from_slice(&[KemType::Sntrup761, KemType::Sntrup761X25519]);
+        all_kems.extend_from_slice(&[
+            KemType::BikeL1,
+            KemType::BikeL3,
+            KemType::BikeL5,
+            KemType::BikeL1X25519,
+            KemType::BikeL3P384,
+            KemType::BikeL5X448,
+        ]);
+        #[cfg(feature = "legacy")]
+        all_kems.extend_from_slice(&[
+            KemType::X25519Kyber768Draft00,
+            KemType::Kyber512,
+            KemType::Kyber768,
+            KemType::Kyber1024,
+        ]);
 
         // This is just to test that the factory can create all KEM types
         for kem_type in all_kems {
@@ -186,4 +728,88 @@ mod tests {
             assert_eq!(kem.get_kem_info().kem_type, kem_type);
         }
     }
+
+    #[test]
+    fn test_kem_manager_all_types() {
+        // Every type gets a manager or an error, never a panic
+        for kem_type in KemType::all() {
+            match KemManager::new(kem_type.clone()) {
+                Ok(kem) => assert_eq!(kem.get_kem_info().kem_type, kem_type),
+                Err(e) => assert!(
+                    matches!(
+                        e,
+                        QuantCryptError::NotImplemented
+                            | QuantCryptError::BackendUnavailable { .. }
+                    ),
+                    "{:?}: {:?}",
+                    kem_type,
+                    e
+                ),
+            }
+        }
+        assert_eq!(
+            KemManager::new(KemType::Combined).err(),
+            Some(QuantCryptError::NotImplemented)
+        );
+    }
+
+    #[test]
+    fn test_kem_manager_is_clone_send_sync() {
+        fn assert_clone_send_sync<T: Clone + Send + Sync>() {}
+        assert_clone_send_sync::<KemManager>();
+
+        // A clone must be usable from another thread
+        let mut kem = KemManager::new(KemType::MlKem768X25519).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let mut kem2 = kem.clone();
+        let (ss, ct) = std::thread::spawn(move || kem2.encap(&pk).unwrap())
+            .join()
+            .unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn test_set_combiner_label() {
+        let mut kem = KemManager::new(KemType::MlKem768X25519).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+
+        let mut custom = kem.clone();
+        custom.set_combiner_label(b"my-deployment").unwrap();
+        let info = custom.get_kem_info().combiner.unwrap();
+        assert_eq!(info.label, b"my-deployment");
+        assert_eq!(info.label_oid, None);
+        assert_eq!(info.kdf, "SHA3-256");
+
+        // The same component secrets, separated by another label
+        let custom_ss = custom.decap(&sk, &ct).unwrap();
+        assert_ne!(custom_ss, ss);
+        let (ss, ct) = custom.encap(&pk).unwrap();
+        assert_eq!(custom.decap(&sk, &ct).unwrap(), ss);
+
+        let mut triple = KemManager::new(KemType::MlKem768X25519P256).unwrap();
+        assert!(triple.set_combiner_label(b"my-deployment").is_ok());
+        let mut ml_kem = KemManager::new(KemType::MlKem768).unwrap();
+        assert!(ml_kem.set_combiner_label(b"my-deployment").is_err());
+    }
+
+    #[test]
+    fn test_encap_batch() {
+        for kem_type in [KemType::MlKem768, KemType::MlKem768X25519, KemType::P256] {
+            let mut kem = KemManager::new(kem_type).unwrap();
+            let keys: Vec<_> = (0..4).map(|_| kem.key_gen().unwrap()).collect();
+            let pks: Vec<&[u8]> = keys.iter().map(|(pk, _)| pk.as_slice()).collect();
+
+            let results = kem.encap_batch(&pks).unwrap();
+            assert_eq!(results.len(), keys.len());
+            for ((_, sk), (ss, ct)) in keys.iter().zip(&results) {
+                assert_eq!(&kem.decap(sk, ct).unwrap(), ss);
+            }
+
+            // A bad key fails the whole batch
+            let bad = [pks[0], &pks[1][1..]];
+            assert!(kem.encap_batch(&bad).is_err());
+            assert!(kem.encap_batch(&[]).unwrap().is_empty());
+        }
+    }
 }
@@ -0,0 +1,360 @@
+use ml_kem::{EncapsulateDeterministic, KemCore, MlKem1024, MlKem512, MlKem768, B32};
+use rand_core::CryptoRngCore;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Shake256};
+
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::ml_kem::{decapsulate, get_encapsulation_key_obj, MlKemManager};
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+// Kyber as submitted to round 3 of the NIST process differs from ML-KEM
+// (FIPS 203) only outside of K-PKE, so it is built on the ML-KEM
+// implementation:
+//
+// * Encaps hashes the random message, m = H(m), and derives the shared secret
+//   as KDF(K || H(c)) instead of returning K
+// * Decaps rejects with KDF(z || H(c)) instead of J(z || c)
+// * KeyGen hashes G(d) instead of G(d || k). This only matters for
+//   reproducing test vectors, keys from random seeds are indistinguishable.
+
+/// KDF(K || H(c)) of Kyber round 3, SHAKE256 with a 32 byte output
+fn kdf(k: &[u8], ct: &[u8]) -> Vec<u8> {
+    let mut shake = Shake256::default();
+    shake.update(k);
+    shake.update(&Sha3_256::digest(ct));
+    let mut ss = vec![0u8; 32];
+    shake.finalize_xof().read(&mut ss);
+    ss
+}
+
+/// Encapsulate a public key with Kyber round 3
+///
+/// # Arguments
+///
+/// * `pk` - The public key, encoded as for ML-KEM
+/// * `rng` - The source of the random message
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and ciphertext (ss, ct)
+pub(crate) fn encapsulate_r3<K: KemCore>(
+    pk: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    encapsulate_r3_deterministic::<K>(pk, &seed)
+}
+
+/// Encapsulate a public key with Kyber round 3 and the given random message
+///
+/// # Arguments
+///
+/// * `pk` - The public key, encoded as for ML-KEM
+/// * `seed` - The 32 byte random message, before it is hashed
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and ciphertext (ss, ct)
+pub(crate) fn encapsulate_r3_deterministic<K: KemCore>(
+    pk: &[u8],
+    seed: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    if seed.len() != 32 {
+        return Err(QuantCryptError::InvalidCoinsLength {
+            expected: 32,
+            actual: seed.len(),
+        });
+    }
+    let ek = get_encapsulation_key_obj::<K>(pk.to_vec())?;
+    let mut m = B32::default();
+    m.copy_from_slice(&Sha3_256::digest(seed));
+    let (ct, k) = ek
+        .encapsulate_deterministic(&m)
+        .map_err(|_| QuantCryptError::EncapFailed)?;
+    Ok((kdf(&k, &ct), ct.to_vec()))
+}
+
+/// Decapsulate a ciphertext with Kyber round 3
+///
+/// # Arguments
+///
+/// * `sk` - The secret key, encoded as for ML-KEM
+/// * `ct` - The ciphertext
+///
+/// # Returns
+///
+/// The shared secret (ss)
+pub(crate) fn decapsulate_r3<K: KemCore>(sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+    // ML-KEM returns K on success and J(z || c) on rejection
+    let k = decapsulate::<K>(sk, ct)?;
+    let z = &sk[sk.len() - 32..];
+    let mut rejection = [0u8; 32];
+    let mut shake = Shake256::default();
+    shake.update(z);
+    shake.update(ct);
+    shake.finalize_xof().read(&mut rejection);
+
+    // Select z on rejection, without branching on it
+    let diff = k
+        .iter()
+        .zip(rejection.iter())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    let rejected = ((u16::from(diff).wrapping_sub(1) >> 8) as u8) & 1;
+    let mask = 0u8.wrapping_sub(rejected);
+    let prefix: Vec<u8> = k
+        .iter()
+        .zip(z.iter())
+        .map(|(k, z)| (z & mask) | (k & !mask))
+        .collect();
+    Ok(kdf(&prefix, ct))
+}
+
+// Implement clone
+#[derive(Clone)]
+/// A KEM manager for Kyber512, Kyber768 and Kyber1024 as submitted to round 3
+/// of the NIST process
+///
+/// This is only meant for decrypting data produced before FIPS 203 was
+/// finalized: the shared secrets differ from ML-KEM for the same keys and
+/// ciphertexts. Keys and ciphertexts are encoded as for the ML-KEM parameter
+/// set of the same size. Generated keys are valid for both, but don't match
+/// the round 3 known answer tests, which hash the seed differently.
+pub struct KyberKemManager {
+    kem_info: KemInfo,
+    ml_kem: MlKemManager,
+    rng: Option<SharedRng>,
+}
+
+impl KyberKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.ml_kem.set_rng(rng.clone());
+        self.rng = Some(rng);
+    }
+}
+
+impl Kem for KyberKemManager {
+    /// Create a new KEM instance
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    fn new(kem_type: KemType) -> Result<Self> {
+        let ml_kem_type = match kem_type {
+            KemType::Kyber512 => KemType::MlKem512,
+            KemType::Kyber768 => KemType::MlKem768,
+            KemType::Kyber1024 => KemType::MlKem1024,
+            _ => return Err(QuantCryptError::NotImplemented),
+        };
+        Ok(KyberKemManager {
+            kem_info: KemInfo::new(kem_type),
+            ml_kem: MlKemManager::new(ml_kem_type)?,
+            rng: None,
+        })
+    }
+
+    /// Generate a keypair using the default RNG
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.ml_kem.key_gen()
+    }
+
+    /// Generate a keypair
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.ml_kem.key_gen_with_rng(rng)
+    }
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
+        match self.kem_info.kem_type {
+            KemType::Kyber512 => encapsulate_r3::<MlKem512>(pk, &mut rng),
+            KemType::Kyber768 => encapsulate_r3::<MlKem768>(pk, &mut rng),
+            KemType::Kyber1024 => encapsulate_r3::<MlKem1024>(pk, &mut rng),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Encapsulate a public key with the 32 byte random message, before it is hashed
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `coins` - The random message
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self.kem_info.kem_type {
+            KemType::Kyber512 => encapsulate_r3_deterministic::<MlKem512>(pk, coins),
+            KemType::Kyber768 => encapsulate_r3_deterministic::<MlKem768>(pk, coins),
+            KemType::Kyber1024 => encapsulate_r3_deterministic::<MlKem1024>(pk, coins),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        match self.kem_info.kem_type {
+            KemType::Kyber512 => decapsulate_r3::<MlKem512>(sk, ct),
+            KemType::Kyber768 => decapsulate_r3::<MlKem768>(sk, ct),
+            KemType::Kyber1024 => decapsulate_r3::<MlKem1024>(sk, ct),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Get KEM metadata information such as the key lengths
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem_info.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::common::config::oids::Oid;
+    use crate::test_kem;
+    use crate::utils::ctr_drbg::CtrDrbg;
+    use crate::utils::kat::RspFile;
+    use ml_kem::EncodedSizeUser;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_kyber_r3() {
+        let (dk, ek) = MlKem768::generate(&mut OsRng);
+        let (pk, sk) = (ek.as_bytes().to_vec(), dk.as_bytes().to_vec());
+        let (ss, ct) = encapsulate_r3::<MlKem768>(&pk, &mut OsRng).unwrap();
+        assert_eq!(decapsulate_r3::<MlKem768>(&sk, &ct).unwrap(), ss);
+
+        // The shared secret differs from ML-KEM's for the same ciphertext
+        assert_ne!(decapsulate::<MlKem768>(&sk, &ct).unwrap(), ss);
+
+        // Implicit rejection with KDF(z || H(c))
+        let mut tampered = ct.clone();
+        tampered[0] ^= 1;
+        let z = &sk[sk.len() - 32..];
+        assert_eq!(
+            decapsulate_r3::<MlKem768>(&sk, &tampered).unwrap(),
+            kdf(z, &tampered)
+        );
+    }
+
+    /// Run a round 3 `.rsp` file of the reference implementation
+    ///
+    /// Keys are generated with G(d) in round 3, so the recorded keys are used
+    /// instead. The reference implementation draws d, z and the message with
+    /// three 32 byte calls to the DRBG, the message is the third.
+    fn run_kyber_r3_kat<K: KemCore>(file: &str) {
+        let rsp = RspFile::from_file(file).unwrap();
+        assert!(!rsp.get_records().is_empty());
+        for record in rsp.get_records() {
+            let seed = record.get_bytes("seed").unwrap();
+            let (pk, sk) = (
+                record.get_bytes("pk").unwrap(),
+                record.get_bytes("sk").unwrap(),
+            );
+            let (ct, ss) = (
+                record.get_bytes("ct").unwrap(),
+                record.get_bytes("ss").unwrap(),
+            );
+
+            let mut drbg = CtrDrbg::new(&seed.try_into().unwrap(), None);
+            drbg.randombytes_vec(32);
+            drbg.randombytes_vec(32);
+            let m = drbg.randombytes_vec(32);
+            let (ss_enc, ct_enc) = encapsulate_r3_deterministic::<K>(&pk, &m).unwrap();
+            assert_eq!(ct_enc, ct, "ct of record {}", record.get_count());
+            assert_eq!(ss_enc, ss, "ss of record {}", record.get_count());
+
+            assert_eq!(decapsulate_r3::<K>(&sk, &ct).unwrap(), ss);
+        }
+    }
+
+    #[test]
+    fn test_kyber_r3_official_kat() {
+        run_kyber_r3_kat::<MlKem512>("test/data/kat/kyber512_r3.rsp");
+        run_kyber_r3_kat::<MlKem768>("test/data/kat/kyber768_r3.rsp");
+        run_kyber_r3_kat::<MlKem1024>("test/data/kat/kyber1024_r3.rsp");
+    }
+
+    #[test]
+    fn test_kyber_kem_manager() {
+        for kem_type in [KemType::Kyber512, KemType::Kyber768, KemType::Kyber1024] {
+            let kem = KyberKemManager::new(kem_type.clone());
+            test_kem!(kem);
+
+            // Shared secrets can't be mistaken for those of ML-KEM
+            let mut kem = KyberKemManager::new(kem_type).unwrap();
+            let (pk, sk) = kem.key_gen().unwrap();
+            let (ss, ct) = kem.encap_deterministic(&pk, &[1u8; 32]).unwrap();
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+            assert_ne!(kem.ml_kem.decap(&sk, &ct).unwrap(), ss);
+        }
+        assert!(KyberKemManager::new(KemType::MlKem768).is_err());
+
+        // Excluded from anything selecting KEMs by OID
+        assert!(KemType::Kyber768.is_legacy());
+        assert!(!KemType::MlKem768.is_legacy());
+        assert!(!KemType::Kyber768.is_composite());
+        assert_eq!(KemType::Kyber768.get_oid(), None);
+    }
+}
@@ -1,13 +1,20 @@
 use crate::kem::common::kem_info::KemInfo;
-use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_trait::{check_buffer_len, copy_into, Kem};
 use crate::kem::common::kem_type::KemType;
+#[cfg(any(test, feature = "test-utils"))]
+use crate::utils::ct_eq::ct_eq;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::QuantCryptError;
 use ml_kem::kem::Decapsulate;
 use ml_kem::kem::Encapsulate;
 use ml_kem::*;
-use rand_chacha::ChaCha20Rng;
 use rand_core::CryptoRngCore;
-use rand_core::SeedableRng;
+#[cfg(any(test, feature = "test-utils"))]
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+#[cfg(any(test, feature = "test-utils"))]
+use sha3::Shake256;
+use sha3::{Digest, Sha3_256};
+use zeroize::{Zeroize, Zeroizing};
 
 macro_rules! key_gen_ml {
     ($rng:expr, $curve:ident) => {{
@@ -16,6 +23,16 @@ macro_rules! key_gen_ml {
     }};
 }
 
+macro_rules! encapsulate_ml_deterministic {
+    ($m:expr, $curve:ident, $pk:expr) => {{
+        let ek = get_encapsulation_key_obj::<$curve>($pk.to_vec())?;
+        let (ct, ss) = ek
+            .encapsulate_deterministic($m)
+            .map_err(|_| QuantCryptError::EncapFailed)?;
+        Ok((ss.as_slice().to_vec(), ct.as_slice().to_vec()))
+    }};
+}
+
 macro_rules! encapsulate_ml {
     ($rng:expr, $curve:ident, $pk:expr) => {{
         let ek = get_encapsulation_key_obj::<$curve>($pk.to_vec())?;
@@ -28,6 +45,81 @@ macro_rules! encapsulate_ml {
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// The ML-KEM modulus
+const Q: u16 = 3329;
+
+/// The length of the seed d || z an ML-KEM key pair is generated from (FIPS 203, section 7.1)
+pub const ML_KEM_SEED_LEN: usize = 64;
+
+/// Run the encapsulation key check of FIPS 203 (section 7.2)
+///
+/// The key must have the length of the parameter set, and every coefficient
+/// of its encoded vector must be reduced modulo q, i.e. the key must be
+/// unchanged by ByteEncode_12(ByteDecode_12(ek)).
+///
+/// # Arguments
+///
+/// * `kem_type` - The ML-KEM parameter set
+/// * `pk` - The encapsulation key
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidPublicKey` will be returned if the key fails the check
+pub(crate) fn check_encapsulation_key(kem_type: &KemType, pk: &[u8]) -> Result<()> {
+    let expected = match kem_type {
+        KemType::MlKem512 => 800,
+        KemType::MlKem768 => 1184,
+        KemType::MlKem1024 => 1568,
+        _ => return Err(QuantCryptError::NotImplemented),
+    };
+    if pk.len() != expected {
+        return Err(QuantCryptError::InvalidPublicKey);
+    }
+    // The encoded vector is 384 * k bytes, followed by the 32 byte seed
+    for group in pk[..expected - 32].chunks_exact(3) {
+        let [b0, b1, b2] = [group[0], group[1], group[2]].map(u16::from);
+        let d1 = b0 | ((b1 & 0x0f) << 8);
+        let d2 = (b1 >> 4) | (b2 << 4);
+        if d1 >= Q || d2 >= Q {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+    }
+    Ok(())
+}
+
+/// Run the decapsulation key check of FIPS 203 (section 7.3)
+///
+/// dk = dk_PKE (384k bytes) || ek (384k + 32 bytes) || H(ek) (32 bytes) || z (32 bytes)
+///
+/// The key must have the length of the parameter set, the embedded
+/// encapsulation key must pass its own check and H(ek) must match it.
+///
+/// # Arguments
+///
+/// * `kem_type` - The ML-KEM parameter set
+/// * `sk` - The decapsulation key
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidPrivateKey` will be returned if the key fails the check
+pub(crate) fn check_decapsulation_key(kem_type: &KemType, sk: &[u8]) -> Result<()> {
+    let expected = match kem_type {
+        KemType::MlKem512 => 1632,
+        KemType::MlKem768 => 2400,
+        KemType::MlKem1024 => 3168,
+        _ => return Err(QuantCryptError::NotImplemented),
+    };
+    if sk.len() != expected {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    let ek = &sk[(expected - 96) / 2..expected - 64];
+    check_encapsulation_key(kem_type, ek).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    if Sha3_256::digest(ek).as_slice() != &sk[expected - 64..expected - 32] {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    Ok(())
+}
+
 // Get the encapsulated key object for the post quantum key encapsulation mechanism
 ///
 /// # Arguments
@@ -37,7 +129,7 @@ type Result<T> = std::result::Result<T, QuantCryptError>;
 /// # Returns
 ///
 /// The encapsulated key object
-fn get_encapsulation_key_obj<K: KemCore>(pk: Vec<u8>) -> Result<K::EncapsulationKey> {
+pub(crate) fn get_encapsulation_key_obj<K: KemCore>(pk: Vec<u8>) -> Result<K::EncapsulationKey> {
     // Deserialize the public key
     let pk = Encoded::<K::EncapsulationKey>::try_from(pk.as_slice())
         .map_err(|_| QuantCryptError::InvalidPublicKey)?;
@@ -70,7 +162,7 @@ fn get_decapsulation_key_obj<K: KemCore>(sk: &[u8]) -> Result<K::DecapsulationKe
 /// # Returns
 ///
 /// The shared secret (ss)
-fn decapsulate<K: KemCore>(sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+pub(crate) fn decapsulate<K: KemCore>(sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
     let c = Ciphertext::<K>::try_from(ct).map_err(|_| QuantCryptError::InvalidCiphertext)?;
     let dk = get_decapsulation_key_obj::<K>(sk)?;
     let session_key = dk
@@ -79,12 +171,275 @@ fn decapsulate<K: KemCore>(sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
     Ok(session_key.as_slice().to_vec())
 }
 
+/// Decapsulate a ciphertext into a buffer, without allocating on the heap
+///
+/// # Arguments
+///
+/// * `sk` - The secret key to decapsulate with
+/// * `ct` - The encapsulated key to decapsulate
+/// * `out` - The buffer receiving the shared secret
+///
+/// # Returns
+///
+/// The number of bytes written to `out`
+fn decapsulate_into<K: KemCore>(sk: &[u8], ct: &[u8], out: &mut [u8]) -> Result<usize> {
+    let c = Ciphertext::<K>::try_from(ct).map_err(|_| QuantCryptError::InvalidCiphertext)?;
+    let dk = get_decapsulation_key_obj::<K>(sk)?;
+    let mut session_key = dk
+        .decapsulate(&c)
+        .map_err(|_| QuantCryptError::DecapFailed)?;
+    let written = copy_into(session_key.as_slice(), out);
+    session_key.as_mut_slice().zeroize();
+    written
+}
+
+/// Encapsulate a public key into buffers, without allocating on the heap
+///
+/// # Arguments
+///
+/// * `pk` - The public key to encapsulate
+/// * `rng` - The random number generator to use
+/// * `ss_out` - The buffer receiving the shared secret
+/// * `ct_out` - The buffer receiving the ciphertext
+///
+/// # Returns
+///
+/// The number of bytes written to each buffer (ss_len, ct_len)
+fn encapsulate_into<K: KemCore>(
+    pk: &[u8],
+    rng: &mut impl CryptoRngCore,
+    ss_out: &mut [u8],
+    ct_out: &mut [u8],
+) -> Result<(usize, usize)> {
+    check_buffer_len(Ciphertext::<K>::default().len(), ct_out)?;
+    let pk = Encoded::<K::EncapsulationKey>::try_from(pk)
+        .map_err(|_| QuantCryptError::InvalidPublicKey)?;
+    let ek = K::EncapsulationKey::from_bytes(&pk);
+    let (ct, mut ss) = ek
+        .encapsulate(rng)
+        .map_err(|_| QuantCryptError::EncapFailed)?;
+    let written = copy_into(ss.as_slice(), ss_out);
+    ss.as_mut_slice().zeroize();
+    Ok((written?, copy_into(&ct, ct_out)?))
+}
+
+/// Encapsulate with a decoded encapsulation key
+fn encapsulate_prepared<K: KemCore>(
+    ek: &K::EncapsulationKey,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let (ct, mut ss) = ek
+        .encapsulate(rng)
+        .map_err(|_| QuantCryptError::EncapFailed)?;
+    let result = (ss.as_slice().to_vec(), ct.as_slice().to_vec());
+    ss.as_mut_slice().zeroize();
+    Ok(result)
+}
+
+/// An ML-KEM encapsulation key decoded once, for repeated encapsulations
+///
+/// The NTT-domain vector t̂ is decoded from the key on construction. The
+/// matrix Â is still sampled from the seed ρ by every encapsulation, as the
+/// `ml-kem` crate doesn't keep it between calls.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum PreparedMlKemKey {
+    MlKem512(<MlKem512 as KemCore>::EncapsulationKey),
+    MlKem768(<MlKem768 as KemCore>::EncapsulationKey),
+    MlKem1024(<MlKem1024 as KemCore>::EncapsulationKey),
+}
+
+impl PreparedMlKemKey {
+    /// Decode an encapsulation key
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The ML-KEM parameter set
+    /// * `pk` - The encapsulation key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` will be returned if the key has the wrong length
+    pub(crate) fn new(kem_type: &KemType, pk: &[u8]) -> Result<Self> {
+        Ok(match kem_type {
+            KemType::MlKem512 => {
+                PreparedMlKemKey::MlKem512(get_encapsulation_key_obj::<MlKem512>(pk.to_vec())?)
+            }
+            KemType::MlKem768 => {
+                PreparedMlKemKey::MlKem768(get_encapsulation_key_obj::<MlKem768>(pk.to_vec())?)
+            }
+            KemType::MlKem1024 => {
+                PreparedMlKemKey::MlKem1024(get_encapsulation_key_obj::<MlKem1024>(pk.to_vec())?)
+            }
+            _ => return Err(QuantCryptError::NotImplemented),
+        })
+    }
+
+    /// Encapsulate a shared secret to the key
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The random number generator to use
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    pub(crate) fn encap(&self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        match self {
+            PreparedMlKemKey::MlKem512(ek) => encapsulate_prepared::<MlKem512>(ek, rng),
+            PreparedMlKemKey::MlKem768(ek) => encapsulate_prepared::<MlKem768>(ek, rng),
+            PreparedMlKemKey::MlKem1024(ek) => encapsulate_prepared::<MlKem1024>(ek, rng),
+        }
+    }
+}
+
+// Implement clone
+#[derive(Clone)]
 /// A KEM manager for the MlKem method
 pub struct MlKemManager {
     kem_info: KemInfo,
+    strict_fips: bool,
+    rng: Option<SharedRng>,
 }
 
 impl MlKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.rng = Some(rng);
+    }
+
+    /// Enforce the input checks of FIPS 203 on every call
+    ///
+    /// When enabled, every encapsulation key passed to `encap`, `encap_batch`
+    /// and `encap_deterministic` must pass the encapsulation key check, and
+    /// every decapsulation key passed to `decap` must pass the decapsulation
+    /// key check. Both are mandatory in FIPS 203 unless the key was already
+    /// checked or generated by the caller, which is why they are off by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict_fips` - True to check the keys on every call
+    pub fn set_strict_fips(&mut self, strict_fips: bool) {
+        self.strict_fips = strict_fips;
+    }
+
+    /// Check if the input checks of FIPS 203 are enforced on every call
+    pub fn get_strict_fips(&self) -> bool {
+        self.strict_fips
+    }
+
+    /// Check an encapsulation key if the strict FIPS mode is enabled
+    fn check_pk(&self, pk: &[u8]) -> Result<()> {
+        if self.strict_fips {
+            check_encapsulation_key(&self.kem_info.kem_type, pk)?;
+        }
+        Ok(())
+    }
+
+    /// Encapsulate a public key with the given RNG
+    fn encap_with_rng(
+        &self,
+        pk: &[u8],
+        mut rng: &mut impl CryptoRngCore,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.check_pk(pk)?;
+        match self.kem_info.kem_type {
+            KemType::MlKem512 => encapsulate_ml!(rng, MlKem512, pk),
+            KemType::MlKem768 => encapsulate_ml!(rng, MlKem768, pk),
+            KemType::MlKem1024 => encapsulate_ml!(rng, MlKem1024, pk),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Generate a keypair in the seed format
+    ///
+    /// The private key is the 64 byte seed d || z of ML-KEM.KeyGen_internal,
+    /// the format of FIPS 203 and of the private key encoding drafts. It is
+    /// expanded with `expand_seed` when the expanded key is needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public key and the seed (pk, seed)
+    pub fn key_gen_seed_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut seed = vec![0u8; ML_KEM_SEED_LEN];
+        rng.fill_bytes(&mut seed);
+        let (pk, _) = self.expand_seed(&seed)?;
+        Ok((pk, seed))
+    }
+
+    /// Generate a keypair in the seed format using the default RNG
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public key and the seed (pk, seed)
+    pub fn key_gen_seed(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
+        self.key_gen_seed_with_rng(&mut rng)
+    }
+
+    /// Expand a seed into the public key and the expanded private key
+    ///
+    /// The expanded key is the format used by `decap`. The conversion only
+    /// goes one way: the seed can't be recovered from the expanded key.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The 64 byte seed d || z
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and expanded secret keys (pk, sk)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` will be returned if the seed is not 64 bytes
+    pub fn expand_seed(&self, seed: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if seed.len() != ML_KEM_SEED_LEN {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let d = B32::try_from(&seed[..32]).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+        let z = B32::try_from(&seed[32..]).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+        self.key_gen_deterministic(&d, &z)
+    }
+
+    /// Decapsulate a ciphertext with a private key in the seed format
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The 64 byte seed d || z
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret
+    pub fn decap_seed(&self, seed: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        let (_, sk) = self.expand_seed(seed)?;
+        let sk = Zeroizing::new(sk);
+        self.decap(&sk, ct)
+    }
+
     pub fn key_gen_deterministic(&self, d: &B32, z: &B32) -> Result<(Vec<u8>, Vec<u8>)> {
         match self.kem_info.kem_type {
             KemType::MlKem512 => {
@@ -104,6 +459,44 @@ impl MlKemManager {
     }
 }
 
+#[cfg(any(test, feature = "test-utils"))]
+impl MlKemManager {
+    /// Decapsulate a ciphertext and report whether it was implicitly rejected
+    ///
+    /// ML-KEM never fails on an invalid ciphertext: the Fujisaki-Okamoto
+    /// transform re-encrypts the decrypted message and, if the result differs
+    /// from the ciphertext, returns the implicit rejection secret
+    /// J(z || c) = SHAKE256(z || c, 32) instead. This recomputes J(z || c) and
+    /// compares it with the shared secret in constant time. A valid ciphertext
+    /// is reported as rejected with negligible probability.
+    ///
+    /// This is for validating the rejection behavior against known answer
+    /// tests, never use it otherwise: telling a peer whether its ciphertext was
+    /// rejected defeats the purpose of implicit rejection.
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and whether the ciphertext was
+    /// implicitly rejected (ss, rejected)
+    pub fn decap_checked(&self, sk: &[u8], ct: &[u8]) -> Result<(Vec<u8>, bool)> {
+        let ss = self.decap(sk, ct)?;
+        // decap checked the length of the key, which ends with z
+        let z = &sk[sk.len() - 32..];
+        let mut shake = Shake256::default();
+        shake.update(z);
+        shake.update(ct);
+        let mut rejection_ss = Zeroizing::new(vec![0u8; ss.len()]);
+        shake.finalize_xof().read(&mut rejection_ss);
+        let rejected = ct_eq(&ss, &rejection_ss);
+        Ok((ss, rejected))
+    }
+}
+
 impl Kem for MlKemManager {
     /// Create a new KEM instance
     ///
@@ -116,7 +509,11 @@ impl Kem for MlKemManager {
     /// A new KEM instance
     fn new(kem_type: KemType) -> Result<Self> {
         let kem_info = KemInfo::new(kem_type);
-        Ok(Self { kem_info })
+        Ok(Self {
+            kem_info,
+            strict_fips: false,
+            rng: None,
+        })
     }
 
     /// Generate a keypair
@@ -139,13 +536,13 @@ impl Kem for MlKemManager {
         }
     }
 
-    /// Generate a keypair using the default RNG ChaCha20Rng
+    /// Generate a keypair using the RNG of the manager, or else the default RNG ChaCha20Rng
     ///
     /// # Returns
     ///
     /// A tuple containing the public and secret keys (pk, sk)
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-        let mut rng = ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
         self.key_gen_with_rng(&mut rng)
     }
 
@@ -159,20 +556,61 @@ impl Kem for MlKemManager {
     ///
     /// A tuple containing the shared secret and ciphertext (ss, ct)
     fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
-        let mut rng = ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
+        self.encap_with_rng(pk, &mut rng)
+    }
+
+    /// Encapsulate a batch of public keys, seeding the RNG once per thread
+    ///
+    /// # Arguments
+    ///
+    /// * `pks` - The public keys to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct) for each public key
+    fn encap_batch(&mut self, pks: &[&[u8]]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let kem = &*self;
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            pks.par_iter()
+                .map_init(
+                    || ManagerRng::for_operation(kem.rng.as_ref()),
+                    |rng, pk| kem.encap_with_rng(pk, rng),
+                )
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            let mut rng = ManagerRng::for_operation(kem.rng.as_ref());
+            pks.iter()
+                .map(|pk| kem.encap_with_rng(pk, &mut rng))
+                .collect()
+        }
+    }
+
+    /// Encapsulate a public key with the 32 byte message m
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `coins` - The message m
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let m = B32::try_from(coins).map_err(|_| QuantCryptError::InvalidCoinsLength {
+            expected: 32,
+            actual: coins.len(),
+        })?;
+        self.check_pk(pk)?;
         match self.kem_info.kem_type {
-            KemType::MlKem512 => {
-                encapsulate_ml!(rng, MlKem512, pk)
-            }
-            KemType::MlKem768 => {
-                encapsulate_ml!(rng, MlKem768, pk)
-            }
-            KemType::MlKem1024 => {
-                encapsulate_ml!(rng, MlKem1024, pk)
-            }
-            _ => {
-                panic!("Not implemented");
-            }
+            KemType::MlKem512 => encapsulate_ml_deterministic!(&m, MlKem512, pk),
+            KemType::MlKem768 => encapsulate_ml_deterministic!(&m, MlKem768, pk),
+            KemType::MlKem1024 => encapsulate_ml_deterministic!(&m, MlKem1024, pk),
+            _ => Err(QuantCryptError::NotImplemented),
         }
     }
 
@@ -187,6 +625,9 @@ impl Kem for MlKemManager {
     ///
     /// The shared secret
     fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        if self.strict_fips {
+            check_decapsulation_key(&self.kem_info.kem_type, sk)?;
+        }
         match self.kem_info.kem_type {
             KemType::MlKem512 => decapsulate::<MlKem512>(sk, ct),
             KemType::MlKem768 => decapsulate::<MlKem768>(sk, ct),
@@ -195,6 +636,56 @@ impl Kem for MlKemManager {
         }
     }
 
+    /// Encapsulate a public key into caller-provided buffers, without allocating on the heap
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `ss_out` - The buffer receiving the 32 byte shared secret
+    /// * `ct_out` - The buffer receiving the ciphertext
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written to each buffer (ss_len, ct_len)
+    fn encap_into(
+        &mut self,
+        pk: &[u8],
+        ss_out: &mut [u8],
+        ct_out: &mut [u8],
+    ) -> Result<(usize, usize)> {
+        self.check_pk(pk)?;
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
+        match self.kem_info.kem_type {
+            KemType::MlKem512 => encapsulate_into::<MlKem512>(pk, &mut rng, ss_out, ct_out),
+            KemType::MlKem768 => encapsulate_into::<MlKem768>(pk, &mut rng, ss_out, ct_out),
+            KemType::MlKem1024 => encapsulate_into::<MlKem1024>(pk, &mut rng, ss_out, ct_out),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Decapsulate a ciphertext into a caller-provided buffer, without allocating on the heap
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `out` - The buffer receiving the 32 byte shared secret
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written to `out`
+    fn decap_into(&self, sk: &[u8], ct: &[u8], out: &mut [u8]) -> Result<usize> {
+        if self.strict_fips {
+            check_decapsulation_key(&self.kem_info.kem_type, sk)?;
+        }
+        match self.kem_info.kem_type {
+            KemType::MlKem512 => decapsulate_into::<MlKem512>(sk, ct, out),
+            KemType::MlKem768 => decapsulate_into::<MlKem768>(sk, ct, out),
+            KemType::MlKem1024 => decapsulate_into::<MlKem1024>(sk, ct, out),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
     /// Get KEM metadata information such as the key lengths,
     /// size of ciphertext, etc.
     ///
@@ -210,15 +701,22 @@ impl Kem for MlKemManager {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "openssl")]
     use x509_cert::builder::Profile;
 
     use super::*;
+    #[cfg(feature = "openssl")]
     use crate::certificates::{CertValidity, CertificateBuilder};
+    #[cfg(feature = "openssl")]
     use crate::content::EnvelopedDataContent;
+    #[cfg(feature = "openssl")]
     use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
     use crate::kem::common::kem_type::KemType;
-    use crate::kem::common::macros::test_kem;
+    use crate::kem::kem_manager::KemManager;
+    #[cfg(feature = "openssl")]
     use crate::keys::{PrivateKey, PublicKey};
+    use crate::test_kem;
+    #[cfg(feature = "openssl")]
     use base64::{engine::general_purpose::STANDARD, Engine as _};
 
     #[test]
@@ -239,6 +737,149 @@ mod tests {
         test_kem!(kem);
     }
 
+    #[test]
+    fn test_ml_kem_implicit_rejection() {
+        // Mutated ciphertexts must be decapsulated exactly as the reference
+        // ml-kem implementation does it, i.e. through implicit rejection
+        let mut kem = MlKemManager::new(KemType::MlKem768).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+
+        let dk = get_decapsulation_key_obj::<MlKem768>(&sk).unwrap();
+        for i in [0, 1, ct.len() / 2, ct.len() - 1] {
+            let mut mutated = ct.clone();
+            mutated[i] ^= 0x01;
+            let ss_mutated = kem.decap(&sk, &mutated).unwrap();
+            assert_ne!(ss_mutated, ss);
+
+            let c = Ciphertext::<MlKem768>::try_from(mutated.as_slice()).unwrap();
+            let reference = dk.decapsulate(&c).unwrap();
+            assert_eq!(ss_mutated, reference.as_slice());
+        }
+
+        // Wrong lengths are rejected rather than implicitly rejected
+        assert!(kem.decap(&sk, &ct[1..]).is_err());
+    }
+
+    #[test]
+    fn test_ml_kem_decap_checked() {
+        for kem_type in [KemType::MlKem512, KemType::MlKem768, KemType::MlKem1024] {
+            let mut kem = MlKemManager::new(kem_type).unwrap();
+            let (pk, sk) = kem.key_gen().unwrap();
+            let (ss, ct) = kem.encap(&pk).unwrap();
+            assert_eq!(kem.decap_checked(&sk, &ct).unwrap(), (ss.clone(), false));
+
+            let mut mutated = ct.clone();
+            mutated[0] ^= 0x01;
+            let (ss_mutated, rejected) = kem.decap_checked(&sk, &mutated).unwrap();
+            assert!(rejected);
+            assert_ne!(ss_mutated, ss);
+            assert_eq!(ss_mutated, kem.decap(&sk, &mutated).unwrap());
+
+            assert!(kem.decap_checked(&sk, &ct[1..]).is_err());
+            assert!(kem.decap_checked(&sk[1..], &ct).is_err());
+        }
+    }
+
+    #[test]
+    fn test_ml_kem_strict_fips() {
+        let mut kem = MlKemManager::new(KemType::MlKem512).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        check_encapsulation_key(&KemType::MlKem512, &pk).unwrap();
+        check_decapsulation_key(&KemType::MlKem512, &sk).unwrap();
+
+        // A coefficient of 4095 is not reduced modulo q
+        let mut bad_pk = pk.clone();
+        bad_pk[0] = 0xff;
+        bad_pk[1] |= 0x0f;
+        assert_eq!(
+            check_encapsulation_key(&KemType::MlKem512, &bad_pk),
+            Err(QuantCryptError::InvalidPublicKey)
+        );
+        // H(ek) doesn't match the embedded encapsulation key
+        let mut bad_sk = sk.clone();
+        bad_sk[768] ^= 1;
+        assert_eq!(
+            check_decapsulation_key(&KemType::MlKem512, &bad_sk),
+            Err(QuantCryptError::InvalidPrivateKey)
+        );
+
+        // The keys are only checked in the strict mode
+        let (_, ct) = kem.encap(&bad_pk).unwrap();
+        kem.decap(&bad_sk, &ct).unwrap();
+        kem.set_strict_fips(true);
+        assert!(kem.get_strict_fips());
+        assert_eq!(kem.encap(&bad_pk), Err(QuantCryptError::InvalidPublicKey));
+        assert_eq!(
+            kem.encap_batch(&[&pk, &bad_pk]),
+            Err(QuantCryptError::InvalidPublicKey)
+        );
+        assert_eq!(
+            kem.decap(&bad_sk, &ct),
+            Err(QuantCryptError::InvalidPrivateKey)
+        );
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn test_ml_kem_into_buffers() {
+        let mut kem = MlKemManager::new(KemType::MlKem768).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let mut ss = [0u8; 32];
+        let mut ct = [0u8; 1088];
+        assert_eq!(kem.encap_into(&pk, &mut ss, &mut ct).unwrap(), (32, 1088));
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        let mut out = [0u8; 40];
+        assert_eq!(kem.decap_into(&sk, &ct, &mut out).unwrap(), 32);
+        assert_eq!(out[..32], ss);
+
+        assert_eq!(
+            kem.decap_into(&sk, &ct, &mut out[..31]),
+            Err(QuantCryptError::BufferTooSmall {
+                needed: 32,
+                actual: 31
+            })
+        );
+        assert!(kem.encap_into(&pk, &mut ss, &mut ct[..1087]).is_err());
+
+        // Other KEMs copy their outputs
+        let mut kem = KemManager::new(KemType::MlKem768X25519).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let mut ct = vec![0u8; 2048];
+        let (ss_len, ct_len) = kem.encap_into(&pk, &mut out, &mut ct).unwrap();
+        assert_eq!(ss_len, 32);
+        let mut ss = [0u8; 32];
+        kem.decap_into(&sk, &ct[..ct_len], &mut ss).unwrap();
+        assert_eq!(out[..32], ss);
+    }
+
+    #[test]
+    fn test_ml_kem_seed_format() {
+        for kem_type in [KemType::MlKem512, KemType::MlKem768, KemType::MlKem1024] {
+            let mut kem = MlKemManager::new(kem_type.clone()).unwrap();
+            let (pk, seed) = kem.key_gen_seed().unwrap();
+            assert_eq!(seed.len(), ML_KEM_SEED_LEN);
+
+            let (pk2, sk) = kem.expand_seed(&seed).unwrap();
+            assert_eq!(pk2, pk);
+            check_decapsulation_key(&kem_type, &sk).unwrap();
+            // The expanded key ends with z
+            assert_eq!(&sk[sk.len() - 32..], &seed[32..]);
+
+            let (ss, ct) = kem.encap(&pk).unwrap();
+            assert_eq!(kem.decap_seed(&seed, &ct).unwrap(), ss);
+            assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        }
+
+        let kem = MlKemManager::new(KemType::MlKem768).unwrap();
+        assert_eq!(
+            kem.expand_seed(&[0u8; 63]),
+            Err(QuantCryptError::InvalidPrivateKey)
+        );
+    }
+
+    #[cfg(feature = "openssl")]
     #[test]
     fn test_ml_kem_512_draft_vectors() {
         let ee_pk = PublicKey::from_file("test/data/mlkem512_pk.pem").unwrap();
@@ -1,9 +1,17 @@
 pub mod api;
 mod asn1;
+pub mod bike;
+pub mod chempat;
 pub mod common;
 pub mod composite_kem;
 pub mod ec_kem;
 pub mod kem_manager;
+#[cfg(feature = "legacy")]
+pub mod kyber;
 pub mod ml_kem;
 pub mod rsa_kem;
+pub mod sntrup;
+pub mod triple_kem;
+#[cfg(feature = "legacy")]
+pub mod x25519_kyber;
 pub mod xwing;
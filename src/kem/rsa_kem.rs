@@ -1,10 +1,12 @@
 use rand::RngCore;
-use rand_chacha::ChaCha20Rng;
-use rand_core::{CryptoRngCore, SeedableRng};
-use sha2::Sha256;
+use rand_core::CryptoRngCore;
+use sha2::digest::DynDigest;
+use sha2::{Sha256, Sha384, Sha512};
 
+use crate::kem::common::kem_info::{OaepHash, OaepParams};
 use crate::kem::common::kem_trait::Kem;
 use crate::kem::common::kem_type::KemType;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::{kem::common::kem_info::KemInfo, QuantCryptError};
 use rsa::{
     oaep::Oaep,
@@ -14,9 +16,79 @@ use rsa::{
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+// Implement clone
+#[derive(Clone)]
 /// A KEM manager for the RSA-KEM method
 pub struct RsaKemManager {
     kem_info: KemInfo,
+    rng: Option<SharedRng>,
+}
+
+impl RsaKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.rng = Some(rng);
+    }
+
+    /// Set the RSA-OAEP parameters used to encapsulate and decapsulate
+    ///
+    /// The default is SHA-256 as both the digest and the MGF1 hash, with an
+    /// empty label, as required by the composite KEM drafts. Other parameters
+    /// are for interoperating with peers that use a different profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The RSA-OAEP parameters
+    #[allow(dead_code)]
+    pub fn set_oaep_params(&mut self, params: OaepParams) {
+        self.kem_info.oaep_params = Some(params);
+    }
+
+    /// Build the OAEP padding scheme from the configured parameters
+    fn get_padding(&self) -> Oaep {
+        oaep_padding(&self.kem_info.oaep_params.clone().unwrap_or_default())
+    }
+}
+
+/// Build an OAEP padding scheme
+///
+/// # Arguments
+///
+/// * `params` - The RSA-OAEP parameters
+///
+/// # Returns
+///
+/// The padding scheme
+pub(crate) fn oaep_padding(params: &OaepParams) -> Oaep {
+    fn digest(hash: OaepHash) -> Box<dyn DynDigest + Send + Sync> {
+        match hash {
+            OaepHash::Sha256 => Box::new(Sha256::default()),
+            OaepHash::Sha384 => Box::new(Sha384::default()),
+            OaepHash::Sha512 => Box::new(Sha512::default()),
+        }
+    }
+
+    Oaep {
+        digest: digest(params.digest),
+        mgf_digest: digest(params.mgf_hash),
+        label: params.label.clone(),
+    }
 }
 
 impl Kem for RsaKemManager {
@@ -31,7 +103,10 @@ impl Kem for RsaKemManager {
     /// A new KEM instance
     fn new(kem_type: KemType) -> Result<Self> {
         let kem_info = KemInfo::new(kem_type);
-        Ok(Self { kem_info })
+        Ok(Self {
+            kem_info,
+            rng: None,
+        })
     }
 
     /// Generate a keypair using the specified RNG
@@ -73,13 +148,13 @@ impl Kem for RsaKemManager {
         Ok((pk, sk))
     }
 
-    /// Generate a keypair using the default RNG ChaCha20Rng
+    /// Generate a keypair using the RNG of the manager, or else the default RNG ChaCha20Rng
     ///
     /// # Returns
     ///
     /// A tuple containing the public and secret keys (pk, sk)
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-        let mut rng = ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_key_gen(self.rng.as_ref())?;
         self.key_gen_with_rng(&mut rng)
     }
 
@@ -105,15 +180,16 @@ impl Kem for RsaKemManager {
         +--------------------+-----------------------------------+
         | ss_len             | 256 bits                          |
         +--------------------+-----------------------------------+
+        The defaults above can be changed with `set_oaep_params`
          */
         // Generate a shared secret (32 bits)
         let mut ss = vec![0u8; 32];
-        let mut rng = ChaCha20Rng::from_entropy();
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
         rng.fill_bytes(&mut ss);
 
         let pub_key =
             RsaPublicKey::from_pkcs1_der(pk).map_err(|_| QuantCryptError::InvalidPublicKey)?;
-        let padding = Oaep::new_with_mgf_hash::<Sha256, Sha256>();
+        let padding = self.get_padding();
         let ct = pub_key
             .encrypt(&mut rng, padding, &ss)
             .map_err(|_| QuantCryptError::EncapFailed)?;
@@ -134,7 +210,7 @@ impl Kem for RsaKemManager {
         // Create a private key from the DER-encoded bytes
         let priv_key =
             RsaPrivateKey::from_pkcs1_der(sk).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
-        let padding = Oaep::new_with_mgf_hash::<Sha256, Sha256>();
+        let padding = self.get_padding();
         let ss = priv_key
             .decrypt(padding, ct)
             .map_err(|_| QuantCryptError::DecapFailed)?;
@@ -158,7 +234,7 @@ impl Kem for RsaKemManager {
 mod tests {
     use super::*;
     use crate::kem::common::kem_type::KemType;
-    use crate::kem::common::macros::test_kem;
+    use crate::test_kem;
 
     #[test]
     fn test_rsa_kem_2048() {
@@ -177,4 +253,26 @@ mod tests {
         let kem = RsaKemManager::new(KemType::RsaOAEP4096);
         test_kem!(kem);
     }
+
+    #[test]
+    fn test_rsa_kem_oaep_params() {
+        let mut kem = RsaKemManager::new(KemType::RsaOAEP2048).unwrap();
+        assert_eq!(kem.get_kem_info().oaep_params, Some(OaepParams::default()));
+        let (pk, sk) = kem.key_gen().unwrap();
+
+        // SHA-384 digest with SHA-256 MGF1 and a label
+        let params = OaepParams {
+            digest: OaepHash::Sha384,
+            mgf_hash: OaepHash::Sha256,
+            label: Some("label".to_string()),
+        };
+        kem.set_oaep_params(params.clone());
+        assert_eq!(kem.get_kem_info().oaep_params, Some(params));
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // The default parameters don't match
+        let default_kem = RsaKemManager::new(KemType::RsaOAEP2048).unwrap();
+        assert!(default_kem.decap(&sk, &ct).is_err());
+    }
 }
@@ -0,0 +1,851 @@
+use rand_core::CryptoRngCore;
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::ec_kem::EcKemManager;
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+// Parameters of sntrup761
+const P: usize = 761;
+const Q: i32 = 4591;
+const W: usize = 286;
+const Q12: i32 = (Q - 1) / 2;
+
+/// The length of an encoded small polynomial
+const SMALL_BYTES: usize = (P + 3) / 4;
+/// The length of an encoded polynomial mod q, which is the public key
+const RQ_BYTES: usize = 1158;
+/// The length of an encoded rounded polynomial
+const ROUNDED_BYTES: usize = 1007;
+/// The length of the hashes
+const HASH_BYTES: usize = 32;
+
+const PK_LEN: usize = RQ_BYTES;
+const SK_LEN: usize = 2 * SMALL_BYTES + RQ_BYTES + SMALL_BYTES + HASH_BYTES;
+const CT_LEN: usize = ROUNDED_BYTES + HASH_BYTES;
+
+/// The length of an X25519 public key, secret key and ciphertext
+const X25519_LEN: usize = 32;
+
+// ----- constant-time helpers
+
+/// -1 if x is non-zero, 0 otherwise
+fn nonzero_mask(x: i16) -> i32 {
+    -(((x as u16 as u32).wrapping_neg() >> 31) as i32)
+}
+
+/// -1 if x is negative, 0 otherwise
+fn negative_mask(x: i16) -> i32 {
+    -(((x as u16) >> 15) as i32)
+}
+
+/// Reduce x to -1, 0 or 1 mod 3, for -16384 <= x < 16384
+fn f3_freeze(x: i32) -> i8 {
+    (x - 3 * ((10923 * x + 16384) >> 15)) as i8
+}
+
+/// Reduce x to -(q-1)/2..(q-1)/2 mod q, for -7000000 < x < 7000000
+fn fq_freeze(mut x: i32) -> i16 {
+    x -= Q * ((57 * x) >> 18);
+    x -= Q * ((29235 * x + 67108864) >> 27);
+    x as i16
+}
+
+/// Reduce x to -(q-1)/2..(q-1)/2 mod q, for the larger values of the inversion
+fn fq_bigfreeze(mut x: i32) -> i16 {
+    x -= Q * ((4 * x) >> 14);
+    x -= Q * ((57 * x) >> 18);
+    x -= Q * ((29235 * x + 67108864) >> 27);
+    x -= Q * ((29235 * x + 67108864) >> 27);
+    x as i16
+}
+
+/// Invert a non-zero element of Fq, as a^(q-2)
+fn fq_recip(a: i16) -> i16 {
+    let mut ai = a;
+    for _ in 1..Q - 2 {
+        ai = fq_bigfreeze(a as i32 * ai as i32);
+    }
+    ai
+}
+
+/// Swap a and b if a > b, in constant time
+fn minmax(a: &mut i32, b: &mut i32) {
+    let ab = *b ^ *a;
+    let mut c = (*b as i64 - *a as i64) as i32;
+    c ^= ab & (c ^ *b);
+    c >>= 31;
+    c &= ab;
+    *a ^= c;
+    *b ^= c;
+}
+
+/// Sort with a sorting network, so the memory accesses don't depend on the values
+fn sort_u32(x: &mut [u32]) {
+    let n = x.len();
+    if n < 2 {
+        return;
+    }
+    let mut y: Vec<i32> = x.iter().map(|v| (v ^ 0x8000_0000) as i32).collect();
+    let mut top = 1;
+    while top < n - top {
+        top += top;
+    }
+    let mut p = top;
+    while p > 0 {
+        for i in 0..n - p {
+            if i & p == 0 {
+                let (mut a, mut b) = (y[i], y[i + p]);
+                minmax(&mut a, &mut b);
+                y[i] = a;
+                y[i + p] = b;
+            }
+        }
+        let mut i = 0;
+        let mut q = top;
+        while q > p {
+            while i < n - q {
+                if i & p == 0 {
+                    let mut a = y[i + p];
+                    let mut r = q;
+                    while r > p {
+                        minmax(&mut a, &mut y[i + r]);
+                        r >>= 1;
+                    }
+                    y[i + p] = a;
+                }
+                i += 1;
+            }
+            q >>= 1;
+        }
+        p >>= 1;
+    }
+    for (v, s) in x.iter_mut().zip(y) {
+        *v = s as u32 ^ 0x8000_0000;
+    }
+}
+
+// ----- polynomials
+
+/// h = f * g in R3 = Z3[x]/(x^p - x - 1)
+fn r3_mult(f: &[i8], g: &[i8]) -> Vec<i8> {
+    let mut fg = vec![0i8; 2 * P - 1];
+    for (i, out) in fg.iter_mut().enumerate() {
+        let mut result = 0i8;
+        for j in i.saturating_sub(P - 1)..=i.min(P - 1) {
+            result = f3_freeze(result as i32 + f[j] as i32 * g[i - j] as i32);
+        }
+        *out = result;
+    }
+    for i in (P..2 * P - 1).rev() {
+        fg[i - P] = f3_freeze(fg[i - P] as i32 + fg[i] as i32);
+        fg[i - P + 1] = f3_freeze(fg[i - P + 1] as i32 + fg[i] as i32);
+    }
+    fg.truncate(P);
+    fg
+}
+
+/// h = f * g in Rq = Zq[x]/(x^p - x - 1), for a small g
+fn rq_mult_small(f: &[i16], g: &[i8]) -> Vec<i16> {
+    let mut fg = vec![0i16; 2 * P - 1];
+    for (i, out) in fg.iter_mut().enumerate() {
+        let mut result = 0i32;
+        for j in i.saturating_sub(P - 1)..=i.min(P - 1) {
+            result += f[j] as i32 * g[i - j] as i32;
+        }
+        *out = fq_freeze(result);
+    }
+    for i in (P..2 * P - 1).rev() {
+        fg[i - P] = fq_freeze(fg[i - P] as i32 + fg[i] as i32);
+        fg[i - P + 1] = fq_freeze(fg[i - P + 1] as i32 + fg[i] as i32);
+    }
+    fg.truncate(P);
+    fg
+}
+
+/// Invert a polynomial in R3
+///
+/// # Returns
+///
+/// The inverse, or `None` if the polynomial is not invertible
+fn r3_recip(input: &[i8]) -> Option<Vec<i8>> {
+    let mut f = vec![0i8; P + 1];
+    let mut g = vec![0i8; P + 1];
+    let mut v = vec![0i8; P + 1];
+    let mut r = vec![0i8; P + 1];
+    r[0] = 1;
+    f[0] = 1;
+    f[P - 1] = -1;
+    f[P] = -1;
+    for i in 0..P {
+        g[P - 1 - i] = input[i];
+    }
+    let mut delta: i32 = 1;
+
+    for _ in 0..2 * P - 1 {
+        v.copy_within(0..P, 1);
+        v[0] = 0;
+
+        let sign = -(g[0] as i32) * f[0] as i32;
+        let swap = negative_mask(-delta as i16) & nonzero_mask(g[0] as i16);
+        delta ^= swap & (delta ^ -delta);
+        delta += 1;
+
+        for i in 0..P + 1 {
+            let t = (swap & (f[i] ^ g[i]) as i32) as i8;
+            f[i] ^= t;
+            g[i] ^= t;
+            let t = (swap & (v[i] ^ r[i]) as i32) as i8;
+            v[i] ^= t;
+            r[i] ^= t;
+        }
+        for i in 0..P + 1 {
+            g[i] = f3_freeze(g[i] as i32 + sign * f[i] as i32);
+            r[i] = f3_freeze(r[i] as i32 + sign * v[i] as i32);
+        }
+        g.copy_within(1..P + 1, 0);
+        g[P] = 0;
+    }
+
+    if delta != 0 {
+        return None;
+    }
+    let sign = f[0];
+    Some((0..P).map(|i| sign * v[P - 1 - i]).collect())
+}
+
+/// Compute 1 / (3 * input) in Rq, for an input of weight w
+fn rq_recip3(input: &[i8]) -> Vec<i16> {
+    let mut f = vec![0i16; P + 1];
+    let mut g = vec![0i16; P + 1];
+    let mut v = vec![0i16; P + 1];
+    let mut r = vec![0i16; P + 1];
+    r[0] = fq_recip(3);
+    f[0] = 1;
+    f[P - 1] = -1;
+    f[P] = -1;
+    for i in 0..P {
+        g[P - 1 - i] = input[i] as i16;
+    }
+    let mut delta: i32 = 1;
+
+    for _ in 0..2 * P - 1 {
+        v.copy_within(0..P, 1);
+        v[0] = 0;
+
+        let swap = negative_mask(-delta as i16) & nonzero_mask(g[0]);
+        delta ^= swap & (delta ^ -delta);
+        delta += 1;
+
+        for i in 0..P + 1 {
+            let t = (swap & (f[i] ^ g[i]) as i32) as i16;
+            f[i] ^= t;
+            g[i] ^= t;
+            let t = (swap & (v[i] ^ r[i]) as i32) as i16;
+            v[i] ^= t;
+            r[i] ^= t;
+        }
+        let (f0, g0) = (f[0] as i32, g[0] as i32);
+        for i in 0..P + 1 {
+            g[i] = fq_bigfreeze(f0 * g[i] as i32 - g0 * f[i] as i32);
+            r[i] = fq_bigfreeze(f0 * r[i] as i32 - g0 * v[i] as i32);
+        }
+        g.copy_within(1..P + 1, 0);
+        g[P] = 0;
+    }
+
+    let scale = fq_recip(f[0]) as i32;
+    (0..P)
+        .map(|i| fq_bigfreeze(scale * v[P - 1 - i] as i32))
+        .collect()
+}
+
+/// Force a polynomial to weight w, replacing it with a fixed one if it hasn't
+fn weight_force(r: &mut [i8]) {
+    let weight: i32 = r.iter().map(|c| (c & 1) as i32).sum();
+    let mask = !(nonzero_mask((weight - W as i32) as i16) as i8);
+    for (i, c) in r.iter_mut().enumerate() {
+        *c = if i < W {
+            ((*c ^ 1) & mask) ^ 1
+        } else {
+            *c & mask
+        };
+    }
+}
+
+// ----- encodings
+
+/// Encode integers r[i] in 0..m[i] compactly
+fn encode(out: &mut Vec<u8>, r: &[u16], m: &[u16]) {
+    if r.len() == 1 {
+        let (mut r0, mut m0) = (r[0], m[0]);
+        while m0 > 1 {
+            out.push(r0 as u8);
+            r0 >>= 8;
+            m0 = (m0 + 255) >> 8;
+        }
+        return;
+    }
+    let mut r2 = Vec::with_capacity((r.len() + 1) / 2);
+    let mut m2 = Vec::with_capacity((r.len() + 1) / 2);
+    for (pair_r, pair_m) in r.chunks(2).zip(m.chunks(2)) {
+        if pair_r.len() == 1 {
+            r2.push(pair_r[0]);
+            m2.push(pair_m[0]);
+            continue;
+        }
+        let m0 = pair_m[0] as u32;
+        let mut rr = pair_r[0] as u32 + pair_r[1] as u32 * m0;
+        let mut mm = pair_m[1] as u32 * m0;
+        while mm >= 16384 {
+            out.push(rr as u8);
+            rr >>= 8;
+            mm = (mm + 255) >> 8;
+        }
+        r2.push(rr as u16);
+        m2.push(mm as u16);
+    }
+    encode(out, &r2, &m2);
+}
+
+/// Decode integers in 0..m[i] encoded by `encode`
+///
+/// Any input of the right length decodes to integers in range.
+fn decode(s: &[u8], m: &[u16]) -> Vec<u16> {
+    if m.len() == 1 {
+        let value = match m[0] {
+            1 => 0,
+            2..=256 => s[0] as u32 % m[0] as u32,
+            _ => (s[0] as u32 + ((s[1] as u32) << 8)) % m[0] as u32,
+        };
+        return vec![value as u16];
+    }
+    let mut m2 = Vec::with_capacity((m.len() + 1) / 2);
+    let mut bottom = Vec::with_capacity(m.len() / 2);
+    let mut pos = 0;
+    for pair_m in m.chunks(2) {
+        if pair_m.len() == 1 {
+            m2.push(pair_m[0] as u32);
+            continue;
+        }
+        let mm = pair_m[0] as u32 * pair_m[1] as u32;
+        if mm > 256 * 16383 {
+            bottom.push((65536, s[pos] as u32 + 256 * s[pos + 1] as u32));
+            pos += 2;
+            m2.push((((mm + 255) >> 8) + 255) >> 8);
+        } else if mm >= 16384 {
+            bottom.push((256, s[pos] as u32));
+            pos += 1;
+            m2.push((mm + 255) >> 8);
+        } else {
+            bottom.push((1, 0));
+            m2.push(mm);
+        }
+    }
+    let m2: Vec<u16> = m2.into_iter().map(|v| v as u16).collect();
+    let r2 = decode(&s[pos..], &m2);
+    let mut out = Vec::with_capacity(m.len());
+    for (i, (t, b)) in bottom.into_iter().enumerate() {
+        let r = b + t * r2[i] as u32;
+        let (m0, m1) = (m[2 * i] as u32, m[2 * i + 1] as u32);
+        out.push((r % m0) as u16);
+        out.push((r / m0 % m1) as u16);
+    }
+    if m.len() % 2 == 1 {
+        out.push(r2[r2.len() - 1]);
+    }
+    out
+}
+
+/// Encode a small polynomial in 2 bits per coefficient
+fn small_encode(f: &[i8]) -> Vec<u8> {
+    f.chunks(4)
+        .map(|c| {
+            c.iter()
+                .enumerate()
+                .fold(0u8, |x, (j, v)| x | (((v + 1) as u8) << (2 * j)))
+        })
+        .collect()
+}
+
+fn small_decode(s: &[u8]) -> Zeroizing<Vec<i8>> {
+    let mut f = Zeroizing::new(Vec::with_capacity(P));
+    for i in 0..P {
+        f.push(((s[i / 4] >> (2 * (i % 4))) & 3) as i8 - 1);
+    }
+    f
+}
+
+fn rq_encode(h: &[i16]) -> Vec<u8> {
+    let r: Vec<u16> = h.iter().map(|c| (*c as i32 + Q12) as u16).collect();
+    let mut out = Vec::with_capacity(RQ_BYTES);
+    encode(&mut out, &r, &[Q as u16; P]);
+    out
+}
+
+fn rq_decode(s: &[u8]) -> Vec<i16> {
+    decode(s, &[Q as u16; P])
+        .into_iter()
+        .map(|r| (r as i32 - Q12) as i16)
+        .collect()
+}
+
+/// Round the coefficients to multiples of 3 and encode them
+fn rounded_encode(h: &[i16]) -> Vec<u8> {
+    let r: Vec<u16> = h
+        .iter()
+        .map(|c| {
+            let rounded = 3 * ((10923 * *c as i32 + 16384) >> 15);
+            (((rounded + Q12) * 10923) >> 15) as u16
+        })
+        .collect();
+    let mut out = Vec::with_capacity(ROUNDED_BYTES);
+    encode(&mut out, &r, &[((Q + 2) / 3) as u16; P]);
+    out
+}
+
+fn rounded_decode(s: &[u8]) -> Vec<i16> {
+    decode(s, &[((Q + 2) / 3) as u16; P])
+        .into_iter()
+        .map(|r| (r as i32 * 3 - Q12) as i16)
+        .collect()
+}
+
+// ----- randomness
+
+fn random_u32s(rng: &mut impl CryptoRngCore) -> Zeroizing<Vec<u32>> {
+    let mut bytes = Zeroizing::new(vec![0u8; 4 * P]);
+    rng.fill_bytes(&mut bytes);
+    Zeroizing::new(
+        bytes
+            .chunks(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+    )
+}
+
+/// A random small polynomial of weight w
+fn short_random(rng: &mut impl CryptoRngCore) -> Zeroizing<Vec<i8>> {
+    let mut l = random_u32s(rng);
+    for (i, x) in l.iter_mut().enumerate() {
+        *x = if i < W { *x & !1 } else { (*x & !3) | 1 };
+    }
+    sort_u32(&mut l);
+    Zeroizing::new(l.iter().map(|x| (x & 3) as i8 - 1).collect())
+}
+
+/// A random small polynomial
+fn small_random(rng: &mut impl CryptoRngCore) -> Zeroizing<Vec<i8>> {
+    let l = random_u32s(rng);
+    Zeroizing::new(
+        l.iter()
+            .map(|x| ((((x & 0x3fff_ffff) as u64 * 3) >> 30) as i8) - 1)
+            .collect(),
+    )
+}
+
+// ----- the KEM
+
+/// The first 32 bytes of SHA-512 over a prefix byte and the input
+fn hash_prefix(prefix: u8, parts: &[&[u8]]) -> [u8; HASH_BYTES] {
+    let mut hasher = Sha512::new();
+    hasher.update([prefix]);
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut out = [0u8; HASH_BYTES];
+    out.copy_from_slice(&hasher.finalize()[..HASH_BYTES]);
+    out
+}
+
+fn sntrup_key_gen(rng: &mut impl CryptoRngCore) -> (Vec<u8>, Vec<u8>) {
+    let (g, v) = loop {
+        let g = small_random(rng);
+        if let Some(v) = r3_recip(&g) {
+            break (g, Zeroizing::new(v));
+        }
+    };
+    let f = short_random(rng);
+    let pk = rq_encode(&rq_mult_small(&rq_recip3(&f), &g));
+
+    let mut rho = Zeroizing::new([0u8; SMALL_BYTES]);
+    rng.fill_bytes(rho.as_mut());
+    let mut sk = Vec::with_capacity(SK_LEN);
+    sk.extend_from_slice(&small_encode(&f));
+    sk.extend_from_slice(&small_encode(&v));
+    sk.extend_from_slice(&pk);
+    sk.extend_from_slice(rho.as_ref());
+    sk.extend_from_slice(&hash_prefix(4, &[&pk]));
+    (pk, sk)
+}
+
+/// Encrypt r, returning the encoding of r and the ciphertext with its confirmation hash
+fn hide(r: &[i8], pk: &[u8], cache: &[u8]) -> (Zeroizing<Vec<u8>>, Vec<u8>) {
+    let r_enc = Zeroizing::new(small_encode(r));
+    let mut ct = rounded_encode(&rq_mult_small(&rq_decode(pk), r));
+    let confirm = hash_prefix(2, &[&hash_prefix(3, &[&r_enc]), cache]);
+    ct.extend_from_slice(&confirm);
+    (r_enc, ct)
+}
+
+fn sntrup_encap(pk: &[u8], rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+    if pk.len() != PK_LEN {
+        return Err(QuantCryptError::InvalidPublicKey);
+    }
+    let cache = hash_prefix(4, &[pk]);
+    let r = short_random(rng);
+    let (r_enc, ct) = hide(&r, pk, &cache);
+    let ss = hash_prefix(1, &[&hash_prefix(3, &[&r_enc]), &ct]);
+    Ok((ss.to_vec(), ct))
+}
+
+fn sntrup_decap(sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+    if sk.len() != SK_LEN {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    if ct.len() != CT_LEN {
+        return Err(QuantCryptError::InvalidCiphertext);
+    }
+    let f = small_decode(&sk[..SMALL_BYTES]);
+    let v = small_decode(&sk[SMALL_BYTES..2 * SMALL_BYTES]);
+    let (pk, rest) = sk[2 * SMALL_BYTES..].split_at(PK_LEN);
+    let (rho, cache) = rest.split_at(SMALL_BYTES);
+
+    let c = rq_mult_small(&rounded_decode(&ct[..ROUNDED_BYTES]), &f);
+    let e: Zeroizing<Vec<i8>> = Zeroizing::new(
+        c.iter()
+            .map(|x| f3_freeze(fq_freeze(3 * *x as i32) as i32))
+            .collect(),
+    );
+    let mut r = Zeroizing::new(r3_mult(&e, &v));
+    weight_force(&mut r);
+
+    // Implicit rejection: a ciphertext that doesn't re-encrypt to itself
+    // gives a secret derived from rho
+    let (mut r_enc, ct_new) = hide(&r, pk, cache);
+    let diff = ct.iter().zip(&ct_new).fold(0u8, |d, (a, b)| d | (a ^ b));
+    let mask = (1 & ((diff as i32 - 1) >> 8)) - 1;
+    for (x, y) in r_enc.iter_mut().zip(rho) {
+        *x ^= (mask as u8) & (*x ^ y);
+    }
+    let ss = hash_prefix((1 + mask) as u8, &[&hash_prefix(3, &[&r_enc]), ct]);
+    Ok(ss.to_vec())
+}
+
+// Implement clone
+#[derive(Clone)]
+/// A KEM manager for Streamlined NTRU Prime sntrup761 and its X25519 hybrid
+///
+/// sntrup761 is implemented in pure Rust after the reference implementation.
+/// `KemType::Sntrup761X25519` is the KEM of the sntrup761x25519-sha512 key
+/// exchange of OpenSSH. Keys and ciphertexts are concatenations, sntrup761 first:
+///
+/// * pk = pk_sntrup761 || pk_X25519 (1190 bytes)
+/// * sk = sk_sntrup761 || sk_X25519 (1795 bytes)
+/// * ct = ct_sntrup761 || ct_X25519 (1071 bytes)
+/// * ss = SHA-512(ss_sntrup761 || ss_X25519) (64 bytes)
+///
+/// The hybrid shared secret is the one OpenSSH hashes into the exchange hash.
+pub struct SntrupKemManager {
+    kem_info: KemInfo,
+    x25519: Option<EcKemManager>,
+    rng: Option<SharedRng>,
+}
+
+impl SntrupKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        if let Some(x25519) = &mut self.x25519 {
+            x25519.set_rng(rng.clone());
+        }
+        self.rng = Some(rng);
+    }
+
+    /// Combine the shared secrets of the hybrid
+    fn combine(ss_s: &[u8], ss_x: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha512::new();
+        hasher.update(ss_s);
+        hasher.update(ss_x);
+        hasher.finalize().to_vec()
+    }
+}
+
+impl Kem for SntrupKemManager {
+    /// Create a new KEM instance
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    fn new(kem_type: KemType) -> Result<Self> {
+        let x25519 = match kem_type {
+            KemType::Sntrup761 => None,
+            KemType::Sntrup761X25519 => Some(EcKemManager::new(KemType::X25519)?),
+            _ => return Err(QuantCryptError::NotImplemented),
+        };
+        Ok(SntrupKemManager {
+            kem_info: KemInfo::new(kem_type),
+            x25519,
+            rng: None,
+        })
+    }
+
+    /// Generate a keypair using the RNG of the manager, or else the default RNG
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.key_gen_with_rng(&mut ManagerRng::for_key_gen(self.rng.as_ref())?)
+    }
+
+    /// Generate a keypair
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk, sk) = sntrup_key_gen(rng);
+        match &mut self.x25519 {
+            Some(x25519) => {
+                let (pk_x, sk_x) = x25519.key_gen_with_rng(rng)?;
+                Ok(([pk, pk_x].concat(), [sk, sk_x].concat()))
+            }
+            None => Ok((pk, sk)),
+        }
+    }
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
+        match &mut self.x25519 {
+            Some(x25519) => {
+                if pk.len() != PK_LEN + X25519_LEN {
+                    return Err(QuantCryptError::InvalidPublicKey);
+                }
+                let (pk_s, pk_x) = pk.split_at(PK_LEN);
+                let (ss_s, ct_s) = sntrup_encap(pk_s, &mut rng)?;
+                let (ss_x, ct_x) = x25519.encap(pk_x)?;
+                let (ss_s, ss_x) = (Zeroizing::new(ss_s), Zeroizing::new(ss_x));
+                Ok((Self::combine(&ss_s, &ss_x), [ct_s, ct_x].concat()))
+            }
+            None => sntrup_encap(pk, &mut rng),
+        }
+    }
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        match &self.x25519 {
+            Some(x25519) => {
+                if sk.len() != SK_LEN + X25519_LEN {
+                    return Err(QuantCryptError::InvalidPrivateKey);
+                }
+                if ct.len() != CT_LEN + X25519_LEN {
+                    return Err(QuantCryptError::InvalidCiphertext);
+                }
+                let (sk_s, sk_x) = sk.split_at(SK_LEN);
+                let (ct_s, ct_x) = ct.split_at(CT_LEN);
+                let ss_s = Zeroizing::new(sntrup_decap(sk_s, ct_s)?);
+                let ss_x = Zeroizing::new(x25519.decap(sk_x, ct_x)?);
+                Ok(Self::combine(&ss_s, &ss_x))
+            }
+            None => sntrup_decap(sk, ct),
+        }
+    }
+
+    /// Get KEM metadata information such as the key lengths
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem_info.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_kem;
+    use crate::utils::ctr_drbg::CtrDrbg;
+    use crate::utils::kat::RspFile;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{CryptoRng, RngCore, SeedableRng};
+    use sha2::Sha256;
+
+    /// The KAT DRBG as an RNG
+    ///
+    /// Every `fill_bytes` call is one `randombytes` call, whose boundaries the DRBG
+    /// output depends on. Like the reference implementation, key generation and
+    /// encapsulation draw all the coefficients of a random small polynomial with a
+    /// single call.
+    struct KatDrbg(CtrDrbg);
+
+    impl RngCore for KatDrbg {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.0.randombytes(dest);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for KatDrbg {}
+
+    /// SHA-256 of the output, to keep the vectors short
+    fn digest(data: &[u8]) -> String {
+        hex::encode(Sha256::digest(data))
+    }
+
+    #[test]
+    fn test_sntrup761() {
+        let kem = SntrupKemManager::new(KemType::Sntrup761);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_sntrup761_reference_vector() {
+        // Generated with the PQClean reference implementation, fed the same
+        // ChaCha20 stream
+        let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+        let (pk, sk) = sntrup_key_gen(&mut rng);
+        let (ss, mut ct) = sntrup_encap(&pk, &mut rng).unwrap();
+        assert_eq!(
+            digest(&pk),
+            "9f4bea4e695c384e0aadd1ed5568b6d81c3a303a4ee377bafcd97bf6e647c283"
+        );
+        assert_eq!(
+            digest(&sk),
+            "0d75d8dd76c3650d376b1e047110f198736608a7eaf59d13ac175add4ed00294"
+        );
+        assert_eq!(
+            digest(&ct),
+            "a287190ab1b223dad13aff828f78fe527a4ad3cd54218b93e8353729c34dc964"
+        );
+        assert_eq!(
+            digest(&ss),
+            "bfe1110722636cb198f8b4599831880e0e99d2379ad88d95da476b8fa5b6af8a"
+        );
+        assert_eq!(sntrup_decap(&sk, &ct).unwrap(), ss);
+
+        // Implicit rejection
+        ct[5] ^= 1;
+        assert_eq!(
+            digest(&sntrup_decap(&sk, &ct).unwrap()),
+            "be0dff8b19c18003ee3c9d21222eebd879a9b30a59c5f778df58fefaf0a901b4"
+        );
+    }
+
+    #[test]
+    fn test_sntrup761_official_kat() {
+        let rsp = RspFile::from_file("test/data/kat/sntrup761.rsp").unwrap();
+        assert!(!rsp.get_records().is_empty());
+        for record in rsp.get_records() {
+            let seed = record.get_bytes("seed").unwrap();
+            let mut rng = KatDrbg(CtrDrbg::new(&seed.try_into().unwrap(), None));
+            let (pk, sk) = sntrup_key_gen(&mut rng);
+            assert_eq!(pk, record.get_bytes("pk").unwrap());
+            assert_eq!(sk, record.get_bytes("sk").unwrap());
+
+            let (ss, ct) = sntrup_encap(&pk, &mut rng).unwrap();
+            assert_eq!(ct, record.get_bytes("ct").unwrap());
+            assert_eq!(ss, record.get_bytes("ss").unwrap());
+
+            let ss = sntrup_decap(
+                &record.get_bytes("sk").unwrap(),
+                &record.get_bytes("ct").unwrap(),
+            )
+            .unwrap();
+            assert_eq!(ss, record.get_bytes("ss").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_sntrup761_x25519_openssh_vector() {
+        // The server side of an exchange accepted by an OpenSSH client, see
+        // test/data/kat/README.md
+        let rsp = RspFile::from_file("test/data/kat/sntrup761x25519_openssh.rsp").unwrap();
+        assert!(!rsp.get_records().is_empty());
+        for record in rsp.get_records() {
+            let seed = record.get_bytes("rng_seed").unwrap();
+            let rng = SharedRng::new(ChaCha20Rng::from_seed(seed.try_into().unwrap()));
+            let mut kem = SntrupKemManager::new_with_rng(KemType::Sntrup761X25519, rng).unwrap();
+            let (ss, ct) = kem.encap(&record.get_bytes("pk").unwrap()).unwrap();
+            assert_eq!(ct, record.get_bytes("ct").unwrap());
+            assert_eq!(ss, record.get_bytes("ss").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_sntrup761_x25519() {
+        let kem = SntrupKemManager::new(KemType::Sntrup761X25519);
+        test_kem!(kem);
+
+        let mut kem = SntrupKemManager::new(KemType::Sntrup761X25519).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        let ss_s = sntrup_decap(&sk[..SK_LEN], &ct[..CT_LEN]).unwrap();
+        let ss_x = EcKemManager::new(KemType::X25519)
+            .unwrap()
+            .decap(&sk[SK_LEN..], &ct[CT_LEN..])
+            .unwrap();
+        assert_eq!(ss, Sha512::digest([ss_s, ss_x].concat()).to_vec());
+        assert!(kem.decap(&sk, &ct[1..]).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use rand_core::CryptoRngCore;
+
+use crate::kem::common::combined_kem::{Combiner, CombinerInput, KdfCombiner};
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::kem_manager::KemManager;
+use crate::utils::entropy::SharedRng;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+// Implement clone
+#[derive(Clone)]
+/// A KEM manager for three-way hybrids of ML-KEM and two traditional KEMs
+///
+/// Two traditional KEMs of different families hedge against a flaw in one of
+/// them, while ML-KEM protects against quantum attackers. Keys and ciphertexts
+/// are concatenations of the fixed length components, post-quantum first:
+///
+/// * pk = pk_pq || pk_t1 || pk_t2
+/// * sk = sk_pq || sk_t1 || sk_t2 || pk_t1 || pk_t2
+/// * ct = ct_pq || ct_t1 || ct_t2
+///
+/// The shared secret extends the SHA3-256 combiner of the composite KEM draft
+/// to the second traditional component:
+/// `SHA3-256(ss_pq || ss_t1 || ss_t2 || ct_t1 || ct_t2 || pk_t1 || pk_t2 || label)`.
+/// As every component has a fixed length, the input is unambiguous.
+pub struct TripleKemManager {
+    /// The KEM metadata information
+    kem_info: KemInfo,
+    /// The post-quantum KEM manager
+    pq_kem: Box<KemManager>,
+    /// The traditional KEM managers
+    trad_kems: [Box<KemManager>; 2],
+    /// The combiner of the shared secrets
+    combiner: KdfCombiner,
+}
+
+impl TripleKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// The three components share the RNG.
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if a component is provided by a registered backend
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng)?;
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the components from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) -> Result<()> {
+        self.pq_kem.set_rng(rng.clone())?;
+        for kem in self.trad_kems.iter_mut() {
+            kem.set_rng(rng.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Get the fixed public key, secret key and ciphertext lengths of a component
+    fn get_lens(kem: &KemManager) -> Result<(usize, usize, usize)> {
+        let info = kem.get_kem_info();
+        match (info.pk_byte_len, info.sk_byte_len, info.ct_byte_len) {
+            (Some(pk), Some(sk), Some(ct)) => Ok((pk, sk, ct)),
+            _ => Err(QuantCryptError::NotImplemented),
+        }
+    }
+
+    /// Split a value into consecutive parts of the given lengths
+    fn split<'a>(value: &'a [u8], lens: &[usize]) -> Option<Vec<&'a [u8]>> {
+        if value.len() != lens.iter().sum::<usize>() {
+            return None;
+        }
+        let mut rest = value;
+        let mut parts = Vec::with_capacity(lens.len());
+        for len in lens {
+            let (part, tail) = rest.split_at(*len);
+            parts.push(part);
+            rest = tail;
+        }
+        Some(parts)
+    }
+
+    /// Encode the key pairs of the components
+    fn encode_keys(
+        (pk_pq, sk_pq): (Vec<u8>, Vec<u8>),
+        (pk_t1, sk_t1): (Vec<u8>, Vec<u8>),
+        (pk_t2, sk_t2): (Vec<u8>, Vec<u8>),
+    ) -> (Vec<u8>, Vec<u8>) {
+        let pk = [pk_pq.as_slice(), &pk_t1, &pk_t2].concat();
+        let sk = [sk_pq.as_slice(), &sk_t1, &sk_t2, &pk_t1, &pk_t2].concat();
+        (pk, sk)
+    }
+
+    /// Replace the domain separation label of the combiner
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label to use instead of the name of the triple
+    pub(crate) fn set_combiner_label(&mut self, label: &[u8]) -> Result<()> {
+        let info = self
+            .kem_info
+            .combiner
+            .as_mut()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        info.label = label.to_vec();
+        self.combiner = KdfCombiner::from_info(info)?;
+        Ok(())
+    }
+
+    /// Combine the shared secrets of the components, binding them to a context
+    fn combine(
+        &self,
+        ss: [&[u8]; 3],
+        ct_t: [&[u8]; 2],
+        pk_t: [&[u8]; 2],
+        context: &[u8],
+    ) -> Result<Vec<u8>> {
+        let input = CombinerInput {
+            ss_pq: ss[0],
+            ss_t: &[ss[1], ss[2]].concat(),
+            ct_pq: &[],
+            ct_t: &ct_t.concat(),
+            pk_pq: &[],
+            pk_t: &pk_t.concat(),
+        };
+        self.combiner.combine_with_context(&input, context)
+    }
+}
+
+impl Kem for TripleKemManager {
+    /// Create a new KEM instance
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    fn new(kem_type: KemType) -> Result<Self> {
+        let (pq, t1, t2) = match kem_type {
+            KemType::MlKem768X25519P256 => (KemType::MlKem768, KemType::X25519, KemType::P256),
+            KemType::MlKem1024X448P384 => (KemType::MlKem1024, KemType::X448, KemType::P384),
+            _ => return Err(QuantCryptError::NotImplemented),
+        };
+        let kem_info = KemInfo::new(kem_type);
+        let combiner_info = kem_info
+            .combiner
+            .as_ref()
+            .ok_or(QuantCryptError::NotImplemented)?;
+        let manager = TripleKemManager {
+            combiner: KdfCombiner::from_info(combiner_info)?,
+            kem_info,
+            pq_kem: Box::new(KemManager::new(pq)?),
+            trad_kems: [
+                Box::new(KemManager::new(t1)?),
+                Box::new(KemManager::new(t2)?),
+            ],
+        };
+        // Components with variable lengths, e.g. from a registered backend, can't be split
+        Self::get_lens(&manager.pq_kem)?;
+        Self::get_lens(&manager.trad_kems[0])?;
+        Self::get_lens(&manager.trad_kems[1])?;
+        Ok(manager)
+    }
+
+    /// Generate a keypair using the default RNGs of the components
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        let pq = self.pq_kem.key_gen()?;
+        let t1 = self.trad_kems[0].key_gen()?;
+        let t2 = self.trad_kems[1].key_gen()?;
+        Ok(Self::encode_keys(pq, t1, t2))
+    }
+
+    /// Generate a keypair
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        let pq = self.pq_kem.key_gen_with_rng(rng)?;
+        let t1 = self.trad_kems[0].key_gen_with_rng(rng)?;
+        let t2 = self.trad_kems[1].key_gen_with_rng(rng)?;
+        Ok(Self::encode_keys(pq, t1, t2))
+    }
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.encap_with_context(pk, &[])
+    }
+
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `context` - The context mixed into the combiner, e.g. the protocol transcript
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_pq_len, _, _) = Self::get_lens(&self.pq_kem)?;
+        let (pk_t1_len, _, _) = Self::get_lens(&self.trad_kems[0])?;
+        let (pk_t2_len, _, _) = Self::get_lens(&self.trad_kems[1])?;
+        let pks = Self::split(pk, &[pk_pq_len, pk_t1_len, pk_t2_len])
+            .ok_or(QuantCryptError::InvalidPublicKey)?;
+
+        let (ss_pq, ct_pq) = self.pq_kem.encap(pks[0])?;
+        let (ss_t1, ct_t1) = self.trad_kems[0].encap(pks[1])?;
+        let (ss_t2, ct_t2) = self.trad_kems[1].encap(pks[2])?;
+
+        let ss = self.combine(
+            [&ss_pq, &ss_t1, &ss_t2],
+            [&ct_t1, &ct_t2],
+            [pks[1], pks[2]],
+            context,
+        )?;
+        Ok((ss, [ct_pq, ct_t1, ct_t2].concat()))
+    }
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        self.decap_with_context(sk, ct, &[])
+    }
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `context` - The context given to `encap_with_context`
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+        let (_, sk_pq_len, ct_pq_len) = Self::get_lens(&self.pq_kem)?;
+        let (pk_t1_len, sk_t1_len, ct_t1_len) = Self::get_lens(&self.trad_kems[0])?;
+        let (pk_t2_len, sk_t2_len, ct_t2_len) = Self::get_lens(&self.trad_kems[1])?;
+
+        let cts = Self::split(ct, &[ct_pq_len, ct_t1_len, ct_t2_len])
+            .ok_or(QuantCryptError::InvalidCiphertext)?;
+        let sks = Self::split(sk, &[sk_pq_len, sk_t1_len, sk_t2_len, pk_t1_len, pk_t2_len])
+            .ok_or(QuantCryptError::InvalidPrivateKey)?;
+
+        let ss_pq = self.pq_kem.decap(sks[0], cts[0])?;
+        let ss_t1 = self.trad_kems[0].decap(sks[1], cts[1])?;
+        let ss_t2 = self.trad_kems[1].decap(sks[2], cts[2])?;
+
+        self.combine(
+            [&ss_pq, &ss_t1, &ss_t2],
+            [cts[1], cts[2]],
+            [sks[3], sks[4]],
+            context,
+        )
+    }
+
+    /// Get KEM metadata information such as the key lengths
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem_info.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_kem;
+
+    #[test]
+    fn test_mlkem_768_x25519_p256() {
+        let kem = TripleKemManager::new(KemType::MlKem768X25519P256);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_mlkem_1024_x448_p384() {
+        let kem = TripleKemManager::new(KemType::MlKem1024X448P384);
+        test_kem!(kem);
+
+        // Every traditional component is bound to the shared secret
+        let mut kem = TripleKemManager::new(KemType::MlKem1024X448P384).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        for offset in [1568, 1568 + 56] {
+            let mut tampered = ct.clone();
+            tampered[offset + 1] ^= 1;
+            assert_ne!(kem.decap(&sk, &tampered).ok(), Some(ss.clone()));
+        }
+        assert!(kem.decap(&sk, &ct[1..]).is_err());
+        assert!(kem.encap(&pk[1..]).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use ml_kem::MlKem768;
+use rand_core::CryptoRngCore;
+
+use crate::kem::common::kem_info::KemInfo;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::ec_kem::EcKemManager;
+use crate::kem::kyber::{decapsulate_r3, encapsulate_r3};
+use crate::kem::ml_kem::MlKemManager;
+use crate::utils::entropy::{ManagerRng, SharedRng};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The length of an X25519 public key, secret key and ciphertext
+const X25519_LEN: usize = 32;
+
+// Implement clone
+#[derive(Clone)]
+/// A KEM manager for the pre-standard X25519Kyber768Draft00 hybrid
+/// (draft-tls-westerbaan-xyber768d00), TLS group 0x6399
+///
+/// This is a legacy construction for talking to early hybrid TLS deployments.
+/// It uses Kyber768 as submitted to round 3 of the NIST process rather than
+/// ML-KEM, and concatenates the shared secrets without a combiner. Keys and
+/// ciphertexts are concatenations, X25519 first:
+///
+/// * pk = pk_X25519 || pk_Kyber768 (1216 bytes)
+/// * sk = sk_X25519 || sk_Kyber768 (2432 bytes)
+/// * ct = ct_X25519 || ct_Kyber768 (1120 bytes)
+/// * ss = ss_X25519 || ss_Kyber768 (64 bytes)
+pub struct X25519Kyber768Draft00Manager {
+    kem_info: KemInfo,
+    x25519: EcKemManager,
+    kyber: MlKemManager,
+    rng: Option<SharedRng>,
+}
+
+impl X25519Kyber768Draft00Manager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager and its components from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.x25519.set_rng(rng.clone());
+        self.kyber.set_rng(rng.clone());
+        self.rng = Some(rng);
+    }
+
+    /// Split a value into its X25519 and Kyber768 parts
+    fn split(value: &[u8], kyber_len: usize) -> Option<(&[u8], &[u8])> {
+        if value.len() != X25519_LEN + kyber_len {
+            return None;
+        }
+        Some(value.split_at(X25519_LEN))
+    }
+}
+
+impl Kem for X25519Kyber768Draft00Manager {
+    /// Create a new KEM instance
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    fn new(kem_type: KemType) -> Result<Self> {
+        if kem_type != KemType::X25519Kyber768Draft00 {
+            return Err(QuantCryptError::NotImplemented);
+        }
+        Ok(X25519Kyber768Draft00Manager {
+            kem_info: KemInfo::new(kem_type),
+            x25519: EcKemManager::new(KemType::X25519)?,
+            // Kyber768 keys are encoded as ML-KEM-768 keys, and a key generated
+            // from a random seed is valid for both
+            kyber: MlKemManager::new(KemType::MlKem768)?,
+            rng: None,
+        })
+    }
+
+    /// Generate a keypair using the default RNGs of the components
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_x, sk_x) = self.x25519.key_gen()?;
+        let (pk_k, sk_k) = self.kyber.key_gen()?;
+        Ok(([pk_x, pk_k].concat(), [sk_x, sk_k].concat()))
+    }
+
+    /// Generate a keypair
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    fn key_gen_with_rng(&mut self, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_x, sk_x) = self.x25519.key_gen_with_rng(rng)?;
+        let (pk_k, sk_k) = self.kyber.key_gen_with_rng(rng)?;
+        Ok(([pk_x, pk_k].concat(), [sk_x, sk_k].concat()))
+    }
+
+    /// Encapsulate a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_x, pk_k) = Self::split(pk, 1184).ok_or(QuantCryptError::InvalidPublicKey)?;
+        let (ss_x, ct_x) = self.x25519.encap(pk_x)?;
+        let mut rng = ManagerRng::for_operation(self.rng.as_ref());
+        let (ss_k, ct_k) = encapsulate_r3::<MlKem768>(pk_k, &mut rng)?;
+        Ok(([ss_x, ss_k].concat(), [ct_x, ct_k].concat()))
+    }
+
+    /// Decapsulate a ciphertext
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The secret key to decapsulate with
+    /// * `ct` - The ciphertext to decapsulate
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        let (sk_x, sk_k) = Self::split(sk, 2400).ok_or(QuantCryptError::InvalidPrivateKey)?;
+        let (ct_x, ct_k) = Self::split(ct, 1088).ok_or(QuantCryptError::InvalidCiphertext)?;
+        let ss_x = self.x25519.decap(sk_x, ct_x)?;
+        let ss_k = decapsulate_r3::<MlKem768>(sk_k, ct_k)?;
+        Ok([ss_x, ss_k].concat())
+    }
+
+    /// Get KEM metadata information such as the key lengths
+    ///
+    /// # Returns
+    ///
+    /// A structure containing metadata about the KEM
+    fn get_kem_info(&self) -> KemInfo {
+        self.kem_info.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_kem;
+    use crate::utils::kat::RspFile;
+
+    #[test]
+    fn test_x25519_kyber768_draft00() {
+        let kem = X25519Kyber768Draft00Manager::new(KemType::X25519Kyber768Draft00);
+        test_kem!(kem);
+
+        // The Kyber768 secret differs from the one ML-KEM-768 would derive
+        let mut kem = X25519Kyber768Draft00Manager::new(KemType::X25519Kyber768Draft00).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        assert_eq!(ss.len(), 64);
+        let ml_kem_ss = kem.kyber.decap(&sk[32..], &ct[32..]).unwrap();
+        assert_ne!(ss[32..], ml_kem_ss[..]);
+        assert!(kem.decap(&sk, &ct[1..]).is_err());
+    }
+
+    #[test]
+    fn test_x25519_kyber768_draft00_x25519_first() {
+        // RFC 7748 § 6.1, Alice's secret key and Bob's public key
+        let sk_x = hex::decode("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")
+            .unwrap();
+        let ct_x = hex::decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+            .unwrap();
+        let ss_x = hex::decode("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742")
+            .unwrap();
+
+        let mut kem = X25519Kyber768Draft00Manager::new(KemType::X25519Kyber768Draft00).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (_, ct) = kem.encap(&pk).unwrap();
+        let sk = [sk_x, sk[32..].to_vec()].concat();
+        let ct = [ct_x, ct[32..].to_vec()].concat();
+        assert_eq!(kem.decap(&sk, &ct).unwrap()[..32], ss_x[..]);
+    }
+
+    #[test]
+    fn test_x25519_kyber768_draft00_vector() {
+        // Composed from official vectors of the components, see test/data/kat/README.md
+        let rsp = RspFile::from_file("test/data/kat/x25519kyber768draft00.rsp").unwrap();
+        assert!(!rsp.get_records().is_empty());
+        let kem = X25519Kyber768Draft00Manager::new(KemType::X25519Kyber768Draft00).unwrap();
+        for record in rsp.get_records() {
+            let ss = kem
+                .decap(
+                    &record.get_bytes("sk").unwrap(),
+                    &record.get_bytes("ct").unwrap(),
+                )
+                .unwrap();
+            assert_eq!(ss, record.get_bytes("ss").unwrap());
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use ml_kem::B32;
-use openssl::pkey::Id;
-use sha2::Digest;
+use rand_core::RngCore;
+use sha3::{Digest, Sha3_256};
+use zeroize::Zeroizing;
 
 use crate::kdf::common::kdf_trait::Kdf;
 use crate::kdf::sha3::Sha3Kdf;
 use crate::kdfs::KdfType;
-use crate::kem::common::kem_info::KemInfo;
+use crate::kem::api::key_encoding;
+use crate::kem::common::combined_kem::{Combiner, CombinerInput, KdfCombiner};
+use crate::kem::common::kem_info::{KemInfo, KemLengths};
 use crate::kem::common::kem_trait::Kem;
 use crate::kem::common::kem_type::KemType;
-use crate::utils::openssl_utils;
+use crate::utils::entropy::{ManagerRng, SharedRng};
 use crate::QuantCryptError;
 
 use crate::kem::ec_kem::EcKemManager;
@@ -16,18 +19,105 @@ use crate::kem::ml_kem::MlKemManager;
 
 type Result<T> = std::result::Result<T, QuantCryptError>;
 
+/// The length of the seed form of the decapsulation key
+const SEED_LEN: usize = 32;
+
+/// The length of the ML-KEM seed d || z expanded from the decapsulation key
+const ML_KEM_SEED_LEN: usize = 64;
+
+/// The length of the ML-KEM message m at the start of the encapsulation seed
+const ML_KEM_COINS_LEN: usize = 32;
+
+/// The length of the expanded form of the X-Wing decapsulation key, sk_M || sk_X || pk_M || pk_X
+pub const XWING_EXPANDED_SK_LEN: usize = 2400 + 32 + 1184 + 32;
+
+// Implement clone
+#[derive(Clone)]
 /// A KEM manager for the Xwing method
+///
+/// Besides X-Wing, it implements `KemType::MlKem1024X448Shake`, the same
+/// construction with ML-KEM-1024 and X448 for NIST security category 5. It
+/// keeps the 32 byte decapsulation key, expanded with SHAKE256 into the ML-KEM
+/// seed and the 56 byte X448 secret key, and combines the shared secrets with
+/// SHA3-256 and its own label.
 pub struct XWingKemManager {
     kem_info: KemInfo,
     ml_kem: MlKemManager,
     ec_kem: EcKemManager,
+    ml_kem_lengths: KemLengths,
+    ec_kem_lengths: KemLengths,
+    combiner: KdfCombiner,
     shake: Sha3Kdf,
+    rng: Option<SharedRng>,
+}
+
+/// Split a concatenation of an ML-KEM value and a traditional KEM value
+///
+/// # Arguments
+///
+/// * `bytes` - The concatenation
+/// * `first` - The length of the ML-KEM value
+/// * `second` - The length of the traditional KEM value
+///
+/// # Returns
+///
+/// The two values, or None if the concatenation has the wrong length
+fn split_components(
+    bytes: &[u8],
+    first: Option<usize>,
+    second: Option<usize>,
+) -> Option<(&[u8], &[u8])> {
+    let first = first?;
+    (bytes.len() == first + second?).then(|| bytes.split_at(first))
 }
 
 impl XWingKemManager {
+    /// Create a new KEM instance drawing all its randomness from a caller-supplied RNG
+    ///
+    /// # Arguments
+    ///
+    /// * `kem_type` - The type of KEM to create
+    /// * `rng` - The RNG used by key generation and encapsulation
+    ///
+    /// # Returns
+    ///
+    /// A new KEM instance
+    pub fn new_with_rng(kem_type: KemType, rng: SharedRng) -> Result<Self> {
+        let mut kem = Self::new(kem_type)?;
+        kem.set_rng(rng);
+        Ok(kem)
+    }
+
+    /// Draw all the randomness of the manager from a caller-supplied RNG
+    pub(crate) fn set_rng(&mut self, rng: SharedRng) {
+        self.ml_kem.set_rng(rng.clone());
+        self.ec_kem.set_rng(rng.clone());
+        self.rng = Some(rng);
+    }
+
+    /// Get the length of the traditional secret key, e.g. 32 for X25519
+    fn ec_sk_len(&self) -> usize {
+        self.ec_kem_lengths.sk.unwrap_or(0)
+    }
+
+    /// Get the length of the expanded form of the decapsulation key
+    fn expanded_sk_len(&self) -> usize {
+        [
+            self.ml_kem_lengths.sk,
+            self.ec_kem_lengths.sk,
+            self.ml_kem_lengths.pk,
+            self.ec_kem_lengths.pk,
+        ]
+        .iter()
+        .map(|len| len.unwrap_or(0))
+        .sum()
+    }
+
     #[allow(clippy::type_complexity)]
     fn expand_decapsulation_key(&self, sk: &[u8]) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>)> {
-        let expanded = self.shake.derive(sk, &[], 96, None)?;
+        let expanded = self
+            .shake
+            .derive(sk, &[], ML_KEM_SEED_LEN + self.ec_sk_len(), None)?;
         let d: B32 = expanded[0..32]
             .try_into()
             .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
@@ -35,37 +125,219 @@ impl XWingKemManager {
             .try_into()
             .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
         let (pk_m, sk_m) = self.ml_kem.key_gen_deterministic(&d, &z)?;
-        let sk_x = expanded[64..96].to_vec();
-        let pk_x = openssl_utils::get_pk_from_sk_pkey_based(&sk_x, Id::X25519)
-            .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+        let sk_x = expanded[ML_KEM_SEED_LEN..].to_vec();
+        let pk_x = self.ec_kem.get_public_key(&sk_x)?;
 
         Ok((sk_m, sk_x, pk_m, pk_x))
     }
 
-    fn combiner(&self, ss_m: &[u8], ss_x: &[u8], ct_x: &[u8], pk_x: &[u8]) -> Result<Vec<u8>> {
-        /*
-         * The XWing KEM uses the following label as the equivalent of a domain
-         * separator string for composite KEMs. The label is defined as:
-         *
-         * \./
-         * /^\
-         *
-         */
-        let xw1 = b"\\./";
-        let xw2 = b"/^\\";
-        let xwing_label = [&xw1[..], &xw2[..]].concat();
-        let mut info = vec![];
-        info.extend_from_slice(ss_m);
-        info.extend_from_slice(ss_x);
-        info.extend_from_slice(ct_x);
-        info.extend_from_slice(pk_x);
-        info.extend_from_slice(&xwing_label.to_vec());
-
-        // Get the SHA3-256 hash of the info
-        let mut sha3 = sha3::Sha3_256::default();
-        sha3.update(&info);
-        let result = sha3.finalize_reset();
-        Ok(result.to_vec())
+    /// Split a decapsulation key in either form into (sk_M, sk_X, pk_M, pk_X)
+    #[allow(clippy::type_complexity)]
+    fn get_key_parts(&self, sk: &[u8]) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>)> {
+        match sk.len() {
+            SEED_LEN => self.expand_decapsulation_key(sk),
+            len if len == self.expanded_sk_len() => {
+                let (sk, pk) = sk.split_at(sk.len() - self.kem_info.pk_byte_len.unwrap_or(0));
+                let (sk_m, sk_x) =
+                    split_components(sk, self.ml_kem_lengths.sk, self.ec_kem_lengths.sk)
+                        .ok_or(QuantCryptError::InvalidPrivateKey)?;
+                let (pk_m, pk_x) = self.split_pk(pk)?;
+                Ok((sk_m.to_vec(), sk_x.to_vec(), pk_m.to_vec(), pk_x.to_vec()))
+            }
+            _ => Err(QuantCryptError::InvalidPrivateKey),
+        }
+    }
+
+    /// Split an encapsulation key into (pk_M, pk_X)
+    fn split_pk<'b>(&self, pk: &'b [u8]) -> Result<(&'b [u8], &'b [u8])> {
+        split_components(pk, self.ml_kem_lengths.pk, self.ec_kem_lengths.pk)
+            .ok_or(QuantCryptError::InvalidPublicKey)
+    }
+
+    /// Export the expanded form of a decapsulation key, sk_M || sk_X || pk_M || pk_X
+    ///
+    /// Some implementations only ship this form (3648 bytes for X-Wing) rather
+    /// than the 32 byte seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The decapsulation key (seed)
+    ///
+    /// # Returns
+    ///
+    /// The expanded decapsulation key
+    pub fn export_expanded_key(&self, sk: &[u8]) -> Result<Vec<u8>> {
+        if sk.len() != SEED_LEN {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let (sk_m, sk_x, pk_m, pk_x) = self.expand_decapsulation_key(sk)?;
+        Ok([sk_m, sk_x, pk_m, pk_x].concat())
+    }
+
+    /// Import an expanded decapsulation key, sk_M || sk_X || pk_M || pk_X
+    ///
+    /// The seed can't be recovered from the expanded form, but `decap` accepts
+    /// either form, so the key can be used as is after it is checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `expanded` - The expanded decapsulation key
+    ///
+    /// # Returns
+    ///
+    /// The encapsulation key (pk)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` if the key has the wrong length or its
+    /// public keys don't match its secret keys
+    pub fn import_expanded_key(&self, expanded: &[u8]) -> Result<Vec<u8>> {
+        if expanded.len() != self.expanded_sk_len() {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let (sk_m, sk_x, pk_m, pk_x) = self.get_key_parts(expanded)?;
+
+        // The ML-KEM decapsulation key ends with its encapsulation key, its hash and z
+        let (embedded_pk_m, rest) = sk_m[sk_m.len() - pk_m.len() - 64..].split_at(pk_m.len());
+        if embedded_pk_m != pk_m.as_slice() || rest[..32] != Sha3_256::digest(&pk_m)[..] {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        if self.ec_kem.get_public_key(&sk_x)? != pk_x {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        Ok([pk_m, pk_x].concat())
+    }
+
+    /// Compute the key pair for a 32 byte decapsulation key
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The decapsulation key (seed)
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the public and secret keys (pk, sk)
+    pub fn key_gen_deterministic(&self, sk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if sk.len() != SEED_LEN {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        let (_, _, pk_m, pk_x) = self.expand_decapsulation_key(sk)?;
+        Ok(([pk_m.as_slice(), pk_x.as_slice()].concat(), sk.to_vec()))
+    }
+
+    /// Get the OID identifying the keys in SPKI and PKCS#8
+    fn key_oid(&self) -> Result<&str> {
+        self.kem_info
+            .oid
+            .as_deref()
+            .ok_or(QuantCryptError::NotImplemented)
+    }
+
+    /// Encode an encapsulation key as a DER SubjectPublicKeyInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The encapsulation key
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded SubjectPublicKeyInfo
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM has no assigned OID
+    pub fn public_key_to_der(&self, pk: &[u8]) -> Result<Vec<u8>> {
+        self.split_pk(pk)?;
+        key_encoding::encode_spki(self.key_oid()?, pk)
+    }
+
+    /// Decode an encapsulation key from a DER SubjectPublicKeyInfo
+    ///
+    /// # Arguments
+    ///
+    /// * `der` - The DER encoded SubjectPublicKeyInfo
+    ///
+    /// # Returns
+    ///
+    /// The encapsulation key
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPublicKey` if the encoding is invalid, or the OID
+    /// or the length of the key don't match the KEM
+    pub fn public_key_from_der(&self, der: &[u8]) -> Result<Vec<u8>> {
+        let (oid, pk) = key_encoding::decode_spki(der)?;
+        if oid != self.key_oid()? {
+            return Err(QuantCryptError::InvalidPublicKey);
+        }
+        self.split_pk(&pk)?;
+        Ok(pk)
+    }
+
+    /// Encode an encapsulation key as a PEM SubjectPublicKeyInfo, labelled "PUBLIC KEY"
+    pub fn public_key_to_pem(&self, pk: &[u8]) -> Result<String> {
+        Ok(key_encoding::spki_to_pem(&self.public_key_to_der(pk)?))
+    }
+
+    /// Decode an encapsulation key from a PEM SubjectPublicKeyInfo, labelled "PUBLIC KEY"
+    pub fn public_key_from_pem(&self, pem: &str) -> Result<Vec<u8>> {
+        self.public_key_from_der(&key_encoding::spki_from_pem(pem)?)
+    }
+
+    /// Encode a decapsulation key as a DER PKCS#8 OneAsymmetricKey
+    ///
+    /// The privateKey holds the 32 byte seed form of the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The decapsulation key (seed)
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded OneAsymmetricKey
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::NotImplemented` if the KEM has no assigned OID
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` if the key isn't in the seed form
+    pub fn private_key_to_der(&self, sk: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        if sk.len() != SEED_LEN {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        key_encoding::encode_pkcs8(self.key_oid()?, sk)
+    }
+
+    /// Decode a decapsulation key from a DER PKCS#8 OneAsymmetricKey
+    ///
+    /// # Arguments
+    ///
+    /// * `der` - The DER encoded OneAsymmetricKey
+    ///
+    /// # Returns
+    ///
+    /// The decapsulation key (seed)
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidPrivateKey` if the encoding is invalid, or the OID
+    /// or the length of the key don't match the KEM
+    pub fn private_key_from_der(&self, der: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        let (oid, sk) = key_encoding::decode_pkcs8(der)?;
+        if oid != self.key_oid()? || sk.len() != SEED_LEN {
+            return Err(QuantCryptError::InvalidPrivateKey);
+        }
+        Ok(sk)
+    }
+
+    /// Encode a decapsulation key as a PEM PKCS#8 OneAsymmetricKey, labelled "PRIVATE KEY"
+    pub fn private_key_to_pem(&self, sk: &[u8]) -> Result<Zeroizing<String>> {
+        Ok(key_encoding::pkcs8_to_pem(&self.private_key_to_der(sk)?))
+    }
+
+    /// Decode a decapsulation key from a PEM PKCS#8 OneAsymmetricKey, labelled "PRIVATE KEY"
+    pub fn private_key_from_pem(&self, pem: &str) -> Result<Zeroizing<Vec<u8>>> {
+        self.private_key_from_der(&key_encoding::pkcs8_from_pem(pem)?)
     }
 }
 
@@ -74,15 +346,32 @@ impl Kem for XWingKemManager {
     where
         Self: Sized,
     {
+        let (ml_kem_type, ec_kem_type) = match kem_type {
+            KemType::XWing => (KemType::MlKem768, KemType::X25519),
+            KemType::MlKem1024X448Shake => (KemType::MlKem1024, KemType::X448),
+            _ => return Err(QuantCryptError::NotImplemented),
+        };
         let kem_info = KemInfo::new(kem_type);
-        let ml_kem = MlKemManager::new(KemType::MlKem768)?;
-        let ec_kem = EcKemManager::new(KemType::X25519)?;
+        let combiner = KdfCombiner::from_info(
+            kem_info
+                .combiner
+                .as_ref()
+                .ok_or(QuantCryptError::NotImplemented)?,
+        )?;
+        let ml_kem = MlKemManager::new(ml_kem_type)?;
+        let ec_kem = EcKemManager::new(ec_kem_type)?;
+        let ml_kem_lengths = ml_kem.get_kem_info().lengths();
+        let ec_kem_lengths = ec_kem.get_kem_info().lengths();
         let shake = Sha3Kdf::new(KdfType::Shake256)?;
         Ok(XWingKemManager {
             kem_info,
             ml_kem,
             ec_kem,
+            ml_kem_lengths,
+            ec_kem_lengths,
+            combiner,
             shake,
+            rng: None,
         })
     }
 
@@ -91,9 +380,9 @@ impl Kem for XWingKemManager {
     }
 
     fn key_gen(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-        // Use OpenSSL to generate 32 bytes of random data
-        let mut sk = vec![0u8; 32];
-        openssl::rand::rand_bytes(&mut sk).map_err(|_| QuantCryptError::KeyPairGenerationFailed)?;
+        // Generate 32 bytes of random data from the RNG of the manager or the health tested entropy
+        let mut sk = vec![0u8; SEED_LEN];
+        ManagerRng::for_key_gen(self.rng.as_ref())?.fill_bytes(&mut sk);
 
         // Expand the secret key
         let (_, _, pk_m, pk_x) = self.expand_decapsulation_key(&sk)?;
@@ -101,9 +390,6 @@ impl Kem for XWingKemManager {
         // Concatentate the public keys
         let pk = [pk_m.as_slice(), pk_x.as_slice()].concat();
 
-        // returns the 32 byte secret decapsulation key sk and
-        // the 1216 byte encapsulation key pk
-
         Ok((pk, sk))
     }
 
@@ -112,7 +398,7 @@ impl Kem for XWingKemManager {
         rng: &mut impl rand_core::CryptoRngCore,
     ) -> Result<(Vec<u8>, Vec<u8>)> {
         // Use the provided RNG to generate 32 bytes of random data
-        let mut sk = vec![0u8; 32];
+        let mut sk = vec![0u8; SEED_LEN];
         rng.fill_bytes(&mut sk);
 
         // Expand the secret key
@@ -121,43 +407,110 @@ impl Kem for XWingKemManager {
         // Concatentate the public keys
         let pk = [pk_m.as_slice(), pk_x.as_slice()].concat();
 
-        // returns the 32 byte secret decapsulation key sk and
-        // the 1216 byte encapsulation key pk
-
         Ok((pk, sk))
     }
 
     fn encap(&mut self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
-        if pk.len() != 1216 {
-            return Err(QuantCryptError::InvalidPublicKey);
-        }
-        let pk_m = &pk[0..1184];
-        let pk_x = &pk[1184..1216];
+        self.encap_with_context(pk, &[])
+    }
+
+    /// Encapsulate a public key, binding the shared secret to a context
+    ///
+    /// The context is appended to the input of SHA3-256, after the label, so an
+    /// empty context derives the X-Wing shared secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The public key to encapsulate
+    /// * `context` - The context to bind the shared secret to, e.g. the protocol transcript
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing the shared secret and ciphertext (ss, ct)
+    fn encap_with_context(&mut self, pk: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_m, pk_x) = self.split_pk(pk)?;
 
         let (ss_x, ct_x) = self.ec_kem.encap(pk_x)?;
         let (ss_m, ct_m) = self.ml_kem.encap(pk_m)?;
 
-        let ss = self.combiner(&ss_m, &ss_x, &ct_x, pk_x)?;
+        let input = CombinerInput {
+            ss_pq: &ss_m,
+            ss_t: &ss_x,
+            ct_pq: &ct_m,
+            ct_t: &ct_x,
+            pk_pq: pk_m,
+            pk_t: pk_x,
+        };
+        let ss = self.combiner.combine_with_context(&input, context)?;
         let ct = [ct_m.as_slice(), ct_x.as_slice()].concat();
 
         Ok((ss, ct))
     }
 
-    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
-        let (sk_m, sk_x, _pk_m, pk_x) = self.expand_decapsulation_key(sk)?;
-        if ct.len() != 1120 {
-            return Err(QuantCryptError::InvalidCiphertext);
+    /// Encapsulate a public key with the 64 byte `eseed` of the X-Wing draft
+    ///
+    /// The first 32 bytes are the ML-KEM-768 message and the last 32 bytes
+    /// the ephemeral X25519 secret key. For `MlKem1024X448Shake`, the seed is
+    /// 88 bytes, ending with the 56 byte ephemeral X448 secret key.
+    fn encap_deterministic(&mut self, pk: &[u8], coins: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let (pk_m, pk_x) = self.split_pk(pk)?;
+        let expected = ML_KEM_COINS_LEN + self.ec_sk_len();
+        if coins.len() != expected {
+            return Err(QuantCryptError::InvalidCoinsLength {
+                expected,
+                actual: coins.len(),
+            });
         }
+        let (coins_m, coins_x) = coins.split_at(ML_KEM_COINS_LEN);
+        let (ss_m, ct_m) = self.ml_kem.encap_deterministic(pk_m, coins_m)?;
+        let (ss_x, ct_x) = self.ec_kem.encap_deterministic(pk_x, coins_x)?;
+
+        let ss = self.combiner.combine(&CombinerInput {
+            ss_pq: &ss_m,
+            ss_t: &ss_x,
+            ct_pq: &ct_m,
+            ct_t: &ct_x,
+            pk_pq: pk_m,
+            pk_t: pk_x,
+        })?;
+        let ct = [ct_m.as_slice(), ct_x.as_slice()].concat();
 
-        let ct_m = &ct[0..1088];
-        let ct_x = &ct[1088..1120];
+        Ok((ss, ct))
+    }
+
+    /// Decapsulate a ciphertext with a decapsulation key in seed or expanded form
+    fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+        self.decap_with_context(sk, ct, &[])
+    }
+
+    /// Decapsulate a ciphertext, binding the shared secret to a context
+    ///
+    /// # Arguments
+    ///
+    /// * `sk` - The decapsulation key, in seed or expanded form
+    /// * `ct` - The ciphertext to decapsulate
+    /// * `context` - The context given to `encap_with_context`
+    ///
+    /// # Returns
+    ///
+    /// The shared secret (ss)
+    fn decap_with_context(&self, sk: &[u8], ct: &[u8], context: &[u8]) -> Result<Vec<u8>> {
+        let (sk_m, sk_x, pk_m, pk_x) = self.get_key_parts(sk)?;
+        let (ct_m, ct_x) = split_components(ct, self.ml_kem_lengths.ct, self.ec_kem_lengths.ct)
+            .ok_or(QuantCryptError::InvalidCiphertext)?;
 
         let ss_m = self.ml_kem.decap(&sk_m, ct_m)?;
         let ss_x = self.ec_kem.decap(&sk_x, ct_x)?;
 
-        let ss = self.combiner(&ss_m, &ss_x, ct_x, &pk_x)?;
-
-        Ok(ss)
+        let input = CombinerInput {
+            ss_pq: &ss_m,
+            ss_t: &ss_x,
+            ct_pq: ct_m,
+            ct_t: ct_x,
+            pk_pq: &pk_m,
+            pk_t: &pk_x,
+        };
+        self.combiner.combine_with_context(&input, context)
     }
 }
 
@@ -166,7 +519,7 @@ mod tests {
     use super::*;
     use crate::kem::common::kem_trait::Kem;
     use crate::kem::common::kem_type::KemType;
-    use crate::kem::common::macros::test_kem;
+    use crate::test_kem;
 
     #[test]
     fn test_xwing() {
@@ -174,6 +527,101 @@ mod tests {
         test_kem!(kem);
     }
 
+    #[test]
+    fn test_mlkem1024_x448_shake() {
+        let kem = XWingKemManager::new(KemType::MlKem1024X448Shake);
+        test_kem!(kem);
+    }
+
+    #[test]
+    fn test_mlkem1024_x448_shake_vectors() {
+        // Regression vectors generated by this implementation, as no external
+        // vectors have been published for this combination
+        let mut kem = XWingKemManager::new(KemType::MlKem1024X448Shake).unwrap();
+        let sk: Vec<u8> = (0..32).collect();
+        let eseed: Vec<u8> = (32..120).collect();
+        let (pk, _) = kem.key_gen_deterministic(&sk).unwrap();
+        assert_eq!(pk.len(), 1624);
+        assert_eq!(
+            hex::encode(Sha3_256::digest(&pk)),
+            "5d7f76c535a0a27c8b4bf8dfc2125f75343cbf6db46070089e6db52f8cdca684"
+        );
+
+        let (ss, ct) = kem.encap_deterministic(&pk, &eseed).unwrap();
+        assert_eq!(
+            hex::encode(Sha3_256::digest(&ct)),
+            "b7781f07eb412302e210143fdbce66db0e7e67fb8f3fc89fe927f579ab8c81b6"
+        );
+        assert_eq!(
+            hex::encode(&ss),
+            "5f42276c7f5dcbf95adad222caa3f0b3c2e9e744fc68d495dc38553f024a3d8e"
+        );
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        // The 64 byte eseed of X-Wing is too short for X448
+        assert!(kem.encap_deterministic(&pk, &eseed[..64]).is_err());
+
+        let expanded = kem.export_expanded_key(&sk).unwrap();
+        assert_eq!(expanded.len(), 3168 + 56 + 1568 + 56);
+        assert_eq!(kem.import_expanded_key(&expanded).unwrap(), pk);
+        assert_eq!(kem.decap(&expanded, &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn test_xwing_expanded_key() {
+        let mut kem = XWingKemManager::new(KemType::XWing).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+        let expanded = kem.export_expanded_key(&sk).unwrap();
+        assert_eq!(expanded.len(), XWING_EXPANDED_SK_LEN);
+        assert_eq!(expanded[2432..], pk[..]);
+        assert_eq!(kem.import_expanded_key(&expanded).unwrap(), pk);
+
+        // Both forms decapsulate
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        assert_eq!(kem.decap(&expanded, &ct).unwrap(), ss);
+
+        // Inconsistent keys are rejected
+        for offset in [1152 + 1, 2400 + 1, 2432 + 1, XWING_EXPANDED_SK_LEN - 1] {
+            let mut tampered = expanded.clone();
+            tampered[offset] ^= 1;
+            assert!(kem.import_expanded_key(&tampered).is_err());
+        }
+        assert!(kem.import_expanded_key(&expanded[1..]).is_err());
+        assert!(kem.export_expanded_key(&expanded).is_err());
+    }
+
+    #[test]
+    fn test_xwing_key_encoding() {
+        let mut kem = XWingKemManager::new(KemType::XWing).unwrap();
+        let (pk, sk) = kem.key_gen().unwrap();
+
+        let der = kem.public_key_to_der(&pk).unwrap();
+        let (oid, _) = key_encoding::decode_spki(&der).unwrap();
+        assert_eq!(oid, "1.3.6.1.4.1.62253.25722");
+        assert_eq!(kem.public_key_from_der(&der).unwrap(), pk);
+        let pem = kem.public_key_to_pem(&pk).unwrap();
+        assert_eq!(kem.public_key_from_pem(&pem).unwrap(), pk);
+
+        let der = kem.private_key_to_der(&sk).unwrap();
+        assert_eq!(*kem.private_key_from_der(&der).unwrap(), sk);
+        let pem = kem.private_key_to_pem(&sk).unwrap();
+        assert_eq!(*kem.private_key_from_pem(&pem).unwrap(), sk);
+
+        // Truncated keys, another OID and the expanded form are rejected
+        assert!(kem.public_key_to_der(&pk[1..]).is_err());
+        let der = key_encoding::encode_spki("1.2.3.4", &pk).unwrap();
+        assert!(kem.public_key_from_der(&der).is_err());
+        let expanded = kem.export_expanded_key(&sk).unwrap();
+        assert!(kem.private_key_to_der(&expanded).is_err());
+
+        // ML-KEM-1024 + X448 has no assigned OID
+        let kem = XWingKemManager::new(KemType::MlKem1024X448Shake).unwrap();
+        assert_eq!(
+            kem.private_key_to_der(&sk),
+            Err(QuantCryptError::NotImplemented)
+        );
+    }
+
     #[test]
     fn test_xwing_vectors() {
         // Test vectors from the XWing KEM specification
@@ -185,10 +633,25 @@ mod tests {
         let ss = hex::decode("d2df0522128f09dd8e2c92b1e905c793d8f57a54c3da25861f10bf4ca613e384")
             .unwrap();
 
-        let kem = XWingKemManager::new(KemType::XWing).unwrap();
+        let mut kem = XWingKemManager::new(KemType::XWing).unwrap();
         let result = kem.decap(&sk, &ct).unwrap();
         assert_eq!(result, ss);
 
+        // Encapsulation with the eseed of the vector
+        let (pk, _) = kem.key_gen_deterministic(&sk).unwrap();
+        let eseed = hex::decode("3cb1eea988004b93103cfb0aeefd2a686e01fa4a58e8a3639ca8a1e3f9ae57e235b8cc873c23dc62b8d260169afa2f75ab916a58d974918835d25e6a435085b2").unwrap();
+        assert_eq!(
+            kem.encap_deterministic(&pk, &eseed).unwrap(),
+            (ss.clone(), ct.clone())
+        );
+        assert!(kem.encap_deterministic(&pk, &eseed[1..]).is_err());
+
+        // An empty context derives the X-Wing shared secret, another one doesn't
+        assert_eq!(kem.decap_with_context(&sk, &ct, &[]).unwrap(), ss);
+        assert_ne!(kem.decap_with_context(&sk, &ct, b"transcript").unwrap(), ss);
+        let (ss, ct) = kem.encap_with_context(&pk, b"transcript").unwrap();
+        assert_eq!(kem.decap_with_context(&sk, &ct, b"transcript").unwrap(), ss);
+
         let sk = hex::decode("badfd6dfaac359a5efbb7bcc4b59d538df9a04302e10c8bc1cbf1a0b3a5120ea")
             .unwrap();
         let _pk = hex::decode("0333285fa253661508c9fb444852caa4061636cb060e69943b431400134ae1fbc02287247cb38068bbb89e6714af10a3fcda6613acc4b5e4b0d6eb960c302a0253b1f507b596f0884d351da89b01c35543214c8e542390b2bc497967961ef10286879c34316e6483b644fc27e8019d73024ba1d1cc83650bb068a5431b33d1221b3d122dc1239010a55cb13782140893f30aca7c09380255a0c621602ffbb6a9db064c1406d12723ab3bbe2950a21fe521b160b30b16724cc359754b4c88342651333ea9412d5137791cf75558ebc5c54c520dd6c622a059f6b332ccebb9f24103e59a297cd69e4a48a3bfe53a5958559e840db5c023f66c10ce23081c2c8261d744799ba078285cfa71ac51f44708d0a6212c3993340724b3ac38f63e82a889a4fc581f6b8353cc6233ac8f5394b6cca292f892360570a3031c90c4da3f02a895677390e60c24684a405f69ccf1a7b95312a47c844a4f9c2c4a37696dc10072a87bf41a2717d45b2a99ce09a4898d5a3f6b67085f9a626646bcf369982d483972b9cd7d244c4f49970f766a22507925eca7df99a491d80c27723e84c7b49b633a46b46785a16a41e02c538251622117364615d9c2cdaa1687a860c18bfc9ce8690efb2a524cb97cdfd1a4ea661fa7d08817998af838679b07c9db8455e2167a67c14d6a347522e89e8971270bec858364b1c1023b82c483cf8a8b76f040fe41c24dec2d49f6376170660605b80383391c4abad1136d874a77ef73b440758b6e7059add20873192e6e372e069c22c5425188e5c240cb3a6e29197ad17e87ec41a813af68531f262a6db25bbdb8a15d2ed9c9f35b9f2063890bd26ef09426f225aa1e6008d31600a29bcdf3b10d0bc72788d35e25f4976b3ca6ac7cbf0b442ae399b225d9714d0638a864bda7018d3b7c793bd2ace6ac68f4284d10977cc029cf203c5698f15a06b162d6c8b4fd40c6af40824f9c6101bb94e9327869ab7efd835dfc805367160d6c8571e3643ac70cbad5b96a1ad99352793f5af71705f95126cb4787392e94d808491a2245064ba5a7a30c066301392a6c315336e10dbc9c2177c7af382765b6c88eeab51588d01d6a95747f3652dc5b5c401a23863c7a0343737c737c99287a40a90896d4594730b552b910d23244684206f0eb842fb9aa316ab182282a75fb72b6806cea4774b822169c386a58773c3edc8229d85905abb87ac228f0f7a2ce9a497bb5325e17a6a82777a997c036c3b862d29c14682ad325a9600872f3913029a1588648ba590a7157809ff740b5138380015c40e9fb90f0311107946f28e5962e21666ad65092a3a60480cd16e61ff7fb5b44b70cf12201878428ef8067fceb1e1dcb49d66c773d312c7e53238cb620e126187009472d41036b702032411dc96cb750631df9d99452e495deb4300df660c8d35f32b424e98c7ed14b12d8ab11a289ac63c50a24d52925950e49ba6bf4c2c38953c92d60b6cd034e575c711ac41bfa66951f62b9392828d7b45aed377ac69c35f1c6b80f388f34e0bb9ce8167eb2bc630382825c396a407e905108081b444ac8a07c2507376a750d18248ee0a81c4318d9a38fc44c3b41e8681f87c34138442659512c41276e1cc8fc4eb66e12727bcb5a9e0e405cdea21538d6ea885ab169050e6b91e1b69f7ed34bcbb48fd4c562a576549f85b528c953926d96ea8a160b8843f1c89c62").unwrap();
@@ -1,14 +1,29 @@
-#![doc = include_str!("../README.md")]
+// Several examples of the README need the AES and signature algorithms of
+// OpenSSL, so they are only run as doctests with the `openssl` feature
+#![cfg_attr(
+    any(feature = "openssl", not(doctest)),
+    doc = include_str!("../README.md")
+)]
 
 mod aead;
 mod asn1;
+mod bundle;
 mod cea;
 mod cms;
 mod dsa;
 mod errors;
+mod escrow;
+mod group;
 mod hash;
+mod hpke;
 mod kdf;
 mod kem;
+mod migration;
+mod paper;
+mod pki;
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
+mod tlog;
 mod utils;
 mod wrap;
 
@@ -16,14 +31,28 @@ pub use errors::QuantCryptError;
 
 /// Dealing with pure/composite certificates
 pub mod certificates {
+    pub use crate::asn1::attestation_evidence::{AttestationEvidence, EvidenceVerifier};
     pub use crate::asn1::cert_builder::CertValidity;
     pub use crate::asn1::cert_builder::CertificateBuilder;
     pub use crate::asn1::cert_builder::Profile;
     pub use crate::asn1::certificate::Certificate;
+    pub use crate::asn1::csr::{CertificateSigningRequest, CsrBuilder};
+    pub use crate::asn1::diff::{diff, Asn1Diff, DiffKind, FieldDiff};
+    pub use crate::asn1::lenient_certificate::{FieldError, LenientCertificate, LenientExtension};
+    pub use crate::asn1::oid_alias::OidAliasTable;
+    pub use crate::pki::downgrade::{detect_downgrade, DowngradeKind, DowngradeReport};
+    pub use crate::pki::issuer::{IssuanceRecord, IssuerContext, MemorySerialStore, SerialStore};
+    pub use crate::pki::path_builder::{get_weakest_signature, PathBuilder, WeakestSignature};
+    pub use crate::pki::pinning::PinSet;
+    pub use crate::pki::tofu::{TofuEvent, TofuRecord, TofuStatus, TofuStore};
+    pub use crate::utils::clock_skew::{get_clock_skew_tolerance, set_clock_skew_tolerance};
+    pub use crate::utils::verification_report::{VerificationCheck, VerificationReport};
 }
 
 /// Dealing with pure/composite keys
 pub mod keys {
+    pub use crate::asn1::key_id::KeyIdMethod;
+    pub use crate::asn1::keygen_attestation::{BuildInfo, KeyGenAttestation};
     pub use crate::asn1::private_key::PrivateKey;
     pub use crate::asn1::public_key::PublicKey;
 }
@@ -31,13 +60,191 @@ pub mod keys {
 /// Defines DSA types and key generation
 pub mod dsas {
     pub use crate::dsa::api::algorithm::DsaAlgorithm;
+    pub use crate::dsa::api::functions::{sign, verify};
     pub use crate::dsa::api::key_generator::DsaKeyGenerator;
+    pub use crate::dsa::api::verify_after_sign::{
+        get_verify_after_sign, set_verify_after_sign, VerifyAfterSign,
+    };
+    pub use crate::dsa::common::dsa_backend::{DsaBackend, PrehashDsaBackend};
+    pub use crate::dsa::common::dsa_info::DsaInfo;
+    pub use crate::dsa::common::dsa_trait::Dsa;
+    pub use crate::dsa::common::dsa_type::DsaType;
+    pub use crate::dsa::common::prehash_dsa_info::PrehashDsaInfo;
+    pub use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+    pub use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+    pub use crate::dsa::dsa_manager::{DsaManager, PrehashDsaManager};
+    pub use crate::utils::manager_pool::{
+        register_dsa_backend, register_prehash_dsa_backend, unregister_dsa_backend,
+        unregister_prehash_dsa_backend,
+    };
 }
 
 /// Defines KEM types and key generation
 pub mod kems {
     pub use crate::kem::api::algorithm::KemAlgorithm;
+    pub use crate::kem::api::ciphertext::Ciphertext;
+    pub use crate::kem::api::functions::{decap, encap};
+    #[cfg(feature = "kem-traits")]
+    pub use crate::kem::api::kem_traits::{generate_key_pair, DecapsulationKey, EncapsulationKey};
     pub use crate::kem::api::key_generator::KemKeyGenerator;
+    pub use crate::kem::api::key_wrap::{unwrap_key, wrap_key_for};
+    pub use crate::kem::api::layered::{decrypt_layer, encrypt_layered, PeeledLayer};
+    pub use crate::kem::api::pake::{pake_kem_decap, pake_kem_encap, PakeType};
+    pub use crate::kem::api::prepared_key::PreparedEncapsulationKey;
+    pub use crate::kem::api::private_key::KemPrivateKey;
+    pub use crate::kem::api::public_key::KemPublicKey;
+    pub use crate::kem::api::shared_secret::{SharedSecret, CHANNEL_BINDING_LABEL};
+    pub use crate::kem::api::traditional_key::{TraditionalKemPrivateKey, TraditionalKemPublicKey};
+    pub use crate::kem::common::async_kem::{AsyncKem, BlockingKem};
+    #[cfg(feature = "bench")]
+    pub use crate::kem::common::bench::{KemBenchmarkResult, KemBenchmarkRunner};
+    pub use crate::kem::common::combined_kem::{
+        ChempatCombiner, CombinedKem, Combiner, CombinerInput, KdfCombiner, KmacCombiner,
+        XWingCombiner,
+    };
+    pub use crate::kem::common::dyn_kem::DynKem;
+    pub use crate::kem::common::kem_backend::KemBackend;
+    pub use crate::kem::common::kem_info::{
+        CombinerInfo, KemInfo, KemLengths, OaepHash, OaepParams, PointEncoding,
+    };
+    pub use crate::kem::common::kem_trait::Kem;
+    pub use crate::kem::common::kem_type::KemType;
+    pub use crate::kem::common::multi_recipient::MultiRecipientKem;
+    pub use crate::kem::composite_kem::CompositeKemManager;
+    pub use crate::kem::kem_manager::KemManager;
+    pub use crate::kem::ml_kem::ML_KEM_SEED_LEN;
+    pub use crate::kem::xwing::{XWingKemManager, XWING_EXPANDED_SK_LEN};
+    pub use crate::utils::blocking_pool::{BlockingPool, BlockingTask};
+    pub use crate::utils::manager_pool::{register_kem_backend, unregister_kem_backend};
+}
+
+/// Experimental group key agreement
+pub mod groups {
+    pub use crate::group::tree_kem::{
+        Commit, KeyPackage, PendingMember, Proposal, TreeKemGroup, Welcome,
+    };
+}
+
+/// HPKE and encodings for using the post-quantum KEMs and ML-DSA in MLS (RFC 9420)
+pub mod mls {
+    pub use crate::hpke::mls::{
+        decode_opaque, decrypt_with_label, encode_basic_credential, encode_hpke_public_key,
+        encode_opaque, encode_signature_public_key, encode_x509_credential, encrypt_with_label,
+        get_signature_scheme, sign_with_label, verify_with_label,
+    };
+    pub use crate::hpke::suite::{HpkeAead, HpkeContext, HpkeKdf, HpkeKem, HpkeSuite};
+}
+
+/// Encoding seeds for backup on paper
+pub mod paper_keys {
+    pub use crate::paper::paper_key::PaperKey;
+    pub use crate::paper::paper_key::MASTER_SEED_HRP;
+    pub use crate::paper::paper_key::ML_KEM_SEED_HRP;
+    pub use crate::paper::paper_key::XWING_SK_HRP;
+}
+
+/// Escrowing private keys to custodians with a k-of-n recovery policy
+pub mod key_escrow {
+    pub use crate::escrow::escrow_envelope::EscrowEnvelope;
+    pub use crate::escrow::shamir::{combine_shares, split_secret, SecretShare};
+}
+
+/// Reproducing the NIST PQC known answer tests
+pub mod kats {
+    #[cfg(feature = "test-utils")]
+    pub use crate::utils::ctr_drbg::CtrDrbg;
+    #[cfg(feature = "test-utils")]
+    pub use crate::utils::kat::run_bike_kat;
+    #[cfg(feature = "test-utils")]
+    pub use crate::utils::kat::{decap_checked, sign_deterministic_with_seed};
+    pub use crate::utils::kat::{run_dsa_kat, run_kem_kat, KatRecord, RspFile};
+}
+
+/// Checking the revocation status of certificates
+pub mod revocation {
+    pub use crate::pki::crl_builder::CrlBuilder;
+    pub use crate::pki::crypto_policy::CryptoPolicy;
+    #[cfg(feature = "http-fetcher")]
+    pub use crate::pki::fetcher::HttpFetcher;
+    pub use crate::pki::fetcher::{CachingFetcher, Fetcher};
+    pub use crate::pki::ocsp_responder::{OcspResponder, OcspResponderResponse};
+    pub use crate::pki::revocation::{
+        check_crl, check_crl_with_delta, check_ocsp_response, RevocationChecker, RevocationStatus,
+    };
+    pub use crate::pki::validation_cache::{ValidationCache, ValidationCacheMetrics};
+}
+
+/// Fixtures and conformance tests for code built on quantcrypt
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing {
+    pub use crate::test_utils::conformance::{
+        check_certificate, check_dsa, check_kem, check_prehash_dsa,
+    };
+    pub use crate::test_utils::fixtures::{
+        ChainFixture, ChainFixtureBuilder, ChainPosition, Defect,
+    };
+    pub use crate::{test_certificate, test_dsa, test_kem, test_prehash_dsa};
+}
+
+/// Deprecation and sunset metadata of algorithms
+pub mod deprecation {
+    pub use crate::utils::deprecation::{
+        clear_deprecation_callback, set_deprecation_callback, Deprecation, DeprecationStatus,
+        DeprecationWarning,
+    };
+}
+
+/// Comparing secrets in constant time
+pub mod constant_time {
+    pub use crate::utils::ct_eq::{ct_eq, ct_is_zero};
+}
+
+/// Constant-time base64 and hex codecs for secrets
+pub mod encoding {
+    pub use crate::utils::ct_codec::{
+        decode_base64_ct, decode_hex_ct, encode_base64_ct, encode_hex_ct,
+    };
+}
+
+/// Monitoring the health of the entropy used to generate keys
+pub mod entropy {
+    pub use crate::utils::entropy::{
+        get_entropy_health_failure, reset_entropy_health_tests, set_entropy_failure_callback,
+        HealthTestFailure, HealthTestedRng, SharedRng,
+    };
+}
+
+/// Refusing the algorithms that aren't approved by FIPS
+pub mod fips {
+    pub use crate::utils::fips::{get_fips_mode, set_fips_mode};
+}
+
+/// Probing the backends at startup and disabling the broken algorithms
+pub mod probe {
+    pub use crate::utils::backend_probe::{
+        probe_algorithms, probe_backends, ProbeFailure, ProbeReport, ProbedAlgorithm,
+    };
+}
+
+/// Limiting the resources spent parsing untrusted input
+pub mod limits {
+    pub use crate::utils::resource_limits::{get_parse_budget, set_parse_budget};
+}
+
+/// Migrating encrypted blobs at rest to post-quantum recipients
+pub mod migrate {
+    pub use crate::migration::blob_store::{BlobStore, DirectoryBlobStore, MemoryBlobStore};
+    pub use crate::migration::migrator::{
+        get_recipient_algorithms, BlobReport, BlobStatus, MigrationReport, Migrator,
+    };
+}
+
+/// Authenticated encryption, including key-committing encryption
+pub mod aeads {
+    pub use crate::aead::aead_manager::AeadManager;
+    pub use crate::aead::common::aead_info::AeadInfo;
+    pub use crate::aead::common::aead_trait::{Aead, KEY_COMMITMENT_LEN};
+    pub use crate::aead::common::aead_type::AeadType;
 }
 
 /// Defines the types of key derivation functions
@@ -45,26 +252,64 @@ pub mod kdfs {
     pub use crate::kdf::api::KdfType;
 }
 
+/// Defines the types of hash functions
+pub mod hashes {
+    pub use crate::hash::common::hash_type::HashType;
+}
+
+/// Merkle trees with inclusion, consistency and multi-leaf proofs
+pub mod merkle {
+    pub use crate::tlog::merkle::{MerkleHasher, MerkleTree};
+}
+
+/// Verifying the Merkle proofs and signed tree heads of transparency logs
+pub mod transparency {
+    pub use crate::tlog::merkle::MerkleHasher;
+    pub use crate::tlog::tree_head::{LogVerifier, SignedTreeHead};
+}
+
 /// Defines the types of key wrapping functions
 pub mod wraps {
     pub use crate::wrap::api::WrapType;
 }
 
+/// Signing and verifying directory trees with detached manifests
+pub mod bundles {
+    pub use crate::bundle::manifest::BundleVerificationReport;
+    pub use crate::bundle::manifest::DirectoryManifest;
+    pub use crate::bundle::manifest::FileFailure;
+    pub use crate::bundle::manifest::SignedBundle;
+}
+
 /// Dealing with Cryptographic Message Syntax (CMS)
 pub mod content {
+    pub use crate::cms::api::verify_cms_with_policy;
     pub use crate::cms::api::Attribute;
     pub use crate::cms::api::AttributeType;
     pub use crate::cms::api::AttributeValue;
     pub use crate::cms::api::AuthEnvelopedDataContent;
     pub use crate::cms::api::CertificateStore;
+    pub use crate::cms::api::CertsOnlyContent;
+    pub use crate::cms::api::CmsVerificationPolicy;
     pub use crate::cms::api::CmsVersion;
+    pub use crate::cms::api::CommitmentType;
+    pub use crate::cms::api::CompressedDataContent;
     pub use crate::cms::api::ContentEncryptionAlgorithm;
     pub use crate::cms::api::ContentEncryptionAlgorithmAead;
+    pub use crate::cms::api::ContentTypeRegistry;
     pub use crate::cms::api::DirectoryCertificateStore;
     pub use crate::cms::api::EnvelopedDataContent;
+    pub use crate::cms::api::Identity;
     pub use crate::cms::api::KdfType;
     pub use crate::cms::api::ObjectIdentifier;
+    pub use crate::cms::api::RecipientRewrapper;
     pub use crate::cms::api::SetOfVec;
+    pub use crate::cms::api::SignedAttributePolicy;
+    pub use crate::cms::api::SignedDataBuilder;
+    pub use crate::cms::api::SignedDataContent;
+    pub use crate::cms::api::SignerAttributes;
+    pub use crate::cms::api::StreamedEnvelopedData;
+    pub use crate::cms::api::StreamedSignedData;
     pub use crate::cms::api::Tag;
     pub use crate::cms::api::Tagged;
     pub use crate::cms::api::UserKeyingMaterial;
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A store of encrypted blobs at rest, walked by a `Migrator`
+///
+/// Blobs are identified by a string, e.g. a relative path or a database key.
+pub trait BlobStore {
+    /// List the identifiers of all the blobs in the store
+    fn list(&self) -> Result<Vec<String>>;
+
+    /// Read a blob
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the blob
+    fn read(&self, id: &str) -> Result<Vec<u8>>;
+
+    /// Replace the contents of a blob
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the blob
+    /// * `data` - The new contents of the blob
+    fn write(&mut self, id: &str, data: &[u8]) -> Result<()>;
+}
+
+/// A `BlobStore` keeping blobs in memory, e.g. for tests or to migrate blobs
+/// loaded from a database
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBlobStore {
+    blobs: BTreeMap<String, Vec<u8>>,
+}
+
+impl MemoryBlobStore {
+    /// Create a new empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a blob
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the blob
+    /// * `data` - The contents of the blob
+    ///
+    /// # Returns
+    ///
+    /// The store, for chaining
+    pub fn insert(&mut self, id: &str, data: &[u8]) -> &mut Self {
+        self.blobs.insert(id.to_string(), data.to_vec());
+        self
+    }
+
+    /// Get a blob
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the blob
+    ///
+    /// # Returns
+    ///
+    /// The contents of the blob, or None if there is no such blob
+    pub fn get(&self, id: &str) -> Option<&[u8]> {
+        self.blobs.get(id).map(|data| data.as_slice())
+    }
+}
+
+impl BlobStore for MemoryBlobStore {
+    fn list(&self) -> Result<Vec<String>> {
+        Ok(self.blobs.keys().cloned().collect())
+    }
+
+    fn read(&self, id: &str) -> Result<Vec<u8>> {
+        self.blobs
+            .get(id)
+            .cloned()
+            .ok_or(QuantCryptError::FileReadError)
+    }
+
+    fn write(&mut self, id: &str, data: &[u8]) -> Result<()> {
+        self.blobs.insert(id.to_string(), data.to_vec());
+        Ok(())
+    }
+}
+
+/// A `BlobStore` over the regular files of a directory tree
+///
+/// Blobs are identified by their path relative to the root, with `/` as the
+/// separator. A blob is replaced by writing a temporary file next to it and
+/// renaming it over the original, so an interrupted migration never leaves a
+/// truncated blob behind.
+#[derive(Clone, Debug)]
+pub struct DirectoryBlobStore {
+    root: PathBuf,
+}
+
+impl DirectoryBlobStore {
+    /// Create a store over a directory tree
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The root of the directory tree
+    ///
+    /// # Returns
+    ///
+    /// The store
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidDirectoryPath` if the path is not a directory
+    pub fn new(path: &str) -> Result<Self> {
+        let root = PathBuf::from(path);
+        if !root.is_dir() {
+            return Err(QuantCryptError::InvalidDirectoryPath);
+        }
+        Ok(Self { root })
+    }
+
+    /// Collect the relative paths of the regular files under a directory
+    fn walk(dir: &Path, prefix: &str, ids: &mut Vec<String>) -> Result<()> {
+        let entries = fs::read_dir(dir).map_err(|_| QuantCryptError::FileReadError)?;
+        for entry in entries {
+            let entry = entry.map_err(|_| QuantCryptError::FileReadError)?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = format!("{}{}", prefix, name);
+            let file_type = entry
+                .file_type()
+                .map_err(|_| QuantCryptError::FileReadError)?;
+            if file_type.is_dir() {
+                Self::walk(&entry.path(), &format!("{}/", id), ids)?;
+            } else if file_type.is_file() {
+                ids.push(id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the path of a blob, refusing identifiers that escape the root
+    fn get_path(&self, id: &str) -> Result<PathBuf> {
+        if id.split('/').any(|part| part.is_empty() || part == "..") {
+            return Err(QuantCryptError::InvalidDirectoryPath);
+        }
+        Ok(self.root.join(id))
+    }
+}
+
+impl BlobStore for DirectoryBlobStore {
+    fn list(&self) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        Self::walk(&self.root, "", &mut ids)?;
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn read(&self, id: &str) -> Result<Vec<u8>> {
+        fs::read(self.get_path(id)?).map_err(|_| QuantCryptError::FileReadError)
+    }
+
+    fn write(&mut self, id: &str, data: &[u8]) -> Result<()> {
+        let path = self.get_path(id)?;
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".migrating");
+        fs::write(&tmp, data).map_err(|_| QuantCryptError::FileWriteError)?;
+        fs::rename(&tmp, &path).map_err(|_| QuantCryptError::FileWriteError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directory_blob_store() {
+        let root = std::env::temp_dir().join(format!("quantcrypt-blobs-{}", std::process::id()));
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("a.p7m"), b"a").unwrap();
+        fs::write(root.join("nested/b.p7m"), b"b").unwrap();
+
+        let mut store = DirectoryBlobStore::new(root.to_str().unwrap()).unwrap();
+        assert_eq!(store.list().unwrap(), vec!["a.p7m", "nested/b.p7m"]);
+        store.write("nested/b.p7m", b"rekeyed").unwrap();
+        assert_eq!(store.read("nested/b.p7m").unwrap(), b"rekeyed");
+        assert_eq!(store.list().unwrap().len(), 2);
+        assert!(store.read("../a.p7m").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
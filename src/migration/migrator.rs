@@ -0,0 +1,640 @@
+use cms::content_info::ContentInfo;
+use cms::enveloped_data::{EnvelopedData, RecipientInfo, RecipientInfos, UserKeyingMaterial};
+use const_oid::db::rfc5911::{ID_CT_AUTH_ENVELOPED_DATA, ID_ENVELOPED_DATA};
+use der::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use crate::certificates::Certificate;
+use crate::cms::asn1::auth_env_data::AuthEnvelopedData;
+use crate::cms::asn1::kemri::KemRecipientInfo;
+use crate::cms::recipient_rewrapper::RecipientRewrapper;
+use crate::kdf::api::KdfType;
+use crate::kem::common::kem_type::KemType;
+use crate::keys::PrivateKey;
+use crate::migration::blob_store::BlobStore;
+use crate::wrap::api::{WrapManager, WrapType};
+use crate::wrap::common::wrap_trait::Wrap;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The number of blobs processed between two writes and progress reports by default
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// What happened, or would happen in a dry run, to a single blob
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BlobStatus {
+    /// All the recipients already satisfy the policy, the blob is left alone
+    Compliant,
+    /// The content encryption key was re-wrapped for the new recipients
+    Migrated,
+    /// The blob is below the policy and would be migrated (dry run only)
+    WouldMigrate,
+    /// The blob is not an EnvelopedData or AuthEnvelopedData, and is left alone
+    Unsupported,
+    /// The blob is below the policy but could not be migrated, e.g. because none of
+    /// the decryption keys belongs to one of its recipients
+    Failed(String),
+}
+
+/// The outcome of the migration of a single blob
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlobReport {
+    /// The identifier of the blob in the store
+    pub id: String,
+    /// The key management algorithm of each recipient before the migration
+    pub algorithms: Vec<String>,
+    /// What happened to the blob
+    pub status: BlobStatus,
+}
+
+/// The result of migrating a `BlobStore`
+///
+/// Like `VerificationReport`, it can be serialized with any serde format for audit logs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// True if nothing was written to the store
+    pub dry_run: bool,
+    /// The outcome for every blob in the store
+    pub blobs: Vec<BlobReport>,
+}
+
+impl MigrationReport {
+    /// Count the blobs with a given status
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status, where the reason of `BlobStatus::Failed` is ignored
+    ///
+    /// # Returns
+    ///
+    /// The number of blobs with that status
+    pub fn count(&self, status: &BlobStatus) -> usize {
+        self.blobs
+            .iter()
+            .filter(|blob| std::mem::discriminant(&blob.status) == std::mem::discriminant(status))
+            .count()
+    }
+
+    /// Check if every encrypted blob satisfies the policy after the migration
+    ///
+    /// # Returns
+    ///
+    /// True if no blob failed, or is still waiting to be migrated
+    pub fn is_complete(&self) -> bool {
+        self.blobs.iter().all(|blob| {
+            matches!(
+                blob.status,
+                BlobStatus::Compliant | BlobStatus::Migrated | BlobStatus::Unsupported
+            )
+        })
+    }
+}
+
+/// The key of an existing recipient, used to decrypt the content encryption keys
+enum DecryptionKey<'a> {
+    /// The private key of a KEM recipient
+    Kem(&'a Certificate, &'a PrivateKey),
+    /// The RSA private key of a key transport recipient, as PKCS #8 or PKCS #1 DER
+    Ktri(&'a Certificate, &'a [u8]),
+}
+
+/// A new KEM recipient of the migrated blobs
+struct NewRecipient {
+    cert: Certificate,
+    kdf: KdfType,
+    wrap_type: WrapType,
+    ukm: Option<UserKeyingMaterial>,
+}
+
+/// Migrates encrypted blobs at rest to post-quantum KEM recipients
+///
+/// The migrator walks a `BlobStore` and reads the key management algorithm of
+/// every recipient of each EnvelopedData or AuthEnvelopedData from its
+/// self-describing headers. A blob is below the policy if any of its recipients
+/// uses a KEM whose NIST security category against quantum attackers is below
+/// the minimum, or a forbidden algorithm. RSA key transport and ECDH key
+/// agreement have no quantum security, so RSA-only blobs are always below the
+/// policy, while recipients sharing a symmetric key (KEK and password
+/// recipients) are not considered.
+///
+/// Blobs below the policy are re-keyed with a `RecipientRewrapper`: the content
+/// encryption key is decrypted with one of the decryption keys and wrapped for the
+/// new KEM recipients, which replace all the existing recipients. The encrypted
+/// content is never re-encrypted. Blobs are processed in batches, which are written
+/// to the store at the end of each batch, after which the progress callback is
+/// invoked.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::Certificate;
+/// use quantcrypt::kdfs::KdfType;
+/// use quantcrypt::keys::PrivateKey;
+/// use quantcrypt::migrate::{BlobStatus, MemoryBlobStore, Migrator};
+/// use quantcrypt::wraps::WrapType;
+///
+/// let cert = Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+/// let sk = PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der").unwrap();
+/// let blob = std::fs::read(
+///     "test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_kemri_id-alg-hkdf-with-sha256.der",
+/// )
+/// .unwrap();
+///
+/// let mut store = MemoryBlobStore::new();
+/// store.insert("archive/2024.p7m", &blob);
+///
+/// // Require NIST category 3, which ML-KEM-512 does not meet
+/// let mut migrator = Migrator::new();
+/// migrator
+///     .set_min_quantum_security_level(3)
+///     .set_dry_run(true)
+///     .kem_decryption_key(&cert, &sk);
+///
+/// let report = migrator.migrate(&mut store).unwrap();
+/// assert_eq!(report.count(&BlobStatus::WouldMigrate), 1);
+/// ```
+pub struct Migrator<'a> {
+    /// The minimum NIST security category against quantum attackers
+    min_quantum_security_level: u8,
+    /// The key management algorithms that are not acceptable
+    forbidden: Vec<String>,
+    /// The keys of existing recipients
+    decryption_keys: Vec<DecryptionKey<'a>>,
+    /// The new recipients of the migrated blobs
+    recipients: Vec<NewRecipient>,
+    /// The number of blobs per batch
+    batch_size: usize,
+    /// Whether to only report what would be migrated
+    dry_run: bool,
+    /// The callback invoked after each batch
+    progress_callback: Option<Box<dyn FnMut(usize, usize) + 'a>>,
+}
+
+impl Default for Migrator<'_> {
+    fn default() -> Self {
+        Migrator::new()
+    }
+}
+
+impl<'a> Migrator<'a> {
+    /// Create a new migrator requiring post-quantum security (NIST category 1 or more)
+    ///
+    /// # Returns
+    ///
+    /// A new migrator without decryption keys or new recipients
+    pub fn new() -> Self {
+        Self {
+            min_quantum_security_level: 1,
+            forbidden: Vec::new(),
+            decryption_keys: Vec::new(),
+            recipients: Vec::new(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            dry_run: false,
+            progress_callback: None,
+        }
+    }
+
+    /// Set the minimum NIST security category against quantum attackers
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The minimum category of the KEM of every recipient
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    pub fn set_min_quantum_security_level(&mut self, level: u8) -> &mut Self {
+        self.min_quantum_security_level = level;
+        self
+    }
+
+    /// Forbid a key management algorithm, e.g. a KEM that is being retired
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The OID of the algorithm
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    pub fn forbid(&mut self, oid: &str) -> &mut Self {
+        self.forbidden.push(oid.to_string());
+        self
+    }
+
+    /// Add the key of an existing KEM recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the recipient
+    /// * `private_key` - The private key of the recipient
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    pub fn kem_decryption_key(
+        &mut self,
+        cert: &'a Certificate,
+        private_key: &'a PrivateKey,
+    ) -> &mut Self {
+        self.decryption_keys
+            .push(DecryptionKey::Kem(cert, private_key));
+        self
+    }
+
+    /// Add the key of an existing key transport (RSA) recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the recipient
+    /// * `private_key` - The RSA private key of the recipient, as PKCS #8 or PKCS #1 DER
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    pub fn ktri_decryption_key(
+        &mut self,
+        cert: &'a Certificate,
+        private_key: &'a [u8],
+    ) -> &mut Self {
+        self.decryption_keys
+            .push(DecryptionKey::Ktri(cert, private_key));
+        self
+    }
+
+    /// Add a new KEM recipient of the migrated blobs
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the recipient
+    /// * `kdf` - The key derivation function to use
+    /// * `wrap_type` - The key wrap type to use
+    /// * `ukm` - The user keying material to use
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if the certificate does not allow key encipherment
+    /// or does not have a KEM key
+    pub fn kem_recipient(
+        &mut self,
+        cert: &Certificate,
+        kdf: &KdfType,
+        wrap_type: &WrapType,
+        ukm: Option<UserKeyingMaterial>,
+    ) -> Result<&mut Self> {
+        if !cert.is_key_encipherment_enabled()
+            || KemType::from_oid(&cert.get_public_key_oid()).is_none()
+        {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        self.recipients.push(NewRecipient {
+            cert: cert.clone(),
+            kdf: kdf.clone(),
+            wrap_type: wrap_type.clone(),
+            ukm,
+        });
+        Ok(self)
+    }
+
+    /// Set the number of blobs per batch
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size` - The number of blobs read before the migrated ones are written
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    pub fn set_batch_size(&mut self, batch_size: usize) -> &mut Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set whether to only report what would be migrated, without writing to the store
+    ///
+    /// A dry run needs neither decryption keys nor new recipients.
+    ///
+    /// # Arguments
+    ///
+    /// * `dry_run` - True to not write to the store
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    pub fn set_dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set the callback invoked after each batch
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback, receiving the number of blobs processed so far
+    ///   and the total number of blobs
+    ///
+    /// # Returns
+    ///
+    /// The migrator, for chaining
+    pub fn set_progress_callback(&mut self, callback: impl FnMut(usize, usize) + 'a) -> &mut Self {
+        self.progress_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Migrate every blob of a store that is below the policy
+    ///
+    /// Failing to migrate a blob does not stop the migration, it is recorded in
+    /// the report instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The store to migrate
+    ///
+    /// # Returns
+    ///
+    /// The report of what happened to every blob
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidRecipientInfo` if this is not a dry run and there are no
+    /// new recipients
+    ///
+    /// `QuantCryptError::CryptoPolicyViolation` if a new recipient is itself below the policy
+    ///
+    /// Any error of the store when listing the blobs or writing a batch
+    pub fn migrate(&mut self, store: &mut dyn BlobStore) -> Result<MigrationReport> {
+        if !self.dry_run {
+            if self.recipients.is_empty() {
+                return Err(QuantCryptError::InvalidRecipientInfo);
+            }
+            for recipient in self.recipients.iter() {
+                let oid = recipient.cert.get_public_key_oid();
+                if !self.is_compliant(&oid) {
+                    return Err(QuantCryptError::CryptoPolicyViolation {
+                        reason: format!("new recipient algorithm {} is below the policy", oid),
+                    });
+                }
+            }
+        }
+
+        let ids = store.list()?;
+        let mut blobs = Vec::with_capacity(ids.len());
+        for batch in ids.chunks(self.batch_size) {
+            let mut writes = Vec::new();
+            for id in batch {
+                let (report, migrated) = self.migrate_blob(store, id);
+                if let Some(data) = migrated {
+                    writes.push((id, data));
+                }
+                blobs.push(report);
+            }
+            for (id, data) in writes {
+                store.write(id, &data)?;
+            }
+            if let Some(callback) = self.progress_callback.as_mut() {
+                callback(blobs.len(), ids.len());
+            }
+        }
+
+        Ok(MigrationReport {
+            dry_run: self.dry_run,
+            blobs,
+        })
+    }
+
+    /// Inspect and, if it is below the policy, re-key a single blob
+    fn migrate_blob(&self, store: &dyn BlobStore, id: &str) -> (BlobReport, Option<Vec<u8>>) {
+        let report = |algorithms: Vec<String>, status: BlobStatus| BlobReport {
+            id: id.to_string(),
+            algorithms,
+            status,
+        };
+
+        let data = match store.read(id) {
+            Ok(data) => data,
+            Err(e) => return (report(Vec::new(), BlobStatus::Failed(e.to_string())), None),
+        };
+        let algorithms = match get_recipient_algorithms(&data) {
+            Ok(algorithms) => algorithms,
+            Err(_) => return (report(Vec::new(), BlobStatus::Unsupported), None),
+        };
+
+        if algorithms.iter().all(|oid| self.is_compliant(oid)) {
+            return (report(algorithms, BlobStatus::Compliant), None);
+        }
+        if self.dry_run {
+            return (report(algorithms, BlobStatus::WouldMigrate), None);
+        }
+
+        match self.rewrap(&data) {
+            Ok(migrated) => (report(algorithms, BlobStatus::Migrated), Some(migrated)),
+            Err(e) => (report(algorithms, BlobStatus::Failed(e.to_string())), None),
+        }
+    }
+
+    /// Re-wrap the content encryption key of a blob with the first decryption key that fits
+    fn rewrap(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut result = Err(QuantCryptError::InvalidEnvelopedData);
+        for key in self.decryption_keys.iter() {
+            let mut rewrapper = match key {
+                DecryptionKey::Kem(cert, private_key) => RecipientRewrapper::new(cert, private_key),
+                DecryptionKey::Ktri(cert, private_key) => {
+                    RecipientRewrapper::new_for_ktri_recipient(cert, private_key)?
+                }
+            };
+            for recipient in self.recipients.iter() {
+                rewrapper.kem_recipient(
+                    &recipient.cert,
+                    &recipient.kdf,
+                    &recipient.wrap_type,
+                    recipient.ukm.clone(),
+                )?;
+            }
+            result = rewrapper.rewrap(data);
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Check if a key management algorithm satisfies the policy
+    fn is_compliant(&self, oid: &str) -> bool {
+        if self.forbidden.iter().any(|forbidden| forbidden == oid) {
+            return false;
+        }
+        // Key wrap algorithms are used by recipients sharing a symmetric key
+        if WrapManager::new_from_oid(oid).is_ok() {
+            return true;
+        }
+        let level = KemType::from_oid(oid)
+            .map(|kem_type| kem_type.get_quantum_security_level())
+            .unwrap_or(0);
+        level >= self.min_quantum_security_level
+    }
+}
+
+/// Get the key management algorithm of every recipient of an EnvelopedData or
+/// AuthEnvelopedData
+///
+/// This is the KEM of KEM recipients (RFC 9629), and the key encryption algorithm
+/// of the other recipients, e.g. rsaEncryption or id-RSAES-OAEP for key transport.
+///
+/// # Arguments
+///
+/// * `data` - The DER or PEM encoded ContentInfo
+///
+/// # Returns
+///
+/// The OIDs of the algorithms, one per recipient
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidContent` if the data is not an EnvelopedData or AuthEnvelopedData
+pub fn get_recipient_algorithms(data: &[u8]) -> Result<Vec<String>> {
+    let ci = if let Ok(content_info) = ContentInfo::from_der(data) {
+        content_info
+    } else {
+        let pem = pem::parse(data).map_err(|_| QuantCryptError::InvalidContent)?;
+        ContentInfo::from_der(pem.contents()).map_err(|_| QuantCryptError::InvalidContent)?
+    };
+    let content = ci
+        .content
+        .to_der()
+        .map_err(|_| QuantCryptError::InvalidContent)?;
+
+    let recip_infos: RecipientInfos = if ci.content_type == ID_ENVELOPED_DATA {
+        EnvelopedData::from_der(&content)
+            .map_err(|_| QuantCryptError::InvalidContent)?
+            .recip_infos
+    } else if ci.content_type == ID_CT_AUTH_ENVELOPED_DATA {
+        AuthEnvelopedData::from_der(&content)
+            .map_err(|_| QuantCryptError::InvalidContent)?
+            .recip_infos
+    } else {
+        return Err(QuantCryptError::InvalidContent);
+    };
+
+    Ok(recip_infos
+        .0
+        .iter()
+        .map(|ri| match ri {
+            RecipientInfo::Ktri(ktri) => ktri.key_enc_alg.oid.to_string(),
+            RecipientInfo::Kari(kari) => kari.key_enc_alg.oid.to_string(),
+            RecipientInfo::Kekri(kekri) => kekri.key_enc_alg.oid.to_string(),
+            RecipientInfo::Pwri(pwri) => pwri.key_enc_alg.oid.to_string(),
+            RecipientInfo::Ori(ori) => ori
+                .ori_value
+                .to_der()
+                .ok()
+                .and_then(|der| KemRecipientInfo::from_der(&der).ok())
+                .map(|kemri| kemri.kem.oid.to_string())
+                .unwrap_or_else(|| ori.ori_type.to_string()),
+        })
+        .collect())
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::content::EnvelopedDataContent;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::kems::{KemAlgorithm, KemKeyGenerator};
+    use crate::migration::blob_store::MemoryBlobStore;
+
+    #[test]
+    fn test_migrator() {
+        let old_cert =
+            Certificate::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_ee.der").unwrap();
+        let old_sk =
+            PrivateKey::from_file("test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_priv.der")
+                .unwrap();
+        let blob = std::fs::read(
+            "test/data/cms/2.16.840.1.101.3.4.4.1_MlKem512_kemri_id-alg-hkdf-with-sha256.der",
+        )
+        .unwrap();
+        assert_eq!(
+            get_recipient_algorithms(&blob).unwrap(),
+            vec![KemAlgorithm::MlKem512.get_oid()]
+        );
+
+        let (_, ta_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+        let (pk, new_sk) = KemKeyGenerator::new(KemAlgorithm::MlKem1024)
+            .generate()
+            .unwrap();
+        let new_cert = CertificateBuilder::new(
+            Profile::Leaf {
+                issuer: "CN=ta.test.com".parse().unwrap(),
+                enable_key_agreement: false,
+                enable_key_encipherment: true,
+            },
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=archive.test.com".to_string(),
+            pk,
+            &ta_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let mut store = MemoryBlobStore::new();
+        store
+            .insert("a.p7m", &blob)
+            .insert("b.p7m", &blob)
+            .insert("notes.txt", b"not encrypted");
+
+        let mut progress = Vec::new();
+        let mut migrator = Migrator::new();
+        migrator
+            .set_min_quantum_security_level(3)
+            .set_batch_size(2)
+            .set_dry_run(true)
+            .set_progress_callback(|done, total| progress.push((done, total)));
+
+        // A dry run reports without writing
+        let report = migrator.migrate(&mut store).unwrap();
+        assert_eq!(report.count(&BlobStatus::WouldMigrate), 2);
+        assert_eq!(report.count(&BlobStatus::Unsupported), 1);
+        assert!(!report.is_complete());
+        assert_eq!(store.get("a.p7m").unwrap(), blob.as_slice());
+
+        // Migrating without the key of a recipient fails per blob
+        migrator
+            .set_dry_run(false)
+            .kem_recipient(&new_cert, &KdfType::HkdfWithSha256, &WrapType::Aes256, None)
+            .unwrap();
+        let report = migrator.migrate(&mut store).unwrap();
+        assert_eq!(report.count(&BlobStatus::Failed(String::new())), 2);
+
+        migrator.kem_decryption_key(&old_cert, &old_sk);
+        let report = migrator.migrate(&mut store).unwrap();
+        assert_eq!(report.count(&BlobStatus::Migrated), 2);
+        assert!(report.is_complete());
+
+        let migrated = store.get("b.p7m").unwrap();
+        assert_eq!(
+            get_recipient_algorithms(migrated).unwrap(),
+            vec![KemAlgorithm::MlKem1024.get_oid()]
+        );
+        let edc = EnvelopedDataContent::from_bytes_for_kem_recipient(migrated, &new_cert, &new_sk)
+            .unwrap();
+        let original =
+            EnvelopedDataContent::from_bytes_for_kem_recipient(&blob, &old_cert, &old_sk).unwrap();
+        assert_eq!(edc.get_content(), original.get_content());
+
+        // Migrated blobs are now compliant
+        let report = migrator.migrate(&mut store).unwrap();
+        assert_eq!(report.count(&BlobStatus::Compliant), 2);
+
+        drop(migrator);
+        assert_eq!(progress, [(2, 3), (3, 3)].repeat(4));
+    }
+}
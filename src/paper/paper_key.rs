@@ -0,0 +1,283 @@
+use zeroize::Zeroizing;
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The Bech32 character set (BIP-173)
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The checksum constant for Bech32m (BIP-350)
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+/// The length of the checksum in characters
+const CHECKSUM_LEN: usize = 6;
+
+/// Human readable part used for X-Wing private keys
+pub const XWING_SK_HRP: &str = "xwingsk";
+
+/// Human readable part used for ML-KEM (d, z) seeds
+pub const ML_KEM_SEED_HRP: &str = "mlkemseed";
+
+/// Human readable part used for generic master seeds
+pub const MASTER_SEED_HRP: &str = "qcseed";
+
+fn polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk: u32 = 1;
+    for v in values {
+        let b = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ (*v as u32);
+        for (i, g) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.iter().map(|c| c >> 5).collect();
+    out.push(0);
+    out.extend(hrp.iter().map(|c| c & 31));
+    out
+}
+
+fn is_valid_hrp(hrp: &str) -> bool {
+    !hrp.is_empty() && hrp.len() <= 83 && hrp.bytes().all(|c| (33..=126).contains(&c))
+}
+
+/// Regroup a sequence of `from`-bit values into `to`-bit values
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max_v: u32 = (1 << to) - 1;
+    let mut out = Vec::new();
+    for value in data {
+        let v = *value as u32;
+        if v >> from != 0 {
+            return Err(QuantCryptError::InvalidPaperKey);
+        }
+        acc = (acc << from) | v;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max_v) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max_v) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max_v) != 0 {
+        return Err(QuantCryptError::InvalidPaperKey);
+    }
+    Ok(out)
+}
+
+/// Encode 5-bit values as a Bech32m string
+fn bech32m_encode(hrp: &str, data: &[u8]) -> Result<String> {
+    if !is_valid_hrp(hrp) {
+        return Err(QuantCryptError::InvalidPaperKey);
+    }
+    let hrp = hrp.to_lowercase();
+    let mut values = hrp_expand(hrp.as_bytes());
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0u8; CHECKSUM_LEN]);
+    let checksum = polymod(&values) ^ BECH32M_CONST;
+
+    let mut out = hrp;
+    out.push('1');
+    for v in data {
+        out.push(CHARSET[*v as usize] as char);
+    }
+    for i in 0..CHECKSUM_LEN {
+        let v = (checksum >> (5 * (5 - i))) & 31;
+        out.push(CHARSET[v as usize] as char);
+    }
+    Ok(out)
+}
+
+/// Decode a Bech32m string into its human readable part and 5-bit values
+fn bech32m_decode(s: &str) -> Result<(String, Vec<u8>)> {
+    let has_lower = s.bytes().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.bytes().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(QuantCryptError::InvalidPaperKey);
+    }
+    let s = Zeroizing::new(s.to_lowercase());
+    let pos = s.rfind('1').ok_or(QuantCryptError::InvalidPaperKey)?;
+    let (hrp, data) = (&s[..pos], &s[pos + 1..]);
+    if !is_valid_hrp(hrp) || data.len() < CHECKSUM_LEN {
+        return Err(QuantCryptError::InvalidPaperKey);
+    }
+
+    let values = Zeroizing::new(
+        data.bytes()
+            .map(|c| {
+                CHARSET
+                    .iter()
+                    .position(|x| *x == c)
+                    .map(|p| p as u8)
+                    .ok_or(QuantCryptError::InvalidPaperKey)
+            })
+            .collect::<Result<Vec<u8>>>()?,
+    );
+
+    let mut check = Zeroizing::new(hrp_expand(hrp.as_bytes()));
+    check.extend_from_slice(&values);
+    if polymod(&check) != BECH32M_CONST {
+        return Err(QuantCryptError::InvalidPaperKey);
+    }
+
+    Ok((
+        hrp.to_string(),
+        values[..values.len() - CHECKSUM_LEN].to_vec(),
+    ))
+}
+
+/// Paper-friendly encoding of secret seeds.
+///
+/// Seeds of 32 or 64 bytes (an X-Wing private key, an ML-KEM `d || z` seed or
+/// a master seed) are encoded as Bech32m strings (BIP-350). The human readable
+/// part identifies what the seed is for and the checksum detects transcription
+/// errors when the seed is typed back in.
+///
+/// A 32 byte seed encodes to 65 to 68 characters, within the 89 characters for
+/// which the checksum is guaranteed to detect any error affecting up to 4
+/// characters. A 64 byte seed encodes to 116 to 119 characters, past the 90
+/// character limit of BIP-173, which is not enforced here. At that length the
+/// guarantee no longer holds: random errors are still detected with probability
+/// about 1 - 2^-30, but some errors in 4 or fewer characters are not. Decode a
+/// transcribed 64 byte seed and compare it before destroying the original, or
+/// split it into two 32 byte halves and encode each on its own.
+///
+/// # Example
+/// ```
+/// use quantcrypt::paper_keys::{PaperKey, XWING_SK_HRP};
+///
+/// let seed = [7u8; 32];
+/// let encoded = PaperKey::encode(XWING_SK_HRP, &seed).unwrap();
+/// assert!(encoded.starts_with("xwingsk1"));
+///
+/// // Whitespace can be used to break the string into groups on paper
+/// let grouped = encoded
+///     .as_bytes()
+///     .chunks(4)
+///     .map(|c| std::str::from_utf8(c).unwrap())
+///     .collect::<Vec<_>>()
+///     .join(" ");
+/// let decoded = PaperKey::decode(XWING_SK_HRP, &grouped).unwrap();
+/// assert_eq!(*decoded, seed);
+/// ```
+pub struct PaperKey;
+
+impl PaperKey {
+    /// Encode a seed as a Bech32m string
+    ///
+    /// # Arguments
+    ///
+    /// * `hrp` - The human readable part, e.g. `XWING_SK_HRP`
+    /// * `seed` - The seed to encode. Must be 32 or 64 bytes long
+    ///
+    /// # Returns
+    ///
+    /// The encoded seed
+    pub fn encode(hrp: &str, seed: &[u8]) -> Result<String> {
+        if seed.len() != 32 && seed.len() != 64 {
+            return Err(QuantCryptError::InvalidPaperKey);
+        }
+        let data = convert_bits(seed, 8, 5, true)?;
+        bech32m_encode(hrp, &data)
+    }
+
+    /// Decode a seed from a Bech32m string
+    ///
+    /// ASCII whitespace in the input is ignored so that the string can be
+    /// written down in groups.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_hrp` - The human readable part the string must carry
+    /// * `encoded` - The encoded seed
+    ///
+    /// # Returns
+    ///
+    /// The decoded seed, zeroized on drop
+    pub fn decode(expected_hrp: &str, encoded: &str) -> Result<Zeroizing<Vec<u8>>> {
+        let compact: Zeroizing<String> = Zeroizing::new(
+            encoded
+                .chars()
+                .filter(|c| !c.is_ascii_whitespace())
+                .collect(),
+        );
+        let (hrp, data) = bech32m_decode(&compact)?;
+        let data = Zeroizing::new(data);
+        if hrp != expected_hrp.to_lowercase() {
+            return Err(QuantCryptError::InvalidPaperKey);
+        }
+        let seed = Zeroizing::new(convert_bits(&data, 5, 8, false)?);
+        if seed.len() != 32 && seed.len() != 64 {
+            return Err(QuantCryptError::InvalidPaperKey);
+        }
+        Ok(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The longest string for which the Bech32m checksum is guaranteed to detect
+    /// any error affecting up to 4 characters
+    const GUARANTEED_DETECTION_LEN: usize = 89;
+
+    #[test]
+    fn test_bech32m_vectors() {
+        // Valid Bech32m strings from BIP-350
+        let valid = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for s in valid {
+            assert!(bech32m_decode(s).is_ok(), "{}", s);
+        }
+
+        // Bech32 (not m) checksum and mixed case must be rejected
+        assert!(bech32m_decode("a12uel5l").is_err());
+        assert!(bech32m_decode("A1lqfn3a").is_err());
+        assert!(bech32m_decode("a1lqfn3b").is_err());
+    }
+
+    #[test]
+    fn test_paper_key_roundtrip() {
+        for len in [32, 64] {
+            let seed: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let encoded = PaperKey::encode(ML_KEM_SEED_HRP, &seed).unwrap();
+            assert_eq!(*PaperKey::decode(ML_KEM_SEED_HRP, &encoded).unwrap(), seed);
+            assert_eq!(
+                *PaperKey::decode(ML_KEM_SEED_HRP, &encoded.to_uppercase()).unwrap(),
+                seed
+            );
+            // Only 32 byte seeds stay within the guaranteed error detection
+            assert_eq!(encoded.len() <= GUARANTEED_DETECTION_LEN, len == 32);
+
+            // Wrong HRP
+            assert!(PaperKey::decode(MASTER_SEED_HRP, &encoded).is_err());
+
+            // Single character transcription error
+            let mut corrupted = encoded.clone().into_bytes();
+            let i = corrupted.len() - 10;
+            corrupted[i] = if corrupted[i] == b'q' { b'p' } else { b'q' };
+            let corrupted = String::from_utf8(corrupted).unwrap();
+            assert!(PaperKey::decode(ML_KEM_SEED_HRP, &corrupted).is_err());
+        }
+
+        assert!(PaperKey::encode(XWING_SK_HRP, &[0u8; 31]).is_err());
+        assert!(PaperKey::encode("", &[0u8; 32]).is_err());
+    }
+}
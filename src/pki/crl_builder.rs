@@ -0,0 +1,474 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use der::asn1::{BitString, GeneralizedTime, Ia5String, Uint, UtcTime};
+use der::Encode;
+use spki::AlgorithmIdentifierOwned;
+use x509_cert::crl::{CertificateList, RevokedCert, TbsCertList};
+use x509_cert::ext::pkix::crl::dp::DistributionPoint;
+use x509_cert::ext::pkix::name::{DistributionPointName, GeneralName};
+use x509_cert::ext::pkix::{BaseCrlNumber, CrlNumber, CrlReason, FreshestCrl};
+use x509_cert::ext::{AsExtension, Extension};
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::time::Time;
+
+use crate::certificates::Certificate;
+use crate::keys::PrivateKey;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The default time between thisUpdate and nextUpdate
+const DEFAULT_VALIDITY: Duration = Duration::from_secs(24 * 3600);
+
+/// Convert a time to an X.509 time, UTCTime up to 2049 as required by RFC 5280
+fn to_x509_time(time: DateTime<Utc>) -> Result<Time> {
+    let secs = u64::try_from(time.timestamp()).map_err(|_| QuantCryptError::InvalidCrl)?;
+    let duration = Duration::from_secs(secs);
+    match UtcTime::from_unix_duration(duration) {
+        Ok(time) => Ok(Time::UtcTime(time)),
+        Err(_) => GeneralizedTime::from_unix_duration(duration)
+            .map(Time::GeneralTime)
+            .map_err(|_| QuantCryptError::InvalidCrl),
+    }
+}
+
+/// Encode a CRL number as an ASN.1 integer
+fn to_uint(number: u64) -> Result<Uint> {
+    Uint::new(&number.to_be_bytes()).map_err(|_| QuantCryptError::InvalidCrl)
+}
+
+/// A builder for complete and delta CRLs
+///
+/// A complete CRL lists every revoked certificate. For large populations it can
+/// grow to megabytes, so it can point to delta CRLs with `add_freshest_crl`: a
+/// delta CRL, built with `set_delta`, only lists the changes since a complete CRL
+/// and can be issued much more often. Relying parties combine both with
+/// `check_crl_with_delta`, which `RevocationChecker` does automatically.
+///
+/// # Example
+/// ```
+/// use chrono::Utc;
+/// use quantcrypt::certificates::{CertValidity, CertificateBuilder, Profile};
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+/// use quantcrypt::revocation::{check_crl_with_delta, CrlBuilder, RevocationStatus};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+/// let ca = CertificateBuilder::new(Profile::Root, None, validity.clone(), "CN=ca".to_string(), pk, &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+/// let (leaf_pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let profile = Profile::Leaf {
+///     issuer: ca.get_subject(),
+///     enable_key_agreement: false,
+///     enable_key_encipherment: false,
+/// };
+/// let leaf = CertificateBuilder::new(profile, None, validity, "CN=leaf".to_string(), leaf_pk, &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// // The complete CRL is empty, the leaf was revoked since
+/// let base = CrlBuilder::new(&ca, &sk, 1).unwrap().build().unwrap();
+/// let mut delta = CrlBuilder::new(&ca, &sk, 2).unwrap();
+/// delta.set_delta(1).add_revoked(&leaf.get_serial_number(), Utc::now(), None);
+/// let delta = delta.build().unwrap();
+///
+/// let status = check_crl_with_delta(&leaf, &ca, &base, Some(&delta)).unwrap();
+/// assert!(matches!(status, RevocationStatus::Revoked { .. }));
+/// ```
+pub struct CrlBuilder<'a> {
+    issuer: Certificate,
+    key: &'a PrivateKey,
+    crl_number: u64,
+    base_crl_number: Option<u64>,
+    validity: Duration,
+    revoked: Vec<(SerialNumber, DateTime<Utc>, Option<CrlReason>)>,
+    freshest_crl: Vec<String>,
+}
+
+impl<'a> CrlBuilder<'a> {
+    /// Create a new builder for a complete CRL
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The certificate of the CA issuing the CRL
+    /// * `key` - The private key of the CA
+    /// * `crl_number` - The CRL number, which must increase with every CRL of the CA, complete or delta
+    ///
+    /// # Returns
+    ///
+    /// The builder, for a CRL valid for a day
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if `issuer` is not a CA certificate or doesn't match `key`
+    pub fn new(
+        issuer: &Certificate,
+        key: &'a PrivateKey,
+        crl_number: u64,
+    ) -> Result<CrlBuilder<'a>> {
+        if !issuer.is_ca() || issuer.get_public_key_oid() != key.get_oid() {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        Ok(CrlBuilder {
+            issuer: issuer.clone(),
+            key,
+            crl_number,
+            base_crl_number: None,
+            validity: DEFAULT_VALIDITY,
+            revoked: Vec::new(),
+            freshest_crl: Vec::new(),
+        })
+    }
+
+    /// Make the CRL a delta CRL
+    ///
+    /// # Arguments
+    ///
+    /// * `base_crl_number` - The number of the oldest complete CRL the delta CRL can be combined with
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn set_delta(&mut self, base_crl_number: u64) -> &mut Self {
+        self.base_crl_number = Some(base_crl_number);
+        self
+    }
+
+    /// Set the time between thisUpdate and nextUpdate
+    ///
+    /// # Arguments
+    ///
+    /// * `validity` - How long the CRL is current
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn set_validity(&mut self, validity: Duration) -> &mut Self {
+        self.validity = validity;
+        self
+    }
+
+    /// List a revoked certificate
+    ///
+    /// In a delta CRL, a certificate released from hold since the complete CRL is
+    /// listed with the `CrlReason::RemoveFromCRL` reason.
+    ///
+    /// # Arguments
+    ///
+    /// * `serial` - The serial number of the certificate
+    /// * `revocation_time` - When the certificate was revoked
+    /// * `reason` - The reason the certificate was revoked, if stated
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn add_revoked(
+        &mut self,
+        serial: &SerialNumber,
+        revocation_time: DateTime<Utc>,
+        reason: Option<CrlReason>,
+    ) -> &mut Self {
+        self.revoked.push((serial.clone(), revocation_time, reason));
+        self
+    }
+
+    /// Point relying parties to a delta CRL, with a Freshest CRL extension
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The HTTP URL of the delta CRL
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn add_freshest_crl(&mut self, url: &str) -> &mut Self {
+        self.freshest_crl.push(url.to_string());
+        self
+    }
+
+    /// Build the extensions of the CRL
+    fn build_extensions(&self) -> Result<Vec<Extension>> {
+        let subject = self.issuer.get_subject();
+        let to_ext = |ext: der::Result<Extension>| ext.map_err(|_| QuantCryptError::BadExtension);
+
+        let mut extensions = vec![to_ext(
+            CrlNumber(to_uint(self.crl_number)?).to_extension(&subject, &[]),
+        )?];
+        if let Some(base) = self.base_crl_number {
+            if base >= self.crl_number {
+                return Err(QuantCryptError::InvalidCrl);
+            }
+            extensions.push(to_ext(
+                BaseCrlNumber(to_uint(base)?).to_extension(&subject, &[]),
+            )?);
+        }
+        if !self.freshest_crl.is_empty() {
+            let mut names = Vec::new();
+            for url in &self.freshest_crl {
+                let uri = Ia5String::new(url).map_err(|_| QuantCryptError::BadExtension)?;
+                names.push(GeneralName::UniformResourceIdentifier(uri));
+            }
+            let freshest = FreshestCrl(vec![DistributionPoint {
+                distribution_point: Some(DistributionPointName::FullName(names)),
+                reasons: None,
+                crl_issuer: None,
+            }]);
+            extensions.push(to_ext(freshest.to_extension(&subject, &[]))?);
+        }
+        Ok(extensions)
+    }
+
+    /// Build and sign the CRL
+    ///
+    /// # Returns
+    ///
+    /// The DER encoded CRL
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCrl` if the base CRL number of a delta CRL is not lower than
+    /// its CRL number, `QuantCryptError::PrivateKeyOutsideUsagePeriod` if the CA key may not
+    /// be used now, or another error if the CRL cannot be signed or encoded
+    pub fn build(&self) -> Result<Vec<u8>> {
+        if !self.issuer.is_private_key_usable() {
+            return Err(QuantCryptError::PrivateKeyOutsideUsagePeriod);
+        }
+
+        let mut revoked = Vec::with_capacity(self.revoked.len());
+        for (serial, revocation_time, reason) in &self.revoked {
+            let crl_entry_extensions = match reason {
+                Some(reason) => Some(vec![reason
+                    .to_extension(&self.issuer.get_subject(), &[])
+                    .map_err(|_| QuantCryptError::BadExtension)?]),
+                None => None,
+            };
+            revoked.push(RevokedCert {
+                serial_number: serial.clone(),
+                revocation_date: to_x509_time(*revocation_time)?,
+                crl_entry_extensions,
+            });
+        }
+
+        let now = Utc::now();
+        let next_update =
+            chrono::Duration::from_std(self.validity).map_err(|_| QuantCryptError::InvalidCrl)?;
+        let signature = AlgorithmIdentifierOwned {
+            oid: self
+                .key
+                .get_oid()
+                .parse()
+                .map_err(|_| QuantCryptError::InvalidOid)?,
+            parameters: None,
+        };
+        let tbs = TbsCertList {
+            version: x509_cert::Version::V2,
+            signature: signature.clone(),
+            issuer: self.issuer.get_subject(),
+            this_update: to_x509_time(now)?,
+            next_update: Some(to_x509_time(now + next_update)?),
+            revoked_certificates: (!revoked.is_empty()).then_some(revoked),
+            crl_extensions: Some(self.build_extensions()?),
+        };
+
+        let tbs_der = tbs.to_der().map_err(|_| QuantCryptError::InvalidCrl)?;
+        let sig = self.key.sign(&tbs_der)?;
+        CertificateList {
+            tbs_cert_list: tbs,
+            signature_algorithm: signature,
+            signature: BitString::from_bytes(&sig).map_err(|_| QuantCryptError::InvalidCrl)?,
+        }
+        .to_der()
+        .map_err(|_| QuantCryptError::InvalidCrl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::pki::fetcher::Fetcher;
+    use crate::pki::revocation::{
+        check_crl, check_crl_with_delta, RevocationChecker, RevocationStatus,
+    };
+
+    const CRL_URL: &str = "http://crl.example.com/ca.crl";
+    const DELTA_URL: &str = "http://crl.example.com/ca-delta.crl";
+
+    struct MockFetcher {
+        resources: HashMap<String, Vec<u8>>,
+    }
+
+    impl Fetcher for MockFetcher {
+        fn get(&self, url: &str) -> Result<Vec<u8>> {
+            self.resources
+                .get(url)
+                .cloned()
+                .ok_or(QuantCryptError::FetchFailed {
+                    url: url.to_string(),
+                })
+        }
+
+        fn post(&self, url: &str, _content_type: &str, _body: &[u8]) -> Result<Vec<u8>> {
+            Err(QuantCryptError::FetchFailed {
+                url: url.to_string(),
+            })
+        }
+    }
+
+    fn issue(ca: Option<&Certificate>, signer: Option<&PrivateKey>) -> (Certificate, PrivateKey) {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let mut builder = match ca {
+            None => CertificateBuilder::new(
+                Profile::Root,
+                None,
+                validity,
+                "CN=ca.example.com".to_string(),
+                pk,
+                &sk,
+            ),
+            Some(ca) => CertificateBuilder::new(
+                Profile::Leaf {
+                    issuer: ca.get_subject(),
+                    enable_key_agreement: false,
+                    enable_key_encipherment: false,
+                },
+                None,
+                validity,
+                "CN=leaf.example.com".to_string(),
+                pk,
+                signer.unwrap(),
+            ),
+        }
+        .unwrap();
+        if ca.is_some() {
+            builder
+                .add_extension(x509_cert::ext::pkix::CrlDistributionPoints(vec![
+                    DistributionPoint {
+                        distribution_point: Some(DistributionPointName::FullName(vec![
+                            GeneralName::UniformResourceIdentifier(
+                                Ia5String::new(CRL_URL).unwrap(),
+                            ),
+                        ])),
+                        reasons: None,
+                        crl_issuer: None,
+                    },
+                ]))
+                .unwrap();
+        }
+        (builder.build().unwrap(), sk)
+    }
+
+    #[test]
+    fn test_delta_crls() {
+        let (ca, ca_sk) = issue(None, None);
+        let (revoked, _) = issue(Some(&ca), Some(&ca_sk));
+        let (held, _) = issue(Some(&ca), Some(&ca_sk));
+        let (good, _) = issue(Some(&ca), Some(&ca_sk));
+        let now = Utc::now();
+
+        let mut base = CrlBuilder::new(&ca, &ca_sk, 10).unwrap();
+        base.add_revoked(
+            &held.get_serial_number(),
+            now,
+            Some(CrlReason::CertificateHold),
+        )
+        .add_freshest_crl(DELTA_URL);
+        let base = base.build().unwrap();
+
+        let mut delta = CrlBuilder::new(&ca, &ca_sk, 11).unwrap();
+        delta
+            .set_delta(10)
+            .set_validity(Duration::from_secs(3600))
+            .add_revoked(
+                &revoked.get_serial_number(),
+                now,
+                Some(CrlReason::KeyCompromise),
+            )
+            .add_revoked(
+                &held.get_serial_number(),
+                now,
+                Some(CrlReason::RemoveFromCRL),
+            );
+        let delta = delta.build().unwrap();
+
+        // The complete CRL alone is out of date
+        assert_eq!(check_crl(&revoked, &ca, &base), Ok(RevocationStatus::Good));
+        assert!(matches!(
+            check_crl(&held, &ca, &base),
+            Ok(RevocationStatus::Revoked { .. })
+        ));
+
+        // Combined with the delta CRL
+        assert!(matches!(
+            check_crl_with_delta(&revoked, &ca, &base, Some(&delta)),
+            Ok(RevocationStatus::Revoked {
+                reason: Some(CrlReason::KeyCompromise),
+                ..
+            })
+        ));
+        assert_eq!(
+            check_crl_with_delta(&held, &ca, &base, Some(&delta)),
+            Ok(RevocationStatus::Good)
+        );
+        assert_eq!(
+            check_crl_with_delta(&good, &ca, &base, Some(&delta)),
+            Ok(RevocationStatus::Good)
+        );
+
+        // The delta CRL can't be used as a complete CRL, nor the other way around
+        assert_eq!(
+            check_crl(&revoked, &ca, &delta),
+            Err(QuantCryptError::InvalidCrl)
+        );
+        assert_eq!(
+            check_crl_with_delta(&revoked, &ca, &base, Some(&base)),
+            Err(QuantCryptError::InvalidCrl)
+        );
+
+        // A delta CRL based on a newer complete CRL doesn't apply
+        let mut newer = CrlBuilder::new(&ca, &ca_sk, 13).unwrap();
+        newer.set_delta(12);
+        let newer = newer.build().unwrap();
+        assert_eq!(
+            check_crl_with_delta(&revoked, &ca, &base, Some(&newer)),
+            Err(QuantCryptError::InvalidCrl)
+        );
+        assert_eq!(
+            CrlBuilder::new(&ca, &ca_sk, 5)
+                .unwrap()
+                .set_delta(5)
+                .build(),
+            Err(QuantCryptError::InvalidCrl)
+        );
+
+        // The checker follows the Freshest CRL extension of the complete CRL
+        let mut fetcher = MockFetcher {
+            resources: HashMap::from([(CRL_URL.to_string(), base), (DELTA_URL.to_string(), delta)]),
+        };
+        let mut checker = RevocationChecker::new(&fetcher);
+        checker.set_use_ocsp(false);
+        assert!(matches!(
+            checker.check(&revoked, &ca).unwrap(),
+            RevocationStatus::Revoked { .. }
+        ));
+        assert_eq!(checker.check(&held, &ca).unwrap(), RevocationStatus::Good);
+
+        // Without the delta CRL, the complete CRL is used alone
+        fetcher.resources.remove(DELTA_URL);
+        let checker = RevocationChecker::new(&fetcher);
+        assert_eq!(
+            checker.check(&revoked, &ca).unwrap(),
+            RevocationStatus::Good
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use crate::certificates::Certificate;
+use crate::dsas::DsaAlgorithm;
+use crate::pki::path_builder::get_weakest_signature;
+use crate::utils::backend_probe::{ProbeReport, ProbedAlgorithm};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Signature algorithms that are never acceptable: MD5, SHA-1 and DSA based ones
+const WEAK_SIGNATURE_OIDS: &[&str] = &[
+    // md5WithRSAEncryption
+    "1.2.840.113549.1.1.4",
+    // sha1WithRSAEncryption
+    "1.2.840.113549.1.1.5",
+    // ecdsa-with-SHA1
+    "1.2.840.10045.4.1",
+    // dsa-with-sha1
+    "1.2.840.10040.4.3",
+];
+
+/// Requirements on the signature algorithms protecting revocation information
+///
+/// A CRL or OCSP response is as important as the certificates it covers: an
+/// attacker able to forge one can keep a revoked certificate alive. Revocation
+/// objects are therefore held to the same standard as the path they protect.
+/// By default, the signature must be made with the key it claims (the signature
+/// algorithm must match the signer's key), must not use a weak hash or
+/// algorithm such as SHA-1, and must be at least as strong against quantum
+/// attackers as the weakest signature on the path.
+///
+/// # Example
+/// ```
+/// use quantcrypt::revocation::CryptoPolicy;
+///
+/// let mut policy = CryptoPolicy::new();
+/// policy
+///     // NIST category 3 at least, even on weaker paths
+///     .set_min_quantum_security_level(3)
+///     // Fail the check rather than ignore the offending CRL or response
+///     .set_fail_on_violation(true);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CryptoPolicy {
+    /// The minimum NIST security category against quantum attackers
+    min_quantum_security_level: u8,
+    /// Whether to require the strength of the weakest signature on the path
+    match_path: bool,
+    /// The signature algorithms that are not acceptable
+    forbidden: Vec<String>,
+    /// Whether a violation fails the check instead of skipping the source
+    fail_on_violation: bool,
+}
+
+impl Default for CryptoPolicy {
+    fn default() -> Self {
+        CryptoPolicy::new()
+    }
+}
+
+impl CryptoPolicy {
+    /// Create a new policy matching the strength of the path and forbidding weak algorithms
+    ///
+    /// # Returns
+    ///
+    /// A new policy
+    pub fn new() -> CryptoPolicy {
+        CryptoPolicy {
+            min_quantum_security_level: 0,
+            match_path: true,
+            forbidden: WEAK_SIGNATURE_OIDS
+                .iter()
+                .map(|oid| oid.to_string())
+                .collect(),
+            fail_on_violation: false,
+        }
+    }
+
+    /// Set the minimum NIST security category against quantum attackers
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The minimum category, 0 to allow classical-only signatures
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_min_quantum_security_level(&mut self, level: u8) -> &mut Self {
+        self.min_quantum_security_level = level;
+        self
+    }
+
+    /// Set whether a signature must be as strong as the weakest signature on the path
+    ///
+    /// # Arguments
+    ///
+    /// * `match_path` - True to require the strength of the path (the default)
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_match_path(&mut self, match_path: bool) -> &mut Self {
+        self.match_path = match_path;
+        self
+    }
+
+    /// Forbid a signature algorithm
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The OID of the signature algorithm
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn forbid(&mut self, oid: &str) -> &mut Self {
+        self.forbidden.push(oid.to_string());
+        self
+    }
+
+    /// Forbid the signature algorithms that failed a backend probe
+    ///
+    /// A revocation source signed with an algorithm that is broken on this host
+    /// can't be verified, and is rejected early with a clear reason.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - The report of the backend probe
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn forbid_failed(&mut self, report: &ProbeReport) -> &mut Self {
+        for failure in &report.failures {
+            if let ProbedAlgorithm::Dsa(alg) = failure.algorithm {
+                self.forbid(&alg.get_oid());
+            }
+        }
+        self
+    }
+
+    /// Set whether a violation fails the check
+    ///
+    /// By default, a CRL or OCSP response violating the policy is ignored like an
+    /// invalid one, and the next source is tried. When failing, the check returns
+    /// `QuantCryptError::CryptoPolicyViolation` instead, which lets callers tell a
+    /// downgraded revocation source apart from an unreachable one.
+    ///
+    /// # Arguments
+    ///
+    /// * `fail_on_violation` - True to fail the check on a violation
+    ///
+    /// # Returns
+    ///
+    /// The policy, for chaining
+    pub fn set_fail_on_violation(&mut self, fail_on_violation: bool) -> &mut Self {
+        self.fail_on_violation = fail_on_violation;
+        self
+    }
+
+    /// Check if a violation fails the check
+    pub(crate) fn is_fail_on_violation(&self) -> bool {
+        self.fail_on_violation
+    }
+
+    /// Get the minimum NIST security category required for a path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path, starting with the end-entity and ending with the trust anchor
+    ///
+    /// # Returns
+    ///
+    /// The minimum category of a signature protecting the path
+    pub fn get_required_level(&self, path: &[Certificate]) -> u8 {
+        let path_level = if self.match_path {
+            get_weakest_signature(path)
+                .map(|weakest| weakest.quantum_security_level)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        self.min_quantum_security_level.max(path_level)
+    }
+
+    /// Check a signature protecting the revocation information of a path
+    ///
+    /// # Arguments
+    ///
+    /// * `signature_oids` - The signature algorithms declared by the signed object
+    /// * `signer` - The certificate whose key made the signature
+    /// * `path` - The path the signed object is used for
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::CryptoPolicyViolation` if the signature does not satisfy the policy
+    pub(crate) fn check_signature(
+        &self,
+        signature_oids: &[String],
+        signer: &Certificate,
+        path: &[Certificate],
+    ) -> Result<()> {
+        let violation = |reason: String| Err(QuantCryptError::CryptoPolicyViolation { reason });
+
+        let key_oid = signer.get_public_key_oid();
+        for oid in signature_oids {
+            if self.forbidden.contains(oid) {
+                return violation(format!("signature algorithm {} is forbidden", oid));
+            }
+            if *oid != key_oid {
+                return violation(format!(
+                    "signature algorithm {} does not match the signer's key {}",
+                    oid, key_oid
+                ));
+            }
+        }
+
+        let level = DsaAlgorithm::from_oid(&key_oid)
+            .map(|alg| alg.get_quantum_security_level())
+            .unwrap_or(0);
+        let required = self.get_required_level(path);
+        if level < required {
+            return violation(format!(
+                "signature algorithm {} has quantum security level {}, {} required",
+                key_oid, level, required
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::backend_probe::ProbeFailure;
+
+    #[test]
+    fn test_crypto_policy() {
+        let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+        let ta = Certificate::from_file(cert_path).unwrap();
+        let oid = ta.get_public_key_oid();
+        let path = [ta.clone(), ta.clone()];
+
+        let mut policy = CryptoPolicy::new();
+        assert_eq!(policy.get_required_level(&path), 2);
+        assert!(policy.check_signature(&[oid.clone()], &ta, &path).is_ok());
+
+        // The declared algorithm must be the one of the key, and must not be weak
+        for declared in ["1.2.840.113549.1.1.5", "1.2.840.10045.4.3.2"] {
+            assert!(matches!(
+                policy.check_signature(&[oid.clone(), declared.to_string()], &ta, &path),
+                Err(QuantCryptError::CryptoPolicyViolation { .. })
+            ));
+        }
+
+        policy.set_min_quantum_security_level(3);
+        assert_eq!(policy.get_required_level(&path), 3);
+        assert!(policy.check_signature(&[oid.clone()], &ta, &path).is_err());
+
+        policy
+            .set_min_quantum_security_level(0)
+            .set_match_path(false);
+        assert_eq!(policy.get_required_level(&path), 0);
+        policy.forbid(&oid);
+        assert!(policy.check_signature(&[oid.clone()], &ta, &path).is_err());
+
+        // Algorithms failing a backend probe are forbidden
+        let mut policy = CryptoPolicy::new();
+        let report = ProbeReport {
+            operational: vec![],
+            failures: vec![ProbeFailure {
+                algorithm: ProbedAlgorithm::Dsa(DsaAlgorithm::from_oid(&oid).unwrap()),
+                reason: "the backend panicked".to_string(),
+            }],
+        };
+        policy.forbid_failed(&report);
+        assert!(policy.check_signature(&[oid], &ta, &path).is_err());
+    }
+}
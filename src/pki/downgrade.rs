@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::certificates::Certificate;
+use crate::dsas::DsaAlgorithm;
+use crate::pki::path_builder::get_weakest_signature;
+use crate::utils::verification_report::unix_now;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The kind of an authentication downgrade
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DowngradeKind {
+    /// The peer holds a PQ or composite key, but a classical-only algorithm was negotiated
+    ClassicalOnly,
+    /// A PQ algorithm was negotiated, but with a lower security category than the key of the peer
+    WeakerAlgorithm,
+}
+
+/// The outcome of a downgrade check
+///
+/// Reports can be serialized with any serde format, e.g. to be sent to a
+/// monitoring pipeline tracking the PQ rollout.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DowngradeReport {
+    /// The downgrade that was detected, if any
+    pub downgrade: Option<DowngradeKind>,
+    /// The OID of the key algorithm of the peer's end-entity certificate
+    pub peer_key_oid: String,
+    /// The OID of the negotiated signature algorithm
+    pub negotiated_oid: String,
+    /// The NIST security category of the peer's key against quantum attackers, 0 if not quantum-safe
+    pub peer_quantum_security_level: u8,
+    /// The NIST security category of the negotiated algorithm, 0 if not quantum-safe
+    pub negotiated_quantum_security_level: u8,
+    /// Whether every signature on the peer's chain is quantum-safe
+    pub chain_quantum_safe: bool,
+    /// When the check took place, in seconds since the Unix epoch
+    pub detected_at: u64,
+}
+
+impl DowngradeReport {
+    /// Check if a downgrade was detected
+    ///
+    /// # Returns
+    ///
+    /// True if the negotiated algorithm is weaker than the key of the peer
+    pub fn is_downgrade(&self) -> bool {
+        self.downgrade.is_some()
+    }
+}
+
+/// Get the NIST security category of an algorithm against quantum attackers
+fn get_quantum_security_level(oid: &str) -> u8 {
+    DsaAlgorithm::from_oid(oid)
+        .map(|alg| alg.get_quantum_security_level())
+        .unwrap_or(0)
+}
+
+/// Detect a downgrade of the authentication of a peer
+///
+/// A peer holding a PQ or composite certificate should authenticate with it.
+/// If a classical-only algorithm was negotiated instead (e.g. the classical
+/// component of a composite key, or a classical certificate the peer also holds),
+/// an attacker with a quantum computer could impersonate the peer. This is a
+/// downgrade, either of the configuration or by an active attacker.
+///
+/// The chain is not validated, it is expected to have been validated already.
+///
+/// # Arguments
+///
+/// * `chain` - The certificate chain of the peer, starting with its end-entity certificate
+/// * `negotiated_oid` - The OID of the signature algorithm the peer authenticated with
+///
+/// # Returns
+///
+/// The downgrade report
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidCertificate` if the chain is empty
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{detect_downgrade, Certificate, DowngradeKind};
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let cert = Certificate::from_file(cert_path).unwrap();
+///
+/// // ecdsa-with-SHA256 was negotiated with a peer holding a composite certificate
+/// let report = detect_downgrade(&[cert], "1.2.840.10045.4.3.2").unwrap();
+/// assert_eq!(report.downgrade, Some(DowngradeKind::ClassicalOnly));
+/// ```
+pub fn detect_downgrade(chain: &[Certificate], negotiated_oid: &str) -> Result<DowngradeReport> {
+    let peer = chain.first().ok_or(QuantCryptError::InvalidCertificate)?;
+    let peer_key_oid = peer.get_public_key_oid();
+    let peer_level = get_quantum_security_level(&peer_key_oid);
+    let negotiated_level = get_quantum_security_level(negotiated_oid);
+
+    let downgrade = if peer_level == 0 || negotiated_level >= peer_level {
+        None
+    } else if negotiated_level == 0 {
+        Some(DowngradeKind::ClassicalOnly)
+    } else {
+        Some(DowngradeKind::WeakerAlgorithm)
+    };
+
+    Ok(DowngradeReport {
+        downgrade,
+        peer_key_oid,
+        negotiated_oid: negotiated_oid.to_string(),
+        peer_quantum_security_level: peer_level,
+        negotiated_quantum_security_level: negotiated_level,
+        chain_quantum_safe: get_weakest_signature(chain)
+            .map(|weakest| weakest.is_quantum_safe())
+            .unwrap_or(true),
+        detected_at: unix_now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_downgrade() {
+        let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+        let cert = Certificate::from_file(cert_path).unwrap();
+        let composite_oid = cert.get_public_key_oid();
+
+        let report = detect_downgrade(&[cert.clone()], &composite_oid).unwrap();
+        assert!(!report.is_downgrade());
+        assert_eq!(report.peer_quantum_security_level, 2);
+        assert!(report.chain_quantum_safe);
+
+        // A stronger algorithm is not a downgrade
+        let report = detect_downgrade(&[cert.clone()], &DsaAlgorithm::MlDsa87.get_oid()).unwrap();
+        assert!(!report.is_downgrade());
+
+        let report = detect_downgrade(&[cert.clone()], "1.2.840.10045.4.3.2").unwrap();
+        assert_eq!(report.downgrade, Some(DowngradeKind::ClassicalOnly));
+        assert_eq!(report.negotiated_quantum_security_level, 0);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"downgrade\":\"ClassicalOnly\""));
+
+        let report = detect_downgrade(&[cert], &DsaAlgorithm::SlhDsaShake128f.get_oid()).unwrap();
+        assert_eq!(report.downgrade, Some(DowngradeKind::WeakerAlgorithm));
+
+        assert!(detect_downgrade(&[], &composite_oid).is_err());
+    }
+}
@@ -0,0 +1,667 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use const_oid::db::rfc6960;
+use der::Decode;
+use x509_cert::crl::CertificateList;
+use x509_ocsp::{BasicOcspResponse, OcspRequest, OcspResponse};
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The transport used to retrieve revocation information and certificates
+///
+/// Implement this trait to plug in your own HTTP stack, a proxy, or a local
+/// mirror. With the `http-fetcher` feature, `HttpFetcher` provides a default
+/// implementation.
+pub trait Fetcher {
+    /// Retrieve a resource with an HTTP GET request
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the resource
+    ///
+    /// # Returns
+    ///
+    /// The body of the response
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::FetchFailed` if the resource cannot be retrieved
+    fn get(&self, url: &str) -> Result<Vec<u8>>;
+
+    /// Send a request with an HTTP POST request
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to post to
+    /// * `content_type` - The content type of the body
+    /// * `body` - The body of the request
+    ///
+    /// # Returns
+    ///
+    /// The body of the response
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::FetchFailed` if the request fails
+    fn post(&self, url: &str, content_type: &str, body: &[u8]) -> Result<Vec<u8>>;
+
+    /// Retrieve a resource with an HTTP GET request, along with how long it may be cached
+    ///
+    /// The default implementation calls `get` and doesn't limit the lifetime.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the resource
+    ///
+    /// # Returns
+    ///
+    /// The body of the response, and the max-age of its `Cache-Control` header if any
+    fn get_with_max_age(&self, url: &str) -> Result<(Vec<u8>, Option<Duration>)> {
+        Ok((self.get(url)?, None))
+    }
+
+    /// Send a request with an HTTP POST request, along with how long the response may be cached
+    ///
+    /// The default implementation calls `post` and doesn't limit the lifetime.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to post to
+    /// * `content_type` - The content type of the body
+    /// * `body` - The body of the request
+    ///
+    /// # Returns
+    ///
+    /// The body of the response, and the max-age of its `Cache-Control` header if any
+    fn post_with_max_age(
+        &self,
+        url: &str,
+        content_type: &str,
+        body: &[u8],
+    ) -> Result<(Vec<u8>, Option<Duration>)> {
+        Ok((self.post(url, content_type, body)?, None))
+    }
+}
+
+/// The default maximum number of responses held by a `CachingFetcher`
+const DEFAULT_MAX_ENTRIES: usize = 256;
+
+/// A cached response
+struct CacheEntry {
+    expires_at: Instant,
+    last_used: u64,
+    body: Vec<u8>,
+}
+
+/// The cached responses, with a counter ordering their uses
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<(String, Vec<u8>), CacheEntry>,
+    uses: u64,
+}
+
+/// Get the time left until the nextUpdate of a CRL or OCSP response
+///
+/// # Arguments
+///
+/// * `body` - A DER encoded CRL or OCSP response
+///
+/// # Returns
+///
+/// The time left until nextUpdate, zero if it has passed, or None if the body is
+/// neither or has no nextUpdate. For an OCSP response, the earliest nextUpdate of
+/// its single responses is used.
+fn time_to_next_update(body: &[u8]) -> Option<Duration> {
+    let next_update = if let Ok(crl) = CertificateList::from_der(body) {
+        crl.tbs_cert_list.next_update?.to_system_time()
+    } else {
+        let response = OcspResponse::from_der(body).ok()?.response_bytes?;
+        if response.response_type != rfc6960::ID_PKIX_OCSP_BASIC {
+            return None;
+        }
+        BasicOcspResponse::from_der(response.response.as_bytes())
+            .ok()?
+            .tbs_response_data
+            .responses
+            .iter()
+            .filter_map(|single| single.next_update.as_ref())
+            .map(|time| time.0.to_system_time())
+            .min()?
+    };
+    Some(
+        next_update
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Check if a POST body is an OCSP request carrying a nonce
+///
+/// The response echoes the nonce, so it only answers this one request.
+fn has_ocsp_nonce(body: &[u8]) -> bool {
+    OcspRequest::from_der(body).is_ok_and(|request| {
+        request
+            .tbs_request
+            .request_extensions
+            .iter()
+            .flatten()
+            .any(|ext| ext.extn_id == rfc6960::ID_PKIX_OCSP_NONCE)
+    })
+}
+
+/// A fetcher that caches the responses of another fetcher
+///
+/// Responses are cached per URL (and body, for POST requests). A response expires
+/// after the time to live of the fetcher, or earlier if the server limits it with
+/// a `Cache-Control` max-age or if it is a CRL or OCSP response whose nextUpdate
+/// comes first. At most 256 responses are held by default, beyond that the least
+/// recently used one is evicted.
+///
+/// Failed requests are not cached, and neither are OCSP requests carrying a nonce,
+/// since the response to such a request only answers that request.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use quantcrypt::revocation::{CachingFetcher, Fetcher};
+/// use quantcrypt::QuantCryptError;
+///
+/// struct Offline;
+///
+/// impl Fetcher for Offline {
+///     fn get(&self, url: &str) -> Result<Vec<u8>, QuantCryptError> {
+///         Err(QuantCryptError::FetchFailed { url: url.to_string() })
+///     }
+///
+///     fn post(&self, url: &str, _: &str, _: &[u8]) -> Result<Vec<u8>, QuantCryptError> {
+///         Err(QuantCryptError::FetchFailed { url: url.to_string() })
+///     }
+/// }
+///
+/// let fetcher = CachingFetcher::new(Offline, Duration::from_secs(300));
+/// assert!(fetcher.get("http://crl.example.com/ca.crl").is_err());
+/// ```
+pub struct CachingFetcher<F: Fetcher> {
+    inner: F,
+    ttl: Duration,
+    max_entries: usize,
+    cache: Mutex<Cache>,
+}
+
+impl<F: Fetcher> CachingFetcher<F> {
+    /// Create a new caching fetcher
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The fetcher used on a cache miss
+    /// * `ttl` - The longest time a response is served from the cache
+    ///
+    /// # Returns
+    ///
+    /// A new caching fetcher
+    pub fn new(inner: F, ttl: Duration) -> CachingFetcher<F> {
+        CachingFetcher {
+            inner,
+            ttl,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            cache: Mutex::new(Cache::default()),
+        }
+    }
+
+    /// Set the maximum number of cached responses
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - The maximum number of responses, 0 disables caching
+    ///
+    /// # Returns
+    ///
+    /// The fetcher, for chaining
+    pub fn set_max_entries(&mut self, max_entries: usize) -> &mut Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Remove all cached responses
+    pub fn clear(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.entries.clear();
+        }
+    }
+
+    /// Serve a request from the cache, or forward it to the inner fetcher
+    fn cached(
+        &self,
+        url: &str,
+        body: &[u8],
+        fetch: impl FnOnce(&F) -> Result<(Vec<u8>, Option<Duration>)>,
+    ) -> Result<Vec<u8>> {
+        let key = (url.to_string(), body.to_vec());
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.uses += 1;
+            let uses = cache.uses;
+            if let Some(entry) = cache.entries.get_mut(&key) {
+                if Instant::now() < entry.expires_at {
+                    entry.last_used = uses;
+                    return Ok(entry.body.clone());
+                }
+            }
+        }
+
+        let (response, max_age) = fetch(&self.inner)?;
+        let lifetime = [Some(self.ttl), max_age, time_to_next_update(&response)]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(Duration::ZERO);
+        if lifetime.is_zero() || self.max_entries == 0 {
+            return Ok(response);
+        }
+
+        if let Ok(mut cache) = self.cache.lock() {
+            let now = Instant::now();
+            cache.entries.retain(|_, entry| now < entry.expires_at);
+            while cache.entries.len() >= self.max_entries {
+                let lru = cache
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone());
+                match lru {
+                    Some(lru) => cache.entries.remove(&lru),
+                    None => break,
+                };
+            }
+            let last_used = cache.uses;
+            cache.entries.insert(
+                key,
+                CacheEntry {
+                    expires_at: now + lifetime,
+                    last_used,
+                    body: response.clone(),
+                },
+            );
+        }
+        Ok(response)
+    }
+}
+
+impl<F: Fetcher> Fetcher for CachingFetcher<F> {
+    fn get(&self, url: &str) -> Result<Vec<u8>> {
+        self.cached(url, &[], |inner| inner.get_with_max_age(url))
+    }
+
+    fn post(&self, url: &str, content_type: &str, body: &[u8]) -> Result<Vec<u8>> {
+        if has_ocsp_nonce(body) {
+            return self.inner.post(url, content_type, body);
+        }
+        self.cached(url, body, |inner| {
+            inner.post_with_max_age(url, content_type, body)
+        })
+    }
+}
+
+/// The default upper bound on the size of a response, in bytes
+#[cfg(feature = "http-fetcher")]
+const DEFAULT_MAX_RESPONSE_LEN: u64 = 64 * 1024 * 1024;
+
+/// A fetcher using plain HTTP, as used for CRL distribution points, OCSP and AIA
+///
+/// Only `http://` URLs are supported, as recommended by RFC 5280 and RFC 6960
+/// to avoid circular dependencies on certificate validation.
+#[cfg(feature = "http-fetcher")]
+pub struct HttpFetcher {
+    agent: ureq::Agent,
+    max_response_len: u64,
+}
+
+#[cfg(feature = "http-fetcher")]
+impl HttpFetcher {
+    /// Create a new HTTP fetcher
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The timeout of a whole request, including connecting and reading the response
+    ///
+    /// # Returns
+    ///
+    /// A new HTTP fetcher
+    pub fn new(timeout: Duration) -> HttpFetcher {
+        HttpFetcher {
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+            max_response_len: DEFAULT_MAX_RESPONSE_LEN,
+        }
+    }
+
+    /// Set the maximum size of a response
+    ///
+    /// # Arguments
+    ///
+    /// * `max_response_len` - The maximum size of a response in bytes
+    ///
+    /// # Returns
+    ///
+    /// The fetcher, for chaining
+    pub fn set_max_response_len(&mut self, max_response_len: u64) -> &mut Self {
+        self.max_response_len = max_response_len;
+        self
+    }
+
+    /// Read the body of a response, bounded by the maximum response size
+    ///
+    /// # Returns
+    ///
+    /// The body, and how long it may be cached according to `Cache-Control`
+    fn read_body(
+        &self,
+        url: &str,
+        response: ureq::Response,
+    ) -> Result<(Vec<u8>, Option<Duration>)> {
+        use std::io::Read;
+
+        let max_age = response.header("Cache-Control").and_then(parse_max_age);
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .take(self.max_response_len + 1)
+            .read_to_end(&mut body)
+            .map_err(|_| QuantCryptError::FetchFailed {
+                url: url.to_string(),
+            })?;
+        if body.len() as u64 > self.max_response_len {
+            return Err(QuantCryptError::FetchFailed {
+                url: url.to_string(),
+            });
+        }
+        Ok((body, max_age))
+    }
+}
+
+/// Get how long a response may be cached from its `Cache-Control` header
+///
+/// # Arguments
+///
+/// * `cache_control` - The value of the header
+///
+/// # Returns
+///
+/// Zero for `no-store` and `no-cache`, the max-age if present, None otherwise
+#[cfg(any(feature = "http-fetcher", test))]
+fn parse_max_age(cache_control: &str) -> Option<Duration> {
+    let mut max_age = None;
+    for directive in cache_control
+        .split(',')
+        .map(|d| d.trim().to_ascii_lowercase())
+    {
+        if directive == "no-store" || directive == "no-cache" {
+            return Some(Duration::ZERO);
+        }
+        if let Some(seconds) = directive.strip_prefix("max-age=") {
+            max_age = Some(Duration::from_secs(seconds.trim_matches('"').parse().ok()?));
+        }
+    }
+    max_age
+}
+
+#[cfg(feature = "http-fetcher")]
+impl Default for HttpFetcher {
+    fn default() -> Self {
+        HttpFetcher::new(Duration::from_secs(10))
+    }
+}
+
+#[cfg(feature = "http-fetcher")]
+impl Fetcher for HttpFetcher {
+    fn get(&self, url: &str) -> Result<Vec<u8>> {
+        Ok(self.get_with_max_age(url)?.0)
+    }
+
+    fn post(&self, url: &str, content_type: &str, body: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.post_with_max_age(url, content_type, body)?.0)
+    }
+
+    fn get_with_max_age(&self, url: &str) -> Result<(Vec<u8>, Option<Duration>)> {
+        let response = self
+            .agent
+            .get(url)
+            .call()
+            .map_err(|_| QuantCryptError::FetchFailed {
+                url: url.to_string(),
+            })?;
+        self.read_body(url, response)
+    }
+
+    fn post_with_max_age(
+        &self,
+        url: &str,
+        content_type: &str,
+        body: &[u8],
+    ) -> Result<(Vec<u8>, Option<Duration>)> {
+        let response = self
+            .agent
+            .post(url)
+            .set("Content-Type", content_type)
+            .send_bytes(body)
+            .map_err(|_| QuantCryptError::FetchFailed {
+                url: url.to_string(),
+            })?;
+        self.read_body(url, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, Certificate, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::keys::PrivateKey;
+    use crate::pki::crl_builder::CrlBuilder;
+    use crate::pki::revocation::build_ocsp_request;
+    use std::cell::Cell;
+
+    struct CountingFetcher {
+        calls: Cell<usize>,
+    }
+
+    /// A fetcher serving a fixed body with a max-age
+    struct FixedFetcher {
+        calls: Cell<usize>,
+        body: Vec<u8>,
+        max_age: Option<Duration>,
+    }
+
+    impl Fetcher for FixedFetcher {
+        fn get(&self, url: &str) -> Result<Vec<u8>> {
+            Ok(self.get_with_max_age(url)?.0)
+        }
+
+        fn post(&self, url: &str, content_type: &str, body: &[u8]) -> Result<Vec<u8>> {
+            Ok(self.post_with_max_age(url, content_type, body)?.0)
+        }
+
+        fn get_with_max_age(&self, _url: &str) -> Result<(Vec<u8>, Option<Duration>)> {
+            self.calls.set(self.calls.get() + 1);
+            Ok((self.body.clone(), self.max_age))
+        }
+
+        fn post_with_max_age(
+            &self,
+            _url: &str,
+            _content_type: &str,
+            _body: &[u8],
+        ) -> Result<(Vec<u8>, Option<Duration>)> {
+            self.calls.set(self.calls.get() + 1);
+            Ok((self.body.clone(), self.max_age))
+        }
+    }
+
+    fn fixed(body: Vec<u8>, max_age: Option<Duration>) -> CachingFetcher<FixedFetcher> {
+        CachingFetcher::new(
+            FixedFetcher {
+                calls: Cell::new(0),
+                body,
+                max_age,
+            },
+            Duration::from_secs(60),
+        )
+    }
+
+    fn issue_ca() -> (Certificate, PrivateKey) {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=ca.example.com".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        (cert, sk)
+    }
+
+    impl Fetcher for CountingFetcher {
+        fn get(&self, url: &str) -> Result<Vec<u8>> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(url.as_bytes().to_vec())
+        }
+
+        fn post(&self, url: &str, _content_type: &str, body: &[u8]) -> Result<Vec<u8>> {
+            self.calls.set(self.calls.get() + 1);
+            Ok([url.as_bytes(), body].concat())
+        }
+    }
+
+    #[test]
+    fn test_caching_fetcher() {
+        let fetcher = CachingFetcher::new(
+            CountingFetcher {
+                calls: Cell::new(0),
+            },
+            Duration::from_secs(60),
+        );
+        assert_eq!(fetcher.get("http://a").unwrap(), b"http://a");
+        assert_eq!(fetcher.get("http://a").unwrap(), b"http://a");
+        assert_eq!(fetcher.inner.calls.get(), 1);
+
+        // POST requests are cached per body
+        fetcher.post("http://a", "text/plain", b"1").unwrap();
+        fetcher.post("http://a", "text/plain", b"1").unwrap();
+        fetcher.post("http://a", "text/plain", b"2").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 3);
+
+        fetcher.clear();
+        fetcher.get("http://a").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 4);
+
+        // A zero TTL disables caching
+        let fetcher = CachingFetcher::new(
+            CountingFetcher {
+                calls: Cell::new(0),
+            },
+            Duration::ZERO,
+        );
+        fetcher.get("http://a").unwrap();
+        fetcher.get("http://a").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 2);
+    }
+
+    #[test]
+    fn test_caching_fetcher_capacity() {
+        let mut fetcher = CachingFetcher::new(
+            CountingFetcher {
+                calls: Cell::new(0),
+            },
+            Duration::from_secs(60),
+        );
+        fetcher.set_max_entries(2);
+        fetcher.get("http://a").unwrap();
+        fetcher.get("http://b").unwrap();
+        // a is now more recently used than b, so b is evicted for c
+        fetcher.get("http://a").unwrap();
+        fetcher.get("http://c").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 3);
+        fetcher.get("http://a").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 3);
+        fetcher.get("http://b").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 4);
+        assert!(fetcher.cache.lock().unwrap().entries.len() <= 2);
+
+        fetcher.set_max_entries(0);
+        fetcher.clear();
+        fetcher.get("http://a").unwrap();
+        fetcher.get("http://a").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 6);
+    }
+
+    #[test]
+    fn test_caching_fetcher_lifetime() {
+        // A max-age of zero, or no-store, disables caching of the response
+        let fetcher = fixed(b"body".to_vec(), Some(Duration::ZERO));
+        fetcher.get("http://a").unwrap();
+        fetcher.get("http://a").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 2);
+
+        let fetcher = fixed(b"body".to_vec(), Some(Duration::from_secs(30)));
+        fetcher.get("http://a").unwrap();
+        fetcher.get("http://a").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 1);
+
+        assert_eq!(
+            parse_max_age("public, max-age=30"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_max_age("no-store"), Some(Duration::ZERO));
+        assert_eq!(parse_max_age("public"), None);
+
+        // A CRL expires at nextUpdate
+        let (ca, ca_sk) = issue_ca();
+        let mut crl = CrlBuilder::new(&ca, &ca_sk, 1).unwrap();
+        crl.set_validity(Duration::from_secs(10));
+        let crl = crl.build().unwrap();
+        let left = time_to_next_update(&crl).unwrap();
+        assert!(left <= Duration::from_secs(10));
+        let fetcher = fixed(crl, None);
+        fetcher.get("http://a").unwrap();
+        let cache = fetcher.cache.lock().unwrap();
+        let entry = cache.entries.values().next().unwrap();
+        assert!(entry.expires_at <= Instant::now() + Duration::from_secs(10));
+        drop(cache);
+
+        // A past nextUpdate isn't cached at all
+        let mut crl = CrlBuilder::new(&ca, &ca_sk, 2).unwrap();
+        crl.set_validity(Duration::ZERO);
+        let crl = crl.build().unwrap();
+        let fetcher = fixed(crl, None);
+        std::thread::sleep(Duration::from_millis(1100));
+        fetcher.get("http://a").unwrap();
+        fetcher.get("http://a").unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 2);
+
+        assert_eq!(time_to_next_update(b"not a crl"), None);
+    }
+
+    #[test]
+    fn test_caching_fetcher_ocsp_nonce() {
+        let (ca, _) = issue_ca();
+        let request = build_ocsp_request(&ca, &ca, &[7u8; 16]).unwrap();
+        assert!(has_ocsp_nonce(&request));
+        assert!(!has_ocsp_nonce(b"not a request"));
+
+        let fetcher = fixed(b"response".to_vec(), None);
+        let content_type = "application/ocsp-request";
+        fetcher.post("http://ocsp", content_type, &request).unwrap();
+        fetcher.post("http://ocsp", content_type, &request).unwrap();
+        assert_eq!(fetcher.inner.calls.get(), 2);
+    }
+}
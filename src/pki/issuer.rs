@@ -0,0 +1,486 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand_core::{OsRng, RngCore};
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::TbsCertificate;
+
+use crate::asn1::cert_builder::{CertValidity, CertificateBuilder, Profile};
+use crate::certificates::Certificate;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The number of random serial numbers tried before giving up
+const MAX_SERIAL_ATTEMPTS: usize = 8;
+
+/// A check run on a certificate before it is signed
+type Lint<'a> = Box<dyn Fn(&TbsCertificate) -> Result<()> + 'a>;
+
+/// A hook run on every issued certificate
+type AuditHook<'a> = Box<dyn Fn(&IssuanceRecord) -> Result<()> + 'a>;
+
+/// The database of the serial numbers issued by a CA
+///
+/// Implement this trait on top of your own database to share serial numbers
+/// across processes and restarts. `MemorySerialStore` keeps them in memory.
+pub trait SerialStore {
+    /// Reserve a serial number, atomically
+    ///
+    /// # Arguments
+    ///
+    /// * `serial` - The serial number to reserve
+    ///
+    /// # Returns
+    ///
+    /// True if the serial number was reserved, false if it was already taken
+    fn reserve(&self, serial: &SerialNumber) -> Result<bool>;
+}
+
+/// A serial store keeping the serial numbers in memory
+#[derive(Default)]
+pub struct MemorySerialStore {
+    serials: Mutex<HashSet<Vec<u8>>>,
+}
+
+impl MemorySerialStore {
+    /// Create a new, empty serial store
+    pub fn new() -> MemorySerialStore {
+        MemorySerialStore::default()
+    }
+
+    /// Get the number of reserved serial numbers
+    pub fn len(&self) -> usize {
+        self.serials
+            .lock()
+            .map(|serials| serials.len())
+            .unwrap_or(0)
+    }
+
+    /// Check if no serial number has been reserved
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SerialStore for MemorySerialStore {
+    fn reserve(&self, serial: &SerialNumber) -> Result<bool> {
+        let mut serials = self.serials.lock().map_err(|_| QuantCryptError::Unknown)?;
+        Ok(serials.insert(serial.as_bytes().to_vec()))
+    }
+}
+
+/// The record of an issued certificate, passed to the audit hooks
+#[derive(Clone)]
+pub struct IssuanceRecord {
+    certificate: Certificate,
+    issued_at: DateTime<Utc>,
+}
+
+impl IssuanceRecord {
+    /// Get the issued certificate
+    pub fn get_certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    /// Get the serial number of the issued certificate
+    pub fn get_serial_number(&self) -> SerialNumber {
+        self.certificate.get_serial_number()
+    }
+
+    /// Get the subject of the issued certificate
+    pub fn get_subject(&self) -> String {
+        self.certificate.get_subject().to_string()
+    }
+
+    /// Get the time the certificate was issued
+    pub fn get_issued_at(&self) -> DateTime<Utc> {
+        self.issued_at
+    }
+}
+
+/// A CA key and certificate, with the policy and hooks applied to everything it issues
+///
+/// Every certificate is issued with a serial number reserved in a `SerialStore`,
+/// so a serial number is never used twice. Before signing, the certificate is
+/// checked against the policy of the context and the registered lints; after
+/// signing, the audit hooks receive an `IssuanceRecord`. If a lint fails,
+/// nothing is signed. If an audit hook fails, the certificate is not returned,
+/// but its serial number stays reserved.
+///
+/// The policy always requires the certificate to be issued under the subject of
+/// the CA and to expire before the CA certificate.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{
+///     CertValidity, CertificateBuilder, IssuerContext, MemorySerialStore, Profile,
+/// };
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65).generate().unwrap();
+/// let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+/// let ca = CertificateBuilder::new(Profile::Root, None, validity, "CN=ca".to_string(), pk, &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// let store = MemorySerialStore::new();
+/// let mut issuer = IssuerContext::new(&ca, &sk, &store).unwrap();
+/// issuer.add_audit_hook(|record| {
+///     println!("Issued {} to {}", record.get_serial_number(), record.get_subject());
+///     Ok(())
+/// });
+///
+/// let (leaf_pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let validity = CertValidity::new(None, "2030-01-01T00:00:00Z").unwrap();
+/// let profile = Profile::Leaf {
+///     issuer: ca.get_subject(),
+///     enable_key_agreement: false,
+///     enable_key_encipherment: false,
+/// };
+/// let leaf = issuer
+///     .issue(profile, None, validity, "CN=leaf".to_string(), leaf_pk, |_| Ok(()))
+///     .unwrap();
+/// assert!(ca.verify_child(&leaf).unwrap());
+/// assert_eq!(store.len(), 1);
+/// ```
+pub struct IssuerContext<'a> {
+    cert: Certificate,
+    key: &'a PrivateKey,
+    store: &'a dyn SerialStore,
+    max_validity: Option<Duration>,
+    allowed_key_oids: Option<Vec<String>>,
+    lints: Vec<Lint<'a>>,
+    audit_hooks: Vec<AuditHook<'a>>,
+}
+
+impl<'a> IssuerContext<'a> {
+    /// Create a new issuer context
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate of the CA
+    /// * `key` - The private key of the CA
+    /// * `store` - The store the serial numbers are reserved in
+    ///
+    /// # Returns
+    ///
+    /// The issuer context, without any restriction beyond the default policy
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if `cert` is not a CA certificate or doesn't match `key`
+    pub fn new(
+        cert: &Certificate,
+        key: &'a PrivateKey,
+        store: &'a dyn SerialStore,
+    ) -> Result<IssuerContext<'a>> {
+        if !cert.is_ca() || cert.get_public_key_oid() != key.get_oid() {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        Ok(IssuerContext {
+            cert: cert.clone(),
+            key,
+            store,
+            max_validity: None,
+            allowed_key_oids: None,
+            lints: Vec::new(),
+            audit_hooks: Vec::new(),
+        })
+    }
+
+    /// Get the certificate of the CA
+    pub fn get_certificate(&self) -> &Certificate {
+        &self.cert
+    }
+
+    /// Set the maximum validity period of the issued certificates
+    ///
+    /// # Arguments
+    ///
+    /// * `max_validity` - The maximum time between not before and not after
+    ///
+    /// # Returns
+    ///
+    /// The issuer context, for chaining
+    pub fn set_max_validity(&mut self, max_validity: Duration) -> &mut Self {
+        self.max_validity = Some(max_validity);
+        self
+    }
+
+    /// Restrict the public key algorithms of the issued certificates
+    ///
+    /// # Arguments
+    ///
+    /// * `oids` - The OIDs of the allowed public key algorithms
+    ///
+    /// # Returns
+    ///
+    /// The issuer context, for chaining
+    pub fn set_allowed_key_algorithms(&mut self, oids: Vec<String>) -> &mut Self {
+        self.allowed_key_oids = Some(oids);
+        self
+    }
+
+    /// Add a lint, run on every certificate before it is signed
+    ///
+    /// # Arguments
+    ///
+    /// * `lint` - Inspects the TBS certificate and returns an error to refuse issuance
+    ///
+    /// # Returns
+    ///
+    /// The issuer context, for chaining
+    pub fn add_lint(&mut self, lint: impl Fn(&TbsCertificate) -> Result<()> + 'a) -> &mut Self {
+        self.lints.push(Box::new(lint));
+        self
+    }
+
+    /// Add an audit hook, run on every certificate after it is signed
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Records the issuance, e.g. in an audit log or a database
+    ///
+    /// # Returns
+    ///
+    /// The issuer context, for chaining
+    pub fn add_audit_hook(
+        &mut self,
+        hook: impl Fn(&IssuanceRecord) -> Result<()> + 'a,
+    ) -> &mut Self {
+        self.audit_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Reserve a serial number, either the requested one or a random one
+    fn reserve_serial(&self, requested: Option<[u8; 20]>) -> Result<[u8; 20]> {
+        if let Some(serial) = requested {
+            let serial_number =
+                SerialNumber::new(&serial).map_err(|_| QuantCryptError::BadSerialNumber)?;
+            return match self.store.reserve(&serial_number)? {
+                true => Ok(serial),
+                false => Err(QuantCryptError::DuplicateSerialNumber),
+            };
+        }
+        for _ in 0..MAX_SERIAL_ATTEMPTS {
+            let mut serial = [0u8; 20];
+            OsRng.fill_bytes(&mut serial);
+            serial[0] = 0x01;
+            let serial_number =
+                SerialNumber::new(&serial).map_err(|_| QuantCryptError::BadSerialNumber)?;
+            if self.store.reserve(&serial_number)? {
+                return Ok(serial);
+            }
+        }
+        Err(QuantCryptError::DuplicateSerialNumber)
+    }
+
+    /// Check a TBS certificate against the policy of the context
+    fn check_policy(&self, tbs: &TbsCertificate) -> Result<()> {
+        let violation = |reason: &str| {
+            Err(QuantCryptError::PolicyViolation {
+                reason: reason.to_string(),
+            })
+        };
+
+        if tbs.issuer != self.cert.get_subject() {
+            return violation("issuer is not the subject of the CA");
+        }
+        let not_before = tbs.validity.not_before.to_system_time();
+        let not_after = tbs.validity.not_after.to_system_time();
+        let ca_not_after = self
+            .cert
+            .get_inner()
+            .tbs_certificate
+            .validity
+            .not_after
+            .to_system_time();
+        if not_after > ca_not_after {
+            return violation("not after is later than the not after of the CA");
+        }
+        if let Some(max_validity) = self.max_validity {
+            let validity = not_after.duration_since(not_before).unwrap_or_default();
+            if validity > max_validity {
+                return violation("validity period is too long");
+            }
+        }
+        if let Some(oids) = &self.allowed_key_oids {
+            let oid = tbs.subject_public_key_info.algorithm.oid.to_string();
+            if !oids.contains(&oid) {
+                return violation("public key algorithm is not allowed");
+            }
+        }
+        Ok(())
+    }
+
+    /// Issue a certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile of the certificate
+    /// * `serial_number` - The serial number to use. If None, a random unused one is allocated.
+    /// * `validity` - The validity period of the certificate
+    /// * `subject` - The subject of the certificate
+    /// * `public_key` - The public key of the certificate
+    /// * `customize` - Customizes the builder before the certificate is built, e.g. to add extensions
+    ///
+    /// # Returns
+    ///
+    /// The signed certificate
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::PrivateKeyOutsideUsagePeriod` if the CA key may not be used now,
+    /// `QuantCryptError::DuplicateSerialNumber` if the serial number was already issued,
+    /// `QuantCryptError::PolicyViolation` if the certificate violates the policy, or the
+    /// error returned by `customize`, a lint or an audit hook
+    pub fn issue(
+        &self,
+        profile: Profile,
+        serial_number: Option<[u8; 20]>,
+        validity: CertValidity,
+        subject: String,
+        public_key: PublicKey,
+        customize: impl FnOnce(&mut CertificateBuilder<'a>) -> Result<()>,
+    ) -> Result<Certificate> {
+        if !self.cert.is_private_key_usable() {
+            return Err(QuantCryptError::PrivateKeyOutsideUsagePeriod);
+        }
+
+        // Serial numbers are never handed out twice, even if issuance fails later on
+        let serial = self.reserve_serial(serial_number)?;
+        let mut builder = CertificateBuilder::new(
+            profile,
+            Some(serial),
+            validity,
+            subject,
+            public_key,
+            self.key,
+        )?;
+        customize(&mut builder)?;
+        let cert = builder.build_checked(|tbs| {
+            self.check_policy(tbs)?;
+            self.lints.iter().try_for_each(|lint| lint(tbs))
+        })?;
+
+        let record = IssuanceRecord {
+            certificate: cert.clone(),
+            issued_at: Utc::now(),
+        };
+        self.audit_hooks.iter().try_for_each(|hook| hook(&record))?;
+        Ok(cert)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+
+    fn leaf_profile(ca: &Certificate) -> Profile {
+        Profile::Leaf {
+            issuer: ca.get_subject(),
+            enable_key_agreement: false,
+            enable_key_encipherment: false,
+        }
+    }
+
+    #[test]
+    fn test_issuer_context() {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let ca = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity,
+            "CN=ca.example.com".to_string(),
+            pk.clone(),
+            &sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let store = MemorySerialStore::new();
+        let audit_log = RefCell::new(Vec::new());
+        let mut issuer = IssuerContext::new(&ca, &sk, &store).unwrap();
+        issuer
+            .set_max_validity(Duration::from_secs(5 * 365 * 24 * 3600))
+            .set_allowed_key_algorithms(vec![DsaAlgorithm::MlDsa44.get_oid()])
+            .add_lint(|tbs| match tbs.subject.to_string().contains("forbidden") {
+                true => Err(QuantCryptError::BadSubject),
+                false => Ok(()),
+            })
+            .add_audit_hook(|record| {
+                audit_log.borrow_mut().push(record.get_serial_number());
+                Ok(())
+            });
+
+        let validity = CertValidity::new(None, "2028-01-01T00:00:00Z").unwrap();
+        let issue = |subject: &str, serial, validity: &CertValidity, pk: &PublicKey| {
+            issuer.issue(
+                leaf_profile(&ca),
+                serial,
+                validity.clone(),
+                subject.to_string(),
+                pk.clone(),
+                |_| Ok(()),
+            )
+        };
+
+        let leaf = issue("CN=leaf.example.com", None, &validity, &pk).unwrap();
+        assert!(ca.verify_child(&leaf).unwrap());
+        assert_eq!(*audit_log.borrow(), vec![leaf.get_serial_number()]);
+
+        // Serial numbers are unique
+        let serial = [0x01; 20];
+        assert!(issue("CN=a.example.com", Some(serial), &validity, &pk).is_ok());
+        assert!(matches!(
+            issue("CN=b.example.com", Some(serial), &validity, &pk),
+            Err(QuantCryptError::DuplicateSerialNumber)
+        ));
+
+        // Lints and policy refuse issuance before signing
+        assert!(matches!(
+            issue("CN=forbidden.example.com", None, &validity, &pk),
+            Err(QuantCryptError::BadSubject)
+        ));
+        let too_long = CertValidity::new(None, "2034-01-01T00:00:00Z").unwrap();
+        assert!(matches!(
+            issue("CN=c.example.com", None, &too_long, &pk),
+            Err(QuantCryptError::PolicyViolation { .. })
+        ));
+        let (other_pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+        assert!(matches!(
+            issue("CN=d.example.com", None, &validity, &other_pk),
+            Err(QuantCryptError::PolicyViolation { .. })
+        ));
+        let wrong_issuer = issuer.issue(
+            leaf_profile(&leaf),
+            None,
+            validity.clone(),
+            "CN=e.example.com".to_string(),
+            pk.clone(),
+            |_| Ok(()),
+        );
+        assert!(matches!(
+            wrong_issuer,
+            Err(QuantCryptError::PolicyViolation { .. })
+        ));
+        assert_eq!(audit_log.borrow().len(), 2);
+
+        // Only CA certificates can issue
+        assert!(IssuerContext::new(&leaf, &sk, &store).is_err());
+    }
+}
@@ -0,0 +1,11 @@
+pub mod crl_builder;
+pub mod crypto_policy;
+pub mod downgrade;
+pub mod fetcher;
+pub mod issuer;
+pub mod ocsp_responder;
+pub mod path_builder;
+pub mod pinning;
+pub mod revocation;
+pub mod tofu;
+pub mod validation_cache;
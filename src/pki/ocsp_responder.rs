@@ -0,0 +1,586 @@
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use const_oid::db::{rfc5912, rfc6960};
+use der::asn1::{BitString, GeneralizedTime, OctetString};
+use der::{Decode, Encode};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use spki::AlgorithmIdentifierOwned;
+use x509_cert::serial_number::SerialNumber;
+use x509_ocsp::{
+    BasicOcspResponse, CertId, CertStatus, OcspGeneralizedTime, OcspRequest, OcspResponse,
+    ResponderId, ResponseData, RevokedInfo, SingleResponse,
+};
+
+use crate::certificates::Certificate;
+use crate::keys::PrivateKey;
+use crate::pki::revocation::{is_ocsp_signer, ocsp_to_chrono, RevocationStatus};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The content type of an OCSP response sent over HTTP (RFC 6960 Appendix A)
+const OCSP_RESPONSE_CONTENT_TYPE: &str = "application/ocsp-response";
+
+/// The default validity of a response, from thisUpdate to nextUpdate
+const DEFAULT_VALIDITY: Duration = Duration::from_secs(3600);
+
+/// The callback looking up the revocation status of a serial number issued by the CA
+type StatusLookup<'a> = Box<dyn Fn(&SerialNumber) -> Result<RevocationStatus> + 'a>;
+
+/// An OCSP response produced by an `OcspResponder`, with the metadata needed to serve it over HTTP
+#[derive(Clone, Debug)]
+pub struct OcspResponderResponse {
+    der: Vec<u8>,
+    produced_at: Option<DateTime<Utc>>,
+    this_update: Option<DateTime<Utc>>,
+    next_update: Option<DateTime<Utc>>,
+    nonce_echoed: bool,
+}
+
+impl OcspResponderResponse {
+    /// Get the DER encoded OCSP response
+    pub fn get_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// Get the time the response was produced, or `None` if the response is not successful
+    pub fn get_produced_at(&self) -> Option<DateTime<Utc>> {
+        self.produced_at
+    }
+
+    /// Get the thisUpdate time of the statuses, or `None` if the response is not successful
+    pub fn get_this_update(&self) -> Option<DateTime<Utc>> {
+        self.this_update
+    }
+
+    /// Get the nextUpdate time of the statuses, or `None` if the response is not successful
+    pub fn get_next_update(&self) -> Option<DateTime<Utc>> {
+        self.next_update
+    }
+
+    /// Check if the response echoes a nonce from the request
+    pub fn is_nonce_echoed(&self) -> bool {
+        self.nonce_echoed
+    }
+
+    /// Get the HTTP headers to serve the response with
+    ///
+    /// Successful responses without a nonce can be cached until nextUpdate, and get the
+    /// headers recommended by RFC 5019 Section 6.2. Responses echoing a nonce are
+    /// specific to one request, and unsuccessful responses should be retried, so
+    /// neither may be cached.
+    ///
+    /// # Returns
+    ///
+    /// The headers as (name, value) pairs
+    pub fn get_http_headers(&self) -> Vec<(String, String)> {
+        let mut headers = vec![(
+            "Content-Type".to_string(),
+            OCSP_RESPONSE_CONTENT_TYPE.to_string(),
+        )];
+        let (Some(produced_at), Some(this_update), Some(next_update)) =
+            (self.produced_at, self.this_update, self.next_update)
+        else {
+            headers.push(("Cache-Control".to_string(), "no-store".to_string()));
+            return headers;
+        };
+
+        headers.push(("Date".to_string(), to_http_date(produced_at)));
+        if self.nonce_echoed {
+            headers.push(("Cache-Control".to_string(), "no-store".to_string()));
+            return headers;
+        }
+
+        let max_age = (next_update - produced_at).num_seconds().max(0);
+        let etag = Sha256::digest(&self.der)
+            .iter()
+            .fold(String::new(), |mut etag, b| {
+                let _ = write!(etag, "{:02x}", b);
+                etag
+            });
+        headers.push(("Last-Modified".to_string(), to_http_date(this_update)));
+        headers.push(("Expires".to_string(), to_http_date(next_update)));
+        headers.push((
+            "Cache-Control".to_string(),
+            format!("max-age={}, public, no-transform, must-revalidate", max_age),
+        ));
+        headers.push(("ETag".to_string(), format!("\"{}\"", etag)));
+        headers
+    }
+}
+
+/// Format a time as an HTTP date (RFC 9110 Section 5.6.7)
+fn to_http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Convert a time to an OCSP time, truncating it to whole seconds
+fn to_ocsp_time(time: SystemTime) -> Result<OcspGeneralizedTime> {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| QuantCryptError::Unknown)?
+        .as_secs();
+    GeneralizedTime::from_system_time(UNIX_EPOCH + Duration::from_secs(secs))
+        .map(OcspGeneralizedTime)
+        .map_err(|_| QuantCryptError::Unknown)
+}
+
+/// An OCSP responder answering for the certificates issued by one CA
+///
+/// The responder signs with either the key of the CA itself, or with the key of a
+/// delegated responder certificate issued by the CA with the OCSP signing extended
+/// key usage, which is then included in the response (RFC 6960 Section 4.2.2.2).
+/// Any signature algorithm supported by the crate can be used, including ML-DSA and
+/// the composite algorithms.
+///
+/// The status of each requested certificate is looked up through a callback, so the
+/// revocation database can live anywhere. A callback error results in an
+/// `internalError` response. Certificates of other issuers are reported as unknown.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{CertValidity, CertificateBuilder, Profile};
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+/// use quantcrypt::revocation::{OcspResponder, RevocationStatus};
+///
+/// let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65).generate().unwrap();
+/// let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+/// let ca = CertificateBuilder::new(Profile::Root, None, validity, "CN=ca".to_string(), pk, &sk)
+///     .unwrap()
+///     .build()
+///     .unwrap();
+///
+/// let responder = OcspResponder::new(&ca, &ca, &sk, |_| Ok(RevocationStatus::Good)).unwrap();
+///
+/// // A malformed request gets a malformedRequest response that must not be cached
+/// let response = responder.respond(b"not a request").unwrap();
+/// assert!(response.get_this_update().is_none());
+/// ```
+pub struct OcspResponder<'a> {
+    issuer: Certificate,
+    signer: Certificate,
+    signer_key: &'a PrivateKey,
+    lookup: StatusLookup<'a>,
+    validity: Duration,
+    responder_id_by_key: bool,
+    issuer_name: Vec<u8>,
+    issuer_key: Vec<u8>,
+}
+
+impl<'a> OcspResponder<'a> {
+    /// Create a new OCSP responder
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The certificate of the CA the responder answers for
+    /// * `signer` - The certificate signing the responses: `issuer` or a delegated responder certificate
+    /// * `signer_key` - The private key of `signer`
+    /// * `lookup` - Looks up the revocation status of a serial number issued by the CA
+    ///
+    /// # Returns
+    ///
+    /// The responder, with responses valid for an hour and the responder identified by name
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCertificate` if `signer` is neither `issuer` nor a delegated
+    /// responder certificate issued by it, or `signer_key` does not match `signer`
+    pub fn new(
+        issuer: &Certificate,
+        signer: &Certificate,
+        signer_key: &'a PrivateKey,
+        lookup: impl Fn(&SerialNumber) -> Result<RevocationStatus> + 'a,
+    ) -> Result<OcspResponder<'a>> {
+        if signer.get_public_key_oid() != signer_key.get_oid() {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+        let is_issuer = signer.to_der()? == issuer.to_der()?;
+        if !(is_issuer || is_ocsp_signer(signer) && issuer.verify_child(signer)?) {
+            return Err(QuantCryptError::InvalidCertificate);
+        }
+
+        let inner = issuer.get_inner();
+        let issuer_name = inner
+            .tbs_certificate
+            .subject
+            .to_der()
+            .map_err(|_| QuantCryptError::InvalidCertificate)?;
+        let issuer_key = inner
+            .tbs_certificate
+            .subject_public_key_info
+            .subject_public_key
+            .raw_bytes()
+            .to_vec();
+
+        Ok(OcspResponder {
+            issuer: issuer.clone(),
+            signer: signer.clone(),
+            signer_key,
+            lookup: Box::new(lookup),
+            validity: DEFAULT_VALIDITY,
+            responder_id_by_key: false,
+            issuer_name,
+            issuer_key,
+        })
+    }
+
+    /// Set how long the statuses in a response are valid, from thisUpdate to nextUpdate
+    ///
+    /// # Arguments
+    ///
+    /// * `validity` - The validity of a response
+    ///
+    /// # Returns
+    ///
+    /// The responder, for chaining
+    pub fn set_validity(&mut self, validity: Duration) -> &mut Self {
+        self.validity = validity;
+        self
+    }
+
+    /// Set whether the responder is identified by the hash of its key instead of its name
+    ///
+    /// # Arguments
+    ///
+    /// * `by_key` - True to identify the responder by key hash
+    ///
+    /// # Returns
+    ///
+    /// The responder, for chaining
+    pub fn set_responder_id_by_key(&mut self, by_key: bool) -> &mut Self {
+        self.responder_id_by_key = by_key;
+        self
+    }
+
+    /// Check if a certificate ID refers to a certificate issued by the CA
+    ///
+    /// SHA-1 and SHA-256 certificate IDs are supported.
+    fn is_issued_by_ca(&self, cert_id: &CertId) -> bool {
+        let (name_hash, key_hash) = match cert_id.hash_algorithm.oid {
+            rfc5912::ID_SHA_1 => (
+                Sha1::digest(&self.issuer_name).to_vec(),
+                Sha1::digest(&self.issuer_key).to_vec(),
+            ),
+            rfc5912::ID_SHA_256 => (
+                Sha256::digest(&self.issuer_name).to_vec(),
+                Sha256::digest(&self.issuer_key).to_vec(),
+            ),
+            _ => return false,
+        };
+        cert_id.issuer_name_hash.as_bytes() == name_hash
+            && cert_id.issuer_key_hash.as_bytes() == key_hash
+    }
+
+    /// Get the ID of the responder
+    fn get_responder_id(&self) -> Result<ResponderId> {
+        if self.responder_id_by_key {
+            let key = self
+                .signer
+                .get_inner()
+                .tbs_certificate
+                .subject_public_key_info
+                .subject_public_key
+                .raw_bytes();
+            let key_hash = OctetString::new(Sha1::digest(key).to_vec())
+                .map_err(|_| QuantCryptError::Unknown)?;
+            Ok(ResponderId::ByKey(key_hash))
+        } else {
+            Ok(ResponderId::ByName(self.signer.get_subject()))
+        }
+    }
+
+    /// Build an unsuccessful response
+    fn unsuccessful(response: OcspResponse) -> Result<OcspResponderResponse> {
+        Ok(OcspResponderResponse {
+            der: response.to_der().map_err(|_| QuantCryptError::Unknown)?,
+            produced_at: None,
+            this_update: None,
+            next_update: None,
+            nonce_echoed: false,
+        })
+    }
+
+    /// Answer a DER encoded OCSP request
+    ///
+    /// Requests that cannot be parsed get a `malformedRequest` response. A nonce in the
+    /// request is echoed in the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_der` - The DER encoded OCSP request
+    ///
+    /// # Returns
+    ///
+    /// The response to send, with its caching metadata
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::PrivateKeyOutsideUsagePeriod` if the private key usage period of
+    /// the signer doesn't include the current time, or another error if the response
+    /// cannot be signed or encoded
+    pub fn respond(&self, request_der: &[u8]) -> Result<OcspResponderResponse> {
+        let request = match OcspRequest::from_der(request_der) {
+            Ok(request) if !request.tbs_request.request_list.is_empty() => request,
+            _ => return Self::unsuccessful(OcspResponse::malformed_request()),
+        };
+
+        let now = SystemTime::now();
+        let produced_at = to_ocsp_time(now)?;
+        let next_update = to_ocsp_time(now + self.validity)?;
+
+        let mut responses = Vec::with_capacity(request.tbs_request.request_list.len());
+        for req in request.tbs_request.request_list.iter() {
+            let cert_id = &req.req_cert;
+            let status = if self.is_issued_by_ca(cert_id) {
+                match (self.lookup)(&cert_id.serial_number) {
+                    Ok(status) => status,
+                    Err(_) => return Self::unsuccessful(OcspResponse::internal_error()),
+                }
+            } else {
+                RevocationStatus::Unknown
+            };
+            let cert_status = match status {
+                RevocationStatus::Good => CertStatus::good(),
+                RevocationStatus::Revoked {
+                    revocation_time,
+                    reason,
+                } => CertStatus::revoked(RevokedInfo {
+                    revocation_time: to_ocsp_time(revocation_time.into())?,
+                    revocation_reason: reason,
+                }),
+                RevocationStatus::Unknown => CertStatus::unknown(),
+            };
+            responses.push(SingleResponse {
+                cert_id: cert_id.clone(),
+                cert_status,
+                this_update: produced_at,
+                next_update: Some(next_update),
+                single_extensions: None,
+            });
+        }
+
+        let nonce = request
+            .tbs_request
+            .request_extensions
+            .iter()
+            .flatten()
+            .find(|ext| ext.extn_id == rfc6960::ID_PKIX_OCSP_NONCE)
+            .cloned();
+        let nonce_echoed = nonce.is_some();
+
+        let tbs = ResponseData {
+            version: Default::default(),
+            responder_id: self.get_responder_id()?,
+            produced_at,
+            responses,
+            response_extensions: nonce.map(|nonce| vec![nonce]),
+        };
+        if !self.signer.is_private_key_usable() {
+            return Err(QuantCryptError::PrivateKeyOutsideUsagePeriod);
+        }
+        let signature = self
+            .signer_key
+            .sign(&tbs.to_der().map_err(|_| QuantCryptError::Unknown)?)?;
+        let signature_algorithm = AlgorithmIdentifierOwned {
+            oid: self
+                .signer_key
+                .get_oid()
+                .parse()
+                .map_err(|_| QuantCryptError::InvalidOid)?,
+            parameters: None,
+        };
+        let certs = if self.signer.to_der()? == self.issuer.to_der()? {
+            None
+        } else {
+            Some(vec![self.signer.get_inner().clone()])
+        };
+        let basic = BasicOcspResponse {
+            tbs_response_data: tbs,
+            signature_algorithm,
+            signature: BitString::from_bytes(&signature).map_err(|_| QuantCryptError::Unknown)?,
+            certs,
+        };
+        let der = OcspResponse::successful(basic)
+            .and_then(|response| response.to_der())
+            .map_err(|_| QuantCryptError::Unknown)?;
+
+        Ok(OcspResponderResponse {
+            der,
+            produced_at: Some(ocsp_to_chrono(&produced_at)),
+            this_update: Some(ocsp_to_chrono(&produced_at)),
+            next_update: Some(ocsp_to_chrono(&next_update)),
+            nonce_echoed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use const_oid::db::rfc5280;
+    use x509_cert::ext::pkix::{CrlReason, ExtendedKeyUsage};
+
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::pki::revocation::{build_ocsp_request, check_ocsp_response};
+
+    fn issue(
+        subject: &str,
+        issuer: Option<(&Certificate, &PrivateKey)>,
+        ocsp_signing: bool,
+    ) -> (Certificate, PrivateKey) {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let cert = match issuer {
+            None => {
+                CertificateBuilder::new(Profile::Root, None, validity, subject.to_string(), pk, &sk)
+                    .unwrap()
+                    .build()
+                    .unwrap()
+            }
+            Some((issuer, issuer_sk)) => {
+                let profile = Profile::Leaf {
+                    issuer: issuer.get_subject(),
+                    enable_key_agreement: false,
+                    enable_key_encipherment: false,
+                };
+                let mut builder = CertificateBuilder::new(
+                    profile,
+                    None,
+                    validity,
+                    subject.to_string(),
+                    pk,
+                    issuer_sk,
+                )
+                .unwrap();
+                if ocsp_signing {
+                    builder
+                        .add_extension(ExtendedKeyUsage(vec![rfc5280::ID_KP_OCSP_SIGNING]))
+                        .unwrap();
+                }
+                builder.build().unwrap()
+            }
+        };
+        (cert, sk)
+    }
+
+    #[test]
+    fn test_ocsp_responder() {
+        let (ca, ca_sk) = issue("CN=ca.example.com", None, false);
+        let (good, _) = issue("CN=good.example.com", Some((&ca, &ca_sk)), false);
+        let (revoked, _) = issue("CN=revoked.example.com", Some((&ca, &ca_sk)), false);
+        let (delegate, delegate_sk) = issue("CN=ocsp.example.com", Some((&ca, &ca_sk)), true);
+
+        let revoked_serial = revoked.get_serial_number();
+        let lookup = |serial: &SerialNumber| {
+            Ok(if *serial == revoked_serial {
+                RevocationStatus::Revoked {
+                    revocation_time: Utc::now(),
+                    reason: Some(CrlReason::KeyCompromise),
+                }
+            } else {
+                RevocationStatus::Good
+            })
+        };
+
+        // Only the CA or a delegated responder it issued can sign
+        assert!(matches!(
+            OcspResponder::new(&ca, &good, &ca_sk, lookup),
+            Err(QuantCryptError::InvalidCertificate)
+        ));
+        let (_, good_sk) = issue("CN=unused", Some((&ca, &ca_sk)), false);
+        assert!(matches!(
+            OcspResponder::new(&ca, &good, &good_sk, lookup),
+            Err(QuantCryptError::InvalidCertificate)
+        ));
+
+        let nonce = [3u8; 16];
+        let mut by_ca = OcspResponder::new(&ca, &ca, &ca_sk, lookup).unwrap();
+        let mut delegated = OcspResponder::new(&ca, &delegate, &delegate_sk, lookup).unwrap();
+        delegated.set_responder_id_by_key(true);
+        for responder in [&mut by_ca, &mut delegated] {
+            responder.set_validity(Duration::from_secs(600));
+
+            let request = build_ocsp_request(&good, &ca, &nonce).unwrap();
+            let response = responder.respond(&request).unwrap();
+            assert!(response.is_nonce_echoed());
+            assert_eq!(
+                check_ocsp_response(&good, &ca, response.get_der(), Some(&nonce)),
+                Ok(RevocationStatus::Good)
+            );
+            let validity =
+                response.get_next_update().unwrap() - response.get_this_update().unwrap();
+            assert_eq!(validity.num_seconds(), 600);
+
+            let request = build_ocsp_request(&revoked, &ca, &nonce).unwrap();
+            let response = responder.respond(&request).unwrap();
+            assert!(matches!(
+                check_ocsp_response(&revoked, &ca, response.get_der(), Some(&nonce)),
+                Ok(RevocationStatus::Revoked {
+                    reason: Some(CrlReason::KeyCompromise),
+                    ..
+                })
+            ));
+        }
+
+        // Certificates of another CA are unknown
+        let (other_ca, other_sk) = issue("CN=other.example.com", None, false);
+        let (stranger, _) = issue(
+            "CN=stranger.example.com",
+            Some((&other_ca, &other_sk)),
+            false,
+        );
+        let request = build_ocsp_request(&stranger, &other_ca, &nonce).unwrap();
+        let response = by_ca.respond(&request).unwrap();
+        let basic = OcspResponse::from_der(response.get_der()).unwrap();
+        let basic =
+            BasicOcspResponse::from_der(basic.response_bytes.unwrap().response.as_bytes()).unwrap();
+        assert_eq!(
+            basic.tbs_response_data.responses[0].cert_status,
+            CertStatus::unknown()
+        );
+    }
+
+    #[test]
+    fn test_unsuccessful_responses() {
+        let (ca, ca_sk) = issue("CN=ca.example.com", None, false);
+        let (leaf, _) = issue("CN=leaf.example.com", Some((&ca, &ca_sk)), false);
+        let request = build_ocsp_request(&leaf, &ca, &[1u8; 16]).unwrap();
+
+        let responder =
+            OcspResponder::new(&ca, &ca, &ca_sk, |_| Ok(RevocationStatus::Good)).unwrap();
+        let response = responder.respond(&request[1..]).unwrap();
+        assert_eq!(
+            OcspResponse::from_der(response.get_der()).unwrap(),
+            OcspResponse::malformed_request()
+        );
+        assert!(response
+            .get_http_headers()
+            .contains(&("Cache-Control".to_string(), "no-store".to_string())));
+
+        // Without a nonce, the response can be cached until nextUpdate
+        let mut no_nonce = OcspRequest::from_der(&request).unwrap();
+        no_nonce.tbs_request.request_extensions = None;
+        let response = responder.respond(&no_nonce.to_der().unwrap()).unwrap();
+        assert!(!response.is_nonce_echoed());
+        let headers = response.get_http_headers();
+        assert!(headers.contains(&(
+            "Cache-Control".to_string(),
+            "max-age=3600, public, no-transform, must-revalidate".to_string()
+        )));
+        assert!(headers.iter().any(|(name, _)| name == "ETag"));
+
+        let failing =
+            OcspResponder::new(&ca, &ca, &ca_sk, |_| Err(QuantCryptError::Unknown)).unwrap();
+        let response = failing.respond(&request).unwrap();
+        assert_eq!(
+            OcspResponse::from_der(response.get_der()).unwrap(),
+            OcspResponse::internal_error()
+        );
+    }
+}
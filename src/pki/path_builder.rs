@@ -0,0 +1,569 @@
+use cms::cert::CertificateChoices;
+use cms::content_info::ContentInfo;
+use cms::signed_data::SignedData;
+use const_oid::db::rfc5911::ID_SIGNED_DATA;
+use der::Decode;
+
+use crate::certificates::Certificate;
+use crate::dsas::DsaAlgorithm;
+use crate::pki::fetcher::Fetcher;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The default maximum number of certificates in a path, including the end-entity and trust anchor
+const DEFAULT_MAX_PATH_LEN: usize = 8;
+
+/// The weakest signature on a certification path
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeakestSignature {
+    /// The index in the path of the certificate carrying the signature
+    pub index: usize,
+    /// The OID of the signature algorithm, i.e. of the key of the issuer
+    pub oid: String,
+    /// The signature algorithm, or None for an algorithm that is not quantum-safe
+    pub algorithm: Option<DsaAlgorithm>,
+    /// The NIST security category against quantum attackers, 0 if not quantum-safe
+    pub quantum_security_level: u8,
+}
+
+impl WeakestSignature {
+    /// Check if the weakest signature, and thus every signature on the path, is quantum-safe
+    ///
+    /// # Returns
+    ///
+    /// True if the signature is pure post-quantum or composite
+    pub fn is_quantum_safe(&self) -> bool {
+        self.quantum_security_level > 0
+    }
+}
+
+/// Check if the key of an issuer is a pure post-quantum or composite signature key
+fn has_quantum_safe_key(issuer: &Certificate) -> bool {
+    DsaAlgorithm::from_oid(&issuer.get_public_key_oid()).is_some()
+}
+
+/// Find the weakest signature on a certification path
+///
+/// Every certificate but the last one carries a signature of the next one, whose
+/// algorithm is the one of the key of the next certificate. The self-signature of
+/// the trust anchor is not considered, as the trust anchor is trusted as configured. Signatures are ranked by their NIST security category
+/// against quantum attackers, classical-only signatures being the weakest.
+///
+/// # Arguments
+///
+/// * `path` - The path, starting with the end-entity and ending with the trust anchor
+///
+/// # Returns
+///
+/// The weakest signature, the one closest to the end-entity among equals, or None if
+/// the path has no signature besides the one of the trust anchor
+pub fn get_weakest_signature(path: &[Certificate]) -> Option<WeakestSignature> {
+    path.windows(2)
+        .enumerate()
+        .map(|(index, link)| {
+            let oid = link[1].get_public_key_oid();
+            let algorithm = DsaAlgorithm::from_oid(&oid);
+            WeakestSignature {
+                index,
+                oid,
+                algorithm,
+                quantum_security_level: algorithm
+                    .map(|alg| alg.get_quantum_security_level())
+                    .unwrap_or(0),
+            }
+        })
+        .reduce(|weakest, sig| {
+            if sig.quantum_security_level < weakest.quantum_security_level {
+                sig
+            } else {
+                weakest
+            }
+        })
+}
+
+/// Builds certification paths from an end-entity certificate to a trust anchor
+///
+/// Issuers are looked up among the trust anchors and the known intermediate
+/// certificates. Partners often send end-entity certificates without their
+/// intermediates. When a `Fetcher` is set, missing issuers are downloaded
+/// from the CA Issuers URLs of the Authority Information Access extension
+/// ("AIA chasing"). A downloaded certificate is only used if it verifies as
+/// the issuer, so an attacker controlling the URL can't inject an issuer.
+///
+/// With `set_require_quantum_safe`, only paths where every certificate is
+/// signed with a pure post-quantum or composite algorithm are built, so a
+/// classical-only link can't be used to forge the path with a quantum computer.
+/// `get_weakest_signature` tells how strong a path is.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{Certificate, PathBuilder};
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let ta = Certificate::from_file(cert_path).unwrap();
+///
+/// let builder = PathBuilder::new(vec![ta.clone()]);
+/// let path = builder.build(&ta).unwrap();
+/// assert_eq!(path.len(), 1);
+/// ```
+pub struct PathBuilder<'a> {
+    trust_anchors: Vec<Certificate>,
+    intermediates: Vec<Certificate>,
+    fetcher: Option<&'a dyn Fetcher>,
+    max_path_len: usize,
+    require_quantum_safe: bool,
+}
+
+impl<'a> PathBuilder<'a> {
+    /// Create a new path builder
+    ///
+    /// # Arguments
+    ///
+    /// * `trust_anchors` - The trusted certificates paths must end at
+    ///
+    /// # Returns
+    ///
+    /// A new path builder
+    pub fn new(trust_anchors: Vec<Certificate>) -> PathBuilder<'a> {
+        PathBuilder {
+            trust_anchors,
+            intermediates: Vec::new(),
+            fetcher: None,
+            max_path_len: DEFAULT_MAX_PATH_LEN,
+            require_quantum_safe: false,
+        }
+    }
+
+    /// Add an intermediate CA certificate that may be used to build paths
+    ///
+    /// Intermediates are not trusted, they must chain up to a trust anchor.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The intermediate certificate
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn add_intermediate(&mut self, cert: Certificate) -> &mut Self {
+        self.intermediates.push(cert);
+        self
+    }
+
+    /// Enable AIA chasing, using a fetcher to download missing issuer certificates
+    ///
+    /// # Arguments
+    ///
+    /// * `fetcher` - The fetcher used to download issuer certificates
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn set_fetcher(&mut self, fetcher: &'a dyn Fetcher) -> &mut Self {
+        self.fetcher = Some(fetcher);
+        self
+    }
+
+    /// Set the maximum number of certificates in a path
+    ///
+    /// # Arguments
+    ///
+    /// * `max_path_len` - The maximum number of certificates, including the end-entity and trust anchor
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn set_max_path_len(&mut self, max_path_len: usize) -> &mut Self {
+        self.max_path_len = max_path_len;
+        self
+    }
+
+    /// Only build paths where every signature is pure post-quantum or composite
+    ///
+    /// # Arguments
+    ///
+    /// * `require_quantum_safe` - True to reject paths with classical-only signatures
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn set_require_quantum_safe(&mut self, require_quantum_safe: bool) -> &mut Self {
+        self.require_quantum_safe = require_quantum_safe;
+        self
+    }
+
+    /// Build a path from a certificate to a trust anchor
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The end-entity certificate
+    ///
+    /// # Returns
+    ///
+    /// The path, starting with `cert` and ending with the trust anchor
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::PathNotFound` if no valid path exists within the maximum path length,
+    /// or, if quantum-safe paths are required, no such path exists
+    pub fn build(&self, cert: &Certificate) -> Result<Vec<Certificate>> {
+        let mut path = vec![cert.clone()];
+        if self.extend_path(&mut path) {
+            Ok(path)
+        } else {
+            Err(QuantCryptError::PathNotFound)
+        }
+    }
+
+    /// Extend a partial path up to a trust anchor, depth first
+    fn extend_path(&self, path: &mut Vec<Certificate>) -> bool {
+        let current = match path.last() {
+            Some(current) => current.clone(),
+            None => return false,
+        };
+
+        // A trust anchor ends the path, even if it is the certificate itself
+        if path.len() == 1 && self.is_trust_anchor(&current) {
+            return true;
+        }
+        if let Some(ta) = self.trust_anchors.iter().find(|ta| {
+            self.is_allowed_issuer(ta)
+                && ta.is_valid()
+                && ta.verify_child(&current).unwrap_or(false)
+        }) {
+            path.push(ta.clone());
+            return true;
+        }
+
+        if path.len() + 1 >= self.max_path_len {
+            return false;
+        }
+
+        let mut candidates = self.find_issuers(&current, self.intermediates.iter().cloned());
+        if candidates.is_empty() {
+            candidates = self.find_issuers(&current, self.fetch_issuers(&current).into_iter());
+        }
+
+        for candidate in candidates {
+            // Don't loop through cross-certificates
+            if path
+                .iter()
+                .any(|cert| cert.get_subject() == candidate.get_subject())
+            {
+                continue;
+            }
+            path.push(candidate);
+            if self.extend_path(path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// Check if a certificate is one of the trust anchors
+    fn is_trust_anchor(&self, cert: &Certificate) -> bool {
+        let der = cert.to_der().ok();
+        self.trust_anchors.iter().any(|ta| ta.to_der().ok() == der)
+    }
+
+    /// Check if the signatures of an issuer are acceptable on a path
+    fn is_allowed_issuer(&self, issuer: &Certificate) -> bool {
+        !self.require_quantum_safe || has_quantum_safe_key(issuer)
+    }
+
+    /// Keep the valid CA certificates that issued a certificate
+    fn find_issuers(
+        &self,
+        cert: &Certificate,
+        candidates: impl Iterator<Item = Certificate>,
+    ) -> Vec<Certificate> {
+        candidates
+            .filter(|candidate| {
+                candidate.is_ca()
+                    && self.is_allowed_issuer(candidate)
+                    && candidate.is_valid()
+                    && candidate.verify_child(cert).unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// Download the certificates listed in the CA Issuers URLs of a certificate
+    fn fetch_issuers(&self, cert: &Certificate) -> Vec<Certificate> {
+        let fetcher = match self.fetcher {
+            Some(fetcher) => fetcher,
+            None => return Vec::new(),
+        };
+        cert.get_ca_issuers()
+            .iter()
+            .filter_map(|url| fetcher.get(url).ok())
+            .flat_map(|body| parse_ca_issuers_response(&body))
+            .collect()
+    }
+}
+
+/// Parse the certificates served at a CA Issuers URL
+///
+/// As per RFC 5280 Section 4.2.2.1, this is either a single DER encoded
+/// certificate or a DER encoded "certs-only" CMS message. PEM is accepted too.
+fn parse_ca_issuers_response(body: &[u8]) -> Vec<Certificate> {
+    if let Ok(cert) = Certificate::from_der(body) {
+        return vec![cert];
+    }
+    if let Some(certs) = parse_certs_only(body) {
+        return certs;
+    }
+    std::str::from_utf8(body)
+        .ok()
+        .and_then(|pem| Certificate::from_pem(pem).ok())
+        .into_iter()
+        .collect()
+}
+
+/// Extract the certificates of a DER encoded "certs-only" CMS SignedData message
+fn parse_certs_only(der: &[u8]) -> Option<Vec<Certificate>> {
+    let content_info = ContentInfo::from_der(der).ok()?;
+    if content_info.content_type != ID_SIGNED_DATA {
+        return None;
+    }
+    let signed_data = content_info.content.decode_as::<SignedData>().ok()?;
+    let certs = signed_data
+        .certificates?
+        .0
+        .into_vec()
+        .into_iter()
+        .filter_map(|choice| match choice {
+            CertificateChoices::Certificate(cert) => Some(Certificate::new(cert)),
+            CertificateChoices::Other(_) => None,
+        })
+        .collect();
+    Some(certs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use cms::cert::CertificateChoices;
+    use cms::content_info::CmsVersion;
+    use cms::signed_data::{CertificateSet, EncapsulatedContentInfo, SignerInfos};
+    use const_oid::db::rfc5911::ID_DATA;
+    use der::asn1::{Any, Ia5String, SetOfVec};
+    use der::Encode;
+    use x509_cert::ext::pkix::name::GeneralName;
+    use x509_cert::ext::pkix::{AccessDescription, AuthorityInfoAccessSyntax};
+
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::keys::PrivateKey;
+
+    const SUB_CA_URL: &str = "http://example.com/sub.der";
+
+    struct MockFetcher {
+        resources: HashMap<String, Vec<u8>>,
+    }
+
+    impl Fetcher for MockFetcher {
+        fn get(&self, url: &str) -> Result<Vec<u8>> {
+            self.resources
+                .get(url)
+                .cloned()
+                .ok_or(QuantCryptError::FetchFailed {
+                    url: url.to_string(),
+                })
+        }
+
+        fn post(&self, url: &str, _content_type: &str, _body: &[u8]) -> Result<Vec<u8>> {
+            Err(QuantCryptError::FetchFailed {
+                url: url.to_string(),
+            })
+        }
+    }
+
+    fn issue(
+        profile: Profile,
+        subject: &str,
+        signer: Option<&PrivateKey>,
+        ca_issuers: Option<&str>,
+    ) -> (Certificate, PrivateKey) {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let signer = signer.unwrap_or(&sk);
+        let mut builder = CertificateBuilder::new(
+            profile,
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            subject.to_string(),
+            pk,
+            signer,
+        )
+        .unwrap();
+        if let Some(url) = ca_issuers {
+            builder
+                .add_extension(AuthorityInfoAccessSyntax(vec![AccessDescription {
+                    access_method: const_oid::db::rfc5280::ID_AD_CA_ISSUERS,
+                    access_location: GeneralName::UniformResourceIdentifier(
+                        Ia5String::new(url).unwrap(),
+                    ),
+                }]))
+                .unwrap();
+        }
+        (builder.build().unwrap(), sk)
+    }
+
+    fn certs_only(certs: &[&Certificate]) -> Vec<u8> {
+        let mut set = SetOfVec::new();
+        for cert in certs {
+            set.insert(CertificateChoices::Certificate(cert.get_inner().clone()))
+                .unwrap();
+        }
+        let signed_data = SignedData {
+            version: CmsVersion::V1,
+            digest_algorithms: SetOfVec::new(),
+            encap_content_info: EncapsulatedContentInfo {
+                econtent_type: ID_DATA,
+                econtent: None,
+            },
+            certificates: Some(CertificateSet(set)),
+            crls: None,
+            signer_infos: SignerInfos(SetOfVec::new()),
+        };
+        ContentInfo {
+            content_type: ID_SIGNED_DATA,
+            content: Any::encode_from(&signed_data).unwrap(),
+        }
+        .to_der()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_aia_chasing() {
+        let (ta, ta_sk) = issue(Profile::Root, "CN=ta.example.com", None, None);
+        let (sub, sub_sk) = issue(
+            Profile::SubCA {
+                issuer: ta.get_subject(),
+                path_len_constraint: None,
+            },
+            "CN=sub.example.com",
+            Some(&ta_sk),
+            None,
+        );
+        let (leaf, _) = issue(
+            Profile::Leaf {
+                issuer: sub.get_subject(),
+                enable_key_agreement: false,
+                enable_key_encipherment: false,
+            },
+            "CN=leaf.example.com",
+            Some(&sub_sk),
+            Some(SUB_CA_URL),
+        );
+        assert_eq!(leaf.get_ca_issuers(), vec![SUB_CA_URL]);
+
+        // Without the intermediate, there's no path
+        let builder = PathBuilder::new(vec![ta.clone()]);
+        assert!(matches!(
+            builder.build(&leaf),
+            Err(QuantCryptError::PathNotFound)
+        ));
+
+        // A known intermediate is used
+        let mut with_sub = PathBuilder::new(vec![ta.clone()]);
+        with_sub.add_intermediate(sub.clone());
+        let path = with_sub.build(&leaf).unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[1].get_subject(), sub.get_subject());
+
+        // The intermediate is fetched, as a certificate or a certs-only message
+        for body in [sub.to_der().unwrap(), certs_only(&[&ta, &sub])] {
+            let fetcher = MockFetcher {
+                resources: HashMap::from([(SUB_CA_URL.to_string(), body)]),
+            };
+            let mut chasing = PathBuilder::new(vec![ta.clone()]);
+            chasing.set_fetcher(&fetcher);
+            let path = chasing.build(&leaf).unwrap();
+            assert_eq!(path.len(), 3);
+            assert_eq!(path[2].get_subject(), ta.get_subject());
+        }
+
+        // A certificate that didn't issue the leaf is ignored
+        let (other, _) = issue(
+            Profile::SubCA {
+                issuer: ta.get_subject(),
+                path_len_constraint: None,
+            },
+            "CN=sub.example.com",
+            Some(&ta_sk),
+            None,
+        );
+        let fetcher = MockFetcher {
+            resources: HashMap::from([(SUB_CA_URL.to_string(), other.to_der().unwrap())]),
+        };
+        let mut chasing = PathBuilder::new(vec![ta.clone()]);
+        chasing.set_fetcher(&fetcher);
+        assert!(chasing.build(&leaf).is_err());
+
+        // The path length is bounded
+        with_sub.set_max_path_len(2);
+        assert!(with_sub.build(&leaf).is_err());
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_quantum_safe_paths() {
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let mut certs: Vec<Certificate> = Vec::new();
+        let mut signer: Option<PrivateKey> = None;
+        for (alg, subject) in [
+            (DsaAlgorithm::MlDsa87, "CN=ta.example.com"),
+            (DsaAlgorithm::MlDsa44EcdsaP256, "CN=sub.example.com"),
+            (DsaAlgorithm::SlhDsaSha2_192s, "CN=leaf.example.com"),
+        ] {
+            let (pk, sk) = DsaKeyGenerator::new(alg).generate().unwrap();
+            let profile = match certs.last() {
+                None => Profile::Root,
+                Some(issuer) => Profile::SubCA {
+                    issuer: issuer.get_subject(),
+                    path_len_constraint: None,
+                },
+            };
+            let cert = CertificateBuilder::new(
+                profile,
+                None,
+                validity.clone(),
+                subject.to_string(),
+                pk,
+                signer.as_ref().unwrap_or(&sk),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+            certs.push(cert);
+            signer = Some(sk);
+        }
+        let (ta, sub, leaf) = (&certs[0], &certs[1], &certs[2]);
+
+        let mut builder = PathBuilder::new(vec![ta.clone()]);
+        builder.add_intermediate(sub.clone());
+        builder.set_require_quantum_safe(true);
+        let path = builder.build(leaf).unwrap();
+        assert_eq!(path.len(), 3);
+
+        // The leaf is signed with the composite key of the sub CA
+        let weakest = get_weakest_signature(&path).unwrap();
+        assert!(weakest.is_quantum_safe());
+        assert_eq!(weakest.index, 0);
+        assert_eq!(weakest.algorithm, Some(DsaAlgorithm::MlDsa44EcdsaP256));
+        assert_eq!(weakest.quantum_security_level, 2);
+        assert_eq!(
+            get_weakest_signature(&path[1..])
+                .unwrap()
+                .quantum_security_level,
+            5
+        );
+        assert!(get_weakest_signature(&path[2..]).is_none());
+    }
+}
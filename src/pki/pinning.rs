@@ -0,0 +1,287 @@
+use std::collections::HashSet;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::asn1::certificate::Certificate;
+use crate::asn1::key_id::KeyIdMethod;
+use crate::asn1::public_key::PublicKey;
+use crate::pki::path_builder::PathBuilder;
+use crate::utils::verification_report::VerificationReport;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The prefix of pins in the `sha256/<base64>` notation of mobile pinning libraries
+const PIN_PREFIX: &str = "sha256/";
+
+/// The length of a SHA-256 digest
+const PIN_LEN: usize = 32;
+
+/// A set of SPKI pins, the keys a client accepts for a server
+///
+/// A pin is the base64 encoded SHA-256 of a DER encoded SubjectPublicKeyInfo
+/// (RFC 7469), so it survives the renewal of a certificate for the same key.
+/// A chain is accepted if one of its certificates carries a pinned key. Pin
+/// the server key and a backup key, or the key of the issuing CA.
+///
+/// Pinning can replace chain validation, for clients that ship with the keys
+/// of their server (`verify_chain`), or be required on top of it (`verify_path`).
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{Certificate, PinSet};
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let cert = Certificate::from_file(cert_path).unwrap();
+///
+/// let mut pins = PinSet::new();
+/// pins.add_pin(&cert.get_spki_pin().unwrap()).unwrap();
+/// assert!(pins.verify_chain(&[cert]).unwrap().is_valid());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PinSet {
+    pins: HashSet<Vec<u8>>,
+}
+
+impl PinSet {
+    /// Create an empty pin set
+    ///
+    /// # Returns
+    ///
+    /// The pin set, which accepts no chain until pins are added
+    pub fn new() -> PinSet {
+        PinSet::default()
+    }
+
+    /// Add a pin
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - The base64 encoded SHA-256 of the SubjectPublicKeyInfo, optionally prefixed with `sha256/`
+    ///
+    /// # Returns
+    ///
+    /// The pin set, for chaining
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEncoding` if the pin is not the base64 encoding of 32 bytes
+    pub fn add_pin(&mut self, pin: &str) -> Result<&mut Self> {
+        let pin = pin.strip_prefix(PIN_PREFIX).unwrap_or(pin);
+        let digest = STANDARD
+            .decode(pin)
+            .map_err(|_| QuantCryptError::InvalidEncoding)?;
+        if digest.len() != PIN_LEN {
+            return Err(QuantCryptError::InvalidEncoding);
+        }
+        self.pins.insert(digest);
+        Ok(self)
+    }
+
+    /// Pin the public key of a certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate
+    ///
+    /// # Returns
+    ///
+    /// The pin set, for chaining
+    pub fn add_certificate(&mut self, cert: &Certificate) -> Result<&mut Self> {
+        self.pins.insert(cert.get_key_id(KeyIdMethod::SpkiSha256)?);
+        Ok(self)
+    }
+
+    /// Pin a public key
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The public key
+    ///
+    /// # Returns
+    ///
+    /// The pin set, for chaining
+    pub fn add_public_key(&mut self, public_key: &PublicKey) -> Result<&mut Self> {
+        self.pins
+            .insert(public_key.get_key_id(KeyIdMethod::SpkiSha256)?);
+        Ok(self)
+    }
+
+    /// Get the number of pins
+    pub fn len(&self) -> usize {
+        self.pins.len()
+    }
+
+    /// Check if the set has no pins
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Check if the public key of a certificate is pinned
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate
+    ///
+    /// # Returns
+    ///
+    /// True if the key is pinned, false otherwise
+    pub fn matches(&self, cert: &Certificate) -> Result<bool> {
+        Ok(self
+            .pins
+            .contains(&cert.get_key_id(KeyIdMethod::SpkiSha256)?))
+    }
+
+    /// Verify that a chain presented by a peer carries a pinned key, without chain validation
+    ///
+    /// Only the pins are checked: the certificates are neither validated
+    /// against each other nor checked for expiry. Use `verify_path` to also
+    /// validate the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The certificates presented by the peer, end-entity first
+    ///
+    /// # Returns
+    ///
+    /// The verification report
+    pub fn verify_chain(&self, chain: &[Certificate]) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.policy = Some(self.describe());
+        if !report.check("chain not empty", !chain.is_empty()) {
+            return Ok(report);
+        }
+        report.valid = self.check_pins(&mut report, chain)?;
+        Ok(report)
+    }
+
+    /// Build and validate the path of a certificate, then require a pinned key in the path
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The path builder, holding the trust anchors and intermediates
+    /// * `cert` - The end-entity certificate
+    ///
+    /// # Returns
+    ///
+    /// The verification report
+    pub fn verify_path(
+        &self,
+        builder: &PathBuilder,
+        cert: &Certificate,
+    ) -> Result<VerificationReport> {
+        let mut report = VerificationReport::new();
+        report.policy = Some(self.describe());
+        if !report.check("certificate valid", cert.is_valid()) {
+            return Ok(report);
+        }
+        let path = match builder.build(cert) {
+            Ok(path) => path,
+            Err(QuantCryptError::PathNotFound) => {
+                report.check("certification path", false);
+                return Ok(report);
+            }
+            Err(e) => return Err(e),
+        };
+        report.check("certification path", true);
+        report.valid = self.check_pins(&mut report, &path)?;
+        Ok(report)
+    }
+
+    /// Record whether one of the certificates carries a pinned key
+    fn check_pins(&self, report: &mut VerificationReport, chain: &[Certificate]) -> Result<bool> {
+        for (i, cert) in chain.iter().enumerate() {
+            if self.matches(cert)? {
+                return Ok(report.check(&format!("certificate {} pinned", i), true));
+            }
+        }
+        Ok(report.check("pinned key in chain", false))
+    }
+
+    /// Describe the pin set for verification reports
+    fn describe(&self) -> String {
+        format!("SPKI pinning with {} pins", self.pins.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+
+    #[test]
+    fn test_pin_set() {
+        let validity = CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap();
+        let (ca_pk, ca_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+        let ca = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            validity.clone(),
+            "CN=ca".to_string(),
+            ca_pk.clone(),
+            &ca_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let (pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let profile = Profile::Leaf {
+            issuer: ca.get_subject(),
+            enable_key_agreement: false,
+            enable_key_encipherment: false,
+        };
+        let leaf = CertificateBuilder::new(
+            profile,
+            None,
+            validity,
+            "CN=server".to_string(),
+            pk.clone(),
+            &ca_sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        // The pin of a certificate is the pin of its key
+        let pin = leaf.get_spki_pin().unwrap();
+        assert_eq!(pk.get_spki_pin().unwrap(), pin);
+        assert_eq!(STANDARD.decode(&pin).unwrap().len(), 32);
+
+        let mut pins = PinSet::new();
+        assert!(!pins.verify_chain(&[leaf.clone()]).unwrap().is_valid());
+        pins.add_pin(&format!("sha256/{}", pin)).unwrap();
+        assert_eq!(pins.len(), 1);
+        assert!(pins.matches(&leaf).unwrap());
+        assert!(!pins.matches(&ca).unwrap());
+        assert!(pins.verify_chain(&[leaf.clone()]).unwrap().is_valid());
+        assert!(!pins.verify_chain(&[ca.clone()]).unwrap().is_valid());
+        assert!(!pins.verify_chain(&[]).unwrap().is_valid());
+        assert!(pins.add_pin("not base64!").is_err());
+        assert!(pins.add_pin(&STANDARD.encode([0u8; 20])).is_err());
+
+        // Pinning the CA key accepts any chain through the CA
+        let mut ca_pins = PinSet::new();
+        ca_pins.add_public_key(&ca_pk).unwrap();
+        let builder = PathBuilder::new(vec![ca.clone()]);
+        let report = ca_pins.verify_path(&builder, &leaf).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.checks.last().unwrap().name, "certificate 1 pinned");
+
+        // A valid path without a pinned key is rejected
+        let mut other = PinSet::new();
+        other.add_certificate(&leaf).unwrap();
+        let stranger = Certificate::from_file(
+            "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der",
+        )
+        .unwrap();
+        let builder = PathBuilder::new(vec![stranger.clone()]);
+        assert!(!other.verify_path(&builder, &stranger).unwrap().is_valid());
+        assert!(!other.verify_path(&builder, &leaf).unwrap().is_valid());
+    }
+}
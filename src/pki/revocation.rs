@@ -0,0 +1,1093 @@
+use chrono::{DateTime, Utc};
+use const_oid::db::{rfc5280, rfc5912, rfc6960};
+use der::asn1::{Null, OctetString};
+use der::{Decode, Encode};
+use num_bigint::BigUint;
+use pkcs8::ObjectIdentifier;
+use rand_core::{OsRng, RngCore};
+use sha1::{Digest, Sha1};
+use spki::AlgorithmIdentifierOwned;
+use x509_cert::crl::CertificateList;
+use x509_cert::ext::pkix::{CrlNumber, CrlReason, ExtendedKeyUsage, FreshestCrl};
+use x509_cert::ext::Extension;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::time::Time;
+use x509_ocsp::ext::Nonce;
+use x509_ocsp::{
+    BasicOcspResponse, CertId, CertStatus, OcspGeneralizedTime, OcspRequest, OcspResponse,
+    OcspResponseStatus, Request, ResponderId, TbsRequest,
+};
+
+use crate::asn1::certificate::get_distribution_point_urls;
+use crate::certificates::Certificate;
+use crate::pki::crypto_policy::CryptoPolicy;
+use crate::pki::fetcher::Fetcher;
+use crate::utils::clock_skew::{get_clock_skew_tolerance, is_within};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The crypto policy to check signatures against, with the path they protect
+type PolicyContext<'p> = Option<(&'p CryptoPolicy, &'p [Certificate])>;
+
+/// The content type of an OCSP request sent over HTTP (RFC 6960 Appendix A)
+const OCSP_REQUEST_CONTENT_TYPE: &str = "application/ocsp-request";
+
+/// The length of the nonce sent with OCSP requests
+const OCSP_NONCE_LEN: usize = 16;
+
+/// The revocation status of a certificate
+#[derive(Clone, Debug, PartialEq)]
+pub enum RevocationStatus {
+    /// The certificate is not revoked
+    Good,
+    /// The certificate is revoked
+    Revoked {
+        /// The time the certificate was revoked
+        revocation_time: DateTime<Utc>,
+        /// The reason the certificate was revoked, if stated
+        reason: Option<CrlReason>,
+    },
+    /// No usable revocation information could be obtained
+    Unknown,
+}
+
+/// Checks the revocation status of certificates using OCSP and CRLs
+///
+/// The OCSP responders and CRL distribution points listed in the certificate
+/// are queried through a `Fetcher`, OCSP first. Responses that cannot be
+/// retrieved, are not signed by the issuer (or a delegated OCSP responder),
+/// or are not current are skipped. If no source yields an answer, the status
+/// is `RevocationStatus::Unknown`, and it is up to the caller to decide
+/// whether to fail open or closed.
+///
+/// With a `CryptoPolicy`, CRLs and OCSP responses must also be signed with
+/// algorithms as strong as the path they are checked for, so a weak CRL can't
+/// undermine an ML-DSA path.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use quantcrypt::certificates::Certificate;
+/// use quantcrypt::revocation::{CachingFetcher, Fetcher, RevocationChecker, RevocationStatus};
+/// use quantcrypt::QuantCryptError;
+///
+/// struct Offline;
+///
+/// impl Fetcher for Offline {
+///     fn get(&self, url: &str) -> Result<Vec<u8>, QuantCryptError> {
+///         Err(QuantCryptError::FetchFailed { url: url.to_string() })
+///     }
+///
+///     fn post(&self, url: &str, _: &str, _: &[u8]) -> Result<Vec<u8>, QuantCryptError> {
+///         Err(QuantCryptError::FetchFailed { url: url.to_string() })
+///     }
+/// }
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let cert = Certificate::from_file(cert_path).unwrap();
+///
+/// let fetcher = CachingFetcher::new(Offline, Duration::from_secs(300));
+/// let checker = RevocationChecker::new(&fetcher);
+/// assert_eq!(checker.check(&cert, &cert).unwrap(), RevocationStatus::Unknown);
+/// ```
+pub struct RevocationChecker<'a> {
+    fetcher: &'a dyn Fetcher,
+    use_ocsp: bool,
+    use_crl: bool,
+    policy: Option<CryptoPolicy>,
+}
+
+impl<'a> RevocationChecker<'a> {
+    /// Create a new revocation checker using both OCSP and CRLs
+    ///
+    /// # Arguments
+    ///
+    /// * `fetcher` - The fetcher used to query OCSP responders and download CRLs
+    ///
+    /// # Returns
+    ///
+    /// A new revocation checker
+    pub fn new(fetcher: &'a dyn Fetcher) -> RevocationChecker<'a> {
+        RevocationChecker {
+            fetcher,
+            use_ocsp: true,
+            use_crl: true,
+            policy: None,
+        }
+    }
+
+    /// Enable or disable querying OCSP responders
+    ///
+    /// # Arguments
+    ///
+    /// * `use_ocsp` - True to query OCSP responders
+    ///
+    /// # Returns
+    ///
+    /// The checker, for chaining
+    pub fn set_use_ocsp(&mut self, use_ocsp: bool) -> &mut Self {
+        self.use_ocsp = use_ocsp;
+        self
+    }
+
+    /// Enable or disable downloading CRLs
+    ///
+    /// # Arguments
+    ///
+    /// * `use_crl` - True to download CRLs from the CRL distribution points
+    ///
+    /// # Returns
+    ///
+    /// The checker, for chaining
+    pub fn set_use_crl(&mut self, use_crl: bool) -> &mut Self {
+        self.use_crl = use_crl;
+        self
+    }
+
+    /// Set the crypto policy the signatures of CRLs and OCSP responses must satisfy
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy
+    ///
+    /// # Returns
+    ///
+    /// The checker, for chaining
+    pub fn set_crypto_policy(&mut self, policy: CryptoPolicy) -> &mut Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Check the revocation status of a certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate to check
+    /// * `issuer` - The certificate of the issuer of `cert`
+    ///
+    /// # Returns
+    ///
+    /// The revocation status of the certificate
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::CryptoPolicyViolation` if a CRL or OCSP response violates the
+    /// crypto policy and the policy fails on violations
+    pub fn check(&self, cert: &Certificate, issuer: &Certificate) -> Result<RevocationStatus> {
+        self.check_in_path(cert, issuer, &[cert.clone(), issuer.clone()])
+    }
+
+    /// Check the revocation status of a certificate of a path
+    fn check_in_path(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        path: &[Certificate],
+    ) -> Result<RevocationStatus> {
+        let policy = self.policy.as_ref().map(|policy| (policy, path));
+
+        if self.use_ocsp {
+            for url in cert.get_ocsp_responders() {
+                match self.query_ocsp(cert, issuer, &url, policy) {
+                    Ok(RevocationStatus::Unknown) => {}
+                    Ok(status) => return Ok(status),
+                    Err(err) if self.is_fatal(&err) => return Err(err),
+                    Err(_) => {}
+                }
+            }
+        }
+
+        if self.use_crl {
+            for url in cert.get_crl_distribution_points() {
+                match self.query_crl(cert, issuer, &url, policy) {
+                    Ok(status) => return Ok(status),
+                    Err(err) if self.is_fatal(&err) => return Err(err),
+                    Err(_) => {}
+                }
+            }
+        }
+
+        Ok(RevocationStatus::Unknown)
+    }
+
+    /// Check if an error from a revocation source fails the check, rather than skipping the source
+    fn is_fatal(&self, err: &QuantCryptError) -> bool {
+        matches!(err, QuantCryptError::CryptoPolicyViolation { .. })
+            && self
+                .policy
+                .as_ref()
+                .map_or(false, |policy| policy.is_fail_on_violation())
+    }
+
+    /// Check the revocation status of every certificate in a path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The certificates, starting with the end-entity and ending with the trust anchor
+    ///
+    /// # Returns
+    ///
+    /// The status of each certificate except the trust anchor, in the same order
+    pub fn check_path(&self, path: &[Certificate]) -> Result<Vec<RevocationStatus>> {
+        path.windows(2)
+            .map(|pair| self.check_in_path(&pair[0], &pair[1], path))
+            .collect()
+    }
+
+    /// Download a CRL, and a delta CRL if one is available
+    ///
+    /// Delta CRLs are looked up in the Freshest CRL extensions of the certificate and
+    /// of the CRL. If none can be retrieved or used, the complete CRL is used alone.
+    fn query_crl(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        url: &str,
+        policy: PolicyContext,
+    ) -> Result<RevocationStatus> {
+        let base = self.fetcher.get(url)?;
+        let mut delta_urls = cert.get_freshest_crl_urls();
+        delta_urls.extend(get_crl_freshest_urls(&base));
+        for delta_url in delta_urls {
+            let status = self
+                .fetcher
+                .get(&delta_url)
+                .and_then(|delta| check_crls(cert, issuer, &base, Some(&delta), policy));
+            match status {
+                Ok(status) => return Ok(status),
+                Err(err) if self.is_fatal(&err) => return Err(err),
+                Err(_) => {}
+            }
+        }
+        check_crls(cert, issuer, &base, None, policy)
+    }
+
+    /// Query an OCSP responder
+    fn query_ocsp(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        url: &str,
+        policy: PolicyContext,
+    ) -> Result<RevocationStatus> {
+        let mut nonce = [0u8; OCSP_NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let request = build_ocsp_request(cert, issuer, &nonce)?;
+        let response = self
+            .fetcher
+            .post(url, OCSP_REQUEST_CONTENT_TYPE, &request)?;
+        verify_ocsp_response(cert, issuer, &response, Some(&nonce), policy)
+    }
+}
+
+/// Convert an X.509 time to a chrono time
+fn to_chrono(time: &Time) -> DateTime<Utc> {
+    time.to_system_time().into()
+}
+
+/// Convert an OCSP time to a chrono time
+pub(crate) fn ocsp_to_chrono(time: &OcspGeneralizedTime) -> DateTime<Utc> {
+    time.0.to_system_time().into()
+}
+
+/// Check if a CRL or OCSP response is current, allowing for clock skew
+fn is_current(this_update: DateTime<Utc>, next_update: Option<DateTime<Utc>>) -> bool {
+    is_within(this_update, next_update, get_clock_skew_tolerance())
+}
+
+/// Parse a CRL and check that it was issued by `issuer`, is current and can be used
+///
+/// A delta CRL must carry a Delta CRL Indicator, and a complete CRL must not. Critical
+/// extensions other than the Delta CRL Indicator and the Issuing Distribution Point
+/// change the meaning of the list, so a CRL carrying one is rejected. The scope of
+/// an issuing distribution point is not checked. The signature is checked against
+/// the crypto policy, if any, once it is known to be the issuer's.
+fn parse_crl(
+    issuer: &Certificate,
+    crl_der: &[u8],
+    delta: bool,
+    policy: PolicyContext,
+) -> Result<CertificateList> {
+    let crl = CertificateList::from_der(crl_der).map_err(|_| QuantCryptError::InvalidCrl)?;
+    let tbs = &crl.tbs_cert_list;
+
+    if tbs.issuer != issuer.get_subject() {
+        return Err(QuantCryptError::InvalidCrl);
+    }
+
+    let mut is_delta = false;
+    for ext in tbs.crl_extensions.iter().flatten() {
+        if ext.extn_id == rfc5280::ID_CE_DELTA_CRL_INDICATOR {
+            is_delta = true;
+        } else if ext.critical && ext.extn_id != rfc5280::ID_CE_ISSUING_DISTRIBUTION_POINT {
+            return Err(QuantCryptError::InvalidCrl);
+        }
+    }
+    if is_delta != delta {
+        return Err(QuantCryptError::InvalidCrl);
+    }
+
+    let msg = tbs.to_der().map_err(|_| QuantCryptError::InvalidCrl)?;
+    let pk = issuer.get_public_key()?;
+    if !pk.verify(&msg, crl.signature.raw_bytes())? {
+        return Err(QuantCryptError::InvalidCrl);
+    }
+    if let Some((policy, path)) = policy {
+        let oids = [
+            crl.signature_algorithm.oid.to_string(),
+            tbs.signature.oid.to_string(),
+        ];
+        policy.check_signature(&oids, issuer, path)?;
+    }
+
+    if !is_current(
+        to_chrono(&tbs.this_update),
+        tbs.next_update.as_ref().map(to_chrono),
+    ) {
+        return Err(QuantCryptError::InvalidCrl);
+    }
+    Ok(crl)
+}
+
+/// Get a CRL number, or the base CRL number of a delta CRL, as an integer
+fn get_crl_number(crl: &CertificateList, oid: ObjectIdentifier) -> Option<BigUint> {
+    let ext = crl
+        .tbs_cert_list
+        .crl_extensions
+        .iter()
+        .flatten()
+        .find(|ext| ext.extn_id == oid)?;
+    // CRL Number and Base CRL Number share the same encoding
+    let number = CrlNumber::from_der(ext.extn_value.as_bytes()).ok()?;
+    Some(BigUint::from_bytes_be(number.0.as_bytes()))
+}
+
+/// Get the status of a certificate listed in a CRL
+fn find_entry(crl: &CertificateList, serial: &SerialNumber) -> Option<RevocationStatus> {
+    let revoked = crl
+        .tbs_cert_list
+        .revoked_certificates
+        .iter()
+        .flatten()
+        .find(|revoked| revoked.serial_number == *serial)?;
+    let reason = revoked
+        .crl_entry_extensions
+        .iter()
+        .flatten()
+        .find(|ext| ext.extn_id == rfc5280::ID_CE_CRL_REASONS)
+        .and_then(|ext| CrlReason::from_der(ext.extn_value.as_bytes()).ok());
+    Some(RevocationStatus::Revoked {
+        revocation_time: to_chrono(&revoked.revocation_date),
+        reason,
+    })
+}
+
+/// Get the HTTP URLs of the delta CRLs listed in the Freshest CRL extension of a CRL
+fn get_crl_freshest_urls(crl_der: &[u8]) -> Vec<String> {
+    let crl = match CertificateList::from_der(crl_der) {
+        Ok(crl) => crl,
+        Err(_) => return Vec::new(),
+    };
+    crl.tbs_cert_list
+        .crl_extensions
+        .iter()
+        .flatten()
+        .filter(|ext| ext.extn_id == rfc5280::ID_CE_FRESHEST_CRL)
+        .filter_map(|ext| FreshestCrl::from_der(ext.extn_value.as_bytes()).ok())
+        .flat_map(|freshest| get_distribution_point_urls(&freshest.0))
+        .collect()
+}
+
+/// Check the revocation status of a certificate against a DER encoded CRL
+///
+/// # Arguments
+///
+/// * `cert` - The certificate to check
+/// * `issuer` - The certificate of the issuer of `cert`, which must also have issued the CRL
+/// * `crl_der` - The DER encoded CRL
+///
+/// # Returns
+///
+/// `RevocationStatus::Revoked` if the certificate is listed, `RevocationStatus::Good` otherwise
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidCrl` if the CRL cannot be parsed, was not issued by `issuer`,
+/// has an invalid signature, is not current, is a delta CRL, or has an unsupported
+/// critical extension
+pub fn check_crl(
+    cert: &Certificate,
+    issuer: &Certificate,
+    crl_der: &[u8],
+) -> Result<RevocationStatus> {
+    check_crl_with_delta(cert, issuer, crl_der, None)
+}
+
+/// Check the revocation status of a certificate against a complete CRL and a delta CRL
+///
+/// The delta CRL lists the changes since the complete CRL it is based on (RFC 5280
+/// Section 5.2.4). Certificates revoked since then are listed with their reason, and
+/// certificates released from hold with the `removeFromCRL` reason.
+///
+/// # Arguments
+///
+/// * `cert` - The certificate to check
+/// * `issuer` - The certificate of the issuer of `cert`, which must also have issued the CRLs
+/// * `base_der` - The DER encoded complete CRL
+/// * `delta_der` - The DER encoded delta CRL, if any
+///
+/// # Returns
+///
+/// `RevocationStatus::Revoked` if the certificate is revoked according to the combined
+/// CRLs, `RevocationStatus::Good` otherwise
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidCrl` if a CRL is invalid as per `check_crl`, the delta CRL
+/// has no Delta CRL Indicator, or the complete CRL is older than the base of the delta CRL
+pub fn check_crl_with_delta(
+    cert: &Certificate,
+    issuer: &Certificate,
+    base_der: &[u8],
+    delta_der: Option<&[u8]>,
+) -> Result<RevocationStatus> {
+    check_crls(cert, issuer, base_der, delta_der, None)
+}
+
+/// Check the revocation status of a certificate against CRLs, under a crypto policy
+fn check_crls(
+    cert: &Certificate,
+    issuer: &Certificate,
+    base_der: &[u8],
+    delta_der: Option<&[u8]>,
+    policy: PolicyContext,
+) -> Result<RevocationStatus> {
+    if cert.get_issuer() != issuer.get_subject() {
+        return Err(QuantCryptError::InvalidCrl);
+    }
+    let base = parse_crl(issuer, base_der, false, policy)?;
+    let serial = cert.get_serial_number();
+
+    if let Some(delta_der) = delta_der {
+        let delta = parse_crl(issuer, delta_der, true, policy)?;
+        let base_number =
+            get_crl_number(&base, rfc5280::ID_CE_CRL_NUMBER).ok_or(QuantCryptError::InvalidCrl)?;
+        let delta_base = get_crl_number(&delta, rfc5280::ID_CE_DELTA_CRL_INDICATOR)
+            .ok_or(QuantCryptError::InvalidCrl)?;
+        let delta_number =
+            get_crl_number(&delta, rfc5280::ID_CE_CRL_NUMBER).ok_or(QuantCryptError::InvalidCrl)?;
+        if base_number < delta_base || delta_number <= base_number {
+            return Err(QuantCryptError::InvalidCrl);
+        }
+
+        match find_entry(&delta, &serial) {
+            Some(RevocationStatus::Revoked {
+                reason: Some(CrlReason::RemoveFromCRL),
+                ..
+            }) => return Ok(RevocationStatus::Good),
+            Some(status) => return Ok(status),
+            None => {}
+        }
+    }
+
+    Ok(find_entry(&base, &serial).unwrap_or(RevocationStatus::Good))
+}
+
+/// Compute the SHA-1 OCSP certificate ID of a certificate
+///
+/// SHA-1 is used as it is the only hash algorithm that RFC 5019 responders must support.
+fn get_cert_id(cert: &Certificate, issuer: &Certificate) -> Result<CertId> {
+    let issuer = issuer.get_inner();
+    let issuer_name = issuer
+        .tbs_certificate
+        .subject
+        .to_der()
+        .map_err(|_| QuantCryptError::InvalidCertificate)?;
+    let issuer_key = issuer
+        .tbs_certificate
+        .subject_public_key_info
+        .subject_public_key
+        .raw_bytes();
+    Ok(CertId {
+        hash_algorithm: AlgorithmIdentifierOwned {
+            oid: rfc5912::ID_SHA_1,
+            parameters: Some(Null.into()),
+        },
+        issuer_name_hash: OctetString::new(Sha1::digest(&issuer_name).to_vec())
+            .map_err(|_| QuantCryptError::InvalidCertificate)?,
+        issuer_key_hash: OctetString::new(Sha1::digest(issuer_key).to_vec())
+            .map_err(|_| QuantCryptError::InvalidCertificate)?,
+        serial_number: cert.get_serial_number(),
+    })
+}
+
+/// Build a DER encoded OCSP request for a certificate
+///
+/// # Arguments
+///
+/// * `cert` - The certificate to query the status of
+/// * `issuer` - The certificate of the issuer of `cert`
+/// * `nonce` - The nonce the responder should echo
+///
+/// # Returns
+///
+/// The DER encoded OCSP request
+pub(crate) fn build_ocsp_request(
+    cert: &Certificate,
+    issuer: &Certificate,
+    nonce: &[u8],
+) -> Result<Vec<u8>> {
+    let nonce = Nonce::new(nonce)
+        .and_then(|nonce| nonce.to_der())
+        .and_then(OctetString::new)
+        .map_err(|_| QuantCryptError::Unknown)?;
+    let request = OcspRequest {
+        tbs_request: TbsRequest {
+            version: Default::default(),
+            requestor_name: None,
+            request_list: vec![Request {
+                req_cert: get_cert_id(cert, issuer)?,
+                single_request_extensions: None,
+            }],
+            request_extensions: Some(vec![Extension {
+                extn_id: rfc6960::ID_PKIX_OCSP_NONCE,
+                critical: false,
+                extn_value: nonce,
+            }]),
+        },
+        optional_signature: None,
+    };
+    request.to_der().map_err(|_| QuantCryptError::Unknown)
+}
+
+/// Check if a certificate is identified by an OCSP responder ID
+fn is_responder(cert: &Certificate, responder_id: &ResponderId) -> bool {
+    match responder_id {
+        ResponderId::ByName(name) => *name == cert.get_subject(),
+        ResponderId::ByKey(key_hash) => {
+            let key = cert
+                .get_inner()
+                .tbs_certificate
+                .subject_public_key_info
+                .subject_public_key
+                .raw_bytes();
+            key_hash.as_bytes() == Sha1::digest(key).as_slice()
+        }
+    }
+}
+
+/// Check if a certificate is authorized to sign OCSP responses on behalf of its issuer
+pub(crate) fn is_ocsp_signer(cert: &Certificate) -> bool {
+    cert.get_inner()
+        .tbs_certificate
+        .extensions
+        .iter()
+        .flatten()
+        .filter(|ext| ext.extn_id == rfc5280::ID_CE_EXT_KEY_USAGE)
+        .filter_map(|ext| ExtendedKeyUsage::from_der(ext.extn_value.as_bytes()).ok())
+        .any(|eku| eku.0.contains(&rfc5280::ID_KP_OCSP_SIGNING))
+}
+
+/// Get the certificate of the responder that signed an OCSP response
+///
+/// This is either the issuer, or a delegated responder certificate included
+/// in the response and issued by the issuer.
+fn get_responder(response: &BasicOcspResponse, issuer: &Certificate) -> Result<Certificate> {
+    let responder_id = &response.tbs_response_data.responder_id;
+    if is_responder(issuer, responder_id) {
+        return Ok(issuer.clone());
+    }
+    for cert in response.certs.iter().flatten() {
+        let cert = Certificate::new(cert.clone());
+        if is_responder(&cert, responder_id)
+            && is_ocsp_signer(&cert)
+            && cert.is_valid()
+            && issuer.verify_child(&cert).unwrap_or(false)
+        {
+            return Ok(cert);
+        }
+    }
+    Err(QuantCryptError::InvalidOcspResponse)
+}
+
+/// Check the revocation status of a certificate against a DER encoded OCSP response
+///
+/// # Arguments
+///
+/// * `cert` - The certificate to check
+/// * `issuer` - The certificate of the issuer of `cert`
+/// * `response_der` - The DER encoded OCSP response
+/// * `nonce` - The nonce sent in the request. If the responder echoed a nonce, it must match.
+///
+/// # Returns
+///
+/// The status reported by the responder
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidOcspResponse` if the response cannot be parsed, is not
+/// successful, has an invalid signature, has a mismatching nonce, or has no current
+/// status for the certificate
+pub fn check_ocsp_response(
+    cert: &Certificate,
+    issuer: &Certificate,
+    response_der: &[u8],
+    nonce: Option<&[u8]>,
+) -> Result<RevocationStatus> {
+    verify_ocsp_response(cert, issuer, response_der, nonce, None)
+}
+
+/// Check the revocation status of a certificate against an OCSP response, under a crypto policy
+fn verify_ocsp_response(
+    cert: &Certificate,
+    issuer: &Certificate,
+    response_der: &[u8],
+    nonce: Option<&[u8]>,
+    policy: PolicyContext,
+) -> Result<RevocationStatus> {
+    let response =
+        OcspResponse::from_der(response_der).map_err(|_| QuantCryptError::InvalidOcspResponse)?;
+    if response.response_status != OcspResponseStatus::Successful {
+        return Err(QuantCryptError::InvalidOcspResponse);
+    }
+    let response_bytes = response
+        .response_bytes
+        .ok_or(QuantCryptError::InvalidOcspResponse)?;
+    if response_bytes.response_type != rfc6960::ID_PKIX_OCSP_BASIC {
+        return Err(QuantCryptError::InvalidOcspResponse);
+    }
+    let basic = BasicOcspResponse::from_der(response_bytes.response.as_bytes())
+        .map_err(|_| QuantCryptError::InvalidOcspResponse)?;
+
+    let msg = basic
+        .tbs_response_data
+        .to_der()
+        .map_err(|_| QuantCryptError::InvalidOcspResponse)?;
+    let responder = get_responder(&basic, issuer)?;
+    if !responder
+        .get_public_key()?
+        .verify(&msg, basic.signature.raw_bytes())?
+    {
+        return Err(QuantCryptError::InvalidOcspResponse);
+    }
+    if let Some((policy, path)) = policy {
+        let oids = [basic.signature_algorithm.oid.to_string()];
+        policy.check_signature(&oids, &responder, path)?;
+    }
+
+    if let (Some(expected), Some(echoed)) = (nonce, basic.nonce()) {
+        if echoed.0.as_bytes() != expected {
+            return Err(QuantCryptError::InvalidOcspResponse);
+        }
+    }
+
+    let cert_id = get_cert_id(cert, issuer)?;
+    let single = basic
+        .tbs_response_data
+        .responses
+        .iter()
+        .find(|single| {
+            single.cert_id.hash_algorithm.oid == cert_id.hash_algorithm.oid
+                && single.cert_id.issuer_name_hash == cert_id.issuer_name_hash
+                && single.cert_id.issuer_key_hash == cert_id.issuer_key_hash
+                && single.cert_id.serial_number == cert_id.serial_number
+        })
+        .ok_or(QuantCryptError::InvalidOcspResponse)?;
+
+    if !is_current(
+        ocsp_to_chrono(&single.this_update),
+        single.next_update.as_ref().map(ocsp_to_chrono),
+    ) {
+        return Err(QuantCryptError::InvalidOcspResponse);
+    }
+
+    Ok(match single.cert_status {
+        CertStatus::Good(_) => RevocationStatus::Good,
+        CertStatus::Revoked(info) => RevocationStatus::Revoked {
+            revocation_time: ocsp_to_chrono(&info.revocation_time),
+            reason: info.revocation_reason,
+        },
+        CertStatus::Unknown(_) => RevocationStatus::Unknown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::{Duration, SystemTime};
+
+    use der::asn1::{BitString, GeneralizedTime, Ia5String};
+    use x509_cert::crl::{RevokedCert, TbsCertList};
+    use x509_cert::ext::pkix::crl::dp::DistributionPoint;
+    use x509_cert::ext::pkix::name::{DistributionPointName, GeneralName};
+    use x509_cert::ext::pkix::{
+        AccessDescription, AuthorityInfoAccessSyntax, CrlDistributionPoints,
+    };
+    use x509_cert::serial_number::SerialNumber;
+    use x509_ocsp::{ResponseData, RevokedInfo, SingleResponse};
+
+    use super::*;
+    use crate::certificates::{CertValidity, CertificateBuilder, Profile};
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use crate::keys::PrivateKey;
+
+    const CRL_URL: &str = "http://crl.example.com/ca.crl";
+    const OCSP_URL: &str = "http://ocsp.example.com";
+
+    fn to_time(time: SystemTime) -> Time {
+        Time::GeneralTime(GeneralizedTime::from_system_time(time).unwrap())
+    }
+
+    fn to_ocsp_time(time: SystemTime) -> OcspGeneralizedTime {
+        OcspGeneralizedTime(GeneralizedTime::from_system_time(time).unwrap())
+    }
+
+    /// A CA serving a CRL and answering OCSP requests
+    struct MockCa {
+        cert: Certificate,
+        sk: PrivateKey,
+        revoked: Vec<SerialNumber>,
+        crl_available: bool,
+        ocsp_available: bool,
+        /// The signature algorithm to declare instead of the one of the key
+        declared_oid: Option<ObjectIdentifier>,
+    }
+
+    impl MockCa {
+        fn get_declared_oid(&self) -> ObjectIdentifier {
+            self.declared_oid
+                .unwrap_or_else(|| self.sk.get_oid().parse().unwrap())
+        }
+
+        fn issue_crl(&self) -> Vec<u8> {
+            let now = SystemTime::now();
+            let revoked = self
+                .revoked
+                .iter()
+                .map(|serial| RevokedCert {
+                    serial_number: serial.clone(),
+                    revocation_date: to_time(now - Duration::from_secs(60)),
+                    crl_entry_extensions: None,
+                })
+                .collect();
+            let tbs = TbsCertList {
+                version: x509_cert::Version::V2,
+                signature: AlgorithmIdentifierOwned {
+                    oid: self.get_declared_oid(),
+                    parameters: None,
+                },
+                issuer: self.cert.get_subject(),
+                this_update: to_time(now - Duration::from_secs(60)),
+                next_update: Some(to_time(now + Duration::from_secs(3600))),
+                revoked_certificates: Some(revoked),
+                crl_extensions: None,
+            };
+            let sig = self.sk.sign(&tbs.to_der().unwrap()).unwrap();
+            CertificateList {
+                signature_algorithm: tbs.signature.clone(),
+                tbs_cert_list: tbs,
+                signature: BitString::from_bytes(&sig).unwrap(),
+            }
+            .to_der()
+            .unwrap()
+        }
+
+        fn respond(&self, request: &[u8]) -> Vec<u8> {
+            let request = OcspRequest::from_der(request).unwrap();
+            let now = SystemTime::now();
+            let responses = request
+                .tbs_request
+                .request_list
+                .iter()
+                .map(|req| {
+                    let cert_status = if self.revoked.contains(&req.req_cert.serial_number) {
+                        CertStatus::revoked(RevokedInfo {
+                            revocation_time: to_ocsp_time(now - Duration::from_secs(60)),
+                            revocation_reason: Some(CrlReason::KeyCompromise),
+                        })
+                    } else {
+                        CertStatus::good()
+                    };
+                    SingleResponse {
+                        cert_id: req.req_cert.clone(),
+                        cert_status,
+                        this_update: to_ocsp_time(now - Duration::from_secs(60)),
+                        next_update: Some(to_ocsp_time(now + Duration::from_secs(3600))),
+                        single_extensions: None,
+                    }
+                })
+                .collect();
+            let tbs = ResponseData {
+                version: Default::default(),
+                responder_id: ResponderId::ByName(self.cert.get_subject()),
+                produced_at: to_ocsp_time(now),
+                responses,
+                response_extensions: request.tbs_request.request_extensions.clone(),
+            };
+            let sig = self.sk.sign(&tbs.to_der().unwrap()).unwrap();
+            let basic = BasicOcspResponse {
+                tbs_response_data: tbs,
+                signature_algorithm: AlgorithmIdentifierOwned {
+                    oid: self.get_declared_oid(),
+                    parameters: None,
+                },
+                signature: BitString::from_bytes(&sig).unwrap(),
+                certs: None,
+            };
+            OcspResponse::successful(basic).unwrap().to_der().unwrap()
+        }
+    }
+
+    impl Fetcher for MockCa {
+        fn get(&self, url: &str) -> Result<Vec<u8>> {
+            if url == CRL_URL && self.crl_available {
+                Ok(self.issue_crl())
+            } else {
+                Err(QuantCryptError::FetchFailed {
+                    url: url.to_string(),
+                })
+            }
+        }
+
+        fn post(&self, url: &str, content_type: &str, body: &[u8]) -> Result<Vec<u8>> {
+            if url == OCSP_URL && content_type == OCSP_REQUEST_CONTENT_TYPE && self.ocsp_available {
+                Ok(self.respond(body))
+            } else {
+                Err(QuantCryptError::FetchFailed {
+                    url: url.to_string(),
+                })
+            }
+        }
+    }
+
+    fn uri(url: &str) -> GeneralName {
+        GeneralName::UniformResourceIdentifier(Ia5String::new(url).unwrap())
+    }
+
+    fn issue_leaf(ca: &MockCa, cn: &str) -> Certificate {
+        let (pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let mut builder = CertificateBuilder::new(
+            Profile::Leaf {
+                issuer: ca.cert.get_subject(),
+                enable_key_agreement: false,
+                enable_key_encipherment: false,
+            },
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            cn.to_string(),
+            pk,
+            &ca.sk,
+        )
+        .unwrap();
+        builder
+            .add_extension(CrlDistributionPoints(vec![DistributionPoint {
+                distribution_point: Some(DistributionPointName::FullName(vec![uri(CRL_URL)])),
+                reasons: None,
+                crl_issuer: None,
+            }]))
+            .unwrap();
+        builder
+            .add_extension(AuthorityInfoAccessSyntax(vec![AccessDescription {
+                access_method: rfc5280::ID_AD_OCSP,
+                access_location: uri(OCSP_URL),
+            }]))
+            .unwrap();
+        builder.build().unwrap()
+    }
+
+    fn new_ca() -> MockCa {
+        let (pk, sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let cert = CertificateBuilder::new(
+            Profile::Root,
+            None,
+            CertValidity::new(None, "2035-01-01T00:00:00Z").unwrap(),
+            "CN=ca.example.com".to_string(),
+            pk,
+            &sk,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        MockCa {
+            cert,
+            sk,
+            revoked: Vec::new(),
+            crl_available: true,
+            ocsp_available: true,
+            declared_oid: None,
+        }
+    }
+
+    #[test]
+    fn test_revocation_sources() {
+        let mut ca = new_ca();
+        let good = issue_leaf(&ca, "CN=good.example.com");
+        let revoked = issue_leaf(&ca, "CN=revoked.example.com");
+        ca.revoked.push(revoked.get_serial_number());
+
+        assert_eq!(good.get_crl_distribution_points(), vec![CRL_URL]);
+        assert_eq!(good.get_ocsp_responders(), vec![OCSP_URL]);
+        assert!(ca.cert.get_crl_distribution_points().is_empty());
+
+        // OCSP first
+        let checker = RevocationChecker::new(&ca);
+        assert_eq!(
+            checker.check(&good, &ca.cert).unwrap(),
+            RevocationStatus::Good
+        );
+        match checker.check(&revoked, &ca.cert).unwrap() {
+            RevocationStatus::Revoked { reason, .. } => {
+                assert_eq!(reason, Some(CrlReason::KeyCompromise))
+            }
+            status => panic!("Unexpected status {:?}", status),
+        }
+
+        // CRLs only
+        let mut checker = RevocationChecker::new(&ca);
+        checker.set_use_ocsp(false);
+        assert_eq!(
+            checker.check(&good, &ca.cert).unwrap(),
+            RevocationStatus::Good
+        );
+        assert!(matches!(
+            checker.check(&revoked, &ca.cert).unwrap(),
+            RevocationStatus::Revoked { reason: None, .. }
+        ));
+
+        // Paths are checked up to the trust anchor
+        let statuses = checker
+            .check_path(&[revoked.clone(), ca.cert.clone()])
+            .unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert!(matches!(statuses[0], RevocationStatus::Revoked { .. }));
+
+        // Falls back to the CRL when the OCSP responder is down
+        ca.ocsp_available = false;
+        let checker = RevocationChecker::new(&ca);
+        assert!(matches!(
+            checker.check(&revoked, &ca.cert).unwrap(),
+            RevocationStatus::Revoked { .. }
+        ));
+
+        // Unknown when no source is reachable
+        ca.crl_available = false;
+        let checker = RevocationChecker::new(&ca);
+        assert_eq!(
+            checker.check(&revoked, &ca.cert).unwrap(),
+            RevocationStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_forged_responses() {
+        let ca = new_ca();
+        let other_ca = new_ca();
+        let leaf = issue_leaf(&ca, "CN=leaf.example.com");
+
+        // Signed by someone else
+        let mut forger = new_ca();
+        forger.cert = ca.cert.clone();
+        forger.sk = other_ca.sk;
+        let crl = forger.issue_crl();
+        assert_eq!(
+            check_crl(&leaf, &ca.cert, &crl),
+            Err(QuantCryptError::InvalidCrl)
+        );
+
+        let nonce = [7u8; OCSP_NONCE_LEN];
+        let request = build_ocsp_request(&leaf, &ca.cert, &nonce).unwrap();
+        let response = forger.respond(&request);
+        assert_eq!(
+            check_ocsp_response(&leaf, &ca.cert, &response, Some(&nonce)),
+            Err(QuantCryptError::InvalidOcspResponse)
+        );
+
+        // Replayed response
+        let response = ca.respond(&request);
+        assert_eq!(
+            check_ocsp_response(&leaf, &ca.cert, &response, Some(&nonce)),
+            Ok(RevocationStatus::Good)
+        );
+        assert_eq!(
+            check_ocsp_response(&leaf, &ca.cert, &response, Some(&[8u8; OCSP_NONCE_LEN])),
+            Err(QuantCryptError::InvalidOcspResponse)
+        );
+
+        // Response for another certificate
+        let other = issue_leaf(&ca, "CN=other.example.com");
+        assert_eq!(
+            check_ocsp_response(&other, &ca.cert, &response, Some(&nonce)),
+            Err(QuantCryptError::InvalidOcspResponse)
+        );
+
+        // The DN in the response must be the issuer's
+        let name = x509_cert::name::Name::from_str("CN=other.example.com").unwrap();
+        assert!(!is_responder(&ca.cert, &ResponderId::ByName(name)));
+        let key_hash = OctetString::new(
+            Sha1::digest(
+                ca.cert
+                    .get_inner()
+                    .tbs_certificate
+                    .subject_public_key_info
+                    .subject_public_key
+                    .raw_bytes(),
+            )
+            .to_vec(),
+        )
+        .unwrap();
+        assert!(is_responder(&ca.cert, &ResponderId::ByKey(key_hash)));
+    }
+
+    #[test]
+    fn test_revocation_crypto_policy() {
+        let mut ca = new_ca();
+        let revoked = issue_leaf(&ca, "CN=revoked.example.com");
+        ca.revoked.push(revoked.get_serial_number());
+
+        // The ML-DSA-44 CA satisfies a policy matching its path
+        let mut checker = RevocationChecker::new(&ca);
+        checker.set_crypto_policy(CryptoPolicy::new());
+        assert!(matches!(
+            checker.check(&revoked, &ca.cert).unwrap(),
+            RevocationStatus::Revoked { .. }
+        ));
+
+        // But not one requiring NIST category 3
+        let mut policy = CryptoPolicy::new();
+        policy.set_min_quantum_security_level(3);
+        let mut checker = RevocationChecker::new(&ca);
+        checker.set_crypto_policy(policy.clone());
+        assert_eq!(
+            checker.check(&revoked, &ca.cert).unwrap(),
+            RevocationStatus::Unknown
+        );
+        policy.set_fail_on_violation(true);
+        checker.set_crypto_policy(policy);
+        assert!(matches!(
+            checker.check_path(&[revoked.clone(), ca.cert.clone()]),
+            Err(QuantCryptError::CryptoPolicyViolation { .. })
+        ));
+
+        // A CRL claiming SHA-1 with RSA is rejected even though the ML-DSA signature verifies
+        ca.ocsp_available = false;
+        ca.declared_oid = Some(rfc5912::SHA_1_WITH_RSA_ENCRYPTION);
+        let crl = ca.issue_crl();
+        assert!(matches!(
+            check_crl(&revoked, &ca.cert, &crl),
+            Ok(RevocationStatus::Revoked { .. })
+        ));
+        let mut policy = CryptoPolicy::new();
+        policy.set_fail_on_violation(true);
+        let mut checker = RevocationChecker::new(&ca);
+        checker.set_crypto_policy(policy);
+        assert!(matches!(
+            checker.check(&revoked, &ca.cert),
+            Err(QuantCryptError::CryptoPolicyViolation { .. })
+        ));
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::asn1::public_key::PublicKey;
+use crate::utils::resource_limits::read_file_limited;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A hook run on every event of a TOFU store
+type EventHook = Box<dyn Fn(&TofuEvent) + Send + Sync>;
+
+/// The outcome of checking the key presented by a peer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TofuStatus {
+    /// The peer was unknown, its key is now trusted
+    FirstUse,
+    /// The key is the one trusted for the peer
+    Trusted,
+    /// The key differs from the one trusted for the peer, which is kept
+    Changed {
+        /// The fingerprint of the trusted key
+        previous: String,
+    },
+}
+
+/// An event of a TOFU store, passed to the event hooks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TofuEvent {
+    /// The key of a new peer was recorded
+    FirstUse {
+        /// The identity of the peer
+        peer: String,
+        /// The fingerprint of the key
+        fingerprint: String,
+    },
+    /// A peer presented another key than the trusted one
+    KeyChanged {
+        /// The identity of the peer
+        peer: String,
+        /// The fingerprint of the trusted key
+        previous: String,
+        /// The fingerprint of the presented key
+        presented: String,
+    },
+    /// The trusted key of a peer was replaced with `accept`
+    KeyReplaced {
+        /// The identity of the peer
+        peer: String,
+        /// The fingerprint of the previously trusted key
+        previous: String,
+        /// The fingerprint of the new key
+        fingerprint: String,
+    },
+}
+
+/// The key trusted for a peer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TofuRecord {
+    fingerprint: String,
+    first_seen: DateTime<Utc>,
+}
+
+impl TofuRecord {
+    /// Get the fingerprint of the key, the base64 encoded SHA-256 of its SubjectPublicKeyInfo
+    pub fn get_fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Get the time the key was first trusted
+    pub fn get_first_seen(&self) -> DateTime<Utc> {
+        self.first_seen
+    }
+}
+
+/// A trust-on-first-use store of the keys of peers
+///
+/// For deployments without a PKI, such as SSH-like tools or IoT devices using
+/// raw ML-DSA keys: the first key a peer presents is trusted, and a later
+/// different key is reported rather than silently accepted. The store keeps
+/// the fingerprint of each key, which is its SPKI pin (see
+/// `PublicKey::get_spki_pin`), keyed by the identity of the peer.
+///
+/// Changed keys are never trusted automatically. Confirm the change out of
+/// band, then call `accept`. Persist the store with `save` and `load`, in a
+/// text format similar to the `known_hosts` file of SSH.
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::{TofuStatus, TofuStore};
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+///
+/// let (pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+/// let (other_pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+///
+/// let mut store = TofuStore::new();
+/// assert_eq!(store.check("sensor-17", &pk).unwrap(), TofuStatus::FirstUse);
+/// assert_eq!(store.check("sensor-17", &pk).unwrap(), TofuStatus::Trusted);
+/// assert!(matches!(
+///     store.check("sensor-17", &other_pk).unwrap(),
+///     TofuStatus::Changed { .. }
+/// ));
+/// ```
+#[derive(Default)]
+pub struct TofuStore {
+    records: BTreeMap<String, TofuRecord>,
+    hooks: Vec<EventHook>,
+}
+
+impl TofuStore {
+    /// Create an empty store
+    pub fn new() -> TofuStore {
+        TofuStore::default()
+    }
+
+    /// Load a store saved with `save`
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file
+    ///
+    /// # Returns
+    ///
+    /// The store, without event hooks
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::FileReadError` if the file can't be read, and
+    /// `QuantCryptError::InvalidEncoding` if a line is malformed
+    pub fn load(path: &str) -> Result<TofuStore> {
+        let contents = read_file_limited(path)?;
+        let contents =
+            std::str::from_utf8(&contents).map_err(|_| QuantCryptError::InvalidEncoding)?;
+        let mut store = TofuStore::new();
+        for line in contents.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [peer, fingerprint, first_seen] = fields[..] else {
+                return Err(QuantCryptError::InvalidEncoding);
+            };
+            let first_seen = DateTime::parse_from_rfc3339(first_seen)
+                .map_err(|_| QuantCryptError::InvalidEncoding)?
+                .with_timezone(&Utc);
+            store.records.insert(
+                peer.to_string(),
+                TofuRecord {
+                    fingerprint: fingerprint.to_string(),
+                    first_seen,
+                },
+            );
+        }
+        Ok(store)
+    }
+
+    /// Save the store, one peer per line
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, which is overwritten
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::FileWriteError` if the file can't be written
+    pub fn save(&self, path: &str) -> Result<()> {
+        let mut contents = String::from("# peer\tfingerprint\tfirst seen\n");
+        for (peer, record) in &self.records {
+            contents.push_str(&format!(
+                "{}\t{}\t{}\n",
+                peer,
+                record.fingerprint,
+                record.first_seen.to_rfc3339()
+            ));
+        }
+        std::fs::write(path, contents).map_err(|_| QuantCryptError::FileWriteError)
+    }
+
+    /// Add a hook run on every event, e.g. to alert on changed keys
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The hook
+    ///
+    /// # Returns
+    ///
+    /// The store, for chaining
+    pub fn add_event_hook(
+        &mut self,
+        hook: impl Fn(&TofuEvent) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Check the key presented by a peer, trusting it if the peer is unknown
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - The identity of the peer, e.g. a host name or device ID
+    /// * `public_key` - The key presented by the peer
+    ///
+    /// # Returns
+    ///
+    /// The status of the key. Only `TofuStatus::FirstUse` and `TofuStatus::Trusted` should be accepted.
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEncoding` if the identity of the peer is empty or contains a control character
+    pub fn check(&mut self, peer: &str, public_key: &PublicKey) -> Result<TofuStatus> {
+        Self::check_peer(peer)?;
+        let fingerprint = public_key.get_spki_pin()?;
+        let Some(record) = self.records.get(peer) else {
+            self.records.insert(
+                peer.to_string(),
+                TofuRecord {
+                    fingerprint: fingerprint.clone(),
+                    first_seen: Utc::now(),
+                },
+            );
+            self.emit(TofuEvent::FirstUse {
+                peer: peer.to_string(),
+                fingerprint,
+            });
+            return Ok(TofuStatus::FirstUse);
+        };
+        if record.fingerprint == fingerprint {
+            return Ok(TofuStatus::Trusted);
+        }
+        let previous = record.fingerprint.clone();
+        self.emit(TofuEvent::KeyChanged {
+            peer: peer.to_string(),
+            previous: previous.clone(),
+            presented: fingerprint,
+        });
+        Ok(TofuStatus::Changed { previous })
+    }
+
+    /// Trust a key for a peer, replacing the trusted key, e.g. after a confirmed key rotation
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - The identity of the peer
+    /// * `public_key` - The key to trust
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEncoding` if the identity of the peer is empty or contains a control character
+    pub fn accept(&mut self, peer: &str, public_key: &PublicKey) -> Result<()> {
+        Self::check_peer(peer)?;
+        let fingerprint = public_key.get_spki_pin()?;
+        let record = TofuRecord {
+            fingerprint: fingerprint.clone(),
+            first_seen: Utc::now(),
+        };
+        match self.records.insert(peer.to_string(), record) {
+            Some(previous) if previous.fingerprint != fingerprint => {
+                self.emit(TofuEvent::KeyReplaced {
+                    peer: peer.to_string(),
+                    previous: previous.fingerprint,
+                    fingerprint,
+                })
+            }
+            Some(_) => {}
+            None => self.emit(TofuEvent::FirstUse {
+                peer: peer.to_string(),
+                fingerprint,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Forget a peer, so its next key is trusted on first use again
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - The identity of the peer
+    ///
+    /// # Returns
+    ///
+    /// True if the peer was known, false otherwise
+    pub fn forget(&mut self, peer: &str) -> bool {
+        self.records.remove(peer).is_some()
+    }
+
+    /// Get the key trusted for a peer
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - The identity of the peer
+    ///
+    /// # Returns
+    ///
+    /// The record of the trusted key, or None if the peer is unknown
+    pub fn get_record(&self, peer: &str) -> Option<&TofuRecord> {
+        self.records.get(peer)
+    }
+
+    /// Get the number of known peers
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Check if no peer is known
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Reject identities that can't be saved
+    fn check_peer(peer: &str) -> Result<()> {
+        if peer.is_empty() || peer.starts_with('#') || peer.chars().any(char::is_control) {
+            return Err(QuantCryptError::InvalidEncoding);
+        }
+        Ok(())
+    }
+
+    /// Run the event hooks
+    fn emit(&self, event: TofuEvent) {
+        for hook in &self.hooks {
+            hook(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_tofu_store() {
+        let (pk, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        let (rotated, _) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44)
+            .generate()
+            .unwrap();
+        // Raw keys without a certificate
+        let pk = PublicKey::new(pk.get_oid(), pk.get_key()).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut store = TofuStore::new();
+        let log = events.clone();
+        store.add_event_hook(move |event| log.lock().unwrap().push(event.clone()));
+
+        assert_eq!(store.check("host a", &pk).unwrap(), TofuStatus::FirstUse);
+        assert_eq!(store.check("host a", &pk).unwrap(), TofuStatus::Trusted);
+        let fingerprint = pk.get_spki_pin().unwrap();
+        assert_eq!(
+            store.check("host a", &rotated).unwrap(),
+            TofuStatus::Changed {
+                previous: fingerprint.clone()
+            }
+        );
+        // The changed key is not trusted until accepted
+        assert_eq!(
+            store.get_record("host a").unwrap().get_fingerprint(),
+            fingerprint
+        );
+        store.accept("host a", &rotated).unwrap();
+        assert_eq!(
+            store.check("host a", &rotated).unwrap(),
+            TofuStatus::Trusted
+        );
+        assert!(store.check("host\nb", &pk).is_err());
+        assert!(store.check("", &pk).is_err());
+
+        let rotated_fingerprint = rotated.get_spki_pin().unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                TofuEvent::FirstUse {
+                    peer: "host a".to_string(),
+                    fingerprint: fingerprint.clone(),
+                },
+                TofuEvent::KeyChanged {
+                    peer: "host a".to_string(),
+                    previous: fingerprint.clone(),
+                    presented: rotated_fingerprint.clone(),
+                },
+                TofuEvent::KeyReplaced {
+                    peer: "host a".to_string(),
+                    previous: fingerprint,
+                    fingerprint: rotated_fingerprint,
+                },
+            ]
+        );
+
+        // The store survives a round trip through a file
+        store.check("host b", &pk).unwrap();
+        let path = std::env::temp_dir().join(format!("quantcrypt-tofu-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        store.save(path).unwrap();
+        let mut loaded = TofuStore::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get_record("host b"), store.get_record("host b"));
+        assert_eq!(
+            loaded.check("host a", &rotated).unwrap(),
+            TofuStatus::Trusted
+        );
+        assert!(loaded.forget("host a"));
+        assert_eq!(loaded.check("host a", &pk).unwrap(), TofuStatus::FirstUse);
+    }
+}
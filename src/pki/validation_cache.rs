@@ -0,0 +1,526 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+
+use crate::certificates::Certificate;
+use crate::pki::path_builder::PathBuilder;
+use crate::pki::revocation::{RevocationChecker, RevocationStatus};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The SHA-256 fingerprint of the DER encoding of a certificate
+type Fingerprint = [u8; 32];
+
+/// The default time to live of a certification path
+const DEFAULT_CHAIN_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The default time to live of a revocation status
+const DEFAULT_REVOCATION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The default maximum number of entries of each kind
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Counters of the activity of a `ValidationCache`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationCacheMetrics {
+    /// The lookups of a certification path answered from the cache
+    pub chain_hits: u64,
+    /// The lookups of a certification path not in the cache, or expired
+    pub chain_misses: u64,
+    /// The lookups of a revocation status answered from the cache
+    pub revocation_hits: u64,
+    /// The lookups of a revocation status not in the cache, or expired
+    pub revocation_misses: u64,
+    /// The entries removed to stay within the size limit
+    pub evictions: u64,
+    /// The entries removed with `invalidate` or `clear`
+    pub invalidations: u64,
+    /// The certification paths currently cached
+    pub chain_entries: usize,
+    /// The revocation statuses currently cached
+    pub revocation_entries: usize,
+}
+
+/// A cached value and the time it was inserted
+struct CacheEntry<T> {
+    inserted_at: Instant,
+    value: T,
+}
+
+/// The entries and counters of a cache, behind its lock
+#[derive(Default)]
+struct CacheState {
+    /// Paths by the fingerprint of their end-entity certificate
+    chains: HashMap<Fingerprint, CacheEntry<Vec<Certificate>>>,
+    /// Statuses by the fingerprints of the certificate and its issuer
+    revocations: HashMap<(Fingerprint, Fingerprint), CacheEntry<RevocationStatus>>,
+    metrics: ValidationCacheMetrics,
+}
+
+/// A thread-safe cache of certification paths and revocation statuses
+///
+/// Building a path and querying OCSP responders or CRL distribution points on
+/// every handshake is expensive, so servers keep the results for a while.
+/// Paths are cached by end-entity certificate for `chain_ttl`, and are
+/// dropped early once a certificate of the path is no longer valid.
+/// Revocation statuses are cached by certificate and issuer for
+/// `revocation_ttl`. `RevocationStatus::Unknown` and errors are never cached,
+/// so an unreachable responder is retried on the next lookup.
+///
+/// Each kind of entry is limited to `max_entries`. When the cache is full,
+/// expired entries are dropped first, then the oldest ones. `invalidate`
+/// drops every entry involving a certificate, for example when it is reported
+/// compromised, and `get_metrics` returns hit and miss counters for
+/// monitoring.
+///
+/// A cache holds the results of one path builder and one revocation checker
+/// configuration. Use separate caches for different trust anchors or
+/// policies.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use quantcrypt::certificates::{Certificate, PathBuilder};
+/// use quantcrypt::revocation::ValidationCache;
+///
+/// let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+/// let ta = Certificate::from_file(cert_path).unwrap();
+/// let builder = PathBuilder::new(vec![ta.clone()]);
+///
+/// let mut cache = ValidationCache::new();
+/// cache.set_chain_ttl(Duration::from_secs(600));
+///
+/// let path = cache.get_or_build_path(&builder, &ta).unwrap();
+/// let cached = cache.get_or_build_path(&builder, &ta).unwrap();
+/// assert_eq!(path.len(), cached.len());
+/// assert_eq!(cache.get_metrics().chain_hits, 1);
+/// ```
+pub struct ValidationCache {
+    chain_ttl: Duration,
+    revocation_ttl: Duration,
+    max_entries: usize,
+    state: Mutex<CacheState>,
+}
+
+impl Default for ValidationCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValidationCache {
+    /// Create a new cache
+    ///
+    /// Paths are cached for an hour and revocation statuses for five minutes,
+    /// with at most 10,000 entries of each kind.
+    ///
+    /// # Returns
+    ///
+    /// A new, empty cache
+    pub fn new() -> ValidationCache {
+        ValidationCache {
+            chain_ttl: DEFAULT_CHAIN_TTL,
+            revocation_ttl: DEFAULT_REVOCATION_TTL,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Set how long a certification path is served from the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The time to live of a path
+    ///
+    /// # Returns
+    ///
+    /// The cache, for chaining
+    pub fn set_chain_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.chain_ttl = ttl;
+        self
+    }
+
+    /// Set how long a revocation status is served from the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The time to live of a status, which should not exceed the update interval of the CRLs and OCSP responses
+    ///
+    /// # Returns
+    ///
+    /// The cache, for chaining
+    pub fn set_revocation_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.revocation_ttl = ttl;
+        self
+    }
+
+    /// Set the maximum number of entries of each kind
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - The maximum number of paths, and of revocation statuses
+    ///
+    /// # Returns
+    ///
+    /// The cache, for chaining
+    pub fn set_max_entries(&mut self, max_entries: usize) -> &mut Self {
+        self.max_entries = max_entries;
+        let state = self.state.get_mut().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        while state.chains.len() > max_entries {
+            evict(&mut state.chains, self.chain_ttl, now, &mut state.metrics);
+        }
+        while state.revocations.len() > max_entries {
+            evict(
+                &mut state.revocations,
+                self.revocation_ttl,
+                now,
+                &mut state.metrics,
+            );
+        }
+        self
+    }
+
+    /// Get the cached certification path of a certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The end-entity certificate
+    ///
+    /// # Returns
+    ///
+    /// The path, starting with `cert` and ending with the trust anchor, if cached and current
+    pub fn get_path(&self, cert: &Certificate) -> Result<Option<Vec<Certificate>>> {
+        let key = fingerprint(cert)?;
+        let mut state = self.lock();
+        let state = &mut *state;
+        let fresh = state.chains.get(&key).and_then(|entry| {
+            let current = entry.inserted_at.elapsed() < self.chain_ttl
+                && entry.value.iter().all(|cert| cert.is_valid());
+            current.then(|| entry.value.clone())
+        });
+        if fresh.is_some() {
+            state.metrics.chain_hits += 1;
+        } else {
+            state.metrics.chain_misses += 1;
+            state.chains.remove(&key);
+        }
+        Ok(fresh)
+    }
+
+    /// Cache the certification path of a certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path, starting with the end-entity certificate and ending with the trust anchor
+    pub fn insert_path(&self, path: &[Certificate]) -> Result<()> {
+        let Some(cert) = path.first() else {
+            return Err(QuantCryptError::PathNotFound);
+        };
+        let key = fingerprint(cert)?;
+        let mut state = self.lock();
+        let state = &mut *state;
+        if self.max_entries == 0 {
+            return Ok(());
+        }
+        if !state.chains.contains_key(&key) && state.chains.len() >= self.max_entries {
+            evict(
+                &mut state.chains,
+                self.chain_ttl,
+                Instant::now(),
+                &mut state.metrics,
+            );
+        }
+        state.chains.insert(
+            key,
+            CacheEntry {
+                inserted_at: Instant::now(),
+                value: path.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Get the certification path of a certificate from the cache, or build and cache it
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The path builder used on a cache miss
+    /// * `cert` - The end-entity certificate
+    ///
+    /// # Returns
+    ///
+    /// The path, starting with `cert` and ending with the trust anchor
+    ///
+    /// # Errors
+    ///
+    /// The errors of `PathBuilder::build`, which are not cached
+    pub fn get_or_build_path(
+        &self,
+        builder: &PathBuilder,
+        cert: &Certificate,
+    ) -> Result<Vec<Certificate>> {
+        if let Some(path) = self.get_path(cert)? {
+            return Ok(path);
+        }
+        // The lock is not held while building, so slow fetches don't block other lookups
+        let path = builder.build(cert)?;
+        self.insert_path(&path)?;
+        Ok(path)
+    }
+
+    /// Get the cached revocation status of a certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate
+    /// * `issuer` - The certificate of the issuer of `cert`
+    ///
+    /// # Returns
+    ///
+    /// The status, if cached and current
+    pub fn get_revocation_status(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+    ) -> Result<Option<RevocationStatus>> {
+        let key = (fingerprint(cert)?, fingerprint(issuer)?);
+        let mut state = self.lock();
+        let state = &mut *state;
+        let fresh = state.revocations.get(&key).and_then(|entry| {
+            (entry.inserted_at.elapsed() < self.revocation_ttl).then(|| entry.value.clone())
+        });
+        if fresh.is_some() {
+            state.metrics.revocation_hits += 1;
+        } else {
+            state.metrics.revocation_misses += 1;
+            state.revocations.remove(&key);
+        }
+        Ok(fresh)
+    }
+
+    /// Cache the revocation status of a certificate
+    ///
+    /// `RevocationStatus::Unknown` is not cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate
+    /// * `issuer` - The certificate of the issuer of `cert`
+    /// * `status` - The revocation status of `cert`
+    pub fn insert_revocation_status(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        status: &RevocationStatus,
+    ) -> Result<()> {
+        if *status == RevocationStatus::Unknown {
+            return Ok(());
+        }
+        let key = (fingerprint(cert)?, fingerprint(issuer)?);
+        let mut state = self.lock();
+        let state = &mut *state;
+        if self.max_entries == 0 {
+            return Ok(());
+        }
+        if !state.revocations.contains_key(&key) && state.revocations.len() >= self.max_entries {
+            evict(
+                &mut state.revocations,
+                self.revocation_ttl,
+                Instant::now(),
+                &mut state.metrics,
+            );
+        }
+        state.revocations.insert(
+            key,
+            CacheEntry {
+                inserted_at: Instant::now(),
+                value: status.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Get the revocation status of a certificate from the cache, or check and cache it
+    ///
+    /// # Arguments
+    ///
+    /// * `checker` - The revocation checker used on a cache miss
+    /// * `cert` - The certificate to check
+    /// * `issuer` - The certificate of the issuer of `cert`
+    ///
+    /// # Returns
+    ///
+    /// The revocation status of the certificate
+    ///
+    /// # Errors
+    ///
+    /// The errors of `RevocationChecker::check`, which are not cached
+    pub fn get_or_check_revocation(
+        &self,
+        checker: &RevocationChecker,
+        cert: &Certificate,
+        issuer: &Certificate,
+    ) -> Result<RevocationStatus> {
+        if let Some(status) = self.get_revocation_status(cert, issuer)? {
+            return Ok(status);
+        }
+        let status = checker.check(cert, issuer)?;
+        self.insert_revocation_status(cert, issuer, &status)?;
+        Ok(status)
+    }
+
+    /// Drop every entry involving a certificate
+    ///
+    /// This removes the paths containing the certificate and the revocation
+    /// statuses of the certificate and of the certificates it issued.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - The certificate
+    ///
+    /// # Returns
+    ///
+    /// The number of entries dropped
+    pub fn invalidate(&self, cert: &Certificate) -> Result<usize> {
+        let key = fingerprint(cert)?;
+        let mut state = self.lock();
+        let state = &mut *state;
+        let before = state.chains.len() + state.revocations.len();
+        state.chains.retain(|_, entry| {
+            !entry
+                .value
+                .iter()
+                .any(|cert| fingerprint(cert).map_or(true, |fp| fp == key))
+        });
+        state
+            .revocations
+            .retain(|(cert, issuer), _| *cert != key && *issuer != key);
+        let dropped = before - state.chains.len() - state.revocations.len();
+        state.metrics.invalidations += dropped as u64;
+        Ok(dropped)
+    }
+
+    /// Drop all entries
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        let dropped = state.chains.len() + state.revocations.len();
+        state.chains.clear();
+        state.revocations.clear();
+        state.metrics.invalidations += dropped as u64;
+    }
+
+    /// Get the counters of the cache
+    ///
+    /// # Returns
+    ///
+    /// A snapshot of the counters and the current number of entries
+    pub fn get_metrics(&self) -> ValidationCacheMetrics {
+        let state = self.lock();
+        ValidationCacheMetrics {
+            chain_entries: state.chains.len(),
+            revocation_entries: state.revocations.len(),
+            ..state.metrics.clone()
+        }
+    }
+
+    /// Lock the state, which stays consistent even if a holder of the lock panicked
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Compute the key of a certificate in the cache
+fn fingerprint(cert: &Certificate) -> Result<Fingerprint> {
+    Ok(Sha256::digest(cert.to_der()?).into())
+}
+
+/// Make room for an entry: drop the expired entries, or else the oldest one
+fn evict<K: Clone + Eq + Hash, T>(
+    entries: &mut HashMap<K, CacheEntry<T>>,
+    ttl: Duration,
+    now: Instant,
+    metrics: &mut ValidationCacheMetrics,
+) {
+    let before = entries.len();
+    entries.retain(|_, entry| now.duration_since(entry.inserted_at) < ttl);
+    if entries.len() == before {
+        let oldest = entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.inserted_at)
+            .map(|(key, _)| key.clone());
+        if let Some(oldest) = oldest {
+            entries.remove(&oldest);
+        }
+    }
+    metrics.evictions += (before - entries.len()) as u64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn ta() -> Certificate {
+        Certificate::from_file(
+            "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der",
+        )
+        .unwrap()
+    }
+
+    fn other_ta() -> Certificate {
+        Certificate::from_file("test/data/chain/2.16.840.1.101.3.4.3.17_MlDsa44_ta.der").unwrap()
+    }
+
+    #[test]
+    fn test_validation_cache() {
+        let ta = ta();
+        let other = other_ta();
+        let builder = PathBuilder::new(vec![ta.clone(), other.clone()]);
+        let mut cache = ValidationCache::new();
+        cache.set_max_entries(1);
+
+        // Paths are served from the cache, and the oldest is evicted when full
+        assert_eq!(cache.get_or_build_path(&builder, &ta).unwrap().len(), 1);
+        assert!(cache.get_path(&ta).unwrap().is_some());
+        cache.get_or_build_path(&builder, &other).unwrap();
+        assert!(cache.get_path(&ta).unwrap().is_none());
+        let metrics = cache.get_metrics();
+        assert_eq!(metrics.chain_hits, 1);
+        assert_eq!(metrics.chain_misses, 3);
+        assert_eq!(metrics.evictions, 1);
+        assert_eq!(metrics.chain_entries, 1);
+
+        // Unknown statuses aren't cached
+        let revoked = RevocationStatus::Revoked {
+            revocation_time: Utc::now(),
+            reason: None,
+        };
+        cache
+            .insert_revocation_status(&ta, &ta, &RevocationStatus::Unknown)
+            .unwrap();
+        assert_eq!(cache.get_revocation_status(&ta, &ta).unwrap(), None);
+        cache.insert_revocation_status(&ta, &ta, &revoked).unwrap();
+        assert_eq!(
+            cache.get_revocation_status(&ta, &ta).unwrap(),
+            Some(revoked)
+        );
+
+        // Invalidation drops the entries involving a certificate
+        assert_eq!(cache.invalidate(&ta).unwrap(), 1);
+        assert_eq!(cache.get_revocation_status(&ta, &ta).unwrap(), None);
+        assert!(cache.get_path(&other).unwrap().is_some());
+        cache.clear();
+        assert_eq!(cache.get_metrics().invalidations, 2);
+        assert_eq!(cache.get_metrics().chain_entries, 0);
+
+        // Expired entries are not served
+        cache.set_chain_ttl(Duration::ZERO);
+        cache.get_or_build_path(&builder, &ta).unwrap();
+        assert!(cache.get_path(&ta).unwrap().is_none());
+    }
+}
@@ -0,0 +1,184 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+use crate::asn1::lenient_certificate::LenientCertificate;
+use crate::certificates::Certificate;
+use crate::dsa::common::dsa_trait::Dsa;
+use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+use crate::kem::common::kem_trait::Kem;
+
+/// The message signed by the DSA conformance battery
+const TEST_MESSAGE: &[u8] = b"Hello, world! This is a test message for the DSA algorithm.";
+
+/// The number of encapsulation round trips of the KEM conformance battery
+const KEM_ROUND_TRIPS: usize = 3;
+
+/// Check that a signature doesn't verify once the message or the signature is altered
+fn assert_tampering_detected(verify: impl Fn(&[u8], &[u8]) -> bool, signature: &[u8]) {
+    let mut msg = TEST_MESSAGE.to_vec();
+    msg[0] ^= 0x01;
+    assert!(!verify(&msg, signature), "altered message verified");
+
+    for pos in [0, signature.len() / 2, signature.len() - 1] {
+        let mut signature = signature.to_vec();
+        signature[pos] ^= 0x01;
+        assert!(
+            !verify(TEST_MESSAGE, &signature),
+            "signature altered at byte {} verified",
+            pos
+        );
+    }
+}
+
+/// Run the KEM conformance battery against a KEM backend, panicking on failure
+///
+/// The key, ciphertext and shared secret lengths must match `get_kem_info`,
+/// encapsulations must round trip, an altered ciphertext must not yield the
+/// shared secret, fresh key pairs must differ, and key generation from a seeded
+/// RNG must be deterministic.
+///
+/// # Arguments
+///
+/// * `kem` - The KEM to check
+pub fn check_kem(kem: &mut impl Kem) {
+    let kem_info = kem.get_kem_info();
+    let (pk, sk) = kem.key_gen().unwrap();
+    if let Some(expected_pk_len) = kem_info.pk_byte_len {
+        assert_eq!(pk.len(), expected_pk_len);
+    }
+    if let Some(expected_sk_len) = kem_info.sk_byte_len {
+        assert_eq!(sk.len(), expected_sk_len);
+    }
+
+    for _ in 0..KEM_ROUND_TRIPS {
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        if let Some(expected_ct_len) = kem_info.ct_byte_len {
+            assert_eq!(ct.len(), expected_ct_len);
+        }
+        assert_eq!(ss.len(), kem_info.ss_byte_len);
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+
+        // Either rejected, or implicitly rejected with another shared secret
+        let mut altered = ct.clone();
+        let last = altered.len() - 1;
+        altered[last] ^= 0x01;
+        assert_ne!(kem.decap(&sk, &altered).ok(), Some(ss));
+    }
+
+    // Should generate different keys
+    let (pk2, sk2) = kem.key_gen().unwrap();
+    assert_ne!(pk, pk2);
+    assert_ne!(sk, sk2);
+
+    // Should generate the same keys
+    let seed = [0u8; 32];
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    let (pk3, sk3) = kem.key_gen_with_rng(&mut rng).unwrap();
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    let (pk4, sk4) = kem.key_gen_with_rng(&mut rng).unwrap();
+    assert_eq!(pk3, pk4);
+    assert_eq!(sk3, sk4);
+}
+
+/// Run the DSA conformance battery against a DSA backend, panicking on failure
+///
+/// The public key must be derivable from the private key, the key and signature
+/// lengths must match `get_dsa_info`, signatures must verify, and must no longer
+/// verify once the message or the signature is altered.
+///
+/// # Arguments
+///
+/// * `dsa` - The DSA to check
+pub fn check_dsa(dsa: &mut impl Dsa) {
+    let dsa_info = dsa.get_dsa_info();
+    let (pk, sk) = dsa.key_gen().unwrap();
+    assert_eq!(dsa.get_public_key(&sk).unwrap(), pk);
+    if let Some(expected_pk_len) = dsa_info.pk_byte_len {
+        assert_eq!(pk.len(), expected_pk_len);
+    }
+    if let Some(expected_sk_len) = dsa_info.sk_byte_len {
+        assert_eq!(sk.len(), expected_sk_len);
+    }
+
+    let signature = dsa.sign(&sk, TEST_MESSAGE).unwrap();
+    if let Some(expected_signature_len) = dsa_info.sig_byte_len {
+        assert_eq!(signature.len(), expected_signature_len);
+    }
+    assert!(dsa.verify(&pk, TEST_MESSAGE, &signature).unwrap());
+    assert_tampering_detected(
+        |msg, sig| dsa.verify(&pk, msg, sig).unwrap_or(false),
+        &signature,
+    );
+}
+
+/// Run the DSA conformance battery against a pre-hash DSA backend, panicking on failure
+///
+/// On top of the checks of `check_dsa`, signatures with a context must verify
+/// with the same context only.
+///
+/// # Arguments
+///
+/// * `dsa` - The DSA to check
+pub fn check_prehash_dsa(dsa: &mut impl PrehashDsa) {
+    let dsa_info = dsa.get_dsa_info();
+    let (pk, sk) = dsa.key_gen().unwrap();
+    assert_eq!(dsa.get_public_key(&sk).unwrap(), pk);
+    if let Some(expected_pk_len) = dsa_info.pk_byte_len {
+        assert_eq!(pk.len(), expected_pk_len);
+    }
+    if let Some(expected_sk_len) = dsa_info.sk_byte_len {
+        assert_eq!(sk.len(), expected_sk_len);
+    }
+
+    let signature = dsa.sign(&sk, TEST_MESSAGE).unwrap();
+    if let Some(expected_signature_len) = dsa_info.sig_byte_len {
+        assert_eq!(signature.len(), expected_signature_len);
+    }
+    assert!(dsa.verify(&pk, TEST_MESSAGE, &signature).unwrap());
+    assert_tampering_detected(
+        |msg, sig| dsa.verify(&pk, msg, sig).unwrap_or(false),
+        &signature,
+    );
+
+    let ctx = b"test context";
+    let signature = dsa.sign_with_ctx(&sk, TEST_MESSAGE, Some(ctx)).unwrap();
+    assert!(dsa
+        .verify_with_ctx(&pk, TEST_MESSAGE, &signature, Some(ctx))
+        .unwrap());
+    assert!(!dsa
+        .verify_with_ctx(&pk, TEST_MESSAGE, &signature, Some(b"other context"))
+        .unwrap_or(false));
+}
+
+/// Run the certificate conformance battery against a certificate, panicking on failure
+///
+/// The certificate must round trip through DER and PEM unchanged, parse
+/// completely field by field, and carry a public key quantcrypt can load.
+///
+/// # Arguments
+///
+/// * `cert` - The certificate to check
+pub fn check_certificate(cert: &Certificate) {
+    let der = cert.to_der().unwrap();
+    assert_eq!(Certificate::from_der(&der).unwrap().to_der().unwrap(), der);
+    let pem = cert.to_pem().unwrap();
+    assert_eq!(Certificate::from_pem(&pem).unwrap().to_der().unwrap(), der);
+
+    let lenient = LenientCertificate::parse(&der);
+    assert!(lenient.is_complete(), "{:?}", lenient.errors);
+
+    let pk = cert.get_public_key().unwrap();
+    assert_eq!(pk.get_oid(), cert.get_public_key_oid());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_certificate() {
+        let cert_path = "test/data/MlDsa44EcdsaP256Sha256-2.16.840.1.114027.80.8.1.43_ta.der";
+        let cert = Certificate::from_file(cert_path).unwrap();
+        crate::test_certificate!(cert);
+    }
+}
@@ -0,0 +1,381 @@
+use std::time::{Duration, SystemTime};
+
+use const_oid::db::rfc5280;
+use der::asn1::{BitString, OctetString, UtcTime};
+use der::Encode;
+use rand_core::{OsRng, RngCore};
+use x509_cert::ext::pkix::SubjectKeyIdentifier;
+use x509_cert::time::Time;
+
+use crate::asn1::composite_public_key::CompositePublicKey;
+use crate::certificates::{CertValidity, Certificate, CertificateBuilder, Profile};
+use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+use crate::keys::{PrivateKey, PublicKey};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A year, for validity periods
+const YEAR: Duration = Duration::from_secs(365 * 24 * 3600);
+
+/// A defect injected into a fixture certificate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Defect {
+    /// The validity period ended a year ago
+    Expired,
+    /// The validity period starts in a year
+    NotYetValid,
+    /// The Subject Key Identifier doesn't match the public key
+    BadSubjectKeyIdentifier,
+    /// The signature is corrupted
+    BadSignature,
+    /// The certificate is signed by an unrelated key of the issuer's algorithm
+    WrongSigner,
+    /// The composite public key combines the PQ component of the certificate's key
+    /// pair with the traditional component of another one
+    MismatchedCompositeComponents,
+}
+
+/// The position of a certificate in a fixture chain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainPosition {
+    /// The self-signed root CA
+    Root,
+    /// The intermediate CA, issued by the root
+    Intermediate,
+    /// The end-entity certificate, issued by the intermediate
+    Leaf,
+}
+
+/// A root, intermediate and leaf certificate chain, with the private keys
+pub struct ChainFixture {
+    root: (Certificate, PrivateKey),
+    intermediate: (Certificate, PrivateKey),
+    leaf: (Certificate, PrivateKey),
+}
+
+impl ChainFixture {
+    /// Get a certificate of the chain
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the certificate
+    ///
+    /// # Returns
+    ///
+    /// The certificate
+    pub fn get_certificate(&self, position: ChainPosition) -> &Certificate {
+        &self.get(position).0
+    }
+
+    /// Get the private key of a certificate of the chain
+    ///
+    /// With `Defect::MismatchedCompositeComponents`, the key only matches the PQ
+    /// component of the certificate's public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the certificate
+    ///
+    /// # Returns
+    ///
+    /// The private key
+    pub fn get_private_key(&self, position: ChainPosition) -> &PrivateKey {
+        &self.get(position).1
+    }
+
+    /// Get the chain as a path
+    ///
+    /// # Returns
+    ///
+    /// The leaf, intermediate and root certificates, in this order
+    pub fn get_path(&self) -> Vec<Certificate> {
+        vec![
+            self.leaf.0.clone(),
+            self.intermediate.0.clone(),
+            self.root.0.clone(),
+        ]
+    }
+
+    fn get(&self, position: ChainPosition) -> &(Certificate, PrivateKey) {
+        match position {
+            ChainPosition::Root => &self.root,
+            ChainPosition::Intermediate => &self.intermediate,
+            ChainPosition::Leaf => &self.leaf,
+        }
+    }
+}
+
+/// Generates certificate chains with deliberately injected defects
+///
+/// This is meant for testing validation logic built on top of quantcrypt: each
+/// defect must be caught by it. Without defects, the chain is valid for ten years.
+/// The certificates are freshly generated on each call to `build`, and must never
+/// be trusted outside of tests.
+///
+/// # Example
+/// ```
+/// use quantcrypt::dsas::DsaAlgorithm;
+/// use quantcrypt::testing::{ChainFixtureBuilder, ChainPosition, Defect};
+///
+/// let chain = ChainFixtureBuilder::new(DsaAlgorithm::MlDsa44)
+///     .inject(ChainPosition::Intermediate, Defect::WrongSigner)
+///     .build()
+///     .unwrap();
+///
+/// let root = chain.get_certificate(ChainPosition::Root);
+/// let intermediate = chain.get_certificate(ChainPosition::Intermediate);
+/// assert!(!root.verify_child(intermediate).unwrap());
+/// ```
+pub struct ChainFixtureBuilder {
+    algorithm: DsaAlgorithm,
+    defects: Vec<(ChainPosition, Defect)>,
+}
+
+impl ChainFixtureBuilder {
+    /// Create a new builder for chains of a signature algorithm
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm of every key in the chain
+    ///
+    /// # Returns
+    ///
+    /// A new builder, without defects
+    pub fn new(algorithm: DsaAlgorithm) -> ChainFixtureBuilder {
+        ChainFixtureBuilder {
+            algorithm,
+            defects: Vec::new(),
+        }
+    }
+
+    /// Inject a defect into a certificate of the chain
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The certificate to inject the defect into
+    /// * `defect` - The defect. Several defects can be injected into the same certificate.
+    ///
+    /// # Returns
+    ///
+    /// The builder, for chaining
+    pub fn inject(&mut self, position: ChainPosition, defect: Defect) -> &mut Self {
+        self.defects.push((position, defect));
+        self
+    }
+
+    /// Generate the chain
+    ///
+    /// # Returns
+    ///
+    /// The chain
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` if `Defect::MismatchedCompositeComponents`
+    /// is injected into a chain of a non-composite algorithm
+    pub fn build(&self) -> Result<ChainFixture> {
+        let root = self.issue(ChainPosition::Root, None)?;
+        let intermediate = self.issue(ChainPosition::Intermediate, Some(&root))?;
+        let leaf = self.issue(ChainPosition::Leaf, Some(&intermediate))?;
+        Ok(ChainFixture {
+            root,
+            intermediate,
+            leaf,
+        })
+    }
+
+    /// Check if a defect is injected into a certificate
+    fn has(&self, position: ChainPosition, defect: Defect) -> bool {
+        self.defects.contains(&(position, defect))
+    }
+
+    /// Generate a key pair of the algorithm of the chain
+    fn generate(&self) -> Result<(PublicKey, PrivateKey)> {
+        DsaKeyGenerator::new(self.algorithm).generate()
+    }
+
+    /// Combine the PQ component of a composite public key with the traditional component of another
+    fn mismatch(&self, pk: &PublicKey) -> Result<PublicKey> {
+        if !self.algorithm.is_composite() {
+            return Err(QuantCryptError::UnsupportedOperation);
+        }
+        let (other, _) = self.generate()?;
+        let oid = self.algorithm.get_oid();
+        let pq = CompositePublicKey::from_der(&oid, pk.get_key())?;
+        let trad = CompositePublicKey::from_der(&oid, other.get_key())?;
+        PublicKey::from_composite(&CompositePublicKey::new(
+            &oid,
+            &pq.get_pq_pk(),
+            &trad.get_trad_pk(),
+        ))
+    }
+
+    /// Issue a certificate of the chain, injecting its defects
+    fn issue(
+        &self,
+        position: ChainPosition,
+        issuer: Option<&(Certificate, PrivateKey)>,
+    ) -> Result<(Certificate, PrivateKey)> {
+        let (pk, sk) = self.generate()?;
+        let cert_pk = if self.has(position, Defect::MismatchedCompositeComponents) {
+            self.mismatch(&pk)?
+        } else {
+            pk
+        };
+
+        let (profile, subject) = match (position, issuer) {
+            (ChainPosition::Root, _) | (_, None) => (Profile::Root, "CN=Fixture Root CA"),
+            (ChainPosition::Intermediate, Some((issuer, _))) => (
+                Profile::SubCA {
+                    issuer: issuer.get_subject(),
+                    path_len_constraint: Some(0),
+                },
+                "CN=Fixture Intermediate CA",
+            ),
+            (ChainPosition::Leaf, Some((issuer, _))) => (
+                Profile::Leaf {
+                    issuer: issuer.get_subject(),
+                    enable_key_agreement: false,
+                    enable_key_encipherment: false,
+                },
+                "CN=fixture.example.com",
+            ),
+        };
+        let signer = issuer.map(|(_, key)| key).unwrap_or(&sk);
+        let not_after = (chrono::Utc::now() + chrono::Duration::days(3650)).to_rfc3339();
+        let validity = CertValidity::new(None, &not_after)?;
+        let cert = CertificateBuilder::new(
+            profile,
+            None,
+            validity,
+            subject.to_string(),
+            cert_pk,
+            signer,
+        )?
+        .build()?;
+
+        let cert = self.inject_defects(position, cert, signer)?;
+        Ok((cert, sk))
+    }
+
+    /// Inject the defects of a certificate, signing it again as needed
+    fn inject_defects(
+        &self,
+        position: ChainPosition,
+        cert: Certificate,
+        signer: &PrivateKey,
+    ) -> Result<Certificate> {
+        let mut inner = cert.get_inner().clone();
+        let tbs = &mut inner.tbs_certificate;
+        let now = SystemTime::now();
+        let to_time = |time: SystemTime| -> Result<Time> {
+            Ok(Time::UtcTime(
+                UtcTime::from_system_time(time).map_err(|_| QuantCryptError::Unknown)?,
+            ))
+        };
+
+        if self.has(position, Defect::Expired) {
+            tbs.validity.not_before = to_time(now - 2 * YEAR)?;
+            tbs.validity.not_after = to_time(now - YEAR)?;
+        }
+        if self.has(position, Defect::NotYetValid) {
+            tbs.validity.not_before = to_time(now + YEAR)?;
+            tbs.validity.not_after = to_time(now + 2 * YEAR)?;
+        }
+        if self.has(position, Defect::BadSubjectKeyIdentifier) {
+            let mut skid = [0u8; 20];
+            OsRng.fill_bytes(&mut skid);
+            let value = OctetString::new(skid)
+                .map(SubjectKeyIdentifier)
+                .and_then(|skid| skid.to_der())
+                .and_then(OctetString::new)
+                .map_err(|_| QuantCryptError::BadExtension)?;
+            for ext in tbs.extensions.iter_mut().flatten() {
+                if ext.extn_id == rfc5280::ID_CE_SUBJECT_KEY_IDENTIFIER {
+                    ext.extn_value = value.clone();
+                }
+            }
+        }
+
+        let wrong_key;
+        let signer = if self.has(position, Defect::WrongSigner) {
+            wrong_key = self.generate()?.1;
+            &wrong_key
+        } else {
+            signer
+        };
+        let tbs_der = tbs.to_der().map_err(|_| QuantCryptError::Unknown)?;
+        let mut signature = signer.sign(&tbs_der)?;
+        if self.has(position, Defect::BadSignature) {
+            let last = signature.len() - 1;
+            signature[last] ^= 0x01;
+        }
+        inner.signature =
+            BitString::from_bytes(&signature).map_err(|_| QuantCryptError::Unknown)?;
+        Ok(Certificate::new(inner))
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_fixtures() {
+        let chain = ChainFixtureBuilder::new(DsaAlgorithm::MlDsa44)
+            .build()
+            .unwrap();
+        let path = chain.get_path();
+        assert!(path[2].verify_self_signed().unwrap());
+        assert!(path[2].verify_child(&path[1]).unwrap());
+        assert!(path[1].verify_child(&path[0]).unwrap());
+        assert!(path.iter().all(|cert| cert.is_valid()));
+
+        let chain = ChainFixtureBuilder::new(DsaAlgorithm::MlDsa44)
+            .inject(ChainPosition::Leaf, Defect::Expired)
+            .inject(ChainPosition::Intermediate, Defect::BadSignature)
+            .inject(ChainPosition::Root, Defect::BadSubjectKeyIdentifier)
+            .build()
+            .unwrap();
+        let leaf = chain.get_certificate(ChainPosition::Leaf);
+        let intermediate = chain.get_certificate(ChainPosition::Intermediate);
+        let root = chain.get_certificate(ChainPosition::Root);
+        assert!(!leaf.is_valid());
+        assert!(intermediate.verify_child(leaf).unwrap());
+        assert!(!root.verify_child(intermediate).unwrap());
+        assert!(root.verify_self_signed().unwrap());
+        let skid = root.get_subject_key_identifier().unwrap();
+        let pk = root.get_public_key().unwrap();
+        assert_ne!(
+            skid.0.as_bytes(),
+            pk.get_key_id(Default::default()).unwrap()
+        );
+
+        let chain = ChainFixtureBuilder::new(DsaAlgorithm::MlDsa44)
+            .inject(ChainPosition::Leaf, Defect::NotYetValid)
+            .build()
+            .unwrap();
+        assert!(!chain.get_certificate(ChainPosition::Leaf).is_valid());
+
+        // The leaf key no longer matches the traditional component of the certificate
+        let chain = ChainFixtureBuilder::new(DsaAlgorithm::MlDsa44EcdsaP256Sha256)
+            .inject(ChainPosition::Leaf, Defect::MismatchedCompositeComponents)
+            .build()
+            .unwrap();
+        let sk = chain.get_private_key(ChainPosition::Leaf);
+        let pk = chain
+            .get_certificate(ChainPosition::Leaf)
+            .get_public_key()
+            .unwrap();
+        let sig = sk.sign(b"message").unwrap();
+        assert!(!pk.verify(b"message", &sig).unwrap_or(false));
+
+        assert!(matches!(
+            ChainFixtureBuilder::new(DsaAlgorithm::MlDsa44)
+                .inject(ChainPosition::Leaf, Defect::MismatchedCompositeComponents)
+                .build(),
+            Err(QuantCryptError::UnsupportedOperation)
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+/// Run the KEM conformance battery against a KEM backend
+///
+/// Takes a `Result` holding the KEM, as returned by `Kem::new`, and panics if it
+/// fails any check of `testing::check_kem`. This is the battery the built-in
+/// KEMs are tested with, so a custom backend (e.g. an HSM adapter) passing it
+/// behaves like them.
+///
+/// # Example
+/// ```ignore
+/// use quantcrypt::kems::{Kem, KemType};
+///
+/// #[test]
+/// fn test_hsm_kem() {
+///     quantcrypt::testing::test_kem!(HsmKem::new(KemType::MlKem768));
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_kem {
+    ($kem:expr) => {{
+        let mut kem = $kem.unwrap();
+        $crate::testing::check_kem(&mut kem);
+    }};
+}
+
+/// Run the DSA conformance battery against a DSA backend
+///
+/// Takes a `Result` holding the DSA, as returned by `Dsa::new`, and panics if it
+/// fails any check of `testing::check_dsa`.
+///
+/// # Example
+/// ```ignore
+/// use quantcrypt::dsas::{Dsa, DsaType};
+///
+/// #[test]
+/// fn test_remote_signer() {
+///     quantcrypt::testing::test_dsa!(RemoteSigner::new(DsaType::Ed25519));
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_dsa {
+    ($dsa:expr) => {{
+        let mut dsa = $dsa.unwrap();
+        $crate::testing::check_dsa(&mut dsa);
+    }};
+}
+
+/// Run the pre-hash DSA conformance battery against a pre-hash DSA backend
+///
+/// Takes a `Result` holding the DSA, as returned by `PrehashDsa::new`, and panics
+/// if it fails any check of `testing::check_prehash_dsa`.
+#[macro_export]
+macro_rules! test_prehash_dsa {
+    ($dsa:expr) => {{
+        let mut dsa = $dsa.unwrap();
+        $crate::testing::check_prehash_dsa(&mut dsa);
+    }};
+}
+
+/// Run the certificate conformance battery against a certificate
+///
+/// Panics if the certificate fails any check of `testing::check_certificate`.
+#[macro_export]
+macro_rules! test_certificate {
+    ($cert:expr) => {{
+        $crate::testing::check_certificate(&$cert);
+    }};
+}
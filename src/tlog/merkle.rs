@@ -0,0 +1,767 @@
+use crate::hash::common::hash_trait::Hash;
+use crate::hash::common::hash_type::HashType;
+use crate::hash::hash_manager::HashManager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The prefix of the hash of a leaf (RFC 9162 § 2.1.1)
+const LEAF_PREFIX: u8 = 0x00;
+
+/// The prefix of the hash of an interior node (RFC 9162 § 2.1.1)
+const NODE_PREFIX: u8 = 0x01;
+
+/// The size of the left subtree of a tree of n > 1 leaves, the largest power of two smaller than n
+fn split(n: u64) -> u64 {
+    1 << (63 - (n - 1).leading_zeros())
+}
+
+/// Hashes the nodes of a Merkle tree and verifies proofs, as in Certificate Transparency
+///
+/// Leaves are hashed as `H(0x00 || data)` and interior nodes as
+/// `H(0x01 || left || right)`, so a leaf can't be passed off as a node. Proofs
+/// are verified with the algorithms of RFC 9162 § 2.1.3.2 and § 2.1.4.2.
+///
+/// # Example
+/// ```
+/// use quantcrypt::hashes::HashType;
+/// use quantcrypt::transparency::MerkleHasher;
+///
+/// let hasher = MerkleHasher::new(HashType::Sha256).unwrap();
+/// let left = hasher.hash_leaf(b"alice").unwrap();
+/// let right = hasher.hash_leaf(b"bob").unwrap();
+/// let root = hasher.hash_children(&left, &right).unwrap();
+///
+/// // The proof of the inclusion of the first leaf is its sibling
+/// assert!(hasher.verify_inclusion(&left, 0, 2, &[right], &root).unwrap());
+/// ```
+#[derive(Clone)]
+pub struct MerkleHasher {
+    hash: HashManager,
+}
+
+impl MerkleHasher {
+    /// Create a hasher
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_type` - The hash function of the tree, SHA-256 for Certificate Transparency
+    ///
+    /// # Returns
+    ///
+    /// The hasher
+    pub fn new(hash_type: HashType) -> Result<MerkleHasher> {
+        Ok(MerkleHasher {
+            hash: HashManager::new(hash_type)?,
+        })
+    }
+
+    /// Get the hash function of the tree
+    pub fn get_hash_type(&self) -> HashType {
+        self.hash.get_hash_info().hash_type
+    }
+
+    /// Hash a leaf
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data of the leaf
+    ///
+    /// # Returns
+    ///
+    /// The hash of the leaf
+    pub fn hash_leaf(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.hash.hash(&[&[LEAF_PREFIX], data].concat())
+    }
+
+    /// Hash an interior node
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The hash of the left child
+    /// * `right` - The hash of the right child
+    ///
+    /// # Returns
+    ///
+    /// The hash of the node
+    pub fn hash_children(&self, left: &[u8], right: &[u8]) -> Result<Vec<u8>> {
+        self.hash.hash(&[&[NODE_PREFIX], left, right].concat())
+    }
+
+    /// Verify that a leaf is included in a tree
+    ///
+    /// # Arguments
+    ///
+    /// * `leaf_hash` - The hash of the leaf, from `hash_leaf`
+    /// * `index` - The index of the leaf
+    /// * `tree_size` - The number of leaves of the tree
+    /// * `proof` - The inclusion proof, the hashes of the siblings from the leaf up
+    /// * `root` - The root hash of the tree
+    ///
+    /// # Returns
+    ///
+    /// True if the proof is valid, false otherwise
+    pub fn verify_inclusion(
+        &self,
+        leaf_hash: &[u8],
+        index: u64,
+        tree_size: u64,
+        proof: &[Vec<u8>],
+        root: &[u8],
+    ) -> Result<bool> {
+        if index >= tree_size {
+            return Ok(false);
+        }
+        let (mut f_n, mut s_n) = (index, tree_size - 1);
+        let mut r = leaf_hash.to_vec();
+        for p in proof {
+            if s_n == 0 {
+                return Ok(false);
+            }
+            if f_n & 1 == 1 || f_n == s_n {
+                r = self.hash_children(p, &r)?;
+                while f_n & 1 == 0 && f_n != 0 {
+                    f_n >>= 1;
+                    s_n >>= 1;
+                }
+            } else {
+                r = self.hash_children(&r, p)?;
+            }
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+        Ok(s_n == 0 && r == root)
+    }
+
+    /// Verify that a tree is an append-only extension of an older tree
+    ///
+    /// # Arguments
+    ///
+    /// * `first_size` - The number of leaves of the older tree
+    /// * `second_size` - The number of leaves of the newer tree
+    /// * `proof` - The consistency proof
+    /// * `first_root` - The root hash of the older tree
+    /// * `second_root` - The root hash of the newer tree
+    ///
+    /// # Returns
+    ///
+    /// True if the proof is valid, false otherwise
+    pub fn verify_consistency(
+        &self,
+        first_size: u64,
+        second_size: u64,
+        proof: &[Vec<u8>],
+        first_root: &[u8],
+        second_root: &[u8],
+    ) -> Result<bool> {
+        if first_size > second_size {
+            return Ok(false);
+        }
+        if first_size == second_size {
+            return Ok(proof.is_empty() && first_root == second_root);
+        }
+        // Every tree extends the empty tree
+        if first_size == 0 {
+            return Ok(proof.is_empty());
+        }
+        if proof.is_empty() {
+            return Ok(false);
+        }
+        // The root of a complete older tree is a node of the newer one, and is omitted
+        let mut path = Vec::with_capacity(proof.len() + 1);
+        if first_size.is_power_of_two() {
+            path.push(first_root.to_vec());
+        }
+        path.extend_from_slice(proof);
+
+        let (mut f_n, mut s_n) = (first_size - 1, second_size - 1);
+        while f_n & 1 == 1 {
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+        let mut f_r = path[0].clone();
+        let mut s_r = path[0].clone();
+        for c in &path[1..] {
+            if s_n == 0 {
+                return Ok(false);
+            }
+            if f_n & 1 == 1 || f_n == s_n {
+                f_r = self.hash_children(c, &f_r)?;
+                s_r = self.hash_children(c, &s_r)?;
+                while f_n & 1 == 0 && f_n != 0 {
+                    f_n >>= 1;
+                    s_n >>= 1;
+                }
+            } else {
+                s_r = self.hash_children(&s_r, c)?;
+            }
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+        Ok(s_n == 0 && f_r == first_root && s_r == second_root)
+    }
+
+    /// Verify that several leaves are included in a tree, with a single proof
+    ///
+    /// The proof is produced by `MerkleTree::multiproof`: the hashes of the
+    /// largest subtrees holding none of the leaves, from left to right. It
+    /// shares the nodes that separate inclusion proofs would repeat.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaves` - The indices and hashes of the leaves, in increasing order of index
+    /// * `tree_size` - The number of leaves of the tree
+    /// * `proof` - The multiproof
+    /// * `root` - The root hash of the tree
+    ///
+    /// # Returns
+    ///
+    /// True if the proof is valid, false otherwise
+    pub fn verify_multiproof(
+        &self,
+        leaves: &[(u64, Vec<u8>)],
+        tree_size: u64,
+        proof: &[Vec<u8>],
+        root: &[u8],
+    ) -> Result<bool> {
+        let sorted = leaves.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        match leaves.last() {
+            Some((last, _)) if sorted && *last < tree_size => {}
+            _ => return Ok(false),
+        }
+        let mut proof = proof.iter();
+        let computed = self.multiproof_root(0, tree_size, leaves, &mut proof)?;
+        Ok(proof.next().is_none() && computed.as_deref() == Some(root))
+    }
+
+    /// Compute the root of the subtree [lo, hi) from the leaves in it and the proof
+    fn multiproof_root<'a>(
+        &self,
+        lo: u64,
+        hi: u64,
+        leaves: &[(u64, Vec<u8>)],
+        proof: &mut impl Iterator<Item = &'a Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>> {
+        if leaves.is_empty() {
+            return Ok(proof.next().cloned());
+        }
+        if hi - lo == 1 {
+            return Ok(Some(leaves[0].1.clone()));
+        }
+        let mid = lo + split(hi - lo);
+        let (left, right) = leaves.split_at(leaves.partition_point(|(index, _)| *index < mid));
+        let Some(left) = self.multiproof_root(lo, mid, left, proof)? else {
+            return Ok(None);
+        };
+        let Some(right) = self.multiproof_root(mid, hi, right, proof)? else {
+            return Ok(None);
+        };
+        Ok(Some(self.hash_children(&left, &right)?))
+    }
+}
+
+/// An append-only Merkle tree, as used by transparency logs
+///
+/// The tree has the shape of RFC 9162 § 2.1.1, so its roots and proofs are
+/// those of Certificate Transparency with SHA-256, and they can be verified
+/// with `MerkleHasher`. Proofs can be produced for any earlier size of the
+/// tree, to match the tree heads a client has seen. The hashes of the complete
+/// subtrees are kept, so a proof costs a logarithmic number of hashes.
+///
+/// # Example
+/// ```
+/// use quantcrypt::hashes::HashType;
+/// use quantcrypt::merkle::MerkleTree;
+///
+/// let mut tree = MerkleTree::new(HashType::Sha256).unwrap();
+/// for leaf in [b"a", b"b", b"c", b"d", b"e"] {
+///     tree.push(leaf).unwrap();
+/// }
+/// let root = tree.get_root().unwrap();
+/// let hasher = tree.get_hasher();
+///
+/// let proof = tree.inclusion_proof(2, 5).unwrap();
+/// let leaf_hash = hasher.hash_leaf(b"c").unwrap();
+/// assert!(hasher.verify_inclusion(&leaf_hash, 2, 5, &proof, &root).unwrap());
+///
+/// let proof = tree.consistency_proof(3, 5).unwrap();
+/// let old_root = tree.get_root_at(3).unwrap();
+/// assert!(hasher.verify_consistency(3, 5, &proof, &old_root, &root).unwrap());
+///
+/// let proof = tree.multiproof(&[0, 4], 5).unwrap();
+/// let leaves = vec![(0, hasher.hash_leaf(b"a").unwrap()), (4, hasher.hash_leaf(b"e").unwrap())];
+/// assert!(hasher.verify_multiproof(&leaves, 5, &proof, &root).unwrap());
+/// ```
+#[derive(Clone)]
+pub struct MerkleTree {
+    hasher: MerkleHasher,
+    /// The hashes of the complete subtrees, by height: `levels[h][i]` covers the leaves [i * 2^h, (i + 1) * 2^h)
+    levels: Vec<Vec<Vec<u8>>>,
+}
+
+impl MerkleTree {
+    /// Create an empty tree
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_type` - The hash function of the tree
+    ///
+    /// # Returns
+    ///
+    /// The tree
+    pub fn new(hash_type: HashType) -> Result<MerkleTree> {
+        Ok(MerkleTree {
+            hasher: MerkleHasher::new(hash_type)?,
+            levels: vec![Vec::new()],
+        })
+    }
+
+    /// Get the hasher of the tree, to verify its proofs
+    pub fn get_hasher(&self) -> &MerkleHasher {
+        &self.hasher
+    }
+
+    /// Get the number of leaves
+    pub fn len(&self) -> u64 {
+        self.levels[0].len() as u64
+    }
+
+    /// Check if the tree has no leaves
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Append a leaf
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data of the leaf
+    ///
+    /// # Returns
+    ///
+    /// The index of the leaf
+    pub fn push(&mut self, data: &[u8]) -> Result<u64> {
+        let leaf_hash = self.hasher.hash_leaf(data)?;
+        self.push_hash(leaf_hash)
+    }
+
+    /// Append a leaf by its hash, from `MerkleHasher::hash_leaf`
+    ///
+    /// # Arguments
+    ///
+    /// * `leaf_hash` - The hash of the leaf
+    ///
+    /// # Returns
+    ///
+    /// The index of the leaf
+    pub fn push_hash(&mut self, leaf_hash: Vec<u8>) -> Result<u64> {
+        let index = self.len();
+        self.levels[0].push(leaf_hash);
+        // Complete the subtrees the leaf closes
+        let mut height = 0;
+        while self.levels[height].len() % 2 == 0 {
+            let level = &self.levels[height];
+            let node = self
+                .hasher
+                .hash_children(&level[level.len() - 2], &level[level.len() - 1])?;
+            if self.levels.len() == height + 1 {
+                self.levels.push(Vec::new());
+            }
+            self.levels[height + 1].push(node);
+            height += 1;
+        }
+        Ok(index)
+    }
+
+    /// Get the root hash of the tree
+    pub fn get_root(&self) -> Result<Vec<u8>> {
+        self.get_root_at(self.len())
+    }
+
+    /// Get the root hash of the tree when it had fewer leaves
+    ///
+    /// # Arguments
+    ///
+    /// * `tree_size` - The number of leaves
+    ///
+    /// # Returns
+    ///
+    /// The root hash of the first `tree_size` leaves, the hash of the empty string for an empty tree
+    pub fn get_root_at(&self, tree_size: u64) -> Result<Vec<u8>> {
+        self.check_size(tree_size)?;
+        if tree_size == 0 {
+            return self.hasher.hash.hash(&[]);
+        }
+        self.subtree_root(0, tree_size)
+    }
+
+    /// Get the inclusion proof of a leaf (RFC 9162 § 2.1.3.1)
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the leaf
+    /// * `tree_size` - The number of leaves of the tree to prove the inclusion in
+    ///
+    /// # Returns
+    ///
+    /// The proof, for `MerkleHasher::verify_inclusion`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::MerkleIndexOutOfRange` if the leaf is not in the tree of that size
+    pub fn inclusion_proof(&self, index: u64, tree_size: u64) -> Result<Vec<Vec<u8>>> {
+        self.check_size(tree_size)?;
+        if index >= tree_size {
+            return Err(QuantCryptError::MerkleIndexOutOfRange {
+                index,
+                size: tree_size,
+            });
+        }
+        let mut proof = Vec::new();
+        self.path(index, 0, tree_size, &mut proof)?;
+        Ok(proof)
+    }
+
+    /// Get the proof that a tree extends an older one (RFC 9162 § 2.1.4.1)
+    ///
+    /// # Arguments
+    ///
+    /// * `first_size` - The number of leaves of the older tree
+    /// * `second_size` - The number of leaves of the newer tree
+    ///
+    /// # Returns
+    ///
+    /// The proof, for `MerkleHasher::verify_consistency`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::MerkleIndexOutOfRange` if the older tree is larger than the newer one
+    pub fn consistency_proof(&self, first_size: u64, second_size: u64) -> Result<Vec<Vec<u8>>> {
+        self.check_size(second_size)?;
+        if first_size > second_size {
+            return Err(QuantCryptError::MerkleIndexOutOfRange {
+                index: first_size,
+                size: second_size,
+            });
+        }
+        let mut proof = Vec::new();
+        if first_size > 0 {
+            self.subproof(first_size, 0, second_size, true, &mut proof)?;
+        }
+        Ok(proof)
+    }
+
+    /// Get a single proof of the inclusion of several leaves
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - The indices of the leaves
+    /// * `tree_size` - The number of leaves of the tree to prove the inclusion in
+    ///
+    /// # Returns
+    ///
+    /// The proof, for `MerkleHasher::verify_multiproof`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::MerkleIndexOutOfRange` if a leaf is not in the tree of that size
+    pub fn multiproof(&self, indices: &[u64], tree_size: u64) -> Result<Vec<Vec<u8>>> {
+        self.check_size(tree_size)?;
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if let Some(&index) = indices.iter().find(|index| **index >= tree_size) {
+            return Err(QuantCryptError::MerkleIndexOutOfRange {
+                index,
+                size: tree_size,
+            });
+        }
+        let mut proof = Vec::new();
+        if !indices.is_empty() {
+            self.multi(&indices, 0, tree_size, &mut proof)?;
+        }
+        Ok(proof)
+    }
+
+    /// Check that the tree has at least `tree_size` leaves
+    fn check_size(&self, tree_size: u64) -> Result<()> {
+        if tree_size > self.len() {
+            return Err(QuantCryptError::MerkleIndexOutOfRange {
+                index: tree_size,
+                size: self.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The root hash of the leaves [lo, hi), MTH of RFC 9162 § 2.1.1
+    ///
+    /// Every left subtree in the recursion is complete and aligned, so it is
+    /// read from the levels.
+    fn subtree_root(&self, lo: u64, hi: u64) -> Result<Vec<u8>> {
+        let n = hi - lo;
+        if n.is_power_of_two() && lo % n == 0 {
+            let height = n.trailing_zeros() as usize;
+            return Ok(self.levels[height][(lo / n) as usize].clone());
+        }
+        let mid = lo + split(n);
+        self.hasher
+            .hash_children(&self.subtree_root(lo, mid)?, &self.subtree_root(mid, hi)?)
+    }
+
+    /// PATH of RFC 9162 § 2.1.3.1, for the leaves [lo, hi)
+    fn path(&self, index: u64, lo: u64, hi: u64, proof: &mut Vec<Vec<u8>>) -> Result<()> {
+        if hi - lo == 1 {
+            return Ok(());
+        }
+        let mid = lo + split(hi - lo);
+        if index < mid {
+            self.path(index, lo, mid, proof)?;
+            proof.push(self.subtree_root(mid, hi)?);
+        } else {
+            self.path(index, mid, hi, proof)?;
+            proof.push(self.subtree_root(lo, mid)?);
+        }
+        Ok(())
+    }
+
+    /// SUBPROOF of RFC 9162 § 2.1.4.1, for the first m of the leaves [lo, hi)
+    fn subproof(
+        &self,
+        m: u64,
+        lo: u64,
+        hi: u64,
+        complete: bool,
+        proof: &mut Vec<Vec<u8>>,
+    ) -> Result<()> {
+        if m == hi - lo {
+            if !complete {
+                proof.push(self.subtree_root(lo, hi)?);
+            }
+            return Ok(());
+        }
+        let k = split(hi - lo);
+        if m <= k {
+            self.subproof(m, lo, lo + k, complete, proof)?;
+            proof.push(self.subtree_root(lo + k, hi)?);
+        } else {
+            self.subproof(m - k, lo + k, hi, false, proof)?;
+            proof.push(self.subtree_root(lo, lo + k)?);
+        }
+        Ok(())
+    }
+
+    /// The hashes of the largest subtrees of [lo, hi) holding none of the leaves, from left to right
+    fn multi(&self, indices: &[u64], lo: u64, hi: u64, proof: &mut Vec<Vec<u8>>) -> Result<()> {
+        if indices.is_empty() {
+            proof.push(self.subtree_root(lo, hi)?);
+            return Ok(());
+        }
+        if hi - lo == 1 {
+            return Ok(());
+        }
+        let mid = lo + split(hi - lo);
+        let (left, right) = indices.split_at(indices.partition_point(|index| *index < mid));
+        self.multi(left, lo, mid, proof)?;
+        self.multi(right, mid, hi, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The largest power of two smaller than n
+    fn split(n: usize) -> usize {
+        let mut k = 1;
+        while k * 2 < n {
+            k *= 2;
+        }
+        k
+    }
+
+    /// The root hash, MTH of RFC 9162 § 2.1.1
+    fn root(hasher: &MerkleHasher, leaves: &[Vec<u8>]) -> Vec<u8> {
+        match leaves.len() {
+            0 => hasher.hash.hash(&[]).unwrap(),
+            1 => hasher.hash_leaf(&leaves[0]).unwrap(),
+            n => {
+                let k = split(n);
+                let left = root(hasher, &leaves[..k]);
+                let right = root(hasher, &leaves[k..]);
+                hasher.hash_children(&left, &right).unwrap()
+            }
+        }
+    }
+
+    /// The inclusion proof, PATH of RFC 9162 § 2.1.3.1
+    fn path(hasher: &MerkleHasher, m: usize, leaves: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        if leaves.len() == 1 {
+            return Vec::new();
+        }
+        let k = split(leaves.len());
+        let (mut proof, sibling) = if m < k {
+            (path(hasher, m, &leaves[..k]), root(hasher, &leaves[k..]))
+        } else {
+            (
+                path(hasher, m - k, &leaves[k..]),
+                root(hasher, &leaves[..k]),
+            )
+        };
+        proof.push(sibling);
+        proof
+    }
+
+    /// The consistency proof, SUBPROOF of RFC 9162 § 2.1.4.1
+    fn subproof(hasher: &MerkleHasher, m: usize, leaves: &[Vec<u8>], b: bool) -> Vec<Vec<u8>> {
+        let n = leaves.len();
+        if m == n {
+            return if b {
+                Vec::new()
+            } else {
+                vec![root(hasher, leaves)]
+            };
+        }
+        let k = split(n);
+        let (mut proof, sibling) = if m <= k {
+            (
+                subproof(hasher, m, &leaves[..k], b),
+                root(hasher, &leaves[k..]),
+            )
+        } else {
+            (
+                subproof(hasher, m - k, &leaves[k..], false),
+                root(hasher, &leaves[..k]),
+            )
+        };
+        proof.push(sibling);
+        proof
+    }
+
+    #[test]
+    fn test_merkle_proofs() {
+        let hasher = MerkleHasher::new(HashType::Sha256).unwrap();
+        let leaves: Vec<Vec<u8>> = (0..9u8).map(|i| vec![i; i as usize + 1]).collect();
+
+        for n in 1..=leaves.len() {
+            let tree = &leaves[..n];
+            let tree_root = root(&hasher, tree);
+            let old_root = root(&hasher, &leaves[..n - 1]);
+            for (m, leaf) in tree.iter().enumerate() {
+                let leaf_hash = hasher.hash_leaf(leaf).unwrap();
+                let proof = path(&hasher, m, tree);
+                let (m, size) = (m as u64, n as u64);
+                assert!(hasher
+                    .verify_inclusion(&leaf_hash, m, size, &proof, &tree_root)
+                    .unwrap());
+                assert!(!hasher
+                    .verify_inclusion(&leaf_hash, m, size, &proof, &old_root)
+                    .unwrap());
+                assert!(!hasher
+                    .verify_inclusion(&leaf_hash, size, size, &proof, &tree_root)
+                    .unwrap());
+                if let Some(first) = proof.first() {
+                    assert!(!hasher
+                        .verify_inclusion(first, m, size, &proof, &tree_root)
+                        .unwrap());
+                }
+            }
+
+            for m in 0..=n {
+                let old_root = root(&hasher, &leaves[..m]);
+                // The empty tree is extended by every tree, with an empty proof
+                let proof = match m {
+                    0 => Vec::new(),
+                    m => subproof(&hasher, m, tree, true),
+                };
+                let (m, size) = (m as u64, n as u64);
+                assert!(hasher
+                    .verify_consistency(m, size, &proof, &old_root, &tree_root)
+                    .unwrap());
+                if m > 0 && m < size {
+                    assert!(!hasher
+                        .verify_consistency(m, size, &proof, &tree_root, &tree_root)
+                        .unwrap());
+                    assert!(!hasher
+                        .verify_consistency(m, size, &proof[1..], &old_root, &tree_root)
+                        .unwrap());
+                }
+            }
+        }
+
+        // The known root of the RFC 6962 test vectors, for a tree of one empty leaf
+        assert_eq!(
+            hex::encode(hasher.hash_leaf(&[]).unwrap()),
+            "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d"
+        );
+    }
+
+    #[test]
+    fn test_merkle_tree() {
+        let mut tree = MerkleTree::new(HashType::Sha512).unwrap();
+        let hasher = tree.get_hasher().clone();
+        assert!(tree.is_empty());
+        assert_eq!(tree.get_root().unwrap(), root(&hasher, &[]));
+        let leaves: Vec<Vec<u8>> = (0..9u8).map(|i| vec![i; 3]).collect();
+        for (i, leaf) in leaves.iter().enumerate() {
+            assert_eq!(tree.push(leaf).unwrap(), i as u64);
+        }
+        assert_eq!(tree.len(), 9);
+
+        // The roots and proofs match the definitions of RFC 9162
+        for n in 1..=leaves.len() {
+            let size = n as u64;
+            let tree_root = tree.get_root_at(size).unwrap();
+            assert_eq!(tree_root, root(&hasher, &leaves[..n]));
+            for m in 0..n {
+                assert_eq!(
+                    tree.inclusion_proof(m as u64, size).unwrap(),
+                    path(&hasher, m, &leaves[..n])
+                );
+            }
+            for m in 1..=n {
+                assert_eq!(
+                    tree.consistency_proof(m as u64, size).unwrap(),
+                    subproof(&hasher, m, &leaves[..n], true)
+                );
+            }
+
+            // Every subset of the leaves has a multiproof
+            for subset in 1..(1u32 << n) {
+                let indices: Vec<u64> = (0..size).filter(|i| subset >> i & 1 == 1).collect();
+                let proof = tree.multiproof(&indices, size).unwrap();
+                let mut proven: Vec<(u64, Vec<u8>)> = indices
+                    .iter()
+                    .map(|i| (*i, hasher.hash_leaf(&leaves[*i as usize]).unwrap()))
+                    .collect();
+                assert!(hasher
+                    .verify_multiproof(&proven, size, &proof, &tree_root)
+                    .unwrap());
+                if !proof.is_empty() {
+                    assert!(!hasher
+                        .verify_multiproof(&proven, size, &proof[1..], &tree_root)
+                        .unwrap());
+                }
+                proven[0].1 = hasher.hash_leaf(b"forged").unwrap();
+                assert!(!hasher
+                    .verify_multiproof(&proven, size, &proof, &tree_root)
+                    .unwrap());
+            }
+        }
+
+        // Indices may be unsorted or repeated, and the leaves 0, 1 and 3 share their siblings
+        let proof = tree.multiproof(&[1, 0, 3, 3], 9).unwrap();
+        let expected = vec![
+            hasher.hash_leaf(&leaves[2]).unwrap(),
+            tree.subtree_root(4, 8).unwrap(),
+            tree.subtree_root(8, 9).unwrap(),
+        ];
+        assert_eq!(proof, expected);
+        assert!(tree.inclusion_proof(9, 9).is_err());
+        assert!(tree.consistency_proof(5, 4).is_err());
+        assert!(tree.multiproof(&[2], 10).is_err());
+        assert!(!hasher
+            .verify_multiproof(&[], 9, &[], &tree.get_root().unwrap())
+            .unwrap());
+    }
+}
@@ -0,0 +1,335 @@
+use crate::asn1::private_key::PrivateKey;
+use crate::asn1::public_key::PublicKey;
+use crate::hash::common::hash_type::HashType;
+use crate::tlog::merkle::MerkleHasher;
+use crate::utils::clock_skew::get_clock_skew_tolerance;
+use crate::utils::verification_report::{unix_now, VerificationReport};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The tree head of a transparency log, signed by the log
+///
+/// The signature covers the `TreeHeadDataV2` structure of RFC 9162 § 4.10,
+/// `timestamp || tree_size || root_hash || extensions`, with TLS encoding and
+/// no extensions. Any DSA supported by `PublicKey::verify` can sign it,
+/// including ML-DSA and the composite signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedTreeHead {
+    /// The number of leaves of the tree
+    pub tree_size: u64,
+    /// When the tree head was produced, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// The root hash of the tree
+    pub root_hash: Vec<u8>,
+    /// The signature of the log over the tree head
+    pub signature: Vec<u8>,
+}
+
+impl SignedTreeHead {
+    /// Sign a tree head, as a log
+    ///
+    /// # Arguments
+    ///
+    /// * `tree_size` - The number of leaves of the tree
+    /// * `timestamp` - The time, in milliseconds since the Unix epoch
+    /// * `root_hash` - The root hash of the tree
+    /// * `log_key` - The signing key of the log
+    ///
+    /// # Returns
+    ///
+    /// The signed tree head
+    pub fn sign(
+        tree_size: u64,
+        timestamp: u64,
+        root_hash: &[u8],
+        log_key: &PrivateKey,
+    ) -> Result<SignedTreeHead> {
+        let mut sth = SignedTreeHead {
+            tree_size,
+            timestamp,
+            root_hash: root_hash.to_vec(),
+            signature: Vec::new(),
+        };
+        sth.signature = log_key.sign(&sth.get_signed_data()?)?;
+        Ok(sth)
+    }
+
+    /// Get the data covered by the signature, the encoded `TreeHeadDataV2`
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidEncoding` if the root hash is empty or longer than 255 bytes
+    pub fn get_signed_data(&self) -> Result<Vec<u8>> {
+        let root_len = u8::try_from(self.root_hash.len())
+            .ok()
+            .filter(|len| *len > 0)
+            .ok_or(QuantCryptError::InvalidEncoding)?;
+        let mut data = Vec::with_capacity(19 + self.root_hash.len());
+        data.extend_from_slice(&self.timestamp.to_be_bytes());
+        data.extend_from_slice(&self.tree_size.to_be_bytes());
+        data.push(root_len);
+        data.extend_from_slice(&self.root_hash);
+        // No extensions
+        data.extend_from_slice(&[0, 0]);
+        Ok(data)
+    }
+}
+
+/// Verifies the tree heads and proofs of a transparency log
+///
+/// This is the client side of a key transparency or certificate transparency
+/// log: tree heads are checked against the key of the log, and the Merkle
+/// proofs against the tree heads. Each verification returns a
+/// `VerificationReport` recording the checks in order.
+///
+/// # Example
+/// ```
+/// use quantcrypt::dsas::{DsaAlgorithm, DsaKeyGenerator};
+/// use quantcrypt::hashes::HashType;
+/// use quantcrypt::transparency::{LogVerifier, MerkleHasher, SignedTreeHead};
+///
+/// let (log_pk, log_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa44).generate().unwrap();
+///
+/// // A log with a single leaf
+/// let hasher = MerkleHasher::new(HashType::Sha256).unwrap();
+/// let root = hasher.hash_leaf(b"alice's key").unwrap();
+/// let sth = SignedTreeHead::sign(1, 1_700_000_000_000, &root, &log_sk).unwrap();
+///
+/// let verifier = LogVerifier::new(log_pk, HashType::Sha256).unwrap();
+/// let report = verifier.verify_inclusion(&sth, b"alice's key", 0, &[]).unwrap();
+/// assert!(report.is_valid());
+/// ```
+#[derive(Clone)]
+pub struct LogVerifier {
+    log_key: PublicKey,
+    hasher: MerkleHasher,
+    hash_len: usize,
+}
+
+impl LogVerifier {
+    /// Create a verifier for a log
+    ///
+    /// # Arguments
+    ///
+    /// * `log_key` - The public key of the log
+    /// * `hash_type` - The hash function of the tree of the log
+    ///
+    /// # Returns
+    ///
+    /// The verifier
+    pub fn new(log_key: PublicKey, hash_type: HashType) -> Result<LogVerifier> {
+        let hasher = MerkleHasher::new(hash_type)?;
+        let hash_len = hasher.hash_leaf(&[])?.len();
+        Ok(LogVerifier {
+            log_key,
+            hasher,
+            hash_len,
+        })
+    }
+
+    /// Get the hasher of the tree of the log
+    pub fn get_hasher(&self) -> &MerkleHasher {
+        &self.hasher
+    }
+
+    /// Verify a signed tree head
+    ///
+    /// The signature and the length of the root hash are checked, and the
+    /// timestamp must not be in the future, beyond the tolerance set by
+    /// `set_clock_skew_tolerance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sth` - The signed tree head
+    ///
+    /// # Returns
+    ///
+    /// The verification report
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` if the key of the log is not a DSA key
+    pub fn verify_tree_head(&self, sth: &SignedTreeHead) -> Result<VerificationReport> {
+        let mut report = self.new_report();
+        report.valid = self.check_tree_head(&mut report, sth, "tree head")?;
+        Ok(report)
+    }
+
+    /// Verify that a leaf is included in the tree of a signed tree head
+    ///
+    /// # Arguments
+    ///
+    /// * `sth` - The signed tree head
+    /// * `leaf` - The data of the leaf
+    /// * `index` - The index of the leaf
+    /// * `proof` - The inclusion proof
+    ///
+    /// # Returns
+    ///
+    /// The verification report
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` if the key of the log is not a DSA key
+    pub fn verify_inclusion(
+        &self,
+        sth: &SignedTreeHead,
+        leaf: &[u8],
+        index: u64,
+        proof: &[Vec<u8>],
+    ) -> Result<VerificationReport> {
+        let mut report = self.new_report();
+        if !self.check_tree_head(&mut report, sth, "tree head")? {
+            return Ok(report);
+        }
+        let leaf_hash = self.hasher.hash_leaf(leaf)?;
+        let included = self.hasher.verify_inclusion(
+            &leaf_hash,
+            index,
+            sth.tree_size,
+            proof,
+            &sth.root_hash,
+        )?;
+        report.valid = report.check(&format!("inclusion of leaf {}", index), included);
+        Ok(report)
+    }
+
+    /// Verify that the tree of a signed tree head extends the tree of an older one
+    ///
+    /// Clients keep the last tree head they verified and check each new one
+    /// against it, so a log can't rewrite its history without being detected.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The older signed tree head
+    /// * `new` - The newer signed tree head
+    /// * `proof` - The consistency proof between the two trees
+    ///
+    /// # Returns
+    ///
+    /// The verification report
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::UnsupportedOperation` if the key of the log is not a DSA key
+    pub fn verify_consistency(
+        &self,
+        old: &SignedTreeHead,
+        new: &SignedTreeHead,
+        proof: &[Vec<u8>],
+    ) -> Result<VerificationReport> {
+        let mut report = self.new_report();
+        if !self.check_tree_head(&mut report, old, "old tree head")?
+            || !self.check_tree_head(&mut report, new, "new tree head")?
+            || !report.check("tree size not decreasing", old.tree_size <= new.tree_size)
+        {
+            return Ok(report);
+        }
+        let consistent = self.hasher.verify_consistency(
+            old.tree_size,
+            new.tree_size,
+            proof,
+            &old.root_hash,
+            &new.root_hash,
+        )?;
+        report.valid = report.check("consistency proof", consistent);
+        Ok(report)
+    }
+
+    /// Create a report describing the log
+    fn new_report(&self) -> VerificationReport {
+        let mut report = VerificationReport::new();
+        report.policy = Some(format!(
+            "transparency log with {:?} tree signed with {}",
+            self.hasher.get_hash_type(),
+            self.log_key.get_oid()
+        ));
+        report
+    }
+
+    /// Record the checks of a signed tree head
+    fn check_tree_head(
+        &self,
+        report: &mut VerificationReport,
+        sth: &SignedTreeHead,
+        name: &str,
+    ) -> Result<bool> {
+        if !report.check(
+            &format!("{} root hash length", name),
+            sth.root_hash.len() == self.hash_len,
+        ) {
+            return Ok(false);
+        }
+        let latest = (unix_now() + get_clock_skew_tolerance().as_secs()).saturating_mul(1000);
+        if !report.check(
+            &format!("{} not in the future", name),
+            sth.timestamp <= latest,
+        ) {
+            return Ok(false);
+        }
+        let signed = self
+            .log_key
+            .verify(&sth.get_signed_data()?, &sth.signature)?;
+        Ok(report.check(&format!("{} signature", name), signed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsas::{DsaAlgorithm, DsaKeyGenerator};
+
+    #[test]
+    fn test_log_verifier() {
+        let (log_pk, log_sk) = DsaKeyGenerator::new(DsaAlgorithm::MlDsa65)
+            .generate()
+            .unwrap();
+        let verifier = LogVerifier::new(log_pk, HashType::Sha256).unwrap();
+        let hasher = verifier.get_hasher();
+
+        // A tree of three leaves, grown from a tree of two
+        let leaves: Vec<Vec<u8>> = [b"a", b"b", b"c"]
+            .iter()
+            .map(|leaf| hasher.hash_leaf(*leaf).unwrap())
+            .collect();
+        let ab = hasher.hash_children(&leaves[0], &leaves[1]).unwrap();
+        let abc = hasher.hash_children(&ab, &leaves[2]).unwrap();
+        let now = unix_now() * 1000;
+        let old = SignedTreeHead::sign(2, now - 1000, &ab, &log_sk).unwrap();
+        let new = SignedTreeHead::sign(3, now, &abc, &log_sk).unwrap();
+
+        assert!(verifier.verify_tree_head(&new).unwrap().is_valid());
+        let report = verifier
+            .verify_inclusion(&new, b"c", 2, &[ab.clone()])
+            .unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.checks.len(), 4);
+        assert!(!verifier
+            .verify_inclusion(&new, b"d", 2, &[ab.clone()])
+            .unwrap()
+            .is_valid());
+        let report = verifier
+            .verify_consistency(&old, &new, &[leaves[2].clone()])
+            .unwrap();
+        assert!(report.is_valid());
+        assert!(!verifier
+            .verify_consistency(&new, &old, &[leaves[2].clone()])
+            .unwrap()
+            .is_valid());
+
+        // Tampered tree heads are rejected
+        let mut forged = new.clone();
+        forged.tree_size = 4;
+        let report = verifier.verify_tree_head(&forged).unwrap();
+        assert_eq!(
+            report.get_failed_check().unwrap().name,
+            "tree head signature"
+        );
+        let future = SignedTreeHead::sign(3, now + 3_600_000, &abc, &log_sk).unwrap();
+        assert!(!verifier.verify_tree_head(&future).unwrap().is_valid());
+        let short = SignedTreeHead::sign(3, now, &abc[..20], &log_sk).unwrap();
+        assert!(!verifier.verify_tree_head(&short).unwrap().is_valid());
+        assert!(SignedTreeHead::sign(3, now, &[], &log_sk).is_err());
+    }
+}
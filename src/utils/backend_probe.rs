@@ -0,0 +1,243 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::dsa::api::algorithm::DsaAlgorithm;
+use crate::dsa::common::dsa_trait::Dsa;
+use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+use crate::dsa::dsa_manager::{DsaManager, PrehashDsaManager};
+use crate::kem::api::algorithm::KemAlgorithm;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::kem_manager::KemManager;
+use crate::utils::ct_eq::ct_eq;
+use crate::utils::manager_pool::{
+    clear_disabled, set_dsa_disabled, set_kem_disabled, set_prehash_dsa_disabled,
+};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The message signed when probing a DSA
+const PROBE_MESSAGE: &[u8] = b"quantcrypt backend probe";
+
+/// An algorithm exercised by a backend probe
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProbedAlgorithm {
+    /// A KEM algorithm
+    Kem(KemAlgorithm),
+    /// A DSA algorithm
+    Dsa(DsaAlgorithm),
+}
+
+impl ProbedAlgorithm {
+    /// Get the OID of the algorithm
+    ///
+    /// # Returns
+    ///
+    /// The OID of the algorithm
+    pub fn get_oid(&self) -> String {
+        match self {
+            ProbedAlgorithm::Kem(alg) => alg.get_oid(),
+            ProbedAlgorithm::Dsa(alg) => alg.get_oid(),
+        }
+    }
+}
+
+/// An algorithm that failed a backend probe
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbeFailure {
+    /// The algorithm that failed
+    pub algorithm: ProbedAlgorithm,
+    /// Why the algorithm failed, e.g. the error of the failing operation
+    pub reason: String,
+}
+
+/// The outcome of a backend probe
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProbeReport {
+    /// The algorithms that passed the probe
+    pub operational: Vec<ProbedAlgorithm>,
+    /// The algorithms that failed the probe, and were disabled
+    pub failures: Vec<ProbeFailure>,
+}
+
+impl ProbeReport {
+    /// Check if every probed algorithm is operational
+    ///
+    /// # Returns
+    ///
+    /// True if no algorithm failed the probe
+    pub fn is_healthy(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Check if an algorithm passed the probe
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm
+    ///
+    /// # Returns
+    ///
+    /// True if the algorithm was probed and is operational
+    pub fn is_operational(&self, algorithm: &ProbedAlgorithm) -> bool {
+        self.operational.contains(algorithm)
+    }
+
+    /// Get the OIDs of the algorithms that failed the probe
+    ///
+    /// # Returns
+    ///
+    /// The OIDs of the failed algorithms
+    pub fn get_failed_oids(&self) -> Vec<String> {
+        self.failures
+            .iter()
+            .map(|failure| failure.algorithm.get_oid())
+            .collect()
+    }
+
+    /// Record the outcome of probing an algorithm
+    fn record(&mut self, algorithm: ProbedAlgorithm, result: std::result::Result<(), String>) {
+        match result {
+            Ok(()) => self.operational.push(algorithm),
+            Err(reason) => self.failures.push(ProbeFailure { algorithm, reason }),
+        }
+    }
+}
+
+/// Probe the backends of all KEM and DSA algorithms
+///
+/// Each algorithm is exercised once: a key pair is generated, and used to
+/// encapsulate and decapsulate, or to sign and verify. Algorithms failing any
+/// step, including by panicking, are disabled: `KemAlgorithm::available()` and
+/// `DsaAlgorithm::available()` leave them out, and every layer creating managers
+/// for them (keys, certificates, CMS, HPKE, ...) fails with
+/// `QuantCryptError::BackendUnavailable` instead of with an error specific to the
+/// broken backend. The algorithms that pass are enabled again, so probing again
+/// after fixing a backend restores them.
+///
+/// Fleets with differing OpenSSL builds can call this at startup, and log or
+/// export the report. Probing exercises every algorithm, including RSA key
+/// generation and SLH-DSA signing, and takes a few seconds; use
+/// `probe_algorithms` to probe a subset.
+///
+/// # Returns
+///
+/// The probe report
+pub fn probe_backends() -> ProbeReport {
+    clear_disabled();
+    probe_algorithms(&KemAlgorithm::all(), &DsaAlgorithm::all())
+}
+
+/// Probe the backends of some KEM and DSA algorithms
+///
+/// Only the given algorithms are enabled or disabled, see `probe_backends`.
+///
+/// # Arguments
+///
+/// * `kems` - The KEM algorithms to probe
+/// * `dsas` - The DSA algorithms to probe
+///
+/// # Returns
+///
+/// The probe report
+pub fn probe_algorithms(kems: &[KemAlgorithm], dsas: &[DsaAlgorithm]) -> ProbeReport {
+    let mut report = ProbeReport::default();
+    for kem in kems {
+        let result = run_probe(|| probe_kem(kem));
+        set_kem_disabled(kem.get_kem_type(), result.is_err());
+        report.record(ProbedAlgorithm::Kem(*kem), result);
+    }
+    for dsa in dsas {
+        let result = run_probe(|| probe_dsa(dsa));
+        match (dsa.get_dsa_type(), dsa.get_prehash_dsa_type()) {
+            (Some(dsa_type), _) => set_dsa_disabled(dsa_type, result.is_err()),
+            (None, Some(dsa_type)) => set_prehash_dsa_disabled(dsa_type, result.is_err()),
+            (None, None) => {}
+        }
+        report.record(ProbedAlgorithm::Dsa(*dsa), result);
+    }
+    report
+}
+
+/// Run a probe, turning its error or panic into a reason
+fn run_probe(probe: impl FnOnce() -> Result<()>) -> std::result::Result<(), String> {
+    match catch_unwind(AssertUnwindSafe(probe)) {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("the backend panicked".to_string()),
+    }
+}
+
+/// Generate a key pair, and check that decapsulation recovers the encapsulated secret
+///
+/// The manager is created directly rather than taken from the pool, so that
+/// algorithms disabled by an earlier probe are exercised again.
+fn probe_kem(kem: &KemAlgorithm) -> Result<()> {
+    let mut manager = KemManager::new(kem.get_kem_type())?;
+    let (pk, sk) = manager.key_gen()?;
+    let (ss, ct) = manager.encap(&pk)?;
+    let recovered = manager.decap(&sk, &ct)?;
+    if !ct_eq(&ss, &recovered) {
+        return Err(QuantCryptError::DecapFailed);
+    }
+    Ok(())
+}
+
+/// Generate a key pair, and check that a signature with it verifies
+fn probe_dsa(dsa: &DsaAlgorithm) -> Result<()> {
+    let verified = match (dsa.get_dsa_type(), dsa.get_prehash_dsa_type()) {
+        (Some(dsa_type), _) => {
+            let mut manager = DsaManager::new(dsa_type)?;
+            let (pk, sk) = manager.key_gen()?;
+            let signature = manager.sign(&sk, PROBE_MESSAGE)?;
+            manager.verify(&pk, PROBE_MESSAGE, &signature)?
+        }
+        (None, Some(dsa_type)) => {
+            let mut manager = PrehashDsaManager::new(dsa_type)?;
+            let (pk, sk) = manager.key_gen()?;
+            let signature = manager.sign(&sk, PROBE_MESSAGE)?;
+            manager.verify(&pk, PROBE_MESSAGE, &signature)?
+        }
+        (None, None) => return Err(QuantCryptError::NotImplemented),
+    };
+    if !verified {
+        return Err(QuantCryptError::SignatureVerificationFailed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_probe_algorithms() {
+        let kems = [KemAlgorithm::MlKem768, KemAlgorithm::MlKem768X25519];
+        let dsas = [DsaAlgorithm::MlDsa44, DsaAlgorithm::MlDsa44Ed25519];
+        let report = probe_algorithms(&kems, &dsas);
+        assert!(report.is_healthy());
+        assert_eq!(report.operational.len(), 4);
+        assert!(report.is_operational(&ProbedAlgorithm::Kem(KemAlgorithm::MlKem768)));
+        assert!(!report.is_operational(&ProbedAlgorithm::Kem(KemAlgorithm::MlKem512)));
+        assert!(report.get_failed_oids().is_empty());
+        assert!(kems.iter().all(|kem| kem.is_available()));
+        assert!(dsas.iter().all(|dsa| dsa.is_available()));
+    }
+
+    #[test]
+    fn test_failed_probe() {
+        let failure = run_probe(|| Err(QuantCryptError::KeyPairGenerationFailed));
+        assert_eq!(
+            failure,
+            Err(QuantCryptError::KeyPairGenerationFailed.to_string())
+        );
+        assert!(run_probe(|| panic!("broken backend")).is_err());
+
+        let mut report = ProbeReport::default();
+        let algorithm = ProbedAlgorithm::Kem(KemAlgorithm::MlKem512);
+        report.record(algorithm, failure);
+        assert!(!report.is_healthy());
+        assert!(!report.is_operational(&algorithm));
+        assert_eq!(report.get_failed_oids(), vec![algorithm.get_oid()]);
+    }
+}
@@ -0,0 +1,207 @@
+use std::future::Future;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// A job run by a worker of the pool
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The pool shared by the blocking adapters that aren't given one
+static DEFAULT_POOL: OnceLock<Arc<BlockingPool>> = OnceLock::new();
+
+/// The state shared between a task and the worker running it
+struct TaskState<T> {
+    /// The result of the job, or the payload of its panic
+    result: Option<thread::Result<T>>,
+    /// The waker of the last poll of the task
+    waker: Option<Waker>,
+}
+
+/// A fixed set of threads running blocking jobs, such as calls to a network HSM,
+/// off the threads of an async runtime
+///
+/// The pool doesn't depend on a runtime: the future returned by `spawn` is
+/// woken by the worker when the job completes, so it works with any executor.
+/// The workers exit when the pool is dropped, after finishing the queued jobs.
+pub struct BlockingPool {
+    /// The queue of the workers
+    sender: Mutex<Sender<Job>>,
+    /// The number of workers
+    threads: usize,
+}
+
+impl BlockingPool {
+    /// Create a new pool
+    ///
+    /// # Arguments
+    ///
+    /// * `threads` - The number of worker threads, at least one is started
+    ///
+    /// # Returns
+    ///
+    /// A new pool
+    pub fn new(threads: usize) -> Self {
+        let threads = threads.max(1);
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..threads {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("quantcrypt-blocking-{}", i))
+                .spawn(move || Self::work(&receiver))
+                .expect("failed to spawn a blocking pool worker");
+        }
+        Self {
+            sender: Mutex::new(sender),
+            threads,
+        }
+    }
+
+    /// Get the pool shared by default, with one worker per available CPU
+    ///
+    /// # Returns
+    ///
+    /// The default pool
+    pub fn get_default() -> Arc<BlockingPool> {
+        DEFAULT_POOL
+            .get_or_init(|| {
+                let threads = thread::available_parallelism().map_or(1, |n| n.get());
+                Arc::new(BlockingPool::new(threads))
+            })
+            .clone()
+    }
+
+    /// Get the number of worker threads
+    ///
+    /// # Returns
+    ///
+    /// The number of worker threads
+    pub fn get_threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Run jobs until the pool is dropped
+    fn work(receiver: &Mutex<Receiver<Job>>) {
+        loop {
+            let job = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => return,
+            };
+            match job {
+                Ok(job) => job(),
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Run a blocking job on the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The job to run
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to the result of the job. If the job panics, the
+    /// panic is resumed when the future is polled.
+    pub fn spawn<T, F>(&self, job: F) -> BlockingTask<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(TaskState {
+            result: None,
+            waker: None,
+        }));
+        let worker_state = state.clone();
+        let job: Job = Box::new(move || {
+            let result = catch_unwind(AssertUnwindSafe(job));
+            let waker = {
+                let mut state = worker_state.lock().unwrap_or_else(|e| e.into_inner());
+                state.result = Some(result);
+                state.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+        // The workers only exit once the sender is dropped with the pool
+        let sender = self.sender.lock().unwrap_or_else(|e| e.into_inner());
+        sender
+            .send(job)
+            .expect("the blocking pool workers have exited");
+        BlockingTask { state }
+    }
+}
+
+/// The future of a job run on a `BlockingPool`
+pub struct BlockingTask<T> {
+    /// The state shared with the worker
+    state: Arc<Mutex<TaskState<T>>>,
+}
+
+impl<T> Future for BlockingTask<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(panic)) => {
+                drop(state);
+                resume_unwind(panic)
+            }
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Run a future to completion on the current thread, for tests
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    use std::task::Wake;
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_pool() {
+        let pool = BlockingPool::new(2);
+        assert_eq!(pool.get_threads(), 2);
+
+        let tasks: Vec<_> = (0..8u64).map(|i| pool.spawn(move || i * i)).collect();
+        let results: Vec<u64> = tasks.into_iter().map(block_on).collect();
+        assert_eq!(results, (0..8u64).map(|i| i * i).collect::<Vec<_>>());
+
+        // A panicking job doesn't take down its worker
+        let task = pool.spawn(|| -> u64 { panic!("job failed") });
+        assert!(catch_unwind(AssertUnwindSafe(|| block_on(task))).is_err());
+        assert_eq!(block_on(pool.spawn(|| 7)), 7);
+    }
+}
@@ -0,0 +1,350 @@
+use pkcs8::ObjectIdentifier;
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The deepest nesting of arrays, maps and tags accepted when decoding
+const MAX_DEPTH: usize = 16;
+
+/// The tag of an object identifier (RFC 9090)
+const OID_TAG: u64 = 111;
+
+/// The tag of a COSE_Sign1 message (RFC 9052)
+pub(crate) const COSE_SIGN1_TAG: u64 = 18;
+
+/// A CBOR data item (RFC 8949)
+///
+/// Only the subset used by the compact encodings of this crate is supported:
+/// integers, byte and text strings, arrays, maps and tags. Items are encoded
+/// with definite lengths and the shortest form of each argument, and decoding
+/// rejects anything else, so every item has exactly one encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    /// An integer, major type 0 or 1
+    Int(i64),
+    /// A byte string, major type 2
+    Bytes(Vec<u8>),
+    /// A UTF-8 text string, major type 3
+    Text(String),
+    /// An array, major type 4
+    Array(Vec<Value>),
+    /// A map with its entries in order, major type 5
+    Map(Vec<(Value, Value)>),
+    /// A tagged item, major type 6
+    Tag(u64, Box<Value>),
+}
+
+/// Encode the head of an item: the major type and its argument, in the shortest form
+fn write_head(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if arg <= u8::MAX as u64 {
+        out.push(major | 24);
+        out.push(arg as u8);
+    } else if arg <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(arg as u16).to_be_bytes());
+    } else if arg <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(arg as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+/// A decoder reading items from a byte slice
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if n > self.data.len() {
+            return Err(QuantCryptError::InvalidCbor);
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(head)
+    }
+
+    /// Read the head of an item, rejecting indefinite lengths and non-shortest forms
+    fn read_head(&mut self) -> Result<(u8, u64)> {
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+        let (arg, min) = match info {
+            0..=23 => return Ok((major, info as u64)),
+            24 => (self.take(1)?[0] as u64, 24),
+            25 => (
+                u16::from_be_bytes([self.take(1)?[0], self.take(1)?[0]]) as u64,
+                1 << 8,
+            ),
+            26 => {
+                let bytes: [u8; 4] = self.take(4)?.try_into().expect("4 bytes");
+                (u32::from_be_bytes(bytes) as u64, 1 << 16)
+            }
+            27 => {
+                let bytes: [u8; 8] = self.take(8)?.try_into().expect("8 bytes");
+                (u64::from_be_bytes(bytes), 1 << 32)
+            }
+            _ => return Err(QuantCryptError::InvalidCbor),
+        };
+        if arg < min {
+            return Err(QuantCryptError::InvalidCbor);
+        }
+        Ok((major, arg))
+    }
+
+    /// Read a length, which must fit in the remaining input
+    fn read_len(&self, arg: u64) -> Result<usize> {
+        // Every element takes at least one byte
+        match usize::try_from(arg) {
+            Ok(len) if len <= self.data.len() => Ok(len),
+            _ => Err(QuantCryptError::InvalidCbor),
+        }
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(QuantCryptError::InvalidCbor);
+        }
+        let (major, arg) = self.read_head()?;
+        match major {
+            0 => Ok(Value::Int(
+                i64::try_from(arg).map_err(|_| QuantCryptError::InvalidCbor)?,
+            )),
+            1 => Ok(Value::Int(
+                -1 - i64::try_from(arg).map_err(|_| QuantCryptError::InvalidCbor)?,
+            )),
+            2 => {
+                let len = self.read_len(arg)?;
+                Ok(Value::Bytes(self.take(len)?.to_vec()))
+            }
+            3 => {
+                let len = self.read_len(arg)?;
+                let text = std::str::from_utf8(self.take(len)?)
+                    .map_err(|_| QuantCryptError::InvalidCbor)?;
+                Ok(Value::Text(text.to_string()))
+            }
+            4 => {
+                let len = self.read_len(arg)?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.read_value(depth + 1)?);
+                }
+                Ok(Value::Array(items))
+            }
+            5 => {
+                let len = self.read_len(arg)?;
+                let mut entries = Vec::with_capacity(len);
+                for _ in 0..len {
+                    let key = self.read_value(depth + 1)?;
+                    let value = self.read_value(depth + 1)?;
+                    entries.push((key, value));
+                }
+                Ok(Value::Map(entries))
+            }
+            6 => Ok(Value::Tag(arg, Box::new(self.read_value(depth + 1)?))),
+            _ => Err(QuantCryptError::InvalidCbor),
+        }
+    }
+}
+
+impl Value {
+    /// Encode the item
+    ///
+    /// # Returns
+    ///
+    /// The CBOR encoding
+    pub(crate) fn to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Value::Int(n) if *n >= 0 => write_head(out, 0, *n as u64),
+            Value::Int(n) => write_head(out, 1, (-1 - *n) as u64),
+            Value::Bytes(bytes) => {
+                write_head(out, 2, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+            Value::Text(text) => {
+                write_head(out, 3, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Value::Array(items) => {
+                write_head(out, 4, items.len() as u64);
+                for item in items {
+                    item.write(out);
+                }
+            }
+            Value::Map(entries) => {
+                write_head(out, 5, entries.len() as u64);
+                for (key, value) in entries {
+                    key.write(out);
+                    value.write(out);
+                }
+            }
+            Value::Tag(tag, item) => {
+                write_head(out, 6, *tag);
+                item.write(out);
+            }
+        }
+    }
+
+    /// Decode an item, which must span the whole input
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The CBOR encoding
+    ///
+    /// # Returns
+    ///
+    /// The item
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidCbor` will be returned if the input is not a single well-formed item
+    pub(crate) fn from_slice(data: &[u8]) -> Result<Value> {
+        let mut decoder = Decoder { data };
+        let value = decoder.read_value(0)?;
+        if !decoder.data.is_empty() {
+            return Err(QuantCryptError::InvalidCbor);
+        }
+        Ok(value)
+    }
+
+    /// Get the integer of an `Int` item
+    pub(crate) fn as_int(&self) -> Result<i64> {
+        match self {
+            Value::Int(n) => Ok(*n),
+            _ => Err(QuantCryptError::InvalidCbor),
+        }
+    }
+
+    /// Get the bytes of a `Bytes` item
+    pub(crate) fn as_bytes(&self) -> Result<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Ok(bytes),
+            _ => Err(QuantCryptError::InvalidCbor),
+        }
+    }
+
+    /// Get the text of a `Text` item
+    pub(crate) fn as_text(&self) -> Result<&str> {
+        match self {
+            Value::Text(text) => Ok(text),
+            _ => Err(QuantCryptError::InvalidCbor),
+        }
+    }
+
+    /// Get the items of an `Array` item
+    pub(crate) fn as_array(&self) -> Result<&[Value]> {
+        match self {
+            Value::Array(items) => Ok(items),
+            _ => Err(QuantCryptError::InvalidCbor),
+        }
+    }
+}
+
+/// Encode an artifact of an algorithm as `[111(oid), bytes]`
+///
+/// This is the encoding of artifacts without a COSE representation.
+///
+/// # Arguments
+///
+/// * `oid` - The OID of the algorithm
+/// * `bytes` - The artifact
+///
+/// # Returns
+///
+/// The CBOR encoding
+pub(crate) fn encode_with_oid(oid: &str, bytes: &[u8]) -> Result<Vec<u8>> {
+    let oid = ObjectIdentifier::new(oid).map_err(|_| QuantCryptError::InvalidOid)?;
+    Ok(Value::Array(vec![
+        Value::Tag(OID_TAG, Box::new(Value::Bytes(oid.as_bytes().to_vec()))),
+        Value::Bytes(bytes.to_vec()),
+    ])
+    .to_vec())
+}
+
+/// Decode an artifact encoded with `encode_with_oid`
+///
+/// # Arguments
+///
+/// * `data` - The CBOR encoding
+///
+/// # Returns
+///
+/// A tuple containing the OID of the algorithm and the artifact (oid, bytes)
+pub(crate) fn decode_with_oid(data: &[u8]) -> Result<(String, Vec<u8>)> {
+    let value = Value::from_slice(data)?;
+    let [Value::Tag(OID_TAG, oid), bytes] = value.as_array()? else {
+        return Err(QuantCryptError::InvalidCbor);
+    };
+    let oid =
+        ObjectIdentifier::from_bytes(oid.as_bytes()?).map_err(|_| QuantCryptError::InvalidCbor)?;
+    Ok((oid.to_string(), bytes.as_bytes()?.to_vec()))
+}
+
+/// Get the protected header of a COSE message signed with an algorithm
+pub(crate) fn cose_protected_header(alg: i64) -> Vec<u8> {
+    Value::Map(vec![(Value::Int(1), Value::Int(alg))]).to_vec()
+}
+
+/// Get the `Sig_structure` of a COSE_Sign1 message without external data (RFC 9052, section 4.4)
+pub(crate) fn cose_sign1_to_be_signed(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+    Value::Array(vec![
+        Value::Text("Signature1".to_string()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(Vec::new()),
+        Value::Bytes(payload.to_vec()),
+    ])
+    .to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cbor_round_trip() {
+        // Examples from appendix A of RFC 8949
+        let vectors: [(Value, &str); 8] = [
+            (Value::Int(10), "0a"),
+            (Value::Int(1000000), "1a000f4240"),
+            (Value::Int(-1000), "3903e7"),
+            (Value::Bytes(vec![1, 2, 3, 4]), "4401020304"),
+            (Value::Text("IETF".to_string()), "6449455446"),
+            (
+                Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]),
+                "83010203",
+            ),
+            (Value::Map(vec![(Value::Int(1), Value::Int(2))]), "a10102"),
+            (
+                Value::Tag(1, Box::new(Value::Int(1363896240))),
+                "c11a514b67b0",
+            ),
+        ];
+        for (value, hex_encoding) in vectors {
+            let encoding = hex::decode(hex_encoding).unwrap();
+            assert_eq!(value.to_vec(), encoding);
+            assert_eq!(Value::from_slice(&encoding).unwrap(), value);
+        }
+
+        // Non-shortest forms, indefinite lengths, truncated and trailing data
+        for bad in ["1800", "5f4101ff", "4401", "0a0a", "fb3ff0000000000000"] {
+            assert_eq!(
+                Value::from_slice(&hex::decode(bad).unwrap()),
+                Err(QuantCryptError::InvalidCbor)
+            );
+        }
+        // An array claiming more items than the input holds
+        assert!(Value::from_slice(&[0x9b, 0xff, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// The default clock-skew tolerance, in seconds
+const DEFAULT_CLOCK_SKEW_SECS: u64 = 5 * 60;
+
+/// The clock-skew tolerance applied to all time checks, in seconds
+static CLOCK_SKEW_SECS: AtomicU64 = AtomicU64::new(DEFAULT_CLOCK_SKEW_SECS);
+
+/// Set the clock-skew tolerance applied to all time checks
+///
+/// Certificate validity periods, the thisUpdate / nextUpdate of CRLs and OCSP
+/// responses, and CMS signing times are accepted if they are off by at most
+/// this much, so that freshly issued objects don't fail on hosts whose clock is
+/// slightly behind. The default is 5 minutes. The setting is process-wide.
+///
+/// # Arguments
+///
+/// * `tolerance` - The tolerance, `Duration::ZERO` for strict comparisons
+pub fn set_clock_skew_tolerance(tolerance: Duration) {
+    CLOCK_SKEW_SECS.store(tolerance.as_secs(), Ordering::Relaxed);
+}
+
+/// Get the clock-skew tolerance applied to all time checks
+///
+/// # Returns
+///
+/// The tolerance, 5 minutes unless changed with `set_clock_skew_tolerance`
+pub fn get_clock_skew_tolerance() -> Duration {
+    Duration::from_secs(CLOCK_SKEW_SECS.load(Ordering::Relaxed))
+}
+
+/// Check if the current time falls within a period, allowing for clock skew
+///
+/// # Arguments
+///
+/// * `not_before` - The start of the period
+/// * `not_after` - The end of the period, or None if it is open-ended
+/// * `tolerance` - The clock-skew tolerance
+///
+/// # Returns
+///
+/// True if `not_before - tolerance <= now <= not_after + tolerance`
+pub(crate) fn is_within(
+    not_before: DateTime<Utc>,
+    not_after: Option<DateTime<Utc>>,
+    tolerance: Duration,
+) -> bool {
+    let now = Utc::now();
+    let tolerance = chrono::Duration::from_std(tolerance).unwrap_or(chrono::Duration::zero());
+    not_before - tolerance <= now
+        && not_after.map_or(true, |not_after| now <= not_after + tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_within() {
+        let now = Utc::now();
+        let minute = chrono::Duration::minutes(1);
+        let tolerance = Duration::from_secs(120);
+
+        assert!(is_within(now - minute, Some(now + minute), Duration::ZERO));
+        // Not yet valid, or expired, by a minute
+        assert!(!is_within(now + minute, None, Duration::ZERO));
+        assert!(is_within(now + minute, None, tolerance));
+        assert!(!is_within(
+            now - minute * 2,
+            Some(now - minute),
+            Duration::ZERO
+        ));
+        assert!(is_within(now - minute * 2, Some(now - minute), tolerance));
+        // Beyond the tolerance
+        assert!(!is_within(now + minute * 3, None, tolerance));
+
+        assert_eq!(
+            get_clock_skew_tolerance(),
+            Duration::from_secs(DEFAULT_CLOCK_SKEW_SECS)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use base64ct::{Base64, Encoding};
+use zeroize::Zeroizing;
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+// The codecs are the ones of the base64ct and base16ct crates, which never
+// branch on or index a table with the encoded data. Only the length of the
+// input and the position of padding and whitespace, which don't depend on the
+// secret, affect the control flow.
+
+/// Remove the ASCII whitespace from an encoded string
+fn strip_whitespace(encoded: &str) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(
+        encoded
+            .bytes()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect(),
+    )
+}
+
+/// Encode bytes as padded standard base64 (RFC 4648) in constant time
+///
+/// # Arguments
+///
+/// * `data` - The bytes to encode
+///
+/// # Returns
+///
+/// The base64 string, zeroized when dropped
+pub fn encode_base64_ct(data: &[u8]) -> Zeroizing<String> {
+    Zeroizing::new(Base64::encode_string(data))
+}
+
+/// Decode padded standard base64 (RFC 4648) in constant time
+///
+/// ASCII whitespace, such as the line breaks of a PEM body, is ignored. The
+/// padding must be present and the unused bits of the last group must be
+/// zero, so every key has exactly one encoding.
+///
+/// # Arguments
+///
+/// * `encoded` - The base64 string
+///
+/// # Returns
+///
+/// The decoded bytes, zeroized when dropped
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidEncoding` will be returned if the string is not valid base64
+///
+/// # Example
+/// ```
+/// use quantcrypt::encoding::{decode_base64_ct, encode_base64_ct};
+///
+/// let encoded = encode_base64_ct(b"secret");
+/// assert_eq!(encoded.as_str(), "c2VjcmV0");
+/// assert_eq!(decode_base64_ct(&encoded).unwrap().as_slice(), b"secret");
+/// assert!(decode_base64_ct("c2VjcmV0=").is_err());
+/// ```
+pub fn decode_base64_ct(encoded: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let chars = strip_whitespace(encoded);
+    let mut out = Zeroizing::new(vec![0u8; chars.len() / 4 * 3]);
+    let len = Base64::decode(&chars, &mut out)
+        .map_err(|_| QuantCryptError::InvalidEncoding)?
+        .len();
+    out.truncate(len);
+    Ok(out)
+}
+
+/// Encode bytes as lowercase hex in constant time
+///
+/// # Arguments
+///
+/// * `data` - The bytes to encode
+///
+/// # Returns
+///
+/// The hex string, zeroized when dropped
+pub fn encode_hex_ct(data: &[u8]) -> Zeroizing<String> {
+    Zeroizing::new(base16ct::lower::encode_string(data))
+}
+
+/// Decode hex, in either case, in constant time
+///
+/// ASCII whitespace is ignored.
+///
+/// # Arguments
+///
+/// * `encoded` - The hex string
+///
+/// # Returns
+///
+/// The decoded bytes, zeroized when dropped
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidEncoding` will be returned if the string is not valid hex
+pub fn decode_hex_ct(encoded: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let chars = strip_whitespace(encoded);
+    let mut out = Zeroizing::new(vec![0u8; chars.len() / 2]);
+    base16ct::mixed::decode(&chars, &mut out).map_err(|_| QuantCryptError::InvalidEncoding)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    #[test]
+    fn test_ct_codecs() {
+        // The whole alphabets, against the variable time implementations
+        let data: Vec<u8> = (0..=255).collect();
+        for len in [0, 1, 2, 3, 4, 5, 256] {
+            let data = &data[..len];
+            let encoded = encode_base64_ct(data);
+            assert_eq!(encoded.as_str(), STANDARD.encode(data));
+            assert_eq!(decode_base64_ct(&encoded).unwrap().as_slice(), data);
+            let encoded = encode_hex_ct(data);
+            assert_eq!(encoded.as_str(), hex::encode(data));
+            assert_eq!(decode_hex_ct(&encoded).unwrap().as_slice(), data);
+            let upper = encoded.to_uppercase();
+            assert_eq!(decode_hex_ct(&upper).unwrap().as_slice(), data);
+        }
+        assert_eq!(
+            decode_base64_ct("c2Vj\ncmV0\n").unwrap().as_slice(),
+            b"secret"
+        );
+
+        // Every character outside of the alphabets is rejected
+        for c in 0..=255u8 {
+            let base64_valid = c.is_ascii_alphanumeric() || c == b'+' || c == b'/';
+            let s = [b'A', b'A', b'A', c];
+            if let Ok(s) = std::str::from_utf8(&s) {
+                let padding = c == b'=';
+                assert_eq!(
+                    decode_base64_ct(s).is_ok(),
+                    base64_valid || padding,
+                    "{}",
+                    c
+                );
+            }
+            let s = [b'0', c];
+            if let Ok(s) = std::str::from_utf8(&s) {
+                assert_eq!(decode_hex_ct(s).is_ok(), c.is_ascii_hexdigit(), "{}", c);
+            }
+        }
+
+        // Missing padding, non-zero unused bits and misplaced padding
+        for bad in ["c2VjcmV", "QR==", "QQ=A", "Q===", "=AAA"] {
+            assert_eq!(
+                decode_base64_ct(bad),
+                Err(QuantCryptError::InvalidEncoding),
+                "{}",
+                bad
+            );
+        }
+        assert!(decode_hex_ct("abc").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use subtle::{Choice, ConstantTimeEq};
+
+// Thin wrappers over the subtle crate, which the rest of the crate uses for
+// its constant-time selections too. Only the lengths of the inputs, which
+// aren't secret, affect the control flow.
+
+/// Compare two byte strings in constant time
+///
+/// # Arguments
+///
+/// * `a` - The first byte string
+/// * `b` - The second byte string
+///
+/// # Returns
+///
+/// True if the byte strings are equal. Strings of different lengths are
+/// unequal, and the time taken only depends on the lengths.
+///
+/// # Example
+/// ```
+/// use quantcrypt::constant_time::ct_eq;
+///
+/// assert!(ct_eq(b"secret", b"secret"));
+/// assert!(!ct_eq(b"secret", b"secreT"));
+/// assert!(!ct_eq(b"secret", b"secrets"));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// Check in constant time if every byte of a byte string is zero
+///
+/// # Arguments
+///
+/// * `a` - The byte string
+///
+/// # Returns
+///
+/// True if every byte is zero, including for an empty string
+pub fn ct_is_zero(a: &[u8]) -> bool {
+    a.iter()
+        .fold(Choice::from(1), |acc, x| acc & x.ct_eq(&0))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(&[], &[]));
+        assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!ct_eq(&[0x80, 2, 3], &[0, 2, 3]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2]));
+
+        assert!(ct_is_zero(&[]));
+        assert!(ct_is_zero(&[0; 32]));
+        for i in 0..8 {
+            let mut a = [0u8; 4];
+            a[2] = 1 << i;
+            assert!(!ct_is_zero(&a));
+        }
+    }
+}
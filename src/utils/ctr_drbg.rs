@@ -0,0 +1,206 @@
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use rand_core::RngCore;
+use zeroize::Zeroize;
+
+/// The length of the entropy input and personalization string in bytes
+pub const CTR_DRBG_SEED_LEN: usize = 48;
+
+/// The AES-256 CTR-DRBG (without derivation function) used by the NIST PQC KAT generators
+///
+/// This is a bit-for-bit port of `randombytes_init` / `randombytes` from the NIST
+/// `rng.c`. Each call to `fill_bytes` corresponds to one call to `randombytes`, and the
+/// internal state is updated after every call. This means the output depends on how
+/// the requested bytes are split into calls, exactly as it does in the reference code.
+///
+/// This RNG is deterministic and only intended for reproducing known answer tests.
+/// Never use it to generate real keys. It isn't a `CryptoRng`, and is only exported
+/// with the `test-utils` feature.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "test-utils")]
+/// # {
+/// use quantcrypt::kats::CtrDrbg;
+///
+/// let entropy_input: [u8; 48] = core::array::from_fn(|i| i as u8);
+/// let mut drbg = CtrDrbg::new(&entropy_input, None);
+/// let mut seed = [0u8; 48];
+/// drbg.randombytes(&mut seed);
+/// assert_eq!(
+///     hex::encode_upper(seed),
+///     "061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1"
+/// );
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CtrDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+    reseed_counter: u64,
+}
+
+impl CtrDrbg {
+    /// Create a new DRBG, equivalent to `randombytes_init(entropy_input, personalization, 256)`
+    ///
+    /// # Arguments
+    ///
+    /// * `entropy_input` - The 48 byte seed
+    /// * `personalization` - An optional 48 byte personalization string
+    ///
+    /// # Returns
+    ///
+    /// A new DRBG
+    pub fn new(
+        entropy_input: &[u8; CTR_DRBG_SEED_LEN],
+        personalization: Option<&[u8; CTR_DRBG_SEED_LEN]>,
+    ) -> CtrDrbg {
+        let mut seed_material = *entropy_input;
+        if let Some(personalization) = personalization {
+            for (s, p) in seed_material.iter_mut().zip(personalization.iter()) {
+                *s ^= p;
+            }
+        }
+
+        let mut drbg = CtrDrbg {
+            key: [0u8; 32],
+            v: [0u8; 16],
+            reseed_counter: 1,
+        };
+        drbg.update(Some(&seed_material));
+        seed_material.zeroize();
+        drbg
+    }
+
+    /// Generate random bytes, equivalent to `randombytes(x, xlen)`
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The buffer to fill
+    pub fn randombytes(&mut self, out: &mut [u8]) {
+        for chunk in out.chunks_mut(16) {
+            self.increment_v();
+            let block = self.aes256_ecb(&self.v);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(None);
+        self.reseed_counter += 1;
+    }
+
+    /// Generate random bytes into a new vector with a single call to `randombytes`
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of bytes to generate
+    ///
+    /// # Returns
+    ///
+    /// The random bytes
+    pub fn randombytes_vec(&mut self, len: usize) -> Vec<u8> {
+        let mut out = vec![0u8; len];
+        self.randombytes(&mut out);
+        out
+    }
+
+    fn increment_v(&mut self) {
+        for b in self.v.iter_mut().rev() {
+            if *b == 0xff {
+                *b = 0x00;
+            } else {
+                *b += 1;
+                break;
+            }
+        }
+    }
+
+    fn aes256_ecb(&self, block: &[u8; 16]) -> [u8; 16] {
+        let cipher = Aes256::new(&self.key.into());
+        let mut out = (*block).into();
+        cipher.encrypt_block(&mut out);
+        out.into()
+    }
+
+    fn update(&mut self, provided_data: Option<&[u8; CTR_DRBG_SEED_LEN]>) {
+        let mut temp = [0u8; CTR_DRBG_SEED_LEN];
+        for i in 0..3 {
+            self.increment_v();
+            let block = self.aes256_ecb(&self.v);
+            temp[16 * i..16 * (i + 1)].copy_from_slice(&block);
+        }
+        if let Some(provided_data) = provided_data {
+            for (t, p) in temp.iter_mut().zip(provided_data.iter()) {
+                *t ^= p;
+            }
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+        temp.zeroize();
+    }
+}
+
+impl RngCore for CtrDrbg {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.randombytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.randombytes(dest);
+        Ok(())
+    }
+}
+
+impl Drop for CtrDrbg {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.v.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nist_kat_seeds() {
+        // The seeds of the first two records of every NIST PQC KAT file
+        let entropy_input: [u8; 48] = core::array::from_fn(|i| i as u8);
+        let mut drbg = CtrDrbg::new(&entropy_input, None);
+
+        let seed0 = drbg.randombytes_vec(48);
+        assert_eq!(
+            hex::encode_upper(seed0),
+            "061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1"
+        );
+        let seed1 = drbg.randombytes_vec(48);
+        assert_eq!(
+            hex::encode_upper(seed1),
+            "D81C4D8D734FCBFBEADE3D3F8A039FAA2A2C9957E835AD55B22E75BF57BB556AC81ADDE6AEEB4A5A875C3BFCADFA958F"
+        );
+    }
+
+    #[test]
+    fn test_call_boundaries_matter() {
+        let entropy_input = [7u8; 48];
+        let mut a = CtrDrbg::new(&entropy_input, None);
+        let mut b = CtrDrbg::new(&entropy_input, None);
+
+        let one_call = a.randombytes_vec(64);
+        let mut two_calls = b.randombytes_vec(32);
+        two_calls.extend(b.randombytes_vec(32));
+
+        assert_eq!(one_call[..32], two_calls[..32]);
+        assert_ne!(one_call[32..], two_calls[32..]);
+
+        let personalization = [1u8; 48];
+        let mut c = CtrDrbg::new(&entropy_input, Some(&personalization));
+        assert_ne!(c.randombytes_vec(64), one_call);
+    }
+}
@@ -0,0 +1,107 @@
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use rand_core::CryptoRngCore;
+
+use crate::utils::entropy::get_key_gen_rng;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The length of an X25519 key in bytes
+const X25519_KEY_LEN: usize = 32;
+
+/// Compute the X25519 public key of a secret key in pure Rust
+///
+/// # Arguments
+///
+/// * `sk` - The 32 byte secret key
+///
+/// # Returns
+///
+/// The 32 byte public key
+pub fn x25519_public_key(sk: &[u8]) -> Result<Vec<u8>> {
+    let sk: [u8; X25519_KEY_LEN] = sk
+        .try_into()
+        .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    Ok(MontgomeryPoint::mul_base_clamped(sk).to_bytes().to_vec())
+}
+
+/// Generate an X25519 key pair in pure Rust
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator
+///
+/// # Returns
+///
+/// A tuple containing the public and secret keys (pk, sk)
+pub fn x25519_key_gen(rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut sk = [0u8; X25519_KEY_LEN];
+    rng.fill_bytes(&mut sk);
+    Ok((x25519_public_key(&sk)?, sk.to_vec()))
+}
+
+/// Compute the X25519 shared secret of a secret key and a public key in pure Rust
+///
+/// # Arguments
+///
+/// * `sk` - The 32 byte secret key
+/// * `pk` - The 32 byte public key of the peer
+///
+/// # Returns
+///
+/// The 32 byte shared secret
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidPublicKey` if the public key is malformed or a low order point,
+/// which OpenSSL rejects as well
+pub fn x25519_derive(sk: &[u8], pk: &[u8]) -> Result<Vec<u8>> {
+    let sk: [u8; X25519_KEY_LEN] = sk
+        .try_into()
+        .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    let pk: [u8; X25519_KEY_LEN] = pk
+        .try_into()
+        .map_err(|_| QuantCryptError::InvalidPublicKey)?;
+    let ss = MontgomeryPoint(pk).mul_clamped(sk).to_bytes();
+    if ss == [0u8; X25519_KEY_LEN] {
+        return Err(QuantCryptError::InvalidPublicKey);
+    }
+    Ok(ss.to_vec())
+}
+
+/// Encapsulate to an X25519 public key in pure Rust, with an ephemeral key drawn from the
+/// health tested system entropy
+///
+/// # Arguments
+///
+/// * `pk` - The public key to encapsulate to
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and ciphertext (ss, ct)
+pub fn x25519_encap(pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    let (ct, esk) = x25519_key_gen(&mut get_key_gen_rng()?)?;
+    Ok((x25519_derive(&esk, pk)?, ct))
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::utils::openssl_utils::{decaps_pkey_based, get_pk_from_sk_pkey_based};
+    use openssl::pkey::Id;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_x25519_matches_openssl() {
+        let (pk, sk) = x25519_key_gen(&mut OsRng).unwrap();
+        assert_eq!(pk, get_pk_from_sk_pkey_based(&sk, Id::X25519).unwrap());
+
+        let (ss, ct) = x25519_encap(&pk).unwrap();
+        assert_eq!(ss, x25519_derive(&sk, &ct).unwrap());
+        assert_eq!(ss, decaps_pkey_based(&sk, &ct, Id::X25519).unwrap());
+
+        // The low order points are rejected
+        assert!(x25519_derive(&sk, &[0u8; 32]).is_err());
+        assert!(x25519_derive(&sk, &[1u8; 31]).is_err());
+    }
+}
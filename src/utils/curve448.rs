@@ -0,0 +1,428 @@
+use std::sync::OnceLock;
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+#[cfg(feature = "openssl")]
+use openssl::pkey::{Id, PKey};
+use rand_core::CryptoRngCore;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+use crate::utils::entropy::get_key_gen_rng;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The length of an X448 key in bytes
+const X448_KEY_LEN: usize = 56;
+
+/// Check if the linked OpenSSL supports an algorithm
+///
+/// Older distribution builds of OpenSSL lack X448 and Ed448. The check is
+/// performed once per algorithm by trying to generate a key.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the algorithm
+///
+/// # Returns
+///
+/// False for X448 or Ed448 if OpenSSL cannot generate keys for them, true otherwise
+#[cfg(feature = "openssl")]
+pub fn openssl_supports(id: Id) -> bool {
+    static X448: OnceLock<bool> = OnceLock::new();
+    static ED448: OnceLock<bool> = OnceLock::new();
+    match id {
+        Id::X448 => *X448.get_or_init(|| PKey::generate_x448().is_ok()),
+        Id::ED448 => *ED448.get_or_init(|| PKey::generate_ed448().is_ok()),
+        _ => true,
+    }
+}
+
+/// Compute the X448 public key of a secret key in pure Rust
+///
+/// # Arguments
+///
+/// * `sk` - The 56 byte secret key
+///
+/// # Returns
+///
+/// The 56 byte public key
+pub fn x448_public_key(sk: &[u8]) -> Result<Vec<u8>> {
+    let sk: [u8; X448_KEY_LEN] = sk
+        .try_into()
+        .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    x448::x448(sk, x448::X448_BASEPOINT_BYTES)
+        .map(|pk| pk.to_vec())
+        .ok_or(QuantCryptError::InvalidPrivateKey)
+}
+
+/// Generate an X448 key pair in pure Rust
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator
+///
+/// # Returns
+///
+/// A tuple containing the public and secret keys (pk, sk)
+pub fn x448_key_gen(rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut sk = [0u8; X448_KEY_LEN];
+    rng.fill_bytes(&mut sk);
+    Ok((x448_public_key(&sk)?, sk.to_vec()))
+}
+
+/// Compute the X448 shared secret of a secret key and a public key in pure Rust
+///
+/// # Arguments
+///
+/// * `sk` - The 56 byte secret key
+/// * `pk` - The 56 byte public key of the peer
+///
+/// # Returns
+///
+/// The 56 byte shared secret
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidPublicKey` if the public key is malformed or a low order point
+pub fn x448_derive(sk: &[u8], pk: &[u8]) -> Result<Vec<u8>> {
+    let sk: [u8; X448_KEY_LEN] = sk
+        .try_into()
+        .map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    let pk: [u8; X448_KEY_LEN] = pk
+        .try_into()
+        .map_err(|_| QuantCryptError::InvalidPublicKey)?;
+    x448::x448(sk, pk)
+        .map(|ss| ss.to_vec())
+        .ok_or(QuantCryptError::InvalidPublicKey)
+}
+
+/// Encapsulate to an X448 public key in pure Rust, with an ephemeral key drawn from the
+/// health tested system entropy
+///
+/// # Arguments
+///
+/// * `pk` - The public key to encapsulate to
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and ciphertext (ss, ct)
+pub fn x448_encap(pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    let (ct, esk) = x448_key_gen(&mut get_key_gen_rng()?)?;
+    Ok((x448_derive(&esk, pk)?, ct))
+}
+
+/// Compute the Ed448 public key of a secret key in pure Rust
+///
+/// # Arguments
+///
+/// * `sk` - The 57 byte secret key
+///
+/// # Returns
+///
+/// The 57 byte public key
+pub fn ed448_public_key(sk: &[u8]) -> Result<Vec<u8>> {
+    let sk =
+        ed448_rust::PrivateKey::try_from(sk).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    Ok(ed448_rust::PublicKey::from(&sk).as_byte().to_vec())
+}
+
+/// Generate an Ed448 key pair in pure Rust
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator
+///
+/// # Returns
+///
+/// A tuple containing the public and secret keys (pk, sk)
+pub fn ed448_key_gen(rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+    let sk = ed448_rust::PrivateKey::new(rng);
+    let pk = ed448_rust::PublicKey::from(&sk);
+    Ok((pk.as_byte().to_vec(), sk.as_bytes().to_vec()))
+}
+
+/// Sign a message with Ed448 (pure, without context) in pure Rust
+///
+/// # Arguments
+///
+/// * `sk` - The 57 byte secret key
+/// * `msg` - The message
+///
+/// # Returns
+///
+/// The 114 byte signature
+pub fn ed448_sign(sk: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+    let sk =
+        ed448_rust::PrivateKey::try_from(sk).map_err(|_| QuantCryptError::InvalidPrivateKey)?;
+    sk.sign(msg, None)
+        .map(|sig| sig.to_vec())
+        .map_err(|_| QuantCryptError::SignatureFailed)
+}
+
+/// Verify an Ed448 signature (pure, without context) in pure Rust
+///
+/// The verification follows RFC 8032 § 5.2.7 on top of `num-bigint`, as
+/// `ed448-rust` cannot load encoded public keys. Only public values are
+/// involved, so it does not need to run in constant time.
+///
+/// # Arguments
+///
+/// * `pk` - The 57 byte public key
+/// * `msg` - The message
+/// * `signature` - The signature
+///
+/// # Returns
+///
+/// True if the signature is valid, false otherwise
+pub fn ed448_verify(pk: &[u8], msg: &[u8], signature: &[u8]) -> Result<bool> {
+    let a = EdwardsPoint::decode(pk).ok_or(QuantCryptError::InvalidPublicKey)?;
+    if signature.len() != 2 * ED448_KEY_LEN {
+        return Ok(false);
+    }
+    let (r, s) = signature.split_at(ED448_KEY_LEN);
+    let s = BigUint::from_bytes_le(s);
+    if s >= *ED448_ORDER {
+        return Ok(false);
+    }
+
+    // h = SHAKE256(dom4(0, "") || R || A || M, 114) mod L
+    let mut hasher = Shake256::default();
+    hasher.update(b"SigEd448\x00\x00");
+    hasher.update(r);
+    hasher.update(pk);
+    hasher.update(msg);
+    let mut h = [0u8; 2 * ED448_KEY_LEN];
+    hasher.finalize_xof().read(&mut h);
+    let h = BigUint::from_bytes_le(&h) % &*ED448_ORDER;
+
+    // [S]B = R + [h]A, checked as [S]B + [h](-A) == R
+    let check = EdwardsPoint::base().mul(&s).add(&a.negate().mul(&h));
+    Ok(check.encode().as_slice() == r)
+}
+
+/// The length of an Ed448 key in bytes
+const ED448_KEY_LEN: usize = 57;
+
+/// The field prime of edwards448, 2^448 - 2^224 - 1
+static ED448_P: LazyBigUint =
+    LazyBigUint::new(|| (BigUint::one() << 448u32) - (BigUint::one() << 224u32) - BigUint::one());
+
+/// The curve constant d of edwards448, -39081
+static ED448_D: LazyBigUint = LazyBigUint::new(|| &*ED448_P - BigUint::from(39081u32));
+
+/// The order of the base point of edwards448
+static ED448_ORDER: LazyBigUint = LazyBigUint::new(|| {
+    (BigUint::one() << 446u32)
+        - BigUint::parse_bytes(
+            b"13818066809895115352007386748515426880336692474882178609894547503885",
+            10,
+        )
+        .unwrap()
+});
+
+/// A `BigUint` constant computed on first use
+struct LazyBigUint {
+    cell: OnceLock<BigUint>,
+    init: fn() -> BigUint,
+}
+
+impl LazyBigUint {
+    const fn new(init: fn() -> BigUint) -> Self {
+        LazyBigUint {
+            cell: OnceLock::new(),
+            init,
+        }
+    }
+}
+
+impl std::ops::Deref for LazyBigUint {
+    type Target = BigUint;
+
+    fn deref(&self) -> &BigUint {
+        self.cell.get_or_init(self.init)
+    }
+}
+
+/// A point on edwards448 in projective coordinates (X : Y : Z)
+#[derive(Clone)]
+struct EdwardsPoint {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+}
+
+impl EdwardsPoint {
+    /// The base point B (RFC 8032 § 5.2)
+    fn base() -> Self {
+        let coordinate = |c: &[u8]| BigUint::parse_bytes(c, 10).unwrap();
+        EdwardsPoint {
+            x: coordinate(b"224580040295924300187604334099896036246789641632564134246125461686950415467406032909029192869357953282578032075146446173674602635247710"),
+            y: coordinate(b"298819210078481492676017930443930673437544040154080242095928241372331506189835876003536878655418784733982303233503462500531545062832660"),
+            z: BigUint::one(),
+        }
+    }
+
+    /// The neutral element
+    fn identity() -> Self {
+        EdwardsPoint {
+            x: BigUint::zero(),
+            y: BigUint::one(),
+            z: BigUint::one(),
+        }
+    }
+
+    /// Decode a point (RFC 8032 § 5.2.3)
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let p = &*ED448_P;
+        if bytes.len() != ED448_KEY_LEN || bytes[56] & 0x7f != 0 {
+            return None;
+        }
+        let x_0 = bytes[56] >> 7;
+        let y = BigUint::from_bytes_le(&bytes[..56]);
+        if y >= *p {
+            return None;
+        }
+
+        // x^2 = (y^2 - 1) / (d y^2 - 1)
+        let yy = &y * &y % p;
+        let u = sub(&yy, &BigUint::one());
+        let v = sub(&(&*ED448_D * &yy % p), &BigUint::one());
+        // x = u^3 v (u^5 v^3)^((p - 3) / 4)
+        let u3v = u.modpow(&BigUint::from(3u32), p) * &v % p;
+        let u5v3 = u.modpow(&BigUint::from(5u32), p) * v.modpow(&BigUint::from(3u32), p) % p;
+        let mut x = u3v * u5v3.modpow(&((p - BigUint::from(3u32)) >> 2u32), p) % p;
+        if &v * &x % p * &x % p != u {
+            return None;
+        }
+        if x.is_zero() && x_0 == 1 {
+            return None;
+        }
+        if (&x % 2u32).to_u8() != Some(x_0) {
+            x = p - x;
+        }
+        Some(EdwardsPoint {
+            x,
+            y,
+            z: BigUint::one(),
+        })
+    }
+
+    /// Encode a point (RFC 8032 § 5.2.2)
+    fn encode(&self) -> [u8; ED448_KEY_LEN] {
+        let p = &*ED448_P;
+        let z_inv = self.z.modpow(&(p - BigUint::from(2u32)), p);
+        let x = &self.x * &z_inv % p;
+        let y = &self.y * &z_inv % p;
+
+        let mut bytes = [0u8; ED448_KEY_LEN];
+        let y = y.to_bytes_le();
+        bytes[..y.len()].copy_from_slice(&y);
+        if x.bit(0) {
+            bytes[56] |= 0x80;
+        }
+        bytes
+    }
+
+    /// Negate a point
+    fn negate(&self) -> Self {
+        EdwardsPoint {
+            x: sub(&BigUint::zero(), &self.x),
+            y: self.y.clone(),
+            z: self.z.clone(),
+        }
+    }
+
+    /// Add two points (RFC 8032 § 5.2.4). The formula is complete, so it also doubles.
+    fn add(&self, other: &Self) -> Self {
+        let p = &*ED448_P;
+        let a = &self.z * &other.z % p;
+        let b = &a * &a % p;
+        let c = &self.x * &other.x % p;
+        let d = &self.y * &other.y % p;
+        let e = &*ED448_D * &c % p * &d % p;
+        let f = sub(&b, &e);
+        let g = (&b + &e) % p;
+        let h = (&self.x + &self.y) * (&other.x + &other.y) % p;
+        EdwardsPoint {
+            x: &a * &f % p * sub(&sub(&h, &c), &d) % p,
+            y: &a * &g % p * sub(&d, &c) % p,
+            z: f * g % p,
+        }
+    }
+
+    /// Multiply a point by a scalar
+    fn mul(&self, scalar: &BigUint) -> Self {
+        let mut result = EdwardsPoint::identity();
+        for i in (0..scalar.bits()).rev() {
+            result = result.add(&result);
+            if scalar.bit(i) {
+                result = result.add(self);
+            }
+        }
+        result
+    }
+}
+
+/// Subtract modulo the field prime
+fn sub(a: &BigUint, b: &BigUint) -> BigUint {
+    let p = &*ED448_P;
+    (a + p - (b % p)) % p
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::utils::openssl_utils::{
+        decaps_pkey_based, encaps_pkey_based, get_key_pair_pkey_based, get_pk_from_sk_pkey_based,
+        sign_pkey_based, verify_pkey_based,
+    };
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_x448_matches_openssl() {
+        assert!(openssl_supports(Id::X448));
+
+        let (pk, sk) = x448_key_gen(&mut OsRng).unwrap();
+        assert_eq!(pk, get_pk_from_sk_pkey_based(&sk, Id::X448).unwrap());
+
+        // Encapsulate with one implementation, decapsulate with the other
+        let (ss, ct) = x448_encap(&pk).unwrap();
+        assert_eq!(ss, decaps_pkey_based(&sk, &ct, Id::X448).unwrap());
+        let (pk, sk) = get_key_pair_pkey_based(Id::X448).unwrap();
+        let (ss, ct) = encaps_pkey_based(&pk, Id::X448).unwrap();
+        assert_eq!(ss, x448_derive(&sk, &ct).unwrap());
+
+        // Low order points are rejected
+        assert_eq!(
+            x448_derive(&sk, &[0u8; X448_KEY_LEN]),
+            Err(QuantCryptError::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn test_ed448_matches_openssl() {
+        assert!(openssl_supports(Id::ED448));
+
+        let (pk, sk) = ed448_key_gen(&mut OsRng).unwrap();
+        assert_eq!(pk, get_pk_from_sk_pkey_based(&sk, Id::ED448).unwrap());
+        assert_eq!(pk, ed448_public_key(&sk).unwrap());
+
+        // Ed448 is deterministic, so both implementations produce the same signature
+        let msg = b"message";
+        let sig = ed448_sign(&sk, msg).unwrap();
+        assert_eq!(sig, sign_pkey_based(Id::ED448, &sk, msg).unwrap());
+        assert!(verify_pkey_based(Id::ED448, &pk, msg, &sig).unwrap());
+        assert!(ed448_verify(&pk, msg, &sig).unwrap());
+        assert!(!ed448_verify(&pk, b"other", &sig).unwrap());
+
+        // Signatures made by OpenSSL verify as well
+        let (pk, sk) = get_key_pair_pkey_based(Id::ED448).unwrap();
+        let sig = sign_pkey_based(Id::ED448, &sk, msg).unwrap();
+        assert!(ed448_verify(&pk, msg, &sig).unwrap());
+        let mut tampered = sig.clone();
+        tampered[0] ^= 1;
+        assert!(!ed448_verify(&pk, msg, &tampered).unwrap());
+    }
+}
@@ -0,0 +1,312 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use crate::dsa::common::config::oids::Oid as DsaOid;
+use crate::dsa::common::dsa_type::DsaType;
+use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+use crate::kem::common::config::oids::Oid as KemOid;
+use crate::kem::common::kem_type::KemType;
+
+/// A callback invoked when a deprecated algorithm is selected
+type DeprecationCallback = Arc<dyn Fn(&DeprecationWarning) + Send + Sync>;
+
+/// The callback receiving the deprecation warnings of the process
+static DEPRECATION_CALLBACK: RwLock<Option<DeprecationCallback>> = RwLock::new(None);
+
+/// The year after which NIST IR 8547 deprecates algorithms with 112 bits of security
+const RSA_2048_SUNSET: u16 = 2030;
+
+/// The year after which the pre-standard algorithms of the `legacy` feature are dropped
+#[cfg(feature = "legacy")]
+const LEGACY_SUNSET: u16 = 2027;
+
+/// Why an algorithm is deprecated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeprecationStatus {
+    /// A pre-standard draft, superseded by the final standard
+    LegacyDraft,
+    /// Only existing signatures can be verified, nothing new can be produced
+    VerifyOnly,
+    /// Standardized, but too weak to use past the sunset year
+    Deprecated,
+}
+
+impl fmt::Display for DeprecationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            DeprecationStatus::LegacyDraft => "legacy draft",
+            DeprecationStatus::VerifyOnly => "verify-only",
+            DeprecationStatus::Deprecated => "deprecated",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// Machine-readable deprecation metadata of an algorithm
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Why the algorithm is deprecated
+    pub status: DeprecationStatus,
+    /// The last year the algorithm should be used in (`None` if no date is set)
+    pub sunset_year: Option<u16>,
+    /// A short explanation, e.g. what to migrate to
+    pub note: &'static str,
+}
+
+impl Deprecation {
+    /// Check if the sunset year has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The current year
+    ///
+    /// # Returns
+    ///
+    /// True if the algorithm should no longer be used in `year`
+    pub fn is_past_sunset(&self, year: u16) -> bool {
+        self.sunset_year.is_some_and(|sunset| year > sunset)
+    }
+}
+
+/// A structured warning emitted when a deprecated algorithm is selected
+///
+/// Warnings are returned by builders, such as
+/// `CertificateBuilder::get_warnings`, and passed to the callback set with
+/// `set_deprecation_callback` whenever a deprecated algorithm is selected.
+///
+/// # Example
+/// ```
+/// use quantcrypt::deprecation::{set_deprecation_callback, DeprecationStatus};
+///
+/// set_deprecation_callback(|warning| {
+///     // Forward to the fleet's monitoring instead
+///     if warning.deprecation.status != DeprecationStatus::Deprecated {
+///         eprintln!("{}", warning);
+///     }
+/// });
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeprecationWarning {
+    /// The name of the algorithm, e.g. `Kyber768`
+    pub algorithm: String,
+    /// The OID of the algorithm (`None` if it has no OID)
+    pub oid: Option<String>,
+    /// The deprecation metadata of the algorithm
+    pub deprecation: Deprecation,
+}
+
+impl DeprecationWarning {
+    /// Build the warning for a KEM, if it is deprecated
+    pub(crate) fn for_kem(kem_type: &KemType) -> Option<DeprecationWarning> {
+        Some(DeprecationWarning {
+            algorithm: format!("{:?}", kem_type),
+            oid: kem_type.get_oid().map(str::to_string),
+            deprecation: kem_type.get_deprecation()?,
+        })
+    }
+
+    /// Build the warning for a DSA, if it is deprecated
+    pub(crate) fn for_dsa(dsa_type: &DsaType) -> Option<DeprecationWarning> {
+        Some(DeprecationWarning {
+            algorithm: format!("{:?}", dsa_type),
+            oid: Some(dsa_type.get_oid()),
+            deprecation: dsa_type.get_deprecation()?,
+        })
+    }
+
+    /// Build the warning for a prehash DSA, if it is deprecated
+    pub(crate) fn for_prehash_dsa(dsa_type: &PrehashDsaType) -> Option<DeprecationWarning> {
+        Some(DeprecationWarning {
+            algorithm: format!("{:?}", dsa_type),
+            oid: Some(dsa_type.get_oid()),
+            deprecation: dsa_type.get_deprecation()?,
+        })
+    }
+
+    /// Build the warning for the algorithm of an OID, if it is deprecated
+    pub(crate) fn for_oid(oid: &str) -> Option<DeprecationWarning> {
+        if let Some(kem_type) = KemType::from_oid(oid) {
+            return DeprecationWarning::for_kem(&kem_type);
+        }
+        if let Some(dsa_type) = DsaType::from_oid(oid) {
+            return DeprecationWarning::for_dsa(&dsa_type);
+        }
+        PrehashDsaType::from_oid(oid).and_then(|dsa_type| Self::for_prehash_dsa(&dsa_type))
+    }
+}
+
+impl fmt::Display for DeprecationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is {}", self.algorithm, self.deprecation.status)?;
+        if let Some(sunset) = self.deprecation.sunset_year {
+            write!(f, " (sunset {})", sunset)?;
+        }
+        write!(f, ": {}", self.deprecation.note)
+    }
+}
+
+/// Set the callback invoked when a deprecated algorithm is selected
+///
+/// The callback receives a warning every time a KEM or DSA manager is created
+/// for a deprecated algorithm, which covers key generation, encapsulation,
+/// signing and verification, and when a certificate builder is given a
+/// deprecated subject key. The setting is process-wide.
+///
+/// # Arguments
+///
+/// * `callback` - The callback, receiving the warning
+pub fn set_deprecation_callback(callback: impl Fn(&DeprecationWarning) + Send + Sync + 'static) {
+    *DEPRECATION_CALLBACK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(callback));
+}
+
+/// Remove the callback set with `set_deprecation_callback`
+pub fn clear_deprecation_callback() {
+    *DEPRECATION_CALLBACK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Pass a warning to the deprecation callback, if one is set
+pub(crate) fn report_deprecation(warning: Option<DeprecationWarning>) {
+    let Some(warning) = warning else {
+        return;
+    };
+    let callback = DEPRECATION_CALLBACK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if let Some(callback) = callback {
+        callback(&warning);
+    }
+}
+
+impl KemType {
+    /// Get the deprecation metadata of the KEM
+    ///
+    /// # Returns
+    ///
+    /// The deprecation metadata, or `None` if the KEM is not deprecated
+    pub fn get_deprecation(&self) -> Option<Deprecation> {
+        match self {
+            #[cfg(feature = "legacy")]
+            KemType::X25519Kyber768Draft00 => Some(Deprecation {
+                status: DeprecationStatus::LegacyDraft,
+                sunset_year: Some(LEGACY_SUNSET),
+                note: "pre-standard hybrid with round 3 Kyber, use X25519MLKEM768 or X-Wing",
+            }),
+            #[cfg(feature = "legacy")]
+            KemType::Kyber512 | KemType::Kyber768 | KemType::Kyber1024 => Some(Deprecation {
+                status: DeprecationStatus::LegacyDraft,
+                sunset_year: Some(LEGACY_SUNSET),
+                note: "round 3 submission, only for decrypting data from before FIPS 203",
+            }),
+            KemType::RsaOAEP2048 | KemType::MlKem768Rsa2048 => Some(Deprecation {
+                status: DeprecationStatus::Deprecated,
+                sunset_year: Some(RSA_2048_SUNSET),
+                note: "RSA-2048 offers 112 bits of security, use RSA-3072 or larger",
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl DsaType {
+    /// Get the deprecation metadata of the DSA
+    ///
+    /// # Returns
+    ///
+    /// The deprecation metadata, or `None` if the DSA is not deprecated
+    pub fn get_deprecation(&self) -> Option<Deprecation> {
+        match self {
+            #[cfg(feature = "legacy")]
+            DsaType::Dilithium2 | DsaType::Dilithium3 | DsaType::Dilithium5 => Some(Deprecation {
+                status: DeprecationStatus::VerifyOnly,
+                sunset_year: Some(LEGACY_SUNSET),
+                note: "round 3 submission, only for verifying signatures from before FIPS 204",
+            }),
+            DsaType::Rsa2048PssSha256 | DsaType::Rsa2048Pkcs15Sha256 => Some(Deprecation {
+                status: DeprecationStatus::Deprecated,
+                sunset_year: Some(RSA_2048_SUNSET),
+                note: "RSA-2048 offers 112 bits of security, use RSA-3072 or larger",
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl PrehashDsaType {
+    /// Get the deprecation metadata of the DSA
+    ///
+    /// # Returns
+    ///
+    /// The deprecation metadata, or `None` if the DSA is not deprecated
+    pub fn get_deprecation(&self) -> Option<Deprecation> {
+        match self {
+            PrehashDsaType::MlDsa44Rsa2048Pss
+            | PrehashDsaType::MlDsa44Rsa2048Pkcs15
+            | PrehashDsaType::MlDsa44Rsa2048PssSha256
+            | PrehashDsaType::MlDsa44Rsa2048Pkcs15Sha256 => Some(Deprecation {
+                status: DeprecationStatus::Deprecated,
+                sunset_year: Some(RSA_2048_SUNSET),
+                note: "the RSA-2048 component offers 112 bits of security",
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::common::kem_trait::Kem;
+    use crate::kem::kem_manager::KemManager;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_deprecation_metadata() {
+        let warning = DeprecationWarning::for_kem(&KemType::RsaOAEP2048).unwrap();
+        assert_eq!(warning.deprecation.status, DeprecationStatus::Deprecated);
+        assert!(!warning.deprecation.is_past_sunset(2030));
+        assert!(warning.deprecation.is_past_sunset(2031));
+        assert!(warning
+            .to_string()
+            .starts_with("RsaOAEP2048 is deprecated (sunset 2030)"));
+
+        let oid = PrehashDsaType::MlDsa44Rsa2048PssSha256.get_oid();
+        assert_eq!(
+            DeprecationWarning::for_oid(&oid).unwrap().oid,
+            Some(oid.clone())
+        );
+        assert!(DeprecationWarning::for_kem(&KemType::MlKem768).is_none());
+        assert!(DeprecationWarning::for_oid(KemType::MlKem768X25519.get_oid().unwrap()).is_none());
+
+        #[cfg(feature = "legacy")]
+        {
+            let warning = DeprecationWarning::for_kem(&KemType::Kyber768).unwrap();
+            assert_eq!(warning.deprecation.status, DeprecationStatus::LegacyDraft);
+            assert_eq!(warning.oid, None);
+            let warning = DeprecationWarning::for_oid(&DsaType::Dilithium3.get_oid()).unwrap();
+            assert_eq!(warning.deprecation.status, DeprecationStatus::VerifyOnly);
+        }
+    }
+
+    #[test]
+    fn test_deprecation_callback() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        set_deprecation_callback(move |warning| {
+            sink.lock().unwrap().push(warning.algorithm.clone());
+        });
+        KemManager::new(KemType::RsaOAEP2048).unwrap();
+        KemManager::new(KemType::MlKem768).unwrap();
+        clear_deprecation_callback();
+        KemManager::new(KemType::MlKem768Rsa2048).unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&"RsaOAEP2048".to_string()));
+        assert!(!seen.contains(&"MlKem768".to_string()));
+        assert!(!seen.contains(&"MlKem768Rsa2048".to_string()));
+    }
+}
@@ -0,0 +1,682 @@
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, CryptoRngCore, OsRng, RngCore, SeedableRng};
+use thiserror::Error;
+use zeroize::Zeroize;
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The window size of the adaptive proportion test for non-binary samples (SP 800-90B 4.4.2)
+const APT_WINDOW_SIZE: usize = 512;
+
+/// The false positive probability of both tests is 2^-FALSE_POSITIVE_EXPONENT
+/// per sample, at the upper end of the range recommended by SP 800-90B
+const FALSE_POSITIVE_EXPONENT: u32 = 40;
+
+/// The result of a failed health test
+#[derive(Error, Clone, Copy, Debug, PartialEq)]
+pub enum HealthTestFailure {
+    /// The same sample was repeated `count` times in a row (SP 800-90B 4.4.1)
+    #[error("Repetition count test failed: {value:#04x} repeated {count} times")]
+    RepetitionCount { value: u8, count: usize },
+    /// The first sample of a window occurred `count` times in that window (SP 800-90B 4.4.2)
+    #[error("Adaptive proportion test failed: {value:#04x} occurred {count} times in {APT_WINDOW_SIZE} samples")]
+    AdaptiveProportion { value: u8, count: usize },
+}
+
+/// A callback invoked when a health test fails
+type FailureCallback = Arc<dyn Fn(&HealthTestFailure) + Send + Sync>;
+
+/// A wrapper running the continuous health tests of SP 800-90B on the output of an RNG
+///
+/// Every output byte is a sample of the repetition count test (RCT) and the adaptive
+/// proportion test (APT). When a test fails, the failure callback is invoked, the
+/// output of the failing call is zeroized and the RNG enters an error state:
+/// `try_fill_bytes` returns an error and `fill_bytes` panics, until `reset` is called.
+///
+/// The default key generation of the crate draws its entropy from the operating
+/// system through such a wrapper, see `set_entropy_failure_callback`. This includes
+/// the EC and EdDSA keys and the ephemeral ECDH keys, which OpenSSL computes from a
+/// secret drawn from that entropy rather than from its own RNG.
+///
+/// # Example
+/// ```
+/// use quantcrypt::entropy::HealthTestedRng;
+/// use rand_core::{OsRng, RngCore};
+///
+/// let mut rng = HealthTestedRng::new(OsRng);
+/// rng.set_failure_callback(|failure| eprintln!("{}", failure));
+/// let mut seed = [0u8; 64];
+/// rng.fill_bytes(&mut seed);
+/// assert!(rng.get_failure().is_none());
+/// ```
+pub struct HealthTestedRng<R> {
+    inner: R,
+    rct_cutoff: usize,
+    apt_cutoff: usize,
+    rct_value: Option<u8>,
+    rct_count: usize,
+    apt_value: u8,
+    apt_count: usize,
+    apt_samples: usize,
+    failure: Option<HealthTestFailure>,
+    on_failure: Option<FailureCallback>,
+}
+
+impl<R: RngCore> HealthTestedRng<R> {
+    /// Wrap an RNG claimed to provide full entropy (8 bits per byte)
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The RNG to monitor
+    ///
+    /// # Returns
+    ///
+    /// The health tested RNG
+    pub fn new(inner: R) -> HealthTestedRng<R> {
+        HealthTestedRng::new_with_min_entropy(inner, 8.0)
+    }
+
+    /// Wrap an RNG with a given assessed min-entropy per byte
+    ///
+    /// The cutoffs of both tests are derived from the min-entropy as specified
+    /// in SP 800-90B. A lower min-entropy tolerates more repetitions.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The RNG to monitor
+    /// * `min_entropy` - The min-entropy of a byte of output, in bits (clamped to 0.1 to 8)
+    ///
+    /// # Returns
+    ///
+    /// The health tested RNG
+    pub fn new_with_min_entropy(inner: R, min_entropy: f64) -> HealthTestedRng<R> {
+        let min_entropy = min_entropy.clamp(0.1, 8.0);
+        HealthTestedRng {
+            inner,
+            rct_cutoff: get_rct_cutoff(min_entropy),
+            apt_cutoff: get_apt_cutoff(min_entropy),
+            rct_value: None,
+            rct_count: 0,
+            apt_value: 0,
+            apt_count: 0,
+            apt_samples: 0,
+            failure: None,
+            on_failure: None,
+        }
+    }
+
+    /// Set the callback invoked when a health test fails
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The callback, receiving the failure
+    pub fn set_failure_callback(
+        &mut self,
+        callback: impl Fn(&HealthTestFailure) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_failure = Some(Arc::new(callback));
+        self
+    }
+
+    /// Get the failure that put the RNG in its error state
+    ///
+    /// # Returns
+    ///
+    /// The failure, or `None` if all health tests passed so far
+    pub fn get_failure(&self) -> Option<HealthTestFailure> {
+        self.failure
+    }
+
+    /// Leave the error state and restart both tests
+    pub fn reset(&mut self) {
+        self.rct_value = None;
+        self.rct_count = 0;
+        self.apt_samples = 0;
+        self.failure = None;
+    }
+
+    /// Get the wrapped RNG
+    ///
+    /// # Returns
+    ///
+    /// The wrapped RNG
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Run both tests on a sample
+    fn test_sample(&mut self, sample: u8) -> Option<HealthTestFailure> {
+        if self.rct_value == Some(sample) {
+            self.rct_count += 1;
+            if self.rct_count >= self.rct_cutoff {
+                return Some(HealthTestFailure::RepetitionCount {
+                    value: sample,
+                    count: self.rct_count,
+                });
+            }
+        } else {
+            self.rct_value = Some(sample);
+            self.rct_count = 1;
+        }
+
+        if self.apt_samples == 0 {
+            self.apt_value = sample;
+            self.apt_count = 1;
+        } else if self.apt_value == sample {
+            self.apt_count += 1;
+            if self.apt_count >= self.apt_cutoff {
+                return Some(HealthTestFailure::AdaptiveProportion {
+                    value: sample,
+                    count: self.apt_count,
+                });
+            }
+        }
+        self.apt_samples = (self.apt_samples + 1) % APT_WINDOW_SIZE;
+        None
+    }
+}
+
+impl<R: RngCore> RngCore for HealthTestedRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(e) = self.try_fill_bytes(dest) {
+            panic!("{}", e);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+        if let Some(failure) = self.failure {
+            return Err(rand_core::Error::new(failure));
+        }
+        self.inner.try_fill_bytes(dest)?;
+        if let Some(failure) = dest.iter().find_map(|&sample| self.test_sample(sample)) {
+            dest.zeroize();
+            self.failure = Some(failure);
+            if let Some(callback) = &self.on_failure {
+                callback(&failure);
+            }
+            return Err(rand_core::Error::new(failure));
+        }
+        Ok(())
+    }
+}
+
+impl<R: CryptoRng + RngCore> CryptoRng for HealthTestedRng<R> {}
+
+/// The cutoff C = 1 + ceil(-log2(alpha) / H) of the repetition count test
+fn get_rct_cutoff(min_entropy: f64) -> usize {
+    1 + (FALSE_POSITIVE_EXPONENT as f64 / min_entropy).ceil() as usize
+}
+
+/// The cutoff C = 1 + CRITBINOM(W, 2^-H, 1 - alpha) of the adaptive proportion test
+fn get_apt_cutoff(min_entropy: f64) -> usize {
+    let alpha = 2f64.powi(-(FALSE_POSITIVE_EXPONENT as i32));
+    let p = 2f64.powf(-min_entropy);
+    let n = APT_WINDOW_SIZE;
+
+    // Sum the binomial distribution until its tail is below alpha. The terms are
+    // computed in log space, as the first ones underflow for low min-entropies.
+    let mut log_pmf = n as f64 * (1.0 - p).ln();
+    let mut cdf = log_pmf.exp();
+    let mut k = 0;
+    while 1.0 - cdf > alpha && k < n {
+        log_pmf += ((n - k) as f64 / (k + 1) as f64 * p / (1.0 - p)).ln();
+        cdf += log_pmf.exp();
+        k += 1;
+    }
+    // At most the whole window can be the same value
+    (1 + k).min(n)
+}
+
+/// The health tested entropy source used to seed the default RNGs of key generation
+fn get_system_entropy() -> &'static Mutex<HealthTestedRng<OsRng>> {
+    static SYSTEM_ENTROPY: OnceLock<Mutex<HealthTestedRng<OsRng>>> = OnceLock::new();
+    SYSTEM_ENTROPY.get_or_init(|| Mutex::new(HealthTestedRng::new(OsRng)))
+}
+
+/// Set the callback invoked when the health tests of the entropy used to generate
+/// keys fail
+///
+/// Key generation with the default RNG seeds a ChaCha20 RNG from the operating
+/// system through a `HealthTestedRng`, shared by the whole process so that the
+/// tests are continuous. After a failure, key generation fails with
+/// `QuantCryptError::EntropyHealthTestFailed` until `reset_entropy_health_tests`
+/// is called.
+///
+/// # Arguments
+///
+/// * `callback` - The callback, receiving the failure
+pub fn set_entropy_failure_callback(callback: impl Fn(&HealthTestFailure) + Send + Sync + 'static) {
+    get_system_entropy()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .set_failure_callback(callback);
+}
+
+/// Get the failure that put the entropy used to generate keys in its error state
+///
+/// # Returns
+///
+/// The failure, or `None` if key generation can draw from the system entropy
+pub fn get_entropy_health_failure() -> Option<HealthTestFailure> {
+    get_system_entropy()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_failure()
+}
+
+/// Leave the error state of the entropy used to generate keys and restart its
+/// health tests
+///
+/// A failure is sticky: key generation with the default RNG keeps failing until
+/// this is called, so that a process doesn't silently resume with a source that
+/// was found faulty. Call it once the cause of the failure has been dealt with.
+/// If the source is still faulty, the tests fail again on the next key generation.
+pub fn reset_entropy_health_tests() {
+    get_system_entropy()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .reset();
+}
+
+/// Get a ChaCha20 RNG for key generation, seeded from the health tested system entropy
+///
+/// # Returns
+///
+/// The seeded RNG
+///
+/// # Errors
+///
+/// `QuantCryptError::EntropyHealthTestFailed` if the health tests failed
+pub(crate) fn get_key_gen_rng() -> Result<ChaCha20Rng> {
+    seed_key_gen_rng(get_system_entropy())
+}
+
+/// Seed a ChaCha20 RNG for key generation from a health tested source
+///
+/// # Arguments
+///
+/// * `source` - The health tested source
+///
+/// # Returns
+///
+/// The seeded RNG
+///
+/// # Errors
+///
+/// `QuantCryptError::EntropyHealthTestFailed` if the health tests failed, now or before
+fn seed_key_gen_rng<R: RngCore>(source: &Mutex<HealthTestedRng<R>>) -> Result<ChaCha20Rng> {
+    let mut source = source.lock().unwrap_or_else(|e| e.into_inner());
+    ChaCha20Rng::from_rng(&mut *source).map_err(|_| QuantCryptError::EntropyHealthTestFailed)
+}
+
+/// A caller-supplied RNG, shared by the managers constructed with it
+///
+/// Managers created with `new_with_rng` draw all their randomness from this RNG
+/// instead of seeding their own: key generation, encapsulation coins, ephemeral
+/// keys, signing randomness and the components of composite algorithms. A
+/// deployment can therefore route every random byte through a single auditable
+/// source, such as an SP 800-90A DRBG. Clones share the same RNG, which is
+/// locked for each request, so one instance can be handed to many managers.
+///
+/// ECDSA nonces and RSA-PSS salts are drawn from it too, by computing those
+/// signatures outside the OpenSSL signer, whose RNG can't be replaced per call.
+///
+/// # Example
+/// ```
+/// use quantcrypt::entropy::SharedRng;
+/// use quantcrypt::kems::{Kem, KemManager, KemType};
+/// use rand_chacha::ChaCha20Rng;
+/// use rand_core::SeedableRng;
+///
+/// // Stands in for the DRBG of the deployment
+/// let rng = SharedRng::new(ChaCha20Rng::from_entropy());
+/// let mut kem = KemManager::new_with_rng(KemType::MlKem768X25519, rng.clone()).unwrap();
+/// let (pk, sk) = kem.key_gen().unwrap();
+/// let (ss, ct) = kem.encap(&pk).unwrap();
+/// assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+/// ```
+#[derive(Clone)]
+pub struct SharedRng {
+    inner: Arc<Mutex<dyn CryptoRngCore + Send>>,
+}
+
+impl SharedRng {
+    /// Wrap an RNG so that it can be shared by managers
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The RNG
+    ///
+    /// # Returns
+    ///
+    /// The shared RNG
+    pub fn new(rng: impl CryptoRngCore + Send + 'static) -> SharedRng {
+        SharedRng {
+            inner: Arc::new(Mutex::new(rng)),
+        }
+    }
+}
+
+impl RngCore for SharedRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // A panic of another holder doesn't leave the RNG in an unusable state
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for SharedRng {}
+
+/// The RNG used by a manager for one operation: the RNG it was constructed with,
+/// or else a ChaCha20 RNG seeded for the operation
+pub(crate) enum ManagerRng {
+    Shared(SharedRng),
+    Default(Box<ChaCha20Rng>),
+}
+
+impl ManagerRng {
+    /// Get the RNG for a key generation
+    ///
+    /// The default RNG is seeded from the health tested system entropy, see `get_key_gen_rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The RNG the manager was constructed with, if any
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::EntropyHealthTestFailed` if the health tests failed
+    pub(crate) fn for_key_gen(rng: Option<&SharedRng>) -> Result<ManagerRng> {
+        Ok(match rng {
+            Some(rng) => ManagerRng::Shared(rng.clone()),
+            None => ManagerRng::Default(Box::new(get_key_gen_rng()?)),
+        })
+    }
+
+    /// Get the RNG for any other operation, such as an encapsulation
+    ///
+    /// The default RNG is seeded from the operating system.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The RNG the manager was constructed with, if any
+    pub(crate) fn for_operation(rng: Option<&SharedRng>) -> ManagerRng {
+        match rng {
+            Some(rng) => ManagerRng::Shared(rng.clone()),
+            None => ManagerRng::Default(Box::new(ChaCha20Rng::from_entropy())),
+        }
+    }
+}
+
+impl RngCore for ManagerRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            ManagerRng::Shared(rng) => rng.next_u32(),
+            ManagerRng::Default(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            ManagerRng::Shared(rng) => rng.next_u64(),
+            ManagerRng::Default(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            ManagerRng::Shared(rng) => rng.fill_bytes(dest),
+            ManagerRng::Default(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+        match self {
+            ManagerRng::Shared(rng) => rng.try_fill_bytes(dest),
+            ManagerRng::Default(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+impl CryptoRng for ManagerRng {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// An RNG cycling through a fixed sequence of bytes
+    struct CyclingRng {
+        sequence: Vec<u8>,
+        index: usize,
+    }
+
+    impl RngCore for CyclingRng {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for b in dest.iter_mut() {
+                *b = self.sequence[self.index % self.sequence.len()];
+                self.index += 1;
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_health_tests() {
+        assert_eq!(get_rct_cutoff(8.0), 6);
+        let apt_cutoff = get_apt_cutoff(8.0);
+        assert!(apt_cutoff > 6 && apt_cutoff < 32);
+        assert!(get_apt_cutoff(1.0) > APT_WINDOW_SIZE / 2);
+
+        // A healthy source passes
+        let mut rng = HealthTestedRng::new(ChaCha20Rng::from_seed([7u8; 32]));
+        let mut buf = vec![0u8; 1 << 20];
+        rng.try_fill_bytes(&mut buf).unwrap();
+        assert!(rng.get_failure().is_none());
+
+        // A stuck source fails the repetition count test
+        let failures = Arc::new(AtomicUsize::new(0));
+        let counter = failures.clone();
+        let mut rng = HealthTestedRng::new(CyclingRng {
+            sequence: vec![0xAA],
+            index: 0,
+        });
+        rng.set_failure_callback(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let mut buf = [1u8; 16];
+        assert!(rng.try_fill_bytes(&mut buf).is_err());
+        assert_eq!(buf, [0u8; 16]);
+        assert_eq!(
+            rng.get_failure(),
+            Some(HealthTestFailure::RepetitionCount {
+                value: 0xAA,
+                count: 6
+            })
+        );
+        assert_eq!(failures.load(Ordering::SeqCst), 1);
+
+        // The error state is sticky until reset
+        assert!(rng.try_fill_bytes(&mut buf).is_err());
+        assert_eq!(failures.load(Ordering::SeqCst), 1);
+        rng.reset();
+        assert!(rng.get_failure().is_none());
+
+        // A biased source without repetitions fails the adaptive proportion test
+        let mut rng = HealthTestedRng::new(CyclingRng {
+            sequence: vec![0, 1, 0, 2, 0, 3],
+            index: 0,
+        });
+        let mut buf = [0u8; APT_WINDOW_SIZE];
+        assert!(rng.try_fill_bytes(&mut buf).is_err());
+        assert!(matches!(
+            rng.get_failure(),
+            Some(HealthTestFailure::AdaptiveProportion { value: 0, .. })
+        ));
+
+        // The same source passes with a low enough assessed min-entropy
+        let mut rng = HealthTestedRng::new_with_min_entropy(
+            CyclingRng {
+                sequence: vec![0, 1, 0, 2, 0, 3],
+                index: 0,
+            },
+            1.0,
+        );
+        rng.try_fill_bytes(&mut buf).unwrap();
+
+        assert!(get_key_gen_rng().is_ok());
+    }
+
+    /// A healthy RNG that outputs a fixed byte pattern while it is faulty
+    struct FaultyRng {
+        healthy: ChaCha20Rng,
+        fault: Arc<Mutex<Option<Vec<u8>>>>,
+        index: usize,
+    }
+
+    impl RngCore for FaultyRng {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            match &*self.fault.lock().unwrap() {
+                Some(pattern) => {
+                    for b in dest.iter_mut() {
+                        *b = pattern[self.index % pattern.len()];
+                        self.index += 1;
+                    }
+                }
+                None => self.healthy.fill_bytes(dest),
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_key_gen_rng_failures() {
+        // The system entropy is healthy
+        assert!(get_key_gen_rng().is_ok());
+        assert!(get_entropy_health_failure().is_none());
+        reset_entropy_health_tests();
+        assert!(get_key_gen_rng().is_ok());
+
+        // A stuck output and a repeated byte pattern, injected into a source like the system one
+        for (pattern, stuck) in [(vec![0x00], true), (vec![0x5A, 0x01, 0x5A, 0x02], false)] {
+            let fault = Arc::new(Mutex::new(None));
+            let failures = Arc::new(AtomicUsize::new(0));
+            let counter = failures.clone();
+            let mut rng = HealthTestedRng::new(FaultyRng {
+                healthy: ChaCha20Rng::from_seed([9u8; 32]),
+                fault: fault.clone(),
+                index: 0,
+            });
+            rng.set_failure_callback(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+            let source = Mutex::new(rng);
+            assert!(seed_key_gen_rng(&source).is_ok());
+
+            // Key generation fails once the source is faulty. A seed of 32 bytes is too
+            // short to fail the adaptive proportion test on its own, the window spans seeds.
+            *fault.lock().unwrap() = Some(pattern);
+            let error = (0..2 * APT_WINDOW_SIZE / 32).find_map(|_| seed_key_gen_rng(&source).err());
+            assert_eq!(error, Some(QuantCryptError::EntropyHealthTestFailed));
+            assert_eq!(failures.load(Ordering::SeqCst), 1);
+            let failure = source.lock().unwrap().get_failure();
+            if stuck {
+                assert!(matches!(
+                    failure,
+                    Some(HealthTestFailure::RepetitionCount { value: 0x00, .. })
+                ));
+            } else {
+                assert!(matches!(
+                    failure,
+                    Some(HealthTestFailure::AdaptiveProportion { .. })
+                ));
+            }
+
+            // The failure is sticky, even once the source is healthy again
+            *fault.lock().unwrap() = None;
+            assert_eq!(
+                seed_key_gen_rng(&source).err(),
+                Some(QuantCryptError::EntropyHealthTestFailed)
+            );
+            assert_eq!(failures.load(Ordering::SeqCst), 1);
+
+            // Resetting recovers
+            source.lock().unwrap().reset();
+            assert!(seed_key_gen_rng(&source).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_shared_rng() {
+        let rng = SharedRng::new(ChaCha20Rng::from_seed([7u8; 32]));
+        let mut expected = ChaCha20Rng::from_seed([7u8; 32]);
+
+        // Clones draw from the same stream
+        let mut a = rng.clone();
+        let mut b = ManagerRng::for_key_gen(Some(&rng)).unwrap();
+        let (mut x, mut y) = ([0u8; 16], [0u8; 16]);
+        a.fill_bytes(&mut x);
+        b.fill_bytes(&mut y);
+        let mut z = [0u8; 32];
+        expected.fill_bytes(&mut z);
+        assert_eq!(x, z[..16]);
+        assert_eq!(y, z[16..]);
+
+        let mut c = ManagerRng::for_operation(None);
+        c.fill_bytes(&mut x);
+        assert!(matches!(c, ManagerRng::Default(_)));
+    }
+}
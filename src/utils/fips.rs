@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Whether algorithms that aren't approved by FIPS are refused
+static FIPS_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Set whether algorithms that aren't approved by FIPS are refused
+///
+/// While the FIPS mode is enabled, creating a hash or KDF that isn't approved,
+/// such as BLAKE3, fails with `QuantCryptError::NotFipsApproved`. This covers
+/// the hashes of Merkle trees and transparency logs and the KDFs used with
+/// shared secrets. The mode is disabled by default. The setting is process-wide.
+///
+/// # Arguments
+///
+/// * `enabled` - True to refuse the algorithms that aren't approved
+pub fn set_fips_mode(enabled: bool) {
+    FIPS_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if the FIPS mode is enabled
+///
+/// # Returns
+///
+/// True if algorithms that aren't approved by FIPS are refused
+pub fn get_fips_mode() -> bool {
+    FIPS_MODE.load(Ordering::Relaxed)
+}
+
+/// Refuse an algorithm that isn't approved if the FIPS mode is enabled
+///
+/// # Arguments
+///
+/// * `approved` - True if the algorithm is approved by FIPS
+///
+/// # Errors
+///
+/// `QuantCryptError::NotFipsApproved` if the algorithm isn't approved and the FIPS mode is enabled
+pub(crate) fn check_fips_approved(approved: bool) -> Result<()> {
+    if !approved && get_fips_mode() {
+        return Err(QuantCryptError::NotFipsApproved);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::common::hash_trait::Hash;
+    use crate::hash::common::hash_type::HashType;
+    use crate::hash::hash_manager::HashManager;
+    use crate::kdf::api::KdfManager;
+    use crate::kdf::common::kdf_trait::Kdf;
+    use crate::kdf::common::kdf_type::KdfType;
+
+    #[test]
+    fn test_fips_mode() {
+        assert!(!get_fips_mode());
+        assert_eq!(
+            HashManager::new(HashType::Blake3).is_ok(),
+            cfg!(feature = "blake3")
+        );
+        assert_eq!(
+            KdfManager::new(KdfType::Blake3).is_ok(),
+            cfg!(feature = "blake3")
+        );
+
+        set_fips_mode(true);
+        let blake3_hash = HashManager::new(HashType::Blake3);
+        let blake3_kdf = KdfManager::new(KdfType::Blake3);
+        let sha256 = HashManager::new(HashType::Sha256);
+        let hkdf = KdfManager::new(KdfType::HkdfWithSha256);
+        set_fips_mode(false);
+
+        assert!(matches!(blake3_hash, Err(QuantCryptError::NotFipsApproved)));
+        assert!(matches!(blake3_kdf, Err(QuantCryptError::NotFipsApproved)));
+        assert!(sha256.is_ok());
+        assert!(hkdf.is_ok());
+    }
+}
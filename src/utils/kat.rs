@@ -0,0 +1,768 @@
+use std::collections::HashMap;
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::dsa::api::algorithm::DsaAlgorithm;
+use crate::dsa::common::dsa_trait::Dsa;
+use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+use crate::dsa::dsa_manager::PrehashDsaManager;
+use crate::dsa::ml_dsa::ML_DSA_SIGNING_SEED_LEN;
+use crate::kem::api::algorithm::KemAlgorithm;
+use crate::kem::common::kem_trait::Kem;
+#[cfg(any(test, feature = "test-utils"))]
+use crate::kem::common::kem_type::KemType;
+#[cfg(any(test, feature = "test-utils"))]
+use crate::kem::kem_manager::KemManager;
+use crate::utils::ctr_drbg::{CtrDrbg, CTR_DRBG_SEED_LEN};
+use crate::utils::entropy::SharedRng;
+use crate::utils::manager_pool::{get_dsa_manager, get_kem_manager, get_prehash_dsa_manager};
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A single record of a NIST `.rsp` KAT file
+#[derive(Clone, Debug)]
+pub struct KatRecord {
+    count: usize,
+    values: HashMap<String, String>,
+}
+
+impl KatRecord {
+    /// Get the record number
+    ///
+    /// # Returns
+    ///
+    /// The value of the `count` field
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    /// Get a raw field value
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The field name, e.g. `pk`
+    ///
+    /// # Returns
+    ///
+    /// The value as written in the file, or None if the field is absent
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|s| s.as_str())
+    }
+
+    /// Get a hex encoded field
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The field name, e.g. `pk`
+    ///
+    /// # Returns
+    ///
+    /// The decoded bytes
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidKatFile` if the field is absent or not valid hex
+    pub fn get_bytes(&self, name: &str) -> Result<Vec<u8>> {
+        let value = self.get(name).ok_or(QuantCryptError::InvalidKatFile)?;
+        hex::decode(value).map_err(|_| QuantCryptError::InvalidKatFile)
+    }
+
+    /// Get a decimal field
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The field name, e.g. `mlen`
+    ///
+    /// # Returns
+    ///
+    /// The parsed value
+    pub fn get_usize(&self, name: &str) -> Result<usize> {
+        let value = self.get(name).ok_or(QuantCryptError::InvalidKatFile)?;
+        value.parse().map_err(|_| QuantCryptError::InvalidKatFile)
+    }
+
+    pub(crate) fn get_seed(&self) -> Result<[u8; CTR_DRBG_SEED_LEN]> {
+        self.get_bytes("seed")?
+            .try_into()
+            .map_err(|_| QuantCryptError::InvalidKatFile)
+    }
+
+    fn check(&self, name: &'static str, actual: &[u8]) -> Result<()> {
+        if self.get_bytes(name)? != actual {
+            return Err(QuantCryptError::KatMismatch {
+                count: self.count,
+                field: name,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A parsed NIST `.rsp` KAT file, as produced by `PQCgenKAT_kem` and `PQCgenKAT_sign`
+#[derive(Clone, Debug)]
+pub struct RspFile {
+    records: Vec<KatRecord>,
+}
+
+impl RspFile {
+    /// Parse the contents of a `.rsp` file
+    ///
+    /// Records are `name = value` lines starting with `count`. Blank lines and lines
+    /// starting with `#` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The contents of the file
+    ///
+    /// # Returns
+    ///
+    /// The parsed file
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::InvalidKatFile` if a line can't be parsed or a field appears before `count`
+    pub fn parse(contents: &str) -> Result<RspFile> {
+        let mut records: Vec<KatRecord> = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or(QuantCryptError::InvalidKatFile)?;
+            let (name, value) = (name.trim(), value.trim());
+            if name == "count" {
+                let count = value.parse().map_err(|_| QuantCryptError::InvalidKatFile)?;
+                records.push(KatRecord {
+                    count,
+                    values: HashMap::new(),
+                });
+            } else {
+                records
+                    .last_mut()
+                    .ok_or(QuantCryptError::InvalidKatFile)?
+                    .values
+                    .insert(name.to_string(), value.to_string());
+            }
+        }
+        Ok(RspFile { records })
+    }
+
+    /// Load a `.rsp` file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the file
+    ///
+    /// # Returns
+    ///
+    /// The parsed file
+    pub fn from_file(path: &str) -> Result<RspFile> {
+        let contents = std::fs::read_to_string(path).map_err(|_| QuantCryptError::FileReadError)?;
+        RspFile::parse(&contents)
+    }
+
+    /// Get the records of the file
+    ///
+    /// # Returns
+    ///
+    /// The records, in file order
+    pub fn get_records(&self) -> &[KatRecord] {
+        &self.records
+    }
+}
+
+/// An RNG that serves bytes drawn ahead of time from the DRBG
+///
+/// The reference implementations draw all key generation randomness with a single
+/// `randombytes` call, while the underlying crates may request it in several pieces.
+/// Since the DRBG output depends on call boundaries, the bytes are drawn in one call
+/// up front and handed out from this buffer instead.
+struct PrefetchedRng {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl PrefetchedRng {
+    fn new(drbg: &mut CtrDrbg, len: usize) -> PrefetchedRng {
+        PrefetchedRng {
+            buf: drbg.randombytes_vec(len),
+            pos: 0,
+        }
+    }
+}
+
+impl RngCore for PrefetchedRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("Prefetched randomness exhausted")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+        let end = self.pos + dest.len();
+        if end > self.buf.len() {
+            return Err(rand_core::Error::new("Prefetched randomness exhausted"));
+        }
+        dest.copy_from_slice(&self.buf[self.pos..end]);
+        self.pos = end;
+        Ok(())
+    }
+}
+
+impl CryptoRng for PrefetchedRng {}
+
+/// Run a KEM known answer test file
+///
+/// For every record, the DRBG is seeded with the record's `seed` and the key pair
+/// is generated the way the reference implementation does (a single 64 byte
+/// `randombytes` call for `d || z`), then compared with `pk` and `sk`. The public
+/// key is encapsulated with the next 32 bytes of the DRBG as the message m and
+/// compared with `ct` and `ss`, then the recorded `ct` is decapsulated with the
+/// recorded `sk` and compared with `ss`.
+///
+/// Only the pure ML-KEM parameter sets are supported.
+///
+/// # Arguments
+///
+/// * `algorithm` - The KEM algorithm the file was generated for
+/// * `rsp` - The parsed KAT file
+///
+/// # Returns
+///
+/// The number of records that passed
+///
+/// # Errors
+///
+/// `QuantCryptError::KatMismatch` for the first record that doesn't match,
+/// `QuantCryptError::NotImplemented` if the algorithm isn't supported
+pub fn run_kem_kat(algorithm: KemAlgorithm, rsp: &RspFile) -> Result<usize> {
+    if !matches!(
+        algorithm,
+        KemAlgorithm::MlKem512 | KemAlgorithm::MlKem768 | KemAlgorithm::MlKem1024
+    ) {
+        return Err(QuantCryptError::NotImplemented);
+    }
+    let mut kem = get_kem_manager(algorithm.get_kem_type())?;
+
+    for record in rsp.get_records() {
+        let mut drbg = CtrDrbg::new(&record.get_seed()?, None);
+        let mut coins = PrefetchedRng::new(&mut drbg, 64);
+        let (pk, sk) = kem.key_gen_with_rng(&mut coins)?;
+        record.check("pk", &pk)?;
+        record.check("sk", &sk)?;
+
+        let m = drbg.randombytes_vec(32);
+        let (ss, ct) = kem.encap_deterministic(&record.get_bytes("pk")?, &m)?;
+        record.check("ct", &ct)?;
+        record.check("ss", &ss)?;
+
+        let ss = kem.decap(&record.get_bytes("sk")?, &record.get_bytes("ct")?)?;
+        record.check("ss", &ss)?;
+    }
+    Ok(rsp.get_records().len())
+}
+
+/// Run a BIKE known answer test file of the round 4 submission
+///
+/// For every record, the DRBG is seeded with the record's `seed` and the key pair
+/// is generated from a single 64 byte `randombytes` call (the seed of the secret
+/// key followed by sigma), then compared with `pk` and `sk`. The encapsulation
+/// draws 64 more bytes, of which the first 32 are the message m, as the reference
+/// implementation does, and is compared with `ct` and `ss`. Finally the recorded
+/// `ct` is decapsulated with the recorded `sk` and compared with `ss`.
+///
+/// BIKE has no OID, so it isn't a `KemAlgorithm` and the file is run for a
+/// `KemType`. Only `BikeL1`, `BikeL3` and `BikeL5` are supported.
+///
+/// # Arguments
+///
+/// * `kem_type` - The BIKE level the file was generated for
+/// * `rsp` - The parsed KAT file
+///
+/// # Returns
+///
+/// The number of records that passed
+///
+/// # Errors
+///
+/// `QuantCryptError::KatMismatch` for the first record that doesn't match,
+/// `QuantCryptError::NotImplemented` if the KEM type isn't supported
+#[cfg(any(test, feature = "test-utils"))]
+pub fn run_bike_kat(kem_type: KemType, rsp: &RspFile) -> Result<usize> {
+    if !matches!(
+        kem_type,
+        KemType::BikeL1 | KemType::BikeL3 | KemType::BikeL5
+    ) {
+        return Err(QuantCryptError::NotImplemented);
+    }
+    let mut kem = get_kem_manager(kem_type)?;
+
+    for record in rsp.get_records() {
+        let mut drbg = CtrDrbg::new(&record.get_seed()?, None);
+        let mut seeds = PrefetchedRng::new(&mut drbg, 64);
+        let (pk, sk) = kem.key_gen_with_rng(&mut seeds)?;
+        record.check("pk", &pk)?;
+        record.check("sk", &sk)?;
+
+        let m = drbg.randombytes_vec(64);
+        let (ss, ct) = kem.encap_deterministic(&record.get_bytes("pk")?, &m[..32])?;
+        record.check("ct", &ct)?;
+        record.check("ss", &ss)?;
+
+        let ss = kem.decap(&record.get_bytes("sk")?, &record.get_bytes("ct")?)?;
+        record.check("ss", &ss)?;
+    }
+    Ok(rsp.get_records().len())
+}
+
+/// Get the security parameter n of an SLH-DSA parameter set
+fn slh_dsa_n(algorithm: DsaAlgorithm) -> Option<usize> {
+    match algorithm {
+        DsaAlgorithm::SlhDsaSha2_128s
+        | DsaAlgorithm::SlhDsaSha2_128f
+        | DsaAlgorithm::SlhDsaShake128s
+        | DsaAlgorithm::SlhDsaShake128f => Some(16),
+        DsaAlgorithm::SlhDsaSha2_192s
+        | DsaAlgorithm::SlhDsaSha2_192f
+        | DsaAlgorithm::SlhDsaShake192s
+        | DsaAlgorithm::SlhDsaShake192f => Some(24),
+        DsaAlgorithm::SlhDsaSha2_256s
+        | DsaAlgorithm::SlhDsaSha2_256f
+        | DsaAlgorithm::SlhDsaShake256s
+        | DsaAlgorithm::SlhDsaShake256f => Some(32),
+        _ => None,
+    }
+}
+
+/// Run a signature known answer test file
+///
+/// For every record, the DRBG is seeded with the record's `seed` and the key pair
+/// is generated the way the reference implementation does, then compared with `pk`
+/// and `sk`. The message is then signed the way the reference implementation does
+/// and the signature followed by the message is compared with `sm`:
+///
+/// * ML-DSA signs deterministically (rnd is all zero) with an empty context
+/// * SLH-DSA draws the n byte opt_rand from the DRBG and signs with an empty context
+///
+/// Only the pure ML-DSA and SLH-DSA parameter sets are supported.
+///
+/// # Arguments
+///
+/// * `algorithm` - The DSA algorithm the file was generated for
+/// * `rsp` - The parsed KAT file
+///
+/// # Returns
+///
+/// The number of records that passed
+///
+/// # Errors
+///
+/// `QuantCryptError::KatMismatch` for the first record that doesn't match,
+/// `QuantCryptError::NotImplemented` if the algorithm isn't supported or is
+/// provided by a registered backend
+pub fn run_dsa_kat(algorithm: DsaAlgorithm, rsp: &RspFile) -> Result<usize> {
+    if !matches!(
+        algorithm,
+        DsaAlgorithm::MlDsa44 | DsaAlgorithm::MlDsa65 | DsaAlgorithm::MlDsa87
+    ) && slh_dsa_n(algorithm).is_none()
+    {
+        return Err(QuantCryptError::NotImplemented);
+    }
+
+    for record in rsp.get_records() {
+        let mut drbg = CtrDrbg::new(&record.get_seed()?, None);
+        let msg = record.get_bytes("msg")?;
+        let (pk, sk, sig) = match algorithm {
+            DsaAlgorithm::MlDsa44 | DsaAlgorithm::MlDsa65 | DsaAlgorithm::MlDsa87 => {
+                let dsa_type = algorithm
+                    .get_prehash_dsa_type()
+                    .ok_or(QuantCryptError::NotImplemented)?;
+                let PrehashDsaManager::Ml(mut dsa) = get_prehash_dsa_manager(dsa_type)? else {
+                    return Err(QuantCryptError::NotImplemented);
+                };
+                // The reference draws the 32 byte seed xi with a single call
+                let (pk, sk) = dsa.key_gen_with_rng(&mut PrefetchedRng::new(&mut drbg, 32))?;
+                let rnd = [0u8; ML_DSA_SIGNING_SEED_LEN];
+                let sig = dsa.sign_with_seed(&sk, &msg, &rnd, None)?;
+                (pk, sk, sig)
+            }
+            _ => {
+                let n = slh_dsa_n(algorithm).ok_or(QuantCryptError::NotImplemented)?;
+                let dsa_type = algorithm
+                    .get_dsa_type()
+                    .ok_or(QuantCryptError::NotImplemented)?;
+                let mut dsa = get_dsa_manager(dsa_type)?;
+                // The reference draws sk_seed || sk_prf || pk_seed with a single call,
+                // then opt_rand when signing
+                let mut seeds = PrefetchedRng::new(&mut drbg, 3 * n);
+                let (pk, sk) = dsa.key_gen_with_rng(&mut seeds)?;
+                dsa.set_rng(SharedRng::new(PrefetchedRng::new(&mut drbg, n)))?;
+                let sig = dsa.sign(&sk, &msg)?;
+                (pk, sk, sig)
+            }
+        };
+        record.check("pk", &pk)?;
+        record.check("sk", &sk)?;
+        record.check("sm", &[sig.as_slice(), msg.as_slice()].concat())?;
+    }
+    Ok(rsp.get_records().len())
+}
+
+/// Sign a message with ML-DSA and the given randomness rnd, to reproduce the
+/// ACVP sigGen test cases
+///
+/// The test cases of the deterministic variant use an all-zero seed, the others
+/// give the seed as `rnd`. The message is signed with the external interface,
+/// so the test cases of the internal interface can't be reproduced.
+///
+/// # Arguments
+///
+/// * `algorithm` - The ML-DSA parameter set
+/// * `sk` - The secret key
+/// * `msg` - The message to sign
+/// * `seed` - The 32 byte randomness rnd
+/// * `ctx` - The context
+///
+/// # Returns
+///
+/// The signature
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidSigningSeedLength` if the seed is not 32 bytes,
+/// `QuantCryptError::NotImplemented` if the algorithm isn't ML-DSA or is
+/// provided by a registered backend
+#[cfg(feature = "test-utils")]
+pub fn sign_deterministic_with_seed(
+    algorithm: DsaAlgorithm,
+    sk: &[u8],
+    msg: &[u8],
+    seed: &[u8],
+    ctx: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    let dsa_type = match algorithm {
+        DsaAlgorithm::MlDsa44 | DsaAlgorithm::MlDsa65 | DsaAlgorithm::MlDsa87 => algorithm
+            .get_prehash_dsa_type()
+            .ok_or(QuantCryptError::NotImplemented)?,
+        _ => return Err(QuantCryptError::NotImplemented),
+    };
+    match get_prehash_dsa_manager(dsa_type)? {
+        PrehashDsaManager::Ml(dsa) => dsa.sign_deterministic_with_seed(sk, msg, seed, ctx),
+        _ => Err(QuantCryptError::NotImplemented),
+    }
+}
+
+/// Decapsulate a ciphertext with ML-KEM and report whether it was implicitly
+/// rejected, to validate the Fujisaki-Okamoto transform against known answer
+/// tests with invalid ciphertexts
+///
+/// See `MlKemManager::decap_checked`. Never use it outside of tests:
+/// revealing whether a ciphertext was rejected defeats implicit rejection.
+///
+/// # Arguments
+///
+/// * `algorithm` - The ML-KEM parameter set
+/// * `sk` - The secret key
+/// * `ct` - The ciphertext
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and whether the ciphertext was
+/// implicitly rejected (ss, rejected)
+///
+/// # Errors
+///
+/// `QuantCryptError::NotImplemented` if the algorithm isn't ML-KEM or is
+/// provided by a registered backend
+#[cfg(feature = "test-utils")]
+pub fn decap_checked(algorithm: KemAlgorithm, sk: &[u8], ct: &[u8]) -> Result<(Vec<u8>, bool)> {
+    match algorithm {
+        KemAlgorithm::MlKem512 | KemAlgorithm::MlKem768 | KemAlgorithm::MlKem1024 => {}
+        _ => return Err(QuantCryptError::NotImplemented),
+    }
+    match get_kem_manager(algorithm.get_kem_type())? {
+        KemManager::Ml(kem) => kem.decap_checked(sk, ct),
+        _ => Err(QuantCryptError::NotImplemented),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+    use fips204::traits::{SerDes as _, Signer as _};
+    use fips205::traits::{SerDes as _, Signer as _};
+    use sha2::{Digest, Sha256};
+
+    /// The seeds of the first records of every NIST KAT file
+    fn kat_seeds(n: usize) -> Vec<Vec<u8>> {
+        let entropy_input: [u8; 48] = core::array::from_fn(|i| i as u8);
+        let mut drbg = CtrDrbg::new(&entropy_input, None);
+        (0..n).map(|_| drbg.randombytes_vec(48)).collect()
+    }
+
+    #[test]
+    fn test_parse_rsp() {
+        let contents =
+            "# ML-KEM-512\n\ncount = 0\nseed = 00ff\nmlen = 33\n\ncount = 1\nseed = 01\n";
+        let rsp = RspFile::parse(contents).unwrap();
+        assert_eq!(rsp.get_records().len(), 2);
+        let record = &rsp.get_records()[0];
+        assert_eq!(record.get_count(), 0);
+        assert_eq!(record.get_bytes("seed").unwrap(), vec![0x00, 0xff]);
+        assert_eq!(record.get_usize("mlen").unwrap(), 33);
+        assert!(record.get("pk").is_none());
+
+        assert!(RspFile::parse("seed = 00\n").is_err());
+        assert!(RspFile::parse("count = 0\nnonsense\n").is_err());
+    }
+
+    #[test]
+    fn test_ml_kem_kat() {
+        // The first record of the ML-KEM KAT files, pinned by the SHA-256 liboqs
+        // 0.12.0 records for them, see test/data/kat/README.md
+        for (algorithm, file, sha256) in [
+            (
+                KemAlgorithm::MlKem512,
+                "test/data/kat/ml_kem_512.rsp",
+                "c70041a761e01cd6426fa60e9fd6a4412c2be817386c8d0f3334898082512782",
+            ),
+            (
+                KemAlgorithm::MlKem768,
+                "test/data/kat/ml_kem_768.rsp",
+                "5352539586b6c3df58be6158a6250aeff402bd73060b0a3de68850ac074c17c3",
+            ),
+            (
+                KemAlgorithm::MlKem1024,
+                "test/data/kat/ml_kem_1024.rsp",
+                "f580d851e5fb27e6876e5e203fa18be4cdbfd49e05d48fec3d3992c8f43a13e6",
+            ),
+        ] {
+            let contents = std::fs::read_to_string(file).unwrap();
+            assert_eq!(hex::encode(Sha256::digest(&contents)), sha256);
+            let rsp = RspFile::parse(&contents).unwrap();
+            assert_eq!(run_kem_kat(algorithm, &rsp).unwrap(), 1);
+        }
+
+        let rsp = RspFile::from_file("test/data/kat/ml_kem_512.rsp").unwrap();
+        assert_eq!(
+            run_kem_kat(KemAlgorithm::MlKem768, &rsp),
+            Err(QuantCryptError::KatMismatch {
+                count: 0,
+                field: "pk"
+            })
+        );
+        assert_eq!(
+            run_kem_kat(KemAlgorithm::MlKem768X25519, &rsp),
+            Err(QuantCryptError::NotImplemented)
+        );
+    }
+
+    /// The ML-KEM manager of an ACVP parameter set
+    fn ml_kem_manager(parameter_set: &serde_json::Value) -> KemManager {
+        let kem_type = match parameter_set.as_str().unwrap() {
+            "ML-KEM-512" => KemType::MlKem512,
+            "ML-KEM-768" => KemType::MlKem768,
+            "ML-KEM-1024" => KemType::MlKem1024,
+            other => panic!("Unexpected parameter set {}", other),
+        };
+        get_kem_manager(kem_type).unwrap()
+    }
+
+    #[test]
+    fn test_ml_kem_acvp_key_gen() {
+        // The NIST ACVP keyGen vectors, see test/data/kat/README.md
+        let vectors = std::fs::read_to_string("test/data/kat/ML-KEM-keyGen-FIPS203.json").unwrap();
+        let vectors: serde_json::Value = serde_json::from_str(&vectors).unwrap();
+        let mut count = 0;
+        for group in vectors["testGroups"].as_array().unwrap() {
+            let mut kem = ml_kem_manager(&group["parameterSet"]);
+            for test in group["tests"].as_array().unwrap() {
+                let field = |name: &str| hex::decode(test[name].as_str().unwrap()).unwrap();
+                // Key generation draws d, then z
+                let mut coins = PrefetchedRng {
+                    buf: [field("d"), field("z")].concat(),
+                    pos: 0,
+                };
+                let (ek, dk) = kem.key_gen_with_rng(&mut coins).unwrap();
+                assert_eq!(ek, field("ek"), "tcId {}", test["tcId"]);
+                assert_eq!(dk, field("dk"), "tcId {}", test["tcId"]);
+                count += 1;
+            }
+        }
+        assert_eq!(count, 75);
+    }
+
+    #[test]
+    fn test_ml_kem_acvp_encap_decap() {
+        // The NIST ACVP encapDecap vectors, see test/data/kat/README.md
+        let vectors =
+            std::fs::read_to_string("test/data/kat/ML-KEM-encapDecap-FIPS203.json").unwrap();
+        let vectors: serde_json::Value = serde_json::from_str(&vectors).unwrap();
+        let mut count = 0;
+        for group in vectors["testGroups"].as_array().unwrap() {
+            let mut kem = ml_kem_manager(&group["parameterSet"]);
+            for test in group["tests"].as_array().unwrap() {
+                let field = |name: &str| hex::decode(test[name].as_str().unwrap()).unwrap();
+                match group["function"].as_str().unwrap() {
+                    "encapsulation" => {
+                        let (k, c) = kem.encap_deterministic(&field("ek"), &field("m")).unwrap();
+                        assert_eq!(c, field("c"), "tcId {}", test["tcId"]);
+                        assert_eq!(k, field("k"), "tcId {}", test["tcId"]);
+                    }
+                    // Including modified ciphertexts, which are implicitly rejected
+                    "decapsulation" => {
+                        let dk = hex::decode(group["dk"].as_str().unwrap()).unwrap();
+                        let k = kem.decap(&dk, &field("c")).unwrap();
+                        assert_eq!(k, field("k"), "tcId {}", test["tcId"]);
+                    }
+                    other => panic!("Unexpected function {}", other),
+                }
+                count += 1;
+            }
+        }
+        assert_eq!(count, 105);
+    }
+
+    #[test]
+    fn test_bike_official_kat() {
+        // The first records of the round 4 KAT files, see test/data/kat/README.md
+        for (kem_type, file) in [
+            (KemType::BikeL1, "test/data/kat/bike_l1.rsp"),
+            (KemType::BikeL3, "test/data/kat/bike_l3.rsp"),
+            (KemType::BikeL5, "test/data/kat/bike_l5.rsp"),
+        ] {
+            let rsp = RspFile::from_file(file).unwrap();
+            assert!(!rsp.get_records().is_empty());
+            assert_eq!(
+                run_bike_kat(kem_type, &rsp).unwrap(),
+                rsp.get_records().len()
+            );
+        }
+
+        let rsp = RspFile::from_file("test/data/kat/bike_l1.rsp").unwrap();
+        assert_eq!(
+            run_bike_kat(KemType::BikeL3, &rsp),
+            Err(QuantCryptError::KatMismatch {
+                count: 0,
+                field: "pk"
+            })
+        );
+        assert_eq!(
+            run_bike_kat(KemType::BikeL1X25519, &rsp),
+            Err(QuantCryptError::NotImplemented)
+        );
+    }
+
+    #[test]
+    fn test_dsa_kat() {
+        for algorithm in [DsaAlgorithm::MlDsa44, DsaAlgorithm::SlhDsaShake128f] {
+            let seed = kat_seeds(1).remove(0);
+            let msg = b"known answer".to_vec();
+            let rsp = |pk: &[u8], sk: &[u8], sig: &[u8]| {
+                let sm = [sig, msg.as_slice()].concat();
+                RspFile::parse(&format!(
+                    "count = 0\nseed = {}\nmlen = {}\nmsg = {}\npk = {}\nsk = {}\nsmlen = {}\nsm = {}\n",
+                    hex::encode(&seed),
+                    msg.len(),
+                    hex::encode(&msg),
+                    hex::encode(pk),
+                    hex::encode(sk),
+                    sm.len(),
+                    hex::encode(&sm),
+                ))
+                .unwrap()
+            };
+
+            // Generate the expected keys and signature with the reference call
+            // pattern, signing with the underlying crates directly
+            let mut drbg = CtrDrbg::new(&seed.clone().try_into().unwrap(), None);
+            let (pk, sk, sig) = if let Some(dsa_type) = algorithm.get_prehash_dsa_type() {
+                let mut dsa = get_prehash_dsa_manager(dsa_type).unwrap();
+                let (pk, sk) = dsa
+                    .key_gen_with_rng(&mut PrefetchedRng::new(&mut drbg, 32))
+                    .unwrap();
+                let ml_sk =
+                    fips204::ml_dsa_44::PrivateKey::try_from_bytes(sk.clone().try_into().unwrap())
+                        .unwrap();
+                let mut zeros = PrefetchedRng {
+                    buf: vec![0u8; 32],
+                    pos: 0,
+                };
+                let sig = ml_sk.try_sign_with_rng(&mut zeros, &msg, &[]).unwrap();
+                (pk, sk, sig.to_vec())
+            } else {
+                let mut dsa = get_dsa_manager(algorithm.get_dsa_type().unwrap()).unwrap();
+                let mut seeds = PrefetchedRng::new(&mut drbg, 48);
+                let (pk, sk) = dsa.key_gen_with_rng(&mut seeds).unwrap();
+                let slh_sk = fips205::slh_dsa_shake_128f::PrivateKey::try_from_bytes(
+                    &sk.clone().try_into().unwrap(),
+                )
+                .unwrap();
+                let mut opt_rand = PrefetchedRng::new(&mut drbg, 16);
+                let sig = slh_sk
+                    .try_sign_with_rng(&mut opt_rand, &msg, &[], true)
+                    .unwrap();
+                (pk, sk, sig.to_vec())
+            };
+
+            assert_eq!(run_dsa_kat(algorithm, &rsp(&pk, &sk, &sig)).unwrap(), 1);
+
+            let mut bad_sig = sig.clone();
+            bad_sig[0] ^= 1;
+            assert_eq!(
+                run_dsa_kat(algorithm, &rsp(&pk, &sk, &bad_sig)),
+                Err(QuantCryptError::KatMismatch {
+                    count: 0,
+                    field: "sm"
+                })
+            );
+        }
+
+        // Not ML-DSA or SLH-DSA
+        let rsp = RspFile::parse("count = 0\nseed = 00\n").unwrap();
+        assert_eq!(
+            run_dsa_kat(DsaAlgorithm::MlDsa44Ed25519, &rsp),
+            Err(QuantCryptError::NotImplemented)
+        );
+    }
+
+    #[test]
+    fn test_ml_dsa_acvp_key_gen() {
+        // The NIST ACVP keyGen vectors, see test/data/kat/README.md
+        let vectors = std::fs::read_to_string("test/data/kat/ML-DSA-keyGen-FIPS204.json").unwrap();
+        let vectors: serde_json::Value = serde_json::from_str(&vectors).unwrap();
+        let mut count = 0;
+        for group in vectors["testGroups"].as_array().unwrap() {
+            let dsa_type = match group["parameterSet"].as_str().unwrap() {
+                "ML-DSA-44" => PrehashDsaType::MlDsa44,
+                "ML-DSA-65" => PrehashDsaType::MlDsa65,
+                "ML-DSA-87" => PrehashDsaType::MlDsa87,
+                other => panic!("Unexpected parameter set {}", other),
+            };
+            let mut dsa = get_prehash_dsa_manager(dsa_type).unwrap();
+            for test in group["tests"].as_array().unwrap() {
+                let field = |name: &str| hex::decode(test[name].as_str().unwrap()).unwrap();
+                let mut seed = PrefetchedRng {
+                    buf: field("seed"),
+                    pos: 0,
+                };
+                let (pk, sk) = dsa.key_gen_with_rng(&mut seed).unwrap();
+                assert_eq!(pk, field("pk"), "tcId {}", test["tcId"]);
+                assert_eq!(sk, field("sk"), "tcId {}", test["tcId"]);
+                count += 1;
+            }
+        }
+        assert_eq!(count, 75);
+    }
+}
@@ -0,0 +1,657 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::dsa::common::dsa_backend::{DsaBackend, PrehashDsaBackend};
+use crate::dsa::common::dsa_trait::Dsa;
+use crate::dsa::common::dsa_type::DsaType;
+use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+use crate::dsa::dsa_manager::{DsaManager, PrehashDsaManager};
+use crate::kem::common::kem_backend::KemBackend;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::kem_manager::KemManager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A thread-safe cache of managers keyed by algorithm type.
+///
+/// Managers are created on first use and cloned out of the pool on every
+/// subsequent request. Managers are cheap to clone, so this avoids
+/// reconstructing them (and any backend state) for repeated one-shot calls.
+struct ManagerPool<K, M> {
+    managers: OnceLock<RwLock<HashMap<K, M>>>,
+}
+
+impl<K: Eq + Hash + Clone, M: Clone> ManagerPool<K, M> {
+    const fn new() -> Self {
+        Self {
+            managers: OnceLock::new(),
+        }
+    }
+
+    /// Get a manager from the pool, creating it if it doesn't exist yet
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The algorithm type
+    /// * `create` - A function to create the manager on a cache miss
+    ///
+    /// # Returns
+    ///
+    /// A clone of the pooled manager
+    fn get_or_create(&self, key: K, create: impl FnOnce(K) -> Result<M>) -> Result<M> {
+        let managers = self.managers.get_or_init(|| RwLock::new(HashMap::new()));
+
+        // A poisoned lock only means another thread panicked while holding it.
+        // The map itself is always in a consistent state, so keep using it.
+        if let Some(manager) = managers.read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            return Ok(manager.clone());
+        }
+
+        let manager = create(key.clone())?;
+        managers
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_insert_with(|| manager.clone());
+        Ok(manager)
+    }
+
+    /// Drop all pooled managers, so that they are created again on next use
+    fn clear(&self) {
+        if let Some(managers) = self.managers.get() {
+            managers.write().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+    }
+}
+
+/// A thread-safe map of the external backends registered for algorithm types
+struct BackendRegistry<K, B: ?Sized> {
+    backends: OnceLock<RwLock<HashMap<K, Arc<B>>>>,
+}
+
+impl<K: Eq + Hash, B: ?Sized> BackendRegistry<K, B> {
+    const fn new() -> Self {
+        Self {
+            backends: OnceLock::new(),
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<Arc<B>> {
+        self.backends
+            .get()?
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .cloned()
+    }
+
+    fn insert(&self, key: K, backend: Arc<B>) -> Option<Arc<B>> {
+        self.backends
+            .get_or_init(|| RwLock::new(HashMap::new()))
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, backend)
+    }
+
+    fn remove(&self, key: &K) -> Option<Arc<B>> {
+        self.backends
+            .get()?
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key)
+    }
+}
+
+/// A thread-safe set of the algorithm types disabled after failing a backend probe
+struct DisabledSet<K> {
+    types: OnceLock<RwLock<HashSet<K>>>,
+}
+
+impl<K: Eq + Hash> DisabledSet<K> {
+    const fn new() -> Self {
+        Self {
+            types: OnceLock::new(),
+        }
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.types.get().is_some_and(|types| {
+            types
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains(key)
+        })
+    }
+
+    fn set(&self, key: K, disabled: bool) {
+        let mut types = self
+            .types
+            .get_or_init(|| RwLock::new(HashSet::new()))
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        if disabled {
+            types.insert(key);
+        } else {
+            types.remove(&key);
+        }
+    }
+
+    fn clear(&self) {
+        if let Some(types) = self.types.get() {
+            types.write().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+    }
+}
+
+static KEM_BACKENDS: BackendRegistry<KemType, dyn KemBackend> = BackendRegistry::new();
+static DSA_BACKENDS: BackendRegistry<DsaType, dyn DsaBackend> = BackendRegistry::new();
+static PREHASH_DSA_BACKENDS: BackendRegistry<PrehashDsaType, dyn PrehashDsaBackend> =
+    BackendRegistry::new();
+
+static DISABLED_KEMS: DisabledSet<KemType> = DisabledSet::new();
+static DISABLED_DSAS: DisabledSet<DsaType> = DisabledSet::new();
+static DISABLED_PREHASH_DSAS: DisabledSet<PrehashDsaType> = DisabledSet::new();
+
+static KEM_MANAGERS: ManagerPool<KemType, KemManager> = ManagerPool::new();
+static DSA_MANAGERS: ManagerPool<DsaType, DsaManager> = ManagerPool::new();
+static PREHASH_DSA_MANAGERS: ManagerPool<PrehashDsaType, PrehashDsaManager> = ManagerPool::new();
+
+/// Get a pooled KEM manager
+///
+/// # Arguments
+///
+/// * `kem_type` - The type of KEM
+///
+/// # Returns
+///
+/// The KEM manager
+///
+/// # Errors
+///
+/// `QuantCryptError::BackendUnavailable` if the KEM type was disabled by a backend probe
+pub(crate) fn get_kem_manager(kem_type: KemType) -> Result<KemManager> {
+    check_enabled(&DISABLED_KEMS, &kem_type)?;
+    KEM_MANAGERS.get_or_create(kem_type, KemManager::new)
+}
+
+/// Get a pooled KEM manager by OID
+///
+/// # Arguments
+///
+/// * `oid` - The OID of the KEM
+///
+/// # Returns
+///
+/// The KEM manager
+pub(crate) fn get_kem_manager_from_oid(oid: &str) -> Result<KemManager> {
+    let kem_type = KemType::from_oid(oid).ok_or(QuantCryptError::InvalidOid)?;
+    get_kem_manager(kem_type)
+}
+
+/// Get a pooled DSA manager
+///
+/// # Arguments
+///
+/// * `dsa_type` - The type of DSA
+///
+/// # Returns
+///
+/// The DSA manager
+///
+/// # Errors
+///
+/// `QuantCryptError::BackendUnavailable` if the DSA type was disabled by a backend probe
+pub(crate) fn get_dsa_manager(dsa_type: DsaType) -> Result<DsaManager> {
+    check_enabled(&DISABLED_DSAS, &dsa_type)?;
+    DSA_MANAGERS.get_or_create(dsa_type, DsaManager::new)
+}
+
+/// Get a pooled DSA manager by OID
+///
+/// # Arguments
+///
+/// * `oid` - The OID of the DSA
+///
+/// # Returns
+///
+/// The DSA manager
+pub(crate) fn get_dsa_manager_from_oid(oid: &str) -> Result<DsaManager> {
+    let dsa_type = DsaType::from_oid(oid).ok_or(QuantCryptError::InvalidOid)?;
+    get_dsa_manager(dsa_type)
+}
+
+/// Get a pooled pre-hash DSA manager
+///
+/// # Arguments
+///
+/// * `dsa_type` - The type of pre-hash DSA
+///
+/// # Returns
+///
+/// The pre-hash DSA manager
+///
+/// # Errors
+///
+/// `QuantCryptError::BackendUnavailable` if the DSA type was disabled by a backend probe
+pub(crate) fn get_prehash_dsa_manager(dsa_type: PrehashDsaType) -> Result<PrehashDsaManager> {
+    check_enabled(&DISABLED_PREHASH_DSAS, &dsa_type)?;
+    PREHASH_DSA_MANAGERS.get_or_create(dsa_type, PrehashDsaManager::new)
+}
+
+/// Fail with `QuantCryptError::BackendUnavailable` if a type was disabled by a backend probe
+fn check_enabled<K: Eq + Hash + std::fmt::Debug>(disabled: &DisabledSet<K>, key: &K) -> Result<()> {
+    if disabled.contains(key) {
+        return Err(QuantCryptError::BackendUnavailable {
+            component: format!("{:?}", key),
+        });
+    }
+    Ok(())
+}
+
+/// Enable or disable a KEM type in every layer using pooled managers
+pub(crate) fn set_kem_disabled(kem_type: KemType, disabled: bool) {
+    DISABLED_KEMS.set(kem_type, disabled);
+}
+
+/// Enable or disable a DSA type in every layer using pooled managers
+pub(crate) fn set_dsa_disabled(dsa_type: DsaType, disabled: bool) {
+    DISABLED_DSAS.set(dsa_type, disabled);
+}
+
+/// Enable or disable a pre-hash DSA type in every layer using pooled managers
+pub(crate) fn set_prehash_dsa_disabled(dsa_type: PrehashDsaType, disabled: bool) {
+    DISABLED_PREHASH_DSAS.set(dsa_type, disabled);
+}
+
+/// Enable again all the types disabled by backend probes
+pub(crate) fn clear_disabled() {
+    DISABLED_KEMS.clear();
+    DISABLED_DSAS.clear();
+    DISABLED_PREHASH_DSAS.clear();
+}
+
+/// Drop the pooled managers of all types. Composite managers hold their
+/// component managers, so a change of any backend can affect any pool.
+fn clear_pools() {
+    KEM_MANAGERS.clear();
+    DSA_MANAGERS.clear();
+    PREHASH_DSA_MANAGERS.clear();
+}
+
+/// Register an external implementation of a KEM, such as a vendor HSM
+///
+/// The backend is used for the KEM type returned by its `get_kem_info`, in place
+/// of the built-in implementation, and enables the type again if a backend probe
+/// disabled it. This applies to every layer that creates KEM
+/// managers: keys, certificates, CMS, HPKE and composite KEMs using the type as
+/// a component.
+///
+/// # Arguments
+///
+/// * `backend` - The backend to register
+///
+/// # Returns
+///
+/// The backend previously registered for the same KEM type, if any
+pub fn register_kem_backend(backend: Arc<dyn KemBackend>) -> Option<Arc<dyn KemBackend>> {
+    let kem_type = backend.get_kem_info().kem_type;
+    let previous = KEM_BACKENDS.insert(kem_type.clone(), backend);
+    DISABLED_KEMS.set(kem_type, false);
+    clear_pools();
+    previous
+}
+
+/// Remove the external implementation registered for a KEM type, restoring
+/// the built-in implementation
+///
+/// # Arguments
+///
+/// * `kem_type` - The type of KEM
+///
+/// # Returns
+///
+/// The backend that was registered, if any
+pub fn unregister_kem_backend(kem_type: KemType) -> Option<Arc<dyn KemBackend>> {
+    let previous = KEM_BACKENDS.remove(&kem_type);
+    clear_pools();
+    previous
+}
+
+/// Get the external implementation registered for a KEM type
+///
+/// # Arguments
+///
+/// * `kem_type` - The type of KEM
+///
+/// # Returns
+///
+/// The registered backend, or `None` if the built-in implementation is used
+pub(crate) fn get_kem_backend(kem_type: &KemType) -> Option<Arc<dyn KemBackend>> {
+    KEM_BACKENDS.get(kem_type)
+}
+
+/// Register an external implementation of a DSA, such as a vendor HSM
+///
+/// The backend is used for the DSA type returned by its `get_dsa_info`, in place
+/// of the built-in implementation, including in composite signatures. It enables
+/// the type again if a backend probe disabled it.
+///
+/// # Arguments
+///
+/// * `backend` - The backend to register
+///
+/// # Returns
+///
+/// The backend previously registered for the same DSA type, if any
+pub fn register_dsa_backend(backend: Arc<dyn DsaBackend>) -> Option<Arc<dyn DsaBackend>> {
+    let dsa_type = backend.get_dsa_info().dsa_type;
+    let previous = DSA_BACKENDS.insert(dsa_type.clone(), backend);
+    DISABLED_DSAS.set(dsa_type, false);
+    clear_pools();
+    previous
+}
+
+/// Remove the external implementation registered for a DSA type, restoring
+/// the built-in implementation
+///
+/// # Arguments
+///
+/// * `dsa_type` - The type of DSA
+///
+/// # Returns
+///
+/// The backend that was registered, if any
+pub fn unregister_dsa_backend(dsa_type: DsaType) -> Option<Arc<dyn DsaBackend>> {
+    let previous = DSA_BACKENDS.remove(&dsa_type);
+    clear_pools();
+    previous
+}
+
+/// Get the external implementation registered for a DSA type
+///
+/// # Arguments
+///
+/// * `dsa_type` - The type of DSA
+///
+/// # Returns
+///
+/// The registered backend, or `None` if the built-in implementation is used
+pub(crate) fn get_dsa_backend(dsa_type: &DsaType) -> Option<Arc<dyn DsaBackend>> {
+    DSA_BACKENDS.get(dsa_type)
+}
+
+/// Register an external implementation of a pre-hash DSA, such as a vendor HSM
+///
+/// The backend is used for the DSA type returned by its `get_dsa_info`, in place
+/// of the built-in implementation, including in composite signatures. It enables
+/// the type again if a backend probe disabled it.
+///
+/// # Arguments
+///
+/// * `backend` - The backend to register
+///
+/// # Returns
+///
+/// The backend previously registered for the same DSA type, if any
+pub fn register_prehash_dsa_backend(
+    backend: Arc<dyn PrehashDsaBackend>,
+) -> Option<Arc<dyn PrehashDsaBackend>> {
+    let dsa_type = backend.get_dsa_info().dsa_type;
+    let previous = PREHASH_DSA_BACKENDS.insert(dsa_type.clone(), backend);
+    DISABLED_PREHASH_DSAS.set(dsa_type, false);
+    clear_pools();
+    previous
+}
+
+/// Remove the external implementation registered for a pre-hash DSA type,
+/// restoring the built-in implementation
+///
+/// # Arguments
+///
+/// * `dsa_type` - The type of pre-hash DSA
+///
+/// # Returns
+///
+/// The backend that was registered, if any
+pub fn unregister_prehash_dsa_backend(
+    dsa_type: PrehashDsaType,
+) -> Option<Arc<dyn PrehashDsaBackend>> {
+    let previous = PREHASH_DSA_BACKENDS.remove(&dsa_type);
+    clear_pools();
+    previous
+}
+
+/// Get the external implementation registered for a pre-hash DSA type
+///
+/// # Arguments
+///
+/// * `dsa_type` - The type of pre-hash DSA
+///
+/// # Returns
+///
+/// The registered backend, or `None` if the built-in implementation is used
+pub(crate) fn get_prehash_dsa_backend(
+    dsa_type: &PrehashDsaType,
+) -> Option<Arc<dyn PrehashDsaBackend>> {
+    PREHASH_DSA_BACKENDS.get(dsa_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsa::common::config::oids::Oid;
+    #[cfg(feature = "openssl")]
+    use crate::dsa::common::prehash_dsa_info::PrehashDsaInfo;
+    #[cfg(feature = "openssl")]
+    use crate::dsa::ml_dsa::MlDsaManager;
+    #[cfg(feature = "openssl")]
+    use crate::hpke::suite::{HpkeAead, HpkeKdf, HpkeKem, HpkeSuite};
+    use crate::kem::common::kem_info::KemInfo;
+    use crate::kem::ml_kem::MlKemManager;
+    use rand_core::CryptoRngCore;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A backend standing in for an HSM, counting the operations it performs
+    struct CountingKem {
+        kem: MlKemManager,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl KemBackend for CountingKem {
+        fn get_kem_info(&self) -> KemInfo {
+            self.kem.get_kem_info()
+        }
+
+        fn key_gen(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.kem.clone().key_gen()
+        }
+
+        fn key_gen_with_rng(&self, mut rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.kem.clone().key_gen_with_rng(&mut rng)
+        }
+
+        fn encap(&self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.kem.clone().encap(pk)
+        }
+
+        fn decap(&self, sk: &[u8], ct: &[u8]) -> Result<Vec<u8>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.kem.decap(sk, ct)
+        }
+    }
+
+    #[cfg(feature = "openssl")]
+    struct CountingDsa {
+        dsa: MlDsaManager,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[cfg(feature = "openssl")]
+    impl PrehashDsaBackend for CountingDsa {
+        fn get_dsa_info(&self) -> PrehashDsaInfo {
+            self.dsa.get_dsa_info()
+        }
+
+        fn key_gen(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.dsa.clone().key_gen()
+        }
+
+        fn key_gen_with_rng(&self, mut rng: &mut dyn CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.dsa.clone().key_gen_with_rng(&mut rng)
+        }
+
+        fn sign_with_ctx(&self, sk: &[u8], msg: &[u8], ctx: Option<&[u8]>) -> Result<Vec<u8>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.dsa.sign_with_ctx(sk, msg, ctx)
+        }
+
+        fn verify_with_ctx(
+            &self,
+            pk: &[u8],
+            msg: &[u8],
+            signature: &[u8],
+            ctx: Option<&[u8]>,
+        ) -> Result<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.dsa.verify_with_ctx(pk, msg, signature, ctx)
+        }
+
+        fn get_public_key(&self, sk: &[u8]) -> Result<Vec<u8>> {
+            self.dsa.get_public_key(sk)
+        }
+    }
+
+    #[test]
+    fn test_manager_pool() {
+        let pool: ManagerPool<u8, u32> = ManagerPool::new();
+        let mut calls = 0;
+        let v = pool
+            .get_or_create(1, |k| {
+                calls += 1;
+                Ok(k as u32 * 10)
+            })
+            .unwrap();
+        assert_eq!(v, 10);
+        let v = pool
+            .get_or_create(1, |_| {
+                calls += 1;
+                Ok(0)
+            })
+            .unwrap();
+        assert_eq!(v, 10);
+        assert_eq!(calls, 1);
+
+        assert!(pool
+            .get_or_create(2, |_| Err(QuantCryptError::InvalidOid))
+            .is_err());
+    }
+
+    #[test]
+    fn test_disabled_set() {
+        let disabled: DisabledSet<KemType> = DisabledSet::new();
+        assert!(check_enabled(&disabled, &KemType::MlKem512).is_ok());
+        disabled.set(KemType::MlKem512, true);
+        assert!(matches!(
+            check_enabled(&disabled, &KemType::MlKem512),
+            Err(QuantCryptError::BackendUnavailable { .. })
+        ));
+        assert!(check_enabled(&disabled, &KemType::MlKem768).is_ok());
+        disabled.set(KemType::MlKem512, false);
+        assert!(check_enabled(&disabled, &KemType::MlKem512).is_ok());
+        disabled.set(KemType::MlKem768, true);
+        disabled.clear();
+        assert!(check_enabled(&disabled, &KemType::MlKem768).is_ok());
+    }
+
+    #[test]
+    fn test_pooled_managers() {
+        let kem = get_kem_manager(KemType::MlKem512).unwrap();
+        assert_eq!(kem.get_kem_info().kem_type, KemType::MlKem512);
+        assert!(get_kem_manager_from_oid("1.2.3").is_err());
+
+        let dsa_type = DsaType::SlhDsaSha2_128f;
+        let dsa = get_dsa_manager_from_oid(&dsa_type.get_oid()).unwrap();
+        assert_eq!(dsa.get_dsa_info().dsa_type, dsa_type);
+
+        let dsa = get_prehash_dsa_manager(PrehashDsaType::MlDsa44).unwrap();
+        assert_eq!(dsa.get_dsa_info().dsa_type, PrehashDsaType::MlDsa44);
+
+        // Concurrent access from several threads
+        let handles: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(|| get_kem_manager(KemType::MlKem768).is_ok()))
+            .collect();
+        for h in handles {
+            assert!(h.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_registered_backends() {
+        // The backends delegate to the built-in managers, so tests running
+        // concurrently with this one are unaffected by the registration
+        let kem_calls = Arc::new(AtomicUsize::new(0));
+        register_kem_backend(Arc::new(CountingKem {
+            kem: MlKemManager::new(KemType::MlKem1024).unwrap(),
+            calls: kem_calls.clone(),
+        }));
+
+        // Directly, as a component of a composite KEM and through HPKE
+        let mut kem = get_kem_manager(KemType::MlKem1024).unwrap();
+        assert!(matches!(kem, KemManager::Custom(_)));
+        let (pk, sk) = kem.key_gen().unwrap();
+        let (ss, ct) = kem.encap(&pk).unwrap();
+        assert_eq!(kem.decap(&sk, &ct).unwrap(), ss);
+        assert!(kem_calls.load(Ordering::SeqCst) >= 3);
+
+        let before = kem_calls.load(Ordering::SeqCst);
+        let mut composite = get_kem_manager(KemType::MlKem1024P384).unwrap();
+        let (pk, sk) = composite.key_gen().unwrap();
+        let (ss, ct) = composite.encap(&pk).unwrap();
+        assert_eq!(composite.decap(&sk, &ct).unwrap(), ss);
+        assert!(kem_calls.load(Ordering::SeqCst) >= before + 3);
+
+        // AES-GCM and Ed448 need OpenSSL
+        #[cfg(feature = "openssl")]
+        {
+            let before = kem_calls.load(Ordering::SeqCst);
+            let suite =
+                HpkeSuite::new(HpkeKem::MlKem1024, HpkeKdf::HkdfSha256, HpkeAead::Aes128Gcm);
+            let (pk, sk) = suite.generate_key_pair().unwrap();
+            let (enc, ct) = suite.seal_base(&pk, b"info", b"aad", b"hello").unwrap();
+            let pt = suite.open_base(&enc, &sk, b"info", b"aad", &ct).unwrap();
+            assert_eq!(pt, b"hello");
+            assert!(kem_calls.load(Ordering::SeqCst) >= before + 3);
+        }
+
+        assert!(unregister_kem_backend(KemType::MlKem1024).is_some());
+        assert!(matches!(
+            get_kem_manager(KemType::MlKem1024).unwrap(),
+            KemManager::Ml(_)
+        ));
+
+        #[cfg(feature = "openssl")]
+        {
+            let dsa_calls = Arc::new(AtomicUsize::new(0));
+            register_prehash_dsa_backend(Arc::new(CountingDsa {
+                dsa: MlDsaManager::new(PrehashDsaType::MlDsa87).unwrap(),
+                calls: dsa_calls.clone(),
+            }));
+            let mut composite = get_prehash_dsa_manager(PrehashDsaType::MlDsa87Ed448).unwrap();
+            let (pk, sk) = composite.key_gen().unwrap();
+            let sig = composite.sign(&sk, b"message").unwrap();
+            assert!(composite.verify(&pk, b"message", &sig).unwrap());
+            assert!(dsa_calls.load(Ordering::SeqCst) >= 3);
+            assert!(unregister_prehash_dsa_backend(PrehashDsaType::MlDsa87).is_some());
+            assert!(unregister_prehash_dsa_backend(PrehashDsaType::MlDsa87).is_none());
+        }
+    }
+}
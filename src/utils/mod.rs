@@ -1 +1,25 @@
+pub mod backend_probe;
+pub mod blocking_pool;
+pub mod cbor;
+pub mod clock_skew;
+pub mod ct_codec;
+pub mod ct_eq;
+pub mod ctr_drbg;
+#[cfg(feature = "pure-rust")]
+pub mod curve25519;
+// Ed448 is only used by the EdDSA manager, which needs OpenSSL
+#[cfg_attr(not(feature = "openssl"), allow(dead_code))]
+pub mod curve448;
+pub mod deprecation;
+pub mod entropy;
+pub mod fips;
+pub mod kat;
+pub mod manager_pool;
+#[cfg(feature = "pure-rust")]
+pub mod nist_curves;
+#[cfg(feature = "openssl")]
 pub mod openssl_utils;
+#[cfg(all(test, feature = "liboqs-interop"))]
+mod oqs_interop;
+pub mod resource_limits;
+pub mod verification_report;
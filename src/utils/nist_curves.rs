@@ -0,0 +1,234 @@
+use p256::elliptic_curve::ecdh::diffie_hellman;
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use p256::elliptic_curve::{AffinePoint, CurveArithmetic, FieldBytes, FieldBytesSize};
+use p256::elliptic_curve::{PublicKey, SecretKey};
+use p256::NistP256;
+use p384::NistP384;
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+use crate::utils::entropy::get_key_gen_rng;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// A NIST curve computed in pure Rust
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NistCurve {
+    /// P-256, with the p256 crate
+    P256,
+    /// P-384, with the p384 crate
+    P384,
+}
+
+// Keys and points are encoded as by OpenSSL: the secret key is the scalar,
+// big-endian and padded to the byte length of the order, and the public key
+// and ciphertext are SEC 1 points. The shared secret is the x-coordinate.
+
+fn secret_key<C: CurveArithmetic>(sk: &[u8]) -> Result<SecretKey<C>> {
+    if sk.len() != FieldBytes::<C>::default().len() {
+        return Err(QuantCryptError::InvalidPrivateKey);
+    }
+    SecretKey::<C>::from_slice(sk).map_err(|_| QuantCryptError::InvalidPrivateKey)
+}
+
+fn public_key<C>(sk: &[u8]) -> Result<Vec<u8>>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let pk = secret_key::<C>(sk)?.public_key();
+    Ok(pk.to_encoded_point(false).as_bytes().to_vec())
+}
+
+fn key_gen<C>(rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    // Like OpenSSL, draw candidates of the byte length of the order until one
+    // is between 1 and n - 1, so the same RNG gives the same key with both
+    let mut candidate = Zeroizing::new(vec![0u8; FieldBytes::<C>::default().len()]);
+    let sk = loop {
+        rng.fill_bytes(&mut candidate);
+        if let Ok(sk) = SecretKey::<C>::from_slice(&candidate) {
+            break sk;
+        }
+    };
+    let pk = sk.public_key().to_encoded_point(false).as_bytes().to_vec();
+    Ok((pk, sk.to_bytes().to_vec()))
+}
+
+fn derive<C>(sk: &[u8], pk: &[u8]) -> Result<Vec<u8>>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let sk = secret_key::<C>(sk)?;
+    let pk = PublicKey::<C>::from_sec1_bytes(pk).map_err(|_| QuantCryptError::InvalidPublicKey)?;
+    let ss = diffie_hellman(sk.to_nonzero_scalar(), pk.as_affine());
+    Ok(ss.raw_secret_bytes().to_vec())
+}
+
+fn convert_point<C>(point: &[u8], compressed: bool) -> Result<Vec<u8>>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let point =
+        PublicKey::<C>::from_sec1_bytes(point).map_err(|_| QuantCryptError::InvalidPublicKey)?;
+    Ok(point.to_encoded_point(compressed).as_bytes().to_vec())
+}
+
+/// Compute the public key of a secret key in pure Rust
+///
+/// # Arguments
+///
+/// * `curve` - The curve
+/// * `sk` - The secret key, of the byte length of the order
+///
+/// # Returns
+///
+/// The public key as an uncompressed point
+pub fn nist_public_key(curve: NistCurve, sk: &[u8]) -> Result<Vec<u8>> {
+    match curve {
+        NistCurve::P256 => public_key::<NistP256>(sk),
+        NistCurve::P384 => public_key::<NistP384>(sk),
+    }
+}
+
+/// Generate a key pair in pure Rust
+///
+/// # Arguments
+///
+/// * `curve` - The curve
+/// * `rng` - The random number generator
+///
+/// # Returns
+///
+/// A tuple containing the public and secret keys (pk, sk), with pk as an uncompressed point
+pub fn nist_key_gen(curve: NistCurve, rng: &mut impl CryptoRngCore) -> Result<(Vec<u8>, Vec<u8>)> {
+    match curve {
+        NistCurve::P256 => key_gen::<NistP256>(rng),
+        NistCurve::P384 => key_gen::<NistP384>(rng),
+    }
+}
+
+/// Compute the ECDH shared secret of a secret key and a public key in pure Rust
+///
+/// # Arguments
+///
+/// * `curve` - The curve
+/// * `sk` - The secret key
+/// * `pk` - The public key of the peer, compressed or uncompressed
+///
+/// # Returns
+///
+/// The shared secret, the x-coordinate of the shared point
+///
+/// # Errors
+///
+/// `QuantCryptError::InvalidPublicKey` if the public key is not a point on the curve
+pub fn nist_derive(curve: NistCurve, sk: &[u8], pk: &[u8]) -> Result<Vec<u8>> {
+    match curve {
+        NistCurve::P256 => derive::<NistP256>(sk, pk),
+        NistCurve::P384 => derive::<NistP384>(sk, pk),
+    }
+}
+
+/// Encapsulate to a public key in pure Rust, with an ephemeral key drawn from the
+/// health tested system entropy
+///
+/// # Arguments
+///
+/// * `curve` - The curve
+/// * `pk` - The public key to encapsulate to
+///
+/// # Returns
+///
+/// A tuple containing the shared secret and ciphertext (ss, ct), with ct as an uncompressed point
+pub fn nist_encap(curve: NistCurve, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    let (ct, esk) = nist_key_gen(curve, &mut get_key_gen_rng()?)?;
+    Ok((nist_derive(curve, &esk, pk)?, ct))
+}
+
+/// Convert an encoded point to another encoding
+///
+/// # Arguments
+///
+/// * `curve` - The curve
+/// * `point` - The point, compressed or uncompressed
+/// * `compressed` - True to convert to the compressed encoding, false for the uncompressed one
+///
+/// # Returns
+///
+/// The point in the requested encoding
+pub fn nist_convert_point(curve: NistCurve, point: &[u8], compressed: bool) -> Result<Vec<u8>> {
+    match curve {
+        NistCurve::P256 => convert_point::<NistP256>(point, compressed),
+        NistCurve::P384 => convert_point::<NistP384>(point, compressed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn test_nist_curves() {
+        for (curve, len) in [(NistCurve::P256, 32), (NistCurve::P384, 48)] {
+            let mut rng = ChaCha20Rng::from_seed([3u8; 32]);
+            let (pk, sk) = nist_key_gen(curve, &mut rng).unwrap();
+            assert_eq!(sk.len(), len);
+            assert_eq!(pk.len(), 2 * len + 1);
+            assert_eq!(nist_public_key(curve, &sk).unwrap(), pk);
+
+            let (ss, ct) = nist_encap(curve, &pk).unwrap();
+            assert_eq!(nist_derive(curve, &sk, &ct).unwrap(), ss);
+
+            // Compressed points are accepted
+            let compressed = nist_convert_point(curve, &ct, true).unwrap();
+            assert_eq!(compressed.len(), len + 1);
+            assert_eq!(nist_derive(curve, &sk, &compressed).unwrap(), ss);
+            assert_eq!(nist_convert_point(curve, &compressed, false).unwrap(), ct);
+
+            // The point at infinity and points off the curve are not
+            assert!(nist_derive(curve, &sk, &[0u8]).is_err());
+            let mut off_curve = ct.clone();
+            off_curve[len] ^= 1;
+            assert!(nist_derive(curve, &sk, &off_curve).is_err());
+            assert!(nist_public_key(curve, &vec![0u8; len]).is_err());
+        }
+    }
+
+    #[cfg(feature = "openssl")]
+    #[test]
+    fn test_nist_curves_match_openssl() {
+        use crate::utils::openssl_utils::{
+            decaps_ec_based, get_key_pair_ec_based_with_rng, get_pk_from_sk_ec_based,
+        };
+        use openssl::nid::Nid;
+
+        for (curve, nid) in [
+            (NistCurve::P256, Nid::X9_62_PRIME256V1),
+            (NistCurve::P384, Nid::SECP384R1),
+        ] {
+            // The same RNG gives the same key
+            let (pk, sk) = nist_key_gen(curve, &mut ChaCha20Rng::from_seed([5u8; 32])).unwrap();
+            let (pk_openssl, sk_openssl) =
+                get_key_pair_ec_based_with_rng(&mut ChaCha20Rng::from_seed([5u8; 32]), nid)
+                    .unwrap();
+            assert_eq!((pk.clone(), sk.clone()), (pk_openssl, sk_openssl));
+            assert_eq!(get_pk_from_sk_ec_based(&sk, nid).unwrap(), pk);
+
+            let (ss, ct) = nist_encap(curve, &pk).unwrap();
+            assert_eq!(decaps_ec_based(&sk, &ct, nid).unwrap(), ss);
+        }
+    }
+}
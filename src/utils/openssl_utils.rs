@@ -1,18 +1,48 @@
 use openssl::bn::{BigNum, BigNumContext, BigNumRef};
 use openssl::derive::Deriver;
 use openssl::ec::{EcGroup, EcKey, EcPoint};
+use openssl::ecdsa::EcdsaSig;
 use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
 use openssl::pkey::{Id, PKey, Private, Public};
 use rand_core::CryptoRngCore;
 use std::error;
+use std::sync::OnceLock;
+
+use crate::utils::entropy::get_key_gen_rng;
 
 // Change the alias to use `Box<dyn error::Error>`.
 type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
 
+/// Check if the linked OpenSSL supports an elliptic curve
+///
+/// Some distribution builds of OpenSSL leave out the Brainpool curves. The
+/// check is performed once per curve by looking up its group.
+///
+/// # Arguments
+///
+/// * `nid` - The NID of the curve
+///
+/// # Returns
+///
+/// False for a Brainpool curve if OpenSSL lacks it, true otherwise
+pub fn openssl_supports_curve(nid: Nid) -> bool {
+    static BRAINPOOL_P256R1: OnceLock<bool> = OnceLock::new();
+    static BRAINPOOL_P384R1: OnceLock<bool> = OnceLock::new();
+    match nid {
+        Nid::BRAINPOOL_P256R1 => {
+            *BRAINPOOL_P256R1.get_or_init(|| EcGroup::from_curve_name(nid).is_ok())
+        }
+        Nid::BRAINPOOL_P384R1 => {
+            *BRAINPOOL_P384R1.get_or_init(|| EcGroup::from_curve_name(nid).is_ok())
+        }
+        _ => true,
+    }
+}
+
 /// Encapsulate a public key using the ECDH key exchange method.
-/// This method is used for curves supported by the `EcKey` API
-/// and uses OpenSSL's RNG for the ephemeral key generation.
+/// This method is used for curves supported by the `EcKey` API.
+/// The ephemeral key is drawn from the health tested system entropy.
 ///
 /// # Arguments
 ///
@@ -35,7 +65,8 @@ pub fn encaps_ec_based(pk: &[u8], nid: Nid) -> Result<(Vec<u8>, Vec<u8>)> {
 
     let (ss, ct) = {
         // Create a new ephemeral key
-        let ephemeral_key = EcKey::generate(&group)?;
+        let (_, esk) = get_key_pair_ec_based(nid)?;
+        let ephemeral_key = get_ec_key_from_sk(nid, &esk)?;
         let es = PKey::from_ec_key(ephemeral_key.clone())?;
         let mut deriver = Deriver::new(&es)?;
         deriver.set_peer(&pk)?;
@@ -53,7 +84,7 @@ pub fn encaps_ec_based(pk: &[u8], nid: Nid) -> Result<(Vec<u8>, Vec<u8>)> {
 
 /// Encapsulate a public key using PKey API
 /// This method is used for X25519 and X448 which are not supported by the `EcKey` API.
-/// It uses OpenSSL's RNG for the ephemeral key generation.
+/// The ephemeral key is drawn from the health tested system entropy.
 ///
 /// # Arguments
 ///
@@ -276,6 +307,29 @@ fn get_pk_sk_from_bignum_ec_based(
     Ok((pks, sks))
 }
 
+/// Convert an encoded EC point to another point conversion form
+///
+/// # Arguments
+///
+/// * `point` - The point, compressed or uncompressed
+/// * `nid` - The curve of the point
+/// * `compressed` - True to convert to the compressed form, false for the uncompressed form
+///
+/// # Returns
+///
+/// The point in the requested form
+pub fn convert_ec_point(point: &[u8], nid: Nid, compressed: bool) -> Result<Vec<u8>> {
+    let mut ctx = BigNumContext::new()?;
+    let group = EcGroup::from_curve_name(nid)?;
+    let point = EcPoint::from_bytes(&group, point, &mut ctx)?;
+    let form = if compressed {
+        openssl::ec::PointConversionForm::COMPRESSED
+    } else {
+        openssl::ec::PointConversionForm::UNCOMPRESSED
+    };
+    Ok(point.to_bytes(&group, form, &mut ctx)?)
+}
+
 /// Get a key pair using the OpenSSL library (default RNG) for an EC curve
 /// that is supported by the `EcKey` API.
 ///
@@ -288,11 +342,7 @@ fn get_pk_sk_from_bignum_ec_based(
 /// A tuple containing the public and secret keys (pk, sk) with pk as an uncompressed point
 /// and sk as a field element bytes
 pub fn get_key_pair_ec_based(nid: Nid) -> Result<(Vec<u8>, Vec<u8>)> {
-    let mut ctx = BigNumContext::new()?;
-    let group = EcGroup::from_curve_name(nid)?;
-    let ec_key = EcKey::generate(&group)?;
-    let private_key_bn = ec_key.private_key();
-    get_pk_sk_from_bignum_ec_based(&mut ctx, private_key_bn, &group)
+    get_key_pair_ec_based_with_rng(&mut get_key_gen_rng()?, nid)
 }
 
 /// Get an EC key pair but specify the RNG to use
@@ -353,7 +403,7 @@ fn compute_public_key(
 ///
 /// This is used for X448, and X25519 which are not supported by the `EcKey` API.
 ///
-/// The default OpenSSL RNG is used.
+/// The secret key is drawn from the health tested system entropy.
 ///
 /// # Arguments
 ///
@@ -363,28 +413,18 @@ fn compute_public_key(
 ///
 /// A tuple containing the public and secret keys (pk, sk) in DER format
 pub fn get_key_pair_pkey_based(id: Id) -> Result<(Vec<u8>, Vec<u8>)> {
-    let sk = match id {
-        Id::X448 => PKey::generate_x448()?,
-        Id::X25519 => PKey::generate_x25519()?,
-        Id::ED25519 => PKey::generate_ed25519()?,
-        Id::ED448 => PKey::generate_ed448()?,
-        _ => panic!("Unsupported ID"),
-    };
-
-    let pk = sk.raw_public_key()?;
-    let sk = sk.raw_private_key()?;
-    Ok((pk, sk))
+    get_keypair_pkey_based_with_rng(&mut get_key_gen_rng()?, id)
 }
 
-/// Get an elliptic curve key pair using a PKey based method. This is used for X448, and X25519
-/// which are not supported by the `EcKey` API.
+/// Get an elliptic curve key pair using a PKey based method. This is used for X448, X25519,
+/// Ed25519 and Ed448 which are not supported by the `EcKey` API.
 ///
 /// The RNG is specified.
 ///
 /// # Arguments
 ///
 /// * `rng` - The random number generator
-/// * `id` - The ID of the curve (X448 or X25519)
+/// * `id` - The ID of the curve (X448, X25519, Ed25519 or Ed448)
 ///
 /// # Returns
 ///
@@ -394,19 +434,14 @@ pub fn get_keypair_pkey_based_with_rng(
     id: Id,
 ) -> Result<(Vec<u8>, Vec<u8>)> {
     // Generate n random bytes according to the curve
-    let sk = match id {
-        Id::X448 => {
-            let mut sk: [u8; 56] = [0; 56];
-            rng.fill_bytes(&mut sk);
-            sk.to_vec()
-        }
-        Id::X25519 => {
-            let mut sk: [u8; 32] = [0; 32];
-            rng.fill_bytes(&mut sk);
-            sk.to_vec()
-        }
+    let sk_len = match id {
+        Id::X448 => 56,
+        Id::X25519 | Id::ED25519 => 32,
+        Id::ED448 => 57,
         _ => panic!("Unsupported ID"),
     };
+    let mut sk = vec![0u8; sk_len];
+    rng.fill_bytes(&mut sk);
 
     let sk_obj = PKey::private_key_from_raw_bytes(&sk, id)?;
     let pk = sk_obj.raw_public_key()?;
@@ -472,6 +507,79 @@ pub fn sign_ec_based(id: Nid, sk: &[u8], msg: &[u8], digest: MessageDigest) -> R
     Ok(signer.sign_to_vec()?)
 }
 
+/// Sign a message using an EC based method, drawing the nonce from an RNG
+///
+/// The ECDSA signature is computed as in SEC 1 § 4.1.3, with the nonce drawn
+/// from `rng` instead of the OpenSSL RNG. The signature is DER encoded, as
+/// produced by `sign_ec_based`.
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator of the nonce
+/// * `id` - The ID of the curve
+/// * `sk` - The secret key
+/// * `msg` - The message to sign
+/// * `digest` - The digest to use
+///
+/// # Returns
+///
+/// The signature as a byte vector
+pub fn sign_ec_based_with_rng(
+    rng: &mut impl CryptoRngCore,
+    id: Nid,
+    sk: &[u8],
+    msg: &[u8],
+    digest: MessageDigest,
+) -> Result<Vec<u8>> {
+    let ec_key = get_ec_key_from_sk(id, sk)?;
+    let group = EcGroup::from_curve_name(id)?;
+    let mut ctx = BigNumContext::new_secure()?;
+    let mut order = BigNum::new()?;
+    group.order(&mut order, &mut ctx)?;
+
+    // The leftmost bits of the hash, as many as the order has
+    let hash = openssl::hash::hash(digest, msg)?;
+    let hash_bits = (hash.len() * 8) as i32;
+    let mut e = BigNum::from_slice(&hash)?;
+    if hash_bits > order.num_bits() {
+        let mut truncated = BigNum::new()?;
+        truncated.rshift(&e, hash_bits - order.num_bits())?;
+        e = truncated;
+    }
+
+    loop {
+        let mut k = get_sk_bignum_ec_based(&mut ctx, &mut *rng, &group)?;
+        k.set_const_time();
+
+        // r = x(kG) mod n
+        let mut point = EcPoint::new(&group)?;
+        point.mul_generator(&group, &k, &ctx)?;
+        let mut x = BigNum::new()?;
+        let mut y = BigNum::new()?;
+        point.affine_coordinates(&group, &mut x, &mut y, &mut ctx)?;
+        let mut r = BigNum::new()?;
+        r.nnmod(&x, &order, &mut ctx)?;
+        if r.num_bits() == 0 {
+            continue;
+        }
+
+        // s = k^-1 (e + r d) mod n
+        let mut k_inv = BigNum::new_secure()?;
+        k_inv.mod_inverse(&k, &order, &mut ctx)?;
+        let mut rd = BigNum::new_secure()?;
+        rd.mod_mul(&r, ec_key.private_key(), &order, &mut ctx)?;
+        let mut sum = BigNum::new_secure()?;
+        sum.mod_add(&e, &rd, &order, &mut ctx)?;
+        let mut s = BigNum::new()?;
+        s.mod_mul(&k_inv, &sum, &order, &mut ctx)?;
+        if s.num_bits() == 0 {
+            continue;
+        }
+
+        return Ok(EcdsaSig::from_private_components(r, s)?.to_der()?);
+    }
+}
+
 /// Sign a message using a PKey based method (used for Ed25519 and Ed448)
 ///
 /// # Arguments
@@ -634,4 +742,35 @@ mod tests {
 
         assert_eq!(ss, ss2);
     }
+
+    #[test]
+    fn test_sign_ec_based_with_rng() {
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        // SHA-384 with P-256 truncates the hash to the order
+        for (nid, digest) in [
+            (Nid::X9_62_PRIME256V1, MessageDigest::sha256()),
+            (Nid::X9_62_PRIME256V1, MessageDigest::sha384()),
+            (Nid::SECP384R1, MessageDigest::sha384()),
+            (Nid::BRAINPOOL_P256R1, MessageDigest::sha256()),
+        ] {
+            if !openssl_supports_curve(nid) {
+                continue;
+            }
+            let (pk, sk) = get_key_pair_ec_based(nid).unwrap();
+            let msg = b"message";
+            let sig = |seed| {
+                let mut rng = ChaCha20Rng::from_seed([seed; 32]);
+                sign_ec_based_with_rng(&mut rng, nid, &sk, msg, digest).unwrap()
+            };
+
+            // OpenSSL verifies the signature, and the nonce comes from the RNG
+            let signature = sig(1);
+            assert!(verify_ec_based(nid, &pk, msg, &signature, digest).unwrap());
+            assert!(!verify_ec_based(nid, &pk, b"other", &signature, digest).unwrap_or(false));
+            assert_eq!(sig(1), signature);
+            assert_ne!(sig(2), signature);
+        }
+    }
 }
@@ -0,0 +1,199 @@
+//! Cross-tests against liboqs.
+//!
+//! These tests are only built with the `liboqs-interop` feature, which pulls
+//! in liboqs through the `oqs` crate (building it requires cmake and a C
+//! toolchain). Run them with:
+//!
+//! ```ignore
+//! cargo test --features liboqs-interop oqs_interop
+//! ```
+//!
+//! Only the pure ML-KEM and ML-DSA parameter sets are covered. liboqs doesn't
+//! implement the hybrid KEMs or the composite drafts, and its SPHINCS+
+//! implementation predates FIPS 205 so it is not wire compatible with SLH-DSA.
+//! For those, the mapping to liboqs returns `QuantCryptError::NotImplemented`
+//! rather than silently skipping them, and `test_unsupported_algorithms` pins
+//! down exactly which algorithms are not cross-tested.
+
+use oqs::kem::Algorithm as OqsKemAlgorithm;
+use oqs::sig::Algorithm as OqsSigAlgorithm;
+use rand::{Rng, RngCore};
+use strum::IntoEnumIterator;
+
+use crate::dsa::common::dsa_type::DsaType;
+use crate::dsa::common::prehash_dsa_trait::PrehashDsa;
+use crate::dsa::common::prehash_dsa_type::PrehashDsaType;
+use crate::dsa::ml_dsa::MlDsaManager;
+use crate::kem::common::kem_trait::Kem;
+use crate::kem::common::kem_type::KemType;
+use crate::kem::ml_kem::MlKemManager;
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// Number of random rounds per algorithm and direction
+const ROUNDS: usize = 16;
+
+/// Get the liboqs counterpart of a KEM
+///
+/// # Errors
+///
+/// `QuantCryptError::NotImplemented` for everything but the pure ML-KEM parameter
+/// sets. The hybrid and composite KEMs combine ML-KEM with a traditional KEM,
+/// which liboqs has no counterpart for.
+fn oqs_kem_algorithm(kem_type: &KemType) -> Result<OqsKemAlgorithm> {
+    match kem_type {
+        KemType::MlKem512 => Ok(OqsKemAlgorithm::MlKem512),
+        KemType::MlKem768 => Ok(OqsKemAlgorithm::MlKem768),
+        KemType::MlKem1024 => Ok(OqsKemAlgorithm::MlKem1024),
+        _ => Err(QuantCryptError::NotImplemented),
+    }
+}
+
+/// Get the liboqs counterpart of a prehash DSA
+///
+/// # Errors
+///
+/// `QuantCryptError::NotImplemented` for the composite signatures, which liboqs
+/// doesn't implement
+fn oqs_prehash_sig_algorithm(dsa_type: &PrehashDsaType) -> Result<OqsSigAlgorithm> {
+    match dsa_type {
+        PrehashDsaType::MlDsa44 => Ok(OqsSigAlgorithm::MlDsa44),
+        PrehashDsaType::MlDsa65 => Ok(OqsSigAlgorithm::MlDsa65),
+        PrehashDsaType::MlDsa87 => Ok(OqsSigAlgorithm::MlDsa87),
+        _ => Err(QuantCryptError::NotImplemented),
+    }
+}
+
+/// Get the liboqs counterpart of a DSA
+///
+/// # Errors
+///
+/// `QuantCryptError::NotImplemented` for every DSA. The liboqs SPHINCS+ variants
+/// predate FIPS 205 and are not wire compatible with SLH-DSA, and liboqs has no
+/// traditional signatures.
+fn oqs_sig_algorithm(_dsa_type: &DsaType) -> Result<OqsSigAlgorithm> {
+    Err(QuantCryptError::NotImplemented)
+}
+
+fn kem_pairs() -> Vec<(KemType, OqsKemAlgorithm)> {
+    KemType::iter()
+        .filter_map(|t| oqs_kem_algorithm(&t).ok().map(|a| (t, a)))
+        .collect()
+}
+
+fn dsa_pairs() -> Vec<(PrehashDsaType, OqsSigAlgorithm)> {
+    PrehashDsaType::iter()
+        .filter_map(|t| oqs_prehash_sig_algorithm(&t).ok().map(|a| (t, a)))
+        .collect()
+}
+
+fn random_message() -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+    let mut msg = vec![0u8; rng.gen_range(0..1024)];
+    rng.fill_bytes(&mut msg);
+    msg
+}
+
+#[test]
+fn test_ml_kem_keygen_here_encap_there() {
+    oqs::init();
+    for (kem_type, oqs_alg) in kem_pairs() {
+        let oqs_kem = oqs::kem::Kem::new(oqs_alg).unwrap();
+        let mut kem = MlKemManager::new(kem_type.clone()).unwrap();
+        for _ in 0..ROUNDS {
+            let (pk, sk) = kem.key_gen().unwrap();
+            let oqs_pk = oqs_kem.public_key_from_bytes(&pk).unwrap();
+            let (ct, ss) = oqs_kem.encapsulate(oqs_pk).unwrap();
+            let ss2 = kem.decap(&sk, ct.as_ref()).unwrap();
+            assert_eq!(ss.as_ref(), ss2.as_slice(), "{:?}", kem_type);
+        }
+    }
+}
+
+#[test]
+fn test_ml_kem_keygen_there_encap_here() {
+    oqs::init();
+    for (kem_type, oqs_alg) in kem_pairs() {
+        let oqs_kem = oqs::kem::Kem::new(oqs_alg).unwrap();
+        let mut kem = MlKemManager::new(kem_type.clone()).unwrap();
+        for _ in 0..ROUNDS {
+            let (pk, sk) = oqs_kem.keypair().unwrap();
+            let (ss, ct) = kem.encap(pk.as_ref()).unwrap();
+            let oqs_ct = oqs_kem.ciphertext_from_bytes(&ct).unwrap();
+            let ss2 = oqs_kem.decapsulate(&sk, oqs_ct).unwrap();
+            assert_eq!(ss.as_slice(), ss2.as_ref(), "{:?}", kem_type);
+        }
+    }
+}
+
+#[test]
+fn test_ml_dsa_sign_here_verify_there() {
+    oqs::init();
+    for (dsa_type, oqs_alg) in dsa_pairs() {
+        let oqs_sig = oqs::sig::Sig::new(oqs_alg).unwrap();
+        let mut dsa = MlDsaManager::new(dsa_type.clone()).unwrap();
+        for _ in 0..ROUNDS {
+            let (pk, sk) = dsa.key_gen().unwrap();
+            let msg = random_message();
+            let sig = dsa.sign(&sk, &msg).unwrap();
+            let oqs_pk = oqs_sig.public_key_from_bytes(&pk).unwrap();
+            let oqs_signature = oqs_sig.signature_from_bytes(&sig).unwrap();
+            assert!(
+                oqs_sig.verify(&msg, oqs_signature, oqs_pk).is_ok(),
+                "{:?}",
+                dsa_type
+            );
+        }
+    }
+}
+
+#[test]
+fn test_ml_dsa_sign_there_verify_here() {
+    oqs::init();
+    for (dsa_type, oqs_alg) in dsa_pairs() {
+        let oqs_sig = oqs::sig::Sig::new(oqs_alg).unwrap();
+        let dsa = MlDsaManager::new(dsa_type.clone()).unwrap();
+        for _ in 0..ROUNDS {
+            let (pk, sk) = oqs_sig.keypair().unwrap();
+            let msg = random_message();
+            let sig = oqs_sig.sign(&msg, &sk).unwrap();
+            assert!(
+                dsa.verify(pk.as_ref(), &msg, sig.as_ref()).unwrap(),
+                "{:?}",
+                dsa_type
+            );
+
+            // The secret key encodings must agree as well
+            let sig2 = dsa.sign(sk.as_ref(), &msg).unwrap();
+            let oqs_signature = oqs_sig.signature_from_bytes(&sig2).unwrap();
+            assert!(oqs_sig.verify(&msg, oqs_signature, &pk).is_ok());
+        }
+    }
+}
+
+#[test]
+fn test_unsupported_algorithms() {
+    assert_eq!(
+        kem_pairs().into_iter().map(|(t, _)| t).collect::<Vec<_>>(),
+        vec![KemType::MlKem512, KemType::MlKem768, KemType::MlKem1024]
+    );
+    for kem_type in [KemType::MlKem768X25519, KemType::XWing] {
+        assert_eq!(
+            oqs_kem_algorithm(&kem_type).err(),
+            Some(QuantCryptError::NotImplemented)
+        );
+    }
+
+    assert_eq!(dsa_pairs().len(), 3);
+    assert_eq!(
+        oqs_prehash_sig_algorithm(&PrehashDsaType::MlDsa44Ed25519).err(),
+        Some(QuantCryptError::NotImplemented)
+    );
+    for dsa_type in DsaType::iter() {
+        assert_eq!(
+            oqs_sig_algorithm(&dsa_type).err(),
+            Some(QuantCryptError::NotImplemented)
+        );
+    }
+}
@@ -0,0 +1,387 @@
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::QuantCryptError;
+
+type Result<T> = std::result::Result<T, QuantCryptError>;
+
+/// The default parse budget, in bytes
+const DEFAULT_PARSE_BUDGET: usize = 64 * 1024 * 1024;
+
+/// The memory charged for each decoded DER element, on top of its content
+///
+/// This is about the size of a decoded `Any`, or of an entry of a decoded
+/// SEQUENCE OF or SET OF, so that inputs made of many tiny elements are
+/// charged for what they decode to rather than for their encoded size.
+const DER_ELEMENT_COST: usize = 64;
+
+/// The allocation budget of a single parse of untrusted input, in bytes
+static PARSE_BUDGET: AtomicUsize = AtomicUsize::new(DEFAULT_PARSE_BUDGET);
+
+/// Set the allocation budget of a single parse of untrusted input
+///
+/// Parsers of certificates, keys and CMS messages account for the memory
+/// they allocate and fail with `QuantCryptError::ResourceLimitExceeded` once
+/// the budget is spent instead of running out of memory. This covers the input
+/// itself, files read from disk (which are never read past the budget),
+/// decompressed content, the elements buffered by the streaming parsers and
+/// the decoded DER structures: every element, such as an entry of a SEQUENCE OF
+/// or an attribute of a set, is charged on top of its content, and DER nested
+/// in an OCTET STRING or BIT STRING is charged again as it is decoded again.
+/// The default is 64 MiB. The setting is process-wide.
+///
+/// # Arguments
+///
+/// * `bytes` - The budget, in bytes
+///
+/// # Example
+/// ```
+/// use quantcrypt::certificates::Certificate;
+/// use quantcrypt::limits::set_parse_budget;
+/// use quantcrypt::QuantCryptError;
+///
+/// set_parse_budget(1024 * 1024);
+/// let hostile = vec![0x30u8; 2 * 1024 * 1024];
+/// assert!(matches!(
+///     Certificate::from_der(&hostile),
+///     Err(QuantCryptError::ResourceLimitExceeded { .. })
+/// ));
+///
+/// // 40 KB decoding to a SEQUENCE OF 20000 elements
+/// let mut hostile = vec![0x30, 0x83, 0x00, 0x9C, 0x40];
+/// hostile.extend([0x05, 0x00].repeat(20000));
+/// assert!(matches!(
+///     Certificate::from_der(&hostile),
+///     Err(QuantCryptError::ResourceLimitExceeded { .. })
+/// ));
+/// # set_parse_budget(64 * 1024 * 1024);
+/// ```
+pub fn set_parse_budget(bytes: usize) {
+    PARSE_BUDGET.store(bytes, Ordering::Relaxed);
+}
+
+/// Get the allocation budget of a single parse of untrusted input
+///
+/// # Returns
+///
+/// The budget in bytes, 64 MiB unless changed with `set_parse_budget`
+pub fn get_parse_budget() -> usize {
+    PARSE_BUDGET.load(Ordering::Relaxed)
+}
+
+/// The memory allocated by one parse, checked against the parse budget
+#[derive(Clone, Debug)]
+pub(crate) struct AllocationAccount {
+    /// The budget in effect when the parse started
+    limit: usize,
+    /// The bytes charged so far
+    used: usize,
+}
+
+impl AllocationAccount {
+    /// Open an account with the current parse budget
+    pub(crate) fn new() -> AllocationAccount {
+        AllocationAccount {
+            limit: get_parse_budget(),
+            used: 0,
+        }
+    }
+
+    /// Get the bytes left in the budget
+    pub(crate) fn remaining(&self) -> usize {
+        self.limit - self.used
+    }
+
+    /// Charge an allocation to the account, before making it
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The size of the allocation
+    ///
+    /// # Errors
+    ///
+    /// `QuantCryptError::ResourceLimitExceeded` will be returned if the budget would be exceeded
+    pub(crate) fn charge(&mut self, bytes: usize) -> Result<()> {
+        if bytes > self.remaining() {
+            return Err(QuantCryptError::ResourceLimitExceeded { limit: self.limit });
+        }
+        self.used += bytes;
+        Ok(())
+    }
+}
+
+/// Check that an input fits in the parse budget
+///
+/// # Arguments
+///
+/// * `len` - The length of the input
+///
+/// # Errors
+///
+/// `QuantCryptError::ResourceLimitExceeded` will be returned if the input is larger than the budget
+pub(crate) fn check_input_len(len: usize) -> Result<()> {
+    AllocationAccount::new().charge(len)
+}
+
+/// Check that a DER input and its decoding fit in the parse budget
+///
+/// The input is charged, then the structures decoded from it, see `charge_der`.
+/// Input that isn't DER, such as PEM, is only charged for its length.
+///
+/// # Arguments
+///
+/// * `der` - The DER input
+///
+/// # Errors
+///
+/// `QuantCryptError::ResourceLimitExceeded` will be returned if the input or its decoding
+/// doesn't fit in the budget
+pub(crate) fn check_der_input(der: &[u8]) -> Result<()> {
+    charge_der_input(der, &mut AllocationAccount::new())
+}
+
+/// Charge a DER input and its decoding to an account
+///
+/// # Arguments
+///
+/// * `der` - The DER input
+/// * `account` - The account to charge
+///
+/// # Errors
+///
+/// `QuantCryptError::ResourceLimitExceeded` will be returned if the budget would be exceeded
+pub(crate) fn charge_der_input(der: &[u8], account: &mut AllocationAccount) -> Result<()> {
+    account.charge(der.len())?;
+    charge_der(der, account)
+}
+
+/// Charge the structures decoded from DER to an account, before decoding it
+///
+/// The encoding is walked without allocating for its elements. Each element is
+/// charged `DER_ELEMENT_COST` and, if primitive, its content, which the decoded
+/// types copy. The content of an OCTET STRING or BIT STRING holding a single
+/// constructed DER element, such as an extension value or the encapsulated
+/// content of a CMS message, is walked and charged as well. The walk stops at
+/// the first malformed element, leaving the error to the decoder.
+///
+/// # Arguments
+///
+/// * `der` - The DER encoding
+/// * `account` - The account to charge
+///
+/// # Errors
+///
+/// `QuantCryptError::ResourceLimitExceeded` will be returned if the budget would be exceeded
+pub(crate) fn charge_der(der: &[u8], account: &mut AllocationAccount) -> Result<()> {
+    // An explicit stack, so that deeply nested input can't overflow the call stack
+    let mut pending = vec![der];
+    while let Some(mut data) = pending.pop() {
+        while let Some((tag, content, rest)) = read_der_element(data) {
+            account.charge(DER_ELEMENT_COST)?;
+            if tag & 0x20 != 0 {
+                pending.push(content);
+            } else {
+                account.charge(content.len())?;
+                match (tag, content) {
+                    (0x04, nested) | (0x03, [0, nested @ ..]) if is_nested_der(nested) => {
+                        pending.push(nested)
+                    }
+                    _ => {}
+                }
+            }
+            data = rest;
+        }
+    }
+    Ok(())
+}
+
+/// Check if a string holds a single constructed DER element
+fn is_nested_der(data: &[u8]) -> bool {
+    matches!(
+        read_der_element(data),
+        Some((tag, _, rest)) if tag & 0x20 != 0 && rest.is_empty()
+    )
+}
+
+/// Read the identifier, content and remainder of the first DER element of some data
+///
+/// # Returns
+///
+/// The first identifier octet, the content and the data after the element, or
+/// `None` if the data doesn't start with a well-formed definite-length element
+fn read_der_element(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, mut rest) = data.split_first()?;
+    if tag & 0x1F == 0x1F {
+        // High tag number form: base 128 digits, the last one without its top bit
+        let digits = rest.iter().position(|b| b & 0x80 == 0)?;
+        rest = &rest[digits + 1..];
+    }
+    let (&first, mut rest) = rest.split_first()?;
+    let len = match first {
+        0..=0x7F => first as usize,
+        0x81..=0x84 => {
+            let n = (first & 0x7F) as usize;
+            if rest.len() < n {
+                return None;
+            }
+            let (len, after) = rest.split_at(n);
+            rest = after;
+            len.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize)
+        }
+        // Indefinite lengths aren't DER, and longer lengths exceed any budget
+        _ => return None,
+    };
+    if rest.len() < len {
+        return None;
+    }
+    let (content, rest) = rest.split_at(len);
+    Some((tag, content, rest))
+}
+
+/// Read a source to the end, charging what is read to an account
+///
+/// Reading stops as soon as the budget is exceeded, so a huge source is
+/// never held in memory.
+///
+/// # Arguments
+///
+/// * `reader` - The source
+/// * `account` - The account to charge
+///
+/// # Returns
+///
+/// The contents of the source
+///
+/// # Errors
+///
+/// `QuantCryptError::ResourceLimitExceeded` will be returned if the source is larger than the budget
+///
+/// `QuantCryptError::StreamIoError` will be returned if reading fails
+pub(crate) fn read_to_end_limited(
+    reader: impl Read,
+    account: &mut AllocationAccount,
+) -> Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    reader
+        .take((account.remaining() as u64).saturating_add(1))
+        .read_to_end(&mut contents)
+        .map_err(|_| QuantCryptError::StreamIoError)?;
+    account.charge(contents.len())?;
+    Ok(contents)
+}
+
+/// Read a file of untrusted input, without reading past the parse budget
+///
+/// # Arguments
+///
+/// * `path` - The path to the file
+///
+/// # Returns
+///
+/// The contents of the file
+///
+/// # Errors
+///
+/// `QuantCryptError::FileReadError` will be returned if the file can't be read
+///
+/// `QuantCryptError::ResourceLimitExceeded` will be returned if the file is larger than the budget
+pub(crate) fn read_file_limited(path: &str) -> Result<Vec<u8>> {
+    let file = std::fs::File::open(path).map_err(|_| QuantCryptError::FileReadError)?;
+    let mut account = AllocationAccount::new();
+    match read_to_end_limited(file, &mut account) {
+        Err(QuantCryptError::StreamIoError) => Err(QuantCryptError::FileReadError),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocation_account() {
+        let mut account = AllocationAccount { limit: 10, used: 0 };
+        account.charge(6).unwrap();
+        assert_eq!(account.remaining(), 4);
+        assert_eq!(
+            account.charge(5),
+            Err(QuantCryptError::ResourceLimitExceeded { limit: 10 })
+        );
+        account.charge(4).unwrap();
+
+        let mut account = AllocationAccount { limit: 10, used: 0 };
+        let source = std::io::Cursor::new(vec![1u8; 10]);
+        assert_eq!(read_to_end_limited(source, &mut account).unwrap().len(), 10);
+        // Reading stops right after the budget
+        let mut account = AllocationAccount { limit: 10, used: 0 };
+        assert!(read_to_end_limited(std::io::repeat(0), &mut account).is_err());
+    }
+
+    #[test]
+    fn test_charge_der() {
+        let charged = |der: &[u8]| {
+            let mut account = AllocationAccount {
+                limit: usize::MAX,
+                used: 0,
+            };
+            charge_der(der, &mut account).unwrap();
+            account.used
+        };
+
+        // SEQUENCE { INTEGER 5, OCTET STRING "ab" }
+        assert_eq!(
+            charged(&[0x30, 0x07, 0x02, 0x01, 0x05, 0x04, 0x02, b'a', b'b']),
+            3 * DER_ELEMENT_COST + 3
+        );
+
+        // A SEQUENCE OF 1000 NULLs is charged for each entry, not for its 2002 bytes
+        let mut seq_of = vec![0x30, 0x82, 0x07, 0xD0];
+        seq_of.extend([0x05, 0x00].repeat(1000));
+        assert_eq!(charged(&seq_of), 1001 * DER_ELEMENT_COST);
+
+        // A SET OF attributes, with a high tag number
+        let set = [0x31, 0x05, 0x9F, 0x81, 0x00, 0x01, 0xFF];
+        assert_eq!(charged(&set), 2 * DER_ELEMENT_COST + 1);
+
+        // DER nested in an OCTET STRING or BIT STRING is charged again
+        let nested = [0x04, 0x04, 0x30, 0x02, 0x05, 0x00];
+        assert_eq!(charged(&nested), 3 * DER_ELEMENT_COST + 4);
+        let nested = [0x03, 0x05, 0x00, 0x30, 0x02, 0x05, 0x00];
+        assert_eq!(charged(&nested), 3 * DER_ELEMENT_COST + 5);
+        // Unless it isn't a single constructed element
+        let opaque = [0x04, 0x03, 0x30, 0x02, 0x05];
+        assert_eq!(charged(&opaque), DER_ELEMENT_COST + 3);
+
+        // Malformed and non-DER input stops the walk
+        assert_eq!(charged(&[0x30, 0x80, 0x05, 0x00, 0x00, 0x00]), 0);
+        assert_eq!(charged(&[0x30, 0x05, 0x05, 0x00]), 0);
+        assert_eq!(charged(b"-----BEGIN CERTIFICATE-----"), 0);
+
+        // Deep nesting doesn't overflow the stack
+        let mut deep = Vec::new();
+        for i in (0..100_000).rev() {
+            let len = 2 + 5 * i;
+            deep.extend([0x30, 0x83, (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+        }
+        deep.extend([0x05, 0x00]);
+        assert_eq!(charged(&deep), 100_001 * DER_ELEMENT_COST);
+
+        // A small input decoding to many elements exceeds a budget larger than itself
+        let mut account = AllocationAccount {
+            limit: 16 * 1024,
+            used: 0,
+        };
+        account.charge(seq_of.len()).unwrap();
+        assert_eq!(
+            charge_der(&seq_of, &mut account),
+            Err(QuantCryptError::ResourceLimitExceeded { limit: 16 * 1024 })
+        );
+        // The default budget refuses 2 MB decoding to a SEQUENCE OF a million elements
+        let mut hostile = vec![0x30, 0x83, 0x21, 0x00, 0x00];
+        hostile.extend([0x05, 0x00].repeat(0x108000));
+        assert!(matches!(
+            crate::certificates::Certificate::from_der(&hostile),
+            Err(QuantCryptError::ResourceLimitExceeded { .. })
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Get the current time in seconds since the Unix epoch
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A single check performed during a verification
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationCheck {
+    /// What was checked, e.g. "signature" or "issuer matches subject"
+    pub name: String,
+    /// Whether the check passed
+    pub passed: bool,
+}
+
+/// The outcome of a verification
+///
+/// Instead of a bare boolean, a report records every check that was performed
+/// and whether it passed, non-fatal findings such as legacy OIDs that were
+/// accepted, the policy that was applied and when the verification took place.
+/// Reports can be serialized with any serde format, e.g. to be written to an
+/// audit log. `is_valid()` is the shortcut for callers that only need the outcome.
+///
+/// Checks are recorded in order, and a verification stops at the first failed
+/// check, so the last check of an invalid report is the one that failed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// Whether the verification succeeded
+    pub valid: bool,
+    /// Non-fatal findings, such as legacy OIDs that were translated
+    pub warnings: Vec<String>,
+    /// The checks that were performed, in order
+    pub checks: Vec<VerificationCheck>,
+    /// A description of the policy that was applied, if any
+    pub policy: Option<String>,
+    /// When the verification took place, in seconds since the Unix epoch
+    pub verified_at: u64,
+}
+
+impl VerificationReport {
+    /// Create an empty report, timestamped now
+    pub(crate) fn new() -> VerificationReport {
+        VerificationReport {
+            valid: false,
+            warnings: Vec::new(),
+            checks: Vec::new(),
+            policy: None,
+            verified_at: unix_now(),
+        }
+    }
+
+    /// Record a check
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - What was checked
+    /// * `passed` - Whether the check passed
+    ///
+    /// # Returns
+    ///
+    /// `passed`, so the outcome can be acted upon directly
+    pub(crate) fn check(&mut self, name: &str, passed: bool) -> bool {
+        self.checks.push(VerificationCheck {
+            name: name.to_string(),
+            passed,
+        });
+        passed
+    }
+
+    /// Check if the verification succeeded
+    ///
+    /// # Returns
+    ///
+    /// True if the verification succeeded, false otherwise
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Get the first check that failed
+    ///
+    /// # Returns
+    ///
+    /// The failed check, or None if all checks passed
+    pub fn get_failed_check(&self) -> Option<&VerificationCheck> {
+        self.checks.iter().find(|c| !c.passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_report() {
+        let mut report = VerificationReport::new();
+        assert!(report.verified_at > 0);
+        assert!(report.check("issuer matches subject", true));
+        assert!(!report.check("signature", false));
+        report.policy = Some("test".to_string());
+        assert!(!report.is_valid());
+        assert_eq!(report.get_failed_check().unwrap().name, "signature");
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"name\":\"signature\",\"passed\":false"));
+        let parsed: VerificationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
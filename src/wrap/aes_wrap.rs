@@ -1,9 +1,73 @@
 //https://datatracker.ietf.org/doc/html/rfc3394#section-2.2.1
 use super::common::wrap_info::WrapInfo;
+use crate::utils::ct_eq::ct_eq;
 use crate::wrap::common::wrap_trait::Wrap;
 use crate::{wrap::common::wrap_type::WrapType, QuantCryptError};
+use aes::cipher::consts::U16;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, BlockSizeUser, KeyInit};
+use aes::{Aes128, Aes192, Aes256, Block};
 type Result<T> = std::result::Result<T, QuantCryptError>;
-use openssl::aes::{unwrap_key, wrap_key};
+
+/// The default initial value of RFC 3394 § 2.2.3.1
+const DEFAULT_IV: [u8; 8] = [0xA6; 8];
+
+/// Wrap a key as per RFC 3394 § 2.2.1
+fn wrap_key<C>(wrapping_key: &[u8], key_to_wrap: &[u8]) -> Result<Vec<u8>>
+where
+    C: BlockEncrypt + BlockSizeUser<BlockSize = U16> + KeyInit,
+{
+    let cipher = C::new_from_slice(wrapping_key).map_err(|_| QuantCryptError::KeyWrapFailed)?;
+    let n = key_to_wrap.len() / 8;
+    let mut a = DEFAULT_IV;
+    let mut r = key_to_wrap.to_vec();
+    let mut block = Block::default();
+    for j in 0..6 {
+        for (i, r_i) in r.chunks_exact_mut(8).enumerate() {
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(r_i);
+            cipher.encrypt_block(&mut block);
+            let t = ((n * j + i + 1) as u64).to_be_bytes();
+            for k in 0..8 {
+                a[k] = block[k] ^ t[k];
+            }
+            r_i.copy_from_slice(&block[8..]);
+        }
+    }
+    let mut wrapped = a.to_vec();
+    wrapped.extend(r);
+    Ok(wrapped)
+}
+
+/// Unwrap a key as per RFC 3394 § 2.2.2, checking the initial value
+fn unwrap_key<C>(wrapping_key: &[u8], key_to_unwrap: &[u8]) -> Result<Vec<u8>>
+where
+    C: BlockDecrypt + BlockSizeUser<BlockSize = U16> + KeyInit,
+{
+    let cipher = C::new_from_slice(wrapping_key).map_err(|_| QuantCryptError::KeyUnwrapFailed)?;
+    let n = key_to_unwrap.len() / 8 - 1;
+    let mut a: [u8; 8] = key_to_unwrap[..8]
+        .try_into()
+        .map_err(|_| QuantCryptError::KeyUnwrapFailed)?;
+    let mut r = key_to_unwrap[8..].to_vec();
+    let mut block = Block::default();
+    for j in (0..6).rev() {
+        for (i, r_i) in r.chunks_exact_mut(8).enumerate().rev() {
+            let t = ((n * j + i + 1) as u64).to_be_bytes();
+            for k in 0..8 {
+                block[k] = a[k] ^ t[k];
+            }
+            block[8..].copy_from_slice(r_i);
+            cipher.decrypt_block(&mut block);
+            a.copy_from_slice(&block[..8]);
+            r_i.copy_from_slice(&block[8..]);
+        }
+    }
+    if !ct_eq(&a, &DEFAULT_IV) {
+        return Err(QuantCryptError::KeyUnwrapFailed);
+    }
+    Ok(r)
+}
+
 #[derive(Clone)]
 pub struct Aes {
     wrap_type: WrapType,
@@ -18,8 +82,8 @@ impl Wrap for Aes {
     }
 
     fn wrap(&self, wrapping_key: &[u8], key_to_wrap: &[u8]) -> Result<Vec<u8>> {
-        // CEK must be a multiple of 64 bits
-        if key_to_wrap.len() % 8 != 0 {
+        // CEK must be a multiple of 64 bits, and at least two 64 bit blocks
+        if key_to_wrap.len() % 8 != 0 || key_to_wrap.len() < 16 {
             return Err(QuantCryptError::KeyWrapFailed);
         }
 
@@ -36,23 +100,23 @@ impl Wrap for Aes {
             }
         };
 
-        let wrapping_key = openssl::aes::AesKey::new_encrypt(wrapping_key)
-            .map_err(|_| QuantCryptError::KeyWrapFailed)?;
-        let mut out_buf = vec![0u8; key_to_wrap.len() + 8];
-        wrap_key(&wrapping_key, None, &mut out_buf, key_to_wrap)
-            .map_err(|_| QuantCryptError::KeyWrapFailed)?;
-        //encrypt_wrap!(Aes128, wrapping_key, key_to_wrap)
-        Ok(out_buf)
+        match self.wrap_type {
+            WrapType::Aes128 => wrap_key::<Aes128>(wrapping_key, key_to_wrap),
+            WrapType::Aes256 => wrap_key::<Aes256>(wrapping_key, key_to_wrap),
+        }
     }
 
     fn unwrap(&self, wrapping_key: &[u8], key_to_unwrap: &[u8]) -> Result<Vec<u8>> {
-        let key = openssl::aes::AesKey::new_decrypt(wrapping_key)
-            .map_err(|_| QuantCryptError::KeyUnwrapFailed)?;
-        let mut out_buf = vec![0u8; key_to_unwrap.len() - 8];
-        unwrap_key(&key, None, &mut out_buf, key_to_unwrap)
-            .map_err(|_| QuantCryptError::KeyUnwrapFailed)?;
-
-        Ok(out_buf)
+        // The initial value and at least two 64 bit blocks
+        if key_to_unwrap.len() % 8 != 0 || key_to_unwrap.len() < 24 {
+            return Err(QuantCryptError::KeyUnwrapFailed);
+        }
+        match wrapping_key.len() {
+            16 => unwrap_key::<Aes128>(wrapping_key, key_to_unwrap),
+            24 => unwrap_key::<Aes192>(wrapping_key, key_to_unwrap),
+            32 => unwrap_key::<Aes256>(wrapping_key, key_to_unwrap),
+            _ => Err(QuantCryptError::KeyUnwrapFailed),
+        }
     }
 
     fn get_wrap_info(&self) -> WrapInfo {
@@ -103,4 +167,44 @@ mod tests {
         let unwrapped_key = aes.unwrap(&wrapping_key, &wrapped_key).unwrap();
         assert_eq!(key_to_wrap, unwrapped_key);
     }
+
+    #[test]
+    fn test_aes_wrap_rfc3394_vectors() {
+        let kek128 = hex::decode("000102030405060708090A0B0C0D0E0F").unwrap();
+        let kek256 =
+            hex::decode("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F")
+                .unwrap();
+        let key128 = hex::decode("00112233445566778899AABBCCDDEEFF").unwrap();
+        let key256 =
+            hex::decode("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F")
+                .unwrap();
+
+        // RFC 3394 § 4.1, 128 bits of key data with a 128 bit KEK
+        let aes = Aes::new(WrapType::Aes128).unwrap();
+        let wrapped = aes.wrap(&kek128, &key128).unwrap();
+        assert_eq!(
+            wrapped,
+            hex::decode("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5").unwrap()
+        );
+        assert_eq!(aes.unwrap(&kek128, &wrapped).unwrap(), key128);
+
+        // RFC 3394 § 4.6, 256 bits of key data with a 256 bit KEK
+        let aes = Aes::new(WrapType::Aes256).unwrap();
+        let wrapped = aes.wrap(&kek256, &key256).unwrap();
+        assert_eq!(
+            wrapped,
+            hex::decode(
+                "28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21"
+            )
+            .unwrap()
+        );
+        assert_eq!(aes.unwrap(&kek256, &wrapped).unwrap(), key256);
+
+        // A tampered or truncated wrapped key is rejected
+        let mut tampered = wrapped.clone();
+        tampered[0] ^= 1;
+        assert!(aes.unwrap(&kek256, &tampered).is_err());
+        assert!(aes.unwrap(&kek256, &wrapped[..16]).is_err());
+        assert!(aes.unwrap(&kek256[..20], &wrapped).is_err());
+    }
 }